@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,6 +10,49 @@ pub struct DisplayConfig {
     pub max_rows_preview: usize,
     #[serde(default)]
     pub sidebar_width: Option<u16>, // falls back to 30 when None
+    #[serde(default)]
+    pub locale: Option<String>, // e.g. "en_US", "de_DE"; "auto" reads $LC_NUMERIC/$LANG
+    #[serde(default)]
+    pub thousands_separator: Option<char>, // overrides the locale's
+    #[serde(default)]
+    pub decimal_separator: Option<char>, // overrides the locale's
+    #[serde(default = "default_decimal_places")]
+    pub decimal_places: usize,
+    #[serde(default)]
+    pub byte_units: ByteUnits,
+}
+
+/// Whether sizes are printed in powers of 1024 (`MiB`) or 1000 (`MB`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteUnits {
+    #[default]
+    Iec,
+    Si,
+}
+
+impl DisplayConfig {
+    /// Number formatting for counts, percentages and sizes in every output.
+    pub fn number_format(&self) -> crate::NumberFormat {
+        let locale = match self.locale.as_deref() {
+            Some("auto") => ["LC_ALL", "LC_NUMERIC", "LANG"]
+                .iter()
+                .find_map(|v| std::env::var(v).ok().filter(|s| !s.is_empty())),
+            other => other.map(str::to_string),
+        };
+        let mut fmt = locale
+            .map(|l| crate::NumberFormat::for_locale(&l))
+            .unwrap_or_default();
+        if let Some(sep) = self.thousands_separator {
+            fmt.thousands = Some(sep);
+        }
+        if let Some(sep) = self.decimal_separator {
+            fmt.decimal = sep;
+        }
+        fmt.decimals = self.decimal_places;
+        fmt.units = self.byte_units;
+        fmt
+    }
 }
 
 fn default_theme() -> String {
@@ -17,6 +61,9 @@ fn default_theme() -> String {
 fn default_max_rows() -> usize {
     100
 }
+fn default_decimal_places() -> usize {
+    1
+}
 
 impl Default for DisplayConfig {
     fn default() -> Self {
@@ -24,6 +71,11 @@ impl Default for DisplayConfig {
             theme: default_theme(),
             max_rows_preview: default_max_rows(),
             sidebar_width: None,
+            locale: None,
+            thousands_separator: None,
+            decimal_separator: None,
+            decimal_places: default_decimal_places(),
+            byte_units: ByteUnits::Iec,
         }
     }
 }
@@ -39,7 +91,21 @@ pub struct ProfilingConfig {
     #[serde(default = "default_large_file_threshold")]
     pub large_file_threshold_bytes: u64,
     #[serde(default)]
+    pub auto_sample_threshold_bytes: Option<u64>, // full scans of larger files sample ~sample_size rows
+    #[serde(default)]
     pub full_scan_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub threads: Option<usize>, // worker threads for full scans; None = all cores
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize, // rows per decoded record batch
+    #[serde(default = "default_prefetch_depth")]
+    pub prefetch_depth: usize, // batches / remote row groups read ahead; 0 disables
+    #[serde(default)]
+    pub memory_limit_bytes: Option<u64>, // full scans switch to smaller sketches past this
+    #[serde(default)]
+    pub histogram_log_scale: bool, // log-spaced bins for columns spanning 3+ orders of magnitude
+    #[serde(default = "default_hll_precision")]
+    pub hll_precision: u8, // 2^p one-byte HLL registers per column (4-14); error ~1.04/sqrt(2^p)
 }
 
 fn default_mode() -> String {
@@ -54,6 +120,15 @@ fn default_bins() -> usize {
 fn default_large_file_threshold() -> u64 {
     1073741824 // 1GiB
 }
+fn default_batch_size() -> usize {
+    65536
+}
+fn default_prefetch_depth() -> usize {
+    4
+}
+fn default_hll_precision() -> u8 {
+    10
+}
 
 impl Default for ProfilingConfig {
     fn default() -> Self {
@@ -62,16 +137,63 @@ impl Default for ProfilingConfig {
             sample_size: default_sample(),
             histogram_bins: default_bins(),
             large_file_threshold_bytes: default_large_file_threshold(),
+            auto_sample_threshold_bytes: None,
             full_scan_timeout_secs: None,
+            threads: None,
+            batch_size: default_batch_size(),
+            prefetch_depth: default_prefetch_depth(),
+            memory_limit_bytes: None,
+            histogram_log_scale: false,
+            hll_precision: default_hll_precision(),
         }
     }
 }
 
+/// `[s3]`: credentials and endpoint for `s3://` inputs. With `role_arn` set the
+/// default credentials are only used to assume that role. Buckets listed under
+/// `[s3.buckets.<name>]` override the endpoint and region for that bucket.
+/// `force_path_style`, `allow_http` and `ca_bundle` are for self-hosted
+/// S3-compatible stores (MinIO, Ceph RGW) on plain HTTP or a private CA.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct S3Config {
     pub region: Option<String>,
     pub profile: Option<String>,
     pub endpoint_url: Option<String>,
+    pub role_arn: Option<String>, // e.g. arn:aws:iam::123456789012:role/reader
+    pub external_id: Option<String>, // required by some cross-account trust policies
+    pub session_name: Option<String>, // shows up in the bucket owner's CloudTrail
+    #[serde(default)]
+    pub requester_pays: bool, // send x-amz-request-payer, billing reads to us
+    pub force_path_style: Option<bool>, // endpoint/bucket/key URLs, as MinIO and Ceph expect
+    pub allow_http: Option<bool>, // defaults to whether endpoint_url is http://
+    pub ca_bundle: Option<String>, // PEM file of extra root certificates to trust
+    #[serde(default)]
+    pub buckets: BTreeMap<String, S3BucketConfig>,
+}
+
+/// `[s3.buckets.<name>]`: where one bucket lives, e.g. an on-prem MinIO
+/// alongside AWS. Unset fields fall back to `[s3]`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct S3BucketConfig {
+    pub endpoint_url: Option<String>,
+    pub region: Option<String>,
+    pub force_path_style: Option<bool>, // false forces virtual-hosted style
+    pub allow_http: Option<bool>,
+    pub ca_bundle: Option<String>,
+}
+
+impl S3Config {
+    /// These settings with `bucket`'s `[s3.buckets]` entry applied.
+    pub fn for_bucket(&self, bucket: &str) -> S3BucketConfig {
+        let entry = self.buckets.get(bucket).cloned().unwrap_or_default();
+        S3BucketConfig {
+            endpoint_url: entry.endpoint_url.or_else(|| self.endpoint_url.clone()),
+            region: entry.region.or_else(|| self.region.clone()),
+            force_path_style: entry.force_path_style.or(self.force_path_style),
+            allow_http: entry.allow_http.or(self.allow_http),
+            ca_bundle: entry.ca_bundle.or_else(|| self.ca_bundle.clone()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +202,9 @@ pub struct ExportConfig {
     pub format: String,
     #[serde(default = "default_output_dir")]
     pub output_dir: String,
+    // `export --distinct-values` lists columns whose HLL estimate is at most this
+    #[serde(default = "default_max_distinct_values")]
+    pub max_distinct_values: u64,
 }
 
 fn default_format() -> String {
@@ -88,20 +213,293 @@ fn default_format() -> String {
 fn default_output_dir() -> String {
     ".".into()
 }
+fn default_max_distinct_values() -> u64 {
+    1000
+}
 
 impl Default for ExportConfig {
     fn default() -> Self {
         Self {
             format: default_format(),
             output_dir: default_output_dir(),
+            max_distinct_values: default_max_distinct_values(),
         }
     }
 }
 
+/// `[gcs]`: credentials for `gs://` inputs. Without `credentials_file` they
+/// come from GOOGLE_APPLICATION_CREDENTIALS or the metadata server.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GcsConfig {
     pub project_id: Option<String>,
-    pub credentials_file: Option<String>,
+    pub credentials_file: Option<String>, // service account key or ADC file
+    pub impersonate_service_account: Option<String>, // email; the credentials above only mint its tokens
+}
+
+/// `[azure]`: credentials for `az://` and `abfs[s]://` inputs. Unset fields fall
+/// back to the `AZURE_STORAGE_*` / `AZURE_*` environment variables.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AzureConfig {
+    pub account: Option<String>, // storage account; abfs URIs name their own
+    pub access_key: Option<String>,
+    pub sas_token: Option<String>,
+    pub client_id: Option<String>, // service principal, with client_secret and tenant_id
+    pub client_secret: Option<String>,
+    pub tenant_id: Option<String>,
+    pub endpoint: Option<String>, // e.g. an Azurite URL
+    #[serde(default)]
+    pub use_emulator: bool,
+}
+
+/// `[hdfs]`: how `hdfs://` inputs reach the namenode's WebHDFS REST API.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HdfsConfig {
+    pub namenode: Option<String>, // WebHDFS base URL; default http://<URI host>:9870
+    pub user: Option<String>,     // `user.name` for simple auth; default $HADOOP_USER_NAME
+    pub delegation_token: Option<String>,
+    // fetch a delegation token with `curl --negotiate` (needs a valid kinit ticket)
+    #[serde(default)]
+    pub kerberos: bool,
+}
+
+/// `[http]`: how `http://` and `https://` inputs are read.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpConfig {
+    // download the whole file when the server ignores range requests
+    #[serde(default = "default_full_download")]
+    pub full_download: bool,
+}
+
+fn default_full_download() -> bool {
+    true
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            full_download: default_full_download(),
+        }
+    }
+}
+
+/// `[cache]`: how long cached remote footers are trusted, how much disk
+/// cached remote column chunks may use, and how local profiles are keyed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfig {
+    // within this many seconds a cached footer is used without even a HEAD;
+    // older entries are revalidated against the object's ETag/last-modified
+    #[serde(default = "default_footer_ttl_secs")]
+    pub footer_ttl_secs: u64,
+    // least recently read chunks are evicted past this; 0 turns the chunk cache off
+    #[serde(default = "default_chunk_cache_mb")]
+    pub chunk_cache_mb: u64,
+    // key cached profiles by a hash of the file's bytes instead of its mtime,
+    // for copies and syncs that don't preserve modification times
+    #[serde(default)]
+    pub content_hash: bool,
+}
+
+fn default_footer_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_chunk_cache_mb() -> u64 {
+    1024
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            footer_ttl_secs: default_footer_ttl_secs(),
+            chunk_cache_mb: default_chunk_cache_mb(),
+            content_hash: false,
+        }
+    }
+}
+
+/// `[retry]`: how every remote reader retries a failed request. Throttling,
+/// server errors and timeouts are retried with exponential backoff; other
+/// failures (missing objects, bad credentials) are not.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    // retries after the first attempt; 0 fails on the first error
+    #[serde(default = "default_max_retries")]
+    pub max_retries: usize,
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    // each wait is this many times the previous one, up to max_backoff_ms
+    #[serde(default = "default_backoff_base")]
+    pub backoff_base: f64,
+    // a single request (connect through body) that takes longer fails and may be retried
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    // no new retries are started once a request has been failing this long
+    #[serde(default = "default_retry_timeout_secs")]
+    pub retry_timeout_secs: u64,
+}
+
+fn default_max_retries() -> usize {
+    5
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    100
+}
+
+fn default_max_backoff_ms() -> u64 {
+    15_000
+}
+
+fn default_backoff_base() -> f64 {
+    2.0
+}
+
+fn default_request_timeout_secs() -> u64 {
+    30
+}
+
+fn default_retry_timeout_secs() -> u64 {
+    60
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_max_retries(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            backoff_base: default_backoff_base(),
+            request_timeout_secs: default_request_timeout_secs(),
+            retry_timeout_secs: default_retry_timeout_secs(),
+        }
+    }
+}
+
+/// `[notifications]`: where `--notify` posts regression alerts.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NotificationsConfig {
+    pub webhook_url: Option<String>,
+    pub kind: Option<String>, // "slack", "teams" or "generic"; guessed from the URL when unset
+    // placeholders: {dataset} {quality} {regression_count} {failed_rules} {worst_deltas} {report}
+    pub template: Option<String>,
+    pub report_url: Option<String>, // base URL the exported report is published under
+}
+
+/// `[openlineage]`: where profiling runs are reported as OpenLineage RunEvents.
+/// Emission is off unless `url` (or `$OPENLINEAGE_URL`) is set.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OpenLineageConfig {
+    pub url: Option<String>,
+    pub endpoint: Option<String>, // path under `url`; "api/v1/lineage" when unset
+    pub namespace: Option<String>, // job namespace; "parquet-lens" when unset
+    pub job_name: Option<String>, // defaults to "parquet-lens.<command>"
+    pub api_key: Option<String>,  // sent as a bearer token
+}
+
+/// `[poll]`: datasets `serve --poll` re-profiles on its schedule.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PollConfig {
+    #[serde(default)]
+    pub datasets: Vec<String>,
+    pub history_path: Option<String>, // defaults to <data dir>/parquet-lens/history.jsonl
+    #[serde(default)]
+    pub notify: bool, // post regressions to the [notifications] webhook
+}
+
+/// `[duplicates]`: defaults for the duplicates command. The first
+/// `[[duplicates.datasets]]` entry whose `path` glob matches the input
+/// replaces whichever of these it sets.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DuplicatesConfig {
+    #[serde(default)]
+    pub ignore_columns: Vec<String>, // left out of the row hash
+    pub threshold: Option<f64>, // duplicate pct above which the command fails
+    #[serde(default)]
+    pub datasets: Vec<DatasetDuplicatesConfig>,
+}
+
+/// `[[rules]]`: data quality requirements on one column, enforced by `check`
+/// along with those in the file given to `check --rules`. Every check an
+/// entry sets passes or fails on its own.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RuleConfig {
+    pub column: String,
+    pub path: Option<String>, // glob the rule is limited to; every dataset when unset
+    #[serde(default)]
+    pub not_null: bool,
+    #[serde(default)]
+    pub unique: bool,
+    pub min: Option<RuleValue>, // inclusive bounds on non-null values
+    pub max: Option<RuleValue>,
+    pub regex: Option<String>, // every non-null value must match (unanchored)
+    pub allowed_values: Option<Vec<RuleValue>>,
+    pub max_null_pct: Option<f64>,
+    pub freshness: Option<String>, // newest value at most this old, e.g. "24h" or "7d"
+}
+
+/// A number or string in a `[[rules]]` bound or value list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleValue {
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl std::fmt::Display for RuleValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(v) => write!(f, "{v}"),
+            Self::Float(v) => write!(f, "{v}"),
+            Self::Text(v) => write!(f, "'{v}'"),
+        }
+    }
+}
+
+impl RuleConfig {
+    /// True when the rule applies to `path`, the path given on the command line.
+    pub fn matches(&self, path: &str) -> bool {
+        self.path
+            .as_deref()
+            .is_none_or(|p| p == path || glob::Pattern::new(p).is_ok_and(|g| g.matches(path)))
+    }
+
+    /// The `[[rules]]` entries of a standalone rules file.
+    pub fn load_file(path: &std::path::Path) -> crate::Result<Vec<Self>> {
+        #[derive(Deserialize)]
+        struct RulesFile {
+            #[serde(default)]
+            rules: Vec<RuleConfig>,
+        }
+        let content = std::fs::read_to_string(path)?;
+        let file: RulesFile = toml::from_str(&content)
+            .map_err(|e| crate::ParquetLensError::Other(format!("{}: {e}", path.display())))?;
+        Ok(file.rules)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DatasetDuplicatesConfig {
+    pub path: String, // glob matched against the path given on the command line
+    pub ignore_columns: Option<Vec<String>>,
+    pub threshold: Option<f64>,
+}
+
+impl DuplicatesConfig {
+    /// Ignored columns and failure threshold that apply to `path`.
+    pub fn resolve(&self, path: &str) -> (Vec<String>, Option<f64>) {
+        let dataset = self
+            .datasets
+            .iter()
+            .find(|d| d.path == path || glob::Pattern::new(&d.path).is_ok_and(|p| p.matches(path)));
+        let ignore = dataset
+            .and_then(|d| d.ignore_columns.clone())
+            .unwrap_or_else(|| self.ignore_columns.clone());
+        let threshold = dataset.and_then(|d| d.threshold).or(self.threshold);
+        (ignore, threshold)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -116,6 +514,26 @@ pub struct Config {
     pub export: ExportConfig,
     #[serde(default)]
     pub gcs: GcsConfig,
+    #[serde(default)]
+    pub azure: AzureConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub hdfs: HdfsConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub openlineage: OpenLineageConfig,
+    #[serde(default)]
+    pub poll: PollConfig,
+    #[serde(default)]
+    pub duplicates: DuplicatesConfig,
+    #[serde(default)]
+    pub rules: Vec<RuleConfig>,
 }
 
 impl Config {
@@ -126,12 +544,17 @@ impl Config {
             .join("config.toml")
     }
 
-    pub fn load() -> crate::Result<Self> {
-        let path = if let Ok(env_path) = std::env::var("PARQUET_LENS_CONFIG") {
+    /// The file `load` reads: `$PARQUET_LENS_CONFIG`, else `config_path()`.
+    pub fn active_path() -> PathBuf {
+        if let Ok(env_path) = std::env::var("PARQUET_LENS_CONFIG") {
             PathBuf::from(env_path) // $PARQUET_LENS_CONFIG overrides default config path
         } else {
             Self::config_path()
-        };
+        }
+    }
+
+    pub fn load() -> crate::Result<Self> {
+        let path = Self::active_path();
         if !path.exists() {
             return Ok(Self::default());
         }
@@ -151,4 +574,226 @@ impl Config {
         std::fs::write(&path, content)?;
         Ok(())
     }
+
+    /// Every dotted key the config file accepts, e.g. `display.theme`.
+    /// Lists such as `duplicates.datasets` are single keys; map sections such
+    /// as `s3.buckets` are a key too, and their entries' fields are listed
+    /// with a `<name>` placeholder, e.g. `s3.buckets.<name>.region`.
+    pub fn keys() -> Vec<String> {
+        fn walk(
+            prefix: &str,
+            value: &serde_json::Value,
+            maps: &[(&str, serde_json::Value)],
+            out: &mut Vec<String>,
+        ) {
+            // an empty map in the default config would otherwise list nothing
+            if let Some((_, entry)) = maps.iter().find(|(section, _)| *section == prefix) {
+                out.push(prefix.to_string());
+                walk(&format!("{prefix}.<name>"), entry, maps, out);
+                return;
+            }
+            match value {
+                serde_json::Value::Object(map) => {
+                    for (k, v) in map {
+                        let key = if prefix.is_empty() {
+                            k.clone()
+                        } else {
+                            format!("{prefix}.{k}")
+                        };
+                        walk(&key, v, maps, out);
+                    }
+                }
+                _ => out.push(prefix.to_string()),
+            }
+        }
+        // sections keyed by names the user picks, with a default entry
+        let maps = [(
+            "s3.buckets",
+            serde_json::to_value(S3BucketConfig::default()).unwrap_or_default(),
+        )];
+        let mut keys = Vec::new();
+        // None fields serialize as null, so optional keys are listed too
+        if let Ok(value) = serde_json::to_value(Self::default()) {
+            walk("", &value, &maps, &mut keys);
+        }
+        keys
+    }
+
+    /// Whether `key` is one of `keys()`, with any name (dots included) in a
+    /// `<name>` slot.
+    pub fn is_key(key: &str) -> bool {
+        Self::keys().iter().any(|k| match k.split_once("<name>") {
+            Some((head, tail)) => key
+                .strip_prefix(head)
+                .and_then(|rest| rest.strip_suffix(tail))
+                .is_some_and(|name| !name.is_empty()),
+            None => k == key,
+        })
+    }
+
+    /// The effective value at a dotted key (a whole section for `display`).
+    /// A bucket's fields resolve through `S3Config::for_bucket`, so unlisted
+    /// buckets report the `[s3]` fallback.
+    pub fn get(&self, key: &str) -> Option<serde_json::Value> {
+        if let Some((bucket, field)) = key
+            .strip_prefix("s3.buckets.")
+            .and_then(|rest| rest.rsplit_once('.'))
+        {
+            let value = serde_json::to_value(self.s3.for_bucket(bucket)).ok()?;
+            return value.get(field).cloned();
+        }
+        let value = serde_json::to_value(self).ok()?;
+        key.split('.')
+            .try_fold(&value, |v, part| v.get(part))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests_config_keys {
+    use super::*;
+
+    #[test]
+    fn keys_cover_optional_fields_and_resolve() {
+        let keys = Config::keys();
+        for key in ["display.theme", "display.locale", "duplicates.datasets"] {
+            assert!(keys.iter().any(|k| k == key), "missing {key}");
+        }
+        assert!(!keys.iter().any(|k| k == "display"));
+        let cfg = Config::default();
+        assert!(keys
+            .iter()
+            .filter(|k| !k.contains("<name>"))
+            .all(|k| cfg.get(k).is_some()));
+        assert_eq!(cfg.get("display.theme"), Some(serde_json::json!("dark")));
+        assert_eq!(cfg.get("display.nope"), None);
+    }
+
+    #[test]
+    fn keys_cover_map_sections() {
+        let keys = Config::keys();
+        for key in ["s3.buckets", "s3.buckets.<name>.endpoint_url", "s3.region"] {
+            assert!(keys.iter().any(|k| k == key), "missing {key}");
+        }
+        assert!(Config::is_key("s3.buckets.minio.endpoint_url"));
+        assert!(Config::is_key("s3.buckets"));
+        assert!(!Config::is_key("s3.buckets.minio.nope"));
+        assert!(Config::is_key("s3.buckets.logs.example.com.region"));
+        assert!(!Config::is_key("s3.buckets..region"));
+        let cfg: Config = toml::from_str(
+            r#"
+            [s3.buckets.minio]
+            endpoint_url = "http://minio:9000"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.get("s3.buckets.minio.endpoint_url"),
+            Some(serde_json::json!("http://minio:9000"))
+        );
+        assert_eq!(
+            cfg.get("s3.buckets.other.region"),
+            Some(serde_json::Value::Null)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_duplicates_config {
+    use super::*;
+
+    #[test]
+    fn dataset_entries_override_defaults() {
+        let cfg: Config = toml::from_str(
+            r#"
+            [duplicates]
+            ignore_columns = ["ingested_at"]
+            threshold = 1.0
+
+            [[duplicates.datasets]]
+            path = "data/events/*.parquet"
+            ignore_columns = ["event_id", "ingested_at"]
+
+            [[duplicates.datasets]]
+            path = "data/users.parquet"
+            threshold = 0.0
+            "#,
+        )
+        .unwrap();
+        let dups = &cfg.duplicates;
+        assert_eq!(
+            dups.resolve("data/events/2024.parquet"),
+            (vec!["event_id".into(), "ingested_at".into()], Some(1.0))
+        );
+        assert_eq!(
+            dups.resolve("data/users.parquet"),
+            (vec!["ingested_at".into()], Some(0.0))
+        );
+        assert_eq!(
+            dups.resolve("other.parquet"),
+            (vec!["ingested_at".into()], Some(1.0))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_s3_buckets {
+    use super::*;
+
+    #[test]
+    fn bucket_entries_override_the_s3_section() {
+        let cfg: Config = toml::from_str(
+            r#"
+            [s3]
+            region = "eu-west-1"
+
+            [s3.buckets.lake-raw]
+            endpoint_url = "http://minio.internal:9000"
+            region = "us-east-1"
+            force_path_style = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.s3.for_bucket("lake-raw"),
+            S3BucketConfig {
+                endpoint_url: Some("http://minio.internal:9000".into()),
+                region: Some("us-east-1".into()),
+                force_path_style: Some(true),
+                ..S3BucketConfig::default()
+            }
+        );
+        assert_eq!(
+            cfg.s3.for_bucket("analytics"),
+            S3BucketConfig {
+                region: Some("eu-west-1".into()),
+                ..S3BucketConfig::default()
+            }
+        );
+    }
+
+    #[test]
+    fn compatibility_switches_apply_to_every_bucket_unless_overridden() {
+        let cfg: Config = toml::from_str(
+            r#"
+            [s3]
+            endpoint_url = "https://ceph.internal"
+            force_path_style = true
+            ca_bundle = "/etc/ssl/internal-ca.pem"
+
+            [s3.buckets.scratch]
+            endpoint_url = "http://minio.dev:9000"
+            allow_http = true
+            force_path_style = false
+            "#,
+        )
+        .unwrap();
+        let ceph = cfg.s3.for_bucket("lake");
+        assert_eq!(ceph.force_path_style, Some(true));
+        assert_eq!(ceph.allow_http, None);
+        assert_eq!(ceph.ca_bundle.as_deref(), Some("/etc/ssl/internal-ca.pem"));
+        let minio = cfg.s3.for_bucket("scratch");
+        assert_eq!(minio.force_path_style, Some(false));
+        assert_eq!(minio.allow_http, Some(true));
+    }
 }
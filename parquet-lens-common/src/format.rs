@@ -0,0 +1,155 @@
+use crate::config::ByteUnits;
+
+/// Renders counts, decimals and byte sizes for people to read. The default
+/// (no grouping, `.`, one decimal place, binary units) matches plain Rust
+/// formatting, so scripts parsing output only see a change when configured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormat {
+    pub thousands: Option<char>, // None: no digit grouping
+    pub decimal: char,
+    pub decimals: usize,
+    pub units: ByteUnits,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            thousands: None,
+            decimal: '.',
+            decimals: 1,
+            units: ByteUnits::Iec,
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Separators customary for a POSIX-style locale name such as `de_DE.UTF-8`;
+    /// unknown locales (and `C`/`POSIX`) get the ungrouped default.
+    pub fn for_locale(locale: &str) -> Self {
+        let name = locale.split(['.', '@']).next().unwrap_or("").to_lowercase();
+        let mut parts = name.split(['_', '-']);
+        let lang = parts.next().unwrap_or("");
+        let region = parts.next().unwrap_or("");
+        let (thousands, decimal) = match (lang, region) {
+            (_, "ch") | ("rm", _) => (Some('\''), '.'),
+            ("en" | "ja" | "zh" | "ko" | "th" | "he" | "ms", _) => (Some(','), '.'),
+            ("de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" | "ro", _) => {
+                (Some('.'), ',')
+            }
+            (
+                "fr" | "ru" | "pl" | "sv" | "nb" | "no" | "fi" | "cs" | "sk" | "uk" | "hu" | "bg",
+                _,
+            ) => (Some(' '), ','),
+            _ => (None, '.'),
+        };
+        Self {
+            thousands,
+            decimal,
+            ..Self::default()
+        }
+    }
+
+    /// `1234567` → `1,234,567`
+    pub fn count(&self, n: impl Into<i128>) -> String {
+        let n: i128 = n.into();
+        let digits = self.group(&n.unsigned_abs().to_string());
+        if n < 0 {
+            format!("-{digits}")
+        } else {
+            digits
+        }
+    }
+
+    /// `v` with the configured number of decimal places.
+    pub fn decimal(&self, v: f64) -> String {
+        self.fixed(v, self.decimals)
+    }
+
+    /// `12.3%`, for a value already scaled to 0–100.
+    pub fn pct(&self, v: f64) -> String {
+        format!("{}%", self.decimal(v))
+    }
+
+    /// `1.5 MiB` (or `1.6 MB` with SI units); whole bytes below the first unit.
+    pub fn bytes(&self, bytes: u64) -> String {
+        let (base, units) = match self.units {
+            ByteUnits::Iec => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB"]),
+            ByteUnits::Si => (1000.0, ["B", "kB", "MB", "GB", "TB", "PB"]),
+        };
+        let mut value = bytes as f64;
+        let mut unit = 0;
+        while value >= base && unit < units.len() - 1 {
+            value /= base;
+            unit += 1;
+        }
+        if unit == 0 {
+            format!("{} B", self.count(bytes))
+        } else {
+            format!("{} {}", self.decimal(value), units[unit])
+        }
+    }
+
+    fn fixed(&self, v: f64, places: usize) -> String {
+        if !v.is_finite() {
+            return v.to_string();
+        }
+        let text = format!("{:.*}", places, v.abs());
+        let (int, frac) = text.split_once('.').unwrap_or((&text, ""));
+        let mut out = String::new();
+        // -0.0 and values rounding to zero print unsigned
+        if v < 0.0 && text.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            out.push('-');
+        }
+        out.push_str(&self.group(int));
+        if !frac.is_empty() {
+            out.push(self.decimal);
+            out.push_str(frac);
+        }
+        out
+    }
+
+    fn group(&self, digits: &str) -> String {
+        let Some(sep) = self.thousands else {
+            return digits.to_string();
+        };
+        let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push(sep);
+            }
+            out.push(c);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests_format {
+    use super::*;
+
+    #[test]
+    fn default_matches_plain_formatting() {
+        let fmt = NumberFormat::default();
+        assert_eq!(fmt.count(1234567u64), "1234567");
+        assert_eq!(fmt.pct(12.345), "12.3%");
+        assert_eq!(fmt.bytes(512), "512 B");
+        assert_eq!(fmt.bytes(1536), "1.5 KiB");
+    }
+
+    #[test]
+    fn locales_and_units() {
+        let de = NumberFormat::for_locale("de_DE.UTF-8");
+        assert_eq!(de.count(-1234567i64), "-1.234.567");
+        assert_eq!(de.decimal(1234.56), "1.234,6");
+        let en = NumberFormat {
+            decimals: 2,
+            units: ByteUnits::Si,
+            ..NumberFormat::for_locale("en_US")
+        };
+        assert_eq!(en.count(999u64), "999");
+        assert_eq!(en.bytes(1_234_567), "1.23 MB");
+        assert_eq!(en.bytes(1000), "1.00 kB");
+        assert_eq!(NumberFormat::for_locale("de_CH").count(1000u64), "1'000");
+        assert_eq!(NumberFormat::for_locale("C"), NumberFormat::default());
+    }
+}
@@ -1,5 +1,11 @@
 pub mod config;
-pub use config::{Config, GcsConfig};
+pub mod format;
+pub use config::{
+    AzureConfig, ByteUnits, CacheConfig, Config, GcsConfig, HdfsConfig, HttpConfig,
+    NotificationsConfig, OpenLineageConfig, PollConfig, RetryConfig, RuleConfig, RuleValue,
+    S3BucketConfig, S3Config,
+};
+pub use format::NumberFormat;
 
 use thiserror::Error;
 
@@ -13,10 +19,121 @@ pub enum ParquetLensError {
     Arrow(#[from] arrow::error::ArrowError),
     #[error("Authentication error: {0}")]
     Auth(String),
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("permission denied: {0}")]
+    PermissionDenied(String),
+    #[error("throttled: {0}")]
+    Throttled(String), // rate limited or temporarily unavailable; safe to retry
+    #[error("corrupt data in {location}: {detail}")]
+    Corrupt { location: String, detail: String },
+    #[error("unsupported: {feature}")]
+    Unsupported { feature: String },
+    #[error("{context}: {source}")]
+    Context {
+        context: String, // e.g. the file or row group being read
+        source: Box<ParquetLensError>,
+    },
+    #[error("cancelled")]
+    Cancelled, // the caller's cancel flag was set mid-scan
     #[error("{0}")]
     Other(String),
 }
 
+impl ParquetLensError {
+    /// Wraps the error with where it happened, e.g. `"data.parquet row group 3"`.
+    pub fn context(self, context: impl Into<String>) -> Self {
+        Self::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// An I/O error on `path`, categorized when the kind says what went wrong.
+    pub fn io_at(e: std::io::Error, path: &std::path::Path) -> Self {
+        let path = path.display().to_string();
+        match e.kind() {
+            std::io::ErrorKind::NotFound => Self::NotFound(path),
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied(path),
+            _ => Self::Io(e).context(path),
+        }
+    }
+
+    /// A decode failure while reading `location`.
+    pub fn corrupt(location: impl Into<String>, detail: impl std::fmt::Display) -> Self {
+        Self::Corrupt {
+            location: location.into(),
+            detail: detail.to_string(),
+        }
+    }
+
+    /// The underlying error, without any context wrappers.
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::Context { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// Stable machine-readable category, used in JSON output.
+    pub fn code(&self) -> &'static str {
+        use parquet::errors::ParquetError;
+        use std::io::ErrorKind;
+        match self.root() {
+            Self::Io(e) if e.kind() == ErrorKind::NotFound => "not_found",
+            Self::Io(e) if e.kind() == ErrorKind::PermissionDenied => "permission_denied",
+            Self::Io(_) => "io",
+            Self::Parquet(ParquetError::EOF(_)) => "corrupt",
+            Self::Parquet(ParquetError::NYI(_)) => "unsupported",
+            Self::Parquet(_) => "parquet",
+            Self::Arrow(_) => "arrow",
+            Self::Auth(_) => "auth",
+            Self::NotFound(_) => "not_found",
+            Self::PermissionDenied(_) => "permission_denied",
+            Self::Throttled(_) => "throttled",
+            Self::Corrupt { .. } => "corrupt",
+            Self::Unsupported { .. } => "unsupported",
+            Self::Cancelled => "cancelled",
+            Self::Context { .. } | Self::Other(_) => "other",
+        }
+    }
+
+    /// Whether the same call may succeed if tried again (throttling, timeouts,
+    /// dropped connections). Missing files, bad data and auth failures won't.
+    pub fn is_retryable(&self) -> bool {
+        use std::io::ErrorKind;
+        match self.root() {
+            Self::Throttled(_) => true,
+            Self::Io(e) => matches!(
+                e.kind(),
+                ErrorKind::Interrupted
+                    | ErrorKind::TimedOut
+                    | ErrorKind::WouldBlock
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+            ),
+            _ => false,
+        }
+    }
+
+    pub fn info(&self) -> ErrorInfo {
+        ErrorInfo {
+            code: self.code().into(),
+            message: self.to_string(),
+            retryable: self.is_retryable(),
+        }
+    }
+}
+
+/// Serializable summary of an error for JSON outputs.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ErrorInfo {
+    pub code: String,
+    pub message: String,
+    pub retryable: bool,
+}
+
 pub type Result<T> = std::result::Result<T, ParquetLensError>;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -25,3 +142,22 @@ pub struct FileInfo {
     pub row_count: i64,
     pub row_group_count: usize,
 }
+
+#[cfg(test)]
+mod tests_error {
+    use super::*;
+
+    #[test]
+    fn context_keeps_code_and_retryability() {
+        let e = ParquetLensError::Throttled("SlowDown".into()).context("s3://b/k.parquet");
+        assert_eq!(e.code(), "throttled");
+        assert!(e.is_retryable());
+        assert_eq!(e.to_string(), "s3://b/k.parquet: throttled: SlowDown");
+
+        let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let e = ParquetLensError::io_at(missing, std::path::Path::new("a.parquet"));
+        assert_eq!(e.code(), "not_found");
+        assert!(!e.is_retryable());
+        assert_eq!(ParquetLensError::corrupt("x", "bad page").code(), "corrupt");
+    }
+}
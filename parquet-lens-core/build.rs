@@ -0,0 +1,24 @@
+// Embeds `git describe` output as PARQUET_LENS_GIT_DESCRIBE for run metadata;
+// builds from a source tarball (no git) simply leave it unset.
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    let describe = Command::new("git")
+        .args(["describe", "--always", "--dirty", "--tags"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    if let Some(d) = describe {
+        println!("cargo:rustc-env=PARQUET_LENS_GIT_DESCRIBE={d}");
+    }
+    // missing files would make cargo rerun this on every build
+    for f in ["../.git/HEAD", "../.git/index"] {
+        if Path::new(f).exists() {
+            println!("cargo:rerun-if-changed={f}");
+        }
+    }
+}
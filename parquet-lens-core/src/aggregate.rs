@@ -0,0 +1,351 @@
+use crate::export::with_offset_zones;
+use crate::filter::{
+    eval_predicate_batch, leaf_projection, plan_row_groups, predicate_columns, resolve_column,
+    resolve_type, scan_selection, Predicate,
+};
+use crate::handle::HandleRegistry;
+use crate::options::{prefetch_batches, ScanOptions};
+use crate::progress::row_group_totals;
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::compute::{cast, filter_record_batch};
+use arrow::datatypes::{DataType, Float64Type, Int64Type};
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AggFunc {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+/// One `func(column)` of an aggregate query; `column` is None for `count(*)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggExpr {
+    pub func: AggFunc,
+    pub column: Option<String>,
+}
+
+impl AggExpr {
+    /// Header for the result column, e.g. `sum(bytes)`.
+    pub fn label(&self) -> String {
+        let func = match self.func {
+            AggFunc::Count => "count",
+            AggFunc::Sum => "sum",
+            AggFunc::Avg => "avg",
+            AggFunc::Min => "min",
+            AggFunc::Max => "max",
+        };
+        format!("{func}({})", self.column.as_deref().unwrap_or("*"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggQuery {
+    pub aggs: Vec<AggExpr>,
+    pub group_by: Vec<String>,
+}
+
+/// Parses `count(*), sum(bytes), avg(latency) group by region`.
+pub fn parse_aggregate(spec: &str) -> Result<AggQuery, String> {
+    let lower = spec.to_ascii_lowercase();
+    let (aggs, group_by) = match lower.find(" group by ") {
+        Some(i) => (&spec[..i], Some(&spec[i + " group by ".len()..])),
+        None => (spec, None),
+    };
+    let aggs = aggs
+        .split(',')
+        .map(parse_agg_expr)
+        .collect::<Result<Vec<_>, _>>()?;
+    let group_by = match group_by {
+        Some(cols) => cols
+            .split(',')
+            .map(|c| match column_name(c) {
+                "" => Err(format!("empty group by column in '{spec}'")),
+                c => Ok(c.to_string()),
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        None => Vec::new(),
+    };
+    Ok(AggQuery { aggs, group_by })
+}
+
+fn parse_agg_expr(expr: &str) -> Result<AggExpr, String> {
+    let expr = expr.trim();
+    let (func, arg) = expr
+        .strip_suffix(')')
+        .and_then(|e| e.split_once('('))
+        .ok_or_else(|| format!("expected func(column), got '{expr}'"))?;
+    let func = match func.trim().to_ascii_lowercase().as_str() {
+        "count" => AggFunc::Count,
+        "sum" => AggFunc::Sum,
+        "avg" => AggFunc::Avg,
+        "min" => AggFunc::Min,
+        "max" => AggFunc::Max,
+        other => {
+            return Err(format!(
+                "unknown aggregate '{other}' (count, sum, avg, min, max)"
+            ))
+        }
+    };
+    let column = match column_name(arg) {
+        "*" if func == AggFunc::Count => None,
+        "*" | "" => return Err(format!("'{expr}' needs a column")),
+        c => Some(c.to_string()),
+    };
+    Ok(AggExpr { func, column })
+}
+
+fn column_name(s: &str) -> &str {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+/// One row per group (a single row without `group by`), sorted by group key:
+/// the group columns, then one column per aggregate. Empty aggregates are `NULL`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggResult {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub matched_rows: u64,
+    pub scanned_rows: u64,
+}
+
+// running state of one aggregate within one group
+#[derive(Debug, Clone, Default)]
+struct Acc {
+    count: u64,
+    int_sum: i128,
+    float_sum: f64,
+    int_min: Option<i64>,
+    int_max: Option<i64>,
+    float_min: Option<f64>,
+    float_max: Option<f64>,
+}
+
+/// An aggregate's input column for one batch, widened for accumulation.
+enum Input {
+    Rows,
+    Int(ArrayRef),
+    Float(ArrayRef),
+    Any(ArrayRef),
+}
+
+/// Aggregates the rows `predicate` matches across `paths`, one batch at a time;
+/// only the predicate, aggregate and group columns are read.
+#[tracing::instrument(skip_all, fields(files = paths.len()))]
+pub fn filter_aggregate(
+    paths: &[PathBuf],
+    predicate: &Predicate,
+    query: &AggQuery,
+    opts: &ScanOptions,
+) -> Result<AggResult, String> {
+    let mut groups: BTreeMap<Vec<String>, Vec<Acc>> = BTreeMap::new();
+    let (mut matched_rows, mut scanned_rows) = (0u64, 0u64);
+    for path in paths {
+        let err = |e: &dyn std::fmt::Display| format!("{}: {e}", path.display());
+        let handle = HandleRegistry::global().get(path).map_err(|e| err(&e))?;
+        let arrow_meta = handle.arrow_metadata().map_err(|e| err(&e))?;
+        let mut wanted: Vec<String> = predicate_columns(predicate)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        wanted.extend(query.group_by.iter().cloned());
+        wanted.extend(query.aggs.iter().filter_map(|a| a.column.clone()));
+        check_columns(query, arrow_meta.schema(), &wanted).map_err(|e| err(&e))?;
+        let mask = leaf_projection(&arrow_meta, &wanted);
+        let meta = handle.metadata().clone();
+        let to_scan = plan_row_groups(path, &meta, predicate).to_scan;
+        if to_scan.is_empty() {
+            continue;
+        }
+        let reader = handle
+            .reader_builder()
+            .map_err(|e| err(&e))?
+            .with_projection(mask)
+            .with_row_selection(scan_selection(&meta, &to_scan))
+            .with_batch_size(opts.batch_size)
+            .build()
+            .map_err(|e| err(&e))?;
+        let progress = opts.start_progress("aggregate", row_group_totals(&meta, &to_scan));
+        let mut batch_gauge = opts.gauge();
+        for batch in prefetch_batches(reader, opts.prefetch_depth) {
+            let batch = batch.map_err(|e| err(&e))?;
+            batch_gauge.set(batch.get_array_memory_size());
+            scanned_rows += batch.num_rows() as u64;
+            progress.advance_rows(batch.num_rows() as u64);
+            let matches = filter_record_batch(&batch, &eval_predicate_batch(predicate, &batch))
+                .map_err(|e| err(&e))?;
+            matched_rows += matches.num_rows() as u64;
+            accumulate(&mut groups, query, &matches).map_err(|e| err(&e))?;
+        }
+    }
+    // SQL semantics: no group by still yields one row when nothing matched
+    if query.group_by.is_empty() && groups.is_empty() {
+        groups.insert(Vec::new(), vec![Acc::default(); query.aggs.len()]);
+    }
+    let mut headers = query.group_by.clone();
+    headers.extend(query.aggs.iter().map(AggExpr::label));
+    let rows = groups
+        .into_iter()
+        .map(|(mut key, accs)| {
+            key.extend(query.aggs.iter().zip(&accs).map(|(a, acc)| finish(a, acc)));
+            key
+        })
+        .collect();
+    Ok(AggResult {
+        headers,
+        rows,
+        matched_rows,
+        scanned_rows,
+    })
+}
+
+fn check_columns(
+    query: &AggQuery,
+    schema: &arrow::datatypes::SchemaRef,
+    wanted: &[String],
+) -> Result<(), String> {
+    for col in wanted {
+        resolve_type(schema, col)?;
+    }
+    for a in &query.aggs {
+        let Some(col) = &a.column else { continue };
+        let dt = resolve_type(schema, col)?;
+        if matches!(
+            a.func,
+            AggFunc::Sum | AggFunc::Avg | AggFunc::Min | AggFunc::Max
+        ) && !dt.is_numeric()
+        {
+            return Err(format!("{}: column is {dt}, not numeric", a.label()));
+        }
+    }
+    Ok(())
+}
+
+fn accumulate(
+    groups: &mut BTreeMap<Vec<String>, Vec<Acc>>,
+    query: &AggQuery,
+    batch: &RecordBatch,
+) -> parquet_lens_common::Result<()> {
+    if batch.num_rows() == 0 {
+        return Ok(());
+    }
+    let keys = query
+        .group_by
+        .iter()
+        .map(|c| with_offset_zones(&resolve_column(batch, c).expect("projected")))
+        .collect::<parquet_lens_common::Result<Vec<_>>>()?;
+    let inputs = query
+        .aggs
+        .iter()
+        .map(|a| {
+            let Some(col) = &a.column else {
+                return Ok(Input::Rows);
+            };
+            let arr = &resolve_column(batch, col).expect("projected");
+            Ok(match (a.func, arr.data_type()) {
+                (AggFunc::Count, _) => Input::Any(arr.clone()),
+                (AggFunc::Avg, _) => Input::Float(cast(arr, &DataType::Float64)?),
+                (_, dt) if dt.is_integer() => Input::Int(cast(arr, &DataType::Int64)?),
+                _ => Input::Float(cast(arr, &DataType::Float64)?),
+            })
+        })
+        .collect::<parquet_lens_common::Result<Vec<_>>>()?;
+    for row in 0..batch.num_rows() {
+        let key: Vec<String> = keys
+            .iter()
+            .map(|k| match k.is_null(row) {
+                true => Ok("NULL".to_string()),
+                false => array_value_to_string(k, row),
+            })
+            .collect::<Result<_, arrow::error::ArrowError>>()?;
+        let accs = groups
+            .entry(key)
+            .or_insert_with(|| vec![Acc::default(); query.aggs.len()]);
+        for (acc, input) in accs.iter_mut().zip(&inputs) {
+            match input {
+                Input::Rows => acc.count += 1,
+                Input::Any(a) => acc.count += a.is_valid(row) as u64,
+                Input::Int(a) => {
+                    let a = a.as_primitive::<Int64Type>();
+                    if a.is_valid(row) {
+                        let v = a.value(row);
+                        acc.count += 1;
+                        acc.int_sum += v as i128;
+                        acc.int_min = Some(acc.int_min.map_or(v, |m| m.min(v)));
+                        acc.int_max = Some(acc.int_max.map_or(v, |m| m.max(v)));
+                    }
+                }
+                Input::Float(a) => {
+                    let a = a.as_primitive::<Float64Type>();
+                    if a.is_valid(row) {
+                        let v = a.value(row);
+                        acc.count += 1;
+                        acc.float_sum += v;
+                        acc.float_min = Some(acc.float_min.map_or(v, |m| m.min(v)));
+                        acc.float_max = Some(acc.float_max.map_or(v, |m| m.max(v)));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn finish(agg: &AggExpr, acc: &Acc) -> String {
+    let null = || "NULL".to_string();
+    if agg.func == AggFunc::Count {
+        return acc.count.to_string();
+    }
+    if acc.count == 0 {
+        return null();
+    }
+    let int = acc.int_min.is_some();
+    match agg.func {
+        AggFunc::Sum if int => acc.int_sum.to_string(),
+        AggFunc::Sum => acc.float_sum.to_string(),
+        AggFunc::Avg => (acc.float_sum / acc.count as f64).to_string(),
+        AggFunc::Min if int => acc.int_min.map_or_else(null, |v| v.to_string()),
+        AggFunc::Min => acc.float_min.map_or_else(null, |v| v.to_string()),
+        AggFunc::Max if int => acc.int_max.map_or_else(null, |v| v.to_string()),
+        AggFunc::Max => acc.float_max.map_or_else(null, |v| v.to_string()),
+        AggFunc::Count => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests_parse_aggregate {
+    use super::*;
+
+    #[test]
+    fn aggregates_and_groups() {
+        let q = parse_aggregate("count(*), SUM(bytes), avg( latency ) group by region, \"day\"")
+            .unwrap();
+        let labels: Vec<String> = q.aggs.iter().map(AggExpr::label).collect();
+        assert_eq!(labels, ["count(*)", "sum(bytes)", "avg(latency)"]);
+        assert_eq!(q.group_by, ["region", "day"]);
+    }
+
+    #[test]
+    fn no_group_by() {
+        let q = parse_aggregate("max(x)").unwrap();
+        assert_eq!(q.aggs[0].func, AggFunc::Max);
+        assert!(q.group_by.is_empty());
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(parse_aggregate("median(x)").is_err());
+        assert!(parse_aggregate("sum(*)").is_err());
+        assert!(parse_aggregate("count").is_err());
+        assert!(parse_aggregate("count(*) group by ").is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use crate::remote::{retry_config, store_err};
+use crate::retry::request_timeout;
+use crate::storage::{AzureBackend, StorageBackend};
+use bytes::Bytes;
+use object_store::azure::{AzureConfigKey, MicrosoftAzure, MicrosoftAzureBuilder};
+use object_store::ClientConfigKey;
+use parquet::file::metadata::ParquetMetaData;
+use parquet_lens_common::{AzureConfig, ParquetLensError, Result};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// parsed `az://container/path` or `abfs[s]://container@account.dfs.core.windows.net/path`
+#[derive(Debug, Clone)]
+pub struct AzureUri {
+    pub scheme: String,
+    pub container: String,
+    pub account: Option<String>, // only abfs URIs carry the account
+    pub path: String,
+}
+
+impl AzureUri {
+    /// The container in this URI's form; blob URIs are `root/path`.
+    pub(crate) fn root(&self) -> String {
+        match &self.account {
+            Some(account) => format!(
+                "{}://{}@{account}.dfs.core.windows.net",
+                self.scheme, self.container
+            ),
+            None => format!("{}://{}", self.scheme, self.container),
+        }
+    }
+}
+
+pub fn parse_azure_uri(uri: &str) -> Option<AzureUri> {
+    let (scheme, rest) = uri.split_once("://")?;
+    let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let (container, account) = match scheme {
+        "az" => (authority, None),
+        "abfs" | "abfss" => {
+            let (container, host) = authority.split_once('@')?;
+            let account = host.split('.').next().filter(|a| !a.is_empty())?;
+            (container, Some(account.to_owned()))
+        }
+        _ => return None,
+    };
+    if container.is_empty() {
+        return None;
+    }
+    Some(AzureUri {
+        scheme: scheme.to_owned(),
+        container: container.to_owned(),
+        account,
+        path: path.to_owned(),
+    })
+}
+
+pub fn is_azure_uri(path: &str) -> bool {
+    ["az://", "abfs://", "abfss://"]
+        .iter()
+        .any(|p| path.starts_with(p))
+}
+
+/// Store for the container `uri` names, with `[azure]` settings layered over
+/// the environment.
+pub(crate) fn azure_store(uri: &str, config: &AzureConfig) -> Result<(MicrosoftAzure, AzureUri)> {
+    let parsed = parse_azure_uri(uri)
+        .ok_or_else(|| ParquetLensError::Other(format!("invalid Azure URI: {uri}")))?;
+    let config = config.clone();
+    let mut builder = MicrosoftAzureBuilder::from_env()
+        .with_container_name(&parsed.container)
+        .with_retry(retry_config())
+        .with_config(
+            AzureConfigKey::Client(ClientConfigKey::Timeout),
+            request_timeout(),
+        );
+    if let Some(account) = parsed.account.clone().or(config.account) {
+        builder = builder.with_account(account);
+    }
+    if let Some(key) = config.access_key {
+        builder = builder.with_access_key(key);
+    }
+    if let Some(sas) = config.sas_token {
+        builder = builder.with_config(AzureConfigKey::SasKey, sas);
+    }
+    if let Some(id) = config.client_id {
+        builder = builder.with_client_id(id);
+    }
+    if let Some(secret) = config.client_secret {
+        builder = builder.with_client_secret(secret);
+    }
+    if let Some(tenant) = config.tenant_id {
+        builder = builder.with_tenant_id(tenant);
+    }
+    if let Some(endpoint) = config.endpoint {
+        builder = builder
+            .with_allow_http(endpoint.starts_with("http://"))
+            .with_endpoint(endpoint);
+    }
+    if config.use_emulator {
+        builder = builder.with_use_emulator(true);
+    }
+    let store = builder.build().map_err(|e| store_err(e).context(uri))?;
+    Ok((store, parsed))
+}
+
+/// list .parquet blobs under a container prefix; a blob URI lists just itself
+pub async fn list_azure_parquet(uri: &str) -> Result<Vec<String>> {
+    AzureBackend.list(uri).await
+}
+
+/// read Parquet metadata from an Azure blob
+pub async fn read_azure_parquet_metadata(uri: &str) -> Result<Arc<ParquetMetaData>> {
+    AzureBackend.open(uri).await.map(|(_, meta)| meta)
+}
+
+/// Blob size, last modification (RFC 3339) and ETag.
+pub async fn azure_object_metadata(uri: &str) -> Result<(u64, Option<String>, Option<String>)> {
+    let info = AzureBackend.metadata(uri).await?;
+    Ok((info.size, info.last_modified, info.etag))
+}
+
+/// bytes [start, end) of an Azure blob
+pub async fn read_azure_range(uri: &str, range: Range<u64>) -> Result<Bytes> {
+    AzureBackend.read_range(uri, range).await
+}
+
+#[cfg(test)]
+mod tests_azure_uri {
+    use super::*;
+
+    #[test]
+    fn parses_az_and_abfs_forms() {
+        let az = parse_azure_uri("az://data/events/2024.parquet").unwrap();
+        assert_eq!(
+            (az.container.as_str(), az.account.as_deref()),
+            ("data", None)
+        );
+        assert_eq!(az.path, "events/2024.parquet");
+        assert_eq!(az.root(), "az://data");
+
+        let abfs = parse_azure_uri("abfss://data@acct.dfs.core.windows.net/events/").unwrap();
+        assert_eq!(abfs.account.as_deref(), Some("acct"));
+        assert_eq!(abfs.path, "events/");
+        assert_eq!(abfs.root(), "abfss://data@acct.dfs.core.windows.net");
+
+        assert!(parse_azure_uri("abfs://data/events").is_none()); // no account
+        assert!(parse_azure_uri("s3://bucket/key").is_none());
+        assert!(is_azure_uri("abfs://c@a.dfs.core.windows.net/x") && !is_azure_uri("gs://b/o"));
+    }
+}
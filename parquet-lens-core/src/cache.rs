@@ -0,0 +1,364 @@
+use crate::options::ScanOptions;
+use crate::parallel_reader::{assemble_dataset, read_file_entry, DatasetProfile, FileEntry};
+use crate::profile::{
+    profile_columns_with_timeout, profile_dataset, ColumnProfileResult, HistogramScale,
+    HllEstimator, DEFAULT_HLL_PRECISION,
+};
+use crate::reader::open_parquet_file;
+use crate::scanner::ParquetFilePath;
+use crate::stats::FileStats;
+use parquet_lens_common::{CacheConfig, Result};
+use rayon::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Identifies one cached computation. An entry is only reused when every field
+/// matches, so rewriting the file, upgrading the tool or changing options
+/// (e.g. histogram bins) invalidates it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheKey {
+    pub path: String,
+    pub size: u64,
+    pub mtime_ms: u64,
+    pub version: String,
+    pub options_hash: u64,
+    /// xxh3 of the file's bytes when keyed by content; `mtime_ms` is then 0 so
+    /// touching or copying the file keeps its entries valid.
+    #[serde(default)]
+    pub content_hash: Option<u64>,
+}
+
+impl CacheKey {
+    /// Builds a key from the file's current size and mtime; None for paths that
+    /// can't be stat'ed locally (e.g. s3:// URIs), which are never cached.
+    pub fn for_file(path: &Path, options: &str) -> Option<Self> {
+        let md = std::fs::metadata(path).ok()?;
+        let mtime_ms = md
+            .modified()
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_millis() as u64;
+        let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        Some(Self {
+            path: canonical.to_string_lossy().into_owned(),
+            size: md.len(),
+            mtime_ms,
+            version: env!("CARGO_PKG_VERSION").into(),
+            options_hash: xxhash_rust::xxh3::xxh3_64(options.as_bytes()),
+            content_hash: None,
+        })
+    }
+
+    /// `for_file` that identifies the file by a hash of its contents rather
+    /// than its mtime; reads the whole file.
+    pub fn for_file_contents(path: &Path, options: &str) -> Option<Self> {
+        let mut key = Self::for_file(path, options)?;
+        let mut file = std::fs::File::open(path).ok()?;
+        let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+        let mut buf = vec![0u8; 1 << 20];
+        loop {
+            let n = file.read(&mut buf).ok()?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        key.mtime_ms = 0;
+        key.content_hash = Some(hasher.digest());
+        Some(key)
+    }
+
+    // one slot per (kind, path, options); stale entries are overwritten in place
+    fn file_name(&self, kind: &str) -> String {
+        let hash =
+            xxhash_rust::xxh3::xxh3_64(format!("{}\0{}", self.path, self.options_hash).as_bytes());
+        format!("{kind}_{hash:016x}.json")
+    }
+}
+
+/// The scan settings that change full-scan results, for `CacheKey::options`;
+/// linear histograms and the default HLL precision keep the original `bins=N`
+/// form so existing entries stay valid.
+fn scan_options(histogram_bins: usize, opts: &ScanOptions) -> String {
+    let mut out = match opts.histogram_scale {
+        HistogramScale::Linear => format!("bins={histogram_bins}"),
+        HistogramScale::Log => format!("bins={histogram_bins},scale=log"),
+    };
+    if opts.hll_precision != DEFAULT_HLL_PRECISION {
+        out.push_str(&format!(",hll={}", opts.hll_precision));
+    }
+    out
+}
+
+// one slot per (file set, options), like `CacheKey::file_name`
+fn dataset_file_name(keys: &[CacheKey]) -> String {
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    for k in keys {
+        hasher.update(k.path.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(&k.options_hash.to_le_bytes());
+    }
+    format!("dataset_{:016x}.json", hasher.digest())
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<K, T> {
+    key: K,
+    value: T,
+}
+
+/// On-disk cache of computed profiles under `<config dir>/parquet-lens/cache`.
+#[derive(Debug, Clone)]
+pub struct ProfileCache {
+    dir: PathBuf,
+    content_hash: bool,
+}
+
+impl ProfileCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            content_hash: false,
+        }
+    }
+
+    /// The default cache, keyed by content when `[cache] content_hash` is set.
+    pub fn from_config(cfg: &CacheConfig) -> Self {
+        Self::open_default().content_hash(cfg.content_hash)
+    }
+
+    /// Key entries by a hash of each file's bytes instead of its mtime.
+    pub fn content_hash(mut self, on: bool) -> Self {
+        self.content_hash = on;
+        self
+    }
+
+    pub fn default_dir() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("parquet-lens")
+            .join("cache")
+    }
+
+    pub fn open_default() -> Self {
+        Self::new(Self::default_dir())
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn key(&self, path: &Path, options: &str) -> Option<CacheKey> {
+        if self.content_hash {
+            CacheKey::for_file_contents(path, options)
+        } else {
+            CacheKey::for_file(path, options)
+        }
+    }
+
+    fn load<K, T>(&self, name: &str, key: &K) -> Option<T>
+    where
+        K: DeserializeOwned + PartialEq,
+        T: DeserializeOwned,
+    {
+        let raw = std::fs::read_to_string(self.dir.join(name)).ok()?;
+        let entry: CacheEntry<K, T> = serde_json::from_str(&raw).ok()?;
+        (entry.key == *key).then_some(entry.value)
+    }
+
+    fn store<K: Serialize, T: Serialize>(&self, name: &str, key: &K, value: &T) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let entry = CacheEntry { key, value };
+        let json = serde_json::to_string(&entry)
+            .map_err(|e| parquet_lens_common::ParquetLensError::Other(e.to_string()))?;
+        // write-then-rename so a concurrent reader never sees a torn entry
+        let tmp = self.dir.join(format!("{name}.tmp"));
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, self.dir.join(name))?;
+        Ok(())
+    }
+
+    /// Removes every cached entry, returning how many files were deleted.
+    pub fn clear(&self) -> Result<usize> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(e) => e,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_file() {
+                std::fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// `read_metadata_parallel` that reuses cached per-file entries and only
+    /// re-reads footers of files that are new or changed since the last run.
+    #[tracing::instrument(name = "cached_read_metadata", skip_all, fields(files = paths.len()))]
+    pub fn read_metadata(&self, paths: &[ParquetFilePath]) -> Result<DatasetProfile> {
+        let entries: Vec<Result<FileEntry>> = paths
+            .par_iter()
+            .map(|pf| {
+                let key = self.key(&pf.path, "");
+                if let Some(entry) = key
+                    .as_ref()
+                    .and_then(|k| self.load(&k.file_name("file"), k))
+                {
+                    return Ok(entry);
+                }
+                let entry = read_file_entry(pf)?;
+                if let Some(k) = &key {
+                    // cache writes are best-effort; a failure just means a slower next run
+                    let _ = self.store(&k.file_name("file"), k, &entry);
+                }
+                Ok(entry)
+            })
+            .collect();
+        assemble_dataset(paths, entries)
+    }
+
+    /// Footer stats of the local file at `path`, read from its footer only
+    /// when the file is new or changed since the last run.
+    pub fn file_stats(&self, path: &Path) -> Result<FileStats> {
+        let key = self.key(path, "");
+        if let Some(stats) = key
+            .as_ref()
+            .and_then(|k| self.load(&k.file_name("stats"), k))
+        {
+            return Ok(stats);
+        }
+        let (info, meta) = open_parquet_file(path)?;
+        let stats = FileStats::from_metadata(info, &meta);
+        if let Some(k) = &key {
+            let _ = self.store(&k.file_name("stats"), k, &stats);
+        }
+        Ok(stats)
+    }
+
+    /// `profile_columns_with_timeout` that reuses cached column results and only
+    /// scans the requested columns missing from the cache. Truncated (timed-out)
+    /// results are returned but never stored.
+    pub fn profile_columns(
+        &self,
+        path: &Path,
+        columns: Option<&[String]>,
+        histogram_bins: usize,
+        timeout_secs: Option<u64>,
+        opts: &ScanOptions,
+    ) -> Result<Vec<ColumnProfileResult>> {
+        let Some(key) = self.key(path, &scan_options(histogram_bins, opts)) else {
+            return profile_columns_with_timeout(path, columns, histogram_bins, timeout_secs, opts);
+        };
+        self.cached_columns(&key.file_name("columns"), &key, columns, opts, |missing| {
+            profile_columns_with_timeout(path, missing, histogram_bins, timeout_secs, opts)
+        })
+    }
+
+    /// `profile_dataset` with the same per-column reuse as `profile_columns`.
+    /// The entry is keyed by every file, so changing, adding or removing any
+    /// of them rescans the requested columns.
+    pub fn profile_dataset(
+        &self,
+        paths: &[ParquetFilePath],
+        columns: Option<&[String]>,
+        histogram_bins: usize,
+        timeout_secs: Option<u64>,
+        opts: &ScanOptions,
+    ) -> Result<Vec<ColumnProfileResult>> {
+        let options = scan_options(histogram_bins, opts);
+        let keys: Option<Vec<CacheKey>> = paths
+            .par_iter()
+            .map(|pf| self.key(&pf.path, &options))
+            .collect();
+        let Some(keys) = keys else {
+            return profile_dataset(paths, columns, histogram_bins, timeout_secs, opts);
+        };
+        self.cached_columns(&dataset_file_name(&keys), &keys, columns, opts, |missing| {
+            profile_dataset(paths, missing, histogram_bins, timeout_secs, opts)
+        })
+    }
+
+    // serves `columns` from the entry at `name`, scanning only the missing ones
+    fn cached_columns<K>(
+        &self,
+        name: &str,
+        key: &K,
+        columns: Option<&[String]>,
+        opts: &ScanOptions,
+        scan: impl FnOnce(Option<&[String]>) -> Result<Vec<ColumnProfileResult>>,
+    ) -> Result<Vec<ColumnProfileResult>>
+    where
+        K: Serialize + DeserializeOwned + PartialEq,
+    {
+        // cached map also records which columns exist so `None` can be answered
+        let mut cached: CachedColumns = self.load(name, key).unwrap_or_default();
+        let wanted: Option<Vec<String>> =
+            columns.map(|c| c.to_vec()).or_else(|| cached.all.clone());
+        // None = column list unknown yet, so scan everything
+        let missing: Option<Vec<String>> = wanted.as_ref().map(|w| {
+            w.iter()
+                .filter(|c| !cached.results.contains_key(*c))
+                .filter(|c| cached.all.as_ref().is_none_or(|all| all.contains(c)))
+                .cloned()
+                .collect()
+        });
+        if missing.as_ref().is_some_and(|m| m.is_empty()) {
+            return Ok(cached.select(wanted.as_deref()));
+        }
+        let quarantined = opts.quarantine.as_ref().map_or(0, |q| q.len());
+        let fresh = scan(missing.as_deref())?;
+        // partial results (deadline hit, row groups skipped) aren't cached, nor
+        // the coarser ones of a scan that ran over its memory limit
+        let skipped = opts.quarantine.as_ref().map_or(0, |q| q.len()) > quarantined;
+        let degraded = opts.memory.as_ref().is_some_and(|m| m.over_limit());
+        if skipped || degraded || fresh.iter().any(|r| r.truncated) {
+            return Ok(fresh);
+        }
+        if missing.is_none() {
+            cached.all = Some(fresh.iter().map(|r| r.column_name.clone()).collect());
+        }
+        for r in fresh {
+            if let Some(sketch) = &r.cardinality.sketch {
+                cached
+                    .sketches
+                    .insert(r.column_name.clone(), sketch.clone());
+            }
+            cached.results.insert(r.column_name.clone(), r);
+        }
+        let _ = self.store(name, key, &cached);
+        Ok(cached.select(wanted.as_deref()))
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CachedColumns {
+    all: Option<Vec<String>>, // every column in the file, once a full scan has run
+    results: HashMap<String, ColumnProfileResult>,
+    // cardinality sketches, which reports leave out of their JSON, kept so
+    // cached results still merge across files
+    #[serde(default)]
+    sketches: HashMap<String, HllEstimator>,
+}
+
+impl CachedColumns {
+    // cached results for `names` (all when None), in file order when it is known
+    fn select(&self, names: Option<&[String]>) -> Vec<ColumnProfileResult> {
+        let order = self.all.as_deref().or(names).unwrap_or_default();
+        order
+            .iter()
+            .filter(|n| names.is_none_or(|w| w.contains(n)))
+            .filter_map(|n| {
+                let mut r = self.results.get(n).cloned()?;
+                r.cardinality.sketch = self.sketches.get(n).cloned();
+                Some(r)
+            })
+            .collect()
+    }
+}
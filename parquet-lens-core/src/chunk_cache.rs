@@ -0,0 +1,309 @@
+use crate::cache::ProfileCache;
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use object_store::ObjectMeta;
+use parquet::arrow::async_reader::{AsyncFileReader, ParquetObjectReader};
+use parquet::file::metadata::ParquetMetaData;
+use parquet_lens_common::{ParquetLensError, Result};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+/// Byte ranges of remote objects (column chunks, mostly), stored next to the
+/// profile cache as `chunk_<hash>.bin` so `cache clear` removes them too.
+/// Entries are keyed by URI, offset and length plus the object's version, so a
+/// rewritten object misses. Once the entries pass `max_bytes` the least
+/// recently read are deleted.
+#[derive(Debug, Clone)]
+pub struct ChunkCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct ChunkHeader {
+    uri: String,
+    version: String, // size plus ETag (or last-modified) of the object the bytes came from
+    start: u64,
+    end: u64,
+}
+
+static CHUNK_CACHE: RwLock<Option<ChunkCache>> = RwLock::new(None);
+
+/// The chunk cache remote full scans consult from now on; None disables it.
+pub fn set_chunk_cache(cache: Option<ChunkCache>) {
+    *CHUNK_CACHE.write().unwrap_or_else(|e| e.into_inner()) = cache;
+}
+
+pub(crate) fn chunk_cache() -> Option<ChunkCache> {
+    CHUNK_CACHE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+impl ChunkCache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    /// `ProfileCache::default_dir()` holding at most `max_bytes` of chunks.
+    pub fn open_default(max_bytes: u64) -> Self {
+        Self::new(ProfileCache::default_dir(), max_bytes)
+    }
+
+    fn path(&self, header: &ChunkHeader) -> PathBuf {
+        let key = format!(
+            "{}\n{}\n{}\n{}",
+            header.uri, header.version, header.start, header.end
+        );
+        let hash = xxhash_rust::xxh3::xxh3_64(key.as_bytes());
+        self.dir.join(format!("chunk_{hash:016x}.bin"))
+    }
+
+    /// Bytes `range` of `uri` at `version`, if cached; a hit counts as a use.
+    pub fn get(&self, uri: &str, version: &str, range: Range<u64>) -> Option<Bytes> {
+        let header = ChunkHeader {
+            uri: uri.to_string(),
+            version: version.to_string(),
+            start: range.start,
+            end: range.end,
+        };
+        let path = self.path(&header);
+        let raw = Bytes::from(std::fs::read(&path).ok()?);
+        let split = raw.iter().position(|&b| b == b'\n')?;
+        let stored: ChunkHeader = serde_json::from_slice(&raw[..split]).ok()?;
+        let bytes = raw.slice(split + 1..);
+        if stored != header || bytes.len() as u64 != range.end - range.start {
+            return None;
+        }
+        // the modification time orders entries for eviction
+        if let Ok(file) = std::fs::File::options().append(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(bytes)
+    }
+
+    /// Records `bytes` as range `range` of `uri` at `version`, then evicts the
+    /// least recently used entries past the size limit.
+    pub fn put(&self, uri: &str, version: &str, range: Range<u64>, bytes: &[u8]) -> Result<()> {
+        if bytes.len() as u64 > self.max_bytes {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        let header = ChunkHeader {
+            uri: uri.to_string(),
+            version: version.to_string(),
+            start: range.start,
+            end: range.end,
+        };
+        let mut raw =
+            serde_json::to_vec(&header).map_err(|e| ParquetLensError::Other(e.to_string()))?;
+        raw.push(b'\n');
+        raw.extend_from_slice(bytes);
+        let path = self.path(&header);
+        // write-then-rename so a concurrent reader never sees half an entry
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        std::fs::write(&tmp, &raw)?;
+        std::fs::rename(&tmp, &path)?;
+        self.evict()
+    }
+
+    /// Total size of the cached chunks, in bytes.
+    pub fn size(&self) -> u64 {
+        self.entries().iter().map(|(_, len, _)| len).sum()
+    }
+
+    fn entries(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let Ok(dir) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        dir.flatten()
+            .filter(|e| {
+                let name = e.file_name();
+                let name = name.to_string_lossy();
+                name.starts_with("chunk_") && name.ends_with(".bin")
+            })
+            .filter_map(|e| {
+                let meta = e.metadata().ok()?;
+                Some((e.path(), meta.len(), meta.modified().ok()?))
+            })
+            .collect()
+    }
+
+    fn evict(&self) -> Result<()> {
+        let mut entries = self.entries();
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total <= self.max_bytes {
+            return Ok(());
+        }
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            // another process may have evicted it first
+            if std::fs::remove_file(&path).is_ok() {
+                total -= len;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Version tag for chunks of the object `meta` describes.
+pub(crate) fn object_version(meta: &ObjectMeta) -> String {
+    let tag = meta
+        .e_tag
+        .clone()
+        .unwrap_or_else(|| meta.last_modified.to_rfc3339());
+    format!("{}:{tag}", meta.size)
+}
+
+/// `ParquetObjectReader` that answers range reads from the chunk cache when it
+/// can and stores what it fetches. Without a cache it just delegates.
+#[derive(Clone)]
+pub(crate) struct CachedObjectReader {
+    inner: ParquetObjectReader,
+    cache: Option<ChunkCache>,
+    uri: Arc<str>,
+    version: Arc<str>,
+}
+
+impl CachedObjectReader {
+    pub(crate) fn new(
+        inner: ParquetObjectReader,
+        uri: &str,
+        meta: &ObjectMeta,
+        cache: Option<ChunkCache>,
+    ) -> Self {
+        Self {
+            inner,
+            cache,
+            uri: uri.into(),
+            version: object_version(meta).into(),
+        }
+    }
+
+    fn cached(&self, range: &Range<usize>) -> Option<Bytes> {
+        self.cache.as_ref()?.get(
+            &self.uri,
+            &self.version,
+            range.start as u64..range.end as u64,
+        )
+    }
+
+    fn store(&self, range: &Range<usize>, bytes: &Bytes) {
+        if let Some(cache) = &self.cache {
+            // best-effort; a failed write only costs a refetch next time
+            let range = range.start as u64..range.end as u64;
+            let _ = cache.put(&self.uri, &self.version, range, bytes);
+        }
+    }
+}
+
+impl AsyncFileReader for CachedObjectReader {
+    fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, parquet::errors::Result<Bytes>> {
+        async move {
+            if let Some(bytes) = self.cached(&range) {
+                return Ok(bytes);
+            }
+            let bytes = self.inner.get_bytes(range.clone()).await?;
+            self.store(&range, &bytes);
+            Ok(bytes)
+        }
+        .boxed()
+    }
+
+    // misses go to the store in one call, so it can still coalesce them
+    fn get_byte_ranges(
+        &mut self,
+        ranges: Vec<Range<usize>>,
+    ) -> BoxFuture<'_, parquet::errors::Result<Vec<Bytes>>> {
+        async move {
+            let mut out: Vec<Option<Bytes>> = ranges.iter().map(|r| self.cached(r)).collect();
+            let missing: Vec<usize> = (0..ranges.len()).filter(|&i| out[i].is_none()).collect();
+            if !missing.is_empty() {
+                let fetch = missing.iter().map(|&i| ranges[i].clone()).collect();
+                let fetched = self.inner.get_byte_ranges(fetch).await?;
+                for (i, bytes) in missing.into_iter().zip(fetched) {
+                    self.store(&ranges[i], &bytes);
+                    out[i] = Some(bytes);
+                }
+            }
+            Ok(out.into_iter().flatten().collect())
+        }
+        .boxed()
+    }
+
+    fn get_metadata(&mut self) -> BoxFuture<'_, parquet::errors::Result<Arc<ParquetMetaData>>> {
+        self.inner.get_metadata()
+    }
+}
+
+#[cfg(test)]
+mod tests_chunk_cache {
+    use super::*;
+
+    #[test]
+    fn hits_by_version_and_evicts_least_recently_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ChunkCache::new(dir.path().to_path_buf(), 400);
+        let uri = "s3://bucket/t.parquet";
+        cache.put(uri, "v1", 0..100, &[1; 100]).unwrap();
+        assert_eq!(cache.get(uri, "v1", 0..100).unwrap(), vec![1; 100]);
+        assert!(cache.get(uri, "v2", 0..100).is_none());
+        assert!(cache.get(uri, "v1", 0..99).is_none());
+
+        // with their ~70-byte headers two 100-byte chunks fit, a third doesn't
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        cache.put(uri, "v1", 100..200, &[2; 100]).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert!(cache.get(uri, "v1", 0..100).is_some());
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        cache.put(uri, "v1", 200..300, &[3; 100]).unwrap();
+        assert!(cache.get(uri, "v1", 0..100).is_some());
+        assert!(cache.get(uri, "v1", 100..200).is_none());
+        assert!(cache.get(uri, "v1", 200..300).is_some());
+        assert!(cache.size() <= 400);
+
+        cache.put(uri, "v1", 0..1000, &[4; 1000]).unwrap();
+        assert!(cache.get(uri, "v1", 0..1000).is_none());
+    }
+
+    #[tokio::test]
+    async fn reader_serves_repeated_ranges_without_the_store() {
+        use object_store::{memory::InMemory, path::Path, ObjectStore};
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ChunkCache::new(dir.path().to_path_buf(), 1 << 20);
+        let store = Arc::new(InMemory::new());
+        let location = Path::from("t.parquet");
+        let body: Vec<u8> = (0..=255).collect();
+        store
+            .put(&location, Bytes::from(body.clone()).into())
+            .await
+            .unwrap();
+        let meta = store.head(&location).await.unwrap();
+        let uri = "mem://t.parquet";
+        let mut reader = CachedObjectReader::new(
+            ParquetObjectReader::new(store.clone(), meta.clone()),
+            uri,
+            &meta,
+            Some(cache.clone()),
+        );
+        assert_eq!(reader.get_bytes(10..20).await.unwrap(), body[10..20]);
+        let ranges = reader.get_byte_ranges(vec![10..20, 30..40]).await.unwrap();
+        assert_eq!(ranges, [&body[10..20], &body[30..40]]);
+
+        // gone from the store, still answered from disk
+        store.delete(&location).await.unwrap();
+        let ranges = reader.get_byte_ranges(vec![30..40, 10..20]).await.unwrap();
+        assert_eq!(ranges, [&body[30..40], &body[10..20]]);
+        assert!(reader.get_bytes(50..60).await.is_err());
+        let version = object_version(&meta);
+        assert!(cache.get(uri, &version, 10..20).is_some());
+    }
+}
@@ -37,22 +37,14 @@ pub fn diff_schemas(left: &[ColumnSchema], right: &[ColumnSchema]) -> Vec<Column
             diffs.push(ColumnSchemaDiff {
                 name: name.to_string(),
                 status,
-                left_type: Some(format!(
-                    "{}/{}",
-                    lc.physical_type,
-                    lc.logical_type.as_deref().unwrap_or("-")
-                )),
-                right_type: Some(format!(
-                    "{}/{}",
-                    rc.physical_type,
-                    rc.logical_type.as_deref().unwrap_or("-")
-                )),
+                left_type: Some(type_label(lc)),
+                right_type: Some(type_label(rc)),
             });
         } else {
             diffs.push(ColumnSchemaDiff {
                 name: name.to_string(),
                 status: DiffStatus::Removed,
-                left_type: Some(lc.physical_type.clone()),
+                left_type: Some(type_label(lc)),
                 right_type: None,
             });
         }
@@ -63,7 +55,7 @@ pub fn diff_schemas(left: &[ColumnSchema], right: &[ColumnSchema]) -> Vec<Column
                 name: name.to_string(),
                 status: DiffStatus::Added,
                 left_type: None,
-                right_type: Some(rc.physical_type.clone()),
+                right_type: Some(type_label(rc)),
             });
         }
     }
@@ -71,6 +63,15 @@ pub fn diff_schemas(left: &[ColumnSchema], right: &[ColumnSchema]) -> Vec<Column
     diffs
 }
 
+// physical/logical, e.g. "BYTE_ARRAY/String" or "INT32/-"
+fn type_label(c: &ColumnSchema) -> String {
+    format!(
+        "{}/{}",
+        c.physical_type,
+        c.logical_type.as_deref().unwrap_or("-")
+    )
+}
+
 // --- Task 48: stats diff ---
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,6 +133,7 @@ pub struct DatasetComparison {
     pub stats_diffs: Vec<ColumnStatsDiff>,
 }
 
+#[tracing::instrument(skip_all)]
 pub fn compare_datasets(
     left: &DatasetProfile,
     right: &DatasetProfile,
@@ -0,0 +1,388 @@
+use crate::filter::{eval_predicate_batch, parse_predicate, predicate_columns, Predicate};
+use crate::handle::HandleRegistry;
+use crate::options::{prefetch_batches, ScanOptions};
+use crate::profile::full_scan::{hashable_array, projection, with_value_bytes};
+use crate::progress::row_group_totals;
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, TimeUnit, TimestampMillisecondType};
+use arrow::util::display::array_value_to_string;
+use parquet_lens_common::{ParquetLensError, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Offending rows kept per constraint.
+pub const MAX_CONSTRAINT_SAMPLES: usize = 10;
+
+/// An ad-hoc requirement on one top-level column, checked by `check_constraints`.
+#[derive(Debug, Clone)]
+pub enum Constraint {
+    NotNull(String),
+    /// No non-null value may repeat. Values are compared by a 64-bit hash.
+    Unique(String),
+    /// Every non-null value must satisfy a filter expression on the column.
+    Range {
+        column: String,
+        expr: String,
+        predicate: Predicate,
+    },
+    /// Every non-null value, as text, must contain a match of `regex`.
+    Regex {
+        column: String,
+        regex: Regex,
+    },
+    /// Every non-null value must be one of `values`, checked as `column IN (...)`.
+    AllowedValues {
+        column: String,
+        values: Vec<String>,
+        predicate: Predicate,
+    },
+    /// At most `max_pct` percent of rows may be null.
+    MaxNullPct {
+        column: String,
+        max_pct: f64,
+    },
+    /// The newest value of a date or timestamp column must be at most
+    /// `max_age` old; `spec` is the age as written, e.g. `24h`.
+    Freshness {
+        column: String,
+        max_age: Duration,
+        spec: String,
+    },
+}
+
+impl Constraint {
+    /// `amount>=0`, `amount >= 0 AND amount < 100`: any filter expression
+    /// that names exactly one column.
+    pub fn range(expr: &str) -> Result<Self> {
+        let predicate = parse_predicate(expr)
+            .map_err(|e| ParquetLensError::Other(format!("range {expr:?}: {e}")))?;
+        let mut columns = predicate_columns(&predicate);
+        columns.sort_unstable();
+        columns.dedup();
+        let [column] = columns[..] else {
+            return Err(ParquetLensError::Other(format!(
+                "range {expr:?}: expected a condition on exactly one column"
+            )));
+        };
+        Ok(Self::Range {
+            column: column.to_string(),
+            expr: expr.to_string(),
+            predicate,
+        })
+    }
+
+    pub fn regex(column: &str, pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| ParquetLensError::Other(format!("regex {pattern:?}: {e}")))?;
+        Ok(Self::Regex {
+            column: column.to_string(),
+            regex,
+        })
+    }
+
+    /// `freshness` of `max_age` such as `24h`, `7d` or `90min`.
+    pub fn freshness(column: &str, max_age: &str) -> Result<Self> {
+        let age = humantime::parse_duration(max_age)
+            .map_err(|e| ParquetLensError::Other(format!("freshness {max_age:?}: {e}")))?;
+        Ok(Self::Freshness {
+            column: column.to_string(),
+            max_age: age,
+            spec: max_age.to_string(),
+        })
+    }
+
+    pub fn column(&self) -> &str {
+        match self {
+            Self::NotNull(c)
+            | Self::Unique(c)
+            | Self::Range { column: c, .. }
+            | Self::Regex { column: c, .. }
+            | Self::AllowedValues { column: c, .. }
+            | Self::MaxNullPct { column: c, .. }
+            | Self::Freshness { column: c, .. } => c,
+        }
+    }
+
+    /// `not_null(user_id)`, `unique(order_id)`, `range(amount>=0)`,
+    /// `regex(sku ~ ^[A-Z]{3})`, `allowed_values(status in a, b)`,
+    /// `max_null_pct(email <= 5%)`, `freshness(updated_at within 24h)`
+    pub fn label(&self) -> String {
+        match self {
+            Self::NotNull(c) => format!("not_null({c})"),
+            Self::Unique(c) => format!("unique({c})"),
+            Self::Range { expr, .. } => format!("range({expr})"),
+            Self::Regex { column, regex } => format!("regex({column} ~ {})", regex.as_str()),
+            Self::AllowedValues { column, values, .. } => {
+                format!("allowed_values({column} in {})", values.join(", "))
+            }
+            Self::MaxNullPct { column, max_pct } => format!("max_null_pct({column} <= {max_pct}%)"),
+            Self::Freshness { column, spec, .. } => format!("freshness({column} within {spec})"),
+        }
+    }
+}
+
+/// A row breaking a constraint; `value` is None for nulls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstraintSample {
+    pub file: String,
+    pub row: u64, // offset within `file`
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstraintResult {
+    pub constraint: String,
+    pub column: String,
+    pub checked_rows: u64,
+    pub violations: u64, // for unique: rows repeating an earlier value
+    pub samples: Vec<ConstraintSample>,
+    /// Share of rows that may violate before the constraint fails.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_violation_pct: Option<f64>,
+    /// Outcome for constraints on the column as a whole, such as freshness.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ConstraintResult {
+    pub fn passed(&self) -> bool {
+        self.violations == 0
+            || self
+                .max_violation_pct
+                .is_some_and(|max| self.violation_pct() <= max)
+    }
+
+    pub fn violation_pct(&self) -> f64 {
+        if self.checked_rows == 0 {
+            0.0
+        } else {
+            self.violations as f64 / self.checked_rows as f64 * 100.0
+        }
+    }
+
+    /// `unique(order_id): 2 of 1000 rows violate (row 17 = 42, row 90 = 7)`
+    pub fn line(&self) -> String {
+        match (&self.detail, self.passed()) {
+            (Some(detail), true) => return format!("{}: ok ({detail})", self.constraint),
+            (Some(detail), false) => return format!("{}: {detail}", self.constraint),
+            (None, true) => return format!("{}: ok ({} rows)", self.constraint, self.checked_rows),
+            (None, false) => {}
+        }
+        let samples: Vec<String> = self
+            .samples
+            .iter()
+            .map(|s| match &s.value {
+                Some(v) => format!("row {} = {v}", s.row),
+                None => format!("row {}", s.row),
+            })
+            .collect();
+        format!(
+            "{}: {} of {} rows violate ({})",
+            self.constraint,
+            self.violations,
+            self.checked_rows,
+            samples.join(", ")
+        )
+    }
+}
+
+/// Checks every constraint in one sequential pass over `paths`, reading only
+/// the constrained columns. Uniqueness and freshness hold across all the files.
+pub fn check_constraints(
+    paths: &[PathBuf],
+    constraints: &[Constraint],
+    opts: &ScanOptions,
+) -> Result<Vec<ConstraintResult>> {
+    let mut results: Vec<ConstraintResult> = constraints
+        .iter()
+        .map(|c| ConstraintResult {
+            constraint: c.label(),
+            column: c.column().to_string(),
+            checked_rows: 0,
+            violations: 0,
+            samples: Vec::new(),
+            max_violation_pct: match c {
+                Constraint::MaxNullPct { max_pct, .. } => Some(*max_pct),
+                _ => None,
+            },
+            detail: None,
+        })
+        .collect();
+    let mut seen: Vec<HashSet<u64>> = constraints.iter().map(|_| HashSet::new()).collect();
+    // newest value of each freshness constraint: (epoch ms, sample)
+    let mut newest: Vec<Option<(i64, ConstraintSample)>> =
+        constraints.iter().map(|_| None).collect();
+    let mut seen_gauge = opts.gauge();
+    let columns: Vec<String> = constraints.iter().map(|c| c.column().to_string()).collect();
+    for path in paths {
+        let handle = HandleRegistry::global().get(path)?;
+        let meta = handle.arrow_metadata()?;
+        if let Some(missing) = columns
+            .iter()
+            .find(|c| meta.schema().index_of(c.as_str()).is_err())
+        {
+            return Err(ParquetLensError::NotFound(format!(
+                "column {missing} in {}",
+                path.display()
+            )));
+        }
+        for c in constraints {
+            if let Constraint::Freshness { column, .. } = c {
+                let field = meta
+                    .schema()
+                    .field_with_name(column)
+                    .expect("checked above");
+                if !matches!(
+                    field.data_type(),
+                    DataType::Date32 | DataType::Date64 | DataType::Timestamp(..)
+                ) {
+                    return Err(ParquetLensError::Other(format!(
+                        "{}: {column} is {}, not a date or timestamp",
+                        c.label(),
+                        field.data_type()
+                    )));
+                }
+            }
+        }
+        let (mask, _) = projection(&meta, Some(&columns));
+        let rgs: Vec<usize> = (0..meta.metadata().num_row_groups()).collect();
+        let progress = opts.start_progress("constraints", row_group_totals(meta.metadata(), &rgs));
+        let reader = handle
+            .reader_builder()?
+            .with_projection(mask)
+            .with_batch_size(opts.batch_size)
+            .build()
+            .map_err(|e| ParquetLensError::corrupt(path.display().to_string(), e))?;
+        let file = path.display().to_string();
+        let mut batch_gauge = opts.gauge();
+        let mut offset = 0u64;
+        for batch in prefetch_batches(reader, opts.prefetch_depth) {
+            let batch = batch.map_err(|e| ParquetLensError::corrupt(file.clone(), e))?;
+            batch_gauge.set(batch.get_array_memory_size());
+            for (((constraint, result), seen), newest) in constraints
+                .iter()
+                .zip(&mut results)
+                .zip(&mut seen)
+                .zip(&mut newest)
+            {
+                let array = batch
+                    .column_by_name(constraint.column())
+                    .expect("constrained columns are projected");
+                let mut violate = |row: usize, value: Option<String>| {
+                    result.violations += 1;
+                    if result.samples.len() < MAX_CONSTRAINT_SAMPLES {
+                        result.samples.push(ConstraintSample {
+                            file: file.clone(),
+                            row: offset + row as u64,
+                            value,
+                        });
+                    }
+                };
+                match constraint {
+                    Constraint::NotNull(_) | Constraint::MaxNullPct { .. } => {
+                        for row in (0..array.len()).filter(|&r| array.is_null(r)) {
+                            violate(row, None);
+                        }
+                    }
+                    Constraint::Unique(_) => {
+                        let hashable = hashable_array(array)?;
+                        for row in (0..array.len()).filter(|&r| array.is_valid(r)) {
+                            let mut hash = 0;
+                            with_value_bytes(hashable.as_ref(), row, |b| hash = xxh3_64(b));
+                            if !seen.insert(hash) {
+                                violate(row, Some(render(array, row)));
+                            }
+                        }
+                    }
+                    Constraint::Range { predicate, .. }
+                    | Constraint::AllowedValues { predicate, .. } => {
+                        let ok = eval_predicate_batch(predicate, &batch);
+                        for row in (0..array.len()).filter(|&r| array.is_valid(r) && !ok.value(r)) {
+                            violate(row, Some(render(array, row)));
+                        }
+                    }
+                    Constraint::Regex { regex, .. } => {
+                        let text = cast(array, &DataType::Utf8).map_err(|e| {
+                            ParquetLensError::Other(format!("{}: {e}", result.constraint))
+                        })?;
+                        let text = text.as_string::<i32>();
+                        for row in (0..array.len()).filter(|&r| array.is_valid(r)) {
+                            if !regex.is_match(text.value(row)) {
+                                violate(row, Some(text.value(row).to_string()));
+                            }
+                        }
+                    }
+                    Constraint::Freshness { .. } => {
+                        let ms = cast(array, &DataType::Timestamp(TimeUnit::Millisecond, None))
+                            .map_err(|e| {
+                                ParquetLensError::Other(format!("{}: {e}", result.constraint))
+                            })?;
+                        let ms = ms.as_primitive::<TimestampMillisecondType>();
+                        let latest = (0..ms.len())
+                            .filter(|&r| ms.is_valid(r))
+                            .max_by_key(|&r| ms.value(r));
+                        if let Some(row) = latest
+                            .filter(|&r| newest.as_ref().is_none_or(|(t, _)| ms.value(r) > *t))
+                        {
+                            *newest = Some((
+                                ms.value(row),
+                                ConstraintSample {
+                                    file: file.clone(),
+                                    row: offset + row as u64,
+                                    value: Some(render(array, row)),
+                                },
+                            ));
+                        }
+                    }
+                }
+                result.checked_rows += batch.num_rows() as u64;
+            }
+            seen_gauge.set(seen.iter().map(|s| s.capacity() * 9).sum()); // value + control byte
+            offset += batch.num_rows() as u64;
+            progress.advance_rows(batch.num_rows() as u64);
+        }
+    }
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as i64);
+    for ((constraint, result), newest) in constraints.iter().zip(&mut results).zip(newest) {
+        match constraint {
+            Constraint::MaxNullPct { .. } => {
+                result.detail = Some(format!(
+                    "{:.2}% of {} rows null",
+                    result.violation_pct(),
+                    result.checked_rows
+                ));
+            }
+            Constraint::Freshness { max_age, .. } => {
+                let Some((t, sample)) = newest else {
+                    result.violations = 1;
+                    result.detail = Some("no non-null values".into());
+                    continue;
+                };
+                let age = Duration::from_millis(now_ms.saturating_sub(t).max(0) as u64);
+                let value = sample.value.clone().unwrap_or_default();
+                // to the second, so the age reads "3days 2h 5m 1s" rather than to the ms
+                let age_text = humantime::format_duration(Duration::from_secs(age.as_secs()));
+                if age > *max_age {
+                    result.violations = 1;
+                    result.samples.push(sample);
+                    result.detail = Some(format!("newest value {value} is {age_text} old"));
+                } else {
+                    result.detail = Some(format!("newest value {value}, {age_text} old"));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(results)
+}
+
+fn render(array: &ArrayRef, row: usize) -> String {
+    array_value_to_string(array, row).unwrap_or_default()
+}
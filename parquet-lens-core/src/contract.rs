@@ -0,0 +1,291 @@
+use crate::constraints::check_constraints;
+use crate::handle::HandleRegistry;
+use crate::options::ScanOptions;
+use crate::quality::rule_constraints;
+use arrow::datatypes::DataType;
+use parquet_lens_common::{ParquetLensError, Result, RuleConfig, RuleValue};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// How much a broken expectation matters: only errors fail `check --contract`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    #[default]
+    Error,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A dataset's declared schema and the values its columns may hold, read
+/// from YAML (or JSON) by `check --contract`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Contract {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub columns: Vec<ContractColumn>,
+    /// Severity for top-level columns the contract doesn't declare; they're
+    /// allowed when unset.
+    pub extra_columns: Option<Severity>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContractColumn {
+    pub name: String,
+    /// Arrow type name (`Int64`, `Utf8`, `Decimal128(10, 2)`) or a common
+    /// alias such as `bigint`, `string`, `double`, `timestamp` or `decimal`.
+    #[serde(rename = "type")]
+    pub data_type: Option<String>,
+    /// `false` requires every value to be present.
+    pub nullable: Option<bool>,
+    pub min: Option<RuleValue>, // inclusive bounds on non-null values
+    pub max: Option<RuleValue>,
+    #[serde(alias = "enum")]
+    pub allowed_values: Option<Vec<RuleValue>>,
+    pub max_null_pct: Option<f64>,
+    /// Applies to every violation on this column.
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+impl Contract {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| ParquetLensError::Other(format!("{}: {e}", path.display())))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractViolation {
+    pub column: String,
+    /// `missing`, `type`, `nullable` or `extra` for the schema; the
+    /// constraint label, e.g. `range(amount >= 0)`, for observed values.
+    pub check: String,
+    pub severity: Severity,
+    pub detail: String,
+}
+
+impl ContractViolation {
+    /// `error type(amount): declared double, found Utf8`
+    pub fn line(&self) -> String {
+        let check = if self.check.contains('(') {
+            self.check.clone()
+        } else {
+            format!("{}({})", self.check, self.column)
+        };
+        format!("{} {check}: {}", self.severity.label(), self.detail)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractReport {
+    pub contract: Option<String>,
+    pub checked_columns: usize,
+    pub violations: Vec<ContractViolation>,
+}
+
+impl ContractReport {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.violations
+            .iter()
+            .filter(|v| v.severity == severity)
+            .count()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.count(Severity::Error) == 0
+    }
+}
+
+/// Checks every file's schema against `contract`, then the declared nullability,
+/// ranges and value sets against the data in one pass over the columns present
+/// in every file. A column missing anywhere is reported once and not scanned.
+pub fn validate_contract(
+    paths: &[PathBuf],
+    contract: &Contract,
+    opts: &ScanOptions,
+) -> Result<ContractReport> {
+    let mut violations = Vec::new();
+    let mut schemas = Vec::new();
+    for path in paths {
+        let meta = HandleRegistry::global().get(path)?.arrow_metadata()?;
+        schemas.push((path, meta.schema().clone()));
+    }
+    let mut present = Vec::new();
+    for col in &contract.columns {
+        let violation = |check: &str, severity: Severity, detail: String| ContractViolation {
+            column: col.name.clone(),
+            check: check.to_string(),
+            severity,
+            detail,
+        };
+        let fields: Vec<_> = schemas
+            .iter()
+            .filter_map(|(path, schema)| Some((path, schema.field_with_name(&col.name).ok()?)))
+            .collect();
+        if fields.len() < schemas.len() {
+            let (path, _) = schemas
+                .iter()
+                .find(|(_, s)| s.field_with_name(&col.name).is_err())
+                .expect("some file lacks the column");
+            let detail = if schemas.len() == 1 {
+                "not in the schema".to_string()
+            } else {
+                format!(
+                    "absent from {} of {} files, e.g. {}",
+                    schemas.len() - fields.len(),
+                    schemas.len(),
+                    path.display()
+                )
+            };
+            violations.push(violation("missing", col.severity, detail));
+        }
+        if let Some(declared) = &col.data_type {
+            if let Some((path, field)) = fields
+                .iter()
+                .find(|(_, f)| !type_matches(declared, f.data_type()))
+            {
+                let detail = format!("declared {declared}, found {}", field.data_type());
+                let detail = if schemas.len() == 1 {
+                    detail
+                } else {
+                    format!("{detail} in {}", path.display())
+                };
+                violations.push(violation("type", col.severity, detail));
+            }
+        }
+        // nulls that do show up are caught by the not_null scan at the column's
+        // severity; a schema that merely allows them is a warning
+        if col.nullable == Some(false) && fields.iter().any(|(_, f)| f.is_nullable()) {
+            violations.push(violation(
+                "nullable",
+                Severity::Warning,
+                "declared non-nullable, but the schema allows nulls".into(),
+            ));
+        }
+        if fields.len() == schemas.len() && !fields.is_empty() {
+            present.push(col);
+        }
+    }
+    if let Some(severity) = contract.extra_columns {
+        let mut extra: Vec<&str> = Vec::new();
+        for (_, schema) in &schemas {
+            for f in schema.fields() {
+                let name = f.name().as_str();
+                if !contract.columns.iter().any(|c| c.name == name) && !extra.contains(&name) {
+                    extra.push(name);
+                }
+            }
+        }
+        for name in extra {
+            violations.push(ContractViolation {
+                column: name.to_string(),
+                check: "extra".into(),
+                severity,
+                detail: "not declared in the contract".into(),
+            });
+        }
+    }
+    let rules: Vec<RuleConfig> = present
+        .iter()
+        .map(|c| RuleConfig {
+            column: c.name.clone(),
+            not_null: c.nullable == Some(false),
+            min: c.min.clone(),
+            max: c.max.clone(),
+            allowed_values: c.allowed_values.clone(),
+            max_null_pct: c.max_null_pct,
+            ..Default::default()
+        })
+        .collect();
+    let constraints = rule_constraints(&rules)?;
+    if !constraints.is_empty() {
+        for result in check_constraints(paths, &constraints, opts)? {
+            if result.passed() {
+                continue;
+            }
+            let severity = present
+                .iter()
+                .find(|c| c.name == result.column)
+                .map_or(Severity::Error, |c| c.severity);
+            let line = result.line();
+            let detail = line
+                .strip_prefix(&format!("{}: ", result.constraint))
+                .unwrap_or(&line)
+                .to_string();
+            violations.push(ContractViolation {
+                column: result.column,
+                check: result.constraint,
+                severity,
+                detail,
+            });
+        }
+    }
+    Ok(ContractReport {
+        contract: contract.name.clone(),
+        checked_columns: contract.columns.len(),
+        violations,
+    })
+}
+
+/// True when `declared` names `dt`, either as Arrow prints it or by an alias,
+/// ignoring case and whitespace.
+fn type_matches(declared: &str, dt: &DataType) -> bool {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_lowercase()
+    };
+    let declared = normalize(declared);
+    if declared == normalize(&dt.to_string()) {
+        return true;
+    }
+    let aliases: &[&str] = match dt {
+        DataType::Boolean => &["bool", "boolean"],
+        DataType::Int8 => &["int8", "tinyint", "byte"],
+        DataType::Int16 => &["int16", "smallint", "short"],
+        DataType::Int32 => &["int32", "int", "integer"],
+        DataType::Int64 => &["int64", "bigint", "long"],
+        DataType::UInt8 => &["uint8"],
+        DataType::UInt16 => &["uint16"],
+        DataType::UInt32 => &["uint32"],
+        DataType::UInt64 => &["uint64"],
+        DataType::Float16 => &["float16", "half"],
+        DataType::Float32 => &["float32", "float", "real"],
+        DataType::Float64 => &["float64", "double"],
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+            &["string", "str", "text", "varchar", "utf8"]
+        }
+        DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView
+        | DataType::FixedSizeBinary(_) => &["binary", "bytes"],
+        DataType::Date32 | DataType::Date64 => &["date"],
+        DataType::Timestamp(..) => &["timestamp", "datetime"],
+        DataType::Time32(_) | DataType::Time64(_) => &["time"],
+        DataType::Decimal128(p, s) | DataType::Decimal256(p, s) => {
+            return declared == "decimal"
+                || declared == "numeric"
+                || declared == format!("decimal({p},{s})");
+        }
+        DataType::List(_) | DataType::LargeList(_) | DataType::FixedSizeList(..) => {
+            &["list", "array"]
+        }
+        DataType::Struct(_) => &["struct"],
+        DataType::Map(..) => &["map"],
+        _ => &[],
+    };
+    aliases.contains(&declared.as_str())
+}
@@ -89,6 +89,7 @@ pub fn identify_engine(created_by: &str) -> EngineInfo {
 }
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests_identify_engine {
     use super::*;
     #[test]
@@ -1,94 +1,137 @@
-use crate::baseline::BaselineRegression;
-use crate::engine::EngineInfo;
-use crate::nested::NestedColumnProfile;
-use crate::null_patterns::NullPatternGroup;
-use crate::parallel_reader::DatasetProfile;
-use crate::quality::{DatasetQuality, QualityScore};
-use crate::repair::RepairSuggestion;
-use crate::stats::{AggregatedColumnStats, RowGroupProfile};
-use crate::timeseries::TimeSeriesProfile;
-use parquet_lens_common::Result;
-use serde_json;
+use crate::profile::{CalendarBucket, WEEKDAYS};
+use crate::report::ProfileReport;
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, Field, FieldRef, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use parquet_lens_common::{NumberFormat, Result};
 use std::io::Write;
 use std::path::Path;
+use std::sync::Arc;
 
 // --- Task 62: headless summary output ---
 
-pub fn print_summary(dataset: &DatasetProfile, quality: Option<&DatasetQuality>) {
-    println!("{:<16} {}", "Files:", dataset.file_count);
-    println!("{:<16} {}", "Rows:", dataset.total_rows);
-    println!("{:<16} {} bytes", "Size:", dataset.total_bytes);
+pub fn print_summary(report: &ProfileReport, fmt: &NumberFormat) {
+    let dataset = &report.dataset;
+    let q = &report.quality;
+    println!("{:<16} {}", "Files:", fmt.count(dataset.file_count as u64));
+    println!("{:<16} {}", "Rows:", fmt.count(dataset.total_rows));
+    println!("{:<16} {}", "Size:", fmt.bytes(dataset.total_bytes));
     println!("{:<16} {}", "Columns:", dataset.combined_schema.len());
-    if let Some(q) = quality {
-        println!("{:<16} {}/100", "Quality:", q.overall_score);
-        println!("{:<16} {:.2}%", "Null cells:", q.total_null_cell_pct);
-        if !q.worst_columns.is_empty() {
-            println!("{:<16} {}", "Worst cols:", q.worst_columns.join(", "));
+    println!("{:<16} {}/100", "Quality:", q.overall_score);
+    println!("{:<16} {}", "Null cells:", fmt.pct(q.total_null_cell_pct));
+    if !q.worst_columns.is_empty() {
+        println!("{:<16} {}", "Worst cols:", q.worst_columns.join(", "));
+    }
+    for (i, c) in report.pii.iter().flat_map(|p| p.flagged()).enumerate() {
+        println!(
+            "{:<16} {}",
+            if i == 0 { "Likely PII:" } else { "" },
+            c.line()
+        );
+    }
+    if let Some(note) = &report.sample_note {
+        println!("{:<16} {}", "Sample:", note);
+        for m in report.sample_confidence.iter().filter(|m| !m.exact) {
+            println!("{:<16} {}", "", m.line());
+        }
+    }
+}
+
+/// Column-aligned table with one row per column: name, type, null %,
+/// distinct estimate (footer, else the full scan's), compressed size, quality.
+pub fn columns_table(report: &ProfileReport, fmt: &NumberFormat) -> String {
+    let header = ["column", "type", "null %", "distinct", "size", "quality"];
+    let rows: Vec<[String; 6]> = report
+        .column_stats
+        .iter()
+        .map(|stat| {
+            let name = stat.column_name.as_str();
+            let ty = report
+                .dataset
+                .combined_schema
+                .iter()
+                .find(|c| c.name == name)
+                .map(|c| {
+                    c.logical_type
+                        .clone()
+                        .unwrap_or_else(|| c.physical_type.clone())
+                })
+                .unwrap_or_else(|| "-".into());
+            let distinct = report.distinct_estimate(name);
+            let quality = report
+                .quality_scores()
+                .iter()
+                .find(|q| q.column_name == name)
+                .map_or("-".into(), |q| q.score.to_string());
+            [
+                name.to_string(),
+                ty,
+                fmt.decimal(stat.null_percentage),
+                distinct.map_or("-".into(), |d| fmt.count(d)),
+                fmt.bytes(stat.total_compressed_size.max(0) as u64),
+                quality,
+            ]
+        })
+        .collect();
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
         }
     }
+    let mut out = String::new();
+    let mut line = |cells: [&str; 6]| {
+        // text columns left-aligned, numbers right-aligned
+        let text = format!(
+            "{:<w0$}  {:<w1$}  {:>w2$}  {:>w3$}  {:>w4$}  {:>w5$}",
+            cells[0],
+            cells[1],
+            cells[2],
+            cells[3],
+            cells[4],
+            cells[5],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+            w4 = widths[4],
+            w5 = widths[5],
+        );
+        out.push_str(text.trim_end());
+        out.push('\n');
+    };
+    line(header);
+    for row in &rows {
+        line(row.each_ref().map(String::as_str));
+    }
+    out
 }
 
 // --- Task 63: JSON export ---
 
-#[allow(clippy::too_many_arguments)]
-pub fn export_json(
-    output_path: &Path,
-    dataset: &DatasetProfile,
-    agg_stats: &[AggregatedColumnStats],
-    row_groups: &[RowGroupProfile],
-    quality_scores: &[QualityScore],
-    null_patterns: &[NullPatternGroup],
-    engine_info: Option<&EngineInfo>,
-    baseline_regressions: &[BaselineRegression],
-    timeseries_profiles: &[TimeSeriesProfile],
-    nested_profiles: &[NestedColumnProfile],
-    repair_suggestions: &[RepairSuggestion],
-) -> Result<()> {
-    let mut doc = serde_json::json!({
-        "dataset": dataset,
-        "column_stats": agg_stats,
-        "row_groups": row_groups,
-        "quality_scores": quality_scores,
-        "null_patterns": null_patterns,
-        "baseline_regressions": baseline_regressions,
-    });
-    if let Some(ei) = engine_info {
-        doc["engine_info"] = serde_json::to_value(ei).unwrap_or(serde_json::Value::Null);
-    }
-    if !timeseries_profiles.is_empty() {
-        doc["timeseries_profiles"] =
-            serde_json::to_value(timeseries_profiles).unwrap_or(serde_json::Value::Null);
-    }
-    if !nested_profiles.is_empty() {
-        doc["nested_profiles"] =
-            serde_json::to_value(nested_profiles).unwrap_or(serde_json::Value::Null);
-    }
-    if !repair_suggestions.is_empty() {
-        doc["repair_suggestions"] =
-            serde_json::to_value(repair_suggestions).unwrap_or(serde_json::Value::Null);
-    }
+pub fn export_json(output_path: &Path, report: &ProfileReport) -> Result<()> {
     let mut file = std::fs::File::create(output_path)?;
-    serde_json::to_writer_pretty(&mut file, &doc)
+    serde_json::to_writer_pretty(&mut file, report)
         .map_err(|e| parquet_lens_common::ParquetLensError::Other(e.to_string()))?;
     Ok(())
 }
 
 // --- Task 64: CSV export ---
 
-pub fn export_csv(
-    output_path: &Path,
-    agg_stats: &[AggregatedColumnStats],
-    quality_scores: &[QualityScore],
-    row_groups: &[RowGroupProfile],
-) -> Result<()> {
+pub fn export_csv(output_path: &Path, report: &ProfileReport) -> Result<()> {
+    let quality_scores = report.quality_scores();
+    let row_groups = &report.row_groups;
     let mut file = std::fs::File::create(output_path)?;
-    writeln!(file, "column_name,type,null_rate,cardinality,data_size_bytes,compressed_size_bytes,compression_ratio,quality_score,breakdown")?;
-    for stat in agg_stats {
+    writeln!(file, "column_name,type,null_rate,cardinality,data_size_bytes,compressed_size_bytes,compression_ratio,quality_score,breakdown,pii")?;
+    for stat in &report.column_stats {
         let qs = quality_scores
             .iter()
             .find(|q| q.column_name == stat.column_name);
         let quality = qs.map(|q| q.score).unwrap_or(100);
         let breakdown_raw = qs.map(|q| q.breakdown.as_str()).unwrap_or("");
+        let pii: Vec<&str> = qs
+            .map(|q| q.pii.iter().map(|k| k.label()).collect())
+            .unwrap_or_default();
         // csv-escape: wrap in quotes if contains comma, quote, or newline
         let breakdown = if breakdown_raw.contains(',')
             || breakdown_raw.contains('"')
@@ -100,18 +143,28 @@ pub fn export_csv(
         };
         writeln!(
             file,
-            "{},-,{:.4},{},{},{},{:.4},{},{}",
+            "{},-,{:.4},{},{},{},{:.4},{},{},{}",
             stat.column_name,
             stat.null_percentage / 100.0,
-            stat.total_distinct_count_estimate
+            report
+                .distinct_estimate(&stat.column_name)
                 .map_or("-".into(), |d| d.to_string()),
             stat.total_data_page_size,
             stat.total_compressed_size,
             stat.compression_ratio,
             quality,
             breakdown,
+            pii.join(";"),
         )?;
     }
+    // run metadata goes to a sibling run.csv, one key,value row per field
+    if let Some(run) = &report.run {
+        let mut run_file = std::fs::File::create(output_path.with_file_name("run.csv"))?;
+        writeln!(run_file, "key,value")?;
+        for (key, value) in run.csv_rows() {
+            writeln!(run_file, "{},{}", key, csv_field(&value))?;
+        }
+    }
     // write row_groups.csv to sibling path
     if !row_groups.is_empty() {
         let rg_path = output_path.with_file_name("row_groups.csv");
@@ -128,5 +181,424 @@ pub fn export_csv(
             )?;
         }
     }
+    // full-scanned temporal columns: rows per weekday and hour, then empty
+    // calendar runs, whose count is the run length
+    let temporal: Vec<_> = report
+        .full_scan
+        .iter()
+        .flatten()
+        .filter_map(|r| Some((r.column_name.as_str(), r.temporal.as_ref()?)))
+        .collect();
+    if !temporal.is_empty() {
+        let mut t_file = std::fs::File::create(output_path.with_file_name("temporal.csv"))?;
+        writeln!(t_file, "column,bucket,label,count")?;
+        for (name, t) in temporal {
+            let name = csv_field(name);
+            for (day, n) in WEEKDAYS.iter().zip(&t.weekday_distribution) {
+                writeln!(t_file, "{name},weekday,{day},{n}")?;
+            }
+            for (hour, n) in t.hour_distribution.iter().enumerate() {
+                writeln!(t_file, "{name},hour,{hour:02},{n}")?;
+            }
+            for g in &t.gaps {
+                let bucket = match g.bucket {
+                    CalendarBucket::Weekday => "weekday_gap",
+                    CalendarBucket::Hour => "hour_gap",
+                    CalendarBucket::Day => "day_gap",
+                };
+                writeln!(t_file, "{name},{bucket},{},{}", g.label, g.span)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// quoted when it holds a comma, quote or line break
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// One JSON object per column stats entry, after a leading `{"run": ...}`
+/// line when the report carries run metadata.
+pub fn export_ndjson(output_path: &Path, report: &ProfileReport) -> Result<()> {
+    let mut file = std::fs::File::create(output_path)?;
+    if let Some(run) = &report.run {
+        let line = serde_json::to_string(&serde_json::json!({ "run": run }))
+            .map_err(|e| parquet_lens_common::ParquetLensError::Other(e.to_string()))?;
+        writeln!(file, "{line}")?;
+    }
+    for stat in &report.column_stats {
+        let line = serde_json::to_string(stat)
+            .map_err(|e| parquet_lens_common::ParquetLensError::Other(e.to_string()))?;
+        writeln!(file, "{line}")?;
+    }
     Ok(())
 }
+
+/// Rows as newline-delimited JSON, one object per row with every column present
+/// (`null` when unset). Structs, lists and maps stay nested and temporal values
+/// are ISO-8601 strings. Returns the number of rows written.
+pub fn write_rows_jsonl<W: Write>(out: W, batches: &[RecordBatch]) -> Result<usize> {
+    let mut writer = arrow::json::WriterBuilder::new()
+        .with_explicit_nulls(true)
+        .build::<_, arrow::json::writer::LineDelimited>(out);
+    for batch in batches {
+        let columns = batch
+            .columns()
+            .iter()
+            .map(with_offset_zones)
+            .collect::<Result<Vec<_>>>()?;
+        writer.write(&RecordBatch::try_new(
+            utc_schema(batch.schema_ref()),
+            columns,
+        )?)?;
+    }
+    writer.finish()?;
+    Ok(batches.iter().map(|b| b.num_rows()).sum())
+}
+
+/// `array` with named time zones (e.g. "UTC", "Europe/Oslo") relabelled "+00:00".
+/// Without chrono-tz arrow can only format offset zones, and the stored values are
+/// UTC instants either way.
+pub(crate) fn with_offset_zones(array: &ArrayRef) -> Result<ArrayRef> {
+    let target = offset_zones(array.data_type());
+    if &target == array.data_type() {
+        return Ok(array.clone());
+    }
+    Ok(arrow::compute::cast(array, &target)?)
+}
+
+fn utc_schema(schema: &SchemaRef) -> SchemaRef {
+    let fields: Vec<Field> = schema
+        .fields()
+        .iter()
+        .map(|f| {
+            f.as_ref()
+                .clone()
+                .with_data_type(offset_zones(f.data_type()))
+        })
+        .collect();
+    Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+fn offset_zones(dt: &DataType) -> DataType {
+    let field = |f: &FieldRef| {
+        Arc::new(
+            f.as_ref()
+                .clone()
+                .with_data_type(offset_zones(f.data_type())),
+        )
+    };
+    match dt {
+        DataType::Timestamp(unit, Some(tz)) if !tz.starts_with(['+', '-']) => {
+            DataType::Timestamp(*unit, Some("+00:00".into()))
+        }
+        DataType::List(f) => DataType::List(field(f)),
+        DataType::LargeList(f) => DataType::LargeList(field(f)),
+        DataType::Struct(fields) => DataType::Struct(fields.iter().map(field).collect()),
+        other => other.clone(),
+    }
+}
+
+// --- Prometheus text exposition ---
+
+/// Report as Prometheus gauges, every series labelled with `dataset`.
+pub fn prometheus_metrics(report: &ProfileReport, dataset: &str) -> String {
+    let ds = escape_label(dataset);
+    let mut out = String::new();
+    let mut gauge = |name: &str, help: &str, samples: Vec<(String, f64)>| {
+        out.push_str(&format!("# HELP {name} {help}\n# TYPE {name} gauge\n"));
+        for (labels, value) in samples {
+            out.push_str(&format!("{name}{{dataset=\"{ds}\"{labels}}} {value}\n"));
+        }
+    };
+    let d = &report.dataset;
+    gauge(
+        "parquet_rows",
+        "Rows across all files.",
+        vec![(String::new(), d.total_rows as f64)],
+    );
+    gauge(
+        "parquet_files",
+        "Parquet files in the dataset.",
+        vec![(String::new(), d.file_count as f64)],
+    );
+    gauge(
+        "parquet_bytes",
+        "On-disk size of all files.",
+        vec![(String::new(), d.total_bytes as f64)],
+    );
+    gauge(
+        "parquet_quality_score",
+        "Overall quality score, 0-100.",
+        vec![(String::new(), report.quality.overall_score as f64)],
+    );
+    gauge(
+        "parquet_baseline_regressions",
+        "Regressions against the saved baseline.",
+        vec![(String::new(), report.baseline_regressions.len() as f64)],
+    );
+    let column = |name: &str| format!(",column=\"{}\"", escape_label(name));
+    gauge(
+        "parquet_null_pct",
+        "Percentage of null values per column.",
+        report
+            .column_stats
+            .iter()
+            .map(|s| (column(&s.column_name), s.null_percentage))
+            .collect(),
+    );
+    gauge(
+        "parquet_column_quality_score",
+        "Quality score per column, 0-100.",
+        report
+            .quality_scores()
+            .iter()
+            .map(|q| (column(&q.column_name), q.score as f64))
+            .collect(),
+    );
+    out
+}
+
+pub fn export_prometheus(output_path: &Path, report: &ProfileReport, dataset: &str) -> Result<()> {
+    std::fs::write(output_path, prometheus_metrics(report, dataset))?;
+    Ok(())
+}
+
+fn escape_label(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+// --- Great Expectations ---
+
+/// Bootstraps a Great Expectations suite from what the profile observed: the
+/// column list, not-null with the observed `mostly`, numeric and length ranges,
+/// and set membership for columns whose top values cover every row. Ranges and
+/// sets need a full scan; without one only the null and schema checks appear.
+pub fn great_expectations_suite(report: &ProfileReport, suite_name: &str) -> serde_json::Value {
+    let expect = |kind: &str, kwargs: serde_json::Value| serde_json::json!({ "expectation_type": kind, "kwargs": kwargs, "meta": {} });
+    let columns: Vec<&str> = report
+        .dataset
+        .combined_schema
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    let mut expectations = vec![expect(
+        "expect_table_columns_to_match_ordered_list",
+        serde_json::json!({ "column_list": columns }),
+    )];
+    for stat in &report.column_stats {
+        let column = stat.column_name.as_str();
+        let non_null = 1.0 - stat.null_percentage / 100.0;
+        if non_null >= 1.0 {
+            expectations.push(expect(
+                "expect_column_values_to_not_be_null",
+                serde_json::json!({ "column": column }),
+            ));
+        } else if non_null > 0.0 {
+            // round down so the observed data still passes
+            let mostly = (non_null * 10_000.0).floor() / 10_000.0;
+            expectations.push(expect(
+                "expect_column_values_to_not_be_null",
+                serde_json::json!({ "column": column, "mostly": mostly }),
+            ));
+        }
+        let Some(profile) = report
+            .full_scan
+            .iter()
+            .flatten()
+            .find(|p| p.column_name == column)
+        else {
+            continue;
+        };
+        if let Some(n) = &profile.numeric {
+            expectations.push(expect(
+                "expect_column_values_to_be_between",
+                serde_json::json!({ "column": column, "min_value": n.min, "max_value": n.max }),
+            ));
+        }
+        if let Some(st) = &profile.string {
+            expectations.push(expect(
+                "expect_column_value_lengths_to_be_between",
+                serde_json::json!({
+                    "column": column,
+                    "min_value": st.min_length,
+                    "max_value": st.max_length,
+                }),
+            ));
+        }
+        if let Some(freq) = &profile.frequency {
+            let covered: u64 = freq.top_values.iter().map(|v| v.count).sum();
+            // a sketch may have dropped rare values, so only exact tables form a set
+            if !freq.approximate && !freq.top_values.is_empty() && covered == freq.total_count {
+                let numeric = profile.numeric.is_some();
+                let mut value_set: Vec<serde_json::Value> = freq
+                    .top_values
+                    .iter()
+                    .map(|v| typed_value(&v.value, numeric))
+                    .collect();
+                // frequency order has arbitrary ties; sorted sets diff cleanly
+                value_set.sort_by(|a, b| match (a.as_f64(), b.as_f64()) {
+                    (Some(x), Some(y)) => x.total_cmp(&y),
+                    _ => a.to_string().cmp(&b.to_string()),
+                });
+                expectations.push(expect(
+                    "expect_column_values_to_be_in_set",
+                    serde_json::json!({ "column": column, "value_set": value_set }),
+                ));
+            }
+        }
+    }
+    serde_json::json!({
+        "expectation_suite_name": suite_name,
+        "data_asset_type": null,
+        "expectations": expectations,
+        "meta": {
+            "great_expectations_version": "0.18.0",
+            "profiler": concat!("parquet-lens ", env!("CARGO_PKG_VERSION")),
+            "rows_profiled": report.dataset.total_rows,
+            "sample_note": report.sample_note,
+            "run": report.run,
+        },
+    })
+}
+
+pub fn export_great_expectations(
+    output_path: &Path,
+    report: &ProfileReport,
+    suite_name: &str,
+) -> Result<()> {
+    let file = std::fs::File::create(output_path)?;
+    serde_json::to_writer_pretty(file, &great_expectations_suite(report, suite_name))
+        .map_err(|e| parquet_lens_common::ParquetLensError::Other(e.to_string()))?;
+    Ok(())
+}
+
+// frequency tables hold display strings; numeric columns need numbers in the set
+fn typed_value(value: &str, numeric: bool) -> serde_json::Value {
+    if numeric {
+        if let Ok(i) = value.parse::<i64>() {
+            return i.into();
+        }
+        if let Ok(f) = value.parse::<f64>() {
+            return f.into();
+        }
+    }
+    value.into()
+}
+
+// --- OpenLineage ---
+
+const OPENLINEAGE_PRODUCER: &str = concat!(
+    "https://github.com/gongahkia/parakeet-cli/tree/v",
+    env!("CARGO_PKG_VERSION")
+);
+const OPENLINEAGE_SPEC: &str = "https://openlineage.io/spec";
+
+/// Identifies a run: the job it belongs to, its id and when it completed
+/// (RFC 3339). Generating the id and clock reading is left to the caller.
+#[derive(Debug, Clone)]
+pub struct LineageRun<'a> {
+    pub namespace: &'a str,
+    pub job_name: &'a str,
+    pub run_id: &'a str,
+    pub event_time: &'a str,
+}
+
+/// Report as an OpenLineage `COMPLETE` RunEvent with `dataset` as its only input,
+/// carrying schema, row count and per-column null/distinct counts as standard
+/// facets and the quality scores plus baseline regressions as a custom facet.
+pub fn openlineage_event(
+    report: &ProfileReport,
+    dataset: &str,
+    run: &LineageRun<'_>,
+) -> serde_json::Value {
+    let (ds_namespace, ds_name) = lineage_dataset_name(dataset);
+    let fields: Vec<_> = report
+        .dataset
+        .combined_schema
+        .iter()
+        .map(|c| {
+            serde_json::json!({
+                "name": c.name,
+                "type": c.logical_type.as_deref().unwrap_or(&c.physical_type),
+            })
+        })
+        .collect();
+    let column_metrics: serde_json::Map<_, _> = report
+        .column_stats
+        .iter()
+        .map(|s| {
+            let mut m = serde_json::json!({
+                "nullCount": s.total_null_count,
+                "count": report.dataset.total_rows,
+            });
+            if let Some(d) = report.distinct_estimate(&s.column_name) {
+                m["distinctCount"] = d.into();
+            }
+            (s.column_name.clone(), m)
+        })
+        .collect();
+    let column_scores: serde_json::Map<_, _> = report
+        .quality_scores()
+        .iter()
+        .map(|q| (q.column_name.clone(), q.score.into()))
+        .collect();
+    let facet = |name: &str| format!("{OPENLINEAGE_SPEC}/facets/1-0-1/{name}.json#/$defs/{name}");
+    serde_json::json!({
+        "eventType": "COMPLETE",
+        "eventTime": run.event_time,
+        "producer": OPENLINEAGE_PRODUCER,
+        "schemaURL": format!("{OPENLINEAGE_SPEC}/2-0-2/OpenLineage.json#/$defs/RunEvent"),
+        "run": { "runId": run.run_id },
+        "job": { "namespace": run.namespace, "name": run.job_name },
+        "inputs": [{
+            "namespace": ds_namespace,
+            "name": ds_name,
+            "facets": {
+                "schema": {
+                    "_producer": OPENLINEAGE_PRODUCER,
+                    "_schemaURL": facet("SchemaDatasetFacet"),
+                    "fields": fields,
+                },
+            },
+            "inputFacets": {
+                "dataQualityMetrics": {
+                    "_producer": OPENLINEAGE_PRODUCER,
+                    "_schemaURL": facet("DataQualityMetricsInputDatasetFacet"),
+                    "rowCount": report.dataset.total_rows,
+                    "bytes": report.dataset.total_bytes,
+                    "fileCount": report.dataset.file_count,
+                    "columnMetrics": column_metrics,
+                },
+                "parquetLens_quality": {
+                    "_producer": OPENLINEAGE_PRODUCER,
+                    "_schemaURL": format!("{OPENLINEAGE_PRODUCER}/quality-facet.json"),
+                    "overallScore": report.quality.overall_score,
+                    "nullCellPct": report.quality.total_null_cell_pct,
+                    "columnScores": column_scores,
+                    "baselineRegressions": report.baseline_regressions,
+                },
+            },
+        }],
+        "outputs": [],
+    })
+}
+
+// OpenLineage naming: s3://bucket/key -> ("s3://bucket", "key"), local paths -> ("file", path)
+fn lineage_dataset_name(dataset: &str) -> (String, String) {
+    if let Some((scheme, rest)) = dataset.split_once("://") {
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        return (format!("{scheme}://{authority}"), path.to_string());
+    }
+    let path = std::path::absolute(dataset)
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| dataset.to_string());
+    ("file".into(), path)
+}
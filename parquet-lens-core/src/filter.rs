@@ -1,15 +1,37 @@
+use crate::options::{prefetch_batches, ScanOptions};
+use crate::profile::full_scan::scan_pool;
+use crate::progress::{row_group_totals, Progress};
 use arrow::array::{
-    Array, ArrayRef, BooleanArray, BooleanBuilder, Date32Array, Date64Array, Decimal128Array,
-    Float32Array, Float64Array, Int32Array, Int64Array, StringArray,
+    make_array, Array, ArrayRef, BooleanArray, BooleanBuilder, Decimal256Array, FixedSizeListArray,
+    Float32Array, Float64Array, GenericListArray, Int32Array, Int64Array, OffsetSizeTrait,
+    StringArray, StructArray, UInt64Array,
 };
+use arrow::buffer::NullBuffer;
+use arrow::compute::kernels::cast_utils::{string_to_time_nanoseconds, string_to_timestamp_nanos};
+use arrow::datatypes::{i256, DataType, Schema, TimeUnit};
 use arrow::record_batch::RecordBatch;
-use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
-use parquet::file::metadata::ParquetMetaData;
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, RowSelection, RowSelector};
+use parquet::arrow::ProjectionMask;
+use parquet::basic::{ConvertedType, Encoding, LogicalType, PageType, Type as PhysicalType};
+use parquet::column::page::Page;
 use parquet::file::metadata::RowGroupMetaData;
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::RowGroupReader;
+use parquet::file::serialized_reader::SerializedRowGroupReader;
 use parquet::file::statistics::Statistics;
+use parquet::format;
+use parquet::thrift::TSerializable;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
 use std::fs::File;
-use std::path::Path;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thrift::protocol::TCompactInputProtocol;
 
 // --- AST ---
 
@@ -28,17 +50,29 @@ pub enum Value {
     Int(i64),
     Float(f64),
     Str(String),
+    StrNoCase(String), // a string literal that matches strings ignoring case
     Bool(bool),
     Null,
 }
 
 #[derive(Debug, Clone)]
 pub enum Predicate {
-    Comparison { col: String, op: CmpOp, val: Value },
+    Comparison {
+        col: String,
+        op: CmpOp,
+        val: Value,
+    },
     IsNull(String),
     IsNotNull(String),
-    In { col: String, vals: Vec<Value> },
-    Like { col: String, pattern: String },
+    In {
+        col: String,
+        vals: Vec<Value>,
+    },
+    Like {
+        col: String,
+        pattern: String,
+        case_insensitive: bool, // ILIKE
+    },
     And(Box<Predicate>, Box<Predicate>),
     Or(Box<Predicate>, Box<Predicate>),
     Not(Box<Predicate>),
@@ -48,12 +82,149 @@ pub enum Predicate {
 pub struct FilterResult {
     pub matched_rows: u64,
     pub scanned_rows: u64,
-    pub skipped_rgs: usize,
+    pub skipped_rgs: usize, // ruled out by min/max statistics
+    #[serde(default)]
+    pub bloom_skipped_rgs: usize, // ruled out by bloom filters after statistics
+    #[serde(default)]
+    pub dict_skipped_rgs: usize, // ruled out by dictionary pages after bloom filters
     pub total_rgs: usize,
     pub sample_headers: Vec<String>,   // schema column names
     pub sample_rows: Vec<Vec<String>>, // up to 10 matching rows as strings
 }
 
+/// One file's share of a dataset-wide filter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileFilterResult {
+    pub path: String,
+    pub result: FilterResult,
+}
+
+/// [`FilterResult`] summed over every file of a dataset, with the per-file breakdown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetFilterResult {
+    pub matched_rows: u64,
+    pub scanned_rows: u64,
+    pub skipped_rgs: usize,
+    pub bloom_skipped_rgs: usize,
+    pub dict_skipped_rgs: usize,
+    pub total_rgs: usize,
+    pub files: Vec<FileFilterResult>, // in input order
+}
+
+/// The pruning step that ruled a row group out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SkipReason {
+    Statistics,
+    BloomFilter,
+    Dictionary,
+}
+
+/// Why one row group was skipped or had to be scanned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowGroupExplain {
+    pub row_group: usize,
+    pub num_rows: i64,
+    pub skipped_by: Option<SkipReason>, // None when the row group must be scanned
+    pub evidence: Vec<String>,          // one line per predicate leaf
+}
+
+impl fmt::Display for CmpOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CmpOp::Eq => "=",
+            CmpOp::Ne => "!=",
+            CmpOp::Lt => "<",
+            CmpOp::Le => "<=",
+            CmpOp::Gt => ">",
+            CmpOp::Ge => ">=",
+        })
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(v) => write!(f, "{v}"),
+            Value::Float(v) => write!(f, "{v}"),
+            Value::Str(v) => write!(f, "'{v}'"),
+            Value::StrNoCase(v) => write!(f, "'{v}' (any case)"),
+            Value::Bool(v) => write!(f, "{v}"),
+            Value::Null => f.write_str("NULL"),
+        }
+    }
+}
+
+// longer IN lists (usually from @file) show only their first values
+const IN_DISPLAY_MAX: usize = 10;
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Predicate::Comparison { col, op, val } => write!(f, "{col} {op} {val}"),
+            Predicate::IsNull(col) => write!(f, "{col} IS NULL"),
+            Predicate::IsNotNull(col) => write!(f, "{col} IS NOT NULL"),
+            Predicate::In { col, vals } if vals.len() > IN_DISPLAY_MAX => {
+                let head: Vec<String> = vals[..3].iter().map(|v| v.to_string()).collect();
+                write!(f, "{col} IN ({}, … {} values)", head.join(", "), vals.len())
+            }
+            Predicate::In { col, vals } => {
+                let vals: Vec<String> = vals.iter().map(|v| v.to_string()).collect();
+                write!(f, "{col} IN ({})", vals.join(", "))
+            }
+            Predicate::Like {
+                col,
+                pattern,
+                case_insensitive,
+            } => {
+                let like = if *case_insensitive { "ILIKE" } else { "LIKE" };
+                write!(f, "{col} {like} '{pattern}'")
+            }
+            Predicate::And(a, b) => write!(f, "({a} AND {b})"),
+            Predicate::Or(a, b) => write!(f, "({a} OR {b})"),
+            Predicate::Not(inner) => write!(f, "NOT {inner}"),
+        }
+    }
+}
+
+impl Predicate {
+    /// Makes string `=`, `!=` and IN comparisons and LIKE patterns ignore case,
+    /// as `filter --case-insensitive` does.
+    pub fn ignore_case(self) -> Predicate {
+        let fold = |v: Value| match v {
+            Value::Str(s) => Value::StrNoCase(s),
+            v => v,
+        };
+        match self {
+            Predicate::Comparison {
+                col,
+                op: op @ (CmpOp::Eq | CmpOp::Ne),
+                val,
+            } => Predicate::Comparison {
+                col,
+                op,
+                val: fold(val),
+            },
+            Predicate::In { col, vals } => Predicate::In {
+                col,
+                vals: vals.into_iter().map(fold).collect(),
+            },
+            Predicate::Like { col, pattern, .. } => Predicate::Like {
+                col,
+                pattern,
+                case_insensitive: true,
+            },
+            Predicate::And(a, b) => {
+                Predicate::And(Box::new(a.ignore_case()), Box::new(b.ignore_case()))
+            }
+            Predicate::Or(a, b) => {
+                Predicate::Or(Box::new(a.ignore_case()), Box::new(b.ignore_case()))
+            }
+            Predicate::Not(inner) => Predicate::Not(Box::new(inner.ignore_case())),
+            p => p,
+        }
+    }
+}
+
 // --- recursive descent parser ---
 
 struct Parser {
@@ -133,18 +304,27 @@ impl Parser {
             Some(t) => t.to_string(),
             None => return Err("expected column name, got EOF".into()),
         };
-        // IS NULL / IS NOT NULL
+        // IS [NOT] NULL / IS [NOT] DISTINCT FROM v
         if self.peek_upper().as_deref() == Some("IS") {
             self.consume();
-            if self.peek_upper().as_deref() == Some("NOT") {
+            let negated = self.peek_upper().as_deref() == Some("NOT");
+            if negated {
+                self.consume();
+            }
+            if self.peek_upper().as_deref() == Some("DISTINCT") {
                 self.consume();
-                self.expect("NULL")?;
-                return Ok(Predicate::IsNotNull(col));
+                self.expect("FROM")?;
+                let val = self.parse_value()?;
+                return Ok(distinct_from(col, val, negated));
             }
             self.expect("NULL")?;
-            return Ok(Predicate::IsNull(col));
+            return Ok(if negated {
+                Predicate::IsNotNull(col)
+            } else {
+                Predicate::IsNull(col)
+            });
         }
-        // NOT IN (...)
+        // NOT IN (...) / NOT IN @file
         if self.peek_upper().as_deref() == Some("NOT")
             && self
                 .tokens
@@ -155,45 +335,13 @@ impl Parser {
         {
             self.consume(); // NOT
             self.consume(); // IN
-            self.expect("(")?;
-            let mut vals = Vec::new();
-            loop {
-                vals.push(self.parse_value()?);
-                match self.peek() {
-                    Some(",") => {
-                        self.consume();
-                    }
-                    Some(")") => {
-                        self.consume();
-                        break;
-                    }
-                    Some(t) => {
-                        return Err(format!("expected ',' or ')' in NOT IN list, got '{t}'"))
-                    }
-                    None => return Err("unexpected EOF in NOT IN list".into()),
-                }
-            }
+            let vals = self.parse_in_list("NOT IN")?;
             return Ok(Predicate::Not(Box::new(Predicate::In { col, vals })));
         }
-        // IN (...)
+        // IN (...) / IN @file
         if self.peek_upper().as_deref() == Some("IN") {
             self.consume();
-            self.expect("(")?;
-            let mut vals = Vec::new();
-            loop {
-                vals.push(self.parse_value()?);
-                match self.peek() {
-                    Some(",") => {
-                        self.consume();
-                    }
-                    Some(")") => {
-                        self.consume();
-                        break;
-                    }
-                    Some(t) => return Err(format!("expected ',' or ')' in IN list, got '{t}'")),
-                    None => return Err("unexpected EOF in IN list".into()),
-                }
-            }
+            let vals = self.parse_in_list("IN")?;
             return Ok(Predicate::In { col, vals });
         }
         // BETWEEN a AND b -> And(>=a, <=b)
@@ -215,14 +363,19 @@ impl Parser {
                 }),
             ));
         }
-        // LIKE
-        if self.peek_upper().as_deref() == Some("LIKE") {
+        // LIKE / ILIKE
+        if let Some(kw @ ("LIKE" | "ILIKE")) = self.peek_upper().as_deref() {
+            let case_insensitive = kw == "ILIKE";
             self.consume();
             let pattern = match self.consume() {
                 Some(t) => strip_quotes(t),
-                None => return Err("expected pattern after LIKE".into()),
+                None => return Err(format!("expected pattern after {kw}")),
             };
-            return Ok(Predicate::Like { col, pattern });
+            return Ok(Predicate::Like {
+                col,
+                pattern,
+                case_insensitive,
+            });
         }
         // comparison op
         let op = match self.consume() {
@@ -238,6 +391,40 @@ impl Parser {
         let val = self.parse_value()?;
         Ok(Predicate::Comparison { col, op, val })
     }
+    // `(v, ...)` or `@path`, a file with one value per line
+    fn parse_in_list(&mut self, what: &str) -> Result<Vec<Value>, String> {
+        if let Some(file) = self.peek().and_then(|t| t.strip_prefix('@')) {
+            let file = if file.is_empty() {
+                // @'name with spaces.txt' tokenizes as `@` then the quoted path
+                self.consume();
+                match self.consume() {
+                    Some(t) => strip_quotes(t),
+                    None => return Err(format!("expected a file name after @ in {what}")),
+                }
+            } else {
+                let file = strip_quotes(file);
+                self.consume();
+                file
+            };
+            return read_in_list(Path::new(&file));
+        }
+        self.expect("(")?;
+        let mut vals = Vec::new();
+        loop {
+            vals.push(self.parse_value()?);
+            match self.peek() {
+                Some(",") => {
+                    self.consume();
+                }
+                Some(")") => {
+                    self.consume();
+                    return Ok(vals);
+                }
+                Some(t) => return Err(format!("expected ',' or ')' in {what} list, got '{t}'")),
+                None => return Err(format!("unexpected EOF in {what} list")),
+            }
+        }
+    }
     fn parse_value(&mut self) -> Result<Value, String> {
         match self.consume() {
             None => Err("expected value, got EOF".into()),
@@ -259,6 +446,44 @@ impl Parser {
     }
 }
 
+// NULL-safe comparison in terms of the null-aware predicates, so pruning and
+// evaluation need nothing new: `a IS DISTINCT FROM v` is `a != v OR a IS NULL`
+fn distinct_from(col: String, val: Value, negated: bool) -> Predicate {
+    match (val, negated) {
+        (Value::Null, false) => Predicate::IsNotNull(col),
+        (Value::Null, true) => Predicate::IsNull(col),
+        (val, false) => Predicate::Or(
+            Box::new(Predicate::Comparison {
+                col: col.clone(),
+                op: CmpOp::Ne,
+                val,
+            }),
+            Box::new(Predicate::IsNull(col)),
+        ),
+        (val, true) => Predicate::And(
+            Box::new(Predicate::Comparison {
+                col: col.clone(),
+                op: CmpOp::Eq,
+                val,
+            }),
+            Box::new(Predicate::IsNotNull(col)),
+        ),
+    }
+}
+
+// Each non-blank line is one value, kept as text (quotes stripped) so it
+// converts to whatever type the column has: `02134` stays a zip code.
+fn read_in_list(path: &Path) -> Result<Vec<Value>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read IN list {}: {e}", path.display()))?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| Value::Str(strip_quotes(l)))
+        .collect())
+}
+
 fn strip_quotes(s: &str) -> String {
     if (s.starts_with('\'') && s.ends_with('\'')) || (s.starts_with('"') && s.ends_with('"')) {
         s[1..s.len() - 1].to_string()
@@ -347,10 +572,10 @@ pub fn can_skip_row_group(pred: &Predicate, rg: &RowGroupMetaData) -> bool {
         Predicate::And(a, b) => can_skip_row_group(a, rg) || can_skip_row_group(b, rg), // skip if EITHER side definitely false
         Predicate::Or(a, b) => can_skip_row_group(a, rg) && can_skip_row_group(b, rg), // skip only if BOTH sides definitely false
         Predicate::Not(_) => false, // conservative: don't skip on NOT
-        Predicate::Comparison { col, op, val } => {
-            let stats = find_col_stats(col, rg);
-            stats.map(|s| stat_can_skip(s, op, val)).unwrap_or(false)
-        }
+        Predicate::Comparison { col, op, val } => column_index(col, rg)
+            .map(|i| rg.column(i))
+            .and_then(|c| Some((c, c.statistics()?)))
+            .is_some_and(|(c, s)| comparison_can_skip(c, s, op, val)),
         Predicate::IsNull(col) => {
             // skip if null_count == 0 for all row groups (no nulls possible)
             let stats = find_col_stats(col, rg);
@@ -362,16 +587,581 @@ pub fn can_skip_row_group(pred: &Predicate, rg: &RowGroupMetaData) -> bool {
     }
 }
 
-fn find_col_stats<'a>(col: &str, rg: &'a RowGroupMetaData) -> Option<&'a Statistics> {
-    for i in 0..rg.num_columns() {
-        let cm = rg.column(i);
-        if cm.column_descr().name() == col {
-            return cm.statistics();
+/// Like [`can_skip_row_group`], but `col = value` comparisons also consult the
+/// column's bloom filter. A bloom hit only means "maybe", so it never forces a scan.
+pub fn bloom_can_skip(pred: &Predicate, rg: &dyn RowGroupReader) -> bool {
+    eq_can_skip(pred, rg.metadata(), &|col, val| {
+        bloom_rules_out(col, val, rg)
+    })
+}
+
+/// Like [`can_skip_row_group`], but `col = 'text'` comparisons on fully
+/// dictionary-encoded string columns also look the literal up in the dictionary page.
+/// `file` is the one `rg` reads from; its page headers show whether every page
+/// used the dictionary.
+pub fn dictionary_can_skip(pred: &Predicate, rg: &dyn RowGroupReader, file: &File) -> bool {
+    eq_can_skip(pred, rg.metadata(), &|col, val| {
+        dictionary_rules_out(col, val, rg, file)
+    })
+}
+
+fn eq_can_skip(
+    pred: &Predicate,
+    rg: &RowGroupMetaData,
+    rules_out: &dyn Fn(&str, &Value) -> bool,
+) -> bool {
+    match pred {
+        Predicate::And(a, b) => eq_can_skip(a, rg, rules_out) || eq_can_skip(b, rg, rules_out),
+        Predicate::Or(a, b) => eq_can_skip(a, rg, rules_out) && eq_can_skip(b, rg, rules_out),
+        Predicate::Comparison {
+            col,
+            op: CmpOp::Eq,
+            val,
+        } => can_skip_row_group(pred, rg) || rules_out(col, val),
+        _ => can_skip_row_group(pred, rg),
+    }
+}
+
+// by full path, so `user.address.zip` finds the struct leaf
+fn column_index(col: &str, rg: &RowGroupMetaData) -> Option<usize> {
+    (0..rg.num_columns()).find(|&i| rg.column(i).column_descr().path().string() == col)
+}
+
+fn bloom_rules_out(col: &str, val: &Value, rg: &dyn RowGroupReader) -> bool {
+    let meta = rg.metadata();
+    // decimal filters hash the unscaled value, not the literal
+    let Some(i) = column_index(col, meta).filter(|&i| decimal_scale(meta.column(i)).is_none())
+    else {
+        return false;
+    };
+    let Some(sbbf) = rg.get_column_bloom_filter(i) else {
+        return false;
+    };
+    // filters hash the plain-encoded physical value
+    let maybe_present = match (meta.column(i).column_type(), val) {
+        (PhysicalType::INT32, Value::Int(v)) => match i32::try_from(*v) {
+            Ok(v) => sbbf.check(&v),
+            Err(_) => true,
+        },
+        (PhysicalType::INT64, Value::Int(v)) => sbbf.check(v),
+        (PhysicalType::BYTE_ARRAY, Value::Str(v)) => sbbf.check(&v.as_str()),
+        (PhysicalType::BOOLEAN, Value::Bool(v)) => sbbf.check(v),
+        _ => true,
+    };
+    !maybe_present
+}
+
+fn dictionary_rules_out(col: &str, val: &Value, rg: &dyn RowGroupReader, file: &File) -> bool {
+    let Value::Str(lit) = val else {
+        return false;
+    };
+    let meta = rg.metadata();
+    let Some(i) = column_index(col, meta)
+        .filter(|&i| has_dictionary(meta.column(i)) && dictionary_only(meta.column(i), file))
+    else {
+        return false;
+    };
+    let page = rg
+        .get_column_page_reader(i)
+        .and_then(|mut pages| pages.get_next_page());
+    let Ok(Some(Page::DictionaryPage {
+        buf, num_values, ..
+    })) = page
+    else {
+        return false;
+    };
+    // plain BYTE_ARRAY values: 4-byte little-endian length, then the bytes
+    let mut rest = &buf[..];
+    for _ in 0..num_values {
+        let Some((len, tail)) = rest.split_first_chunk::<4>() else {
+            return false;
+        };
+        let len = u32::from_le_bytes(*len) as usize;
+        if tail.len() < len {
+            return false;
+        }
+        if &tail[..len] == lit.as_bytes() {
+            return false;
+        }
+        rest = &tail[len..];
+    }
+    true
+}
+
+fn has_dictionary(c: &ColumnChunkMetaData) -> bool {
+    c.column_type() == PhysicalType::BYTE_ARRAY
+        && c.dictionary_page_offset().is_some()
+        && decimal_scale(c).is_none()
+}
+
+// every data page reads from the dictionary, so it lists every value in the
+// chunk; writers that fell back to plain encoding fail this
+fn dictionary_only(c: &ColumnChunkMetaData, file: &File) -> bool {
+    if let Some(stats) = c.page_encoding_stats() {
+        return stats
+            .iter()
+            .filter(|s| s.page_type != PageType::DICTIONARY_PAGE)
+            .all(|s| {
+                matches!(
+                    s.encoding,
+                    Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY
+                )
+            });
+    }
+    // parquet-rs writes no encoding stats: walk the page headers instead,
+    // seeking over the page bodies
+    let (start, len) = c.byte_range();
+    let mut reader = CountingReader {
+        inner: BufReader::new(file),
+        read: 0,
+    };
+    if reader.inner.seek(SeekFrom::Start(start)).is_err() {
+        return false;
+    }
+    while reader.read < len {
+        let mut prot = TCompactInputProtocol::new(&mut reader);
+        let Ok(header) = format::PageHeader::read_from_in_protocol(&mut prot) else {
+            return false;
+        };
+        let encoding = match (&header.data_page_header, &header.data_page_header_v2) {
+            (Some(h), _) => Some(h.encoding),
+            (_, Some(h)) => Some(h.encoding),
+            _ => None,
+        };
+        let dict = [
+            format::Encoding::PLAIN_DICTIONARY,
+            format::Encoding::RLE_DICTIONARY,
+        ];
+        if encoding.is_some_and(|e| !dict.contains(&e)) || header.compressed_page_size < 0 {
+            return false;
+        }
+        let body = header.compressed_page_size as i64;
+        if reader.inner.seek_relative(body).is_err() {
+            return false;
+        }
+        reader.read += body as u64;
+    }
+    true
+}
+
+struct CountingReader<R> {
+    inner: R,
+    read: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        Ok(n)
+    }
+}
+
+// any `col = value` whose column passes `usable`
+fn wants_eq_check(
+    pred: &Predicate,
+    rg: &RowGroupMetaData,
+    usable: &dyn Fn(&ColumnChunkMetaData) -> bool,
+) -> bool {
+    match pred {
+        Predicate::And(a, b) | Predicate::Or(a, b) => {
+            wants_eq_check(a, rg, usable) || wants_eq_check(b, rg, usable)
+        }
+        Predicate::Comparison {
+            col, op: CmpOp::Eq, ..
+        } => column_index(col, rg).is_some_and(|i| usable(rg.column(i))),
+        _ => false,
+    }
+}
+
+/// Row groups left to scan, plus how many each pruning step ruled out.
+pub(crate) struct RowGroupPlan {
+    pub to_scan: Vec<usize>,
+    pub stats_skipped: usize,
+    pub bloom_skipped: usize,
+    pub dict_skipped: usize,
+}
+
+// cheapest first: footer statistics, then bloom filters, then dictionary pages
+pub(crate) fn plan_row_groups(
+    path: &Path,
+    meta: &ParquetMetaData,
+    pred: &Predicate,
+) -> RowGroupPlan {
+    let mut plan = RowGroupPlan {
+        to_scan: Vec::new(),
+        stats_skipped: 0,
+        bloom_skipped: 0,
+        dict_skipped: 0,
+    };
+    let mut file: Option<Arc<File>> = None;
+    for (idx, rg) in meta.row_groups().iter().enumerate() {
+        match prune_row_group(path, &mut file, rg, pred) {
+            Some(SkipReason::Statistics) => plan.stats_skipped += 1,
+            Some(SkipReason::BloomFilter) => plan.bloom_skipped += 1,
+            Some(SkipReason::Dictionary) => plan.dict_skipped += 1,
+            None => plan.to_scan.push(idx),
         }
     }
+    plan
+}
+
+// `file` is opened on first use and shared across the row groups of `path`
+fn prune_row_group(
+    path: &Path,
+    file: &mut Option<Arc<File>>,
+    rg: &RowGroupMetaData,
+    pred: &Predicate,
+) -> Option<SkipReason> {
+    if can_skip_row_group(pred, rg) {
+        return Some(SkipReason::Statistics);
+    }
+    let bloom = wants_eq_check(pred, rg, &|c| c.bloom_filter_offset().is_some());
+    let dict = wants_eq_check(pred, rg, &has_dictionary);
+    if !bloom && !dict {
+        return None;
+    }
+    // an unreadable filter or dictionary just means the row group gets scanned
+    let f = open_shared(path, file)?;
+    let reader = row_group_reader(f, rg, bloom)?;
+    if bloom && bloom_can_skip(pred, &reader) {
+        return Some(SkipReason::BloomFilter);
+    }
+    if dict && dictionary_can_skip(pred, &reader, f) {
+        return Some(SkipReason::Dictionary);
+    }
     None
 }
 
+fn open_shared<'a>(path: &Path, file: &'a mut Option<Arc<File>>) -> Option<&'a Arc<File>> {
+    if file.is_none() {
+        *file = File::open(path).ok().map(Arc::new);
+    }
+    file.as_ref()
+}
+
+fn row_group_reader<'a>(
+    file: &Arc<File>,
+    rg: &'a RowGroupMetaData,
+    bloom: bool,
+) -> Option<SerializedRowGroupReader<'a, File>> {
+    let props = ReaderProperties::builder()
+        .set_read_bloom_filter(bloom)
+        .build();
+    SerializedRowGroupReader::new(file.clone(), rg, None, Arc::new(props)).ok()
+}
+
+/// Walks `predicate` over every row group of `path` and records which step
+/// pruned each one, with the statistics, bloom filter and dictionary evidence
+/// for every leaf of the predicate.
+#[tracing::instrument(skip_all, fields(path = %path.display()))]
+pub fn explain_filter(path: &Path, predicate: &Predicate) -> Result<Vec<RowGroupExplain>, String> {
+    let builder = crate::handle::HandleRegistry::global()
+        .get(path)
+        .and_then(|h| h.reader_builder())
+        .map_err(|e| e.to_string())?;
+    let meta = builder.metadata().clone();
+    check_columns(builder.schema(), predicate)?;
+    let mut file: Option<Arc<File>> = None;
+    let mut out = Vec::with_capacity(meta.num_row_groups());
+    for (idx, rg) in meta.row_groups().iter().enumerate() {
+        let skipped_by = prune_row_group(path, &mut file, rg, predicate);
+        let f = open_shared(path, &mut file).cloned();
+        let reader = f.as_ref().and_then(|f| row_group_reader(f, rg, true));
+        let mut evidence = Vec::new();
+        leaf_evidence(
+            predicate,
+            rg,
+            reader.as_ref().zip(f.as_deref()),
+            &mut evidence,
+        );
+        out.push(RowGroupExplain {
+            row_group: idx,
+            num_rows: rg.num_rows(),
+            skipped_by,
+            evidence,
+        });
+    }
+    Ok(out)
+}
+
+fn leaf_evidence(
+    pred: &Predicate,
+    rg: &RowGroupMetaData,
+    reader: Option<(&SerializedRowGroupReader<'_, File>, &File)>,
+    out: &mut Vec<String>,
+) {
+    let line = match pred {
+        Predicate::And(a, b) | Predicate::Or(a, b) => {
+            leaf_evidence(a, rg, reader, out);
+            leaf_evidence(b, rg, reader, out);
+            return;
+        }
+        Predicate::Not(_) => "NOT is never pruned".to_string(),
+        Predicate::Comparison { col, op, val } => {
+            let mut parts = vec![stats_evidence(col, op, val, rg)];
+            if matches!(op, CmpOp::Eq) {
+                parts.extend(eq_evidence(col, val, rg, reader));
+            }
+            parts.join("; ")
+        }
+        Predicate::IsNull(col) => match find_col_stats(col, rg).and_then(|s| s.null_count_opt()) {
+            Some(0) => "null count 0 rules it out".to_string(),
+            Some(n) => format!("null count {n}, may match"),
+            None => "no null count".to_string(),
+        },
+        Predicate::IsNotNull(_) | Predicate::In { .. } | Predicate::Like { .. } => {
+            "not used for pruning".to_string()
+        }
+    };
+    out.push(format!("{pred}: {line}"));
+}
+
+fn stats_evidence(col: &str, op: &CmpOp, val: &Value, rg: &RowGroupMetaData) -> String {
+    let Some((c, stats)) = column_index(col, rg)
+        .map(|i| rg.column(i))
+        .and_then(|c| Some((c, c.statistics()?)))
+    else {
+        return "no statistics".to_string();
+    };
+    let scale = decimal_scale(c);
+    let bounds = match scale {
+        Some(scale) => decimal_bounds(stats)
+            .map(|(min, max)| (decimal_text(min, scale), decimal_text(max, scale))),
+        None => stat_bounds(stats),
+    };
+    let Some((min, max)) = bounds else {
+        return "statistics have no min/max".to_string();
+    };
+    let comparable = match scale {
+        Some(_) => decimal_literal(val).is_some(),
+        None => stat_comparable(stats, val),
+    };
+    let verdict = if comparison_can_skip(c, stats, op, val) {
+        "rules it out"
+    } else if matches!(op, CmpOp::Ne) {
+        "!= is never pruned"
+    } else if comparable {
+        "may match"
+    } else {
+        "not comparable with the literal"
+    };
+    format!("min {min}, max {max} {verdict}")
+}
+
+fn eq_evidence(
+    col: &str,
+    val: &Value,
+    rg: &RowGroupMetaData,
+    reader: Option<(&SerializedRowGroupReader<'_, File>, &File)>,
+) -> Vec<String> {
+    let Some(c) = column_index(col, rg).map(|i| rg.column(i)) else {
+        return Vec::new();
+    };
+    let mut parts = Vec::new();
+    parts.push(match (c.bloom_filter_offset(), reader) {
+        (None, _) => "no bloom filter".to_string(),
+        (Some(_), None) => "bloom filter unreadable".to_string(),
+        (Some(_), Some((r, _))) if bloom_rules_out(col, val, r) => {
+            "bloom filter rules it out".to_string()
+        }
+        (Some(_), Some(_)) => "bloom filter may contain it".to_string(),
+    });
+    if has_dictionary(c) && matches!(val, Value::Str(_)) {
+        parts.push(match reader {
+            None => "dictionary unreadable".to_string(),
+            Some((_, f)) if !dictionary_only(c, f) => {
+                "some pages are not dictionary-encoded".to_string()
+            }
+            Some((r, f)) if dictionary_rules_out(col, val, r, f) => {
+                "dictionary lacks it".to_string()
+            }
+            Some(_) => "dictionary contains it".to_string(),
+        });
+    }
+    parts
+}
+
+fn stat_bounds(stats: &Statistics) -> Option<(String, String)> {
+    fn pair<T: ToString>(min: Option<&T>, max: Option<&T>) -> Option<(String, String)> {
+        Some((min?.to_string(), max?.to_string()))
+    }
+    match stats {
+        Statistics::Boolean(s) => pair(s.min_opt(), s.max_opt()),
+        Statistics::Int32(s) => pair(s.min_opt(), s.max_opt()),
+        Statistics::Int64(s) => pair(s.min_opt(), s.max_opt()),
+        Statistics::Float(s) => pair(s.min_opt(), s.max_opt()),
+        Statistics::Double(s) => pair(s.min_opt(), s.max_opt()),
+        Statistics::ByteArray(s) => {
+            let text = |v: &parquet::data_type::ByteArray| {
+                format!("'{}'", String::from_utf8_lossy(v.data()))
+            };
+            Some((text(s.min_opt()?), text(s.max_opt()?)))
+        }
+        Statistics::Int96(_) | Statistics::FixedLenByteArray(_) => None,
+    }
+}
+
+// the stats/literal pairs stat_can_skip knows how to compare
+fn stat_comparable(stats: &Statistics, val: &Value) -> bool {
+    matches!(
+        (stats, val),
+        (
+            Statistics::Int32(_) | Statistics::Int64(_),
+            Value::Int(_) | Value::Float(_)
+        ) | (
+            Statistics::Float(_) | Statistics::Double(_),
+            Value::Float(_)
+        )
+    )
+}
+
+/// Reads the rows of `rgs` and skips the rest.
+pub(crate) fn scan_selection(meta: &ParquetMetaData, rgs: &[usize]) -> RowSelection {
+    RowSelection::from(
+        meta.row_groups()
+            .iter()
+            .enumerate()
+            .map(|(i, rg)| {
+                let count = rg.num_rows() as usize;
+                if rgs.contains(&i) {
+                    RowSelector::select(count)
+                } else {
+                    RowSelector::skip(count)
+                }
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn comparison_can_skip(
+    c: &ColumnChunkMetaData,
+    stats: &Statistics,
+    op: &CmpOp,
+    val: &Value,
+) -> bool {
+    match decimal_scale(c) {
+        Some(scale) => decimal_stat_can_skip(stats, scale, op, val),
+        None => stat_can_skip(stats, op, val),
+    }
+}
+
+/// The scale of a DECIMAL column, whatever its physical type.
+fn decimal_scale(c: &ColumnChunkMetaData) -> Option<i32> {
+    let d = c.column_descr();
+    let decimal = matches!(d.logical_type(), Some(LogicalType::Decimal { .. }))
+        || d.converted_type() == ConvertedType::DECIMAL;
+    decimal.then(|| d.type_scale())
+}
+
+// decimal statistics hold the unscaled value
+fn decimal_stat_can_skip(stats: &Statistics, scale: i32, op: &CmpOp, val: &Value) -> bool {
+    let (Some((min, max)), Some(lit)) = (decimal_bounds(stats), decimal_literal(val)) else {
+        return false;
+    };
+    let (Some(lo), Some(hi)) = (
+        decimal_cmp((min, scale), lit),
+        decimal_cmp((max, scale), lit),
+    ) else {
+        return false;
+    };
+    match op {
+        CmpOp::Eq => lo == Ordering::Greater || hi == Ordering::Less,
+        CmpOp::Lt => lo != Ordering::Less,
+        CmpOp::Le => lo == Ordering::Greater,
+        CmpOp::Gt => hi != Ordering::Greater,
+        CmpOp::Ge => hi == Ordering::Less,
+        CmpOp::Ne => false,
+    }
+}
+
+fn decimal_bounds(stats: &Statistics) -> Option<(i256, i256)> {
+    match stats {
+        Statistics::Int32(s) => Some((
+            i256::from_i128(*s.min_opt()? as i128),
+            i256::from_i128(*s.max_opt()? as i128),
+        )),
+        Statistics::Int64(s) => Some((
+            i256::from_i128(*s.min_opt()? as i128),
+            i256::from_i128(*s.max_opt()? as i128),
+        )),
+        Statistics::FixedLenByteArray(s) => {
+            Some((be_i256(s.min_opt()?.data())?, be_i256(s.max_opt()?.data())?))
+        }
+        Statistics::ByteArray(s) => {
+            Some((be_i256(s.min_opt()?.data())?, be_i256(s.max_opt()?.data())?))
+        }
+        _ => None,
+    }
+}
+
+// big-endian two's complement, as FIXED_LEN_BYTE_ARRAY and BYTE_ARRAY decimals store it
+fn be_i256(b: &[u8]) -> Option<i256> {
+    if b.is_empty() || b.len() > 32 {
+        return None;
+    }
+    let fill = if b[0] & 0x80 != 0 { 0xff } else { 0 };
+    let mut buf = [fill; 32];
+    buf[32 - b.len()..].copy_from_slice(b);
+    Some(i256::from_be_bytes(buf))
+}
+
+/// A numeric or quoted literal as an unscaled value and its scale, exactly:
+/// `12.340` is (12340, 3). Floats go through their shortest round-trip text.
+fn decimal_literal(val: &Value) -> Option<(i256, i32)> {
+    let text = match val {
+        Value::Int(v) => v.to_string(),
+        Value::Float(v) if v.is_finite() => v.to_string(),
+        Value::Str(v) | Value::StrNoCase(v) => v.trim().to_string(),
+        _ => return None,
+    };
+    let (neg, body) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(&text)),
+    };
+    let (int, frac) = body.split_once('.').unwrap_or((body, ""));
+    let digits = format!("{int}{frac}");
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let v = i256::from_string(&digits)?;
+    let v = if neg { v.checked_neg()? } else { v };
+    Some((v, i32::try_from(frac.len()).ok()?))
+}
+
+// both sides at the larger scale; `None` when that overflows 256 bits
+fn decimal_cmp(a: (i256, i32), b: (i256, i32)) -> Option<Ordering> {
+    let scale = a.1.max(b.1);
+    let up = |(v, s): (i256, i32)| {
+        let factor = i256::from_i128(10).checked_pow(u32::try_from(scale - s).ok()?)?;
+        v.checked_mul(factor)
+    };
+    Some(up(a)?.cmp(&up(b)?))
+}
+
+fn decimal_text(raw: i256, scale: i32) -> String {
+    let text = raw.to_string();
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(d) => ("-", d),
+        None => ("", text.as_str()),
+    };
+    if scale <= 0 {
+        let zeros = if raw == i256::ZERO {
+            0
+        } else {
+            -scale as usize
+        };
+        return format!("{sign}{digits}{}", "0".repeat(zeros));
+    }
+    let digits = format!("{digits:0>width$}", width = scale as usize + 1);
+    let (int, frac) = digits.split_at(digits.len() - scale as usize);
+    format!("{sign}{int}.{frac}")
+}
+
+fn find_col_stats<'a>(col: &str, rg: &'a RowGroupMetaData) -> Option<&'a Statistics> {
+    column_index(col, rg).and_then(|i| rg.column(i).statistics())
+}
+
 fn stat_can_skip(stats: &Statistics, op: &CmpOp, val: &Value) -> bool {
     // only handle numeric comparisons where we have typed min/max
     match (stats, val) {
@@ -465,48 +1255,73 @@ fn stat_can_skip(stats: &Statistics, op: &CmpOp, val: &Value) -> bool {
 
 // --- filter evaluation on RecordBatch ---
 
-fn eval_predicate_batch(pred: &Predicate, batch: &RecordBatch) -> BooleanArray {
+/// The rows where `pred` is TRUE; rows where it is NULL (unknown) don't match.
+pub(crate) fn eval_predicate_batch(pred: &Predicate, batch: &RecordBatch) -> BooleanArray {
+    let mask = eval_kleene(pred, batch);
+    match mask.nulls() {
+        Some(_) => arrow::compute::prep_null_mask_filter(&mask),
+        None => mask,
+    }
+}
+
+// SQL three-valued logic: a comparison with a NULL side is NULL, AND/OR follow
+// Kleene's rules and NOT NULL stays NULL, so `NOT (a = 1)` skips NULL rows
+fn eval_kleene(pred: &Predicate, batch: &RecordBatch) -> BooleanArray {
     let n = batch.num_rows();
     match pred {
         Predicate::And(a, b) => {
-            let ma = eval_predicate_batch(a, batch);
-            let mb = eval_predicate_batch(b, batch);
-            arrow::compute::and(&ma, &mb).unwrap_or_else(|_| BooleanArray::from(vec![false; n]))
+            let ma = eval_kleene(a, batch);
+            let mb = eval_kleene(b, batch);
+            arrow::compute::and_kleene(&ma, &mb)
+                .unwrap_or_else(|_| BooleanArray::from(vec![false; n]))
         }
         Predicate::Or(a, b) => {
-            let ma = eval_predicate_batch(a, batch);
-            let mb = eval_predicate_batch(b, batch);
-            arrow::compute::or(&ma, &mb).unwrap_or_else(|_| BooleanArray::from(vec![false; n]))
+            let ma = eval_kleene(a, batch);
+            let mb = eval_kleene(b, batch);
+            arrow::compute::or_kleene(&ma, &mb)
+                .unwrap_or_else(|_| BooleanArray::from(vec![false; n]))
         }
         Predicate::Not(inner) => {
-            let m = eval_predicate_batch(inner, batch);
+            let m = eval_kleene(inner, batch);
             arrow::compute::not(&m).unwrap_or_else(|_| BooleanArray::from(vec![false; n]))
         }
-        Predicate::IsNull(col) => match batch.schema().index_of(col) {
-            Ok(i) => arrow::compute::is_null(batch.column(i))
-                .unwrap_or_else(|_| BooleanArray::from(vec![false; n])),
-            Err(_) => BooleanArray::from(vec![false; n]),
+        Predicate::IsNull(col) => match resolve_column(batch, col) {
+            Some(arr) => {
+                arrow::compute::is_null(&arr).unwrap_or_else(|_| BooleanArray::from(vec![false; n]))
+            }
+            None => BooleanArray::from(vec![false; n]),
         },
-        Predicate::IsNotNull(col) => match batch.schema().index_of(col) {
-            Ok(i) => arrow::compute::is_not_null(batch.column(i))
+        Predicate::IsNotNull(col) => match resolve_column(batch, col) {
+            Some(arr) => arrow::compute::is_not_null(&arr)
                 .unwrap_or_else(|_| BooleanArray::from(vec![false; n])),
-            Err(_) => BooleanArray::from(vec![false; n]),
+            None => BooleanArray::from(vec![false; n]),
         },
         Predicate::Comparison { col, op, val } => eval_comparison(col, op, val, batch),
         Predicate::In { col, vals } => eval_in(col, vals, batch),
-        Predicate::Like { col, pattern } => eval_like(col, pattern, batch),
+        Predicate::Like {
+            col,
+            pattern,
+            case_insensitive,
+        } => eval_like(col, pattern, *case_insensitive, batch),
     }
 }
 
 fn eval_comparison(col: &str, op: &CmpOp, val: &Value, batch: &RecordBatch) -> BooleanArray {
     let n = batch.num_rows();
     let false_arr = || BooleanArray::from(vec![false; n]);
-    let idx = match batch.schema().index_of(col) {
-        Ok(i) => i,
-        Err(_) => return false_arr(),
+    let Some(arr) = resolve_column(batch, col) else {
+        return false_arr();
     };
-    let arr = batch.column(idx);
-    build_mask(arr, op, val, n)
+    if matches!(val, Value::Null) {
+        return BooleanArray::new_null(n);
+    }
+    null_where_null(build_mask(&arr, op, val, n), &arr)
+}
+
+// build_mask says false for NULL values; SQL says NULL
+fn null_where_null(mask: BooleanArray, arr: &ArrayRef) -> BooleanArray {
+    let nulls = NullBuffer::union(mask.nulls(), arr.logical_nulls().as_ref());
+    BooleanArray::new(mask.values().clone(), nulls)
 }
 
 fn build_mask(arr: &ArrayRef, op: &CmpOp, val: &Value, n: usize) -> BooleanArray {
@@ -594,6 +1409,26 @@ fn build_mask(arr: &ArrayRef, op: &CmpOp, val: &Value, n: usize) -> BooleanArray
     }
     // try string
     if let Some(a) = arr.as_any().downcast_ref::<StringArray>() {
+        if let Value::StrNoCase(sv) = val {
+            let lower = sv.to_lowercase();
+            let mut b = BooleanBuilder::with_capacity(n);
+            for i in 0..n {
+                if a.is_null(i) {
+                    b.append_value(false);
+                    continue;
+                }
+                let ord = cmp_folded(a.value(i), &lower);
+                b.append_value(match op {
+                    CmpOp::Eq => ord == Ordering::Equal,
+                    CmpOp::Ne => ord != Ordering::Equal,
+                    CmpOp::Lt => ord == Ordering::Less,
+                    CmpOp::Le => ord != Ordering::Greater,
+                    CmpOp::Gt => ord == Ordering::Greater,
+                    CmpOp::Ge => ord != Ordering::Less,
+                });
+            }
+            return b.finish();
+        }
         if let Value::Str(sv) = val {
             let mut b = BooleanBuilder::with_capacity(n);
             for i in 0..n {
@@ -636,55 +1471,107 @@ fn build_mask(arr: &ArrayRef, op: &CmpOp, val: &Value, n: usize) -> BooleanArray
         }
         return false_arr;
     }
-    // Decimal128: convert to f64 using scale for comparison
-    if let Some(a) = arr.as_any().downcast_ref::<Decimal128Array>() {
-        if let Value::Float(fv) = val {
-            let scale = a.scale();
-            let divisor = 10f64.powi(scale as i32);
+    // Decimal128 and Decimal256: compare exactly, widening Decimal128 to 256 bits
+    if let DataType::Decimal128(_, scale) | DataType::Decimal256(_, scale) = arr.data_type() {
+        let scale = *scale as i32;
+        let wide = arrow::compute::cast(arr, &DataType::Decimal256(76, scale as i8)).ok();
+        let wide = wide
+            .as_ref()
+            .and_then(|w| w.as_any().downcast_ref::<Decimal256Array>());
+        if let (Some(lit), Some(a)) = (decimal_literal(val), wide) {
             let mut b = BooleanBuilder::with_capacity(n);
             for i in 0..n {
                 if a.is_null(i) {
                     b.append_value(false);
                     continue;
                 }
-                let v = a.value(i) as f64 / divisor;
-                b.append_value(cmp_f64(v, op, *fv));
+                let ord = decimal_cmp((a.value(i), scale), lit);
+                b.append_value(ord.is_some_and(|o| match op {
+                    CmpOp::Eq => o == Ordering::Equal,
+                    CmpOp::Ne => o != Ordering::Equal,
+                    CmpOp::Lt => o == Ordering::Less,
+                    CmpOp::Le => o != Ordering::Greater,
+                    CmpOp::Gt => o == Ordering::Greater,
+                    CmpOp::Ge => o != Ordering::Less,
+                }));
             }
             return b.finish();
         }
         return false_arr;
     }
-    // Date32: days since epoch as i64 comparison
-    if let Some(a) = arr.as_any().downcast_ref::<Date32Array>() {
-        if let Value::Int(iv) = val {
+    // dates, timestamps, times and durations: compare in nanoseconds so a
+    // literal like '2024-01-02' works whatever the column's unit
+    if let Some(scale) = temporal_scale(arr.data_type()) {
+        let cv = match val {
+            Value::Int(v) => Some(*v as i128 * scale),
+            Value::Str(s) | Value::StrNoCase(s) => parse_temporal_literal(arr.data_type(), s),
+            _ => None,
+        };
+        let raw = arrow::compute::cast(arr, &DataType::Int64).ok();
+        let raw = raw
+            .as_ref()
+            .and_then(|r| r.as_any().downcast_ref::<Int64Array>());
+        if let (Some(cv), Some(a)) = (cv, raw) {
             let mut b = BooleanBuilder::with_capacity(n);
             for i in 0..n {
                 if a.is_null(i) {
                     b.append_value(false);
                     continue;
                 }
-                b.append_value(cmp_i64(a.value(i) as i64, op, *iv));
+                let v = a.value(i) as i128 * scale;
+                b.append_value(match op {
+                    CmpOp::Eq => v == cv,
+                    CmpOp::Ne => v != cv,
+                    CmpOp::Lt => v < cv,
+                    CmpOp::Le => v <= cv,
+                    CmpOp::Gt => v > cv,
+                    CmpOp::Ge => v >= cv,
+                });
             }
             return b.finish();
         }
         return false_arr;
     }
-    // Date64: milliseconds since epoch as i64 comparison
-    if let Some(a) = arr.as_any().downcast_ref::<Date64Array>() {
-        if let Value::Int(iv) = val {
-            let mut b = BooleanBuilder::with_capacity(n);
-            for i in 0..n {
-                if a.is_null(i) {
-                    b.append_value(false);
-                    continue;
-                }
-                b.append_value(cmp_i64(a.value(i), op, *iv));
-            }
-            return b.finish();
+    false_arr
+}
+
+/// Nanoseconds per stored unit for temporal types, `None` for the rest.
+fn temporal_scale(dt: &DataType) -> Option<i128> {
+    let per_unit = |u: &TimeUnit| match u {
+        TimeUnit::Second => 1_000_000_000,
+        TimeUnit::Millisecond => 1_000_000,
+        TimeUnit::Microsecond => 1_000,
+        TimeUnit::Nanosecond => 1,
+    };
+    match dt {
+        DataType::Date32 => Some(86_400 * 1_000_000_000),
+        DataType::Date64 => Some(1_000_000),
+        DataType::Timestamp(u, _)
+        | DataType::Time32(u)
+        | DataType::Time64(u)
+        | DataType::Duration(u) => Some(per_unit(u)),
+        _ => None,
+    }
+}
+
+/// A quoted date, timestamp or time literal in nanoseconds since the epoch
+/// (or since midnight for time columns). Zone-less timestamps are UTC.
+fn parse_temporal_literal(dt: &DataType, s: &str) -> Option<i128> {
+    match dt {
+        DataType::Time32(_) | DataType::Time64(_) => {
+            string_to_time_nanoseconds(s).ok().map(i128::from)
         }
-        return false_arr;
+        DataType::Duration(_) => None,
+        _ => string_to_timestamp_nanos(s).ok().map(i128::from),
     }
-    false_arr
+}
+
+// `lower` is already lowercase; folds `v` char by char instead of allocating
+fn cmp_folded(v: &str, lower: &str) -> Ordering {
+    if v.is_ascii() && lower.is_ascii() {
+        return v.bytes().map(|b| b.to_ascii_lowercase()).cmp(lower.bytes());
+    }
+    v.chars().flat_map(char::to_lowercase).cmp(lower.chars())
 }
 
 fn cmp_i64(v: i64, op: &CmpOp, cv: i64) -> bool {
@@ -712,40 +1599,128 @@ fn cmp_f64(v: f64, op: &CmpOp, cv: f64) -> bool {
 fn eval_in(col: &str, vals: &[Value], batch: &RecordBatch) -> BooleanArray {
     let n = batch.num_rows();
     let false_arr = BooleanArray::from(vec![false; n]);
-    let idx = match batch.schema().index_of(col) {
-        Ok(i) => i,
-        Err(_) => return false_arr,
+    let Some(arr) = resolve_column(batch, col) else {
+        return false_arr;
     };
-    let arr = batch.column(idx);
-    // build OR of equality masks
+    let arr = &arr;
     if vals.is_empty() {
         return false_arr;
     }
+    if let Some(mask) = eval_in_set(arr, vals) {
+        return mask;
+    }
+    // other types: OR of equality masks; a NULL in the list leaves non-matches NULL
     let mut result = BooleanArray::from(vec![false; n]);
     for v in vals {
-        let mask = build_mask(arr, &CmpOp::Eq, v, n);
-        result = arrow::compute::or(&result, &mask)
+        let mask = match v {
+            Value::Null => BooleanArray::new_null(n),
+            v => null_where_null(build_mask(arr, &CmpOp::Eq, v, n), arr),
+        };
+        result = arrow::compute::or_kleene(&result, &mask)
             .unwrap_or_else(|_| BooleanArray::from(vec![false; n]));
     }
     result
 }
 
-fn eval_like(col: &str, pattern: &str, batch: &RecordBatch) -> BooleanArray {
+// IN over integer, float and string columns as one hash lookup per row, so a
+// list of tens of thousands of values costs no more than a short one. Values
+// convert as `=` would convert them; string values (what @file lists hold)
+// also parse as numbers for numeric columns.
+fn eval_in_set(arr: &ArrayRef, vals: &[Value]) -> Option<BooleanArray> {
+    let list_has_null = vals.iter().any(|v| matches!(v, Value::Null));
+    let finish = |hits: &mut dyn Iterator<Item = Option<bool>>| -> BooleanArray {
+        hits.map(|hit| match hit {
+            None => None,
+            Some(true) => Some(true),
+            Some(false) if list_has_null => None,
+            Some(false) => Some(false),
+        })
+        .collect()
+    };
+    let int_of = |v: &Value| match v {
+        Value::Int(i) => Some(*i),
+        Value::Float(f) => Some(*f as i64),
+        Value::Str(s) | Value::StrNoCase(s) => s.parse().ok(),
+        _ => None,
+    };
+    // NaN never equals anything and -0.0 == 0.0, as with `=`
+    let float_key = |f: f64| (!f.is_nan()).then(|| if f == 0.0 { 0 } else { f.to_bits() });
+    let float_of = |v: &Value| match v {
+        Value::Int(i) => float_key(*i as f64),
+        Value::Float(f) => float_key(*f),
+        Value::Str(s) | Value::StrNoCase(s) => s.parse().ok().and_then(float_key),
+        _ => None,
+    };
+    if let Some(a) = arr.as_any().downcast_ref::<Int32Array>() {
+        let set: HashSet<i64> = vals.iter().filter_map(int_of).collect();
+        return Some(finish(
+            &mut a.iter().map(|v| v.map(|v| set.contains(&(v as i64)))),
+        ));
+    }
+    if let Some(a) = arr.as_any().downcast_ref::<Int64Array>() {
+        let set: HashSet<i64> = vals.iter().filter_map(int_of).collect();
+        return Some(finish(&mut a.iter().map(|v| v.map(|v| set.contains(&v)))));
+    }
+    if let Some(a) = arr.as_any().downcast_ref::<Float32Array>() {
+        let set: HashSet<u64> = vals.iter().filter_map(float_of).collect();
+        let hit = |v: f32| float_key(v as f64).is_some_and(|k| set.contains(&k));
+        return Some(finish(&mut a.iter().map(|v| v.map(hit))));
+    }
+    if let Some(a) = arr.as_any().downcast_ref::<Float64Array>() {
+        let set: HashSet<u64> = vals.iter().filter_map(float_of).collect();
+        let hit = |v: f64| float_key(v).is_some_and(|k| set.contains(&k));
+        return Some(finish(&mut a.iter().map(|v| v.map(hit))));
+    }
+    if let Some(a) = arr.as_any().downcast_ref::<StringArray>() {
+        let exact: HashSet<&str> = vals
+            .iter()
+            .filter_map(|v| match v {
+                Value::Str(s) => Some(s.as_str()),
+                _ => None,
+            })
+            .collect();
+        let folded: HashSet<String> = vals
+            .iter()
+            .filter_map(|v| match v {
+                Value::StrNoCase(s) => Some(s.to_lowercase()),
+                _ => None,
+            })
+            .collect();
+        let hit = |v: &str| {
+            exact.contains(v) || (!folded.is_empty() && folded.contains(&v.to_lowercase()))
+        };
+        return Some(finish(&mut a.iter().map(|v| v.map(hit))));
+    }
+    None
+}
+
+fn eval_like(
+    col: &str,
+    pattern: &str,
+    case_insensitive: bool,
+    batch: &RecordBatch,
+) -> BooleanArray {
     let n = batch.num_rows();
     let false_arr = BooleanArray::from(vec![false; n]);
-    let idx = match batch.schema().index_of(col) {
-        Ok(i) => i,
-        Err(_) => return false_arr,
+    let Some(arr) = resolve_column(batch, col) else {
+        return false_arr;
     };
-    let arr = batch.column(idx);
+    let arr = &arr;
     let Some(a) = arr.as_any().downcast_ref::<StringArray>() else {
         return false_arr;
     };
-    let re = like_to_regex(pattern);
+    let mut re = like_to_regex(pattern);
+    if case_insensitive {
+        for part in &mut re {
+            if let LikePart::Literal(lit) = part {
+                *part = LikePart::Folded(lit.to_lowercase());
+            }
+        }
+    }
     let mut b = BooleanBuilder::with_capacity(n);
     for i in 0..n {
         if a.is_null(i) {
-            b.append_value(false);
+            b.append_null();
             continue;
         }
         b.append_value(like_match(a.value(i), &re));
@@ -784,6 +1759,7 @@ fn like_to_regex(pattern: &str) -> Vec<LikePart> {
 #[derive(Debug)]
 enum LikePart {
     Literal(String),
+    Folded(String), // lowercase literal matched ignoring case (ILIKE)
     Any,
     One,
 }
@@ -804,6 +1780,10 @@ fn like_match_at(s: &str, parts: &[LikePart]) -> bool {
                 false
             }
         }
+        LikePart::Folded(lit) => match strip_prefix_folded(s, lit) {
+            Some(rest) => like_match_at(rest, &parts[1..]),
+            None => false,
+        },
         LikePart::One => {
             let mut chars = s.chars();
             if chars.next().is_some() {
@@ -828,8 +1808,195 @@ fn like_match_at(s: &str, parts: &[LikePart]) -> bool {
     }
 }
 
+// `s` minus a prefix that lowercases to `lower`, whole chars only
+fn strip_prefix_folded<'a>(s: &'a str, lower: &str) -> Option<&'a str> {
+    let mut want = lower.chars();
+    for (i, c) in s.char_indices() {
+        if want.as_str().is_empty() {
+            return Some(&s[i..]);
+        }
+        for l in c.to_lowercase() {
+            if want.next() != Some(l) {
+                return None;
+            }
+        }
+    }
+    want.as_str().is_empty().then_some("")
+}
+
+// --- nested column paths ---
+
+/// One step of a column reference: a struct field or a list index.
+#[derive(Debug, PartialEq)]
+enum PathStep {
+    Field(String),
+    Index(usize),
+}
+
+// `user.address.zip`, `tags[0]`, `items[2].sku`; an exact top-level name wins,
+// so flat columns with dots in their names still resolve
+fn column_path(schema: &Schema, col: &str) -> Option<Vec<PathStep>> {
+    if schema.field_with_name(col).is_ok() {
+        return Some(vec![PathStep::Field(col.to_string())]);
+    }
+    let mut steps = Vec::new();
+    for part in col.split('.') {
+        let (name, mut rest) = part.split_once('[').map_or((part, ""), |(n, r)| (n, r));
+        if name.is_empty() {
+            return None;
+        }
+        steps.push(PathStep::Field(name.to_string()));
+        while !rest.is_empty() {
+            let (idx, tail) = rest.split_once(']')?;
+            steps.push(PathStep::Index(idx.parse().ok()?));
+            rest = match tail {
+                "" => "",
+                t => t.strip_prefix('[')?,
+            };
+        }
+    }
+    Some(steps)
+}
+
+// the type one step selects, and how many leaf columns come before it
+fn step_type<'a>(dt: &'a DataType, step: &PathStep) -> Option<(&'a DataType, usize)> {
+    match (dt, step) {
+        (DataType::Struct(fields), PathStep::Field(name)) => {
+            let pos = fields.iter().position(|f| f.name() == name)?;
+            let before = fields[..pos]
+                .iter()
+                .map(|f| leaf_count(f.data_type()))
+                .sum();
+            Some((fields[pos].data_type(), before))
+        }
+        (
+            DataType::List(f) | DataType::LargeList(f) | DataType::FixedSizeList(f, _),
+            PathStep::Index(_),
+        ) => Some((f.data_type(), 0)),
+        _ => None,
+    }
+}
+
+fn leaf_count(dt: &DataType) -> usize {
+    match dt {
+        DataType::Struct(fields) => fields.iter().map(|f| leaf_count(f.data_type())).sum(),
+        DataType::List(f)
+        | DataType::LargeList(f)
+        | DataType::FixedSizeList(f, _)
+        | DataType::Map(f, _) => leaf_count(f.data_type()),
+        _ => 1,
+    }
+}
+
+// the type `col` refers to and the parquet leaf columns it reads
+fn walk_path(schema: &Schema, col: &str) -> Option<(DataType, std::ops::Range<usize>)> {
+    let root = DataType::Struct(schema.fields().clone());
+    let mut dt = &root;
+    let mut first = 0;
+    for step in column_path(schema, col)? {
+        let (next, before) = step_type(dt, &step)?;
+        dt = next;
+        first += before;
+    }
+    Some((dt.clone(), first..first + leaf_count(dt)))
+}
+
+/// The type of a column reference, or the usual "not found" error listing
+/// every path the schema offers.
+pub(crate) fn resolve_type(schema: &Schema, col: &str) -> Result<DataType, String> {
+    walk_path(schema, col).map(|(dt, _)| dt).ok_or_else(|| {
+        let mut paths = Vec::new();
+        for f in schema.fields() {
+            nested_paths(f.name(), f.data_type(), &mut paths);
+        }
+        format!(
+            "column '{col}' not found in schema (available: {})",
+            paths.join(", ")
+        )
+    })
+}
+
+fn nested_paths(prefix: &str, dt: &DataType, out: &mut Vec<String>) {
+    match dt {
+        DataType::Struct(fields) => {
+            for f in fields {
+                nested_paths(&format!("{prefix}.{}", f.name()), f.data_type(), out);
+            }
+        }
+        DataType::List(f) | DataType::LargeList(f) | DataType::FixedSizeList(f, _) => {
+            nested_paths(&format!("{prefix}[n]"), f.data_type(), out)
+        }
+        _ => out.push(prefix.to_string()),
+    }
+}
+
+/// Reads just the leaf columns under each reference in `cols`.
+pub(crate) fn leaf_projection(meta: &ArrowReaderMetadata, cols: &[String]) -> ProjectionMask {
+    let leaves = cols
+        .iter()
+        .filter_map(|c| walk_path(meta.schema(), c))
+        .flat_map(|(_, range)| range);
+    ProjectionMask::leaves(meta.metadata().file_metadata().schema_descr(), leaves)
+}
+
+/// The values `col` refers to in `batch`, one per row. A null struct makes its
+/// fields null, and a list index past the end of a row's list is null.
+pub(crate) fn resolve_column(batch: &RecordBatch, col: &str) -> Option<ArrayRef> {
+    let steps = column_path(batch.schema_ref(), col)?;
+    let mut arr: ArrayRef = Arc::new(StructArray::from(batch.clone()));
+    for step in &steps {
+        arr = step_array(&arr, step)?;
+    }
+    Some(arr)
+}
+
+fn step_array(arr: &ArrayRef, step: &PathStep) -> Option<ArrayRef> {
+    match step {
+        PathStep::Field(name) => {
+            let parent = arr.as_any().downcast_ref::<StructArray>()?;
+            let child = parent.column_by_name(name)?;
+            if parent.nulls().is_none() {
+                return Some(child.clone());
+            }
+            let nulls = NullBuffer::union(parent.nulls(), child.nulls());
+            let data = child.to_data().into_builder().nulls(nulls).build().ok()?;
+            Some(make_array(data))
+        }
+        PathStep::Index(i) => {
+            let any = arr.as_any();
+            let (values, picks) = if let Some(a) = any.downcast_ref::<GenericListArray<i32>>() {
+                (a.values(), list_picks(a, *i))
+            } else if let Some(a) = any.downcast_ref::<GenericListArray<i64>>() {
+                (a.values(), list_picks(a, *i))
+            } else if let Some(a) = any.downcast_ref::<FixedSizeListArray>() {
+                let picks = (0..a.len())
+                    .map(|r| {
+                        let len = a.value_length() as usize;
+                        (a.is_valid(r) && *i < len).then(|| (a.value_offset(r) as usize + i) as u64)
+                    })
+                    .collect();
+                (a.values(), picks)
+            } else {
+                return None;
+            };
+            arrow::compute::take(values.as_ref(), &picks, None).ok()
+        }
+    }
+}
+
+// the position in `values` of each row's element `i`, null when the list is shorter
+fn list_picks<O: OffsetSizeTrait>(a: &GenericListArray<O>, i: usize) -> UInt64Array {
+    let offsets = a.value_offsets();
+    (0..a.len())
+        .map(|r| {
+            let (start, end) = (offsets[r].as_usize(), offsets[r + 1].as_usize());
+            (a.is_valid(r) && start + i < end).then(|| (start + i) as u64)
+        })
+        .collect()
+}
+
 /// collect all column names referenced in predicate
-fn predicate_columns(pred: &Predicate) -> Vec<&str> {
+pub(crate) fn predicate_columns(pred: &Predicate) -> Vec<&str> {
     match pred {
         Predicate::Comparison { col, .. }
         | Predicate::IsNull(col)
@@ -845,62 +2012,50 @@ fn predicate_columns(pred: &Predicate) -> Vec<&str> {
     }
 }
 
+// use path_in_schema for nested dot-notation path matching
+fn check_columns(schema: &Schema, predicate: &Predicate) -> Result<(), String> {
+    for col in predicate_columns(predicate) {
+        resolve_type(schema, col)?;
+    }
+    Ok(())
+}
+
 // --- main filter_count entry point ---
 
-pub fn filter_count(path: &Path, predicate: &Predicate) -> Result<FilterResult, String> {
-    let file = File::open(path).map_err(|e| e.to_string())?;
-    let builder = ParquetRecordBatchReaderBuilder::try_new(file).map_err(|e| e.to_string())?;
+#[tracing::instrument(skip_all, fields(path = %path.display()))]
+pub fn filter_count(
+    path: &Path,
+    predicate: &Predicate,
+    opts: &ScanOptions,
+) -> Result<FilterResult, String> {
+    let builder = crate::handle::HandleRegistry::global()
+        .get(path)
+        .and_then(|h| h.reader_builder())
+        .map_err(|e| e.to_string())?;
     let meta: std::sync::Arc<ParquetMetaData> = builder.metadata().clone(); // single open
-                                                                            // bounds check: verify all referenced columns exist in schema
-                                                                            // use path_in_schema for nested dot-notation path matching
-    let schema = meta.file_metadata().schema_descr();
-    let schema_paths: Vec<String> = (0..schema.num_columns())
-        .map(|i| schema.column(i).path().string())
-        .collect();
-    for col in predicate_columns(predicate) {
-        if !schema_paths.iter().any(|n| n == col) {
-            return Err(format!(
-                "column '{}' not found in schema (available: {})",
-                col,
-                schema_paths.join(", ")
-            ));
-        }
-    }
+    check_columns(builder.schema(), predicate)?; // bounds check: verify all referenced columns exist in schema
     let total_rgs = meta.num_row_groups();
-    let mut skipped_rgs = 0usize;
-    let mut rgs_to_scan: Vec<usize> = Vec::new();
-    for rg_idx in 0..total_rgs {
-        let rg = meta.row_group(rg_idx);
-        if can_skip_row_group(predicate, rg) {
-            skipped_rgs += 1;
-        } else {
-            rgs_to_scan.push(rg_idx);
-        }
-    }
+    let plan = plan_row_groups(path, &meta, predicate);
+    let rgs_to_scan = plan.to_scan;
     let mut matched_rows = 0u64;
     let mut scanned_rows = 0u64;
     let mut sample_headers: Vec<String> = Vec::new();
     let mut sample_rows: Vec<Vec<String>> = Vec::new();
     if !rgs_to_scan.is_empty() {
-        let selection = parquet::arrow::arrow_reader::RowSelection::from(
-            (0..total_rgs)
-                .map(|i| {
-                    let count = meta.row_group(i).num_rows() as usize;
-                    if rgs_to_scan.contains(&i) {
-                        parquet::arrow::arrow_reader::RowSelector::select(count)
-                    } else {
-                        parquet::arrow::arrow_reader::RowSelector::skip(count)
-                    }
-                })
-                .collect::<Vec<_>>(),
-        );
+        let selection = scan_selection(&meta, &rgs_to_scan);
         let reader = builder
             .with_row_selection(selection)
+            .with_batch_size(opts.batch_size)
             .build()
             .map_err(|e| e.to_string())?;
-        for batch_result in reader {
+        let progress = opts.start_progress("filter", row_group_totals(&meta, &rgs_to_scan));
+        let mut batch_gauge = opts.gauge();
+        for batch_result in prefetch_batches(reader, opts.prefetch_depth) {
+            opts.check_cancelled().map_err(|e| e.to_string())?;
             let batch = batch_result.map_err(|e| e.to_string())?;
+            batch_gauge.set(batch.get_array_memory_size());
             scanned_rows += batch.num_rows() as u64;
+            progress.advance_rows(batch.num_rows() as u64);
             let mask = eval_predicate_batch(predicate, &batch);
             matched_rows += mask.true_count() as u64;
             // collect up to 10 sample rows from first matching batch
@@ -930,80 +2085,161 @@ pub fn filter_count(path: &Path, predicate: &Predicate) -> Result<FilterResult,
     Ok(FilterResult {
         matched_rows,
         scanned_rows,
-        skipped_rgs,
+        skipped_rgs: plan.stats_skipped,
+        bloom_skipped_rgs: plan.bloom_skipped,
+        dict_skipped_rgs: plan.dict_skipped,
         total_rgs,
         sample_headers,
         sample_rows,
     })
 }
 
+/// [`filter_count`] over several files, `opts.threads` of them at a time.
+/// Progress is reported per finished file rather than per batch.
+#[tracing::instrument(skip_all, fields(files = paths.len()))]
+pub fn filter_count_dataset(
+    paths: &[PathBuf],
+    predicate: &Predicate,
+    opts: &ScanOptions,
+) -> Result<DatasetFilterResult, String> {
+    let registry = crate::handle::HandleRegistry::global();
+    let handles = paths
+        .iter()
+        .map(|p| registry.get(p).map_err(|e| format!("{}: {e}", p.display())))
+        .collect::<Result<Vec<_>, _>>()?;
+    let total = handles.iter().fold(Progress::default(), |acc, h| Progress {
+        rows: acc.rows + h.metadata().file_metadata().num_rows().max(0) as u64,
+        bytes: acc.bytes + h.file_size(),
+    });
+    let progress = opts.start_progress("filter", total);
+    let per_file = ScanOptions {
+        progress: None,
+        ..opts.clone()
+    };
+    let pool = scan_pool(opts.threads).map_err(|e| e.to_string())?;
+    let results: Vec<Result<FilterResult, String>> = pool.install(|| {
+        handles
+            .par_iter()
+            .map(|h| {
+                let r = filter_count(h.path(), predicate, &per_file)
+                    .map_err(|e| format!("{}: {e}", h.path().display()))?;
+                progress.advance(
+                    h.metadata().file_metadata().num_rows().max(0) as u64,
+                    h.file_size(),
+                );
+                Ok(r)
+            })
+            .collect()
+    });
+    let mut out = DatasetFilterResult {
+        matched_rows: 0,
+        scanned_rows: 0,
+        skipped_rgs: 0,
+        bloom_skipped_rgs: 0,
+        dict_skipped_rgs: 0,
+        total_rgs: 0,
+        files: Vec::with_capacity(paths.len()),
+    };
+    for (path, r) in paths.iter().zip(results) {
+        let r = r?;
+        out.matched_rows += r.matched_rows;
+        out.scanned_rows += r.scanned_rows;
+        out.skipped_rgs += r.skipped_rgs;
+        out.bloom_skipped_rgs += r.bloom_skipped_rgs;
+        out.dict_skipped_rgs += r.dict_skipped_rgs;
+        out.total_rgs += r.total_rgs;
+        out.files.push(FileFilterResult {
+            path: path.display().to_string(),
+            result: r,
+        });
+    }
+    Ok(out)
+}
+
 /// Like filter_count but returns matching rows as RecordBatches, up to `limit` total rows.
-/// Used by the filter subcommand for CSV export.
+/// Collects what [`filter_rows_each`] streams.
+#[tracing::instrument(skip_all, fields(path = %path.display(), limit))]
 pub fn filter_rows(
     path: &Path,
     predicate: &Predicate,
     limit: Option<usize>,
+    opts: &ScanOptions,
 ) -> Result<Vec<RecordBatch>, String> {
-    let file = File::open(path).map_err(|e| e.to_string())?;
-    let builder = ParquetRecordBatchReaderBuilder::try_new(file).map_err(|e| e.to_string())?;
+    let mut out: Vec<RecordBatch> = Vec::new();
+    // the matching rows collected so far
+    let mut out_gauge = opts.gauge();
+    let mut out_bytes = 0usize;
+    filter_rows_each(path, predicate, limit, opts, &mut |batch| {
+        out_bytes += batch.get_array_memory_size();
+        out_gauge.set(out_bytes);
+        out.push(batch);
+        Ok(())
+    })?;
+    Ok(out)
+}
+
+/// Hands the rows matching `predicate` to `on_batch` one filtered batch at a time,
+/// so nothing accumulates, and stops reading once `limit` rows have gone out.
+/// The counts cover the rows read; the result carries no sample rows.
+#[tracing::instrument(skip_all, fields(path = %path.display(), limit))]
+pub fn filter_rows_each(
+    path: &Path,
+    predicate: &Predicate,
+    limit: Option<usize>,
+    opts: &ScanOptions,
+    on_batch: &mut dyn FnMut(RecordBatch) -> Result<(), String>,
+) -> Result<FilterResult, String> {
+    let builder = crate::handle::HandleRegistry::global()
+        .get(path)
+        .and_then(|h| h.reader_builder())
+        .map_err(|e| e.to_string())?;
+    check_columns(builder.schema(), predicate)?;
     let meta = builder.metadata().clone();
-    let total_rgs = meta.num_row_groups();
-    let mut rgs_to_scan: Vec<usize> = Vec::new();
-    for rg_idx in 0..total_rgs {
-        if !can_skip_row_group(predicate, meta.row_group(rg_idx)) {
-            rgs_to_scan.push(rg_idx);
-        }
+    let plan = plan_row_groups(path, &meta, predicate);
+    let mut result = FilterResult {
+        matched_rows: 0,
+        scanned_rows: 0,
+        skipped_rgs: plan.stats_skipped,
+        bloom_skipped_rgs: plan.bloom_skipped,
+        dict_skipped_rgs: plan.dict_skipped,
+        total_rgs: meta.num_row_groups(),
+        sample_headers: Vec::new(),
+        sample_rows: Vec::new(),
+    };
+    let mut remaining = limit.unwrap_or(usize::MAX);
+    if plan.to_scan.is_empty() || remaining == 0 {
+        return Ok(result);
     }
-    let mut out: Vec<RecordBatch> = Vec::new();
-    let mut total_collected: usize = 0;
-    if rgs_to_scan.is_empty() {
-        return Ok(out);
-    }
-    let selection = parquet::arrow::arrow_reader::RowSelection::from(
-        (0..total_rgs)
-            .map(|i| {
-                let count = meta.row_group(i).num_rows() as usize;
-                if rgs_to_scan.contains(&i) {
-                    parquet::arrow::arrow_reader::RowSelector::select(count)
-                } else {
-                    parquet::arrow::arrow_reader::RowSelector::skip(count)
-                }
-            })
-            .collect::<Vec<_>>(),
-    );
     let reader = builder
-        .with_row_selection(selection)
+        .with_row_selection(scan_selection(&meta, &plan.to_scan))
+        .with_batch_size(opts.batch_size)
         .build()
         .map_err(|e| e.to_string())?;
-    for batch_result in reader {
+    let progress = opts.start_progress("filter", row_group_totals(&meta, &plan.to_scan));
+    let mut batch_gauge = opts.gauge();
+    // dropping the prefetcher at the limit stops the background reads
+    for batch_result in prefetch_batches(reader, opts.prefetch_depth) {
+        opts.check_cancelled().map_err(|e| e.to_string())?;
         let batch = batch_result.map_err(|e| e.to_string())?;
+        batch_gauge.set(batch.get_array_memory_size());
+        result.scanned_rows += batch.num_rows() as u64;
+        progress.advance_rows(batch.num_rows() as u64);
         let mask = eval_predicate_batch(predicate, &batch);
-        if mask.true_count() == 0 {
+        let matched = mask.true_count();
+        if matched == 0 {
             continue;
         }
-        let indices: Vec<u64> = (0..batch.num_rows())
-            .filter(|&r| mask.value(r))
-            .map(|r| r as u64)
-            .collect();
-        let idx_arr = arrow::array::UInt64Array::from(indices);
+        result.matched_rows += matched as u64;
         let filtered =
-            arrow::compute::take_record_batch(&batch, &idx_arr).map_err(|e| e.to_string())?;
-        let remaining = limit
-            .map(|lim| lim.saturating_sub(total_collected))
-            .unwrap_or(usize::MAX);
-        if filtered.num_rows() <= remaining {
-            total_collected += filtered.num_rows();
-            out.push(filtered);
-        } else {
-            let sliced = filtered.slice(0, remaining);
-            total_collected += sliced.num_rows();
-            out.push(sliced);
-        }
-        if limit.is_some_and(|lim| total_collected >= lim) {
+            arrow::compute::filter_record_batch(&batch, &mask).map_err(|e| e.to_string())?;
+        let kept = filtered.slice(0, filtered.num_rows().min(remaining));
+        remaining -= kept.num_rows();
+        on_batch(kept)?;
+        if remaining == 0 {
             break;
         }
     }
-    Ok(out)
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -1233,6 +2469,401 @@ mod tests_parse_predicate {
     }
 }
 
+#[cfg(test)]
+mod tests_null_semantics {
+    use super::*;
+    use arrow::datatypes::{Field, Schema};
+
+    fn matches(expr: &str) -> Vec<bool> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("s", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), Some(2), None])),
+                Arc::new(StringArray::from(vec![Some("x"), None, Some("y")])),
+            ],
+        )
+        .unwrap();
+        let pred = parse_predicate(expr).unwrap();
+        eval_predicate_batch(&pred, &batch)
+            .iter()
+            .map(|v| v.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn not_keeps_unknown_rows_out() {
+        assert_eq!(matches("NOT (a = 1)"), [false, true, false]);
+        assert_eq!(matches("NOT (s LIKE 'x%')"), [false, false, true]);
+        assert_eq!(matches("a = 1 OR NOT (a = 1)"), [true, true, false]);
+        assert_eq!(matches("NOT (a = 1 AND s = 'x')"), [false, true, true]);
+        assert_eq!(matches("NOT (a = NULL)"), [false, false, false]);
+    }
+
+    #[test]
+    fn null_in_lists_is_unknown() {
+        assert_eq!(matches("a IN (2, NULL)"), [false, true, false]);
+        assert_eq!(matches("a NOT IN (2, NULL)"), [false, false, false]);
+        assert_eq!(matches("a NOT IN (2)"), [true, false, false]);
+    }
+
+    #[test]
+    fn distinct_from_treats_null_as_a_value() {
+        assert_eq!(matches("a IS DISTINCT FROM 1"), [false, true, true]);
+        assert_eq!(matches("a IS NOT DISTINCT FROM 1"), [true, false, false]);
+        assert_eq!(matches("a IS DISTINCT FROM NULL"), [true, true, false]);
+        assert_eq!(matches("a is not distinct from null"), [false, false, true]);
+        assert_eq!(
+            matches("NOT (s IS DISTINCT FROM 'y')"),
+            [false, false, true]
+        );
+        let p = parse_predicate("a IS DISTINCT FROM 1").unwrap();
+        assert_eq!(p.to_string(), "(a != 1 OR a IS NULL)");
+        assert!(parse_predicate("a IS DISTINCT 1").is_err());
+    }
+}
+
+#[cfg(test)]
+mod tests_nested_paths {
+    use super::*;
+    use arrow::array::{ListArray, StringBuilder};
+    use arrow::datatypes::{Field, Fields, Int32Type};
+
+    fn batch() -> RecordBatch {
+        // row 0: user {zip '123'}, tags [1, 2]; row 1: user NULL, tags []; row 2: tags NULL
+        let zip = Field::new("zip", DataType::Utf8, true);
+        let mut zips = StringBuilder::new();
+        for z in ["123", "456", "789"] {
+            zips.append_value(z);
+        }
+        let user = StructArray::try_new(
+            Fields::from(vec![zip]),
+            vec![Arc::new(zips.finish())],
+            Some(NullBuffer::from(vec![true, false, true])),
+        )
+        .unwrap();
+        let tags = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1), Some(2)]),
+            Some(vec![]),
+            None,
+        ]);
+        let schema = Schema::new(vec![
+            Field::new("user", user.data_type().clone(), true),
+            Field::new("tags", tags.data_type().clone(), true),
+            Field::new("a.b", DataType::Int32, false),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(user),
+                Arc::new(tags),
+                Arc::new(Int32Array::from(vec![7, 8, 9])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn matches(expr: &str) -> Vec<bool> {
+        let pred = parse_predicate(expr).unwrap();
+        eval_predicate_batch(&pred, &batch())
+            .iter()
+            .map(|v| v.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn paths_split_into_fields_and_indexes() {
+        let schema = batch().schema();
+        let steps = column_path(&schema, "items[2][0].sku").unwrap();
+        assert_eq!(
+            steps,
+            [
+                PathStep::Field("items".into()),
+                PathStep::Index(2),
+                PathStep::Index(0),
+                PathStep::Field("sku".into()),
+            ]
+        );
+        assert_eq!(column_path(&schema, "a.b").unwrap().len(), 1);
+        assert!(column_path(&schema, "tags[x]").is_none());
+        assert!(column_path(&schema, "tags[1").is_none());
+        assert!(column_path(&schema, ".zip").is_none());
+    }
+
+    #[test]
+    fn struct_fields_inherit_parent_nulls() {
+        assert_eq!(matches("user.zip = '456'"), [false, false, false]);
+        assert_eq!(matches("user.zip IS NULL"), [false, true, false]);
+        assert_eq!(matches("user.zip LIKE '%3'"), [true, false, false]);
+    }
+
+    #[test]
+    fn list_indexes_past_the_end_are_null() {
+        assert_eq!(matches("tags[1] = 2"), [true, false, false]);
+        assert_eq!(matches("tags[0] IS NULL"), [false, true, true]);
+        assert_eq!(matches("tags[0] IN (1, 3)"), [true, false, false]);
+    }
+
+    #[test]
+    fn dotted_top_level_names_still_resolve() {
+        assert_eq!(matches("a.b >= 8"), [false, true, true]);
+    }
+
+    #[test]
+    fn types_and_leaf_ranges() {
+        let schema = batch().schema();
+        assert_eq!(resolve_type(&schema, "tags[3]").unwrap(), DataType::Int32);
+        assert_eq!(walk_path(&schema, "a.b").unwrap().1, 2..3);
+        assert_eq!(walk_path(&schema, "user").unwrap().1, 0..1);
+        let err = resolve_type(&schema, "user.city").unwrap_err();
+        assert_eq!(
+            err,
+            "column 'user.city' not found in schema (available: user.zip, tags[n], a.b)"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_case_insensitive {
+    use super::*;
+    use arrow::datatypes::{Field, Schema};
+    use std::sync::Arc;
+
+    fn matches(expr: &str, ignore_case: bool) -> Vec<bool> {
+        let schema = Schema::new(vec![Field::new("name", DataType::Utf8, true)]);
+        let names = StringArray::from(vec![Some("Alice"), Some("ALICE"), Some("Ärger"), None]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(names)]).unwrap();
+        let mut pred = parse_predicate(expr).unwrap();
+        if ignore_case {
+            pred = pred.ignore_case();
+        }
+        eval_predicate_batch(&pred, &batch)
+            .iter()
+            .map(|v| v.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn ilike_folds_pattern_and_value() {
+        assert_eq!(
+            matches("name ILIKE 'ali%'", false),
+            [true, true, false, false]
+        );
+        assert_eq!(
+            matches("name LIKE 'ali%'", false),
+            [false, false, false, false]
+        );
+        assert_eq!(
+            matches("name ilike '%ICE'", false),
+            [true, true, false, false]
+        );
+        assert_eq!(
+            matches("name ILIKE 'äR_er'", false),
+            [false, false, true, false]
+        );
+    }
+
+    #[test]
+    fn ignore_case_applies_to_equality_in_and_like() {
+        assert_eq!(matches("name = 'alice'", true), [true, true, false, false]);
+        assert_eq!(
+            matches("name != 'alice'", true),
+            [false, false, true, false]
+        );
+        assert_eq!(
+            matches("name IN ('ärger', 'bob')", true),
+            [false, false, true, false]
+        );
+        assert_eq!(matches("name LIKE 'a%'", true), [true, true, false, false]);
+        assert_eq!(
+            matches("name = 'alice'", false),
+            [false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn folded_comparison_does_not_split_chars() {
+        assert_eq!(cmp_folded("ÄRGER", "ärger"), Ordering::Equal);
+        assert_eq!(cmp_folded("Abc", "abd"), Ordering::Less);
+        assert_eq!(strip_prefix_folded("ÄRGER", "är"), Some("GER"));
+        assert_eq!(strip_prefix_folded("Ab", "abc"), None);
+    }
+
+    #[test]
+    fn ignore_case_leaves_other_literals_alone() {
+        let pred = parse_predicate("n < 'B' AND x = 3").unwrap().ignore_case();
+        assert_eq!(pred.to_string(), "(n < 'B' AND x = 3)");
+        let pred = parse_predicate("n = 'B'").unwrap().ignore_case();
+        assert_eq!(pred.to_string(), "n = 'B' (any case)");
+    }
+}
+
+#[cfg(test)]
+mod tests_temporal_filter {
+    use super::*;
+    use arrow::array::{Date32Array, Time64NanosecondArray, TimestampMillisecondArray};
+    use arrow::datatypes::{Field, Schema};
+    use std::sync::Arc;
+
+    fn batch() -> RecordBatch {
+        // 2024-01-01, 2024-01-02, 2024-01-03 at noon UTC
+        let days: [i32; 3] = [19723, 19724, 19725];
+        let ms: Vec<i64> = days
+            .iter()
+            .map(|&d| d as i64 * 86_400_000 + 43_200_000)
+            .collect();
+        let schema = Schema::new(vec![
+            Field::new("d", DataType::Date32, true),
+            Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+                true,
+            ),
+            Field::new("t", DataType::Time64(TimeUnit::Nanosecond), true),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Date32Array::from(days.to_vec())),
+                Arc::new(TimestampMillisecondArray::from(ms).with_timezone("UTC")),
+                Arc::new(Time64NanosecondArray::from(vec![
+                    8 * 3_600_000_000_000,
+                    12 * 3_600_000_000_000,
+                    18 * 3_600_000_000_000,
+                ])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn matches(expr: &str) -> Vec<bool> {
+        let pred = parse_predicate(expr).unwrap();
+        eval_predicate_batch(&pred, &batch())
+            .iter()
+            .map(|v| v.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn date_literals() {
+        assert_eq!(matches("d >= '2024-01-02'"), [false, true, true]);
+        assert_eq!(matches("d = '2024-01-03'"), [false, false, true]);
+        assert_eq!(matches("d < 19724"), [true, false, false]);
+    }
+
+    #[test]
+    fn timestamp_literals_convert_units() {
+        assert_eq!(matches("ts >= '2024-01-02'"), [false, true, true]);
+        assert_eq!(matches("ts = '2024-01-02T12:00:00Z'"), [false, true, false]);
+        assert_eq!(
+            matches("ts <= '2024-01-02 13:00:00+01:00'"),
+            [true, true, false]
+        );
+    }
+
+    #[test]
+    fn time_literals() {
+        assert_eq!(matches("t > '09:30'"), [false, true, true]);
+        assert_eq!(matches("t <= '12:00:00'"), [true, true, false]);
+    }
+
+    #[test]
+    fn unparseable_literal_matches_nothing() {
+        assert_eq!(matches("ts > 'yesterday'"), [false, false, false]);
+    }
+
+    #[test]
+    fn temporal_values_render_as_text() {
+        let b = batch();
+        assert_eq!(col_val_str(b.column(0).as_ref(), 1), "2024-01-02");
+        assert_eq!(col_val_str(b.column(2).as_ref(), 0), "08:00:00");
+        assert_eq!(col_val_str(b.column(1).as_ref(), 2), "2024-01-03T12:00:00Z");
+    }
+}
+
+#[cfg(test)]
+mod tests_decimal_filter {
+    use super::*;
+    use arrow::array::Decimal128Array;
+    use arrow::datatypes::{Field, Schema};
+    use std::sync::Arc;
+
+    fn batch() -> RecordBatch {
+        // price: 1.05, -2.50, 1234567890123456.78; wide: 1.0500, -2.5000, NULL
+        let schema = Schema::new(vec![
+            Field::new("price", DataType::Decimal128(20, 2), true),
+            Field::new("wide", DataType::Decimal256(40, 4), true),
+        ]);
+        let prices = Decimal128Array::from(vec![105, -250, 123456789012345678])
+            .with_precision_and_scale(20, 2)
+            .unwrap();
+        let wide = Decimal256Array::from(vec![
+            Some(i256::from_i128(10_500)),
+            Some(i256::from_i128(-25_000)),
+            None,
+        ])
+        .with_precision_and_scale(40, 4)
+        .unwrap();
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(prices), Arc::new(wide)]).unwrap()
+    }
+
+    fn matches(expr: &str) -> Vec<bool> {
+        let pred = parse_predicate(expr).unwrap();
+        eval_predicate_batch(&pred, &batch())
+            .iter()
+            .map(|v| v.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn literals_keep_their_scale() {
+        let lit = |s: &str| decimal_literal(&Value::Str(s.into()));
+        assert_eq!(lit("12.340"), Some((i256::from_i128(12340), 3)));
+        assert_eq!(lit("-0.5"), Some((i256::from_i128(-5), 1)));
+        assert_eq!(lit("7"), Some((i256::from_i128(7), 0)));
+        assert_eq!(lit("1.2.3"), None);
+        assert_eq!(lit("abc"), None);
+        let float = decimal_literal(&Value::Float(1.05));
+        assert_eq!(float, Some((i256::from_i128(105), 2)));
+    }
+
+    #[test]
+    fn comparisons_are_exact_across_scales() {
+        assert_eq!(matches("price = 1.05"), [true, false, false]);
+        assert_eq!(matches("price = 1.050"), [true, false, false]);
+        assert_eq!(matches("price > 1.049"), [true, false, true]);
+        assert_eq!(matches("price < -2"), [false, true, false]);
+        assert_eq!(
+            matches("price >= '1234567890123456.78'"),
+            [false, false, true]
+        );
+        assert_eq!(
+            matches("price > '1234567890123456.775'"),
+            [false, false, true]
+        );
+        assert_eq!(matches("price IN (1.05, -2.5)"), [true, true, false]);
+    }
+
+    #[test]
+    fn decimal256_and_nulls() {
+        assert_eq!(matches("wide = 1.05"), [true, false, false]);
+        assert_eq!(matches("wide != 1.05"), [false, true, false]);
+        assert_eq!(matches("wide > 'many'"), [false, false, false]);
+    }
+
+    #[test]
+    fn decimals_render_with_their_scale() {
+        let b = batch();
+        assert_eq!(col_val_str(b.column(0).as_ref(), 1), "-2.50");
+        assert_eq!(col_val_str(b.column(1).as_ref(), 0), "1.0500");
+        assert_eq!(decimal_text(i256::from_i128(-5), 3), "-0.005");
+        assert_eq!(decimal_text(i256::from_i128(12), -2), "1200");
+    }
+}
+
 fn col_val_str(col: &dyn arrow::array::Array, row: usize) -> String {
     if col.is_null(row) {
         return "NULL".into();
@@ -1268,6 +2899,99 @@ fn col_val_str(col: &dyn arrow::array::Array, row: usize) -> String {
             .downcast_ref::<BooleanArray>()
             .map(|a| a.value(row).to_string())
             .unwrap_or_default(),
+        arrow::datatypes::DataType::Decimal128(..) | arrow::datatypes::DataType::Decimal256(..) => {
+            arrow::util::display::array_value_to_string(col, row).unwrap_or_default()
+        }
+        dt if temporal_scale(dt).is_some() => {
+            crate::export::with_offset_zones(&arrow::array::make_array(col.to_data()))
+                .and_then(|a| Ok(arrow::util::display::array_value_to_string(&a, row)?))
+                .unwrap_or_default()
+        }
         _ => format!("{:?}", col.data_type()),
     }
 }
+
+#[cfg(test)]
+mod tests_in_file {
+    use super::*;
+    use arrow::datatypes::{Field, Schema};
+    use std::io::Write;
+
+    fn batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("zip", DataType::Utf8, true),
+            Field::new("score", DataType::Float64, true),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![Some(7), Some(8), None, Some(9)])),
+                Arc::new(StringArray::from(vec![
+                    Some("02134"),
+                    Some("10001"),
+                    Some("Boston"),
+                    None,
+                ])),
+                Arc::new(Float64Array::from(vec![
+                    Some(0.5),
+                    Some(-0.0),
+                    Some(f64::NAN),
+                    None,
+                ])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn matches(pred: &Predicate) -> Vec<Option<bool>> {
+        eval_kleene(pred, &batch()).iter().collect()
+    }
+
+    fn list_file(lines: &str) -> tempfile::NamedTempFile {
+        let mut f = tempfile::NamedTempFile::new().unwrap();
+        f.write_all(lines.as_bytes()).unwrap();
+        f
+    }
+
+    #[test]
+    fn file_lines_convert_to_the_column_type() {
+        let f = list_file("9\n\n  7  \n'02134'\nboston\n");
+        let path = f.path().display();
+        let p = parse_predicate(&format!("id IN @{path}")).unwrap();
+        assert_eq!(matches(&p), [Some(true), Some(false), None, Some(true)]);
+        // leading zeros survive for string columns
+        let p = parse_predicate(&format!("zip IN @'{path}'")).unwrap();
+        assert_eq!(matches(&p), [Some(true), Some(false), Some(false), None]);
+        let p = parse_predicate(&format!("zip NOT IN @{path}"))
+            .unwrap()
+            .ignore_case();
+        assert_eq!(matches(&p), [Some(false), Some(true), Some(false), None]);
+    }
+
+    #[test]
+    fn long_lists_match_the_or_chain() {
+        let vals: Vec<Value> = (0..20_000).map(|i| Value::Int(i * 2 + 1)).collect();
+        let p = Predicate::In {
+            col: "id".into(),
+            vals,
+        };
+        assert_eq!(matches(&p), [Some(true), Some(false), None, Some(true)]);
+        assert_eq!(
+            p.to_string(),
+            "id IN (1, 3, 5, … 20000 values)",
+            "long lists are abbreviated"
+        );
+        let p = parse_predicate("score IN (0.0, 0.5, NULL)").unwrap();
+        assert_eq!(matches(&p), [Some(true), Some(true), None, None]);
+    }
+
+    #[test]
+    fn missing_list_file_is_an_error() {
+        let err = parse_predicate("id IN @/no/such/ids.txt").unwrap_err();
+        assert!(
+            err.starts_with("cannot read IN list /no/such/ids.txt"),
+            "{err}"
+        );
+    }
+}
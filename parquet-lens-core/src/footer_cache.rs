@@ -0,0 +1,193 @@
+use crate::cache::ProfileCache;
+use crate::reader::ParquetFileInfo;
+use crate::storage::{decode_footer, ObjectInfo};
+use bytes::Bytes;
+use parquet::file::metadata::ParquetMetaData;
+use parquet_lens_common::{ParquetLensError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Raw footers of remote objects, stored next to the profile cache as
+/// `footer_<hash>.bin` (so `cache clear` removes them too). An entry younger
+/// than `ttl` is used without contacting the store; an older one only after a
+/// HEAD shows the object's size, ETag and last-modified time are unchanged.
+#[derive(Debug, Clone)]
+pub struct FooterCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FooterHeader {
+    object: ObjectInfo,
+    fetched_at: u64, // unix secs of the last fetch or revalidation
+}
+
+struct CachedFooter {
+    header: FooterHeader,
+    footer: Bytes,
+}
+
+static FOOTER_CACHE: RwLock<Option<FooterCache>> = RwLock::new(None);
+
+/// The footer cache every remote open consults from now on; None disables it.
+pub fn set_footer_cache(cache: Option<FooterCache>) {
+    *FOOTER_CACHE.write().unwrap_or_else(|e| e.into_inner()) = cache;
+}
+
+pub(crate) fn footer_cache() -> Option<FooterCache> {
+    FOOTER_CACHE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+impl FooterCache {
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        Self { dir, ttl }
+    }
+
+    /// `ProfileCache::default_dir()` with the given TTL.
+    pub fn open_default(ttl: Duration) -> Self {
+        Self::new(ProfileCache::default_dir(), ttl)
+    }
+
+    fn path(&self, uri: &str) -> PathBuf {
+        let hash = xxhash_rust::xxh3::xxh3_64(uri.as_bytes());
+        self.dir.join(format!("footer_{hash:016x}.bin"))
+    }
+
+    // a JSON header line, then the footer bytes
+    fn load(&self, uri: &str) -> Option<CachedFooter> {
+        let raw = Bytes::from(std::fs::read(self.path(uri)).ok()?);
+        let split = raw.iter().position(|&b| b == b'\n')?;
+        let header: FooterHeader = serde_json::from_slice(&raw[..split]).ok()?;
+        (header.object.uri == uri).then(|| CachedFooter {
+            header,
+            footer: raw.slice(split + 1..),
+        })
+    }
+
+    /// Whether any entry, fresh or not, exists for `uri`.
+    pub fn contains(&self, uri: &str) -> bool {
+        self.load(uri).is_some()
+    }
+
+    /// The cached footer of `uri` if it is younger than the TTL.
+    pub(crate) fn open_fresh(&self, uri: &str) -> Option<(ParquetFileInfo, Arc<ParquetMetaData>)> {
+        let entry = self.load(uri)?;
+        let age = unix_secs().saturating_sub(entry.header.fetched_at);
+        if age >= self.ttl.as_secs() {
+            return None;
+        }
+        decode_footer(uri, entry.header.object.size, &entry.footer).ok()
+    }
+
+    /// The cached footer of `object` if it still describes the same version of
+    /// it; a hit restarts the entry's TTL. Objects with neither an ETag nor a
+    /// last-modified time can't be revalidated.
+    pub(crate) fn open_validated(
+        &self,
+        object: &ObjectInfo,
+    ) -> Option<(ParquetFileInfo, Arc<ParquetMetaData>)> {
+        if object.etag.is_none() && object.last_modified.is_none() {
+            return None;
+        }
+        let entry = self.load(&object.uri)?;
+        let cached = &entry.header.object;
+        if (cached.size, &cached.etag, &cached.last_modified)
+            != (object.size, &object.etag, &object.last_modified)
+        {
+            return None;
+        }
+        let opened = decode_footer(&object.uri, object.size, &entry.footer).ok()?;
+        let _ = self.store(object, &entry.footer);
+        Some(opened)
+    }
+
+    /// Records `footer` as the current footer of `object`.
+    pub fn store(&self, object: &ObjectInfo, footer: &[u8]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let header = FooterHeader {
+            object: object.clone(),
+            fetched_at: unix_secs(),
+        };
+        let mut raw =
+            serde_json::to_vec(&header).map_err(|e| ParquetLensError::Other(e.to_string()))?;
+        raw.push(b'\n');
+        raw.extend_from_slice(footer);
+        let path = self.path(&object.uri);
+        let tmp = path.with_extension("bin.tmp");
+        std::fs::write(&tmp, raw)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests_footer_cache {
+    use super::*;
+    use crate::storage::footer_range;
+    use arrow::array::Int32Array;
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    fn footer_of(rows: i32) -> (u64, Bytes) {
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int32Array::from_iter_values(0..rows)) as _,
+        )])
+        .unwrap();
+        let mut buf = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let range = footer_range("t", buf.len() as u64, &buf).unwrap();
+        let footer = Bytes::copy_from_slice(&buf[range.start as usize..range.end as usize]);
+        (buf.len() as u64, footer)
+    }
+
+    #[test]
+    fn fresh_entries_skip_validation_and_stale_ones_check_the_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let (size, footer) = footer_of(42);
+        let object = ObjectInfo {
+            uri: "s3://bucket/a.parquet".into(),
+            size,
+            last_modified: Some("2024-01-01T00:00:00+00:00".into()),
+            etag: Some("\"v1\"".into()),
+        };
+        let fresh = FooterCache::new(dir.path().into(), Duration::from_secs(3600));
+        assert!(fresh.open_fresh(&object.uri).is_none());
+        fresh.store(&object, &footer).unwrap();
+        let (info, _) = fresh.open_fresh(&object.uri).unwrap();
+        assert_eq!(info.row_count, 42);
+        assert!(fresh.open_fresh("s3://bucket/b.parquet").is_none());
+
+        // a zero TTL forces revalidation against the object's current version
+        let stale = FooterCache::new(dir.path().into(), Duration::ZERO);
+        assert!(stale.contains(&object.uri) && stale.open_fresh(&object.uri).is_none());
+        assert!(stale.open_validated(&object).is_some());
+        let rewritten = ObjectInfo {
+            etag: Some("\"v2\"".into()),
+            ..object.clone()
+        };
+        assert!(stale.open_validated(&rewritten).is_none());
+        let untagged = ObjectInfo {
+            etag: None,
+            last_modified: None,
+            ..object
+        };
+        assert!(stale.open_validated(&untagged).is_none());
+    }
+}
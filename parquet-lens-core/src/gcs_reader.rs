@@ -1,7 +1,7 @@
-use bytes::Bytes;
+use crate::storage::{GcsBackend, StorageBackend};
 use parquet::file::metadata::ParquetMetaData;
-use parquet::file::reader::{FileReader, SerializedFileReader};
-use parquet_lens_common::{ParquetLensError, Result};
+use parquet_lens_common::Result;
+use std::sync::Arc;
 
 /// parsed gs:// URI
 #[derive(Debug, Clone)]
@@ -23,122 +23,13 @@ pub fn is_gcs_uri(path: &str) -> bool {
     path.starts_with("gs://")
 }
 
-/// list .parquet objects under gs://bucket/prefix via GCS JSON API
-/// uses application default credentials (GOOGLE_APPLICATION_CREDENTIALS or metadata server)
+/// list .parquet objects under gs://bucket/prefix
 pub async fn list_gcs_parquet(uri: &str) -> Result<Vec<String>> {
-    let gcs_uri = parse_gcs_uri(uri)
-        .ok_or_else(|| ParquetLensError::Other(format!("invalid GCS URI: {uri}")))?;
-    let token = get_adc_token(None).await?;
-    let url = format!(
-        "https://storage.googleapis.com/storage/v1/b/{}/o?prefix={}&fields=items/name",
-        gcs_uri.bucket, gcs_uri.object
-    );
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(&url)
-        .bearer_auth(&token)
-        .send()
-        .await
-        .map_err(|e| ParquetLensError::Other(e.to_string()))?
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|e| ParquetLensError::Other(e.to_string()))?;
-    let mut keys = Vec::new();
-    if let Some(items) = resp.get("items").and_then(|v| v.as_array()) {
-        for item in items {
-            if let Some(name) = item.get("name").and_then(|v| v.as_str()) {
-                if name.ends_with(".parquet") {
-                    keys.push(format!("gs://{}/{}", gcs_uri.bucket, name));
-                }
-            }
-        }
-    }
-    Ok(keys)
+    GcsBackend::default().list(uri).await
 }
 
-/// read Parquet metadata from GCS object (uses ADC or metadata server)
-pub async fn read_gcs_parquet_metadata(uri: &str) -> Result<ParquetMetaData> {
-    read_gcs_parquet_metadata_with_creds(uri, None).await
-}
-
-/// read Parquet metadata from GCS object with optional credentials_file path.
-/// If credentials_file is None, falls back to GOOGLE_APPLICATION_CREDENTIALS env var,
-/// then to the GCE metadata server.
-pub async fn read_gcs_parquet_metadata_with_creds(
-    uri: &str,
-    credentials_file: Option<&str>,
-) -> Result<ParquetMetaData> {
-    let bytes = fetch_gcs_bytes(uri, credentials_file).await?;
-    let reader = SerializedFileReader::new(bytes).map_err(ParquetLensError::Parquet)?;
-    Ok(reader.metadata().clone())
-}
-
-async fn fetch_gcs_bytes(uri: &str, credentials_file: Option<&str>) -> Result<Bytes> {
-    let gcs_uri = parse_gcs_uri(uri)
-        .ok_or_else(|| ParquetLensError::Other(format!("invalid GCS URI: {uri}")))?;
-    let token = get_adc_token(credentials_file).await?;
-    let url = format!(
-        "https://storage.googleapis.com/storage/v1/b/{}/o/{}?alt=media",
-        gcs_uri.bucket,
-        urlencoded(&gcs_uri.object)
-    );
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(&url)
-        .bearer_auth(&token)
-        .send()
-        .await
-        .map_err(|e| ParquetLensError::Other(e.to_string()))?;
-    let status = resp.status();
-    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
-        return Err(ParquetLensError::Auth(format!(
-            "GCS returned HTTP {status} for {uri}"
-        )));
-    }
-    let bytes = resp
-        .bytes()
-        .await
-        .map_err(|e| ParquetLensError::Other(e.to_string()))?;
-    Ok(bytes)
-}
-
-/// fetch application default credentials token.
-/// Priority: credentials_file param → GOOGLE_APPLICATION_CREDENTIALS env → GCE metadata server
-async fn get_adc_token(credentials_file: Option<&str>) -> Result<String> {
-    let creds_path = credentials_file
-        .map(|s| s.to_owned())
-        .or_else(|| std::env::var("GOOGLE_APPLICATION_CREDENTIALS").ok());
-    if let Some(path) = creds_path {
-        // credentials file present — read and validate it (SA JSON key)
-        // full JWT-based token exchange is a future improvement; validate path at minimum
-        if !std::path::Path::new(&path).exists() {
-            return Err(ParquetLensError::Auth(format!(
-                "credentials_file not found: {path}"
-            )));
-        }
-        // TODO: implement JWT token exchange from service account key JSON
-        return Err(ParquetLensError::Auth(
-            "service account JSON auth not yet implemented; unset GOOGLE_APPLICATION_CREDENTIALS to use metadata server".into(),
-        ));
-    }
-    // fall back to GCE metadata server
-    let client = reqwest::Client::new();
-    let url = "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
-    let resp = client
-        .get(url)
-        .header("Metadata-Flavor", "Google")
-        .send()
-        .await
-        .map_err(|e| ParquetLensError::Other(format!("ADC token fetch failed: {e}")))?
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|e| ParquetLensError::Other(e.to_string()))?;
-    resp.get("access_token")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_owned())
-        .ok_or_else(|| ParquetLensError::Other("no access_token in ADC response".into()))
-}
-
-fn urlencoded(s: &str) -> String {
-    s.replace('/', "%2F")
+/// read Parquet metadata from a GCS object with range requests; credentials come
+/// from `[gcs] credentials_file`, GOOGLE_APPLICATION_CREDENTIALS or the metadata server
+pub async fn read_gcs_parquet_metadata(uri: &str) -> Result<Arc<ParquetMetaData>> {
+    GcsBackend::default().open(uri).await.map(|(_, meta)| meta)
 }
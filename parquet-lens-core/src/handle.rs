@@ -0,0 +1,134 @@
+use crate::reader::ParquetFileInfo;
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+};
+use parquet::file::metadata::{ParquetMetaData, ParquetMetaDataReader};
+use parquet_lens_common::{ParquetLensError, Result};
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// An opened local Parquet file. The footer is parsed once and shared (cheap to
+/// clone); readers built from the handle reuse it instead of re-reading it.
+#[derive(Clone)]
+pub struct FileHandle {
+    inner: Arc<HandleInner>,
+}
+
+struct HandleInner {
+    path: PathBuf,
+    file_size: u64,
+    modified: Option<SystemTime>,
+    metadata: Arc<ParquetMetaData>,
+}
+
+impl FileHandle {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).map_err(|e| ParquetLensError::io_at(e, path))?;
+        let md = file.metadata()?;
+        let metadata = ParquetMetaDataReader::new()
+            .parse_and_finish(&file)
+            .map_err(|e| ParquetLensError::corrupt(format!("{} footer", path.display()), e))?;
+        Ok(Self {
+            inner: Arc::new(HandleInner {
+                path: path.to_path_buf(),
+                file_size: md.len(),
+                modified: md.modified().ok(),
+                metadata: Arc::new(metadata),
+            }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    pub fn file_size(&self) -> u64 {
+        self.inner.file_size
+    }
+
+    pub fn metadata(&self) -> &Arc<ParquetMetaData> {
+        &self.inner.metadata
+    }
+
+    /// Arrow view of the footer; fails for schemas arrow can't represent.
+    pub fn arrow_metadata(&self) -> Result<ArrowReaderMetadata> {
+        ArrowReaderMetadata::try_new(self.inner.metadata.clone(), ArrowReaderOptions::default())
+            .map_err(ParquetLensError::Parquet)
+    }
+
+    /// Fresh record batch reader builder over the file, reusing the parsed footer.
+    pub fn reader_builder(&self) -> Result<ParquetRecordBatchReaderBuilder<File>> {
+        Ok(ParquetRecordBatchReaderBuilder::new_with_metadata(
+            File::open(&self.inner.path)?,
+            self.arrow_metadata()?,
+        ))
+    }
+
+    pub fn file_info(&self) -> ParquetFileInfo {
+        ParquetFileInfo::from_metadata(
+            self.inner.path.clone(),
+            self.inner.file_size,
+            &self.inner.metadata,
+        )
+    }
+
+    // true when the file on disk no longer matches what was parsed
+    fn is_stale(&self) -> bool {
+        match std::fs::metadata(&self.inner.path) {
+            Ok(md) => md.len() != self.inner.file_size || md.modified().ok() != self.inner.modified,
+            Err(_) => true,
+        }
+    }
+}
+
+// bounds the footers kept alive; oldest handles are dropped first
+const MAX_OPEN_HANDLES: usize = 32;
+
+/// Shares `FileHandle`s between every analysis that touches the same path, so a
+/// single TUI session parses each footer once. Handles are reopened when the
+/// file's size or mtime changes (e.g. under `--watch`).
+#[derive(Default)]
+pub struct HandleRegistry {
+    handles: Mutex<(HashMap<PathBuf, FileHandle>, VecDeque<PathBuf>)>,
+}
+
+impl HandleRegistry {
+    pub fn global() -> &'static HandleRegistry {
+        static REGISTRY: OnceLock<HandleRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(HandleRegistry::default)
+    }
+
+    pub fn get(&self, path: &Path) -> Result<FileHandle> {
+        {
+            let guard = self.handles.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(h) = guard.0.get(path) {
+                if !h.is_stale() {
+                    return Ok(h.clone());
+                }
+            }
+        }
+        // parse outside the lock so slow footers don't serialize unrelated paths
+        let handle = FileHandle::open(path)?;
+        let mut guard = self.handles.lock().unwrap_or_else(|e| e.into_inner());
+        let (map, order) = &mut *guard;
+        if map.insert(path.to_path_buf(), handle.clone()).is_none() {
+            order.push_back(path.to_path_buf());
+        }
+        while order.len() > MAX_OPEN_HANDLES {
+            if let Some(old) = order.pop_front() {
+                map.remove(&old);
+            }
+        }
+        Ok(handle)
+    }
+
+    pub fn invalidate(&self, path: &Path) {
+        let mut guard = self.handles.lock().unwrap_or_else(|e| e.into_inner());
+        let (map, order) = &mut *guard;
+        map.remove(path);
+        order.retain(|p| p != path);
+    }
+}
@@ -0,0 +1,402 @@
+use crate::footer_cache::footer_cache;
+use crate::reader::ParquetFileInfo;
+use crate::retry::{http_client, with_retries};
+use crate::storage::{decode_footer, footer_range, slice_tail, ObjectInfo};
+use bytes::Bytes;
+use parquet::file::metadata::ParquetMetaData;
+use parquet_lens_common::{HdfsConfig, ParquetLensError, Result};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::ops::Range;
+use std::sync::{Arc, RwLock};
+
+// the first footer read fetches this much of the tail, enough for most footers
+const FOOTER_PREFETCH: u64 = 64 * 1024;
+// Hadoop 3's namenode HTTP port (2.x used 50070; set [hdfs] namenode for that)
+const DEFAULT_WEBHDFS_PORT: u16 = 9870;
+
+pub fn is_hdfs_uri(path: &str) -> bool {
+    ["hdfs://", "webhdfs://", "swebhdfs://"]
+        .iter()
+        .any(|p| path.starts_with(p))
+}
+
+static CONFIG: RwLock<Option<HdfsConfig>> = RwLock::new(None);
+// (endpoint, token) fetched for `kerberos = true`, reused for the whole process
+static KERBEROS_TOKEN: RwLock<Option<(String, String)>> = RwLock::new(None);
+
+/// Namenode and credentials every HDFS read uses from now on, usually `Config::hdfs`.
+pub fn set_hdfs_config(config: HdfsConfig) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = Some(config);
+    *KERBEROS_TOKEN.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn config() -> HdfsConfig {
+    CONFIG
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// parsed `hdfs://[host[:port]]/path`; `webhdfs://host:port/path` and
+/// `swebhdfs://` name the WebHDFS endpoint itself
+#[derive(Debug, Clone)]
+pub struct HdfsUri {
+    pub scheme: String,
+    pub authority: String, // may be empty: `hdfs:///path` uses [hdfs] namenode
+    pub path: String,      // absolute, e.g. "/warehouse/events"
+}
+
+impl HdfsUri {
+    // the URI of another path on the same filesystem, in this URI's form
+    fn with_path(&self, path: &str) -> String {
+        format!("{}://{}{path}", self.scheme, self.authority)
+    }
+
+    /// Base URL of the WebHDFS REST API serving this URI.
+    fn endpoint(&self, config: &HdfsConfig) -> Result<String> {
+        if let Some(namenode) = &config.namenode {
+            return Ok(namenode.trim_end_matches('/').to_string());
+        }
+        let host = self
+            .authority
+            .rsplit_once(':')
+            .map_or(self.authority.as_str(), |(h, _)| h);
+        match self.scheme.as_str() {
+            "webhdfs" => Ok(format!("http://{}", self.authority)),
+            "swebhdfs" => Ok(format!("https://{}", self.authority)),
+            _ if host.is_empty() => Err(ParquetLensError::Other(format!(
+                "{}: no namenode host; set [hdfs] namenode",
+                self.with_path(&self.path)
+            ))),
+            // the RPC port in hdfs:// URIs (usually 8020) is not the HTTP one
+            _ => Ok(format!("http://{host}:{DEFAULT_WEBHDFS_PORT}")),
+        }
+    }
+}
+
+pub fn parse_hdfs_uri(uri: &str) -> Option<HdfsUri> {
+    let (scheme, rest) = uri.split_once("://")?;
+    if !matches!(scheme, "hdfs" | "webhdfs" | "swebhdfs") {
+        return None;
+    }
+    let (authority, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, "/"),
+    };
+    Some(HdfsUri {
+        scheme: scheme.to_owned(),
+        authority: authority.to_owned(),
+        path: path.to_owned(),
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileStatus {
+    path_suffix: String,
+    #[serde(rename = "type")]
+    kind: String, // FILE, DIRECTORY or SYMLINK
+    length: u64,
+    modification_time: u64, // unix ms
+}
+
+#[derive(Deserialize)]
+struct FileStatusResponse {
+    #[serde(rename = "FileStatus")]
+    status: FileStatus,
+}
+
+#[derive(Deserialize)]
+struct ListStatusResponse {
+    #[serde(rename = "FileStatuses")]
+    statuses: FileStatuses,
+}
+
+#[derive(Deserialize)]
+struct FileStatuses {
+    #[serde(rename = "FileStatus")]
+    entries: Vec<FileStatus>,
+}
+
+#[derive(Deserialize)]
+struct RemoteExceptionResponse {
+    #[serde(rename = "RemoteException")]
+    remote: RemoteException,
+}
+
+#[derive(Deserialize)]
+struct RemoteException {
+    exception: String, // e.g. FileNotFoundException, AccessControlException
+    message: String,
+}
+
+/// list .parquet files under an HDFS directory, recursively; a file URI lists
+/// just itself. `_`/`.`-prefixed entries (`_SUCCESS`, `_temporary`) are skipped.
+pub async fn list_hdfs_parquet(uri: &str) -> Result<Vec<String>> {
+    let parsed = parse(uri)?;
+    if file_status(uri).await?.kind != "DIRECTORY" {
+        return Ok(vec![uri.to_string()]);
+    }
+    let mut out = Vec::new();
+    let mut dirs = vec![parsed.path.trim_end_matches('/').to_string()];
+    while let Some(dir) = dirs.pop() {
+        let resp = request(&parsed.with_path(&dir), "LISTSTATUS", &[]).await?;
+        let listing: ListStatusResponse = json(uri, resp).await?;
+        for entry in listing.statuses.entries {
+            if entry.path_suffix.starts_with(['_', '.']) {
+                continue;
+            }
+            let child = format!("{dir}/{}", entry.path_suffix);
+            match entry.kind.as_str() {
+                "DIRECTORY" => dirs.push(child),
+                "FILE" if child.ends_with(".parquet") => out.push(parsed.with_path(&child)),
+                _ => {}
+            }
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// File size, last modification (unix seconds) and ETag (always None).
+pub async fn hdfs_object_metadata(uri: &str) -> Result<(u64, Option<String>, Option<String>)> {
+    let status = file_status(uri).await?;
+    if status.kind == "DIRECTORY" {
+        return Err(ParquetLensError::Other(format!(
+            "{uri} is a directory, not a file"
+        )));
+    }
+    Ok((
+        status.length,
+        Some((status.modification_time / 1000).to_string()),
+        None,
+    ))
+}
+
+/// bytes [start, end) of an HDFS file; the namenode redirects to a datanode
+pub async fn read_hdfs_range(uri: &str, range: Range<u64>) -> Result<Bytes> {
+    if range.is_empty() {
+        return Ok(Bytes::new());
+    }
+    let resp = request(
+        uri,
+        "OPEN",
+        &[
+            ("offset", range.start.to_string()),
+            ("length", (range.end - range.start).to_string()),
+        ],
+    )
+    .await?;
+    resp.bytes().await.map_err(|e| http_err(uri, e))
+}
+
+/// read Parquet metadata from an HDFS file
+pub async fn read_hdfs_parquet_metadata(uri: &str) -> Result<Arc<ParquetMetaData>> {
+    open_hdfs_parquet(uri).await.map(|(_, meta)| meta)
+}
+
+/// Footer of an HDFS Parquet file: a GETFILESTATUS, then one read of the
+/// file's tail that usually holds the whole footer. A footer cache hit skips
+/// the read, and a fresh entry the status call too.
+pub(crate) async fn open_hdfs_parquet(
+    uri: &str,
+) -> Result<(ParquetFileInfo, Arc<ParquetMetaData>)> {
+    let cache = footer_cache();
+    if let Some(opened) = cache.as_ref().and_then(|c| c.open_fresh(uri)) {
+        return Ok(opened);
+    }
+    let (size, last_modified, etag) = hdfs_object_metadata(uri).await?;
+    let info = ObjectInfo {
+        uri: uri.to_string(),
+        size,
+        last_modified,
+        etag,
+    };
+    if let Some(opened) = cache.as_ref().and_then(|c| c.open_validated(&info)) {
+        return Ok(opened);
+    }
+    let tail = read_hdfs_range(uri, size.saturating_sub(FOOTER_PREFETCH)..size).await?;
+    let range = footer_range(uri, size, &tail)?;
+    let footer = match slice_tail(&tail, size, &range) {
+        Some(footer) => footer,
+        None => read_hdfs_range(uri, range).await?,
+    };
+    let opened = decode_footer(uri, size, &footer)?;
+    if let Some(cache) = &cache {
+        let _ = cache.store(&info, &footer);
+    }
+    Ok(opened)
+}
+
+fn parse(uri: &str) -> Result<HdfsUri> {
+    parse_hdfs_uri(uri).ok_or_else(|| ParquetLensError::Other(format!("invalid HDFS URI: {uri}")))
+}
+
+async fn file_status(uri: &str) -> Result<FileStatus> {
+    let resp = request(uri, "GETFILESTATUS", &[]).await?;
+    json::<FileStatusResponse>(uri, resp)
+        .await
+        .map(|r| r.status)
+}
+
+async fn json<T: serde::de::DeserializeOwned>(uri: &str, resp: reqwest::Response) -> Result<T> {
+    let body = resp.bytes().await.map_err(|e| http_err(uri, e))?;
+    serde_json::from_slice(&body).map_err(|e| ParquetLensError::corrupt(uri, e))
+}
+
+/// One WebHDFS call on `uri`, authenticated per `[hdfs]`.
+async fn request(uri: &str, op: &str, params: &[(&str, String)]) -> Result<reqwest::Response> {
+    let parsed = parse(uri)?;
+    let config = config();
+    let endpoint = parsed.endpoint(&config)?;
+    let mut url = url::Url::parse(&endpoint)
+        .map_err(|e| ParquetLensError::Other(format!("[hdfs] namenode {endpoint}: {e}")))?;
+    url.path_segments_mut()
+        .map_err(|_| {
+            ParquetLensError::Other(format!("[hdfs] namenode {endpoint}: not a base URL"))
+        })?
+        .pop_if_empty()
+        .extend(["webhdfs", "v1"])
+        .extend(parsed.path.split('/').filter(|s| !s.is_empty()));
+    let token = match (&config.delegation_token, config.kerberos) {
+        (Some(token), _) => Some(token.clone()),
+        (None, true) => Some(kerberos_token(&endpoint).await?),
+        (None, false) => None,
+    };
+    let user = config
+        .user
+        .clone()
+        .or_else(|| std::env::var("HADOOP_USER_NAME").ok());
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("op", op);
+        // a delegation token already names its user
+        if let Some(token) = &token {
+            query.append_pair("delegation", token);
+        } else if let Some(user) = &user {
+            query.append_pair("user.name", user);
+        }
+        for (k, v) in params {
+            query.append_pair(k, v);
+        }
+    }
+    let client = http_client();
+    with_retries(uri, || async {
+        let resp = client
+            .get(url.clone())
+            .send()
+            .await
+            .map_err(|e| http_err(uri, e))?;
+        if resp.status().is_success() {
+            return Ok(resp);
+        }
+        let status = resp.status();
+        let remote = resp
+            .bytes()
+            .await
+            .ok()
+            .and_then(|b| serde_json::from_slice::<RemoteExceptionResponse>(&b).ok())
+            .map(|r| r.remote);
+        Err(status_err(uri, status, remote))
+    })
+    .await
+}
+
+fn status_err(uri: &str, status: StatusCode, remote: Option<RemoteException>) -> ParquetLensError {
+    let exception = remote.as_ref().map_or("", |r| r.exception.as_str());
+    let detail = match &remote {
+        Some(r) => format!("HTTP {status} for {uri}: {}: {}", r.exception, r.message),
+        None => format!("HTTP {status} for {uri}"),
+    };
+    match (status, exception) {
+        (StatusCode::NOT_FOUND, _) | (_, "FileNotFoundException") => {
+            ParquetLensError::NotFound(uri.to_string())
+        }
+        (_, "AccessControlException") => ParquetLensError::PermissionDenied(uri.to_string()),
+        (_, "StandbyException") => ParquetLensError::Other(format!(
+            "{detail} (point [hdfs] namenode at the active namenode)"
+        )),
+        (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN, _) => ParquetLensError::Auth(detail),
+        (s, _) if s == StatusCode::TOO_MANY_REQUESTS || s.is_server_error() => {
+            ParquetLensError::Throttled(detail)
+        }
+        _ => ParquetLensError::Other(detail),
+    }
+}
+
+/// A delegation token from the namenode via `curl --negotiate`, which does the
+/// SPNEGO handshake with the caller's Kerberos ticket.
+async fn kerberos_token(endpoint: &str) -> Result<String> {
+    if let Some((cached_for, token)) = KERBEROS_TOKEN
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+    {
+        if cached_for == endpoint {
+            return Ok(token);
+        }
+    }
+    let url = format!("{endpoint}/webhdfs/v1/?op=GETDELEGATIONTOKEN");
+    let out = tokio::process::Command::new("curl")
+        .args(["-sSf", "--negotiate", "-u", ":", &url])
+        .output()
+        .await
+        .map_err(|e| ParquetLensError::Auth(format!("[hdfs] kerberos needs curl: {e}")))?;
+    if !out.status.success() {
+        return Err(ParquetLensError::Auth(format!(
+            "curl --negotiate {url} failed (is there a kinit ticket?): {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        )));
+    }
+    #[derive(Deserialize)]
+    struct TokenResponse {
+        #[serde(rename = "Token")]
+        token: Token,
+    }
+    #[derive(Deserialize)]
+    struct Token {
+        #[serde(rename = "urlString")]
+        url_string: String,
+    }
+    let token = serde_json::from_slice::<TokenResponse>(&out.stdout)
+        .map_err(|e| ParquetLensError::Auth(format!("{url}: unexpected response: {e}")))?
+        .token
+        .url_string;
+    *KERBEROS_TOKEN.write().unwrap_or_else(|e| e.into_inner()) =
+        Some((endpoint.to_string(), token.clone()));
+    Ok(token)
+}
+
+fn http_err(uri: &str, e: reqwest::Error) -> ParquetLensError {
+    if e.is_timeout() {
+        return ParquetLensError::Throttled(format!("{uri}: {e}"));
+    }
+    ParquetLensError::Other(format!("{uri}: {e}"))
+}
+
+#[cfg(test)]
+mod tests_hdfs_uri {
+    use super::*;
+
+    #[test]
+    fn endpoints_follow_scheme_and_config() {
+        let uri = parse_hdfs_uri("hdfs://nn.corp:8020/warehouse/events").unwrap();
+        assert_eq!(uri.path, "/warehouse/events");
+        let defaults = HdfsConfig::default();
+        assert_eq!(uri.endpoint(&defaults).unwrap(), "http://nn.corp:9870");
+        let pinned = HdfsConfig {
+            namenode: Some("https://gateway/".into()),
+            ..Default::default()
+        };
+        assert_eq!(uri.endpoint(&pinned).unwrap(), "https://gateway");
+        assert_eq!(uri.with_path("/x.parquet"), "hdfs://nn.corp:8020/x.parquet");
+
+        let web = parse_hdfs_uri("swebhdfs://nn:9871/t").unwrap();
+        assert_eq!(web.endpoint(&defaults).unwrap(), "https://nn:9871");
+        let bare = parse_hdfs_uri("hdfs:///t/a.parquet").unwrap();
+        assert!(bare.endpoint(&defaults).is_err());
+        assert!(parse_hdfs_uri("s3://b/k").is_none());
+    }
+}
@@ -0,0 +1,98 @@
+use crate::baseline::BaselineRegression;
+use crate::report::ProfileReport;
+use parquet_lens_common::{ParquetLensError, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// One profiling run of a dataset, as kept in the history store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub dataset: String,
+    pub captured_at: u64, // unix timestamp secs
+    pub total_rows: i64,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub quality_score: u8,
+    pub null_cell_pct: f64,
+    pub regressions: Vec<BaselineRegression>,
+}
+
+impl HistoryEntry {
+    pub fn from_report(dataset: &str, captured_at: u64, report: &ProfileReport) -> Self {
+        Self {
+            dataset: dataset.to_string(),
+            captured_at,
+            total_rows: report.dataset.total_rows,
+            file_count: report.dataset.file_count,
+            total_bytes: report.dataset.total_bytes,
+            quality_score: report.quality.overall_score,
+            null_cell_pct: report.quality.total_null_cell_pct,
+            regressions: report.baseline_regressions.clone(),
+        }
+    }
+}
+
+/// Append-only JSON Lines log of profiling runs, by default
+/// `<data dir>/parquet-lens/history.jsonl`. Unlike the profile cache it is
+/// never invalidated, so trends survive file rewrites and upgrades.
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("parquet-lens")
+            .join("history.jsonl")
+    }
+
+    pub fn open_default() -> Self {
+        Self::new(Self::default_path())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entry: &HistoryEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let line =
+            serde_json::to_string(entry).map_err(|e| ParquetLensError::Other(e.to_string()))?;
+        // one write per line keeps concurrent appenders from interleaving
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(format!("{line}\n").as_bytes())?;
+        Ok(())
+    }
+
+    /// Entries in the order they were recorded, optionally for one dataset.
+    /// Unparseable lines (e.g. a torn final write) are skipped.
+    pub fn entries(&self, dataset: Option<&str>) -> Result<Vec<HistoryEntry>> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut out = Vec::new();
+        for line in std::io::BufReader::new(file).lines() {
+            let Ok(entry) = serde_json::from_str::<HistoryEntry>(&line?) else {
+                continue;
+            };
+            if dataset.is_none_or(|d| d == entry.dataset) {
+                out.push(entry);
+            }
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,246 @@
+use crate::footer_cache::footer_cache;
+use crate::reader::ParquetFileInfo;
+use crate::retry::{http_client, with_retries};
+use crate::storage::{decode_footer, footer_range, slice_tail, ObjectInfo};
+use bytes::Bytes;
+use parquet::file::metadata::ParquetMetaData;
+use parquet_lens_common::{HttpConfig, ParquetLensError, Result};
+use reqwest::header::{HeaderMap, CONTENT_LENGTH, CONTENT_RANGE, ETAG, LAST_MODIFIED, RANGE};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::ops::Range;
+use std::sync::{Arc, RwLock};
+
+// the first request asks for this much of the tail, enough for most footers
+const FOOTER_PREFETCH: u64 = 64 * 1024;
+
+pub fn is_http_uri(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+/// Whether `uri` is a pre-signed S3 or GCS URL, i.e. carries its own
+/// signature in the query string. These only allow the signed method (GET),
+/// so their size is probed with a one-byte range read instead of a HEAD.
+pub fn is_presigned_url(uri: &str) -> bool {
+    let Some(url) = url::Url::parse(uri)
+        .ok()
+        .filter(|u| is_http_uri(u.as_str()))
+    else {
+        return false;
+    };
+    let names: Vec<String> = url
+        .query_pairs()
+        .map(|(k, _)| k.to_ascii_lowercase())
+        .collect();
+    let has = |name: &str| names.iter().any(|n| n == name);
+    // SigV4 / GCS V4, then the older SigV2 / GCS V2 forms
+    has("x-amz-signature")
+        || has("x-goog-signature")
+        || (has("signature") && (has("awsaccesskeyid") || has("googleaccessid")))
+}
+
+static CONFIG: RwLock<Option<HttpConfig>> = RwLock::new(None);
+
+/// Settings every HTTP read uses from now on, usually `Config::http`.
+pub fn set_http_config(config: HttpConfig) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = Some(config);
+}
+
+fn full_download_allowed() -> bool {
+    CONFIG
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .is_none_or(|c| c.full_download)
+}
+
+/// read Parquet metadata from an HTTP(S) URL, via range requests when the
+/// server supports them
+pub async fn read_http_parquet_metadata(uri: &str) -> Result<Arc<ParquetMetaData>> {
+    open_http_parquet(uri).await.map(|(_, meta)| meta)
+}
+
+/// Object size, Last-Modified and ETag from a HEAD request, or for a
+/// pre-signed URL from a GET of its first byte.
+pub async fn http_object_metadata(uri: &str) -> Result<(u64, Option<String>, Option<String>)> {
+    let client = http_client();
+    let presigned = is_presigned_url(uri);
+    let resp = send(uri, || match presigned {
+        true => client.get(uri).header(RANGE, "bytes=0-0"),
+        false => client.head(uri),
+    })
+    .await?;
+    let headers = resp.headers();
+    // `Content-Range: bytes 0-0/2000`; a 200 (range ignored) has the full length
+    let total = match resp.status() {
+        StatusCode::PARTIAL_CONTENT => header_str(headers, CONTENT_RANGE.as_str())
+            .and_then(|v| v.rsplit_once('/').and_then(|(_, t)| t.parse().ok())),
+        _ => header_str(headers, CONTENT_LENGTH.as_str()).and_then(|v| v.parse().ok()),
+    };
+    let size = total
+        .ok_or_else(|| ParquetLensError::Other(format!("{uri}: server sent no Content-Length")))?;
+    Ok((
+        size,
+        header_str(headers, LAST_MODIFIED.as_str()),
+        header_str(headers, ETAG.as_str()),
+    ))
+}
+
+/// bytes [start, end) of an HTTP(S) resource via a Range request; a server
+/// that ignores the range costs a full download, if `[http] full_download` allows it
+pub async fn read_http_range(uri: &str, range: Range<u64>) -> Result<Bytes> {
+    if range.is_empty() {
+        return Ok(Bytes::new());
+    }
+    let client = http_client();
+    let resp = send(uri, || {
+        client
+            .get(uri)
+            .header(RANGE, format!("bytes={}-{}", range.start, range.end - 1))
+    })
+    .await?;
+    if resp.status() == StatusCode::PARTIAL_CONTENT {
+        return resp.bytes().await.map_err(|e| http_err(uri, e));
+    }
+    let body = full_body(uri, resp).await?;
+    let end = (range.end as usize).min(body.len());
+    Ok(body.slice((range.start as usize).min(end)..end))
+}
+
+/// Footer of an HTTP(S) Parquet file. One suffix range request usually returns
+/// the whole footer; a 200 response means the server ignored the range, and the
+/// footer is cut from the full body instead. A stale footer cache entry costs a
+/// HEAD to revalidate.
+pub(crate) async fn open_http_parquet(
+    uri: &str,
+) -> Result<(ParquetFileInfo, Arc<ParquetMetaData>)> {
+    let cache = footer_cache();
+    if let Some(opened) = cache.as_ref().and_then(|c| c.open_fresh(uri)) {
+        return Ok(opened);
+    }
+    if let Some(cache) = cache.as_ref().filter(|c| c.contains(uri)) {
+        let (size, last_modified, etag) = http_object_metadata(uri).await?;
+        let info = ObjectInfo {
+            uri: uri.to_string(),
+            size,
+            last_modified,
+            etag,
+        };
+        if let Some(opened) = cache.open_validated(&info) {
+            return Ok(opened);
+        }
+    }
+    let (info, footer) = fetch_http_footer(uri).await?;
+    let opened = decode_footer(uri, info.size, &footer)?;
+    if let Some(cache) = &cache {
+        let _ = cache.store(&info, &footer);
+    }
+    Ok(opened)
+}
+
+// the object's size and version tags, plus its raw footer bytes
+async fn fetch_http_footer(uri: &str) -> Result<(ObjectInfo, Bytes)> {
+    let client = http_client();
+    let resp = send(uri, || {
+        client
+            .get(uri)
+            .header(RANGE, format!("bytes=-{FOOTER_PREFETCH}"))
+    })
+    .await?;
+    let headers = resp.headers();
+    let (last_modified, etag) = (
+        header_str(headers, LAST_MODIFIED.as_str()),
+        header_str(headers, ETAG.as_str()),
+    );
+    let object = |size| ObjectInfo {
+        uri: uri.to_string(),
+        size,
+        last_modified: last_modified.clone(),
+        etag: etag.clone(),
+    };
+    if resp.status() != StatusCode::PARTIAL_CONTENT {
+        let body = full_body(uri, resp).await?;
+        let size = body.len() as u64;
+        let range = footer_range(uri, size, &body)?;
+        let footer = body.slice(range.start as usize..range.end as usize);
+        return Ok((object(size), footer));
+    }
+    // `Content-Range: bytes 1000-1999/2000`; an unknown total (`*`) needs a HEAD
+    let total = header_str(headers, CONTENT_RANGE.as_str())
+        .and_then(|v| v.rsplit_once('/').and_then(|(_, t)| t.parse::<u64>().ok()));
+    let tail = resp.bytes().await.map_err(|e| http_err(uri, e))?;
+    let size = match total {
+        Some(size) => size,
+        None => http_object_metadata(uri).await?.0,
+    };
+    let range = footer_range(uri, size, &tail)?;
+    let footer = match slice_tail(&tail, size, &range) {
+        Some(footer) => footer,
+        None => read_http_range(uri, range).await?,
+    };
+    Ok((object(size), footer))
+}
+
+async fn full_body(uri: &str, resp: reqwest::Response) -> Result<Bytes> {
+    if !full_download_allowed() {
+        return Err(ParquetLensError::Unsupported {
+            feature: format!(
+                "{uri}: server ignores range requests and [http] full_download is off"
+            ),
+        });
+    }
+    tracing::info!("{uri}: server ignored the range request; downloading the whole file");
+    resp.bytes().await.map_err(|e| http_err(uri, e))
+}
+
+fn header_str(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_owned())
+}
+
+// sends the request `build` makes, retrying transient failures per `[retry]`
+async fn send(uri: &str, build: impl Fn() -> RequestBuilder) -> Result<Response> {
+    with_retries(uri, || async {
+        let resp = build().send().await.map_err(|e| http_err(uri, e))?;
+        check_status(uri, resp.status())?;
+        Ok(resp)
+    })
+    .await
+}
+
+fn check_status(uri: &str, status: StatusCode) -> Result<()> {
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        let hint = match is_presigned_url(uri) {
+            true => " (the pre-signed URL may have expired)",
+            false => "",
+        };
+        return Err(ParquetLensError::Auth(format!(
+            "HTTP {status} for {uri}{hint}"
+        )));
+    }
+    if status == StatusCode::NOT_FOUND || status == StatusCode::GONE {
+        return Err(ParquetLensError::NotFound(uri.to_string()));
+    }
+    if status == StatusCode::NOT_IMPLEMENTED {
+        return Err(ParquetLensError::Unsupported {
+            feature: format!("HTTP {status} for {uri}"),
+        });
+    }
+    if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        return Err(ParquetLensError::Throttled(format!(
+            "HTTP {status} for {uri}"
+        )));
+    }
+    if !status.is_success() {
+        return Err(ParquetLensError::Other(format!("HTTP {status} for {uri}")));
+    }
+    Ok(())
+}
+
+fn http_err(uri: &str, e: reqwest::Error) -> ParquetLensError {
+    if e.is_timeout() {
+        return ParquetLensError::Throttled(format!("{uri}: {e}"));
+    }
+    ParquetLensError::Other(format!("{uri}: {e}"))
+}
@@ -0,0 +1,814 @@
+use crate::profile::temporal::format_date;
+use crate::scanner::ParquetFilePath;
+use crate::storage::StorageRegistry;
+use apache_avro::types::Value as AvroValue;
+use bytes::Bytes;
+use parquet_lens_common::{ParquetLensError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// The current state of an Apache Iceberg table: its metadata file plus the
+/// live data files of the current snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IcebergTable {
+    pub location: String,
+    pub metadata_file: String,
+    pub format_version: u32,
+    pub table_uuid: Option<String>,
+    pub snapshot: Option<IcebergSnapshot>, // None until the first commit
+    pub partition_spec: Vec<IcebergPartitionField>, // the default spec; empty when unpartitioned
+    pub schema_id: i32,
+    pub schema_history: Vec<IcebergSchemaVersion>, // oldest first
+    pub data_files: Vec<ParquetFilePath>, // partitions carry the manifest's partition values
+    pub delete_files: usize,              // position/equality deletes, which are not applied
+    pub skipped_files: usize,             // ORC and Avro data files
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IcebergSnapshot {
+    pub snapshot_id: i64,
+    pub parent_id: Option<i64>,
+    pub timestamp_ms: i64,
+    pub operation: Option<String>, // append, overwrite, delete or replace
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IcebergPartitionField {
+    pub name: String,
+    pub source_column: String,
+    pub transform: String, // identity, day, bucket[16], truncate[4], ...
+}
+
+/// One entry of the table's `schemas` list and how it differs from the one before.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IcebergSchemaVersion {
+    pub schema_id: i32,
+    pub columns: usize,       // leaf and nested fields alike
+    pub changes: Vec<String>, // e.g. "renamed name to full_name"; empty for the first schema
+}
+
+impl IcebergPartitionField {
+    /// `day(ts)`, or just the column name for identity partitions.
+    pub fn describe(&self) -> String {
+        match self.transform.as_str() {
+            "identity" => self.source_column.clone(),
+            t => format!("{t}({})", self.source_column),
+        }
+    }
+}
+
+impl IcebergTable {
+    /// `label: value` lines for summaries, aligned like `print_summary`.
+    pub fn lines(&self) -> Vec<String> {
+        let mut out = vec![format!(
+            "{:<16} {} (format v{})",
+            "Iceberg table:", self.location, self.format_version
+        )];
+        let snapshot = match &self.snapshot {
+            Some(s) => format!(
+                "{} ({}, {})",
+                s.snapshot_id,
+                s.operation.as_deref().unwrap_or("unknown operation"),
+                format_timestamp_ms(s.timestamp_ms)
+            ),
+            None => "none (empty table)".into(),
+        };
+        out.push(format!("{:<16} {snapshot}", "Snapshot:"));
+        let spec = if self.partition_spec.is_empty() {
+            "unpartitioned".to_string()
+        } else {
+            self.partition_spec
+                .iter()
+                .map(|f| f.describe())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        out.push(format!("{:<16} {spec}", "Partitioning:"));
+        out.push(format!(
+            "{:<16} id {} ({} version{})",
+            "Schema:",
+            self.schema_id,
+            self.schema_history.len(),
+            if self.schema_history.len() == 1 {
+                ""
+            } else {
+                "s"
+            }
+        ));
+        for v in self.schema_history.iter().filter(|v| !v.changes.is_empty()) {
+            out.push(format!(
+                "{:<16} schema {}: {}",
+                "",
+                v.schema_id,
+                v.changes.join("; ")
+            ));
+        }
+        if self.delete_files > 0 {
+            out.push(format!(
+                "{:<16} {} not applied; row counts include deleted rows",
+                "Delete files:", self.delete_files
+            ));
+        }
+        if self.skipped_files > 0 {
+            out.push(format!(
+                "{:<16} {} non-Parquet data files left out",
+                "Skipped:", self.skipped_files
+            ));
+        }
+        out
+    }
+}
+
+/// The Iceberg table at `location` (a table directory or a `*.metadata.json`
+/// file), or None when it isn't one. Remote tables need a
+/// `metadata/version-hint.text`, or the metadata file's URI.
+pub async fn open_iceberg_table(location: &str) -> Result<Option<IcebergTable>> {
+    match find_metadata_file(location).await? {
+        Some(metadata_file) => load_iceberg_table(&metadata_file).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Reads a table's metadata file and the manifests of its current snapshot.
+pub async fn load_iceberg_table(metadata_file: &str) -> Result<IcebergTable> {
+    let raw = read_object(metadata_file).await?;
+    let meta: TableMetadata =
+        serde_json::from_slice(&raw).map_err(|e| ParquetLensError::corrupt(metadata_file, e))?;
+
+    let mut schemas = meta.schemas.clone();
+    if schemas.is_empty() {
+        schemas.extend(meta.schema.clone()); // v1 tables may carry only `schema`
+    }
+    schemas.sort_by_key(|s| s.schema_id);
+    let schema_id = meta
+        .current_schema_id
+        .or_else(|| schemas.last().map(|s| s.schema_id))
+        .unwrap_or(0);
+    let current_fields = schemas
+        .iter()
+        .find(|s| s.schema_id == schema_id)
+        .map(|s| flatten_fields(&s.fields))
+        .unwrap_or_default();
+
+    let mut specs: HashMap<i32, Vec<IcebergPartitionField>> = meta
+        .partition_specs
+        .iter()
+        .map(|s| (s.spec_id, partition_fields(&s.fields, &current_fields)))
+        .collect();
+    if let Some(fields) = &meta.partition_spec {
+        specs
+            .entry(0)
+            .or_insert_with(|| partition_fields(fields, &current_fields));
+    }
+    let default_spec_id = meta.default_spec_id.unwrap_or(0);
+
+    let snapshot = meta
+        .current_snapshot_id
+        .filter(|&id| id != -1)
+        .and_then(|id| meta.snapshots.iter().find(|s| s.snapshot_id == id));
+    let mut files = LiveFiles::default();
+    if let Some(s) = snapshot {
+        let manifests = match &s.manifest_list {
+            Some(list) => read_manifest_list(list).await?,
+            None => s.manifests.iter().map(|p| (p.clone(), None, 0)).collect(),
+        };
+        for (path, spec_id, content) in manifests {
+            let spec = specs
+                .get(&spec_id.unwrap_or(default_spec_id))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            files.read_manifest(&path, content, spec).await?;
+        }
+    }
+
+    Ok(IcebergTable {
+        location: meta.location.clone(),
+        metadata_file: metadata_file.to_string(),
+        format_version: meta.format_version,
+        table_uuid: meta.table_uuid.clone(),
+        snapshot: snapshot.map(|s| IcebergSnapshot {
+            snapshot_id: s.snapshot_id,
+            parent_id: s.parent_snapshot_id,
+            timestamp_ms: s.timestamp_ms,
+            operation: s.summary.get("operation").cloned(),
+        }),
+        partition_spec: specs.remove(&default_spec_id).unwrap_or_default(),
+        schema_id,
+        schema_history: schema_history(&schemas),
+        data_files: files.data,
+        delete_files: files.deletes,
+        skipped_files: files.skipped,
+    })
+}
+
+// metadata.json, as much of it as is shown; v1 and v2 layouts both parse
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct TableMetadata {
+    format_version: u32,
+    table_uuid: Option<String>,
+    location: String,
+    #[serde(default)]
+    schemas: Vec<SchemaJson>,
+    schema: Option<SchemaJson>,
+    current_schema_id: Option<i32>,
+    #[serde(default)]
+    partition_specs: Vec<SpecJson>,
+    partition_spec: Option<Vec<SpecFieldJson>>,
+    default_spec_id: Option<i32>,
+    current_snapshot_id: Option<i64>,
+    #[serde(default)]
+    snapshots: Vec<SnapshotJson>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SchemaJson {
+    #[serde(default)]
+    schema_id: i32,
+    fields: Vec<FieldJson>,
+}
+
+#[derive(Clone, Deserialize)]
+struct FieldJson {
+    id: i32,
+    name: String,
+    required: bool,
+    #[serde(rename = "type")]
+    ty: serde_json::Value, // a primitive name, or a struct/list/map object
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SpecJson {
+    spec_id: i32,
+    fields: Vec<SpecFieldJson>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SpecFieldJson {
+    name: String,
+    transform: String,
+    source_id: i32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SnapshotJson {
+    snapshot_id: i64,
+    parent_snapshot_id: Option<i64>,
+    timestamp_ms: i64,
+    manifest_list: Option<String>,
+    #[serde(default)]
+    manifests: Vec<String>, // v1 snapshots without a manifest list
+    #[serde(default)]
+    summary: HashMap<String, String>,
+}
+
+// a field of some schema, by field id; nested names are dotted
+#[derive(Clone, PartialEq)]
+struct FlatField {
+    name: String,
+    ty: String,
+    required: bool,
+}
+
+fn flatten_fields(fields: &[FieldJson]) -> BTreeMap<i32, FlatField> {
+    fn walk(prefix: &str, fields: &[FieldJson], out: &mut BTreeMap<i32, FlatField>) {
+        for f in fields {
+            let name = if prefix.is_empty() {
+                f.name.clone()
+            } else {
+                format!("{prefix}.{}", f.name)
+            };
+            out.insert(
+                f.id,
+                FlatField {
+                    name: name.clone(),
+                    ty: type_name(&f.ty),
+                    required: f.required,
+                },
+            );
+            let nested =
+                f.ty.get("fields")
+                    .and_then(|v| serde_json::from_value::<Vec<FieldJson>>(v.clone()).ok());
+            if let Some(nested) = nested {
+                walk(&name, &nested, out);
+            }
+        }
+    }
+    let mut out = BTreeMap::new();
+    walk("", fields, &mut out);
+    out
+}
+
+fn type_name(ty: &serde_json::Value) -> String {
+    if let Some(s) = ty.as_str() {
+        return s.to_string();
+    }
+    match ty.get("type").and_then(|t| t.as_str()) {
+        Some("list") => format!("list<{}>", type_name(&ty["element"])),
+        Some("map") => format!(
+            "map<{}, {}>",
+            type_name(&ty["key"]),
+            type_name(&ty["value"])
+        ),
+        Some(other) => other.to_string(),
+        None => "unknown".into(),
+    }
+}
+
+fn partition_fields(
+    fields: &[SpecFieldJson],
+    columns: &BTreeMap<i32, FlatField>,
+) -> Vec<IcebergPartitionField> {
+    fields
+        .iter()
+        .map(|f| IcebergPartitionField {
+            name: f.name.clone(),
+            source_column: columns
+                .get(&f.source_id)
+                .map_or_else(|| format!("field {}", f.source_id), |c| c.name.clone()),
+            transform: f.transform.clone(),
+        })
+        .collect()
+}
+
+fn schema_history(schemas: &[SchemaJson]) -> Vec<IcebergSchemaVersion> {
+    let mut history = Vec::new();
+    let mut previous: Option<BTreeMap<i32, FlatField>> = None;
+    for s in schemas {
+        let fields = flatten_fields(&s.fields);
+        let changes = previous
+            .as_ref()
+            .map(|prev| schema_changes(prev, &fields))
+            .unwrap_or_default();
+        history.push(IcebergSchemaVersion {
+            schema_id: s.schema_id,
+            columns: fields.len(),
+            changes,
+        });
+        previous = Some(fields);
+    }
+    history
+}
+
+// fields are matched by id, so a rename is not an add plus a drop
+fn schema_changes(prev: &BTreeMap<i32, FlatField>, next: &BTreeMap<i32, FlatField>) -> Vec<String> {
+    let mut changes = Vec::new();
+    for (id, new) in next {
+        let Some(old) = prev.get(id) else {
+            changes.push(format!("added {} {}", new.name, new.ty));
+            continue;
+        };
+        if old.name != new.name {
+            changes.push(format!("renamed {} to {}", old.name, new.name));
+        }
+        if old.ty != new.ty {
+            changes.push(format!("{} {} -> {}", new.name, old.ty, new.ty));
+        }
+        if old.required != new.required {
+            let now = if new.required { "required" } else { "optional" };
+            changes.push(format!("{} made {now}", new.name));
+        }
+    }
+    for (id, old) in prev {
+        if !next.contains_key(id) {
+            changes.push(format!("dropped {}", old.name));
+        }
+    }
+    changes
+}
+
+/// `(manifest path, partition spec id, content)` per manifest in a manifest
+/// list; content 1 marks delete manifests (v2).
+async fn read_manifest_list(uri: &str) -> Result<Vec<(String, Option<i32>, i32)>> {
+    let bytes = read_object(uri).await?;
+    let mut out = Vec::new();
+    for record in avro_records(uri, &bytes)? {
+        let path = field(&record, "manifest_path")
+            .and_then(as_str)
+            .ok_or_else(|| ParquetLensError::corrupt(uri, "manifest entry without a path"))?;
+        let spec_id = field(&record, "partition_spec_id")
+            .and_then(as_i64)
+            .map(|v| v as i32);
+        let content = field(&record, "content").and_then(as_i64).unwrap_or(0) as i32;
+        out.push((path.to_string(), spec_id, content));
+    }
+    Ok(out)
+}
+
+#[derive(Default)]
+struct LiveFiles {
+    data: Vec<ParquetFilePath>,
+    deletes: usize,
+    skipped: usize,
+}
+
+impl LiveFiles {
+    async fn read_manifest(
+        &mut self,
+        uri: &str,
+        content: i32,
+        spec: &[IcebergPartitionField],
+    ) -> Result<()> {
+        let bytes = read_object(uri).await?;
+        for entry in avro_records(uri, &bytes)? {
+            // status 2 = deleted by this snapshot
+            if field(&entry, "status").and_then(as_i64) == Some(2) {
+                continue;
+            }
+            let Some(file) = field(&entry, "data_file") else {
+                continue;
+            };
+            let file_content = field(file, "content").and_then(as_i64).unwrap_or(0);
+            if content != 0 || file_content != 0 {
+                self.deletes += 1;
+                continue;
+            }
+            let format = field(file, "file_format").and_then(as_str).unwrap_or("");
+            if !format.eq_ignore_ascii_case("parquet") {
+                self.skipped += 1;
+                continue;
+            }
+            let Some(path) = field(file, "file_path").and_then(as_str) else {
+                return Err(ParquetLensError::corrupt(uri, "data file without a path"));
+            };
+            let partitions = field(file, "partition")
+                .map(|p| partition_values(p, spec))
+                .unwrap_or_default();
+            self.data.push(ParquetFilePath {
+                path: PathBuf::from(local_or_uri(path)),
+                partitions,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn avro_records(uri: &str, bytes: &[u8]) -> Result<Vec<AvroValue>> {
+    let corrupt = |e: apache_avro::Error| ParquetLensError::corrupt(uri, e);
+    let reader = apache_avro::Reader::new(bytes).map_err(corrupt)?;
+    reader.map(|r| r.map_err(corrupt)).collect()
+}
+
+// a record field, looking through `["null", T]` unions
+fn field<'a>(value: &'a AvroValue, name: &str) -> Option<&'a AvroValue> {
+    let AvroValue::Record(fields) = unwrap_union(value) else {
+        return None;
+    };
+    fields
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| unwrap_union(v))
+}
+
+fn unwrap_union(value: &AvroValue) -> &AvroValue {
+    match value {
+        AvroValue::Union(_, inner) => inner,
+        v => v,
+    }
+}
+
+fn as_str(value: &AvroValue) -> Option<&str> {
+    match value {
+        AvroValue::String(s) | AvroValue::Enum(_, s) => Some(s),
+        _ => None,
+    }
+}
+
+fn as_i64(value: &AvroValue) -> Option<i64> {
+    match value {
+        AvroValue::Int(v) | AvroValue::Date(v) => Some(*v as i64),
+        AvroValue::Long(v) => Some(*v),
+        _ => None,
+    }
+}
+
+/// `name=value` pairs of a data file's partition tuple, with date transforms
+/// rendered as dates.
+fn partition_values(
+    partition: &AvroValue,
+    spec: &[IcebergPartitionField],
+) -> HashMap<String, String> {
+    let AvroValue::Record(values) = partition else {
+        return HashMap::new();
+    };
+    values
+        .iter()
+        .map(|(name, value)| {
+            let transform = spec
+                .iter()
+                .find(|f| &f.name == name)
+                .map_or("identity", |f| f.transform.as_str());
+            (
+                name.clone(),
+                render_partition_value(unwrap_union(value), transform),
+            )
+        })
+        .collect()
+}
+
+fn render_partition_value(value: &AvroValue, transform: &str) -> String {
+    if let (Some(n), "year" | "month" | "day" | "hour") = (as_i64(value), transform) {
+        return match transform {
+            "year" => format!("{}", 1970 + n),
+            "month" => format!("{}-{:02}", 1970 + n.div_euclid(12), n.rem_euclid(12) + 1),
+            "day" => format_date(n),
+            _ => format!("{}-{:02}", format_date(n.div_euclid(24)), n.rem_euclid(24)),
+        };
+    }
+    match value {
+        AvroValue::Null => "null".into(),
+        AvroValue::Boolean(b) => b.to_string(),
+        AvroValue::Int(v) => v.to_string(),
+        AvroValue::Long(v) => v.to_string(),
+        AvroValue::Float(v) => v.to_string(),
+        AvroValue::Double(v) => v.to_string(),
+        AvroValue::String(s) | AvroValue::Enum(_, s) => s.clone(),
+        AvroValue::Date(d) => format_date(*d as i64),
+        AvroValue::Uuid(u) => u.to_string(),
+        AvroValue::Bytes(b) | AvroValue::Fixed(_, b) => {
+            b.iter().map(|x| format!("{x:02x}")).collect()
+        }
+        other => format!("{other:?}"),
+    }
+}
+
+fn format_timestamp_ms(ms: i64) -> String {
+    let secs = ms.div_euclid(1000);
+    let t = secs.rem_euclid(86400);
+    format!(
+        "{} {:02}:{:02}:{:02} UTC",
+        format_date(secs.div_euclid(86400)),
+        t / 3600,
+        t % 3600 / 60,
+        t % 60
+    )
+}
+
+/// Local path for `file:` URIs (Iceberg writes both `file:/x` and `file:///x`).
+fn local_or_uri(uri: &str) -> String {
+    match uri.strip_prefix("file:") {
+        Some(rest) if rest.starts_with("///") => rest[2..].to_string(),
+        Some(rest) if !rest.starts_with("//") => rest.to_string(),
+        _ => uri.to_string(),
+    }
+}
+
+async fn read_object(uri: &str) -> Result<Bytes> {
+    let uri = local_or_uri(uri);
+    let backend = StorageRegistry::global().for_uri(&uri)?;
+    let size = backend.metadata(&uri).await?.size;
+    backend.read_range(&uri, 0..size).await
+}
+
+/// The metadata file of the table at `location`: the location itself when it
+/// names one, else the version `metadata/version-hint.text` points at, else
+/// (local tables) the highest-numbered `metadata/*.metadata.json`.
+async fn find_metadata_file(location: &str) -> Result<Option<String>> {
+    if location.ends_with(".metadata.json") {
+        return Ok(Some(location.to_string()));
+    }
+    // a URL with a query (e.g. a pre-signed one) names a single object
+    if location.ends_with(".parquet")
+        || (crate::scanner::is_remote_uri(location) && location.contains('?'))
+    {
+        return Ok(None);
+    }
+    let location = local_or_uri(location.trim_end_matches('/'));
+    let is_local = !crate::scanner::is_remote_uri(&location);
+    let metadata_dir = format!("{location}/metadata");
+    if is_local && !Path::new(&metadata_dir).is_dir() {
+        return Ok(None);
+    }
+    let hint_uri = format!("{metadata_dir}/version-hint.text");
+    // a failed remote probe just means "not a table"; listing reports real errors
+    let hint = if is_local && !Path::new(&hint_uri).is_file() {
+        None
+    } else {
+        read_object(&hint_uri)
+            .await
+            .ok()
+            .map(|hint| String::from_utf8_lossy(&hint).trim().to_string())
+    };
+    let file = hint.and_then(|hint| {
+        if hint.parse::<u64>().is_ok() {
+            Some(format!("v{hint}.metadata.json"))
+        } else {
+            (hint.ends_with(".metadata.json") && !hint.contains('/')).then_some(hint)
+        }
+    });
+    if let Some(file) = file {
+        return Ok(Some(format!("{metadata_dir}/{file}")));
+    }
+    if !is_local {
+        return Ok(None);
+    }
+    // `v3.metadata.json` (Hadoop tables) or `00003-<uuid>.metadata.json` (catalogs)
+    let mut newest: Option<(u64, String)> = None;
+    for entry in std::fs::read_dir(&metadata_dir)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if !name.ends_with(".metadata.json") {
+            continue;
+        }
+        let digits: String = name
+            .trim_start_matches('v')
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        let version = digits.parse().unwrap_or(0);
+        if newest
+            .as_ref()
+            .is_none_or(|(v, n)| (version, &name) > (*v, n))
+        {
+            newest = Some((version, name));
+        }
+    }
+    Ok(newest.map(|(_, name)| format!("{metadata_dir}/{name}")))
+}
+
+#[cfg(test)]
+mod tests_iceberg {
+    use super::*;
+    use apache_avro::{Schema, Writer};
+
+    fn write_avro(path: &Path, schema: &str, records: Vec<AvroValue>) {
+        let schema = Schema::parse_str(schema).unwrap();
+        let mut writer = Writer::new(&schema, Vec::new());
+        for r in records {
+            writer.append(r).unwrap();
+        }
+        std::fs::write(path, writer.into_inner().unwrap()).unwrap();
+    }
+
+    fn rec(fields: Vec<(&str, AvroValue)>) -> AvroValue {
+        AvroValue::Record(
+            fields
+                .into_iter()
+                .map(|(n, v)| (n.to_string(), v))
+                .collect(),
+        )
+    }
+
+    const MANIFEST_LIST: &str = r#"{"type": "record", "name": "manifest_file", "fields": [
+        {"name": "manifest_path", "type": "string"},
+        {"name": "partition_spec_id", "type": "int"},
+        {"name": "content", "type": "int"}]}"#;
+    const MANIFEST: &str = r#"{"type": "record", "name": "manifest_entry", "fields": [
+        {"name": "status", "type": "int"},
+        {"name": "data_file", "type": {"type": "record", "name": "r2", "fields": [
+            {"name": "content", "type": "int"},
+            {"name": "file_path", "type": "string"},
+            {"name": "file_format", "type": "string"},
+            {"name": "partition", "type": {"type": "record", "name": "r102", "fields": [
+                {"name": "ts_day", "type": ["null", {"type": "int", "logicalType": "date"}]}]}},
+            {"name": "record_count", "type": "long"}]}}]}"#;
+
+    fn entry(status: i32, content: i32, path: &str, day: Option<i32>) -> AvroValue {
+        let day = match day {
+            Some(d) => AvroValue::Union(1, Box::new(AvroValue::Date(d))),
+            None => AvroValue::Union(0, Box::new(AvroValue::Null)),
+        };
+        rec(vec![
+            ("status", AvroValue::Int(status)),
+            (
+                "data_file",
+                rec(vec![
+                    ("content", AvroValue::Int(content)),
+                    ("file_path", AvroValue::String(path.into())),
+                    ("file_format", AvroValue::String("PARQUET".into())),
+                    ("partition", rec(vec![("ts_day", day)])),
+                    ("record_count", AvroValue::Long(10)),
+                ]),
+            ),
+        ])
+    }
+
+    fn metadata(location: &Path, snapshot: Option<&Path>) -> String {
+        let snapshots = match snapshot {
+            Some(list) => format!(
+                r#"[{{"snapshot-id": 7, "timestamp-ms": 1704067200000,
+                    "manifest-list": "file:{}", "summary": {{"operation": "append"}}}}]"#,
+                list.display()
+            ),
+            None => "[]".into(),
+        };
+        format!(
+            r#"{{"format-version": 2, "table-uuid": "u", "location": "{}",
+            "schemas": [
+              {{"type": "struct", "schema-id": 0, "fields": [
+                {{"id": 1, "name": "id", "required": true, "type": "int"}},
+                {{"id": 2, "name": "name", "required": true, "type": "string"}},
+                {{"id": 3, "name": "ts", "required": true, "type": "timestamp"}}]}},
+              {{"type": "struct", "schema-id": 1, "fields": [
+                {{"id": 1, "name": "id", "required": true, "type": "long"}},
+                {{"id": 2, "name": "full_name", "required": false, "type": "string"}},
+                {{"id": 3, "name": "ts", "required": true, "type": "timestamp"}},
+                {{"id": 4, "name": "tags", "required": false,
+                  "type": {{"type": "list", "element-id": 5, "element": "string", "element-required": false}}}}]}}],
+            "current-schema-id": 1,
+            "partition-specs": [{{"spec-id": 0, "fields": [
+              {{"name": "ts_day", "transform": "day", "source-id": 3, "field-id": 1000}}]}}],
+            "default-spec-id": 0,
+            "current-snapshot-id": {},
+            "snapshots": {snapshots}}}"#,
+            location.display(),
+            if snapshot.is_some() { "7" } else { "-1" },
+        )
+    }
+
+    #[tokio::test]
+    async fn expands_the_current_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let table = dir.path();
+        let meta_dir = table.join("metadata");
+        std::fs::create_dir_all(&meta_dir).unwrap();
+        let data = |name: &str| format!("file://{}", table.join("data").join(name).display());
+        let manifest = meta_dir.join("m0.avro");
+        write_avro(
+            &manifest,
+            MANIFEST,
+            vec![
+                entry(1, 0, &data("a.parquet"), Some(19723)), // 2024-01-01
+                entry(0, 0, &data("b.parquet"), None),
+                entry(2, 0, &data("removed.parquet"), Some(19723)),
+            ],
+        );
+        let deletes = meta_dir.join("d0.avro");
+        write_avro(
+            &deletes,
+            MANIFEST,
+            vec![entry(1, 1, &data("del.parquet"), None)],
+        );
+        let list = meta_dir.join("snap-7.avro");
+        let manifest_rec = |path: &Path, content| {
+            rec(vec![
+                (
+                    "manifest_path",
+                    AvroValue::String(path.display().to_string()),
+                ),
+                ("partition_spec_id", AvroValue::Int(0)),
+                ("content", AvroValue::Int(content)),
+            ])
+        };
+        write_avro(
+            &list,
+            MANIFEST_LIST,
+            vec![manifest_rec(&manifest, 0), manifest_rec(&deletes, 1)],
+        );
+        std::fs::write(meta_dir.join("v1.metadata.json"), metadata(table, None)).unwrap();
+        std::fs::write(
+            meta_dir.join("v2.metadata.json"),
+            metadata(table, Some(&list)),
+        )
+        .unwrap();
+
+        // no version hint: the highest-numbered metadata file wins
+        let t = open_iceberg_table(table.to_str().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(t.snapshot.as_ref().map(|s| s.snapshot_id), Some(7));
+        let files: Vec<_> = t.data_files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            files,
+            [table.join("data/a.parquet"), table.join("data/b.parquet")]
+        );
+        assert_eq!(t.data_files[0].partitions["ts_day"], "2024-01-01");
+        assert_eq!(t.data_files[1].partitions["ts_day"], "null");
+        assert_eq!(t.delete_files, 1);
+        assert_eq!(t.partition_spec[0].describe(), "day(ts)");
+        assert_eq!(
+            t.schema_history[1].changes,
+            [
+                "id int -> long",
+                "renamed name to full_name",
+                "full_name made optional",
+                "added tags list<string>"
+            ]
+            .map(String::from)
+        );
+        let lines = t.lines();
+        assert!(lines[1].ends_with("7 (append, 2024-01-01 00:00:00 UTC)"));
+
+        let resolved = crate::scanner::resolve_paths(table.to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resolved.len(), 2);
+
+        // a version hint pins the table to an older metadata file
+        std::fs::write(meta_dir.join("version-hint.text"), "1\n").unwrap();
+        let t = open_iceberg_table(table.to_str().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(t.snapshot.is_none() && t.data_files.is_empty());
+        assert!(open_iceberg_table(meta_dir.to_str().unwrap())
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,83 @@
+use crate::engine::identify_engine;
+use crate::null_patterns::analyze_null_patterns;
+use crate::parallel_reader::{DatasetProfile, FileProfile};
+use crate::quality::{score_columns, summarize_quality};
+use crate::reader::ParquetFileInfo;
+use crate::recommendations::recommend_row_group_size;
+use crate::repair::detect_repair_suggestions;
+use crate::report::ProfileReport;
+use crate::schema::schema_from_metadata;
+use crate::stats::{
+    aggregate_column_stats, analyze_compression, analyze_encodings, profile_row_groups,
+    read_column_stats,
+};
+use bytes::Bytes;
+use parquet::file::metadata::{ParquetMetaData, ParquetMetaDataReader};
+use parquet_lens_common::{ParquetLensError, Result};
+use std::path::PathBuf;
+
+/// Parses the footer of a Parquet file held in memory.
+pub fn read_metadata_bytes(name: &str, data: &Bytes) -> Result<ParquetMetaData> {
+    ParquetMetaDataReader::new()
+        .parse_and_finish(data)
+        .map_err(|e| ParquetLensError::corrupt(format!("{name} footer"), e))
+}
+
+/// Metadata, statistics and quality report for a Parquet file held in memory,
+/// e.g. one dropped into a browser. Touches neither the filesystem nor a runtime,
+/// so it works on `wasm32-unknown-unknown` with `default-features = false`.
+/// Sections that need decoded pages (full scan, time series, nested) stay empty.
+pub fn profile_bytes(name: &str, data: impl Into<Bytes>) -> Result<ProfileReport> {
+    let data = data.into();
+    let meta = read_metadata_bytes(name, &data)?;
+    let file_info = ParquetFileInfo::from_metadata(PathBuf::from(name), data.len() as u64, &meta);
+    let total_rows = file_info.row_count;
+    let dataset = DatasetProfile {
+        file_count: 1,
+        total_rows,
+        total_bytes: file_info.file_size,
+        files: vec![FileProfile {
+            path: file_info.path.clone(),
+            row_count: total_rows,
+            row_group_count: file_info.row_group_count,
+            file_size: file_info.file_size,
+            created_by: file_info.created_by.clone(),
+        }],
+        combined_schema: schema_from_metadata(&meta),
+        schema_inconsistencies: Vec::new(),
+    };
+    let column_stats = aggregate_column_stats(&read_column_stats(&meta), total_rows);
+    let row_groups = profile_row_groups(&meta);
+    let encodings = analyze_encodings(&meta);
+    let compression = analyze_compression(&meta);
+    let scores = score_columns(&column_stats, &encodings, total_rows);
+    let total_cells = total_rows * dataset.combined_schema.len() as i64;
+    let total_nulls: u64 = column_stats.iter().map(|s| s.total_null_count).sum();
+    let quality = summarize_quality(scores, total_cells, total_nulls, true, &column_stats);
+    Ok(ProfileReport {
+        null_patterns: analyze_null_patterns(&column_stats),
+        repair_suggestions: detect_repair_suggestions(&row_groups, &column_stats, &encodings),
+        row_group_recommendation: recommend_row_group_size(&row_groups),
+        engine_info: file_info.created_by.as_deref().map(identify_engine),
+        dataset,
+        file_info,
+        row_groups,
+        column_stats,
+        encodings,
+        compression,
+        quality,
+        timeseries_profiles: Vec::new(),
+        nested_profiles: Vec::new(),
+        nested_data: None,
+        baseline_captured_at: None,
+        baseline_regressions: Vec::new(),
+        full_scan: None,
+        sample_note: None,
+        sample_confidence: Vec::new(),
+        quarantine: None,
+        row_counts: None,
+        pii: None,
+        warnings: Vec::new(),
+        run: None,
+    })
+}
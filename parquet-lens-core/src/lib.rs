@@ -1,49 +1,148 @@
+pub mod handle;
+pub mod inspect;
+pub mod memory;
+pub mod options;
 pub mod parallel_reader;
 pub mod profile;
+pub mod profiler;
+pub mod progress;
+pub mod quarantine;
 pub mod reader;
+pub mod report;
 pub mod scanner;
 pub mod schema;
 pub mod schema_diff;
 pub mod stats;
-pub use parallel_reader::{read_metadata_parallel, DatasetProfile, FileProfile};
-pub use parquet_lens_common::{ParquetLensError, Result};
+pub mod storage;
+pub use handle::{FileHandle, HandleRegistry};
+pub use inspect::{profile_bytes, read_metadata_bytes};
+pub use memory::{format_bytes, MemoryGauge, MemoryTracker};
+pub use options::{prefetch_batches, ScanOptions};
+pub use parallel_reader::{
+    read_metadata_parallel, read_metadata_parallel_with_progress, read_metadata_remote,
+    DatasetProfile, FileProfile,
+};
+pub use parquet_lens_common::{ByteUnits, ErrorInfo, NumberFormat, ParquetLensError, Result};
+pub use profile::{
+    build_histogram, hll_relative_error, profile_columns, profile_columns_with_handle,
+    profile_columns_with_timeout, profile_dataset, union_cardinality, BenfordConformity,
+    BenfordProfile, BinaryProfile, BooleanProfile, CalendarBucket, CalendarGap,
+    CardinalityEstimate, ColumnEntropy, ColumnProfileResult, EncodingAnomalies, FrequencyEntry,
+    FrequencyResult, HistogramBin, HistogramScale, HllEstimator, NumericProfile, StringProfile,
+    TemporalProfile, DEFAULT_HLL_PRECISION, HLL_PRECISION_RANGE, WEEKDAYS,
+};
+#[cfg(feature = "tokio")]
 pub use profile::{
-    build_histogram, profile_columns, profile_columns_with_timeout, BooleanProfile,
-    CardinalityEstimate, ColumnProfileResult, FrequencyResult, HistogramBin, NumericProfile,
-    StringProfile, TemporalProfile,
+    object_store_for_uri, preview_object, profile_columns_async,
+    profile_columns_async_with_timeout, profile_object,
 };
+pub use profiler::{Profiler, ProfilerBuilder};
+pub use progress::{Progress, ProgressSink};
+pub use quarantine::{QuarantineLog, QuarantineReport, QuarantinedRowGroup};
 pub use reader::{open_parquet_auto, open_parquet_file, ParquetFileInfo, SchemaFieldInfo};
+pub use report::ProfileReport;
 pub use scanner::{resolve_paths, scan_directory, ParquetFilePath};
-pub use schema::{extract_schema, ColumnSchema};
+pub use schema::{
+    extract_schema, extract_schema_with_handle, schema_from_metadata, schema_tree, ColumnSchema,
+    SchemaTreeNode,
+};
 pub use schema_diff::{check_schema_consistency, InconsistencyKind, SchemaInconsistency};
 pub use stats::{
     aggregate_column_stats, analyze_compression, analyze_encodings, analyze_uniformity,
     profile_row_groups, read_column_stats, AggregatedColumnStats, ColumnStats, CompressionAnalysis,
-    EncodingAnalysis, RowGroupProfile, UniformityReport,
+    EncodingAnalysis, FileStats, RowGroupProfile, UniformityReport,
+};
+#[cfg(feature = "azure")]
+pub use storage::AzureBackend;
+#[cfg(feature = "gcs")]
+pub use storage::GcsBackend;
+#[cfg(feature = "hdfs")]
+pub use storage::HdfsBackend;
+#[cfg(feature = "http")]
+pub use storage::HttpBackend;
+#[cfg(feature = "s3")]
+pub use storage::S3Backend;
+pub use storage::{
+    download_object, set_list_options, ListOptions, LocalBackend, ObjectInfo, StorageBackend,
+    StorageRegistry,
 };
+#[cfg(feature = "azure")]
+pub mod azure_reader;
+#[cfg(feature = "tokio")]
+pub mod chunk_cache;
 pub mod compare;
 pub mod export;
+#[cfg(feature = "tokio")]
+pub mod footer_cache;
+#[cfg(feature = "gcs")]
 pub mod gcs_reader;
+#[cfg(feature = "hdfs")]
+pub mod hdfs_reader;
+#[cfg(feature = "http")]
+pub mod http_reader;
+#[cfg(feature = "iceberg")]
+pub mod iceberg;
 pub mod quality;
 pub mod recommendations;
+#[cfg(feature = "tokio")]
+pub mod remote;
+#[cfg(feature = "tokio")]
+pub mod retry;
+#[cfg(feature = "s3")]
 pub mod s3_reader;
 pub mod stats_ext;
+#[cfg(feature = "azure")]
+pub use azure_reader::{
+    azure_object_metadata, is_azure_uri, list_azure_parquet, parse_azure_uri,
+    read_azure_parquet_metadata, read_azure_range, AzureUri,
+};
+#[cfg(feature = "tokio")]
+pub use chunk_cache::{set_chunk_cache, ChunkCache};
 pub use compare::{
     compare_datasets, diff_schemas, diff_stats, ColumnSchemaDiff, ColumnStatsDiff,
     DatasetComparison, DiffStatus,
 };
-pub use export::{export_csv, export_json, print_summary};
+pub use export::{
+    columns_table, export_csv, export_great_expectations, export_json, export_ndjson,
+    export_prometheus, great_expectations_suite, openlineage_event, print_summary,
+    prometheus_metrics, write_rows_jsonl, LineageRun,
+};
+#[cfg(feature = "tokio")]
+pub use footer_cache::{set_footer_cache, FooterCache};
+#[cfg(feature = "gcs")]
 pub use gcs_reader::{
     is_gcs_uri, list_gcs_parquet, parse_gcs_uri, read_gcs_parquet_metadata, GcsUri,
 };
+#[cfg(feature = "hdfs")]
+pub use hdfs_reader::{
+    hdfs_object_metadata, is_hdfs_uri, list_hdfs_parquet, parse_hdfs_uri,
+    read_hdfs_parquet_metadata, read_hdfs_range, set_hdfs_config, HdfsUri,
+};
+#[cfg(feature = "http")]
+pub use http_reader::{
+    http_object_metadata, is_http_uri, is_presigned_url, read_http_parquet_metadata,
+    read_http_range, set_http_config,
+};
+#[cfg(feature = "iceberg")]
+pub use iceberg::{
+    load_iceberg_table, open_iceberg_table, IcebergPartitionField, IcebergSchemaVersion,
+    IcebergSnapshot, IcebergTable,
+};
 pub use quality::{
-    detect_duplicates, score_column, summarize_quality, DatasetQuality, DuplicateReport,
-    QualityScore,
+    apply_full_scan_findings, apply_pii_findings, detect_duplicates, detect_duplicates_with_handle,
+    rule_constraints, run_rules, scan_pii, score_column, score_columns, summarize_quality,
+    ColumnPii, DatasetQuality, DuplicateReport, PiiKind, PiiMatch, PiiReport, QualityScore,
+    LIKELY_PII_PCT,
 };
 pub use recommendations::{
     recommend_compression, recommend_encodings, recommend_row_group_size,
     CompressionRecommendation, EncodingRecommendation, RowGroupSizeRecommendation,
 };
+#[cfg(feature = "tokio")]
+pub use remote::{set_remote_config, ObjectStoreBackend, ObjectStoreReader, RemoteConfig};
+#[cfg(feature = "tokio")]
+pub use retry::{record_retry, retry_count, set_retry_config};
+#[cfg(feature = "s3")]
 pub use s3_reader::{
     is_s3_uri, list_s3_parquet, parse_s3_uri, read_s3_parquet_metadata, read_s3_range, S3Uri,
 };
@@ -53,18 +152,60 @@ pub use stats_ext::{
     PartitionInfo, SortedOrderInfo, StringLengthHist,
 };
 pub mod filter;
-pub use filter::{filter_count, filter_rows, parse_predicate, FilterResult, Predicate};
+pub use filter::{
+    explain_filter, filter_count, filter_count_dataset, filter_rows, filter_rows_each,
+    parse_predicate, DatasetFilterResult, FileFilterResult, FilterResult, Predicate,
+    RowGroupExplain, SkipReason,
+};
+pub mod aggregate;
+pub use aggregate::{filter_aggregate, parse_aggregate, AggExpr, AggFunc, AggQuery, AggResult};
 pub mod sample;
-pub use sample::{sample_row_groups, SampleConfig, SampledProfile};
+pub use sample::{
+    sample_partitions, sample_row_groups, MetricConfidence, SampleConfig, SampleMethod,
+    SampledProfile, StrataAllocation, StratumSample,
+};
+pub mod mask;
+pub use mask::{MaskMode, MaskRule, MaskSpec, FAKE_BINS};
+pub mod sample_export;
+pub use sample_export::{
+    export_sample, export_sample_with_handle, sample_manifest_path, SampleManifest,
+};
+pub mod constraints;
+pub use constraints::{check_constraints, Constraint, ConstraintResult, ConstraintSample};
+pub mod contract;
+pub use contract::{
+    validate_contract, Contract, ContractColumn, ContractReport, ContractViolation, Severity,
+};
+pub mod top_values;
+pub use top_values::{
+    distinct_values, mask_top_values, top_values, top_values_with_handle, write_top_values_csv,
+    write_top_values_json, ColumnTopValues,
+};
+pub mod run_info;
+pub use run_info::{RunInfo, StageTiming};
 pub mod baseline;
+pub mod cache;
 pub mod engine;
+pub mod history;
 pub mod nested;
 pub mod null_patterns;
 pub mod repair;
+pub mod row_count;
 pub mod timeseries;
 pub use baseline::{load_baseline_regressions, BaselineProfile, BaselineRegression};
+pub use cache::{CacheKey, ProfileCache};
 pub use engine::{identify_engine, EngineInfo};
-pub use nested::{profile_nested_columns, NestedColumnProfile};
+pub use history::{HistoryEntry, HistoryStore};
+pub use nested::{
+    profile_nested_columns, profile_nested_columns_with_handle, profile_nested_data,
+    profile_nested_data_with_handle, ListLengthProfile, MapKeyProfile, NestedColumnProfile,
+    NestedDataProfile, StructFieldNulls,
+};
 pub use null_patterns::{analyze_null_patterns, NullPatternGroup};
 pub use repair::{detect_repair_suggestions, RepairSuggestion};
-pub use timeseries::{profile_timeseries, TimeSeriesProfile};
+pub use row_count::{
+    reconcile_row_counts, reconcile_row_counts_with_handle, RowCountCheck, RowCountMismatch,
+};
+pub use timeseries::{
+    detect_timestamp_columns, profile_timeseries, profile_timeseries_with_handle, TimeSeriesProfile,
+};
@@ -0,0 +1,560 @@
+use crate::handle::FileHandle;
+use crate::options::ScanOptions;
+use crate::profile::frequency::FrequencyEntry;
+use crate::profile::{profile_columns_with_handle, ColumnProfileResult, HistogramBin};
+use arrow::array::{
+    new_null_array, Array, ArrayRef, AsArray, BooleanArray, Float64Array, StringArray,
+    TimestampMillisecondArray,
+};
+use arrow::buffer::NullBuffer;
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+use parquet_lens_common::{ParquetLensError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use xxhash_rust::xxh3::{xxh3_128_with_seed, xxh3_64_with_seed};
+
+// fixed so the same value hashes the same in every export and stays joinable
+const MASK_SEED: u64 = 0x6d61_736b_5f6c_656e;
+// characters `partial` leaves visible at the end of a value
+const PARTIAL_KEEP: usize = 4;
+
+/// How a masked column's values are rewritten.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskMode {
+    /// Stable 128-bit hex digest: joins and distinct counts still work, but
+    /// low-entropy values can be guessed, so it is pseudonymisation only.
+    #[default]
+    Hash,
+    /// Every value becomes null.
+    Null,
+    /// All but the last four characters become `*`.
+    Partial,
+    /// Synthetic values drawn from the column's profiled distribution
+    /// (categories, histogram, date range or string length); see
+    /// `MaskSpec::fit_with_handle`. Unfitted columns are hashed instead.
+    Fake,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaskRule {
+    pub column: String,
+    pub mode: MaskMode,
+}
+
+/// Columns to redact before row data is written anywhere. Empty masks
+/// nothing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaskSpec {
+    pub rules: Vec<MaskRule>,
+    #[serde(skip)]
+    fakes: HashMap<String, Arc<FakeColumn>>,
+}
+
+impl MaskSpec {
+    /// `email,ssn:partial,notes:null`; columns without a mode are hashed.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+        for item in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (column, mode) = match item.rsplit_once(':') {
+                Some((column, mode)) => (column, parse_mode(mode)?),
+                None => (item, MaskMode::Hash),
+            };
+            rules.push(MaskRule {
+                column: column.to_string(),
+                mode,
+            });
+        }
+        Ok(Self {
+            rules,
+            fakes: HashMap::new(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    fn mode(&self, column: &str) -> Option<MaskMode> {
+        self.rules
+            .iter()
+            .find(|r| r.column == column)
+            .map(|r| r.mode)
+    }
+
+    /// Columns masked with `MaskMode::Fake`, which need a profile to draw from.
+    pub fn fake_columns(&self) -> Vec<String> {
+        self.rules
+            .iter()
+            .filter(|r| r.mode == MaskMode::Fake)
+            .map(|r| r.column.clone())
+            .collect()
+    }
+
+    /// Profiles the `fake` columns of `handle`'s file so their values can be
+    /// synthesised. Categories are only reused when every value occurs at
+    /// least `MIN_CATEGORY_ROWS` times, so rare real values never appear.
+    pub fn fit_with_handle(&mut self, handle: &FileHandle, opts: &ScanOptions) -> Result<()> {
+        let columns = self.fake_columns();
+        if columns.is_empty() {
+            return Ok(());
+        }
+        let schema = handle.arrow_metadata()?.schema().clone();
+        self.check(&schema)?;
+        let profiles = profile_columns_with_handle(handle, Some(&columns), FAKE_BINS, None, opts)?;
+        self.fit_profiles(&schema, &profiles)
+    }
+
+    /// `fit_with_handle` for files profiled elsewhere (remote ones, say);
+    /// `profiles` should cover `fake_columns()` at `FAKE_BINS` bins.
+    pub fn fit_profiles(
+        &mut self,
+        schema: &Schema,
+        profiles: &[ColumnProfileResult],
+    ) -> Result<()> {
+        for profile in profiles {
+            let data_type = schema.field_with_name(&profile.column_name)?.data_type();
+            if let Some(fake) = FakeColumn::fit(profile, data_type) {
+                self.fakes
+                    .insert(profile.column_name.clone(), Arc::new(fake));
+            }
+        }
+        Ok(())
+    }
+
+    /// Errors for masked columns `schema` doesn't have, so a typo can't
+    /// silently let a column through in cleartext.
+    pub fn check(&self, schema: &Schema) -> Result<()> {
+        for r in &self.rules {
+            if schema.index_of(&r.column).is_err() {
+                return Err(ParquetLensError::Other(format!(
+                    "--mask: no column {} (top-level columns only)",
+                    r.column
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// `batch` with every masked column rewritten. Hashed and partial
+    /// columns become nullable strings; nulled ones keep their type.
+    pub fn apply(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        if self.is_empty() {
+            return Ok(batch.clone());
+        }
+        let schema = batch.schema();
+        self.check(&schema)?;
+        let mut fields = Vec::with_capacity(schema.fields().len());
+        let mut columns = Vec::with_capacity(batch.num_columns());
+        for (field, column) in schema.fields().iter().zip(batch.columns()) {
+            let Some(mode) = self.mode(field.name()) else {
+                fields.push(field.as_ref().clone());
+                columns.push(column.clone());
+                continue;
+            };
+            let fake = self
+                .fakes
+                .get(field.name())
+                .filter(|_| mode == MaskMode::Fake);
+            let masked = match fake {
+                Some(fake) => Ok(fake.generate(column.nulls(), column.len())),
+                None => mask_array(column, mode),
+            };
+            let masked = masked
+                .map_err(|e| ParquetLensError::Other(format!("--mask {}: {e}", field.name())))?;
+            fields.push(Field::new(field.name(), masked.data_type().clone(), true));
+            columns.push(masked);
+        }
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+
+    /// Masks `column`'s listed values (top values, distinct-value sets) in
+    /// place, as its rows would be; fakes are hashed. Values that mask alike,
+    /// as `partial` ones can, are merged, and a nulled column lists none.
+    pub fn apply_to_values(&self, column: &str, values: &mut Vec<FrequencyEntry>) {
+        let Some(mode) = self.mode(column) else {
+            return;
+        };
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut masked: Vec<FrequencyEntry> = Vec::new();
+        for entry in values.drain(..) {
+            let Some(value) = mask_str(&entry.value, mode) else {
+                continue;
+            };
+            match index.get(&value) {
+                Some(&i) => {
+                    masked[i].count += entry.count;
+                    masked[i].percentage += entry.percentage;
+                }
+                None => {
+                    index.insert(value.clone(), masked.len());
+                    masked.push(FrequencyEntry { value, ..entry });
+                }
+            }
+        }
+        masked.sort_by_key(|e| std::cmp::Reverse(e.count));
+        *values = masked;
+    }
+
+    /// Masks already-rendered rows in place; `headers` names their columns.
+    pub fn apply_to_rows(&self, headers: &[String], rows: &mut [Vec<String>]) {
+        for (col, header) in headers.iter().enumerate() {
+            let Some(mode) = self.mode(header) else {
+                continue;
+            };
+            let fake = self.fakes.get(header).filter(|_| mode == MaskMode::Fake);
+            let fakes = fake.map(|f| f.generate(None, rows.len()));
+            for (i, row) in rows.iter_mut().enumerate() {
+                let Some(value) = row.get_mut(col) else {
+                    continue;
+                };
+                *value = match &fakes {
+                    Some(a) => array_value_to_string(a, i).unwrap_or_default(),
+                    None => mask_str(value, mode).unwrap_or_default(),
+                };
+            }
+        }
+    }
+}
+
+fn parse_mode(mode: &str) -> Result<MaskMode> {
+    match mode {
+        "hash" => Ok(MaskMode::Hash),
+        "null" => Ok(MaskMode::Null),
+        "partial" => Ok(MaskMode::Partial),
+        "fake" => Ok(MaskMode::Fake),
+        other => Err(ParquetLensError::Other(format!(
+            "--mask: unknown mode {other:?} (use hash, null, partial or fake)"
+        ))),
+    }
+}
+
+fn mask_array(array: &ArrayRef, mode: MaskMode) -> std::result::Result<ArrayRef, String> {
+    if mode == MaskMode::Null {
+        return Ok(new_null_array(array.data_type(), array.len()));
+    }
+    let values = cast(array, &DataType::Utf8).map_err(|e| format!("can't mask this type: {e}"))?;
+    let values = values.as_string::<i32>();
+    let masked: StringArray = (0..values.len())
+        .map(|i| {
+            if values.is_null(i) {
+                None
+            } else {
+                mask_str(values.value(i), mode)
+            }
+        })
+        .collect();
+    Ok(Arc::new(masked))
+}
+
+// None for `Null`; unfitted `Fake` columns are hashed
+fn mask_str(value: &str, mode: MaskMode) -> Option<String> {
+    match mode {
+        MaskMode::Hash | MaskMode::Fake => Some(format!(
+            "{:032x}",
+            xxh3_128_with_seed(value.as_bytes(), MASK_SEED)
+        )),
+        MaskMode::Null => None,
+        MaskMode::Partial => {
+            let chars = value.chars().count();
+            let hidden = if chars > PARTIAL_KEEP {
+                chars - PARTIAL_KEEP
+            } else {
+                chars
+            };
+            Some(
+                value
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| if i < hidden { '*' } else { c })
+                    .collect(),
+            )
+        }
+    }
+}
+
+/// Histogram resolution fake numbers are drawn at.
+pub const FAKE_BINS: usize = 20;
+// a category must cover this many rows before fakes may repeat it verbatim
+const MIN_CATEGORY_ROWS: u64 = 5;
+const TEXT_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+#[derive(Debug)]
+enum FakeValues {
+    Categories {
+        values: Vec<String>,
+        weights: Vec<u64>,
+    },
+    Numeric {
+        bins: Vec<HistogramBin>,
+    },
+    Temporal {
+        min_ms: i64,
+        max_ms: i64,
+    },
+    Boolean {
+        true_fraction: f64,
+    },
+    Text {
+        min_len: usize,
+        max_len: usize,
+        digits: bool,
+    },
+}
+
+/// Draws synthetic values for one column. Draws are numbered so the same
+/// spec produces the same fakes, batch after batch.
+#[derive(Debug)]
+struct FakeColumn {
+    values: FakeValues,
+    data_type: DataType,
+    seed: u64,
+    drawn: AtomicU64,
+}
+
+impl FakeColumn {
+    fn fit(profile: &ColumnProfileResult, data_type: &DataType) -> Option<Self> {
+        let values = if let Some(b) = &profile.boolean {
+            FakeValues::Boolean {
+                true_fraction: b.true_percentage / 100.0,
+            }
+        } else if let Some(f) = profile.frequency.as_ref().filter(|f| {
+            let listed: u64 = f.top_values.iter().map(|e| e.count).sum();
+            // every value listed, none rare enough to identify anyone
+            listed == f.total_count && f.top_values.iter().all(|e| e.count >= MIN_CATEGORY_ROWS)
+        }) {
+            FakeValues::Categories {
+                values: f.top_values.iter().map(|e| e.value.clone()).collect(),
+                weights: f.top_values.iter().map(|e| e.count).collect(),
+            }
+        } else if let Some((min_ms, max_ms)) = profile
+            .temporal
+            .as_ref()
+            .and_then(|t| t.min_timestamp_ms.zip(t.max_timestamp_ms))
+        {
+            FakeValues::Temporal { min_ms, max_ms }
+        } else if let Some(bins) = profile.histogram.clone().filter(|b| !b.is_empty()) {
+            FakeValues::Numeric { bins }
+        } else if let Some(s) = &profile.string {
+            FakeValues::Text {
+                min_len: s.min_length,
+                max_len: s.max_length,
+                digits: s.patterns.all_numeric_pct >= 50.0,
+            }
+        } else {
+            return None;
+        };
+        Some(Self {
+            values,
+            data_type: data_type.clone(),
+            seed: xxh3_64_with_seed(profile.column_name.as_bytes(), MASK_SEED),
+            drawn: AtomicU64::new(0),
+        })
+    }
+
+    // uniform in [0, 1) for the `k`th random number of draw `n`
+    fn unit(&self, n: u64, k: u64) -> f64 {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&n.to_le_bytes());
+        bytes[8..].copy_from_slice(&k.to_le_bytes());
+        (xxh3_64_with_seed(&bytes, self.seed) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn pick(&self, weights: impl Iterator<Item = u64> + Clone, u: f64) -> usize {
+        let total: u64 = weights.clone().sum();
+        let mut target = u * total as f64;
+        let mut last = 0;
+        for (i, w) in weights.enumerate() {
+            last = i;
+            if target < w as f64 {
+                return i;
+            }
+            target -= w as f64;
+        }
+        last
+    }
+
+    /// `len` fakes in the column's type, null wherever `nulls` is.
+    fn generate(&self, nulls: Option<&NullBuffer>, len: usize) -> ArrayRef {
+        let first = self.drawn.fetch_add(len as u64, Ordering::Relaxed);
+        let valid = |i: usize| nulls.is_none_or(|n| n.is_valid(i));
+        let draws = (0..len).map(|i| (valid(i), first + i as u64));
+        let array: ArrayRef = match &self.values {
+            FakeValues::Boolean { true_fraction } => Arc::new(
+                draws
+                    .map(|(v, n)| v.then(|| self.unit(n, 0) < *true_fraction))
+                    .collect::<BooleanArray>(),
+            ),
+            FakeValues::Categories { values, weights } => Arc::new(
+                draws
+                    .map(|(v, n)| {
+                        v.then(|| {
+                            values[self.pick(weights.iter().copied(), self.unit(n, 0))].as_str()
+                        })
+                    })
+                    .collect::<StringArray>(),
+            ),
+            FakeValues::Temporal { min_ms, max_ms } => Arc::new(
+                draws
+                    .map(|(v, n)| {
+                        let span = (max_ms - min_ms) as f64;
+                        v.then(|| min_ms + (self.unit(n, 0) * span) as i64)
+                    })
+                    .collect::<TimestampMillisecondArray>(),
+            ),
+            FakeValues::Numeric { bins } => {
+                let integral = self.data_type.is_integer();
+                Arc::new(
+                    draws
+                        .map(|(v, n)| {
+                            v.then(|| {
+                                let bin =
+                                    &bins[self.pick(bins.iter().map(|b| b.count), self.unit(n, 0))];
+                                let x = bin.range_start
+                                    + self.unit(n, 1) * (bin.range_end - bin.range_start);
+                                if integral {
+                                    x.round()
+                                } else {
+                                    x
+                                }
+                            })
+                        })
+                        .collect::<Float64Array>(),
+                )
+            }
+            FakeValues::Text {
+                min_len,
+                max_len,
+                digits,
+            } => {
+                let chars = if *digits {
+                    &TEXT_CHARS[26..]
+                } else {
+                    TEXT_CHARS
+                };
+                Arc::new(
+                    draws
+                        .map(|(v, n)| {
+                            v.then(|| {
+                                let spread = (max_len - min_len + 1) as f64;
+                                let len = min_len + (self.unit(n, 0) * spread) as usize;
+                                (0..len)
+                                    .map(|k| {
+                                        let u = self.unit(n, k as u64 + 1);
+                                        chars[(u * chars.len() as f64) as usize] as char
+                                    })
+                                    .collect::<String>()
+                            })
+                        })
+                        .collect::<StringArray>(),
+                )
+            }
+        };
+        // back to the source type where arrow can; otherwise the fake's own type
+        cast(&array, &self.data_type).unwrap_or(array)
+    }
+}
+
+#[cfg(test)]
+mod tests_mask {
+    use super::*;
+    use arrow::array::Int64Array;
+
+    fn batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("card", DataType::Utf8, true),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("4111222233334444"), None])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_modes_with_hash_default() {
+        let spec = MaskSpec::parse("email, card:partial,notes:null").unwrap();
+        let modes: Vec<_> = spec.rules.iter().map(|r| r.mode).collect();
+        assert_eq!(modes, [MaskMode::Hash, MaskMode::Partial, MaskMode::Null]);
+        assert!(MaskSpec::parse("card:blur").is_err());
+    }
+
+    #[test]
+    fn masks_batches_by_mode() {
+        let partial = MaskSpec::parse("card:partial")
+            .unwrap()
+            .apply(&batch())
+            .unwrap();
+        let card = partial.column(1).as_string::<i32>();
+        assert_eq!(card.value(0), "************4444");
+        assert!(card.is_null(1));
+
+        let hashed = MaskSpec::parse("id").unwrap().apply(&batch()).unwrap();
+        let id = hashed.column(0).as_string::<i32>();
+        assert_eq!(id.value(0).len(), 32);
+        assert_ne!(id.value(0), id.value(1));
+        assert!(hashed.schema().field(0).is_nullable());
+
+        let nulled = MaskSpec::parse("id:null").unwrap().apply(&batch()).unwrap();
+        assert_eq!(nulled.column(0).null_count(), 2);
+        assert_eq!(nulled.schema().field(0).data_type(), &DataType::Int64);
+
+        assert!(MaskSpec::parse("cardd").unwrap().apply(&batch()).is_err());
+    }
+
+    #[test]
+    fn masks_rendered_rows() {
+        let spec = MaskSpec::parse("card:partial").unwrap();
+        let mut rows = vec![vec!["1".to_string(), "abc".to_string()]];
+        spec.apply_to_rows(&["id".into(), "card".into()], &mut rows);
+        assert_eq!(rows[0], ["1", "***"]);
+
+        // fake without a fitted profile falls back to hashing
+        let spec = MaskSpec::parse("card:fake").unwrap();
+        spec.apply_to_rows(&["id".into(), "card".into()], &mut rows);
+        assert_eq!(rows[0][1].len(), 32);
+    }
+
+    #[test]
+    fn masks_listed_values() {
+        let entry = |value: &str, count| FrequencyEntry {
+            value: value.into(),
+            count,
+            percentage: count as f64 * 10.0,
+        };
+        let listed = || vec![entry("ab1234", 3), entry("cd1234", 2), entry("x", 1)];
+
+        let mut values = listed();
+        MaskSpec::parse("code:partial")
+            .unwrap()
+            .apply_to_values("code", &mut values);
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].value, "**1234");
+        assert_eq!(values[0].count, 5);
+        assert_eq!(values[0].percentage, 50.0);
+
+        let mut values = listed();
+        MaskSpec::parse("code:null")
+            .unwrap()
+            .apply_to_values("code", &mut values);
+        assert!(values.is_empty());
+
+        let mut values = listed();
+        MaskSpec::parse("other")
+            .unwrap()
+            .apply_to_values("code", &mut values);
+        assert_eq!(values[0].value, "ab1234");
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Approximate memory accounting for one analysis. Components report the size of
+/// their dominant allocations (sketches, hash sets, decoded batches, preview rows)
+/// and the tracker keeps the running total and its peak. These are estimates of
+/// what the analysis holds, not process RSS.
+///
+/// With a limit, the tracker also acts as a budget: full scans poll
+/// `over_limit` and fall back to smaller sketches once it is set.
+#[derive(Debug, Default)]
+pub struct MemoryTracker {
+    current: AtomicU64,
+    peak: AtomicU64,
+    limit: Option<u64>,
+    exceeded: AtomicBool,
+}
+
+impl MemoryTracker {
+    pub fn with_limit(limit: u64) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::default()
+        }
+    }
+
+    pub fn add(&self, bytes: usize) {
+        let now = self.current.fetch_add(bytes as u64, Ordering::Relaxed) + bytes as u64;
+        self.peak.fetch_max(now, Ordering::Relaxed);
+        if self.limit.is_some_and(|l| now > l) {
+            self.exceeded.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn sub(&self, bytes: usize) {
+        let _ = self
+            .current
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |c| {
+                Some(c.saturating_sub(bytes as u64))
+            });
+    }
+
+    pub fn current(&self) -> u64 {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    pub fn peak(&self) -> u64 {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// True once the running total has passed the limit. It stays set after
+    /// usage drops again, so work started later is lean from the outset.
+    pub fn over_limit(&self) -> bool {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+}
+
+/// One tracked allocation whose size changes over time (e.g. a per-row-group
+/// accumulator). Whatever it last reported is released when it is dropped.
+/// A gauge without a tracker does nothing, so call sites don't need to branch.
+pub struct MemoryGauge {
+    tracker: Option<Arc<MemoryTracker>>,
+    bytes: usize,
+}
+
+impl MemoryGauge {
+    pub fn new(tracker: Option<Arc<MemoryTracker>>) -> Self {
+        Self { tracker, bytes: 0 }
+    }
+
+    pub fn is_tracking(&self) -> bool {
+        self.tracker.is_some()
+    }
+
+    pub fn over_limit(&self) -> bool {
+        self.tracker.as_ref().is_some_and(|t| t.over_limit())
+    }
+
+    pub fn set(&mut self, bytes: usize) {
+        if let Some(t) = &self.tracker {
+            if bytes > self.bytes {
+                t.add(bytes - self.bytes);
+            } else {
+                t.sub(self.bytes - bytes);
+            }
+        }
+        self.bytes = bytes;
+    }
+}
+
+impl Drop for MemoryGauge {
+    fn drop(&mut self) {
+        if let Some(t) = &self.tracker {
+            t.sub(self.bytes);
+        }
+    }
+}
+
+/// Human-readable size using binary units, e.g. `12.3 MiB`; see
+/// `NumberFormat::bytes` for the configurable form.
+pub fn format_bytes(bytes: u64) -> String {
+    parquet_lens_common::NumberFormat::default().bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests_memory {
+    use super::*;
+
+    #[test]
+    fn gauges_track_peak_and_release_on_drop() {
+        let tracker = Arc::new(MemoryTracker::default());
+        {
+            let mut a = MemoryGauge::new(Some(tracker.clone()));
+            let mut b = MemoryGauge::new(Some(tracker.clone()));
+            a.set(100);
+            b.set(50);
+            a.set(20);
+            assert_eq!(tracker.current(), 70);
+        }
+        assert_eq!(tracker.current(), 0);
+        assert_eq!(tracker.peak(), 150);
+    }
+
+    #[test]
+    fn limit_latches_once_exceeded() {
+        let tracker = Arc::new(MemoryTracker::with_limit(100));
+        let mut gauge = MemoryGauge::new(Some(tracker.clone()));
+        gauge.set(80);
+        assert!(!gauge.over_limit());
+        gauge.set(120);
+        gauge.set(10);
+        assert!(gauge.over_limit());
+        assert!(!MemoryGauge::new(None).over_limit());
+    }
+
+    #[test]
+    fn formats_binary_units() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
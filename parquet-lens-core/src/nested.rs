@@ -1,11 +1,20 @@
-use bytes::Bytes;
-use memmap2::Mmap;
-use parquet::file::reader::{FileReader, SerializedFileReader};
+use crate::handle::{FileHandle, HandleRegistry};
+use crate::options::ScanOptions;
+use crate::profile::cardinality::{CardinalityEstimate, HllEstimator};
+use crate::profile::frequency::{FrequencyCounter, FrequencyResult};
+use crate::profile::full_scan::{
+    hashable_array, projection, read_row_group, scan_pool, track_value,
+};
+use crate::profile::histogram::LengthBuckets;
+use crate::progress::row_group_totals;
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::DataType;
 use parquet_lens_common::{ParquetLensError, Result};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NestedColumnProfile {
     pub column_name: String,
     pub nesting_depth: usize,
@@ -18,11 +27,12 @@ pub struct NestedColumnProfile {
 }
 
 pub fn profile_nested_columns(path: &Path) -> Result<Vec<NestedColumnProfile>> {
-    let file = std::fs::File::open(path)?;
-    let mmap: Mmap = unsafe { Mmap::map(&file)? };
-    let bytes = Bytes::copy_from_slice(&mmap);
-    let reader = SerializedFileReader::new(bytes).map_err(ParquetLensError::Parquet)?;
-    let meta = reader.metadata();
+    profile_nested_columns_with_handle(&HandleRegistry::global().get(path)?)
+}
+
+#[tracing::instrument(name = "nested_profile", skip_all, fields(path = %handle.path().display()))]
+pub fn profile_nested_columns_with_handle(handle: &FileHandle) -> Result<Vec<NestedColumnProfile>> {
+    let meta = handle.metadata();
     let schema = meta.file_metadata().schema_descr();
     let mut profiles = Vec::new();
     for i in 0..schema.num_columns() {
@@ -53,3 +63,355 @@ pub fn profile_nested_columns(path: &Path) -> Result<Vec<NestedColumnProfile>> {
     }
     Ok(profiles)
 }
+
+/// Value-level shape of the nested columns, read from their data: how long
+/// lists run, which keys maps carry and how often struct fields are null.
+/// Paths are dotted field names; `[]` steps into list items and `.value` into
+/// map values, e.g. `orders[].items[]` or `attrs.value.source`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NestedDataProfile {
+    pub lists: Vec<ListLengthProfile>,
+    pub maps: Vec<MapKeyProfile>,
+    pub struct_fields: Vec<StructFieldNulls>,
+}
+
+impl NestedDataProfile {
+    pub fn is_empty(&self) -> bool {
+        self.lists.is_empty() && self.maps.is_empty() && self.struct_fields.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListLengthProfile {
+    pub path: String,
+    pub count: u64, // non-null lists
+    pub null_count: u64,
+    pub empty_count: u64,
+    pub min_length: usize,
+    pub max_length: usize,
+    pub mean_length: f64,
+    /// (upper bound, count) per power-of-two length bucket, as in `BinaryProfile`.
+    pub length_distribution: Vec<(usize, u64)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapKeyProfile {
+    pub path: String,
+    pub count: u64, // non-null maps
+    pub null_count: u64,
+    pub entry_count: u64,
+    pub key_cardinality: CardinalityEstimate,
+    pub top_keys: Option<FrequencyResult>, // None when too many distinct keys to count
+}
+
+/// Nulls of one struct field, out of the rows where its parent struct is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructFieldNulls {
+    pub path: String,
+    pub parent_rows: u64,
+    pub null_count: u64,
+    pub null_percentage: f64,
+}
+
+pub fn profile_nested_data(path: &Path, opts: &ScanOptions) -> Result<NestedDataProfile> {
+    profile_nested_data_with_handle(&HandleRegistry::global().get(path)?, opts)
+}
+
+/// Reads every top-level list, map and struct column of the file, one task
+/// per row group, and folds their values into a `NestedDataProfile`.
+#[tracing::instrument(name = "nested_data", skip_all, fields(path = %handle.path().display()))]
+pub fn profile_nested_data_with_handle(
+    handle: &FileHandle,
+    opts: &ScanOptions,
+) -> Result<NestedDataProfile> {
+    let meta = handle.arrow_metadata()?;
+    let nested: Vec<String> = meta
+        .schema()
+        .fields()
+        .iter()
+        .filter(|f| f.data_type().is_nested())
+        .map(|f| f.name().clone())
+        .collect();
+    if nested.is_empty() {
+        return Ok(NestedDataProfile::default());
+    }
+    let (mask, fields) = projection(&meta, Some(&nested));
+    let rgs: Vec<usize> = (0..meta.metadata().num_row_groups()).collect();
+    let progress = opts.start_progress("nested", row_group_totals(meta.metadata(), &rgs));
+    let pool = scan_pool(opts.threads)?;
+    let state = pool.install(|| {
+        rgs.par_iter()
+            .map(|&rg| -> Result<NestedState> {
+                let mut state = NestedState::default();
+                let mut gauge = opts.gauge();
+                progress.enter_row_group(&handle.path().to_string_lossy(), rg);
+                let reader =
+                    read_row_group(handle, &meta, mask.clone(), rg, None, opts.batch_size)?;
+                for batch in reader {
+                    opts.check_cancelled()?;
+                    let batch = batch.map_err(|e| {
+                        ParquetLensError::corrupt(
+                            format!("{} row group {rg}", handle.path().display()),
+                            e,
+                        )
+                    })?;
+                    for (field, array) in fields.iter().zip(batch.columns()) {
+                        state.walk(field.name(), array.as_ref())?;
+                    }
+                    gauge.set(batch.get_array_memory_size() + state.approx_bytes());
+                    progress.advance_rows(batch.num_rows() as u64);
+                }
+                Ok(state)
+            })
+            .try_reduce(NestedState::default, |a, b| Ok(a.merge(b)))
+    })?;
+    Ok(state.finish())
+}
+
+struct ListAcc {
+    count: u64,
+    nulls: u64,
+    empty: u64,
+    min_len: usize,
+    max_len: usize,
+    total_len: u64,
+    buckets: LengthBuckets,
+}
+
+impl ListAcc {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            nulls: 0,
+            empty: 0,
+            min_len: usize::MAX,
+            max_len: 0,
+            total_len: 0,
+            buckets: LengthBuckets::new(),
+        }
+    }
+    fn add(&mut self, len: Option<usize>) {
+        let Some(len) = len else {
+            self.nulls += 1;
+            return;
+        };
+        self.count += 1;
+        if len == 0 {
+            self.empty += 1;
+        }
+        self.min_len = self.min_len.min(len);
+        self.max_len = self.max_len.max(len);
+        self.total_len += len as u64;
+        self.buckets.add(len);
+    }
+    fn merge(&mut self, other: Self) {
+        self.count += other.count;
+        self.nulls += other.nulls;
+        self.empty += other.empty;
+        self.min_len = self.min_len.min(other.min_len);
+        self.max_len = self.max_len.max(other.max_len);
+        self.total_len += other.total_len;
+        self.buckets.merge(&other.buckets);
+    }
+}
+
+struct MapAcc {
+    count: u64,
+    nulls: u64,
+    entries: u64,
+    hll: HllEstimator,
+    keys: FrequencyCounter,
+}
+
+impl MapAcc {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            nulls: 0,
+            entries: 0,
+            hll: HllEstimator::new(),
+            keys: FrequencyCounter::new(),
+        }
+    }
+    fn merge(&mut self, other: Self) {
+        self.count += other.count;
+        self.nulls += other.nulls;
+        self.entries += other.entries;
+        self.hll.merge(&other.hll);
+        self.keys.merge(other.keys);
+    }
+}
+
+/// Accumulators per nested path, in the order the paths were first seen.
+#[derive(Default)]
+struct NestedState {
+    lists: Vec<(String, ListAcc)>,
+    maps: Vec<(String, MapAcc)>,
+    fields: Vec<(String, (u64, u64))>, // (parent rows, nulls)
+}
+
+// the accumulator for `path`, created on first use
+fn slot<'a, T>(
+    entries: &'a mut Vec<(String, T)>,
+    path: &str,
+    new: impl FnOnce() -> T,
+) -> &'a mut T {
+    let i = match entries.iter().position(|(p, _)| p == path) {
+        Some(i) => i,
+        None => {
+            entries.push((path.to_string(), new()));
+            entries.len() - 1
+        }
+    };
+    &mut entries[i].1
+}
+
+impl NestedState {
+    fn walk(&mut self, path: &str, array: &dyn Array) -> Result<()> {
+        match array.data_type() {
+            DataType::List(_) => {
+                let a = array.as_list::<i32>();
+                let offsets = a.value_offsets();
+                let acc = slot(&mut self.lists, path, ListAcc::new);
+                for i in 0..a.len() {
+                    acc.add(a.is_valid(i).then(|| a.value_length(i) as usize));
+                }
+                let (start, end) = (offsets[0] as usize, offsets[a.len()] as usize);
+                self.walk(&format!("{path}[]"), &a.values().slice(start, end - start))?;
+            }
+            DataType::LargeList(_) => {
+                let a = array.as_list::<i64>();
+                let offsets = a.value_offsets();
+                let acc = slot(&mut self.lists, path, ListAcc::new);
+                for i in 0..a.len() {
+                    acc.add(a.is_valid(i).then(|| a.value_length(i) as usize));
+                }
+                let (start, end) = (offsets[0] as usize, offsets[a.len()] as usize);
+                self.walk(&format!("{path}[]"), &a.values().slice(start, end - start))?;
+            }
+            DataType::FixedSizeList(_, size) => {
+                let a = array.as_fixed_size_list();
+                let acc = slot(&mut self.lists, path, ListAcc::new);
+                for i in 0..a.len() {
+                    acc.add(a.is_valid(i).then_some(*size as usize));
+                }
+                let size = *size as usize;
+                let values = a.values().slice(a.offset() * size, a.len() * size);
+                self.walk(&format!("{path}[]"), &values)?;
+            }
+            DataType::Map(_, _) => {
+                let a = array.as_map();
+                let offsets = a.value_offsets();
+                let (start, end) = (offsets[0] as usize, offsets[a.len()] as usize);
+                let keys = hashable_array(&a.keys().slice(start, end - start))?;
+                let acc = slot(&mut self.maps, path, MapAcc::new);
+                for i in 0..a.len() {
+                    if a.is_valid(i) {
+                        acc.count += 1;
+                        acc.entries += a.value_length(i) as u64;
+                    } else {
+                        acc.nulls += 1;
+                    }
+                }
+                for row in (0..keys.len()).filter(|&r| keys.is_valid(r)) {
+                    track_value(keys.as_ref(), row, &mut acc.hll, &mut acc.keys);
+                }
+                self.walk(
+                    &format!("{path}.value"),
+                    &a.values().slice(start, end - start),
+                )?;
+            }
+            DataType::Struct(fields) => {
+                let a = array.as_struct();
+                let parent_rows = (0..a.len()).filter(|&i| a.is_valid(i)).count() as u64;
+                for (field, child) in fields.iter().zip(a.columns()) {
+                    let child_path = format!("{path}.{}", field.name());
+                    let nulls = (0..a.len())
+                        .filter(|&i| a.is_valid(i) && child.is_null(i))
+                        .count() as u64;
+                    let acc = slot(&mut self.fields, &child_path, || (0, 0));
+                    acc.0 += parent_rows;
+                    acc.1 += nulls;
+                    self.walk(&child_path, child.as_ref())?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for (path, acc) in other.lists {
+            slot(&mut self.lists, &path, ListAcc::new).merge(acc);
+        }
+        for (path, acc) in other.maps {
+            slot(&mut self.maps, &path, MapAcc::new).merge(acc);
+        }
+        for (path, (rows, nulls)) in other.fields {
+            let acc = slot(&mut self.fields, &path, || (0, 0));
+            acc.0 += rows;
+            acc.1 += nulls;
+        }
+        self
+    }
+
+    fn approx_bytes(&self) -> usize {
+        self.maps
+            .iter()
+            .map(|(_, m)| m.hll.approx_bytes() + m.keys.approx_bytes())
+            .sum::<usize>()
+            + self.lists.len() * std::mem::size_of::<ListAcc>()
+    }
+
+    fn finish(self) -> NestedDataProfile {
+        let lists = self
+            .lists
+            .into_iter()
+            .map(|(path, acc)| ListLengthProfile {
+                path,
+                count: acc.count,
+                null_count: acc.nulls,
+                empty_count: acc.empty,
+                min_length: if acc.count > 0 { acc.min_len } else { 0 },
+                max_length: acc.max_len,
+                mean_length: if acc.count > 0 {
+                    acc.total_len as f64 / acc.count as f64
+                } else {
+                    0.0
+                },
+                length_distribution: acc.buckets.finish(),
+            })
+            .collect();
+        let maps = self
+            .maps
+            .into_iter()
+            .map(|(path, acc)| MapKeyProfile {
+                path,
+                count: acc.count,
+                null_count: acc.nulls,
+                entry_count: acc.entries,
+                key_cardinality: acc.hll.estimate(),
+                top_keys: (!acc.keys.overflowed()).then(|| acc.keys.top_n(20)),
+            })
+            .collect();
+        let struct_fields = self
+            .fields
+            .into_iter()
+            .map(|(path, (parent_rows, null_count))| StructFieldNulls {
+                path,
+                parent_rows,
+                null_count,
+                null_percentage: if parent_rows > 0 {
+                    null_count as f64 / parent_rows as f64 * 100.0
+                } else {
+                    0.0
+                },
+            })
+            .collect();
+        NestedDataProfile {
+            lists,
+            maps,
+            struct_fields,
+        }
+    }
+}
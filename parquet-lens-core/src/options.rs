@@ -0,0 +1,108 @@
+use crate::memory::{MemoryGauge, MemoryTracker};
+use crate::profile::{HistogramScale, DEFAULT_HLL_PRECISION};
+use crate::progress::{Progress, ProgressReporter, ProgressSink};
+use crate::quarantine::QuarantineLog;
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReader;
+use parquet_lens_common::config::ProfilingConfig;
+use parquet_lens_common::ParquetLensError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Throughput knobs shared by the scanning entry points (profile, sample,
+/// filter, duplicates), plus optional memory and progress reporting, a
+/// quarantine log that makes full scans skip row groups that fail to decode,
+/// and a cancel flag the caller can set to stop a scan between batches.
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    pub batch_size: usize,
+    pub threads: Option<usize>, // local reader concurrency; None = all cores
+    pub prefetch_depth: usize,  // batches/row groups read ahead of the consumer
+    pub memory: Option<Arc<MemoryTracker>>, // receives approximate peak usage when set
+    pub progress: Option<Arc<dyn ProgressSink>>,
+    pub quarantine: Option<Arc<QuarantineLog>>,
+    pub cancel: Option<Arc<AtomicBool>>, // scans fail with `Cancelled` once set
+    pub histogram_scale: HistogramScale, // spacing of full-scan histogram bins
+    pub hll_precision: u8,               // log2 of the registers per distinct-count sketch
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 65536,
+            threads: None,
+            prefetch_depth: 4,
+            memory: None,
+            progress: None,
+            quarantine: None,
+            cancel: None,
+            histogram_scale: HistogramScale::Linear,
+            hll_precision: DEFAULT_HLL_PRECISION,
+        }
+    }
+}
+
+impl ScanOptions {
+    pub fn from_config(cfg: &ProfilingConfig) -> Self {
+        Self {
+            batch_size: cfg.batch_size.max(1),
+            threads: cfg.threads,
+            prefetch_depth: cfg.prefetch_depth,
+            memory: cfg
+                .memory_limit_bytes
+                .map(|limit| Arc::new(MemoryTracker::with_limit(limit))),
+            progress: None,
+            quarantine: None,
+            cancel: None,
+            histogram_scale: if cfg.histogram_log_scale {
+                HistogramScale::Log
+            } else {
+                HistogramScale::Linear
+            },
+            hll_precision: cfg.hll_precision,
+        }
+    }
+
+    /// New gauge reporting to `memory`; a no-op when no tracker is attached.
+    pub fn gauge(&self) -> MemoryGauge {
+        MemoryGauge::new(self.memory.clone())
+    }
+
+    pub(crate) fn start_progress(
+        &self,
+        operation: &'static str,
+        total: Progress,
+    ) -> ProgressReporter {
+        ProgressReporter::start(self.progress.clone(), operation, total)
+    }
+
+    /// `Err(Cancelled)` once the cancel flag is set; scans call this per batch.
+    pub(crate) fn check_cancelled(&self) -> parquet_lens_common::Result<()> {
+        match &self.cancel {
+            Some(c) if c.load(Ordering::Relaxed) => Err(ParquetLensError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Decodes batches on a background thread, keeping up to `depth` of them ready so
+/// reads overlap with whatever the consumer does per batch. `depth == 0` reads inline.
+pub fn prefetch_batches(
+    reader: ParquetRecordBatchReader,
+    depth: usize,
+) -> Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>> + Send> {
+    if depth == 0 {
+        return Box::new(reader);
+    }
+    let (tx, rx) = std::sync::mpsc::sync_channel(depth);
+    std::thread::spawn(move || {
+        for batch in reader {
+            // receiver dropped: consumer stopped early (e.g. hit a row limit)
+            if tx.send(batch).is_err() {
+                break;
+            }
+        }
+    });
+    Box::new(rx.into_iter())
+}
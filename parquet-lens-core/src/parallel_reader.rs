@@ -1,10 +1,12 @@
-use crate::reader::open_parquet_file;
+use crate::progress::{Progress, ProgressReporter, ProgressSink};
+use crate::reader::{open_parquet_auto, open_parquet_file};
 use crate::scanner::ParquetFilePath;
-use crate::schema::{extract_schema, ColumnSchema};
+use crate::schema::{extract_schema, schema_from_metadata, ColumnSchema};
 use parquet_lens_common::Result;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatasetProfile {
@@ -26,26 +28,110 @@ pub struct FileProfile {
 }
 
 pub fn read_metadata_parallel(paths: &[ParquetFilePath]) -> Result<DatasetProfile> {
-    let results: Vec<Result<FileProfile>> = paths
+    read_metadata_parallel_with_progress(paths, None)
+}
+
+/// `read_metadata_parallel` reporting each footer read as `"metadata"` progress:
+/// the file's rows and size once it has been read.
+#[tracing::instrument(name = "read_metadata", skip_all, fields(files = paths.len()))]
+pub fn read_metadata_parallel_with_progress(
+    paths: &[ParquetFilePath],
+    progress: Option<Arc<dyn ProgressSink>>,
+) -> Result<DatasetProfile> {
+    let total_bytes = match progress {
+        Some(_) => paths
+            .iter()
+            .filter_map(|p| std::fs::metadata(&p.path).ok())
+            .map(|m| m.len())
+            .sum(),
+        None => 0,
+    };
+    let reporter = ProgressReporter::start(
+        progress,
+        "metadata",
+        Progress {
+            rows: 0,
+            bytes: total_bytes,
+        },
+    );
+    let entries: Vec<Result<FileEntry>> = paths
         .par_iter()
         .map(|pf| {
-            let (info, _meta) = open_parquet_file(&pf.path)?;
-            Ok(FileProfile {
+            let entry = read_file_entry(pf)?;
+            reporter.advance(
+                entry.profile.row_count.max(0) as u64,
+                entry.profile.file_size,
+            );
+            Ok(entry)
+        })
+        .collect();
+    assemble_dataset(paths, entries)
+}
+
+/// `read_metadata_parallel` for object-store and HTTP URIs: footers are fetched
+/// concurrently through the storage backend of each URI.
+pub async fn read_metadata_remote(
+    paths: &[ParquetFilePath],
+    s3_endpoint: Option<&str>,
+) -> Result<DatasetProfile> {
+    let entries = futures::future::join_all(paths.iter().map(|pf| async move {
+        let uri = pf.path.to_string_lossy();
+        let (info, meta) = open_parquet_auto(&uri, s3_endpoint).await?;
+        Ok(FileEntry {
+            profile: FileProfile {
                 path: info.path,
                 row_count: info.row_count,
                 row_group_count: info.row_group_count,
                 file_size: info.file_size,
                 created_by: info.created_by,
-            })
+            },
+            schema: schema_from_metadata(&meta),
         })
-        .collect();
+    }))
+    .await;
+    assemble_dataset(paths, entries)
+}
 
-    let mut files = Vec::with_capacity(results.len());
+/// Per-file footer summary plus schema; the unit that the profile cache stores.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct FileEntry {
+    pub profile: FileProfile,
+    pub schema: Vec<ColumnSchema>,
+}
+
+pub(crate) fn read_file_entry(pf: &ParquetFilePath) -> Result<FileEntry> {
+    let (info, _meta) = open_parquet_file(&pf.path)?;
+    Ok(FileEntry {
+        profile: FileProfile {
+            path: info.path,
+            row_count: info.row_count,
+            row_group_count: info.row_group_count,
+            file_size: info.file_size,
+            created_by: info.created_by,
+        },
+        // schema failures only disable the consistency check for this file
+        schema: extract_schema(&pf.path).unwrap_or_default(),
+    })
+}
+
+/// Combines per-file entries (in `paths` order) into a dataset profile.
+pub(crate) fn assemble_dataset(
+    paths: &[ParquetFilePath],
+    entries: Vec<Result<FileEntry>>,
+) -> Result<DatasetProfile> {
+    let mut files = Vec::with_capacity(entries.len());
+    let mut schemas: Vec<Option<Vec<ColumnSchema>>> = Vec::with_capacity(entries.len());
     let mut errors = Vec::new();
-    for r in results {
+    for r in entries {
         match r {
-            Ok(fp) => files.push(fp),
-            Err(e) => errors.push(e),
+            Ok(entry) => {
+                files.push(entry.profile);
+                schemas.push(Some(entry.schema));
+            }
+            Err(e) => {
+                errors.push(e);
+                schemas.push(None);
+            }
         }
     }
     if files.is_empty() && !errors.is_empty() {
@@ -55,19 +141,15 @@ pub fn read_metadata_parallel(paths: &[ParquetFilePath]) -> Result<DatasetProfil
     let total_rows = files.iter().map(|f| f.row_count).sum();
     let total_bytes = files.iter().map(|f| f.file_size).sum();
 
-    let combined_schema = if !paths.is_empty() {
-        extract_schema(&paths[0].path).unwrap_or_default()
-    } else {
-        Vec::new()
-    };
+    let combined_schema = schemas.first().cloned().flatten().unwrap_or_default();
 
     // check schema consistency across all files vs first file
     let mut schema_inconsistencies = Vec::new();
     if paths.len() > 1 {
         let ref_col_names: std::collections::HashSet<&str> =
             combined_schema.iter().map(|c| c.name.as_str()).collect(); // O(1) lookup
-        for pf in &paths[1..] {
-            if let Ok(other_schema) = extract_schema(&pf.path) {
+        for (pf, other_schema) in paths[1..].iter().zip(&schemas[1..]) {
+            if let Some(other_schema) = other_schema.as_ref().filter(|s| !s.is_empty()) {
                 let other_names: std::collections::HashSet<&str> =
                     other_schema.iter().map(|c| c.name.as_str()).collect(); // O(1) lookup
                 for &name in &ref_col_names {
@@ -0,0 +1,240 @@
+use super::full_scan::{corrupt_detail, projection, ColumnProfileResult, ScanState};
+use crate::chunk_cache::{chunk_cache, CachedObjectReader};
+use crate::options::ScanOptions;
+use crate::progress::row_group_totals;
+use crate::quarantine::QuarantinedRowGroup;
+use crate::remote::{remote_config, store_err, ObjectStoreReader};
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectMeta, ObjectStore};
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
+use parquet::arrow::async_reader::ParquetObjectReader;
+use parquet::arrow::ParquetRecordBatchStreamBuilder;
+use parquet_lens_common::{ParquetLensError, Result};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// Resolves `s3://`, `gs://`, Azure (`az://`, `abfs://`, ...) or local paths to an
+/// object store plus the object's location. Credentials come from the environment
+/// the same way the SDK readers pick them up. Each cloud scheme needs its cargo
+/// feature (`s3`, `gcs`, `azure`).
+pub fn object_store_for_uri(
+    uri: &str,
+    s3_endpoint: Option<&str>,
+) -> Result<(Arc<dyn ObjectStore>, ObjectPath)> {
+    let mut config = remote_config();
+    if let Some(ep) = s3_endpoint {
+        config.s3.endpoint_url = Some(ep.to_string());
+    }
+    let (reader, location) = ObjectStoreReader::for_uri(uri, &config)?;
+    Ok((reader.store(), location))
+}
+
+/// Async full scan over any object store URI. Row groups are fetched and decoded
+/// as independent streams, at most `opts.prefetch_depth` at a time, so memory stays
+/// bounded by a handful of row groups while downloads overlap with decoding.
+pub async fn profile_columns_async(
+    uri: &str,
+    columns: Option<&[String]>,
+    histogram_bins: usize,
+    opts: &ScanOptions,
+    s3_endpoint: Option<&str>,
+) -> Result<Vec<ColumnProfileResult>> {
+    profile_columns_async_with_timeout(uri, columns, histogram_bins, None, opts, s3_endpoint).await
+}
+
+/// `profile_columns_async` that stops reading once `timeout_secs` have passed,
+/// marking the results `truncated`, like `profile_columns_with_timeout`.
+pub async fn profile_columns_async_with_timeout(
+    uri: &str,
+    columns: Option<&[String]>,
+    histogram_bins: usize,
+    timeout_secs: Option<u64>,
+    opts: &ScanOptions,
+    s3_endpoint: Option<&str>,
+) -> Result<Vec<ColumnProfileResult>> {
+    let (store, location) = object_store_for_uri(uri, s3_endpoint)?;
+    let deadline =
+        timeout_secs.map(|s| std::time::Instant::now() + std::time::Duration::from_secs(s));
+    scan_object(
+        store,
+        &location,
+        uri,
+        columns,
+        histogram_bins,
+        deadline,
+        opts,
+    )
+    .await
+}
+
+/// Same as `profile_columns_async` for callers that already hold a store handle.
+#[tracing::instrument(skip_all, fields(location = %location))]
+pub async fn profile_object(
+    store: Arc<dyn ObjectStore>,
+    location: &ObjectPath,
+    columns: Option<&[String]>,
+    histogram_bins: usize,
+    opts: &ScanOptions,
+) -> Result<Vec<ColumnProfileResult>> {
+    let uri = format!("{store}/{location}");
+    scan_object(store, location, &uri, columns, histogram_bins, None, opts).await
+}
+
+/// Up to `max_rows` rows from the first row group of a remote file, for the
+/// data preview. Only the footer and that row group's chunks are fetched.
+pub async fn preview_object(
+    uri: &str,
+    max_rows: usize,
+    s3_endpoint: Option<&str>,
+) -> Result<(SchemaRef, Vec<RecordBatch>)> {
+    let (store, location) = object_store_for_uri(uri, s3_endpoint)?;
+    let object_meta = head(&store, &location, uri).await?;
+    let reader = CachedObjectReader::new(
+        ParquetObjectReader::new(store, object_meta.clone()),
+        uri,
+        &object_meta,
+        chunk_cache(),
+    );
+    let builder = ParquetRecordBatchStreamBuilder::new(reader)
+        .await
+        .map_err(ParquetLensError::Parquet)?;
+    let schema = builder.schema().clone();
+    if builder.metadata().num_row_groups() == 0 || max_rows == 0 {
+        return Ok((schema, Vec::new()));
+    }
+    let batches = builder
+        .with_row_groups(vec![0])
+        .with_batch_size(max_rows)
+        .with_limit(max_rows)
+        .build()
+        .map_err(ParquetLensError::Parquet)?
+        .try_collect()
+        .await
+        .map_err(ParquetLensError::Parquet)?;
+    Ok((schema, batches))
+}
+
+// `uri` keys the object's byte ranges in the chunk cache
+async fn scan_object(
+    store: Arc<dyn ObjectStore>,
+    location: &ObjectPath,
+    uri: &str,
+    columns: Option<&[String]>,
+    histogram_bins: usize,
+    deadline: Option<std::time::Instant>,
+    opts: &ScanOptions,
+) -> Result<Vec<ColumnProfileResult>> {
+    let batch_size = opts.batch_size;
+    let object_meta = head(&store, location, uri).await?;
+    let mut reader = CachedObjectReader::new(
+        ParquetObjectReader::new(store, object_meta.clone()),
+        uri,
+        &object_meta,
+        chunk_cache(),
+    );
+    let meta = ArrowReaderMetadata::load_async(&mut reader, ArrowReaderOptions::default())
+        .await
+        .map_err(ParquetLensError::Parquet)?;
+    let (mask, fields) = projection(&meta, columns);
+    let num_rgs = meta.metadata().num_row_groups();
+    let rgs: Vec<usize> = (0..num_rgs).collect();
+    let progress =
+        Arc::new(opts.start_progress("profile", row_group_totals(meta.metadata(), &rgs)));
+
+    let tasks = rgs.into_iter().map(|rg| {
+        let reader = reader.clone();
+        let progress = progress.clone();
+        let location = location.to_string();
+        let meta = meta.clone();
+        let mask = mask.clone();
+        let fields = fields.clone();
+        let mut state = ScanState::new(&fields, opts.hll_precision).with_gauge(opts.gauge());
+        let mut batch_gauge = opts.gauge();
+        let quarantine = opts.quarantine.clone();
+        let cancel = opts.cancel.clone();
+        let hll_precision = opts.hll_precision;
+        let rows = meta.metadata().row_group(rg).num_rows();
+        tokio::spawn(async move {
+            let scanned = async {
+                // row groups queued past the deadline aren't fetched at all
+                if deadline.is_some_and(|dl| std::time::Instant::now() >= dl) {
+                    state.truncated = true;
+                    return Ok(());
+                }
+                progress.enter_row_group(&location, rg);
+                let mut stream = ParquetRecordBatchStreamBuilder::new_with_metadata(reader, meta)
+                    .with_projection(mask)
+                    .with_row_groups(vec![rg])
+                    .with_batch_size(batch_size)
+                    .build()
+                    .map_err(|e| {
+                        ParquetLensError::corrupt(format!("{location} row group {rg}"), e)
+                    })?;
+                while let Some(batch) = stream.next().await {
+                    if deadline.is_some_and(|dl| std::time::Instant::now() >= dl) {
+                        state.truncated = true;
+                        break;
+                    }
+                    if cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed)) {
+                        return Err(ParquetLensError::Cancelled);
+                    }
+                    let batch = batch.map_err(|e| {
+                        ParquetLensError::corrupt(format!("{location} row group {rg}"), e)
+                    })?;
+                    batch_gauge.set(batch.get_array_memory_size());
+                    state.add_batch(&batch);
+                    progress.advance_rows(batch.num_rows() as u64);
+                }
+                Ok::<_, ParquetLensError>(())
+            }
+            .await;
+            match (scanned, quarantine) {
+                (Ok(()), _) => Ok(state),
+                // columns aren't probed one by one here: each retry is another round trip
+                (Err(e), Some(log)) if e.code() == "corrupt" => {
+                    log.record(QuarantinedRowGroup {
+                        file: location,
+                        row_group: rg,
+                        column: None,
+                        rows,
+                        error: corrupt_detail(&e),
+                    });
+                    Ok(ScanState::new(&fields, hll_precision))
+                }
+                (Err(e), _) => Err(e),
+            }
+        })
+    });
+    let mut results = futures::stream::iter(tasks).buffer_unordered(opts.prefetch_depth.max(1));
+    let mut state = ScanState::new(&fields, opts.hll_precision);
+    while let Some(joined) = results.next().await {
+        let part = joined.map_err(|e| ParquetLensError::Other(format!("scan task: {e}")))??;
+        state = state.merge(part);
+    }
+    Ok(state.finish(&fields, histogram_bins, opts.histogram_scale))
+}
+
+// pre-signed URLs refuse HEAD, so their size comes from a one-byte GET
+async fn head(
+    store: &Arc<dyn ObjectStore>,
+    location: &ObjectPath,
+    uri: &str,
+) -> Result<ObjectMeta> {
+    #[cfg(feature = "http")]
+    if crate::http_reader::is_presigned_url(uri) {
+        let (size, _, e_tag) = crate::http_reader::http_object_metadata(uri).await?;
+        return Ok(ObjectMeta {
+            location: location.clone(),
+            last_modified: Default::default(), // unused by the reader; e_tag versions the chunks
+            size: size as usize,
+            e_tag,
+            version: None,
+        });
+    }
+    #[cfg(not(feature = "http"))]
+    let _ = uri;
+    store.head(location).await.map_err(store_err)
+}
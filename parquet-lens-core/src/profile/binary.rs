@@ -0,0 +1,204 @@
+use super::histogram::LengthBuckets;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinaryProfile {
+    pub count: u64,
+    pub empty_count: u64,
+    pub min_length: usize,
+    pub max_length: usize,
+    pub mean_length: f64,
+    /// (upper bound, count) per power-of-two length bucket: 0, 1, 2, 4, 8, ...;
+    /// a value of 5..=8 bytes lands in the 8 bucket.
+    pub length_distribution: Vec<(usize, u64)>,
+    /// (content type, count) sniffed from leading magic bytes, most common first.
+    pub content_types: Vec<(String, u64)>,
+    pub uuid_like_pct: f64,
+    /// Shannon entropy over every byte seen, 0 (constant) to 8 (random or compressed).
+    pub entropy_bits_per_byte: f64,
+}
+
+pub struct BinaryAccumulator {
+    count: u64,
+    empty: u64,
+    min_len: usize,
+    max_len: usize,
+    total_len: u64,
+    length_buckets: LengthBuckets,
+    content_types: std::collections::HashMap<&'static str, u64>,
+    uuid: u64,
+    byte_counts: [u64; 256],
+}
+
+impl BinaryAccumulator {
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            empty: 0,
+            min_len: usize::MAX,
+            max_len: 0,
+            total_len: 0,
+            length_buckets: LengthBuckets::new(),
+            content_types: std::collections::HashMap::new(),
+            uuid: 0,
+            byte_counts: [0; 256],
+        }
+    }
+    pub fn add(&mut self, v: &[u8]) {
+        self.count += 1;
+        let len = v.len();
+        if len == 0 {
+            self.empty += 1;
+        }
+        self.min_len = self.min_len.min(len);
+        self.max_len = self.max_len.max(len);
+        self.total_len += len as u64;
+        self.length_buckets.add(len);
+        *self.content_types.entry(sniff_content_type(v)).or_insert(0) += 1;
+        if is_uuid_like(v) {
+            self.uuid += 1;
+        }
+        for &b in v {
+            self.byte_counts[b as usize] += 1;
+        }
+    }
+    pub fn merge(&mut self, other: Self) {
+        self.count += other.count;
+        self.empty += other.empty;
+        self.min_len = self.min_len.min(other.min_len);
+        self.max_len = self.max_len.max(other.max_len);
+        self.total_len += other.total_len;
+        self.length_buckets.merge(&other.length_buckets);
+        for (kind, n) in other.content_types {
+            *self.content_types.entry(kind).or_insert(0) += n;
+        }
+        self.uuid += other.uuid;
+        for (a, b) in self.byte_counts.iter_mut().zip(other.byte_counts) {
+            *a += b;
+        }
+    }
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.content_types.capacity() * 24
+    }
+    pub fn finish(self) -> BinaryProfile {
+        let n = self.count as f64;
+        let length_distribution = self.length_buckets.finish();
+        let mut content_types: Vec<(String, u64)> = self
+            .content_types
+            .into_iter()
+            .map(|(kind, c)| (kind.to_string(), c))
+            .collect();
+        content_types.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let total_bytes: u64 = self.byte_counts.iter().sum();
+        let entropy_bits_per_byte = if total_bytes > 0 {
+            let t = total_bytes as f64;
+            self.byte_counts
+                .iter()
+                .filter(|&&c| c > 0)
+                .map(|&c| {
+                    let p = c as f64 / t;
+                    -p * p.log2()
+                })
+                .sum()
+        } else {
+            0.0
+        };
+        BinaryProfile {
+            count: self.count,
+            empty_count: self.empty,
+            min_length: if self.min_len == usize::MAX {
+                0
+            } else {
+                self.min_len
+            },
+            max_length: self.max_len,
+            mean_length: if self.count > 0 {
+                self.total_len as f64 / n
+            } else {
+                0.0
+            },
+            length_distribution,
+            content_types,
+            uuid_like_pct: if self.count > 0 {
+                self.uuid as f64 / n * 100.0
+            } else {
+                0.0
+            },
+            entropy_bits_per_byte,
+        }
+    }
+}
+
+impl Default for BinaryAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Content type guessed from a value's magic bytes; values with none are tried
+/// as a protobuf message before falling back to `"unknown"`.
+pub fn sniff_content_type(v: &[u8]) -> &'static str {
+    const MAGIC: &[(&[u8], &str)] = &[
+        (&[0xFF, 0xD8, 0xFF], "jpeg"),
+        (&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A], "png"),
+        (b"GIF8", "gif"),
+        (&[0x1F, 0x8B], "gzip"),
+        (&[0x28, 0xB5, 0x2F, 0xFD], "zstd"),
+        (b"%PDF", "pdf"),
+        (b"PK\x03\x04", "zip"),
+    ];
+    if v.is_empty() {
+        return "empty";
+    }
+    if let Some((_, kind)) = MAGIC.iter().find(|(magic, _)| v.starts_with(magic)) {
+        return kind;
+    }
+    if is_protobuf_like(v) {
+        return "protobuf";
+    }
+    "unknown"
+}
+
+/// 16 bytes carrying an RFC 4122 version (1-8) and variant, as random or
+/// time-based UUIDs stored as FIXED_LEN_BYTE_ARRAY(16) do.
+pub fn is_uuid_like(v: &[u8]) -> bool {
+    v.len() == 16 && (1..=8).contains(&(v[6] >> 4)) && v[8] & 0xC0 == 0x80
+}
+
+/// True when `v` parses end to end as protobuf wire format: a run of tagged
+/// varint, fixed32/64 and length-delimited fields with nothing left over.
+fn is_protobuf_like(v: &[u8]) -> bool {
+    fn varint(v: &[u8], pos: &mut usize) -> Option<u64> {
+        let mut out = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = *v.get(*pos)?;
+            *pos += 1;
+            out |= u64::from(b & 0x7F) << shift;
+            if b & 0x80 == 0 {
+                return Some(out);
+            }
+        }
+        None
+    }
+    let mut pos = 0;
+    while pos < v.len() {
+        let Some(tag) = varint(v, &mut pos) else {
+            return false;
+        };
+        if tag >> 3 == 0 {
+            return false;
+        }
+        let skip = match tag & 7 {
+            0 => varint(v, &mut pos).map(|_| 0),
+            1 => Some(8),
+            2 => varint(v, &mut pos).and_then(|n| usize::try_from(n).ok()),
+            5 => Some(4),
+            _ => None,
+        };
+        match skip.and_then(|n| pos.checked_add(n)) {
+            Some(end) if end <= v.len() => pos = end,
+            _ => return false,
+        }
+    }
+    true
+}
@@ -29,6 +29,11 @@ impl BooleanAccumulator {
             None => self.null_count += 1,
         }
     }
+    pub fn merge(&mut self, other: Self) {
+        self.true_count += other.true_count;
+        self.false_count += other.false_count;
+        self.null_count += other.null_count;
+    }
     pub fn finish(self) -> BooleanProfile {
         let total = self.true_count + self.false_count;
         let true_percentage = if total > 0 {
@@ -1,30 +1,127 @@
+use super::ColumnProfileResult;
 use hyperloglog::HyperLogLog;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CardinalityEstimate {
     pub approximate_distinct: u64,
+    /// Expected relative standard error of `approximate_distinct`, 1.04/sqrt(2^precision).
     pub error_rate: f64,
+    /// log2 of the sketch's register count; 0 for estimates saved before it was recorded.
+    #[serde(default)]
+    pub precision: u8,
+    /// The full-scan sketch behind the estimate, so estimates over different
+    /// files can be unioned with `merge` rather than summed. Left out of
+    /// serialized reports; the profile cache stores it on its own.
+    #[serde(skip)]
+    pub sketch: Option<HllEstimator>,
 }
 
+impl CardinalityEstimate {
+    /// Half-width of the ~95% interval around `approximate_distinct`.
+    pub fn margin(&self) -> u64 {
+        (self.approximate_distinct as f64 * self.error_rate * 2.0).round() as u64
+    }
+
+    /// Folds in the estimate of other rows. With both sketches this is their
+    /// union, so a value seen on both sides counts once; otherwise the counts
+    /// are summed, an upper bound, and the sketch is dropped. Sketches of
+    /// different precisions can't be unioned and are summed too.
+    pub fn merge(&mut self, other: &Self) {
+        match (&mut self.sketch, &other.sketch) {
+            (Some(a), Some(b)) if a.precision() == b.precision() => {
+                a.merge(b);
+                self.approximate_distinct = a.count();
+            }
+            _ => {
+                self.approximate_distinct += other.approximate_distinct;
+                self.error_rate = self.error_rate.max(other.error_rate);
+                self.sketch = None;
+            }
+        }
+    }
+}
+
+/// Dataset-level distinct counts from full scans of each file: every column's
+/// estimates are merged across the files that have it, in first-seen order.
+pub fn union_cardinality<'a>(
+    per_file: impl IntoIterator<Item = &'a [ColumnProfileResult]>,
+) -> Vec<(String, CardinalityEstimate)> {
+    let mut out: Vec<(String, CardinalityEstimate)> = Vec::new();
+    for file in per_file {
+        for r in file {
+            match out.iter_mut().find(|(name, _)| *name == r.column_name) {
+                Some((_, c)) => c.merge(&r.cardinality),
+                None => out.push((r.column_name.clone(), r.cardinality.clone())),
+            }
+        }
+    }
+    out
+}
+
+const HLL_SEED: u128 = 0x7061_7271_7565_745f_6c65_6e73_5f68_6c6c;
+/// 1024 one-byte registers, for a ~3.3% expected error.
+pub const DEFAULT_HLL_PRECISION: u8 = 10;
+/// Precisions the sketch supports: 16 registers (26% error) to 16384 (0.8%).
+pub const HLL_PRECISION_RANGE: std::ops::RangeInclusive<u8> = 4..=14;
+
+/// Expected relative standard error of a sketch with 2^`precision` registers.
+pub fn hll_relative_error(precision: u8) -> f64 {
+    1.04 / ((1u64 << precision) as f64).sqrt()
+}
+
+/// HyperLogLog sketch with a fixed seed, so sketches of the same precision
+/// built by different threads, runs or machines can be merged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HllEstimator {
+    precision: u8,
     hll: HyperLogLog,
 }
 
 impl HllEstimator {
     pub fn new() -> Self {
-        // error rate ~0.8%
+        Self::with_precision(DEFAULT_HLL_PRECISION)
+    }
+    /// 2^`precision` registers, clamped to `HLL_PRECISION_RANGE`.
+    pub fn with_precision(precision: u8) -> Self {
+        let precision = precision.clamp(*HLL_PRECISION_RANGE.start(), *HLL_PRECISION_RANGE.end());
+        // the crate sizes itself as ceil(ln((1.04 / error)^2)); aim between
+        // two integers so exactly `precision` comes out
+        let error = 1.04 / ((precision as f64 - 0.5) / 2.0).exp();
         Self {
-            hll: HyperLogLog::new(0.00813),
+            precision,
+            hll: HyperLogLog::new_deterministic(error, HLL_SEED),
         }
     }
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
     pub fn add_bytes(&mut self, val: &[u8]) {
         self.hll.insert(&val);
     }
+    pub fn merge(&mut self, other: &Self) {
+        self.hll.merge(&other.hll);
+    }
+    pub fn approx_bytes(&self) -> usize {
+        1 << self.precision
+    }
+    pub fn count(&self) -> u64 {
+        self.hll.len().round() as u64
+    }
     pub fn estimate(&self) -> CardinalityEstimate {
         CardinalityEstimate {
-            approximate_distinct: self.hll.len().round() as u64,
-            error_rate: 0.00813,
+            approximate_distinct: self.count(),
+            error_rate: hll_relative_error(self.precision),
+            precision: self.precision,
+            sketch: None,
+        }
+    }
+    /// `estimate` that keeps the sketch for later merging.
+    pub fn into_estimate(self) -> CardinalityEstimate {
+        let estimate = self.estimate();
+        CardinalityEstimate {
+            sketch: Some(self),
+            ..estimate
         }
     }
 }
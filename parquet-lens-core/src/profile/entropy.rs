@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use xxhash_rust::xxh3::xxh3_64;
+
+// values sampled from each row group, and the cap on the merged sample
+const SAMPLE_PER_SLICE: u64 = 4096;
+const MAX_SAMPLE: u64 = 65_536;
+
+// above this share of distinct values a sample is too small for its value
+// frequencies to mean anything, so only the byte distribution is trusted
+const DISTINCT_SHARE_UNRELIABLE: f64 = 0.5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnEntropy {
+    pub sampled_values: u64,
+    pub bytes_per_value: f64,
+    /// Shannon entropy over every sampled byte, 0 (constant) to 8 (random).
+    pub bits_per_byte: f64,
+    /// Estimated information per value: the lower of the value-frequency and
+    /// byte-level entropies, or byte-level alone when most values are distinct.
+    pub bits_per_value: f64,
+    /// Share of the raw value bytes an entropy coder could remove, 0-100.
+    pub compressible_pct: f64,
+}
+
+/// Order-0 entropy over a bounded sample of a column's values, hashed from the
+/// same native bytes the cardinality and frequency trackers see.
+pub struct EntropySampler {
+    byte_counts: [u64; 256],
+    values: HashMap<u64, u64>,
+    sampled: u64,
+    bytes: u64,
+}
+
+impl EntropySampler {
+    pub fn new() -> Self {
+        Self {
+            byte_counts: [0; 256],
+            values: HashMap::new(),
+            sampled: 0,
+            bytes: 0,
+        }
+    }
+    pub fn is_full(&self) -> bool {
+        self.sampled >= SAMPLE_PER_SLICE
+    }
+    pub fn add(&mut self, v: &[u8]) {
+        self.sampled += 1;
+        self.bytes += v.len() as u64;
+        *self.values.entry(xxh3_64(v)).or_insert(0) += 1;
+        for &b in v {
+            self.byte_counts[b as usize] += 1;
+        }
+    }
+    // whole slices are kept or dropped so the sample stays a union of row group prefixes
+    pub fn merge(&mut self, other: Self) {
+        if self.sampled + other.sampled > MAX_SAMPLE {
+            return;
+        }
+        self.sampled += other.sampled;
+        self.bytes += other.bytes;
+        for (hash, n) in other.values {
+            *self.values.entry(hash).or_insert(0) += n;
+        }
+        for (a, b) in self.byte_counts.iter_mut().zip(other.byte_counts) {
+            *a += b;
+        }
+    }
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.values.capacity() * 16
+    }
+    pub fn finish(self) -> Option<ColumnEntropy> {
+        if self.sampled == 0 || self.bytes == 0 {
+            return None;
+        }
+        let n = self.sampled as f64;
+        let bytes_per_value = self.bytes as f64 / n;
+        let bits_per_byte = shannon(self.byte_counts.iter().copied(), self.bytes as f64);
+        let byte_bits = bits_per_byte * bytes_per_value;
+        let bits_per_value = if self.values.len() as f64 / n > DISTINCT_SHARE_UNRELIABLE {
+            byte_bits
+        } else {
+            shannon(self.values.values().copied(), n).min(byte_bits)
+        };
+        let compressible_pct =
+            ((1.0 - bits_per_value / (8.0 * bytes_per_value)) * 100.0).clamp(0.0, 100.0);
+        Some(ColumnEntropy {
+            sampled_values: self.sampled,
+            bytes_per_value,
+            bits_per_byte,
+            bits_per_value,
+            compressible_pct,
+        })
+    }
+}
+
+impl Default for EntropySampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn shannon(counts: impl Iterator<Item = u64>, total: f64) -> f64 {
+    counts
+        .filter(|&c| c > 0)
+        .map(|c| {
+            let p = c as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
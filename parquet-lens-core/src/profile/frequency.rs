@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use xxhash_rust::xxh3::xxh3_64;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FrequencyEntry {
@@ -12,28 +13,159 @@ pub struct FrequencyEntry {
 pub struct FrequencyResult {
     pub top_values: Vec<FrequencyEntry>,
     pub total_count: u64,
+    /// Counts come from a bounded top-k sketch (kept under a memory limit)
+    /// and may overstate how often a value occurs.
+    #[serde(default)]
+    pub approximate: bool,
+    /// Values seen exactly once, when every value was counted exactly.
+    #[serde(default)]
+    pub singletons: Option<u64>,
 }
 
+/// Counts values keyed by a 64-bit hash of their bytes. The display string is
+/// only built the first time a value is seen, and tracking stops altogether
+/// once more than `MAX_TRACKED_VALUES` distinct values turn up, since high
+/// cardinality columns never report a frequency table anyway.
+///
+/// `bound` turns the counter into a space-saving sketch of at most k values:
+/// an unseen value evicts the least counted one and inherits its count.
 pub struct FrequencyCounter {
-    map: HashMap<String, u64>,
+    map: HashMap<u64, (String, u64)>,
     total: u64,
+    overflowed: bool,
+    text_bytes: usize,       // display strings held by `map`
+    capacity: Option<usize>, // Some(k) once bounded
 }
 
+pub const MAX_TRACKED_VALUES: usize = 16_384;
+
 impl FrequencyCounter {
     pub fn new() -> Self {
         Self {
             map: HashMap::new(),
             total: 0,
+            overflowed: false,
+            text_bytes: 0,
+            capacity: None,
         }
     }
     pub fn add(&mut self, val: String) {
-        *self.map.entry(val).or_insert(0) += 1;
+        self.add_hashed(xxh3_64(val.as_bytes()), || val);
+    }
+    /// Counts a value by its precomputed hash; `render` runs only for unseen values.
+    pub fn add_hashed(&mut self, hash: u64, render: impl FnOnce() -> String) {
         self.total += 1;
+        if self.overflowed {
+            return;
+        }
+        if let Some(k) = self.capacity {
+            if let Some(entry) = self.map.get_mut(&hash) {
+                entry.1 += 1;
+                return;
+            }
+            let mut count = 1;
+            if self.map.len() >= k {
+                let (&victim, &(_, min)) = self
+                    .map
+                    .iter()
+                    .min_by_key(|(_, (_, c))| *c)
+                    .expect("a full sketch has entries");
+                let (val, _) = self.map.remove(&victim).expect("victim was just found");
+                self.text_bytes -= val.len();
+                count += min;
+            }
+            let val = render();
+            self.text_bytes += val.len();
+            self.map.insert(hash, (val, count));
+            return;
+        }
+        let text_bytes = &mut self.text_bytes;
+        self.map
+            .entry(hash)
+            .or_insert_with(|| {
+                let val = render();
+                *text_bytes += val.len();
+                (val, 0)
+            })
+            .1 += 1;
+        self.check_overflow();
+    }
+    pub fn merge(&mut self, other: Self) {
+        self.total += other.total;
+        self.overflowed |= other.overflowed;
+        if self.overflowed {
+            self.map = HashMap::new();
+            self.text_bytes = 0;
+            return;
+        }
+        let capacity = match (self.capacity, other.capacity) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        for (hash, (val, count)) in other.map {
+            let text_bytes = &mut self.text_bytes;
+            self.map
+                .entry(hash)
+                .or_insert_with(|| {
+                    *text_bytes += val.len();
+                    (val, 0)
+                })
+                .1 += count;
+        }
+        match capacity {
+            Some(k) => self.bound(k),
+            None => self.check_overflow(),
+        }
+    }
+    /// Keeps only the `k` most counted values and sketches from here on, so
+    /// the counter stays small however many distinct values follow.
+    pub fn bound(&mut self, k: usize) {
+        self.capacity = Some(self.capacity.map_or(k, |c| c.min(k)));
+        if self.map.len() <= k {
+            return;
+        }
+        let mut entries: Vec<(u64, (String, u64))> = self.map.drain().collect();
+        entries.sort_by(|a, b| b.1 .1.cmp(&a.1 .1).then_with(|| a.1 .0.cmp(&b.1 .0)));
+        entries.truncate(k);
+        self.text_bytes = entries.iter().map(|(_, (v, _))| v.len()).sum();
+        self.map = entries.into_iter().collect();
+    }
+    /// True once `bound` has turned the exact counts into a sketch.
+    pub fn is_approximate(&self) -> bool {
+        self.capacity.is_some()
+    }
+    /// True once too many distinct values were seen for counts to be kept.
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+    /// Distinct values counted so far; zero once overflowed.
+    pub fn distinct(&self) -> usize {
+        self.map.len()
+    }
+    /// Values counted exactly once; None once counts stopped being exact.
+    pub fn singletons(&self) -> Option<u64> {
+        if self.overflowed || self.is_approximate() {
+            return None;
+        }
+        Some(self.map.values().filter(|(_, c)| *c == 1).count() as u64)
+    }
+    pub fn approx_bytes(&self) -> usize {
+        self.map.capacity() * std::mem::size_of::<(u64, (String, u64))>() + self.text_bytes
+    }
+    fn check_overflow(&mut self) {
+        if self.map.len() > MAX_TRACKED_VALUES {
+            self.overflowed = true;
+            self.map = HashMap::new(); // release memory; counts are no longer exact
+            self.text_bytes = 0;
+        }
     }
     pub fn top_n(self, n: usize) -> FrequencyResult {
         let total = self.total;
-        let mut entries: Vec<(String, u64)> = self.map.into_iter().collect();
-        entries.sort_by(|a, b| b.1.cmp(&a.1));
+        let approximate = self.is_approximate();
+        let singletons = self.singletons();
+        let mut entries: Vec<(String, u64)> = self.map.into_values().collect();
+        // ties broken by value so the same data always lists in the same order
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         let top_values = entries
             .into_iter()
             .take(n)
@@ -50,6 +182,8 @@ impl FrequencyCounter {
         FrequencyResult {
             top_values,
             total_count: total,
+            approximate,
+            singletons,
         }
     }
 }
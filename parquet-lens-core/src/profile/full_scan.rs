@@ -1,20 +1,35 @@
+use super::binary::BinaryAccumulator;
 use super::boolean::BooleanAccumulator;
 use super::cardinality::HllEstimator;
+use super::entropy::EntropySampler;
 use super::frequency::FrequencyCounter;
-use super::histogram::{build_histogram, HistogramBin};
+use super::histogram::{HistogramBin, HistogramScale};
 use super::numeric::NumericAccumulator;
 use super::string_profiler::StringAccumulator;
 use super::temporal::TemporalAccumulator;
 use super::{
-    BooleanProfile, CardinalityEstimate, FrequencyResult, NumericProfile, StringProfile,
-    TemporalProfile,
+    BinaryProfile, BooleanProfile, CardinalityEstimate, ColumnEntropy, FrequencyResult,
+    NumericProfile, StringProfile, TemporalProfile,
 };
+use crate::handle::{FileHandle, HandleRegistry};
+use crate::memory::MemoryGauge;
+use crate::options::ScanOptions;
+use crate::progress::{row_group_totals, Progress, ProgressReporter};
+use crate::quarantine::{QuarantineLog, QuarantinedRowGroup};
+use crate::sample::RowSampler;
+use crate::scanner::ParquetFilePath;
 use arrow::array::*;
-use arrow::datatypes::{DataType, TimeUnit};
-use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use arrow::datatypes::{DataType, FieldRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder, RowSelection,
+};
+use parquet::arrow::ProjectionMask;
 use parquet_lens_common::{ParquetLensError, Result};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use xxhash_rust::xxh3::xxh3_64;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColumnProfileResult {
@@ -26,6 +41,10 @@ pub struct ColumnProfileResult {
     pub string: Option<StringProfile>,
     pub temporal: Option<TemporalProfile>,
     pub boolean: Option<BooleanProfile>,
+    #[serde(default)]
+    pub binary: Option<BinaryProfile>,
+    #[serde(default)]
+    pub entropy: Option<ColumnEntropy>,
     pub truncated: bool, // true if scan was aborted early by timeout
 }
 
@@ -35,205 +54,621 @@ pub fn profile_columns(
     batch_size: usize,
     histogram_bins: usize,
 ) -> Result<Vec<ColumnProfileResult>> {
-    profile_columns_with_timeout(path, columns, batch_size, histogram_bins, None)
+    let opts = ScanOptions {
+        batch_size,
+        ..ScanOptions::default()
+    };
+    profile_columns_with_timeout(path, columns, histogram_bins, None, &opts)
 }
 
+/// Full scan with an optional wall-clock budget. Row groups are processed
+/// concurrently on a pool of `opts.threads` workers (None = all cores).
 pub fn profile_columns_with_timeout(
     path: &Path,
     columns: Option<&[String]>,
-    batch_size: usize,
     histogram_bins: usize,
     timeout_secs: Option<u64>,
+    opts: &ScanOptions,
 ) -> Result<Vec<ColumnProfileResult>> {
-    let file = std::fs::File::open(path)?;
-    let builder =
-        ParquetRecordBatchReaderBuilder::try_new(file).map_err(ParquetLensError::Parquet)?;
-    let schema = builder.schema().clone();
-    let builder = if let Some(cols) = columns {
-        let indices: Vec<usize> = cols
-            .iter()
-            .filter_map(|c| schema.fields().iter().position(|f| f.name() == c))
-            .collect();
-        // capture parquet_schema ref before consuming builder
-        let mask = parquet::arrow::ProjectionMask::roots(builder.parquet_schema(), indices);
-        builder.with_projection(mask)
-    } else {
-        builder
-    };
-    let reader = builder
-        .with_batch_size(batch_size)
+    profile_columns_with_handle(
+        &HandleRegistry::global().get(path)?,
+        columns,
+        histogram_bins,
+        timeout_secs,
+        opts,
+    )
+}
+
+pub fn profile_columns_with_handle(
+    handle: &FileHandle,
+    columns: Option<&[String]>,
+    histogram_bins: usize,
+    timeout_secs: Option<u64>,
+    opts: &ScanOptions,
+) -> Result<Vec<ColumnProfileResult>> {
+    profile_row_group_subset(
+        handle,
+        columns,
+        None,
+        None,
+        histogram_bins,
+        timeout_secs,
+        opts,
+        "profile",
+    )
+}
+
+/// Builds a rayon pool for scan work; `None` or `Some(0)` uses every core.
+pub(crate) fn scan_pool(threads: Option<usize>) -> Result<rayon::ThreadPool> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.unwrap_or(0))
         .build()
-        .map_err(ParquetLensError::Parquet)?;
+        .map_err(|e| ParquetLensError::Other(format!("thread pool: {e}")))
+}
 
-    let field_names: Vec<String> = reader
-        .schema()
-        .fields()
-        .iter()
-        .map(|f| f.name().clone())
-        .collect();
-    let ncols = field_names.len();
-    let mut hlls: Vec<HllEstimator> = (0..ncols).map(|_| HllEstimator::new()).collect();
-    let mut freq_counters: Vec<FrequencyCounter> =
-        (0..ncols).map(|_| FrequencyCounter::new()).collect();
-    let mut numeric_accs: Vec<Option<NumericAccumulator>> = reader
-        .schema()
-        .fields()
-        .iter()
-        .map(|f| match f.data_type() {
-            DataType::Int8
-            | DataType::Int16
-            | DataType::Int32
-            | DataType::Int64
-            | DataType::UInt8
-            | DataType::UInt16
-            | DataType::UInt32
-            | DataType::UInt64
-            | DataType::Float32
-            | DataType::Float64 => Some(NumericAccumulator::new()),
-            _ => None,
-        })
-        .collect();
-    let mut str_accs: Vec<Option<StringAccumulator>> = reader
-        .schema()
-        .fields()
+/// Profiles `row_groups` (all when None), reading only the rows `rows` selects
+/// when set. Progress is reported under `operation`.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "full_scan", skip_all, fields(path = %handle.path().display(), operation))]
+pub(crate) fn profile_row_group_subset(
+    handle: &FileHandle,
+    columns: Option<&[String]>,
+    row_groups: Option<&[usize]>,
+    rows: Option<&RowSampler>,
+    histogram_bins: usize,
+    timeout_secs: Option<u64>,
+    opts: &ScanOptions,
+    operation: &'static str,
+) -> Result<Vec<ColumnProfileResult>> {
+    let meta = handle.arrow_metadata()?;
+    let (mask, fields) = projection(&meta, columns);
+    let rgs: Vec<usize> = match row_groups {
+        Some(r) => r.to_vec(),
+        None => (0..meta.metadata().num_row_groups()).collect(),
+    };
+    let deadline =
+        timeout_secs.map(|s| std::time::Instant::now() + std::time::Duration::from_secs(s));
+    let progress = opts.start_progress(operation, row_group_totals(meta.metadata(), &rgs));
+    let state = scan_row_groups(
+        handle, &meta, &mask, &fields, &rgs, rows, deadline, opts, &progress,
+    )?;
+    Ok(state.finish(&fields, histogram_bins, opts.histogram_scale))
+}
+
+/// Full scan of every file of a dataset, reported as one `"profile"` operation.
+/// Each file is scanned like `profile_columns_with_timeout` and its accumulators
+/// are folded into one set keyed by column name, so distinct counts, quantiles,
+/// histograms and min/max describe all files rather than the first. A column
+/// missing from some files is profiled over the files that have it; one whose
+/// type differs from its first occurrence only keeps its cardinality there.
+pub fn profile_dataset(
+    paths: &[ParquetFilePath],
+    columns: Option<&[String]>,
+    histogram_bins: usize,
+    timeout_secs: Option<u64>,
+    opts: &ScanOptions,
+) -> Result<Vec<ColumnProfileResult>> {
+    let handles = paths
         .iter()
-        .map(|f| match f.data_type() {
-            DataType::Utf8 | DataType::LargeUtf8 => Some(StringAccumulator::new()),
-            _ => None,
-        })
-        .collect();
-    let mut temporal_accs: Vec<Option<TemporalAccumulator>> = reader
-        .schema()
-        .fields()
+        .map(|p| HandleRegistry::global().get(&p.path))
+        .collect::<Result<Vec<_>>>()?;
+    let files: Vec<(&FileHandle, Option<&RowSampler>)> =
+        handles.iter().map(|h| (h, None)).collect();
+    profile_files(
+        &files,
+        columns,
+        histogram_bins,
+        timeout_secs,
+        opts,
+        "profile",
+    )
+}
+
+/// `profile_dataset` over open handles, each read in full or through its own
+/// row sampler. Progress is reported under `operation`.
+pub(crate) fn profile_files(
+    handles: &[(&FileHandle, Option<&RowSampler>)],
+    columns: Option<&[String]>,
+    histogram_bins: usize,
+    timeout_secs: Option<u64>,
+    opts: &ScanOptions,
+    operation: &'static str,
+) -> Result<Vec<ColumnProfileResult>> {
+    let mut files = Vec::with_capacity(handles.len());
+    for &(handle, rows) in handles {
+        let meta = handle.arrow_metadata()?;
+        let (mask, fields) = projection(&meta, columns);
+        files.push((handle, rows, meta, mask, fields));
+    }
+    // union of the projected columns, in the order they are first seen
+    let mut dataset_fields: Vec<FieldRef> = Vec::new();
+    for (_, _, _, _, fields) in &files {
+        for f in fields {
+            if !dataset_fields.iter().any(|d| d.name() == f.name()) {
+                dataset_fields.push(f.clone());
+            }
+        }
+    }
+    let deadline =
+        timeout_secs.map(|s| std::time::Instant::now() + std::time::Duration::from_secs(s));
+    let total = files
         .iter()
-        .map(|f| match f.data_type() {
-            DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => {
-                Some(TemporalAccumulator::new())
+        .fold(Progress::default(), |acc, (_, _, meta, _, _)| {
+            let rgs: Vec<usize> = (0..meta.metadata().num_row_groups()).collect();
+            let t = row_group_totals(meta.metadata(), &rgs);
+            Progress {
+                rows: acc.rows + t.rows,
+                bytes: acc.bytes + t.bytes,
             }
-            _ => None,
-        })
-        .collect();
-    let mut bool_accs: Vec<Option<BooleanAccumulator>> = reader
-        .schema()
-        .fields()
+        });
+    let progress = opts.start_progress(operation, total);
+    let mut state = ScanState::new(&dataset_fields, opts.hll_precision);
+    for (handle, rows, meta, mask, fields) in &files {
+        let rgs: Vec<usize> = (0..meta.metadata().num_row_groups()).collect();
+        let file_state = scan_row_groups(
+            handle, meta, mask, fields, &rgs, *rows, deadline, opts, &progress,
+        )?;
+        state = state.merge(file_state.align(fields, &dataset_fields));
+        if state.truncated {
+            break;
+        }
+    }
+    Ok(state.finish(&dataset_fields, histogram_bins, opts.histogram_scale))
+}
+
+/// Scans `rgs` of one file on the scan pool, one task per row group, each
+/// filling its own accumulators which are merged once every task has finished.
+#[allow(clippy::too_many_arguments)]
+fn scan_row_groups(
+    handle: &FileHandle,
+    meta: &ArrowReaderMetadata,
+    mask: &ProjectionMask,
+    fields: &[FieldRef],
+    rgs: &[usize],
+    rows: Option<&RowSampler>,
+    deadline: Option<std::time::Instant>,
+    opts: &ScanOptions,
+    progress: &ProgressReporter,
+) -> Result<ScanState> {
+    let pool = scan_pool(opts.threads)?;
+    pool.install(|| {
+        rgs.par_iter()
+            .map(|&rg| -> Result<ScanState> {
+                let mut state = ScanState::new(fields, opts.hll_precision).with_gauge(opts.gauge());
+                let mut batch_gauge = opts.gauge();
+                let location = || format!("{} row group {rg}", handle.path().display());
+                progress.enter_row_group(&handle.path().to_string_lossy(), rg);
+                let selection = rows.map(|r| r.selection(handle, rg)).transpose()?;
+                let scanned =
+                    read_row_group(handle, meta, mask.clone(), rg, selection, opts.batch_size)
+                        .and_then(|reader| {
+                            for batch_result in reader {
+                                if deadline.is_some_and(|dl| std::time::Instant::now() >= dl) {
+                                    state.truncated = true;
+                                    break;
+                                }
+                                opts.check_cancelled()?;
+                                let batch = batch_result
+                                    .map_err(|e| ParquetLensError::corrupt(location(), e))?;
+                                batch_gauge.set(batch.get_array_memory_size());
+                                state.add_batch(&batch);
+                                if rows.is_none() {
+                                    progress.advance_rows(batch.num_rows() as u64);
+                                }
+                            }
+                            // a sampled row group is still read end to end
+                            if rows.is_some() {
+                                let n = meta.metadata().row_group(rg).num_rows();
+                                progress.advance_rows(n.max(0) as u64);
+                            }
+                            Ok(())
+                        });
+                match (scanned, &opts.quarantine) {
+                    (Ok(()), _) => Ok(state),
+                    (Err(e), Some(log)) if e.code() == "corrupt" => {
+                        quarantine_row_group(handle, meta, fields, rg, opts.batch_size, &e, log);
+                        Ok(ScanState::new(fields, opts.hll_precision))
+                    }
+                    (Err(e), _) => Err(e),
+                }
+            })
+            .try_reduce(
+                || ScanState::new(fields, opts.hll_precision),
+                |a, b| Ok(a.merge(b)),
+            )
+    })
+}
+
+pub(crate) fn read_row_group(
+    handle: &FileHandle,
+    meta: &ArrowReaderMetadata,
+    mask: ProjectionMask,
+    rg: usize,
+    selection: Option<RowSelection>,
+    batch_size: usize,
+) -> Result<ParquetRecordBatchReader> {
+    let mut builder = ParquetRecordBatchReaderBuilder::new_with_metadata(
+        std::fs::File::open(handle.path())
+            .map_err(|e| ParquetLensError::io_at(e, handle.path()))?,
+        meta.clone(),
+    )
+    .with_projection(mask)
+    .with_row_groups(vec![rg])
+    .with_batch_size(batch_size);
+    if let Some(selection) = selection {
+        builder = builder.with_row_selection(selection);
+    }
+    builder.build().map_err(|e| {
+        ParquetLensError::corrupt(format!("{} row group {rg}", handle.path().display()), e)
+    })
+}
+
+/// Records row group `rg` as skipped. Each projected column is re-read on its
+/// own so the report can name the ones that fail; when none fails alone the
+/// whole row group is blamed with the original error.
+fn quarantine_row_group(
+    handle: &FileHandle,
+    meta: &ArrowReaderMetadata,
+    fields: &[FieldRef],
+    rg: usize,
+    batch_size: usize,
+    err: &ParquetLensError,
+    log: &QuarantineLog,
+) {
+    let file = handle.path().display().to_string();
+    let rows = meta.metadata().row_group(rg).num_rows();
+    let mut blamed = false;
+    for field in fields {
+        let (mask, _) = projection(meta, Some(std::slice::from_ref(field.name())));
+        let failed = read_row_group(handle, meta, mask, rg, None, batch_size).and_then(|reader| {
+            for batch in reader {
+                batch.map_err(|e| ParquetLensError::corrupt(String::new(), e))?;
+            }
+            Ok(())
+        });
+        if let Err(e) = failed {
+            blamed = true;
+            log.record(QuarantinedRowGroup {
+                file: file.clone(),
+                row_group: rg,
+                column: Some(field.name().clone()),
+                rows,
+                error: corrupt_detail(&e),
+            });
+        }
+    }
+    if !blamed {
+        log.record(QuarantinedRowGroup {
+            file,
+            row_group: rg,
+            column: None,
+            rows,
+            error: corrupt_detail(err),
+        });
+    }
+}
+
+// the decoder's message without the location prefix, which the entry already carries
+pub(crate) fn corrupt_detail(e: &ParquetLensError) -> String {
+    match e.root() {
+        ParquetLensError::Corrupt { detail, .. } => detail.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Projection mask for `columns` (all when None) plus the projected fields,
+/// in file order since that is how the reader yields them.
+pub(crate) fn projection(
+    meta: &ArrowReaderMetadata,
+    columns: Option<&[String]>,
+) -> (ProjectionMask, Vec<FieldRef>) {
+    let schema = meta.schema();
+    let mut indices: Vec<usize> = match columns {
+        Some(cols) => cols
+            .iter()
+            .filter_map(|c| schema.fields().iter().position(|f| f.name() == c))
+            .collect(),
+        None => (0..schema.fields().len()).collect(),
+    };
+    indices.sort_unstable();
+    indices.dedup();
+    let fields = indices
         .iter()
-        .map(|f| match f.data_type() {
-            DataType::Boolean => Some(BooleanAccumulator::new()),
-            _ => None,
-        })
+        .map(|&i| schema.fields()[i].clone())
         .collect();
-    let mut numeric_vals: Vec<Vec<f64>> = (0..ncols).map(|_| Vec::new()).collect();
-    let deadline =
-        timeout_secs.map(|s| std::time::Instant::now() + std::time::Duration::from_secs(s));
-    let mut timed_out = false;
-    let mut reader = reader.peekable();
+    let mask = ProjectionMask::roots(meta.metadata().file_metadata().schema_descr(), indices);
+    (mask, fields)
+}
+
+/// Accumulators for every projected column over one slice of the file.
+pub(crate) struct ScanState {
+    hlls: Vec<HllEstimator>,
+    freq_counters: Vec<FrequencyCounter>,
+    numeric_accs: Vec<Option<NumericAccumulator>>,
+    str_accs: Vec<Option<StringAccumulator>>,
+    temporal_accs: Vec<Option<TemporalAccumulator>>,
+    bool_accs: Vec<Option<BooleanAccumulator>>,
+    binary_accs: Vec<Option<BinaryAccumulator>>,
+    entropy: Vec<EntropySampler>,
+    pub(crate) truncated: bool, // true if any slice hit the deadline
+    low_memory: bool,           // shrunk after the memory limit was hit
+    gauge: MemoryGauge,
+    hll_precision: u8,
+}
+
+// values each frequency sketch keeps once a scan runs over its memory limit
+const LOW_MEMORY_TOP_VALUES: usize = 64;
+
+impl ScanState {
+    pub(crate) fn new(fields: &[FieldRef], hll_precision: u8) -> Self {
+        let ncols = fields.len();
+        Self {
+            hlls: (0..ncols)
+                .map(|_| HllEstimator::with_precision(hll_precision))
+                .collect(),
+            hll_precision,
+            freq_counters: (0..ncols).map(|_| FrequencyCounter::new()).collect(),
+            numeric_accs: fields
+                .iter()
+                .map(|f| match f.data_type() {
+                    DataType::Int8
+                    | DataType::Int16
+                    | DataType::Int32
+                    | DataType::Int64
+                    | DataType::UInt8
+                    | DataType::UInt16
+                    | DataType::UInt32
+                    | DataType::UInt64
+                    | DataType::Float32
+                    | DataType::Float64 => Some(NumericAccumulator::new()),
+                    _ => None,
+                })
+                .collect(),
+            str_accs: fields
+                .iter()
+                .map(|f| match f.data_type() {
+                    DataType::Utf8 | DataType::LargeUtf8 => Some(StringAccumulator::new()),
+                    _ => None,
+                })
+                .collect(),
+            temporal_accs: fields
+                .iter()
+                .map(|f| match f.data_type() {
+                    DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => {
+                        Some(TemporalAccumulator::new())
+                    }
+                    _ => None,
+                })
+                .collect(),
+            bool_accs: fields
+                .iter()
+                .map(|f| match f.data_type() {
+                    DataType::Boolean => Some(BooleanAccumulator::new()),
+                    _ => None,
+                })
+                .collect(),
+            binary_accs: fields
+                .iter()
+                .map(|f| match f.data_type() {
+                    DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => {
+                        Some(BinaryAccumulator::new())
+                    }
+                    _ => None,
+                })
+                .collect(),
+            entropy: (0..ncols).map(|_| EntropySampler::new()).collect(),
+            truncated: false,
+            low_memory: false,
+            gauge: MemoryGauge::new(None),
+        }
+    }
+
+    /// Reports this state's accumulator memory to `gauge` as it grows, and
+    /// shrinks the accumulators once the gauge's tracker is over its limit.
+    pub(crate) fn with_gauge(mut self, gauge: MemoryGauge) -> Self {
+        self.gauge = gauge;
+        if self.gauge.over_limit() {
+            self.shrink();
+        }
+        self
+    }
+
+    // top-k sketches instead of exact frequency maps, coarser digests and
+    // small numeric buffers; HLLs and the other accumulators are already bounded
+    fn shrink(&mut self) {
+        self.low_memory = true;
+        for f in &mut self.freq_counters {
+            f.bound(LOW_MEMORY_TOP_VALUES);
+        }
+        for acc in self.numeric_accs.iter_mut().flatten() {
+            acc.shrink();
+        }
+    }
+
+    /// Moves the accumulators of `fields` into the slots of the same-named
+    /// columns of `target`; target columns this file lacks start out empty.
+    fn align(self, fields: &[FieldRef], target: &[FieldRef]) -> Self {
+        let mut out = ScanState::new(target, self.hll_precision).with_gauge(self.gauge);
+        out.truncated = self.truncated;
+        let columns = fields
+            .iter()
+            .zip(self.hlls)
+            .zip(self.freq_counters)
+            .zip(self.numeric_accs)
+            .zip(self.str_accs)
+            .zip(self.temporal_accs)
+            .zip(self.bool_accs)
+            .zip(self.binary_accs)
+            .zip(self.entropy);
+        for ((((((((field, hll), freq), numeric), string), temporal), boolean), binary), entropy) in
+            columns
+        {
+            let Some(i) = target.iter().position(|t| t.name() == field.name()) else {
+                continue;
+            };
+            out.hlls[i] = hll;
+            out.freq_counters[i] = freq;
+            out.entropy[i] = entropy;
+            // a type change between files leaves the first type's accumulator empty
+            if let (Some(slot), Some(acc)) = (&mut out.numeric_accs[i], numeric) {
+                *slot = acc;
+            }
+            if let (Some(slot), Some(acc)) = (&mut out.str_accs[i], string) {
+                *slot = acc;
+            }
+            if let (Some(slot), Some(acc)) = (&mut out.temporal_accs[i], temporal) {
+                *slot = acc;
+            }
+            if let (Some(slot), Some(acc)) = (&mut out.bool_accs[i], boolean) {
+                *slot = acc;
+            }
+            if let (Some(slot), Some(acc)) = (&mut out.binary_accs[i], binary) {
+                *slot = acc;
+            }
+        }
+        out
+    }
+
+    // dominated by the frequency maps; numeric columns keep bounded digests
+    fn approx_bytes(&self) -> usize {
+        let sketches: usize = self.hlls.iter().map(|h| h.approx_bytes()).sum::<usize>()
+            + self
+                .freq_counters
+                .iter()
+                .map(|f| f.approx_bytes())
+                .sum::<usize>();
+        let numeric: usize = self
+            .numeric_accs
+            .iter()
+            .flatten()
+            .map(|a| a.approx_bytes())
+            .sum::<usize>();
+        let temporal: usize = self
+            .temporal_accs
+            .iter()
+            .flatten()
+            .map(|a| a.approx_bytes())
+            .sum();
+        let binary: usize = self
+            .binary_accs
+            .iter()
+            .flatten()
+            .map(|a| a.approx_bytes())
+            .sum();
+        let entropy: usize = self.entropy.iter().map(|e| e.approx_bytes()).sum();
+        let fixed = self.str_accs.len() * std::mem::size_of::<Option<StringAccumulator>>()
+            + self.bool_accs.len() * std::mem::size_of::<Option<BooleanAccumulator>>();
+        sketches + numeric + temporal + binary + entropy + fixed
+    }
 
-    for batch_result in &mut reader {
-        if let Some(dl) = deadline {
-            if std::time::Instant::now() >= dl {
-                timed_out = true;
-                break;
+    fn update_gauge(&mut self) {
+        if self.gauge.is_tracking() {
+            let bytes = self.approx_bytes();
+            self.gauge.set(bytes);
+            if !self.low_memory && self.gauge.over_limit() {
+                self.shrink();
+                let bytes = self.approx_bytes();
+                self.gauge.set(bytes);
             }
         }
-        let batch = batch_result.map_err(ParquetLensError::Arrow)?;
+    }
+
+    pub(crate) fn add_batch(&mut self, batch: &RecordBatch) {
         for (col_idx, col_array) in batch.columns().iter().enumerate() {
             for row in 0..col_array.len() {
                 if col_array.is_null(row) {
-                    if let Some(acc) = &mut temporal_accs[col_idx] {
+                    if let Some(acc) = &mut self.temporal_accs[col_idx] {
                         acc.add_null();
                     }
-                    if let Some(acc) = &mut bool_accs[col_idx] {
+                    if let Some(acc) = &mut self.bool_accs[col_idx] {
                         acc.add(None);
                     }
                     continue;
                 }
-                let val_str = array_value_to_str(col_array.as_ref(), row);
-                hlls[col_idx].add_bytes(val_str.as_bytes());
-                freq_counters[col_idx].add(val_str);
+                track_value(
+                    col_array.as_ref(),
+                    row,
+                    &mut self.hlls[col_idx],
+                    &mut self.freq_counters[col_idx],
+                );
+                let sampler = &mut self.entropy[col_idx];
+                if !sampler.is_full() {
+                    with_value_bytes(col_array.as_ref(), row, |bytes| sampler.add(bytes));
+                }
                 match col_array.data_type() {
                     DataType::Int8 => {
                         let a = col_array.as_any().downcast_ref::<Int8Array>().unwrap();
                         let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
+                        if let Some(acc) = &mut self.numeric_accs[col_idx] {
                             acc.add(v);
                         }
-                        numeric_vals[col_idx].push(v);
                     }
                     DataType::Int16 => {
                         let a = col_array.as_any().downcast_ref::<Int16Array>().unwrap();
                         let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
+                        if let Some(acc) = &mut self.numeric_accs[col_idx] {
                             acc.add(v);
                         }
-                        numeric_vals[col_idx].push(v);
                     }
                     DataType::Int32 => {
                         let a = col_array.as_any().downcast_ref::<Int32Array>().unwrap();
                         let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
+                        if let Some(acc) = &mut self.numeric_accs[col_idx] {
                             acc.add(v);
                         }
-                        numeric_vals[col_idx].push(v);
                     }
                     DataType::Int64 => {
                         let a = col_array.as_any().downcast_ref::<Int64Array>().unwrap();
                         let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
+                        if let Some(acc) = &mut self.numeric_accs[col_idx] {
                             acc.add(v);
                         }
-                        numeric_vals[col_idx].push(v);
                     }
                     DataType::UInt8 => {
                         let a = col_array.as_any().downcast_ref::<UInt8Array>().unwrap();
                         let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
+                        if let Some(acc) = &mut self.numeric_accs[col_idx] {
                             acc.add(v);
                         }
-                        numeric_vals[col_idx].push(v);
                     }
                     DataType::UInt16 => {
                         let a = col_array.as_any().downcast_ref::<UInt16Array>().unwrap();
                         let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
+                        if let Some(acc) = &mut self.numeric_accs[col_idx] {
                             acc.add(v);
                         }
-                        numeric_vals[col_idx].push(v);
                     }
                     DataType::UInt32 => {
                         let a = col_array.as_any().downcast_ref::<UInt32Array>().unwrap();
                         let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
+                        if let Some(acc) = &mut self.numeric_accs[col_idx] {
                             acc.add(v);
                         }
-                        numeric_vals[col_idx].push(v);
                     }
                     DataType::UInt64 => {
                         let a = col_array.as_any().downcast_ref::<UInt64Array>().unwrap();
                         let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
+                        if let Some(acc) = &mut self.numeric_accs[col_idx] {
                             acc.add(v);
                         }
-                        numeric_vals[col_idx].push(v);
                     }
                     DataType::Float32 => {
                         let a = col_array.as_any().downcast_ref::<Float32Array>().unwrap();
                         let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
+                        if let Some(acc) = &mut self.numeric_accs[col_idx] {
                             acc.add(v);
                         }
-                        numeric_vals[col_idx].push(v);
                     }
                     DataType::Float64 => {
                         let a = col_array.as_any().downcast_ref::<Float64Array>().unwrap();
                         let v = a.value(row);
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
+                        if let Some(acc) = &mut self.numeric_accs[col_idx] {
                             acc.add(v);
                         }
-                        numeric_vals[col_idx].push(v);
                     }
                     DataType::Utf8 => {
                         let a = col_array.as_any().downcast_ref::<StringArray>().unwrap();
-                        if let Some(acc) = &mut str_accs[col_idx] {
+                        if let Some(acc) = &mut self.str_accs[col_idx] {
                             acc.add(a.value(row));
                         }
                     }
@@ -242,22 +677,46 @@ pub fn profile_columns_with_timeout(
                             .as_any()
                             .downcast_ref::<LargeStringArray>()
                             .unwrap();
-                        if let Some(acc) = &mut str_accs[col_idx] {
+                        if let Some(acc) = &mut self.str_accs[col_idx] {
                             acc.add(a.value(row));
                         }
                     }
                     DataType::Boolean => {
                         let a = col_array.as_any().downcast_ref::<BooleanArray>().unwrap();
-                        if let Some(acc) = &mut bool_accs[col_idx] {
+                        if let Some(acc) = &mut self.bool_accs[col_idx] {
                             acc.add(Some(a.value(row)));
                         }
                     }
+                    DataType::Binary => {
+                        let a = col_array.as_any().downcast_ref::<BinaryArray>().unwrap();
+                        if let Some(acc) = &mut self.binary_accs[col_idx] {
+                            acc.add(a.value(row));
+                        }
+                    }
+                    DataType::LargeBinary => {
+                        let a = col_array
+                            .as_any()
+                            .downcast_ref::<LargeBinaryArray>()
+                            .unwrap();
+                        if let Some(acc) = &mut self.binary_accs[col_idx] {
+                            acc.add(a.value(row));
+                        }
+                    }
+                    DataType::FixedSizeBinary(_) => {
+                        let a = col_array
+                            .as_any()
+                            .downcast_ref::<FixedSizeBinaryArray>()
+                            .unwrap();
+                        if let Some(acc) = &mut self.binary_accs[col_idx] {
+                            acc.add(a.value(row));
+                        }
+                    }
                     DataType::Timestamp(TimeUnit::Millisecond, _) => {
                         let a = col_array
                             .as_any()
                             .downcast_ref::<TimestampMillisecondArray>()
                             .unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
+                        if let Some(acc) = &mut self.temporal_accs[col_idx] {
                             acc.add_ms(a.value(row));
                         }
                     }
@@ -266,7 +725,7 @@ pub fn profile_columns_with_timeout(
                             .as_any()
                             .downcast_ref::<TimestampSecondArray>()
                             .unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
+                        if let Some(acc) = &mut self.temporal_accs[col_idx] {
                             acc.add_ms(a.value(row) * 1000);
                         }
                     }
@@ -275,28 +734,29 @@ pub fn profile_columns_with_timeout(
                             .as_any()
                             .downcast_ref::<TimestampMicrosecondArray>()
                             .unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
+                        if let Some(acc) = &mut self.temporal_accs[col_idx] {
                             acc.add_ms(a.value(row) / 1000);
                         }
                     }
+                    // also how the reader hands over legacy INT96 columns
                     DataType::Timestamp(TimeUnit::Nanosecond, _) => {
                         let a = col_array
                             .as_any()
                             .downcast_ref::<TimestampNanosecondArray>()
                             .unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
+                        if let Some(acc) = &mut self.temporal_accs[col_idx] {
                             acc.add_ms(a.value(row) / 1_000_000);
                         }
                     }
                     DataType::Date32 => {
                         let a = col_array.as_any().downcast_ref::<Date32Array>().unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
+                        if let Some(acc) = &mut self.temporal_accs[col_idx] {
                             acc.add_ms(a.value(row) as i64 * 86400 * 1000);
                         }
                     }
                     DataType::Date64 => {
                         let a = col_array.as_any().downcast_ref::<Date64Array>().unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
+                        if let Some(acc) = &mut self.temporal_accs[col_idx] {
                             acc.add_ms(a.value(row));
                         }
                     }
@@ -304,43 +764,209 @@ pub fn profile_columns_with_timeout(
                 }
             }
         }
-    } // end while
+        self.update_gauge();
+    }
 
-    let results = field_names
-        .into_iter()
-        .enumerate()
-        .map(|(i, name)| {
-            let cardinality = hlls.remove(0).estimate();
-            let freq_counter = freq_counters.remove(0);
-            let frequency = if cardinality.approximate_distinct < 10000 {
-                Some(freq_counter.top_n(20))
-            } else {
-                let _ = freq_counter.top_n(0);
-                None
-            };
-            let numeric = numeric_accs[i].take().map(|acc| acc.finish());
-            let histogram = if !numeric_vals[i].is_empty() {
-                Some(build_histogram(&numeric_vals[i], histogram_bins))
-            } else {
-                None
-            };
-            let string = str_accs[i].take().map(|acc| acc.finish());
-            let temporal = temporal_accs[i].take().map(|acc| acc.finish());
-            let boolean = bool_accs[i].take().map(|acc| acc.finish());
-            ColumnProfileResult {
-                column_name: name,
-                cardinality,
-                frequency,
-                numeric,
-                histogram,
-                string,
-                temporal,
-                boolean,
-                truncated: timed_out,
+    pub(crate) fn merge(mut self, mut other: Self) -> Self {
+        if !self.gauge.is_tracking() {
+            std::mem::swap(&mut self.gauge, &mut other.gauge);
+        }
+        for (a, b) in self.hlls.iter_mut().zip(&other.hlls) {
+            a.merge(b);
+        }
+        for (a, b) in self.freq_counters.iter_mut().zip(other.freq_counters) {
+            a.merge(b);
+        }
+        for (a, b) in self.numeric_accs.iter_mut().zip(other.numeric_accs) {
+            if let (Some(a), Some(b)) = (a, b) {
+                a.merge(b);
             }
-        })
-        .collect();
-    Ok(results)
+        }
+        for (a, b) in self.str_accs.iter_mut().zip(other.str_accs) {
+            if let (Some(a), Some(b)) = (a, b) {
+                a.merge(b);
+            }
+        }
+        for (a, b) in self.temporal_accs.iter_mut().zip(other.temporal_accs) {
+            if let (Some(a), Some(b)) = (a, b) {
+                a.merge(b);
+            }
+        }
+        for (a, b) in self.bool_accs.iter_mut().zip(other.bool_accs) {
+            if let (Some(a), Some(b)) = (a, b) {
+                a.merge(b);
+            }
+        }
+        for (a, b) in self.binary_accs.iter_mut().zip(other.binary_accs) {
+            if let (Some(a), Some(b)) = (a, b) {
+                a.merge(b);
+            }
+        }
+        for (a, b) in self.entropy.iter_mut().zip(other.entropy) {
+            a.merge(b);
+        }
+        self.truncated |= other.truncated;
+        self.low_memory |= other.low_memory;
+        self.update_gauge();
+        self
+    }
+
+    pub(crate) fn finish(
+        self,
+        fields: &[FieldRef],
+        histogram_bins: usize,
+        histogram_scale: HistogramScale,
+    ) -> Vec<ColumnProfileResult> {
+        let truncated = self.truncated;
+        fields
+            .iter()
+            .zip(self.hlls)
+            .zip(self.freq_counters)
+            .zip(self.numeric_accs)
+            .zip(self.str_accs)
+            .zip(self.temporal_accs)
+            .zip(self.bool_accs)
+            .zip(self.binary_accs)
+            .zip(self.entropy)
+            .map(
+                |(
+                    (
+                        (
+                            (((((field, hll), freq_counter), mut numeric), string), temporal),
+                            boolean,
+                        ),
+                        binary,
+                    ),
+                    entropy,
+                )| {
+                    let cardinality = hll.into_estimate();
+                    let frequency =
+                        if !freq_counter.overflowed() && cardinality.approximate_distinct < 10000 {
+                            Some(freq_counter.top_n(20))
+                        } else {
+                            None
+                        };
+                    let histogram = numeric
+                        .as_mut()
+                        .filter(|acc| !acc.is_empty())
+                        .map(|acc| acc.histogram(histogram_bins, histogram_scale));
+                    ColumnProfileResult {
+                        column_name: field.name().clone(),
+                        cardinality,
+                        frequency,
+                        numeric: numeric.map(|acc| acc.finish()),
+                        histogram,
+                        string: string.map(|acc| acc.finish()),
+                        temporal: temporal.map(|acc| acc.finish()),
+                        boolean: boolean.map(|acc| acc.finish()),
+                        binary: binary.map(|acc| acc.finish()),
+                        entropy: entropy.finish(),
+                        truncated,
+                    }
+                },
+            )
+            .collect()
+    }
+}
+
+fn downcast<T: 'static>(array: &dyn Array) -> &T {
+    array.as_any().downcast_ref::<T>().unwrap()
+}
+
+/// Feeds one non-null cell to the cardinality and frequency trackers. Values are
+/// hashed from their native bytes (little-endian for numerics); a display string
+/// is only built the first time the frequency counter sees a value.
+pub(crate) fn track_value(
+    array: &dyn Array,
+    row: usize,
+    hll: &mut HllEstimator,
+    freq: &mut FrequencyCounter,
+) {
+    with_value_bytes(array, row, |bytes| {
+        hll.add_bytes(bytes);
+        freq.add_hashed(xxh3_64(bytes), || array_value_to_str(array, row));
+    });
+}
+
+/// Counts one non-null cell, hashed the same way `track_value` does.
+pub(crate) fn count_value(array: &dyn Array, row: usize, freq: &mut FrequencyCounter) {
+    with_value_bytes(array, row, |bytes| {
+        freq.add_hashed(xxh3_64(bytes), || array_value_to_str(array, row))
+    });
+}
+
+/// Calls `track` with the bytes a cell is hashed by. Only the types matched
+/// here hash by value; pass other columns through `hashable_array` first.
+pub(crate) fn with_value_bytes(array: &dyn Array, row: usize, mut track: impl FnMut(&[u8])) {
+    match array.data_type() {
+        DataType::Int8 => track(&downcast::<Int8Array>(array).value(row).to_le_bytes()),
+        DataType::Int16 => track(&downcast::<Int16Array>(array).value(row).to_le_bytes()),
+        DataType::Int32 => track(&downcast::<Int32Array>(array).value(row).to_le_bytes()),
+        DataType::Int64 => track(&downcast::<Int64Array>(array).value(row).to_le_bytes()),
+        DataType::UInt8 => track(&downcast::<UInt8Array>(array).value(row).to_le_bytes()),
+        DataType::UInt16 => track(&downcast::<UInt16Array>(array).value(row).to_le_bytes()),
+        DataType::UInt32 => track(&downcast::<UInt32Array>(array).value(row).to_le_bytes()),
+        DataType::UInt64 => track(&downcast::<UInt64Array>(array).value(row).to_le_bytes()),
+        DataType::Float32 => track(&downcast::<Float32Array>(array).value(row).to_le_bytes()),
+        DataType::Float64 => track(&downcast::<Float64Array>(array).value(row).to_le_bytes()),
+        DataType::Utf8 => track(downcast::<StringArray>(array).value(row).as_bytes()),
+        DataType::LargeUtf8 => track(downcast::<LargeStringArray>(array).value(row).as_bytes()),
+        DataType::Boolean => track(&[downcast::<BooleanArray>(array).value(row) as u8]),
+        DataType::Timestamp(TimeUnit::Second, _) => track(
+            &downcast::<TimestampSecondArray>(array)
+                .value(row)
+                .to_le_bytes(),
+        ),
+        DataType::Timestamp(TimeUnit::Millisecond, _) => track(
+            &downcast::<TimestampMillisecondArray>(array)
+                .value(row)
+                .to_le_bytes(),
+        ),
+        DataType::Timestamp(TimeUnit::Microsecond, _) => track(
+            &downcast::<TimestampMicrosecondArray>(array)
+                .value(row)
+                .to_le_bytes(),
+        ),
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => track(
+            &downcast::<TimestampNanosecondArray>(array)
+                .value(row)
+                .to_le_bytes(),
+        ),
+        DataType::Date32 => track(&downcast::<Date32Array>(array).value(row).to_le_bytes()),
+        DataType::Date64 => track(&downcast::<Date64Array>(array).value(row).to_le_bytes()),
+        DataType::Binary => track(downcast::<BinaryArray>(array).value(row)),
+        DataType::LargeBinary => track(downcast::<LargeBinaryArray>(array).value(row)),
+        DataType::FixedSizeBinary(_) => track(downcast::<FixedSizeBinaryArray>(array).value(row)),
+        // unsupported types fall back to the row position, as array_value_to_str does
+        _ => track(&(row as u64).to_le_bytes()),
+    }
+}
+
+/// `array` unchanged when `with_value_bytes` hashes its type by value, else
+/// cast to its text form.
+pub(crate) fn hashable_array(array: &ArrayRef) -> Result<ArrayRef> {
+    match array.data_type() {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::Utf8
+        | DataType::LargeUtf8
+        | DataType::Boolean
+        | DataType::Timestamp(_, _)
+        | DataType::Date32
+        | DataType::Date64
+        | DataType::Binary
+        | DataType::LargeBinary
+        | DataType::FixedSizeBinary(_) => Ok(array.clone()),
+        _ => Ok(arrow::compute::cast(array, &DataType::Utf8)?),
+    }
 }
 
 fn array_value_to_str(array: &dyn arrow::array::Array, row: usize) -> String {
@@ -410,6 +1036,24 @@ fn array_value_to_str(array: &dyn arrow::array::Array, row: usize) -> String {
             .downcast_ref::<BooleanArray>()
             .map(|a| a.value(row).to_string())
             .unwrap_or_default(),
+        DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => {
+            arrow::util::display::array_value_to_string(array, row).unwrap_or_default()
+        }
+        DataType::Binary => hex_preview(downcast::<BinaryArray>(array).value(row)),
+        DataType::LargeBinary => hex_preview(downcast::<LargeBinaryArray>(array).value(row)),
+        DataType::FixedSizeBinary(_) => {
+            hex_preview(downcast::<FixedSizeBinaryArray>(array).value(row))
+        }
         _ => format!("row_{row}"),
     }
 }
+
+// first 16 bytes as hex, enough to tell values apart in a top-values list
+fn hex_preview(v: &[u8]) -> String {
+    let hex: String = v.iter().take(16).map(|b| format!("{b:02x}")).collect();
+    if v.len() > 16 {
+        format!("0x{hex}…")
+    } else {
+        format!("0x{hex}")
+    }
+}
@@ -7,7 +7,38 @@ pub struct HistogramBin {
     pub count: u64,
 }
 
-pub fn build_histogram(values: &[f64], bins: usize) -> Vec<HistogramBin> {
+/// How bin edges are spaced between a column's min and max.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistogramScale {
+    #[default]
+    Linear,
+    /// Logarithmic edges for strictly positive columns whose range spans at
+    /// least `LOG_SCALE_MIN_RATIO`; anything else falls back to linear bins.
+    Log,
+}
+
+/// max / min a column needs before `HistogramScale::Log` kicks in: three
+/// orders of magnitude.
+pub const LOG_SCALE_MIN_RATIO: f64 = 1000.0;
+
+/// `bins + 1` edges from `min` to `max`, the last pinned to `max` exactly.
+pub(crate) fn bin_edges(min: f64, max: f64, bins: usize, scale: HistogramScale) -> Vec<f64> {
+    let log = scale == HistogramScale::Log && min > 0.0 && max / min >= LOG_SCALE_MIN_RATIO;
+    let mut edges: Vec<f64> = if log {
+        let (lo, hi) = (min.ln(), max.ln());
+        let step = (hi - lo) / bins as f64;
+        (0..=bins).map(|i| (lo + i as f64 * step).exp()).collect()
+    } else {
+        let width = (max - min) / bins as f64;
+        (0..=bins).map(|i| min + i as f64 * width).collect()
+    };
+    edges[0] = min;
+    edges[bins] = max;
+    edges
+}
+
+pub fn build_histogram(values: &[f64], bins: usize, scale: HistogramScale) -> Vec<HistogramBin> {
     if values.is_empty() || bins == 0 {
         return Vec::new();
     }
@@ -20,20 +51,53 @@ pub fn build_histogram(values: &[f64], bins: usize) -> Vec<HistogramBin> {
             count: values.len() as u64,
         }];
     }
-    let width = (max - min) / bins as f64;
+    let edges = bin_edges(min, max, bins, scale);
     let mut counts = vec![0u64; bins];
     for &v in values {
-        let idx = ((v - min) / width) as usize;
-        let idx = idx.min(bins - 1);
-        counts[idx] += 1;
+        // first bin whose upper edge is at or above v
+        let idx = edges[1..].partition_point(|&e| e < v);
+        counts[idx.min(bins - 1)] += 1;
     }
     counts
         .iter()
-        .enumerate()
-        .map(|(i, &c)| HistogramBin {
-            range_start: min + i as f64 * width,
-            range_end: min + (i + 1) as f64 * width,
+        .zip(edges.windows(2))
+        .map(|(&c, w)| HistogramBin {
+            range_start: w[0],
+            range_end: w[1],
             count: c,
         })
         .collect()
 }
+
+/// Counts of lengths per power-of-two bucket: bucket 0 holds zero, bucket k
+/// lengths of 2^(k-1)+1..=2^k.
+#[derive(Clone)]
+pub(crate) struct LengthBuckets([u64; 65]);
+
+impl LengthBuckets {
+    pub(crate) fn new() -> Self {
+        Self([0; 65])
+    }
+    pub(crate) fn add(&mut self, len: usize) {
+        let bucket = if len == 0 {
+            0
+        } else {
+            len.next_power_of_two().trailing_zeros() as usize + 1
+        };
+        self.0[bucket] += 1;
+    }
+    pub(crate) fn merge(&mut self, other: &Self) {
+        for (a, b) in self.0.iter_mut().zip(other.0) {
+            *a += b;
+        }
+    }
+    /// (upper bound, count) of every non-empty bucket, shortest first.
+    pub(crate) fn finish(&self) -> Vec<(usize, u64)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c > 0)
+            .map(|(k, &c)| (if k == 0 { 0 } else { 1usize << (k - 1) }, c))
+            .collect()
+    }
+}
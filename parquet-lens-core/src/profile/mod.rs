@@ -1,5 +1,9 @@
+#[cfg(feature = "tokio")]
+pub mod async_scan;
+pub mod binary;
 pub mod boolean;
 pub mod cardinality;
+pub mod entropy;
 pub mod frequency;
 pub mod full_scan;
 pub mod histogram;
@@ -7,11 +11,24 @@ pub mod numeric;
 pub mod string_profiler;
 pub mod temporal;
 
+#[cfg(feature = "tokio")]
+pub use async_scan::{
+    object_store_for_uri, preview_object, profile_columns_async,
+    profile_columns_async_with_timeout, profile_object,
+};
+pub use binary::BinaryProfile;
 pub use boolean::BooleanProfile;
-pub use cardinality::CardinalityEstimate;
-pub use frequency::FrequencyResult;
-pub use full_scan::{profile_columns, profile_columns_with_timeout, ColumnProfileResult};
-pub use histogram::{build_histogram, HistogramBin};
-pub use numeric::NumericProfile;
-pub use string_profiler::StringProfile;
-pub use temporal::TemporalProfile;
+pub use cardinality::{
+    hll_relative_error, union_cardinality, CardinalityEstimate, HllEstimator,
+    DEFAULT_HLL_PRECISION, HLL_PRECISION_RANGE,
+};
+pub use entropy::ColumnEntropy;
+pub use frequency::{FrequencyEntry, FrequencyResult};
+pub use full_scan::{
+    profile_columns, profile_columns_with_handle, profile_columns_with_timeout, profile_dataset,
+    ColumnProfileResult,
+};
+pub use histogram::{build_histogram, HistogramBin, HistogramScale, LOG_SCALE_MIN_RATIO};
+pub use numeric::{BenfordConformity, BenfordProfile, NumericProfile};
+pub use string_profiler::{EncodingAnomalies, StringProfile};
+pub use temporal::{CalendarBucket, CalendarGap, TemporalProfile, WEEKDAYS};
@@ -1,3 +1,4 @@
+use super::histogram::{bin_edges, HistogramBin, HistogramScale};
 use serde::{Deserialize, Serialize};
 use tdigest::TDigest;
 
@@ -17,6 +18,117 @@ pub struct NumericProfile {
     pub skewness: f64,
     pub kurtosis: f64,
     pub count: u64,
+    /// values outside [p25 - 1.5 IQR, p75 + 1.5 IQR]
+    #[serde(default)]
+    pub iqr_outlier_count: u64,
+    /// values more than 3 standard deviations from the mean
+    #[serde(default)]
+    pub zscore_outlier_count: u64,
+    /// `iqr_outlier_count` as a % of `count`
+    #[serde(default)]
+    pub outlier_pct: f64,
+    /// up to `OUTLIER_EXAMPLES` IQR outliers, furthest from the median first
+    #[serde(default)]
+    pub outlier_examples: Vec<f64>,
+    /// leading-digit test, for positive columns spanning two or more orders
+    /// of magnitude (amounts, counts, sizes) with `BENFORD_MIN_VALUES` values
+    #[serde(default)]
+    pub benford: Option<BenfordProfile>,
+}
+
+/// How closely leading digits follow Benford's law, P(d) = log10(1 + 1/d).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenfordProfile {
+    /// values with leading digit 1..=9
+    pub digit_counts: Vec<u64>,
+    /// Pearson chi-square against the expected counts, 8 degrees of freedom;
+    /// it grows with the row count, so large columns rarely pass it
+    pub chi_square: f64,
+    /// chi_square is past the 5% critical value (15.507)
+    pub chi_square_rejects: bool,
+    /// mean absolute deviation of the digit proportions, which doesn't grow
+    /// with the row count
+    pub mad: f64,
+    pub conformity: BenfordConformity,
+}
+
+/// Nigrini's first-digit MAD bands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BenfordConformity {
+    Close,      // MAD < 0.006
+    Acceptable, // < 0.012
+    Marginal,   // < 0.015
+    Nonconforming,
+}
+
+impl BenfordConformity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Close => "close conformity",
+            Self::Acceptable => "acceptable conformity",
+            Self::Marginal => "marginal conformity",
+            Self::Nonconforming => "nonconforming",
+        }
+    }
+}
+
+pub const BENFORD_MIN_VALUES: u64 = 100;
+const CHI_SQUARE_CRITICAL_8DF: f64 = 15.507;
+
+/// 1..=9 for a finite non-zero `v`, `None` otherwise.
+fn leading_digit(v: f64) -> Option<usize> {
+    let a = v.abs();
+    if !a.is_finite() || a == 0.0 {
+        return None;
+    }
+    let scaled = a / 10f64.powi(a.log10().floor() as i32);
+    // log10 can land a hair off for exact powers of ten
+    Some((scaled as usize).clamp(1, 9))
+}
+
+fn benford(counts: &[u64; 9]) -> BenfordProfile {
+    let n: u64 = counts.iter().sum();
+    let (mut chi_square, mut abs_dev) = (0.0, 0.0);
+    for (d, &c) in counts.iter().enumerate() {
+        let p = (1.0 + 1.0 / (d + 1) as f64).log10();
+        let expected = p * n as f64;
+        chi_square += (c as f64 - expected).powi(2) / expected;
+        abs_dev += (c as f64 / n as f64 - p).abs();
+    }
+    let mad = abs_dev / 9.0;
+    BenfordProfile {
+        digit_counts: counts.to_vec(),
+        chi_square,
+        chi_square_rejects: chi_square > CHI_SQUARE_CRITICAL_8DF,
+        mad,
+        conformity: match mad {
+            m if m < 0.006 => BenfordConformity::Close,
+            m if m < 0.012 => BenfordConformity::Acceptable,
+            m if m < 0.015 => BenfordConformity::Marginal,
+            _ => BenfordConformity::Nonconforming,
+        },
+    }
+}
+
+const DIGEST_SIZE: usize = 100;
+const BUFFER_LEN: usize = 10_000;
+// after `shrink`: a quarter of the centroids and a small staging buffer
+const LOW_MEMORY_DIGEST_SIZE: usize = 25;
+const LOW_MEMORY_BUFFER_LEN: usize = 256;
+// smallest and largest values kept exactly: outlier counts up to this many per
+// side are exact, larger ones come from the digest
+const EXTREMES: usize = 16;
+pub const OUTLIER_EXAMPLES: usize = 5;
+
+/// Adds `v` to `kept`, the (ascending) `EXTREMES` smallest values seen.
+fn keep_smallest(kept: &mut Vec<f64>, v: f64) {
+    if v.is_nan() || (kept.len() == EXTREMES && v >= kept[EXTREMES - 1]) {
+        return;
+    }
+    let at = kept.partition_point(|&k| k <= v);
+    kept.insert(at, v);
+    kept.truncate(EXTREMES);
 }
 
 pub struct NumericAccumulator {
@@ -29,12 +141,16 @@ pub struct NumericAccumulator {
     max: f64,
     count: u64,
     values_buf: Vec<f64>,
+    buffer_len: usize, // values staged before they are merged into the digest
+    lowest: Vec<f64>,
+    highest: Vec<f64>, // negated, so `keep_smallest` serves both ends
+    leading_digits: [u64; 9],
 }
 
 impl NumericAccumulator {
     pub fn new() -> Self {
         Self {
-            digest: TDigest::new_with_size(100),
+            digest: TDigest::new_with_size(DIGEST_SIZE),
             sum: 0.0,
             sum_sq: 0.0,
             sum_cube: 0.0,
@@ -43,6 +159,10 @@ impl NumericAccumulator {
             max: f64::MIN,
             count: 0,
             values_buf: Vec::new(),
+            buffer_len: BUFFER_LEN,
+            lowest: Vec::new(),
+            highest: Vec::new(),
+            leading_digits: [0; 9],
         }
     }
     pub fn add(&mut self, v: f64) {
@@ -58,20 +178,146 @@ impl NumericAccumulator {
             self.max = v;
         }
         self.count += 1;
-        if self.values_buf.len() >= 10000 {
+        if self.values_buf.len() >= self.buffer_len {
             self.flush();
         }
     }
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+    pub fn approx_bytes(&self) -> usize {
+        // digest is capped at max_size centroids of (mean, weight)
+        self.values_buf.capacity() * 8 + self.digest.max_size() * 16 + EXTREMES * 16
+    }
+    /// Trades accuracy for memory once a memory limit is hit: a coarser digest
+    /// (so rougher quantiles and histograms) and a small staging buffer.
+    pub fn shrink(&mut self) {
+        self.flush();
+        self.buffer_len = LOW_MEMORY_BUFFER_LEN;
+        self.values_buf = Vec::new();
+        let digest = std::mem::replace(
+            &mut self.digest,
+            TDigest::new_with_size(LOW_MEMORY_DIGEST_SIZE),
+        );
+        if !digest.is_empty() {
+            // the first digest's size bounds the merged one
+            self.digest = TDigest::merge_digests(vec![self.digest.clone(), digest]);
+        }
+    }
     fn flush(&mut self) {
         if self.values_buf.is_empty() {
             return;
         }
+        for &v in &self.values_buf {
+            keep_smallest(&mut self.lowest, v);
+            keep_smallest(&mut self.highest, -v);
+            if let Some(d) = leading_digit(v) {
+                self.leading_digits[d - 1] += 1;
+            }
+        }
         // merge_unsorted is a method on &self returning a new TDigest
         let merged = self
             .digest
             .merge_unsorted(self.values_buf.drain(..).collect());
         self.digest = merged;
     }
+    pub fn merge(&mut self, mut other: Self) {
+        self.flush();
+        other.flush();
+        // a shrunk side keeps the merged digest small
+        let size = self.digest.max_size().min(other.digest.max_size());
+        let digest = std::mem::replace(&mut self.digest, TDigest::new_with_size(size));
+        self.digest =
+            TDigest::merge_digests(vec![TDigest::new_with_size(size), digest, other.digest]);
+        self.buffer_len = self.buffer_len.min(other.buffer_len);
+        for v in other.lowest {
+            keep_smallest(&mut self.lowest, v);
+        }
+        for v in other.highest {
+            keep_smallest(&mut self.highest, v);
+        }
+        for (a, b) in self.leading_digits.iter_mut().zip(other.leading_digits) {
+            *a += b;
+        }
+        self.sum += other.sum;
+        self.sum_sq += other.sum_sq;
+        self.sum_cube += other.sum_cube;
+        self.sum_quad += other.sum_quad;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.count += other.count;
+    }
+    /// Histogram over [min, max] with `scale`-spaced edges, read off the digest,
+    /// so the counts are approximate but no raw values are kept. They still
+    /// add up to `count`.
+    pub fn histogram(&mut self, bins: usize, scale: HistogramScale) -> Vec<HistogramBin> {
+        self.flush();
+        if self.count == 0 || bins == 0 {
+            return Vec::new();
+        }
+        if (self.max - self.min).abs() < f64::EPSILON {
+            return vec![HistogramBin {
+                range_start: self.min,
+                range_end: self.max,
+                count: self.count,
+            }];
+        }
+        let edges = bin_edges(self.min, self.max, bins, scale);
+        let n = self.count as f64;
+        // rows at or below each bin's upper edge; the last edge takes every row
+        let below: Vec<u64> = (1..=bins)
+            .map(|i| {
+                if i == bins {
+                    self.count
+                } else {
+                    (n * self.cdf(edges[i])).round() as u64
+                }
+            })
+            .collect();
+        let mut prev = 0;
+        below
+            .iter()
+            .zip(edges.windows(2))
+            .map(|(&cum, w)| {
+                let count = cum.max(prev) - prev;
+                prev = cum.max(prev);
+                HistogramBin {
+                    range_start: w[0],
+                    range_end: w[1],
+                    count,
+                }
+            })
+            .collect()
+    }
+    // the digest only answers quantile -> value, so invert that by bisection
+    fn cdf(&self, x: f64) -> f64 {
+        let (mut lo, mut hi) = (0.0, 1.0);
+        for _ in 0..40 {
+            let mid = (lo + hi) / 2.0;
+            if self.digest.estimate_quantile(mid) <= x {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+    /// Values below `lo` and above `hi`: exact while the kept extremes reach
+    /// past the fence, a digest estimate beyond that.
+    fn count_outside(&self, lo: f64, hi: f64) -> u64 {
+        let n = self.count as f64;
+        let side = |kept: &[f64], known: usize, estimate: f64| {
+            if known < kept.len() || kept.len() < EXTREMES {
+                known as u64
+            } else {
+                (estimate.round() as u64).max(known as u64)
+            }
+        };
+        let below = self.lowest.iter().filter(|&&v| v < lo).count();
+        let above = self.highest.iter().filter(|&&v| -v > hi).count();
+        side(&self.lowest, below, n * self.cdf(lo))
+            + side(&self.highest, above, n * (1.0 - self.cdf(hi)))
+    }
     pub fn finish(mut self) -> NumericProfile {
         self.flush();
         let n = self.count as f64;
@@ -91,6 +337,11 @@ impl NumericAccumulator {
                 skewness: 0.0,
                 kurtosis: 0.0,
                 count: 0,
+                iqr_outlier_count: 0,
+                zscore_outlier_count: 0,
+                outlier_pct: 0.0,
+                outlier_examples: Vec::new(),
+                benford: None,
             };
         }
         let mean = self.sum / n;
@@ -110,21 +361,57 @@ impl NumericAccumulator {
         } else {
             0.0
         };
+        let (p25, p50, p75) = (
+            self.digest.estimate_quantile(0.25),
+            self.digest.estimate_quantile(0.50),
+            self.digest.estimate_quantile(0.75),
+        );
+        let iqr = p75 - p25;
+        let (lo, hi) = (p25 - 1.5 * iqr, p75 + 1.5 * iqr);
+        let iqr_outlier_count = self.count_outside(lo, hi);
+        let zscore_outlier_count = if stddev > 0.0 {
+            self.count_outside(mean - 3.0 * stddev, mean + 3.0 * stddev)
+        } else {
+            0
+        };
+        let mut outlier_examples: Vec<f64> = self
+            .lowest
+            .iter()
+            .copied()
+            .filter(|&v| v < lo)
+            .chain(self.highest.iter().map(|&v| -v).filter(|&v| v > hi))
+            .collect();
+        outlier_examples.sort_by(|a, b| (b - p50).abs().total_cmp(&(a - p50).abs()));
+        outlier_examples.truncate(OUTLIER_EXAMPLES);
+        let (p1, p99) = (
+            self.digest.estimate_quantile(0.01),
+            self.digest.estimate_quantile(0.99),
+        );
+        // negative or narrow columns (ratings, percentages, ids in one decade)
+        // have no reason to follow Benford
+        let natural = self.min >= 0.0 && p1 > 0.0 && p99 / p1 >= 100.0;
+        let benford = (natural && self.leading_digits.iter().sum::<u64>() >= BENFORD_MIN_VALUES)
+            .then(|| benford(&self.leading_digits));
         NumericProfile {
             mean,
             stddev,
             min: self.min,
             max: self.max,
-            p1: self.digest.estimate_quantile(0.01),
+            p1,
             p5: self.digest.estimate_quantile(0.05),
-            p25: self.digest.estimate_quantile(0.25),
-            p50: self.digest.estimate_quantile(0.50),
-            p75: self.digest.estimate_quantile(0.75),
+            p25,
+            p50,
+            p75,
             p95: self.digest.estimate_quantile(0.95),
-            p99: self.digest.estimate_quantile(0.99),
+            p99,
             skewness,
             kurtosis,
             count: self.count,
+            iqr_outlier_count,
+            zscore_outlier_count,
+            outlier_pct: iqr_outlier_count as f64 / n * 100.0,
+            outlier_examples,
+            benford,
         }
     }
 }
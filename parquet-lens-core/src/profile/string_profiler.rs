@@ -8,6 +8,39 @@ pub struct PatternStats {
     pub email_like_pct: f64,
     pub uuid_like_pct: f64,
     pub iso_date_like_pct: f64,
+    #[serde(default)]
+    pub url_like_pct: f64,
+    /// IPv4 or IPv6 addresses
+    #[serde(default)]
+    pub ip_like_pct: f64,
+    /// 7-15 digits with a leading `+` or separators (spaces, dashes, dots, parens)
+    #[serde(default)]
+    pub phone_like_pct: f64,
+    /// objects or arrays that parse as JSON
+    #[serde(default)]
+    pub json_like_pct: f64,
+}
+
+impl PatternStats {
+    /// (pattern, pct) of every pattern some value matched, most common first,
+    /// so a column that is mostly one of them stands out.
+    pub fn matches(&self) -> Vec<(&'static str, f64)> {
+        let mut out: Vec<(&'static str, f64)> = [
+            ("numeric", self.all_numeric_pct),
+            ("email", self.email_like_pct),
+            ("uuid", self.uuid_like_pct),
+            ("iso date", self.iso_date_like_pct),
+            ("url", self.url_like_pct),
+            ("ip", self.ip_like_pct),
+            ("phone", self.phone_like_pct),
+            ("json", self.json_like_pct),
+        ]
+        .into_iter()
+        .filter(|&(_, pct)| pct > 0.0)
+        .collect();
+        out.sort_by(|a, b| b.1.total_cmp(&a.1));
+        out
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,12 +52,119 @@ pub struct StringProfile {
     pub max_length: usize,
     pub mean_length: f64,
     pub patterns: PatternStats,
+    #[serde(default)]
+    pub encoding: EncodingAnomalies,
+}
+
+/// Values carrying signs of a broken charset round trip. One value can count
+/// towards several kinds; `affected_count` counts each value once.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EncodingAnomalies {
+    /// U+FFFD, left behind when invalid UTF-8 was decoded lossily
+    pub replacement_char_count: u64,
+    /// C0 controls other than tab/CR/LF, and C1 controls (U+0080-U+009F),
+    /// which only appear when Latin-1 or cp1252 bytes were read as Unicode
+    pub control_char_count: u64,
+    /// UTF-8 read back as Latin-1: `Ã©` for `é`, `â€™` for `’`
+    pub mojibake_count: u64,
+    /// a single word mixing Latin, Greek, Cyrillic or other scripts, as
+    /// homoglyph substitutions do
+    pub mixed_script_count: u64,
+    pub affected_count: u64,
+}
+
+impl EncodingAnomalies {
+    pub fn affected_pct(&self, count: u64) -> f64 {
+        if count > 0 {
+            self.affected_count as f64 / count as f64 * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    fn add(&mut self, s: &str) {
+        let control = s
+            .chars()
+            .any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r'));
+        let (replacement, mojibake, mixed) = if s.is_ascii() {
+            (false, false, false)
+        } else {
+            (
+                s.contains('\u{FFFD}'),
+                is_mojibake(s),
+                has_mixed_script_word(s),
+            )
+        };
+        self.replacement_char_count += u64::from(replacement);
+        self.control_char_count += u64::from(control);
+        self.mojibake_count += u64::from(mojibake);
+        self.mixed_script_count += u64::from(mixed);
+        self.affected_count += u64::from(replacement || control || mojibake || mixed);
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.replacement_char_count += other.replacement_char_count;
+        self.control_char_count += other.control_char_count;
+        self.mojibake_count += other.mojibake_count;
+        self.mixed_script_count += other.mixed_script_count;
+        self.affected_count += other.affected_count;
+    }
+}
+
+// a UTF-8 lead byte 0xC2/0xC3 decoded as Latin-1 is Â/Ã, followed by the
+// continuation byte as a Latin-1 char; cp1252 turns the E2 80 prefix of
+// curly quotes and dashes into â€
+fn is_mojibake(s: &str) -> bool {
+    s.contains("\u{E2}\u{20AC}")
+        || s.chars()
+            .zip(s.chars().skip(1))
+            .any(|(a, b)| matches!(a, 'Â' | 'Ã') && ('\u{80}'..='\u{BF}').contains(&b))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Thai,
+    Cjk,
+}
+
+fn script(c: char) -> Option<Script> {
+    Some(match c as u32 {
+        0x41..=0x5A | 0x61..=0x7A | 0xC0..=0x24F | 0x1E00..=0x1EFF => Script::Latin,
+        0x370..=0x3FF | 0x1F00..=0x1FFF => Script::Greek,
+        0x400..=0x52F => Script::Cyrillic,
+        0x530..=0x58F => Script::Armenian,
+        0x590..=0x5FF => Script::Hebrew,
+        0x600..=0x6FF | 0x750..=0x77F => Script::Arabic,
+        0x900..=0x97F => Script::Devanagari,
+        0xE00..=0xE7F => Script::Thai,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF => Script::Cjk,
+        _ => return None,
+    })
+}
+
+// whole values mixing scripts are normal ("iPhone 手机"); one word doing so isn't
+fn has_mixed_script_word(s: &str) -> bool {
+    s.split(|c: char| !c.is_alphanumeric()).any(|word| {
+        let mut scripts = word.chars().filter_map(script);
+        scripts
+            .next()
+            .is_some_and(|first| scripts.any(|other| other != first))
+    })
 }
 
 static RE_NUMERIC: OnceLock<Regex> = OnceLock::new();
 static RE_EMAIL: OnceLock<Regex> = OnceLock::new();
 static RE_UUID: OnceLock<Regex> = OnceLock::new();
 static RE_ISODATE: OnceLock<Regex> = OnceLock::new();
+static RE_URL: OnceLock<Regex> = OnceLock::new();
+static RE_PHONE: OnceLock<Regex> = OnceLock::new();
 
 fn re_numeric() -> &'static Regex {
     RE_NUMERIC.get_or_init(|| Regex::new(r"^\d+(\.\d+)?$").unwrap())
@@ -42,6 +182,26 @@ fn re_isodate() -> &'static Regex {
     RE_ISODATE.get_or_init(|| Regex::new(r"^\d{4}-\d{2}-\d{2}(T\d{2}:\d{2}:\d{2})?").unwrap())
 }
 
+fn re_url() -> &'static Regex {
+    RE_URL.get_or_init(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.-]*://[^\s/?#]+\S*$").unwrap())
+}
+fn re_phone() -> &'static Regex {
+    RE_PHONE.get_or_init(|| Regex::new(r"^\+?\(?\d[\d\s().-]{5,22}\d$").unwrap())
+}
+
+fn is_phone_like(s: &str) -> bool {
+    let digits = s.bytes().filter(u8::is_ascii_digit).count();
+    // a bare run of digits is a number, not a phone number
+    (7..=15).contains(&digits) && digits < s.len() && re_phone().is_match(s)
+}
+
+fn is_json_like(s: &str) -> bool {
+    let t = s.trim();
+    let wrapped =
+        (t.starts_with('{') && t.ends_with('}')) || (t.starts_with('[') && t.ends_with(']'));
+    wrapped && serde_json::from_str::<serde::de::IgnoredAny>(t).is_ok()
+}
+
 pub struct StringAccumulator {
     count: u64,
     empty: u64,
@@ -53,6 +213,11 @@ pub struct StringAccumulator {
     email: u64,
     uuid: u64,
     isodate: u64,
+    url: u64,
+    ip: u64,
+    phone: u64,
+    json: u64,
+    encoding: EncodingAnomalies,
 }
 
 impl StringAccumulator {
@@ -68,6 +233,11 @@ impl StringAccumulator {
             email: 0,
             uuid: 0,
             isodate: 0,
+            url: 0,
+            ip: 0,
+            phone: 0,
+            json: 0,
+            encoding: EncodingAnomalies::default(),
         }
     }
     pub fn add(&mut self, s: &str) {
@@ -94,9 +264,42 @@ impl StringAccumulator {
         if re_uuid().is_match(s) {
             self.uuid += 1;
         }
-        if re_isodate().is_match(s) {
+        let isodate = re_isodate().is_match(s);
+        if isodate {
             self.isodate += 1;
         }
+        if re_url().is_match(s) {
+            self.url += 1;
+        }
+        let ip = s.parse::<std::net::IpAddr>().is_ok();
+        if ip {
+            self.ip += 1;
+        }
+        // dates and dotted quads are digits and separators too
+        if !isodate && !ip && is_phone_like(s) {
+            self.phone += 1;
+        }
+        if is_json_like(s) {
+            self.json += 1;
+        }
+        self.encoding.add(s);
+    }
+    pub fn merge(&mut self, other: Self) {
+        self.count += other.count;
+        self.empty += other.empty;
+        self.whitespace += other.whitespace;
+        self.min_len = self.min_len.min(other.min_len);
+        self.max_len = self.max_len.max(other.max_len);
+        self.total_len += other.total_len;
+        self.numeric += other.numeric;
+        self.email += other.email;
+        self.uuid += other.uuid;
+        self.isodate += other.isodate;
+        self.url += other.url;
+        self.ip += other.ip;
+        self.phone += other.phone;
+        self.json += other.json;
+        self.encoding.merge(&other.encoding);
     }
     pub fn finish(self) -> StringProfile {
         let n = self.count as f64;
@@ -127,7 +330,12 @@ impl StringAccumulator {
                 email_like_pct: pct(self.email),
                 uuid_like_pct: pct(self.uuid),
                 iso_date_like_pct: pct(self.isodate),
+                url_like_pct: pct(self.url),
+                ip_like_pct: pct(self.ip),
+                phone_like_pct: pct(self.phone),
+                json_like_pct: pct(self.json),
             },
+            encoding: self.encoding,
         }
     }
 }
@@ -1,3 +1,4 @@
+use parquet::data_type::Int96;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,6 +9,95 @@ pub struct TemporalProfile {
     pub max_timestamp_ms: Option<i64>,
     pub range_days: Option<f64>,
     pub year_distribution: Vec<(i32, u64)>,
+    /// rows per UTC weekday, Monday first
+    #[serde(default)]
+    pub weekday_distribution: Vec<u64>,
+    /// rows per UTC hour of day, 00 first
+    #[serde(default)]
+    pub hour_distribution: Vec<u64>,
+    /// calendar slots with no rows at all: `Weekday` and `Hour` gaps once the
+    /// range covers two weeks / two days, `Day` runs between min and max on
+    /// weekdays that otherwise have data (longest `MAX_DAY_GAPS` only)
+    #[serde(default)]
+    pub gaps: Vec<CalendarGap>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CalendarBucket {
+    Weekday,
+    Hour,
+    Day,
+}
+
+/// A run of consecutive empty `bucket`s, e.g. `Sat-Sun` (span 2), `02:00-04:59`
+/// (span 3) or `2024-03-09..2024-03-10` (span 2).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarGap {
+    pub bucket: CalendarBucket,
+    pub label: String,
+    pub span: u64,
+}
+
+pub const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+/// Day runs reported per column, longest kept.
+pub const MAX_DAY_GAPS: usize = 20;
+// distinct days remembered for day gaps, ~180 years; past it, or when min and
+// max are more than four times that apart, day gaps are skipped
+const MAX_TRACKED_DAYS: usize = 1 << 16;
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// YYYY-MM-DD for a count of days since 1970-01-01.
+pub(crate) fn format_date(days: i64) -> String {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+// 1970-01-01 was a Thursday
+fn weekday(day: i64) -> usize {
+    (day + 3).rem_euclid(7) as usize
+}
+
+/// Runs of empty slots in a cyclic distribution (Sun wraps to Mon, 23 to 00),
+/// as (first slot, length); nothing when every slot or no slot is empty.
+fn empty_runs(counts: &[u64]) -> Vec<(usize, usize)> {
+    let n = counts.len();
+    let Some(filled) = counts.iter().position(|&c| c > 0) else {
+        return Vec::new();
+    };
+    let mut runs = Vec::new();
+    let mut start = None;
+    // start right after a filled slot so no run is split by the wrap
+    for step in 1..=n {
+        let i = (filled + step) % n;
+        match (counts[i] == 0, start) {
+            (true, None) => start = Some((i, 1)),
+            (true, Some((s, len))) => start = Some((s, len + 1)),
+            (false, Some(run)) => {
+                runs.push(run);
+                start = None;
+            }
+            (false, None) => {}
+        }
+    }
+    runs.sort_unstable();
+    runs
+}
+
+/// Epoch millis of a legacy (Spark/Impala/Hive) INT96 timestamp: nanoseconds of
+/// the day plus a Julian day number. Unlike the nanosecond value the Arrow reader
+/// produces, this doesn't wrap for dates outside 1677-2262.
+pub fn int96_to_ms(v: &Int96) -> i64 {
+    let (seconds, nanos) = v.to_seconds_and_nanos();
+    seconds * 1000 + nanos / 1_000_000
 }
 
 pub struct TemporalAccumulator {
@@ -16,6 +106,9 @@ pub struct TemporalAccumulator {
     min: Option<i64>,
     max: Option<i64>,
     year_counts: std::collections::HashMap<i32, u64>,
+    weekday_counts: [u64; 7],
+    hour_counts: [u64; 24],
+    days: Option<std::collections::HashSet<i64>>, // None once MAX_TRACKED_DAYS is passed
 }
 
 impl TemporalAccumulator {
@@ -26,6 +119,9 @@ impl TemporalAccumulator {
             min: None,
             max: None,
             year_counts: std::collections::HashMap::new(),
+            weekday_counts: [0; 7],
+            hour_counts: [0; 24],
+            days: Some(std::collections::HashSet::new()),
         }
     }
     pub fn add_ms(&mut self, ts_ms: i64) {
@@ -35,15 +131,122 @@ impl TemporalAccumulator {
         // approximate year from ms: 1970 + ms / (365.25 * 86400 * 1000)
         let year = 1970 + (ts_ms as f64 / (365.25 * 86400.0 * 1000.0)) as i32;
         *self.year_counts.entry(year).or_insert(0) += 1;
+        let day = ts_ms.div_euclid(MS_PER_DAY);
+        self.weekday_counts[weekday(day)] += 1;
+        self.hour_counts[(ts_ms.rem_euclid(MS_PER_DAY) / 3_600_000) as usize] += 1;
+        if let Some(days) = &mut self.days {
+            days.insert(day);
+            if days.len() > MAX_TRACKED_DAYS {
+                self.days = None;
+            }
+        }
+    }
+    pub fn add_int96(&mut self, v: &Int96) {
+        self.add_ms(int96_to_ms(v));
     }
     pub fn add_null(&mut self) {
         self.null_count += 1;
     }
+    pub fn merge(&mut self, other: Self) {
+        self.count += other.count;
+        self.null_count += other.null_count;
+        self.min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        for (year, count) in other.year_counts {
+            *self.year_counts.entry(year).or_insert(0) += count;
+        }
+        for (a, b) in self.weekday_counts.iter_mut().zip(other.weekday_counts) {
+            *a += b;
+        }
+        for (a, b) in self.hour_counts.iter_mut().zip(other.hour_counts) {
+            *a += b;
+        }
+        self.days = match (self.days.take(), other.days) {
+            (Some(mut a), Some(b)) => {
+                a.extend(b);
+                (a.len() <= MAX_TRACKED_DAYS).then_some(a)
+            }
+            _ => None,
+        };
+    }
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.year_counts.capacity() * 16
+            + self.days.as_ref().map_or(0, |d| d.capacity() * 16)
+    }
+    fn gaps(&self, range_days: f64) -> Vec<CalendarGap> {
+        let mut gaps = Vec::new();
+        if range_days >= 14.0 {
+            for (first, len) in empty_runs(&self.weekday_counts) {
+                let last = (first + len - 1) % 7;
+                gaps.push(CalendarGap {
+                    bucket: CalendarBucket::Weekday,
+                    label: if len == 1 {
+                        WEEKDAYS[first].to_string()
+                    } else {
+                        format!("{}-{}", WEEKDAYS[first], WEEKDAYS[last])
+                    },
+                    span: len as u64,
+                });
+            }
+        }
+        // date columns land every value on 00:00, which says nothing about hours
+        let date_only = self.hour_counts[1..].iter().all(|&c| c == 0);
+        if range_days >= 2.0 && !date_only {
+            for (first, len) in empty_runs(&self.hour_counts) {
+                gaps.push(CalendarGap {
+                    bucket: CalendarBucket::Hour,
+                    label: format!("{first:02}:00-{:02}:59", (first + len - 1) % 24),
+                    span: len as u64,
+                });
+            }
+        }
+        if let (Some(days), Some(min), Some(max)) = (&self.days, self.min, self.max) {
+            let (first, last) = (min.div_euclid(MS_PER_DAY), max.div_euclid(MS_PER_DAY));
+            if last - first > 4 * MAX_TRACKED_DAYS as i64 {
+                // a stray far-future value; walking every day to it isn't worth it
+                return gaps;
+            }
+            // a weekday that never has rows is already reported above
+            let expected = |d: i64| self.weekday_counts[weekday(d)] > 0;
+            let mut runs: Vec<(i64, i64)> = Vec::new();
+            let mut run: Option<(i64, i64)> = None;
+            for d in first..=last {
+                if days.contains(&d) {
+                    if let Some(r) = run.take() {
+                        runs.push(r);
+                    }
+                } else if expected(d) {
+                    run = Some(run.map_or((d, d), |(s, _)| (s, d)));
+                }
+            }
+            runs.sort_by_key(|&(s, e)| std::cmp::Reverse(e - s));
+            runs.truncate(MAX_DAY_GAPS);
+            runs.sort_unstable();
+            gaps.extend(runs.into_iter().map(|(s, e)| CalendarGap {
+                bucket: CalendarBucket::Day,
+                label: if s == e {
+                    format_date(s)
+                } else {
+                    format!("{}..{}", format_date(s), format_date(e))
+                },
+                span: (e - s + 1) as u64,
+            }));
+        }
+        gaps
+    }
     pub fn finish(self) -> TemporalProfile {
         let range_days = match (self.min, self.max) {
             (Some(mn), Some(mx)) => Some((mx - mn) as f64 / (86400.0 * 1000.0)),
             _ => None,
         };
+        let gaps = range_days.map_or_else(Vec::new, |r| self.gaps(r));
         let mut year_distribution: Vec<(i32, u64)> = self.year_counts.into_iter().collect();
         year_distribution.sort_by_key(|(y, _)| *y);
         TemporalProfile {
@@ -53,6 +256,9 @@ impl TemporalAccumulator {
             max_timestamp_ms: self.max,
             range_days,
             year_distribution,
+            weekday_distribution: self.weekday_counts.to_vec(),
+            hour_distribution: self.hour_counts.to_vec(),
+            gaps,
         }
     }
 }
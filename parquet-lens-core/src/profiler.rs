@@ -0,0 +1,590 @@
+use crate::baseline::load_baseline_regressions;
+use crate::cache::ProfileCache;
+use crate::engine::identify_engine;
+use crate::handle::HandleRegistry;
+use crate::memory::format_bytes;
+use crate::nested::{profile_nested_columns_with_handle, profile_nested_data_with_handle};
+use crate::null_patterns::analyze_null_patterns;
+use crate::options::ScanOptions;
+use crate::parallel_reader::{read_metadata_parallel, read_metadata_remote};
+use crate::profile::ColumnProfileResult;
+use crate::profile::{profile_columns_with_timeout, profile_dataset};
+use crate::quality::{
+    apply_full_scan_findings, apply_pii_findings, scan_pii, score_columns, summarize_quality,
+};
+use crate::quarantine::QuarantineLog;
+use crate::reader::open_parquet_auto;
+use crate::recommendations::recommend_row_group_size;
+use crate::repair::detect_repair_suggestions;
+use crate::report::ProfileReport;
+use crate::row_count::reconcile_row_counts_with_handle;
+use crate::run_info::StageClock;
+use crate::sample::{
+    auto_sample_percentage, sample_partitions, sample_row_groups, SampleConfig, SampleMethod,
+    StrataAllocation,
+};
+use crate::scanner::{is_remote_uri, resolve_paths, ParquetFilePath};
+use crate::stats::{
+    aggregate_column_stats, analyze_compression, analyze_encodings, profile_row_groups,
+    read_column_stats,
+};
+use crate::storage::StorageRegistry;
+use crate::timeseries::{detect_timestamp_columns, profile_timeseries_with_handle};
+use parquet_lens_common::{Config, ParquetLensError, Result};
+use std::sync::Arc;
+
+/// Runs the same chain of analyses as `parquet-lens inspect` and returns one
+/// `ProfileReport`, so embedders don't have to wire the individual calls.
+///
+/// ```no_run
+/// # async fn demo() -> parquet_lens_core::Result<()> {
+/// let report = parquet_lens_core::Profiler::builder()
+///     .path("data/events.parquet")
+///     .sample(10.0)
+///     .with_full_scan(true)
+///     .run()
+///     .await?;
+/// println!("quality: {}/100", report.quality.overall_score);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Profiler;
+
+impl Profiler {
+    pub fn builder() -> ProfilerBuilder {
+        ProfilerBuilder::default()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProfilerBuilder {
+    path: Option<String>,
+    resolved: Option<Vec<ParquetFilePath>>,
+    sample: Option<f64>,
+    sample_seed: Option<u64>,
+    sample_method: SampleMethod,
+    sample_rows: Option<u64>,
+    stratify_by: Option<(String, StrataAllocation)>,
+    auto_sample_bytes: Option<u64>,
+    auto_sample_rows: u64,
+    no_extrapolation: bool,
+    full_scan: bool,
+    metadata_only: bool,
+    verify_row_counts: bool,
+    pii: bool,
+    baseline: bool,
+    columns: Option<Vec<String>>,
+    histogram_bins: usize,
+    timeout_secs: Option<u64>,
+    scan: ScanOptions,
+    cache: Option<ProfileCache>,
+    s3_endpoint: Option<String>,
+}
+
+impl Default for ProfilerBuilder {
+    fn default() -> Self {
+        Self::from_config(&Config::default())
+    }
+}
+
+impl ProfilerBuilder {
+    /// Starts from the `[profiling]` and `[s3]` settings of a loaded config.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            path: None,
+            resolved: None,
+            sample: None,
+            sample_seed: None,
+            sample_method: SampleMethod::RowGroups,
+            sample_rows: None,
+            stratify_by: None,
+            auto_sample_bytes: config.profiling.auto_sample_threshold_bytes,
+            auto_sample_rows: config.profiling.sample_size as u64,
+            no_extrapolation: false,
+            full_scan: false,
+            metadata_only: false,
+            verify_row_counts: false,
+            pii: false,
+            baseline: false,
+            columns: None,
+            histogram_bins: config.profiling.histogram_bins,
+            timeout_secs: config.profiling.full_scan_timeout_secs,
+            scan: ScanOptions::from_config(&config.profiling),
+            cache: None,
+            s3_endpoint: config.s3.endpoint_url.clone(),
+        }
+    }
+
+    /// File, directory, glob, or `s3://` / `gs://` URI.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Files already resolved by the caller (skips listing `path` again).
+    pub fn resolved_paths(mut self, paths: Vec<ParquetFilePath>) -> Self {
+        self.resolved = Some(paths);
+        self
+    }
+
+    /// Profile only this percentage of row groups, extrapolating stats.
+    pub fn sample(mut self, percentage: f64) -> Self {
+        self.sample = Some(percentage);
+        self
+    }
+
+    pub fn sample_seed(mut self, seed: u64) -> Self {
+        self.sample_seed = Some(seed);
+        self
+    }
+
+    /// Sample whole row groups (the default) or individual rows.
+    pub fn sample_method(mut self, method: SampleMethod) -> Self {
+        self.sample_method = method;
+        self
+    }
+
+    /// Full scans of files larger than `threshold_bytes` profile an automatic
+    /// two-stage sample of about `rows` rows instead; `None` always scans
+    /// everything. An explicit `sample`/`sample_rows` takes precedence.
+    pub fn auto_sample(mut self, threshold_bytes: Option<u64>, rows: u64) -> Self {
+        self.auto_sample_bytes = threshold_bytes;
+        self.auto_sample_rows = rows;
+        self
+    }
+
+    /// Balance the sample over the values of a low-cardinality `column`, or
+    /// over the partitions of a hive-partitioned dataset when `column` is a
+    /// partition key; implies `SampleMethod::Rows`. Needs `sample` or
+    /// `sample_rows` for the size.
+    pub fn stratify_by(mut self, column: impl Into<String>, allocation: StrataAllocation) -> Self {
+        self.stratify_by = Some((column.into(), allocation));
+        self.sample_method = SampleMethod::Rows;
+        self
+    }
+
+    /// Profile a uniform sample of exactly `rows` rows; implies `SampleMethod::Rows`.
+    pub fn sample_rows(mut self, rows: u64) -> Self {
+        self.sample_rows = Some(rows);
+        self.sample_method = SampleMethod::Rows;
+        self
+    }
+
+    pub fn no_extrapolation(mut self, no_extrapolation: bool) -> Self {
+        self.no_extrapolation = no_extrapolation;
+        self
+    }
+
+    /// Also read the data for per-column distributions, over every file of a local
+    /// dataset (only the first file when `sample` is set).
+    pub fn with_full_scan(mut self, full_scan: bool) -> Self {
+        self.full_scan = full_scan;
+        self
+    }
+
+    /// Read nothing but footers: skips sampling, the full scan and the time
+    /// series / nested profiles, which all decode pages. Bounds the cost by the
+    /// number of files rather than their size.
+    pub fn metadata_only(mut self, metadata_only: bool) -> Self {
+        self.metadata_only = metadata_only;
+        self
+    }
+
+    /// Decode each row group of the first file and compare the rows present
+    /// with the footer's counts, catching truncated or torn writes. Local only.
+    pub fn verify_row_counts(mut self, verify: bool) -> Self {
+        self.verify_row_counts = verify;
+        self
+    }
+
+    /// Scan the string columns of every local file for PII, flagging the
+    /// columns that hold it in their quality scores.
+    pub fn scan_pii(mut self, pii: bool) -> Self {
+        self.pii = pii;
+        self
+    }
+
+    /// Compare against the saved baseline and report regressions.
+    pub fn with_baseline(mut self, baseline: bool) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    /// Restricts the full scan to these columns.
+    pub fn columns(mut self, columns: Vec<String>) -> Self {
+        self.columns = Some(columns);
+        self
+    }
+
+    pub fn histogram_bins(mut self, bins: usize) -> Self {
+        self.histogram_bins = bins;
+        self
+    }
+
+    pub fn timeout_secs(mut self, secs: u64) -> Self {
+        self.timeout_secs = Some(secs);
+        self
+    }
+
+    pub fn scan_options(mut self, scan: ScanOptions) -> Self {
+        self.scan = scan;
+        self
+    }
+
+    /// Reuse and update an on-disk profile cache.
+    pub fn cache(mut self, cache: ProfileCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn s3_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.s3_endpoint = Some(endpoint.into());
+        self
+    }
+
+    #[tracing::instrument(name = "profiler", skip_all, fields(path = ?self.path, sample = ?self.sample))]
+    pub async fn run(mut self) -> Result<ProfileReport> {
+        let mut clock = StageClock::start();
+        let paths = match (self.resolved.take(), &self.path) {
+            (Some(p), _) => p,
+            (None, Some(input)) => resolve_paths(input).await?,
+            (None, None) => {
+                return Err(ParquetLensError::Other("profiler: no path set".into()));
+            }
+        };
+        if paths.is_empty() {
+            return Err(ParquetLensError::NotFound(format!(
+                "no Parquet files match {}",
+                self.path.as_deref().unwrap_or_default()
+            )));
+        }
+        let first = paths[0].path.to_string_lossy().into_owned();
+        let remote = is_remote_uri(&first);
+        let dataset = match &self.cache {
+            _ if remote => read_metadata_remote(&paths, self.s3_endpoint.as_deref()).await?,
+            Some(c) => c.read_metadata(&paths)?,
+            None => read_metadata_parallel(&paths)?,
+        };
+        clock.lap("metadata");
+        let (file_info, meta) = open_parquet_auto(&first, self.s3_endpoint.as_deref()).await?;
+        let handle = if remote {
+            None
+        } else {
+            Some(HandleRegistry::global().get(&paths[0].path)?)
+        };
+        let total_rows = file_info.row_count;
+        let mut warnings = Vec::new();
+        // scans skip row groups that fail to decode rather than failing the run
+        let quarantine = self
+            .scan
+            .quarantine
+            .get_or_insert_with(|| Arc::new(QuarantineLog::default()))
+            .clone();
+
+        let mut sample_note = None;
+        let mut sample_confidence = Vec::new();
+        let mut full_scan = None;
+        let mut column_stats = None;
+        if self.metadata_only {
+            self.sample = None;
+            self.sample_rows = None;
+            self.full_scan = false;
+        }
+        let sample = match self.sample.or(self.sample_rows.map(|_| 100.0)) {
+            Some(_) if remote => {
+                warnings.push("sampling is only supported for local files".into());
+                None
+            }
+            Some(pct) => Some(SampleConfig {
+                percentage: pct,
+                no_extrapolation: self.no_extrapolation,
+                seed: self.sample_seed,
+                method: self.sample_method,
+                rows: self.sample_rows,
+                stratify_by: self.stratify_by.as_ref().map(|(c, _)| c.clone()),
+                allocation: self
+                    .stratify_by
+                    .as_ref()
+                    .map(|(_, a)| *a)
+                    .unwrap_or_default(),
+            }),
+            // a full scan of a huge local file reads a planned sample instead
+            None => self
+                .auto_sample_bytes
+                .filter(|&t| self.full_scan && !remote && file_info.file_size > t)
+                .map(|t| SampleConfig {
+                    percentage: auto_sample_percentage(file_info.file_size, t),
+                    no_extrapolation: false,
+                    seed: self.sample_seed,
+                    method: SampleMethod::Auto,
+                    rows: Some(self.auto_sample_rows),
+                    stratify_by: None,
+                    allocation: StrataAllocation::default(),
+                }),
+        };
+        // a hive partition key stratifies the whole dataset rather than the first file
+        let partition_key = sample
+            .as_ref()
+            .and_then(|cfg| cfg.stratify_by.as_deref())
+            .filter(|key| paths.iter().any(|p| p.partitions.contains_key(*key)));
+        let dataset_sample = partition_key.is_some();
+        if let Some(cfg) = &sample {
+            let sp = match partition_key {
+                Some(key) => sample_partitions(&paths, key, cfg, self.histogram_bins, &self.scan)?,
+                None => sample_row_groups(&paths[0].path, cfg, self.histogram_bins, &self.scan)?,
+            };
+            sample_note = Some(sp.confidence_note);
+            sample_confidence = sp.metric_confidence;
+            column_stats = Some(sp.agg_stats);
+            if self.full_scan {
+                full_scan = Some(sp.profile_results);
+            }
+            clock.lap("sample");
+        }
+        // footer stats and the full scan cover every local file; a sample stays on the first
+        let whole_dataset = !remote && paths.len() > 1;
+        let stats_rows = if dataset_sample || (whole_dataset && column_stats.is_none()) {
+            dataset.total_rows
+        } else {
+            total_rows
+        };
+        let column_stats = match column_stats {
+            Some(stats) => stats,
+            None if whole_dataset => {
+                let mut per_rg = Vec::new();
+                for p in &paths {
+                    per_rg.extend(read_column_stats(
+                        HandleRegistry::global().get(&p.path)?.metadata(),
+                    ));
+                }
+                aggregate_column_stats(&per_rg, stats_rows)
+            }
+            None => aggregate_column_stats(&read_column_stats(&meta), total_rows),
+        };
+        if self.full_scan && full_scan.is_none() {
+            let columns = self.columns.as_deref();
+            full_scan = Some(if remote {
+                self.scan_remote(&first, columns).await?
+            } else if let (true, Some(c)) = (whole_dataset, &self.cache) {
+                c.profile_dataset(
+                    &paths,
+                    columns,
+                    self.histogram_bins,
+                    self.timeout_secs,
+                    &self.scan,
+                )?
+            } else if whole_dataset {
+                profile_dataset(
+                    &paths,
+                    columns,
+                    self.histogram_bins,
+                    self.timeout_secs,
+                    &self.scan,
+                )?
+            } else if let Some(c) = &self.cache {
+                c.profile_columns(
+                    &paths[0].path,
+                    columns,
+                    self.histogram_bins,
+                    self.timeout_secs,
+                    &self.scan,
+                )?
+            } else {
+                profile_columns_with_timeout(
+                    &paths[0].path,
+                    columns,
+                    self.histogram_bins,
+                    self.timeout_secs,
+                    &self.scan,
+                )?
+            });
+            clock.lap("full_scan");
+        }
+        if let Some(limit) = self
+            .scan
+            .memory
+            .as_ref()
+            .filter(|m| m.over_limit())
+            .and_then(|m| m.limit())
+        {
+            warnings.push(format!(
+                "memory limit of {} reached: top values are approximate and quantiles coarser",
+                format_bytes(limit)
+            ));
+        }
+
+        let row_groups = profile_row_groups(&meta);
+        let encodings = analyze_encodings(&meta);
+        let compression = analyze_compression(&meta);
+        let mut scores = score_columns(&column_stats, &encodings, stats_rows);
+        if let Some(fs) = &full_scan {
+            apply_full_scan_findings(&mut scores, fs);
+        }
+        let mut pii = None;
+        if self.pii && !self.metadata_only {
+            if remote {
+                warnings.push("PII scanning is only supported for local files".into());
+            } else {
+                let files: Vec<_> = paths.iter().map(|p| p.path.clone()).collect();
+                match scan_pii(&files, &self.scan) {
+                    Ok(report) => {
+                        apply_pii_findings(&mut scores, &report);
+                        pii = Some(report);
+                    }
+                    Err(e) => warnings.push(format!("pii: {e}")),
+                }
+                clock.lap("pii");
+            }
+        }
+        let (baseline_captured_at, baseline_regressions) = if self.baseline {
+            let (base, regressions) = load_baseline_regressions(
+                &paths[0].path,
+                &column_stats,
+                &scores,
+                &dataset.combined_schema,
+            );
+            (base.map(|b| b.captured_at), regressions)
+        } else {
+            (None, Vec::new())
+        };
+        let total_cells = stats_rows * dataset.combined_schema.len() as i64;
+        let total_nulls: u64 = column_stats.iter().map(|s| s.total_null_count).sum();
+        let quality = summarize_quality(
+            scores,
+            total_cells,
+            total_nulls,
+            dataset.schema_inconsistencies.is_empty(),
+            &column_stats,
+        );
+        clock.lap("stats");
+
+        let mut timeseries = Vec::new();
+        let mut nested = Vec::new();
+        let mut nested_data = None;
+        if let Some(h) = handle.as_ref().filter(|_| !self.metadata_only) {
+            let ts_cols = detect_timestamp_columns(&dataset.combined_schema);
+            if !ts_cols.is_empty() {
+                match profile_timeseries_with_handle(h, &ts_cols) {
+                    Ok(ts) => timeseries = ts,
+                    Err(e) => warnings.push(format!("timeseries: {e}")),
+                }
+            }
+            match profile_nested_columns_with_handle(h) {
+                Ok(np) => nested = np,
+                Err(e) => warnings.push(format!("nested profile: {e}")),
+            }
+            // reading the nested values costs a scan, so it comes with one
+            if self.full_scan && !nested.is_empty() {
+                match profile_nested_data_with_handle(h, &self.scan) {
+                    Ok(nd) => nested_data = Some(nd),
+                    Err(e) => warnings.push(format!("nested data: {e}")),
+                }
+            }
+            clock.lap("timeseries_nested");
+        }
+
+        let mut row_counts = None;
+        if self.verify_row_counts && !self.metadata_only {
+            match &handle {
+                Some(h) => {
+                    row_counts = Some(reconcile_row_counts_with_handle(h, &self.scan)?);
+                    clock.lap("row_counts");
+                }
+                None => {
+                    warnings.push("row count verification is only supported for local files".into())
+                }
+            }
+        }
+
+        let mut inputs = Vec::with_capacity(paths.len());
+        let fingerprints = futures::future::join_all(paths.iter().map(|p| async move {
+            let uri = p.path.to_string_lossy();
+            StorageRegistry::global()
+                .for_uri(&uri)?
+                .metadata(&uri)
+                .await
+        }))
+        .await;
+        for (p, info) in paths.iter().zip(fingerprints) {
+            match info {
+                Ok(info) => inputs.push(info),
+                Err(e) => warnings.push(format!("fingerprint {}: {e}", p.path.display())),
+            }
+        }
+        clock.lap("fingerprint");
+
+        Ok(ProfileReport {
+            null_patterns: analyze_null_patterns(&column_stats),
+            repair_suggestions: detect_repair_suggestions(&row_groups, &column_stats, &encodings),
+            row_group_recommendation: recommend_row_group_size(&row_groups),
+            engine_info: file_info.created_by.as_deref().map(identify_engine),
+            dataset,
+            file_info,
+            row_groups,
+            column_stats,
+            encodings,
+            compression,
+            quality,
+            timeseries_profiles: timeseries,
+            nested_profiles: nested,
+            nested_data,
+            baseline_captured_at,
+            baseline_regressions,
+            full_scan,
+            sample_note,
+            sample_confidence,
+            quarantine: quarantine.report(stats_rows),
+            row_counts,
+            pii,
+            warnings,
+            run: Some(clock.finish(inputs)),
+        })
+    }
+
+    /// Remote objects are streamed down in chunks to a temp file, as
+    /// `duplicates` and `filter` do, and scanned like a local file.
+    #[cfg(feature = "tokio")]
+    async fn scan_remote(
+        &self,
+        uri: &str,
+        columns: Option<&[String]>,
+    ) -> Result<Vec<ColumnProfileResult>> {
+        let mut tmp = tempfile::Builder::new().suffix(".parquet").tempfile()?;
+        let backend = crate::reader::backend_for(uri, self.s3_endpoint.as_deref())?;
+        crate::storage::download_object(backend.as_ref(), uri, tmp.as_file_mut()).await?;
+        profile_columns_with_timeout(
+            tmp.path(),
+            columns,
+            self.histogram_bins,
+            self.timeout_secs,
+            &self.scan,
+        )
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    async fn scan_remote(
+        &self,
+        uri: &str,
+        _columns: Option<&[String]>,
+    ) -> Result<Vec<ColumnProfileResult>> {
+        Err(ParquetLensError::Unsupported {
+            feature: format!("full scan of {uri} (build with the `tokio` feature)"),
+        })
+    }
+
+    /// `run` for synchronous callers; must not be called from inside a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn run_blocking(self) -> Result<ProfileReport> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(self.run())
+    }
+
+    /// `run` for synchronous callers. Without the `tokio` feature only local
+    /// inputs need no runtime, so a plain executor is enough.
+    #[cfg(not(feature = "tokio"))]
+    pub fn run_blocking(self) -> Result<ProfileReport> {
+        futures::executor::block_on(self.run())
+    }
+}
@@ -0,0 +1,172 @@
+use parquet::file::metadata::ParquetMetaData;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Amount of work, in rows and bytes. Zero fields in a total mean "unknown".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    pub rows: u64,
+    pub bytes: u64, // on-disk (compressed) bytes
+}
+
+/// Receives progress from long-running core operations (full scan, sampling,
+/// duplicates, filtering, footer reads). Attach one through
+/// `ScanOptions::progress`; `operation` names the call, e.g. `"profile"`.
+/// Callbacks can arrive from worker threads, so implementations must be cheap.
+pub trait ProgressSink: Send + Sync {
+    fn on_start(&self, _operation: &str, _total: Progress) {}
+    /// Cumulative work done so far.
+    fn on_progress(&self, _operation: &str, _done: Progress) {}
+    /// A worker started reading `row_group` of `file` (a path or URI). Scans
+    /// run row groups in parallel, so several can be in flight at once.
+    fn on_row_group(&self, _operation: &str, _file: &str, _row_group: usize) {}
+    /// Called once, also when the operation fails or stops early.
+    fn on_finish(&self, _operation: &str, _done: Progress) {}
+}
+
+impl std::fmt::Debug for dyn ProgressSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressSink")
+    }
+}
+
+/// One running operation's counters. Workers add increments and the sink sees
+/// running totals; `on_finish` fires on drop. Without a sink it does nothing.
+pub(crate) struct ProgressReporter {
+    sink: Option<Arc<dyn ProgressSink>>,
+    operation: &'static str,
+    total: Progress,
+    rows: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl ProgressReporter {
+    pub(crate) fn start(
+        sink: Option<Arc<dyn ProgressSink>>,
+        operation: &'static str,
+        total: Progress,
+    ) -> Self {
+        if let Some(s) = &sink {
+            s.on_start(operation, total);
+        }
+        Self {
+            sink,
+            operation,
+            total,
+            rows: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn advance(&self, rows: u64, bytes: u64) {
+        let Some(s) = &self.sink else {
+            return;
+        };
+        let done = Progress {
+            rows: self.rows.fetch_add(rows, Ordering::Relaxed) + rows,
+            bytes: self.bytes.fetch_add(bytes, Ordering::Relaxed) + bytes,
+        };
+        s.on_progress(self.operation, done);
+    }
+
+    pub(crate) fn enter_row_group(&self, file: &str, row_group: usize) {
+        if let Some(s) = &self.sink {
+            s.on_row_group(self.operation, file, row_group);
+        }
+    }
+
+    /// Advances by `rows`, crediting bytes pro rata from the totals (decoded
+    /// batches don't map back to on-disk bytes exactly).
+    pub(crate) fn advance_rows(&self, rows: u64) {
+        let Some(s) = &self.sink else {
+            return;
+        };
+        let done_rows = self.rows.fetch_add(rows, Ordering::Relaxed) + rows;
+        // derived from the running row count so rounding doesn't accumulate
+        let bytes = if self.total.rows > 0 {
+            (done_rows as u128 * self.total.bytes as u128 / self.total.rows as u128) as u64
+        } else {
+            0
+        };
+        let done_bytes = self.bytes.fetch_max(bytes, Ordering::Relaxed).max(bytes);
+        s.on_progress(
+            self.operation,
+            Progress {
+                rows: done_rows,
+                bytes: done_bytes,
+            },
+        );
+    }
+
+    fn done(&self) -> Progress {
+        Progress {
+            rows: self.rows.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Drop for ProgressReporter {
+    fn drop(&mut self) {
+        if let Some(s) = &self.sink {
+            s.on_finish(self.operation, self.done());
+        }
+    }
+}
+
+/// Rows and compressed bytes of the given row groups.
+pub(crate) fn row_group_totals(meta: &ParquetMetaData, row_groups: &[usize]) -> Progress {
+    row_groups
+        .iter()
+        .map(|&i| meta.row_group(i))
+        .fold(Progress::default(), |acc, rg| Progress {
+            rows: acc.rows + rg.num_rows().max(0) as u64,
+            bytes: acc.bytes + rg.compressed_size().max(0) as u64,
+        })
+}
+
+#[cfg(test)]
+mod tests_progress {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, Progress)>>);
+
+    impl ProgressSink for Recorder {
+        fn on_progress(&self, _operation: &str, done: Progress) {
+            self.0.lock().unwrap().push(("progress".into(), done));
+        }
+        fn on_finish(&self, _operation: &str, done: Progress) {
+            self.0.lock().unwrap().push(("finish".into(), done));
+        }
+    }
+
+    #[test]
+    fn reports_running_totals_and_finishes_on_drop() {
+        let rec = Arc::new(Recorder::default());
+        {
+            let p = ProgressReporter::start(
+                Some(rec.clone()),
+                "test",
+                Progress {
+                    rows: 100,
+                    bytes: 1000,
+                },
+            );
+            p.advance_rows(40);
+            p.advance_rows(60);
+        }
+        let events = rec.0.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[1].1,
+            Progress {
+                rows: 100,
+                bytes: 1000
+            }
+        );
+        assert_eq!(events[2].0, "finish");
+    }
+}
@@ -0,0 +1,645 @@
+pub mod pii;
+
+pub use pii::{
+    apply_pii_findings, scan_pii, ColumnPii, PiiKind, PiiMatch, PiiReport, LIKELY_PII_PCT,
+};
+
+use crate::constraints::{check_constraints, Constraint, ConstraintResult};
+use crate::filter::{CmpOp, Predicate, Value};
+use crate::handle::{FileHandle, HandleRegistry};
+use crate::options::{prefetch_batches, ScanOptions};
+use crate::profile::ColumnProfileResult;
+use crate::progress::row_group_totals;
+use crate::stats::{AggregatedColumnStats, EncodingAnalysis};
+use arrow::array::Array;
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::ProjectionMask;
+use parquet_lens_common::{ParquetLensError, Result, RuleConfig, RuleValue};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// task 23: per-column quality score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityScore {
+    pub column_name: String,
+    pub score: u8, // 0-100
+    pub null_penalty: f64,
+    pub is_constant: bool,
+    pub cardinality_flag: bool,
+    pub is_plain_only_encoding: bool,
+    pub breakdown: String,
+    /// % of string values with charset artifacts; only known after a full scan
+    #[serde(default)]
+    pub encoding_anomaly_pct: f64,
+    /// % of numeric values outside the IQR fences; only known after a full scan
+    #[serde(default)]
+    pub outlier_pct: f64,
+    /// points `apply_full_scan_findings` took off `score`
+    #[serde(default)]
+    pub full_scan_penalty: u8,
+    /// PII kinds a `scan_pii` pass flagged; see `apply_pii_findings`
+    #[serde(default)]
+    pub pii: Vec<PiiKind>,
+}
+
+pub fn score_column(
+    column_name: &str,
+    null_percentage: f64,
+    distinct_count: Option<u64>,
+    total_rows: i64,
+    is_plain_only: bool,
+) -> QualityScore {
+    let mut score: f64 = 100.0;
+    let mut notes = Vec::new();
+    // null penalty: each 1% above 5% costs 2 points
+    let null_penalty = if null_percentage > 5.0 {
+        (null_percentage - 5.0) * 2.0
+    } else {
+        0.0
+    };
+    score -= null_penalty.min(60.0);
+    if null_penalty > 0.0 {
+        notes.push(format!("null_rate={null_percentage:.1}%"));
+    }
+    // constant column
+    let is_constant = distinct_count.is_some_and(|d| d <= 1);
+    if is_constant {
+        score -= 20.0;
+        notes.push("constant_column".into());
+    }
+    // high cardinality (= row count, likely an ID or raw event column)
+    let cardinality_flag = distinct_count.is_some_and(|d| total_rows > 0 && d as i64 == total_rows);
+    if cardinality_flag {
+        score -= 5.0;
+        notes.push("cardinality=row_count".into());
+    }
+    // plain-only encoding
+    if is_plain_only {
+        score -= 5.0;
+        notes.push("plain_only_encoding".into());
+    }
+    QualityScore {
+        column_name: column_name.to_owned(),
+        score: score.max(0.0).round() as u8,
+        null_penalty,
+        is_constant,
+        cardinality_flag,
+        is_plain_only_encoding: is_plain_only,
+        breakdown: notes.join(", "),
+        encoding_anomaly_pct: 0.0,
+        outlier_pct: 0.0,
+        full_scan_penalty: 0,
+        pii: Vec::new(),
+    }
+}
+
+/// `score_column` for every aggregated column, looking up its encoding analysis.
+pub fn score_columns(
+    agg_stats: &[AggregatedColumnStats],
+    encodings: &[EncodingAnalysis],
+    total_rows: i64,
+) -> Vec<QualityScore> {
+    agg_stats
+        .iter()
+        .map(|agg| {
+            let is_plain = encodings
+                .iter()
+                .find(|e| e.column_name == agg.column_name)
+                .map(|e| e.is_plain_only)
+                .unwrap_or(false);
+            score_column(
+                &agg.column_name,
+                agg.null_percentage,
+                agg.total_distinct_count_estimate,
+                total_rows,
+                is_plain,
+            )
+        })
+        .collect()
+}
+
+/// Docks columns for what only a full scan sees. String columns with charset
+/// artifacts (mojibake, control characters, replacement characters,
+/// mixed-script words) lose 2 points per 1% of affected values, at least 5
+/// and at most 30; numeric columns lose a point per 1% of IQR outliers past
+/// the first 1%, at most 15. Applying it again, after a rescan, replaces the
+/// earlier deduction.
+pub fn apply_full_scan_findings(scores: &mut [QualityScore], full_scan: &[ColumnProfileResult]) {
+    for r in full_scan {
+        let Some(score) = scores.iter_mut().find(|s| s.column_name == r.column_name) else {
+            continue;
+        };
+        score.score += score.full_scan_penalty;
+        score.full_scan_penalty = 0;
+        let mut notes: Vec<String> = score
+            .breakdown
+            .split(", ")
+            .filter(|n| {
+                !n.is_empty()
+                    && !n.starts_with("encoding_anomalies=")
+                    && !n.starts_with("outliers=")
+            })
+            .map(str::to_owned)
+            .collect();
+        let mut penalty = 0.0;
+        if let Some(sp) = &r.string {
+            let e = &sp.encoding;
+            score.encoding_anomaly_pct = e.affected_pct(sp.count);
+            if e.affected_count > 0 {
+                penalty += (score.encoding_anomaly_pct * 2.0).clamp(5.0, 30.0);
+                let kinds: Vec<String> = [
+                    ("mojibake", e.mojibake_count),
+                    ("control", e.control_char_count),
+                    ("replacement", e.replacement_char_count),
+                    ("mixed_script", e.mixed_script_count),
+                ]
+                .iter()
+                .filter(|(_, n)| *n > 0)
+                .map(|(k, n)| format!("{k}={n}"))
+                .collect();
+                notes.push(format!(
+                    "encoding_anomalies={:.1}% ({})",
+                    score.encoding_anomaly_pct,
+                    kinds.join(" ")
+                ));
+            }
+        }
+        if let Some(np) = &r.numeric {
+            score.outlier_pct = np.outlier_pct;
+            if np.outlier_pct > 1.0 {
+                penalty += (np.outlier_pct - 1.0).min(15.0);
+                notes.push(format!("outliers={:.1}%", np.outlier_pct));
+            }
+        }
+        score.full_scan_penalty = (penalty.round() as u8).min(score.score);
+        score.score -= score.full_scan_penalty;
+        score.breakdown = notes.join(", ");
+    }
+}
+
+// task 24: dataset-level quality summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetQuality {
+    pub overall_score: u8,
+    pub total_null_cell_pct: f64,
+    pub worst_columns: Vec<String>,
+    pub schema_consistent: bool,
+    pub column_scores: Vec<QualityScore>,
+}
+
+pub fn summarize_quality(
+    scores: Vec<QualityScore>,
+    total_cells: i64,
+    total_nulls: u64,
+    schema_consistent: bool,
+    agg_stats: &[AggregatedColumnStats],
+) -> DatasetQuality {
+    let overall_score = if scores.is_empty() {
+        100
+    } else {
+        // weighted mean by total_data_page_size; fall back to arithmetic mean if all weights zero
+        let weight_sum: i64 = scores
+            .iter()
+            .filter_map(|s| agg_stats.iter().find(|a| a.column_name == s.column_name))
+            .map(|a| a.total_data_page_size.max(0))
+            .sum();
+        if weight_sum > 0 {
+            let weighted: f64 = scores
+                .iter()
+                .map(|s| {
+                    let w = agg_stats
+                        .iter()
+                        .find(|a| a.column_name == s.column_name)
+                        .map(|a| a.total_data_page_size.max(0))
+                        .unwrap_or(0);
+                    s.score as f64 * w as f64
+                })
+                .sum::<f64>();
+            (weighted / weight_sum as f64).round() as u8
+        } else {
+            (scores.iter().map(|s| s.score as u32).sum::<u32>() / scores.len() as u32) as u8
+        }
+    };
+    let total_null_cell_pct = if total_cells > 0 {
+        total_nulls as f64 / total_cells as f64 * 100.0
+    } else {
+        0.0
+    };
+    let mut sorted = scores.clone();
+    #[allow(clippy::unnecessary_sort_by)]
+    sorted.sort_by(|a, b| a.score.cmp(&b.score));
+    let worst_columns = sorted
+        .iter()
+        .filter(|s| s.score < 80) // only genuinely poor columns
+        .take(5)
+        .map(|s| s.column_name.clone())
+        .collect();
+    DatasetQuality {
+        overall_score,
+        total_null_cell_pct,
+        worst_columns,
+        schema_consistent,
+        column_scores: scores,
+    }
+}
+
+/// One constraint per check each rule sets, in rule order and, within a rule,
+/// in `RuleConfig` field order.
+pub fn rule_constraints(rules: &[RuleConfig]) -> Result<Vec<Constraint>> {
+    let mut out = Vec::new();
+    for rule in rules {
+        let column = rule.column.as_str();
+        if rule.not_null {
+            out.push(Constraint::NotNull(column.to_string()));
+        }
+        if rule.unique {
+            out.push(Constraint::Unique(column.to_string()));
+        }
+        let bound = |op: CmpOp, v: &RuleValue| Predicate::Comparison {
+            col: column.to_string(),
+            op,
+            val: filter_value(v),
+        };
+        let range = match (&rule.min, &rule.max) {
+            (Some(min), Some(max)) => Some((
+                format!("{column} >= {min} AND {column} <= {max}"),
+                Predicate::And(
+                    Box::new(bound(CmpOp::Ge, min)),
+                    Box::new(bound(CmpOp::Le, max)),
+                ),
+            )),
+            (Some(min), None) => Some((format!("{column} >= {min}"), bound(CmpOp::Ge, min))),
+            (None, Some(max)) => Some((format!("{column} <= {max}"), bound(CmpOp::Le, max))),
+            (None, None) => None,
+        };
+        if let Some((expr, predicate)) = range {
+            out.push(Constraint::Range {
+                column: column.to_string(),
+                expr,
+                predicate,
+            });
+        }
+        if let Some(pattern) = &rule.regex {
+            out.push(Constraint::regex(column, pattern)?);
+        }
+        if let Some(values) = &rule.allowed_values {
+            out.push(Constraint::AllowedValues {
+                column: column.to_string(),
+                values: values.iter().map(|v| v.to_string()).collect(),
+                predicate: Predicate::In {
+                    col: column.to_string(),
+                    vals: values.iter().map(filter_value).collect(),
+                },
+            });
+        }
+        if let Some(max_pct) = rule.max_null_pct {
+            out.push(Constraint::MaxNullPct {
+                column: column.to_string(),
+                max_pct,
+            });
+        }
+        if let Some(age) = &rule.freshness {
+            out.push(Constraint::freshness(column, age)?);
+        }
+    }
+    Ok(out)
+}
+
+fn filter_value(v: &RuleValue) -> Value {
+    match v {
+        RuleValue::Int(i) => Value::Int(*i),
+        RuleValue::Float(f) => Value::Float(*f),
+        RuleValue::Text(t) => Value::Str(t.clone()),
+    }
+}
+
+/// Evaluates `[[rules]]` over `paths` in one pass, one pass/fail result per
+/// check. Rules limited to other datasets should be filtered out first with
+/// `RuleConfig::matches`.
+pub fn run_rules(
+    paths: &[PathBuf],
+    rules: &[RuleConfig],
+    opts: &ScanOptions,
+) -> Result<Vec<ConstraintResult>> {
+    let constraints = rule_constraints(rules)?;
+    if constraints.is_empty() {
+        return Ok(Vec::new());
+    }
+    check_constraints(paths, &constraints, opts)
+}
+
+// task 25: duplicate row detection with bloom filter + xxhash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateReport {
+    pub total_rows: u64,
+    pub estimated_duplicates: u64,
+    pub estimated_duplicate_pct: f64,
+    #[serde(default)]
+    pub ignored_columns: Vec<String>, // left out of the row hash
+}
+
+/// Hash a single row across all columns into a u64 fingerprint.
+fn hash_row(batch: &arrow::record_batch::RecordBatch, row: usize) -> u64 {
+    use xxhash_rust::xxh3::xxh3_64;
+    let mut row_bytes = Vec::new();
+    for col in batch.columns() {
+        if !col.is_null(row) {
+            match col.data_type() {
+                arrow::datatypes::DataType::Int32 => {
+                    if let Some(arr) = col.as_any().downcast_ref::<arrow::array::Int32Array>() {
+                        row_bytes.extend_from_slice(&arr.value(row).to_le_bytes());
+                    }
+                }
+                arrow::datatypes::DataType::Int64 => {
+                    if let Some(arr) = col.as_any().downcast_ref::<arrow::array::Int64Array>() {
+                        row_bytes.extend_from_slice(&arr.value(row).to_le_bytes());
+                    }
+                }
+                arrow::datatypes::DataType::Float32 => {
+                    if let Some(arr) = col.as_any().downcast_ref::<arrow::array::Float32Array>() {
+                        row_bytes.extend_from_slice(&arr.value(row).to_le_bytes());
+                    }
+                }
+                arrow::datatypes::DataType::Float64 => {
+                    if let Some(arr) = col.as_any().downcast_ref::<arrow::array::Float64Array>() {
+                        row_bytes.extend_from_slice(&arr.value(row).to_le_bytes());
+                    }
+                }
+                arrow::datatypes::DataType::Boolean => {
+                    if let Some(arr) = col.as_any().downcast_ref::<arrow::array::BooleanArray>() {
+                        row_bytes.push(arr.value(row) as u8);
+                    }
+                }
+                arrow::datatypes::DataType::Utf8 => {
+                    if let Some(arr) = col.as_any().downcast_ref::<arrow::array::StringArray>() {
+                        row_bytes.extend_from_slice(arr.value(row).as_bytes());
+                    }
+                }
+                arrow::datatypes::DataType::LargeUtf8 => {
+                    if let Some(arr) = col
+                        .as_any()
+                        .downcast_ref::<arrow::array::LargeStringArray>()
+                    {
+                        row_bytes.extend_from_slice(arr.value(row).as_bytes());
+                    }
+                }
+                _ => row_bytes.push(0u8),
+            }
+        } else {
+            row_bytes.push(0xFF);
+        }
+    }
+    xxh3_64(&row_bytes)
+}
+
+/// Set of row hashes seen so far; one instance per shard.
+trait SeenHashes: Send {
+    /// Records `hash`, returning true if it was (probably) already present.
+    fn check_and_insert(&mut self, hash: u64) -> bool;
+    fn approx_bytes(&self) -> usize;
+}
+
+impl SeenHashes for std::collections::HashSet<u64> {
+    fn check_and_insert(&mut self, hash: u64) -> bool {
+        !self.insert(hash)
+    }
+    fn approx_bytes(&self) -> usize {
+        self.capacity() * 9 // value + control byte
+    }
+}
+
+impl SeenHashes for bloomfilter::Bloom<u64> {
+    fn check_and_insert(&mut self, hash: u64) -> bool {
+        if self.check(&hash) {
+            true
+        } else {
+            self.set(&hash);
+            false
+        }
+    }
+    fn approx_bytes(&self) -> usize {
+        (self.number_of_bits() / 8) as usize
+    }
+}
+
+// row hashes are partitioned across shards so workers rarely contend on a lock
+const DUP_SHARDS: usize = 64;
+
+/// Hashes every row group in parallel, routing each hash to the shard that owns it.
+/// Returns (total_rows, duplicates).
+fn count_duplicates<S: SeenHashes>(
+    path: &Path,
+    meta: &ArrowReaderMetadata,
+    mask: &ProjectionMask,
+    shards: &[Mutex<S>],
+    opts: &ScanOptions,
+) -> Result<(u64, u64)> {
+    let pool = crate::profile::full_scan::scan_pool(opts.threads)?;
+    let rgs: Vec<usize> = (0..meta.metadata().num_row_groups()).collect();
+    let progress = opts.start_progress("duplicates", row_group_totals(meta.metadata(), &rgs));
+    // shards only grow, so their size is tracked as deltas while inserting
+    let shard_bytes = |s: &Mutex<S>| s.lock().unwrap_or_else(|e| e.into_inner()).approx_bytes();
+    let initial: usize = shards.iter().map(shard_bytes).sum();
+    if let Some(t) = &opts.memory {
+        t.add(initial);
+    }
+    let counted = pool.install(|| {
+        rgs.par_iter()
+            .map(|&rg| -> Result<(u64, u64)> {
+                let location = || format!("{} row group {rg}", path.display());
+                let reader = ParquetRecordBatchReaderBuilder::new_with_metadata(
+                    std::fs::File::open(path).map_err(|e| ParquetLensError::io_at(e, path))?,
+                    meta.clone(),
+                )
+                .with_projection(mask.clone())
+                .with_row_groups(vec![rg])
+                .with_batch_size(opts.batch_size)
+                .build()
+                .map_err(|e| ParquetLensError::corrupt(location(), e))?;
+                let mut batch_gauge = opts.gauge();
+                let mut rows = 0u64;
+                let mut dups = 0u64;
+                let mut buckets: Vec<Vec<u64>> = vec![Vec::new(); shards.len()];
+                for batch_result in prefetch_batches(reader, opts.prefetch_depth) {
+                    opts.check_cancelled()?;
+                    let batch =
+                        batch_result.map_err(|e| ParquetLensError::corrupt(location(), e))?;
+                    batch_gauge.set(batch.get_array_memory_size());
+                    for row in 0..batch.num_rows() {
+                        let hash = hash_row(&batch, row);
+                        buckets[(hash % shards.len() as u64) as usize].push(hash);
+                    }
+                    rows += batch.num_rows() as u64;
+                    progress.advance_rows(batch.num_rows() as u64);
+                    for (shard, bucket) in shards.iter().zip(buckets.iter_mut()) {
+                        if bucket.is_empty() {
+                            continue;
+                        }
+                        let mut seen = shard.lock().unwrap_or_else(|e| e.into_inner());
+                        let before = seen.approx_bytes();
+                        for hash in bucket.drain(..) {
+                            if seen.check_and_insert(hash) {
+                                dups += 1;
+                            }
+                        }
+                        if let Some(t) = &opts.memory {
+                            t.add(seen.approx_bytes().saturating_sub(before));
+                        }
+                    }
+                }
+                Ok((rows, dups))
+            })
+            .try_reduce(|| (0, 0), |a, b| Ok((a.0 + b.0, a.1 + b.1)))
+    });
+    if let Some(t) = &opts.memory {
+        t.sub(shards.iter().map(shard_bytes).sum());
+    }
+    counted
+}
+
+/// Detect duplicate rows. For files with <= 5_000_000 rows (or when exact=true),
+/// uses a HashSet<u64> for authoritative counts. Otherwise uses a bloom filter
+/// (~1% false-positive rate) to keep memory bounded. Row groups are hashed on
+/// `opts.threads` workers (None = all cores). Top-level `ignore_columns` are
+/// left out of the row hash, so rows differing only there count as duplicates.
+pub fn detect_duplicates(
+    path: &Path,
+    exact: bool,
+    ignore_columns: &[String],
+    opts: &ScanOptions,
+) -> Result<DuplicateReport> {
+    detect_duplicates_with_handle(
+        &HandleRegistry::global().get(path)?,
+        exact,
+        ignore_columns,
+        opts,
+    )
+}
+
+#[tracing::instrument(name = "duplicates", skip_all, fields(path = %handle.path().display(), exact))]
+pub fn detect_duplicates_with_handle(
+    handle: &FileHandle,
+    exact: bool,
+    ignore_columns: &[String],
+    opts: &ScanOptions,
+) -> Result<DuplicateReport> {
+    use bloomfilter::Bloom;
+
+    let path = handle.path();
+    let meta = handle.arrow_metadata()?;
+    let schema = meta.schema();
+    if let Some(missing) = ignore_columns
+        .iter()
+        .find(|c| schema.index_of(c.as_str()).is_err())
+    {
+        return Err(ParquetLensError::NotFound(format!("column {missing}")));
+    }
+    let hashed: Vec<String> = schema
+        .fields()
+        .iter()
+        .map(|f| f.name().clone())
+        .filter(|name| !ignore_columns.contains(name))
+        .collect();
+    if hashed.is_empty() {
+        return Err(ParquetLensError::Other(
+            "every column is ignored; nothing left to compare rows by".into(),
+        ));
+    }
+    let (mask, _) = crate::profile::full_scan::projection(&meta, Some(&hashed));
+    // estimate row count from metadata for bloom sizing / exact threshold
+    let total_rows_estimate = meta.metadata().file_metadata().num_rows().max(1) as usize;
+
+    let use_exact = exact || total_rows_estimate <= 5_000_000; // exact threshold: 5M rows
+    let (total_rows, dups) = if use_exact {
+        let per_shard = total_rows_estimate.min(5_000_000) / DUP_SHARDS;
+        let shards: Vec<Mutex<std::collections::HashSet<u64>>> = (0..DUP_SHARDS)
+            .map(|_| Mutex::new(std::collections::HashSet::with_capacity(per_shard)))
+            .collect();
+        count_duplicates(path, &meta, &mask, &shards, opts)?
+    } else {
+        // bloom filter: 1% false positive rate, capped at 50M to prevent OOM
+        if total_rows_estimate > 10_000_000 {
+            tracing::warn!(
+                "bloom filter for {} rows may use significant memory; consider --exact for authoritative results",
+                total_rows_estimate
+            );
+        }
+        let bloom_size = total_rows_estimate.clamp(1000, 50_000_000);
+        let per_shard = (bloom_size / DUP_SHARDS).max(1000);
+        let shards: Vec<Mutex<Bloom<u64>>> = (0..DUP_SHARDS)
+            .map(|_| Mutex::new(Bloom::new_for_fp_rate(per_shard, 0.01)))
+            .collect();
+        count_duplicates(path, &meta, &mask, &shards, opts)?
+    };
+
+    let estimated_duplicate_pct = if total_rows > 0 {
+        dups as f64 / total_rows as f64 * 100.0
+    } else {
+        0.0
+    };
+    Ok(DuplicateReport {
+        total_rows,
+        estimated_duplicates: dups,
+        estimated_duplicate_pct,
+        ignored_columns: ignore_columns.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests_score_column {
+    use super::*;
+
+    fn sc(null_pct: f64, distinct: Option<u64>, total: i64) -> QualityScore {
+        score_column("col", null_pct, distinct, total, false)
+    }
+
+    #[test]
+    fn null_0pct() {
+        let s = sc(0.0, None, 100);
+        assert_eq!(s.null_penalty, 0.0);
+        assert_eq!(s.score, 100);
+    }
+    #[test]
+    fn null_5pct() {
+        let s = sc(5.0, None, 100);
+        assert_eq!(s.null_penalty, 0.0);
+        assert_eq!(s.score, 100);
+    }
+    #[test]
+    fn null_50pct() {
+        let s = sc(50.0, None, 100);
+        assert!((s.null_penalty - 90.0).abs() < 0.01);
+        assert_eq!(s.score, 40);
+    }
+    #[test]
+    fn null_100pct() {
+        let s = sc(100.0, None, 100);
+        assert!(s.null_penalty >= 60.0);
+        assert_eq!(s.score, 40);
+    } // capped
+    #[test]
+    fn constant_distinct_0() {
+        let s = sc(0.0, Some(0), 100);
+        assert!(s.is_constant);
+        assert_eq!(s.score, 80);
+    }
+    #[test]
+    fn constant_distinct_1() {
+        let s = sc(0.0, Some(1), 100);
+        assert!(s.is_constant);
+        assert_eq!(s.score, 80);
+    }
+    #[test]
+    fn cardinality_flag() {
+        let s = sc(0.0, Some(100), 100);
+        assert!(s.cardinality_flag);
+        assert_eq!(s.score, 95);
+    }
+    #[test]
+    fn no_cardinality_flag() {
+        let s = sc(0.0, Some(50), 100);
+        assert!(!s.cardinality_flag);
+    }
+}
@@ -0,0 +1,334 @@
+use super::QualityScore;
+use crate::handle::HandleRegistry;
+use crate::options::{prefetch_batches, ScanOptions};
+use crate::profile::full_scan::projection;
+use crate::progress::row_group_totals;
+use arrow::array::AsArray;
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use parquet_lens_common::{ParquetLensError, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Share of a column's values with some kind of PII above which the column
+/// is flagged as likely holding it.
+pub const LIKELY_PII_PCT: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiKind {
+    Email,
+    Phone,
+    /// US social security or UK national insurance numbers
+    NationalId,
+    /// Card numbers with a known issuer prefix that pass the Luhn check
+    CreditCard,
+    IpAddress,
+}
+
+impl PiiKind {
+    pub const ALL: [PiiKind; 5] = [
+        Self::Email,
+        Self::Phone,
+        Self::NationalId,
+        Self::CreditCard,
+        Self::IpAddress,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Phone => "phone",
+            Self::NationalId => "national_id",
+            Self::CreditCard => "credit_card",
+            Self::IpAddress => "ip_address",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PiiMatch {
+    pub kind: PiiKind,
+    pub count: u64, // values holding at least one match
+    pub pct: f64,   // of non-null values scanned
+}
+
+/// PII found in one string column. Only counts are kept: reports get
+/// exported and shared, so matched values never leave the scan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnPii {
+    pub column: String,
+    pub scanned_values: u64,
+    pub matches: Vec<PiiMatch>, // kinds found at all, highest rate first
+    pub likely_pii: bool,       // some kind is in at least `LIKELY_PII_PCT` of values
+}
+
+impl ColumnPii {
+    /// Kinds at or above the flagging threshold.
+    pub fn flagged_kinds(&self) -> Vec<PiiKind> {
+        self.matches
+            .iter()
+            .filter(|m| m.pct >= LIKELY_PII_PCT)
+            .map(|m| m.kind)
+            .collect()
+    }
+
+    /// `email: email 98.20%, phone 1.10%`
+    pub fn line(&self) -> String {
+        let matches: Vec<String> = self
+            .matches
+            .iter()
+            .map(|m| format!("{} {:.2}%", m.kind.label(), m.pct))
+            .collect();
+        format!("{}: {}", self.column, matches.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PiiReport {
+    pub scanned_rows: u64,
+    pub columns: Vec<ColumnPii>, // every string column scanned, in schema order
+}
+
+impl PiiReport {
+    pub fn flagged(&self) -> impl Iterator<Item = &ColumnPii> {
+        self.columns.iter().filter(|c| c.likely_pii)
+    }
+}
+
+/// Scans every top-level string column of `paths` for emails, phone numbers,
+/// national IDs, card numbers and IP addresses anywhere in the value, so free
+/// text columns are caught as well as ones holding nothing else.
+pub fn scan_pii(paths: &[PathBuf], opts: &ScanOptions) -> Result<PiiReport> {
+    let mut columns: Vec<(String, [u64; 5], u64)> = Vec::new();
+    let mut scanned_rows = 0u64;
+    for path in paths {
+        let handle = HandleRegistry::global().get(path)?;
+        let meta = handle.arrow_metadata()?;
+        let names: Vec<String> = meta
+            .schema()
+            .fields()
+            .iter()
+            .filter(|f| {
+                matches!(
+                    f.data_type(),
+                    DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+                )
+            })
+            .map(|f| f.name().clone())
+            .collect();
+        for name in &names {
+            if !columns.iter().any(|(c, ..)| c == name) {
+                columns.push((name.clone(), [0; 5], 0));
+            }
+        }
+        if names.is_empty() {
+            scanned_rows += meta.metadata().file_metadata().num_rows().max(0) as u64;
+            continue;
+        }
+        let (mask, _) = projection(&meta, Some(&names));
+        let rgs: Vec<usize> = (0..meta.metadata().num_row_groups()).collect();
+        let progress = opts.start_progress("pii", row_group_totals(meta.metadata(), &rgs));
+        let reader = handle
+            .reader_builder()?
+            .with_projection(mask)
+            .with_batch_size(opts.batch_size)
+            .build()
+            .map_err(|e| ParquetLensError::corrupt(path.display().to_string(), e))?;
+        let file = path.display().to_string();
+        let mut batch_gauge = opts.gauge();
+        for batch in prefetch_batches(reader, opts.prefetch_depth) {
+            let batch = batch.map_err(|e| ParquetLensError::corrupt(file.clone(), e))?;
+            batch_gauge.set(batch.get_array_memory_size());
+            for (name, counts, scanned) in columns.iter_mut() {
+                let Some(array) = batch.column_by_name(name) else {
+                    continue;
+                };
+                let text = cast(array, &DataType::Utf8)
+                    .map_err(|e| ParquetLensError::corrupt(file.clone(), e))?;
+                for value in text.as_string::<i32>().iter().flatten() {
+                    *scanned += 1;
+                    for (kind, count) in PiiKind::ALL.iter().zip(counts.iter_mut()) {
+                        if contains_pii(*kind, value) {
+                            *count += 1;
+                        }
+                    }
+                }
+            }
+            scanned_rows += batch.num_rows() as u64;
+            progress.advance_rows(batch.num_rows() as u64);
+        }
+    }
+    let columns = columns
+        .into_iter()
+        .map(|(column, counts, scanned)| {
+            let mut matches: Vec<PiiMatch> = PiiKind::ALL
+                .iter()
+                .zip(counts)
+                .filter(|&(_, n)| n > 0)
+                .map(|(&kind, count)| PiiMatch {
+                    kind,
+                    count,
+                    pct: count as f64 / scanned.max(1) as f64 * 100.0,
+                })
+                .collect();
+            matches.sort_by(|a, b| b.pct.total_cmp(&a.pct));
+            let likely_pii = matches.first().is_some_and(|m| m.pct >= LIKELY_PII_PCT);
+            ColumnPii {
+                column,
+                scanned_values: scanned,
+                matches,
+                likely_pii,
+            }
+        })
+        .collect();
+    Ok(PiiReport {
+        scanned_rows,
+        columns,
+    })
+}
+
+/// Marks columns the scan flagged, e.g. `pii=email`, without changing the
+/// score: holding PII says how to handle a column, not that its data is bad.
+pub fn apply_pii_findings(scores: &mut [QualityScore], pii: &PiiReport) {
+    for score in scores.iter_mut() {
+        let Some(c) = pii.flagged().find(|c| c.column == score.column_name) else {
+            continue;
+        };
+        score.pii = c.flagged_kinds();
+        let kinds: Vec<&str> = score.pii.iter().map(|k| k.label()).collect();
+        let note = format!("pii={}", kinds.join("+"));
+        score.breakdown = if score.breakdown.is_empty() {
+            note
+        } else {
+            format!("{}, {note}", score.breakdown)
+        };
+    }
+}
+
+static RE_EMAIL: OnceLock<Regex> = OnceLock::new();
+static RE_PHONE: OnceLock<Regex> = OnceLock::new();
+static RE_NATIONAL_ID: OnceLock<Regex> = OnceLock::new();
+static RE_CARD: OnceLock<Regex> = OnceLock::new();
+static RE_IPV4: OnceLock<Regex> = OnceLock::new();
+static RE_IPV6: OnceLock<Regex> = OnceLock::new();
+
+fn re_email() -> &'static Regex {
+    RE_EMAIL.get_or_init(|| {
+        Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b").unwrap()
+    })
+}
+// North American 3-3-4 with separators, or `+` and a country code; a bare run
+// of digits is more likely an ID or amount
+fn re_phone() -> &'static Regex {
+    RE_PHONE.get_or_init(|| {
+        Regex::new(
+            r"(^|[^\w+])(\(\d{3}\)\s?|\d{3}[\s.-])\d{3}[\s.-]\d{4}\b|\+\d{1,3}([\s.-]?\(?\d{1,4}\)?){2,5}\b",
+        )
+        .unwrap()
+    })
+}
+// US SSN (no 000, 666 or 9xx area, 00 group or 0000 serial) or UK NINO
+fn re_national_id() -> &'static Regex {
+    RE_NATIONAL_ID.get_or_init(|| {
+        Regex::new(
+            r"\b(?:(?:00[1-9]|0[1-9]\d|[1-578]\d\d|6[0-57-9]\d|66[0-57-9])-(?:0[1-9]|[1-9]\d)-(?:000[1-9]|00[1-9]\d|0[1-9]\d\d|[1-9]\d{3})|[A-CEGHJ-PR-TW-Z][A-CEGHJ-NPR-TW-Z] ?\d{2} ?\d{2} ?\d{2} ?[A-D])\b",
+        )
+        .unwrap()
+    })
+}
+fn re_card() -> &'static Regex {
+    RE_CARD.get_or_init(|| Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").unwrap())
+}
+fn re_ipv4() -> &'static Regex {
+    RE_IPV4.get_or_init(|| {
+        Regex::new(
+            r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b",
+        )
+        .unwrap()
+    })
+}
+fn re_ipv6() -> &'static Regex {
+    RE_IPV6.get_or_init(|| Regex::new(r"[0-9A-Fa-f]{0,4}(?::[0-9A-Fa-f]{0,4}){2,7}").unwrap())
+}
+
+fn contains_pii(kind: PiiKind, s: &str) -> bool {
+    // cheap byte checks first: most values can't hold most kinds
+    let has_digit = || s.bytes().any(|b| b.is_ascii_digit());
+    match kind {
+        PiiKind::Email => s.contains('@') && re_email().is_match(s),
+        PiiKind::Phone => {
+            has_digit()
+                && re_phone()
+                    .find_iter(s)
+                    .any(|m| (10..=15).contains(&digit_count(m.as_str())))
+        }
+        PiiKind::NationalId => has_digit() && re_national_id().is_match(s),
+        PiiKind::CreditCard => {
+            has_digit()
+                && re_card().find_iter(s).any(|m| {
+                    let digits: Vec<u8> = m
+                        .as_str()
+                        .bytes()
+                        .filter(u8::is_ascii_digit)
+                        .map(|b| b - b'0')
+                        .collect();
+                    card_prefix(&digits) && luhn(&digits)
+                })
+        }
+        PiiKind::IpAddress => {
+            (s.contains('.') && re_ipv4().is_match(s))
+                || (s.contains(':')
+                    && re_ipv6().find_iter(s).any(|m| {
+                        // `a::b` parses too, but is far more often a path in code
+                        m.as_str().split(':').filter(|g| !g.is_empty()).count() >= 3
+                            && m.as_str().parse::<std::net::Ipv6Addr>().is_ok()
+                    }))
+        }
+    }
+}
+
+fn digit_count(s: &str) -> usize {
+    s.bytes().filter(u8::is_ascii_digit).count()
+}
+
+// Visa, Mastercard, Amex, Discover, Diners, JCB and UnionPay issuer prefixes
+fn card_prefix(d: &[u8]) -> bool {
+    let lead = |n: usize| d.iter().take(n).fold(0u32, |acc, &x| acc * 10 + x as u32);
+    match d.len() {
+        13..=19 => {
+            d[0] == 4
+                || (51..=55).contains(&lead(2))
+                || (2221..=2720).contains(&lead(4))
+                || matches!(lead(2), 34 | 37 | 36 | 38 | 62 | 65)
+                || lead(4) == 6011
+                || (300..=305).contains(&lead(3))
+                || (3528..=3589).contains(&lead(4))
+        }
+        _ => false,
+    }
+}
+
+fn luhn(d: &[u8]) -> bool {
+    let sum: u32 = d
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &x)| {
+            let x = x as u32;
+            if i % 2 == 1 {
+                let y = x * 2;
+                if y > 9 {
+                    y - 9
+                } else {
+                    y
+                }
+            } else {
+                x
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+/// A row group, or one column of it, that a scan couldn't decode and skipped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuarantinedRowGroup {
+    pub file: String,
+    pub row_group: usize,
+    pub column: Option<String>, // None when no single column could be blamed
+    pub rows: i64,
+    pub error: String,
+}
+
+/// Collects row groups skipped by scans. Attach one through
+/// `ScanOptions::quarantine` to keep scanning past decode failures; without
+/// one the first unreadable row group fails the whole scan.
+#[derive(Debug, Default)]
+pub struct QuarantineLog {
+    entries: Mutex<Vec<QuarantinedRowGroup>>,
+}
+
+impl QuarantineLog {
+    pub fn record(&self, entry: QuarantinedRowGroup) {
+        tracing::warn!(
+            "quarantined {} row group {}{}: {}",
+            entry.file,
+            entry.row_group,
+            entry
+                .column
+                .as_deref()
+                .map(|c| format!(" column {c}"))
+                .unwrap_or_default(),
+            entry.error
+        );
+        self.entries.lock().unwrap().push(entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Entries so far, ordered by file and row group.
+    pub fn entries(&self) -> Vec<QuarantinedRowGroup> {
+        let mut entries = self.entries.lock().unwrap().clone();
+        entries.sort_by(|a, b| {
+            (&a.file, a.row_group, &a.column).cmp(&(&b.file, b.row_group, &b.column))
+        });
+        entries
+    }
+
+    /// Summary against the `total_rows` that were meant to be scanned; None
+    /// when nothing was skipped.
+    pub fn report(&self, total_rows: i64) -> Option<QuarantineReport> {
+        let entries = self.entries();
+        if entries.is_empty() {
+            return None;
+        }
+        // a row group counts once however many of its columns failed
+        let mut seen = BTreeSet::new();
+        let rows_affected: i64 = entries
+            .iter()
+            .filter(|e| seen.insert((e.file.as_str(), e.row_group)))
+            .map(|e| e.rows)
+            .sum();
+        let pct_rows_affected = if total_rows > 0 {
+            rows_affected as f64 / total_rows as f64 * 100.0
+        } else {
+            0.0
+        };
+        Some(QuarantineReport {
+            entries,
+            rows_affected,
+            total_rows,
+            pct_rows_affected,
+        })
+    }
+}
+
+/// Row groups left out of a run's scanned results because they failed to decode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantineReport {
+    pub entries: Vec<QuarantinedRowGroup>,
+    pub rows_affected: i64,
+    pub total_rows: i64,
+    pub pct_rows_affected: f64,
+}
+
+impl QuarantineReport {
+    /// One line per entry, e.g. `row group 3 column price: 1000 rows: EOF ...`.
+    pub fn lines(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|e| {
+                let column = e
+                    .column
+                    .as_deref()
+                    .map(|c| format!(" column {c}"))
+                    .unwrap_or_default();
+                format!(
+                    "{} row group {}{column}: {} rows: {}",
+                    e.file, e.row_group, e.rows, e.error
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests_quarantine {
+    use super::*;
+
+    fn entry(row_group: usize, column: Option<&str>) -> QuarantinedRowGroup {
+        QuarantinedRowGroup {
+            file: "a.parquet".into(),
+            row_group,
+            column: column.map(String::from),
+            rows: 100,
+            error: "bad page".into(),
+        }
+    }
+
+    #[test]
+    fn rows_counted_once_per_row_group() {
+        let log = QuarantineLog::default();
+        assert!(log.report(1000).is_none());
+        log.record(entry(3, Some("b")));
+        log.record(entry(1, None));
+        log.record(entry(3, Some("a")));
+        let report = log.report(1000).unwrap();
+        assert_eq!(report.rows_affected, 200);
+        assert!((report.pct_rows_affected - 20.0).abs() < 1e-9);
+        let order: Vec<_> = report
+            .entries
+            .iter()
+            .map(|e| (e.row_group, e.column.as_deref()))
+            .collect();
+        assert_eq!(order, vec![(1, None), (3, Some("a")), (3, Some("b"))]);
+    }
+}
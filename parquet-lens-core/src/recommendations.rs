@@ -1,3 +1,4 @@
+use crate::profile::{ColumnEntropy, ColumnProfileResult};
 use crate::schema::ColumnSchema;
 use crate::stats::{AggregatedColumnStats, CompressionAnalysis, EncodingAnalysis, RowGroupProfile};
 use serde::{Deserialize, Serialize};
@@ -107,13 +108,33 @@ pub struct CompressionRecommendation {
     pub reason: String,
 }
 
+// below this share of removable bytes a column is treated as incompressible
+const INCOMPRESSIBLE_PCT: f64 = 10.0;
+
+/// Codec name without its level, e.g. `ZSTD` for `ZSTD(ZstdLevel(3))`.
+fn codec_family(codec: &str) -> &str {
+    codec.split('(').next().unwrap_or(codec)
+}
+
+/// Codec suggestions per column. Columns with a full-scan entropy estimate in
+/// `profiles` are judged on their sampled redundancy; the rest fall back to
+/// typical savings for their current codec.
 pub fn recommend_compression(
     compression: &[CompressionAnalysis],
+    profiles: &[ColumnProfileResult],
 ) -> Vec<CompressionRecommendation> {
     compression
         .iter()
         .filter_map(|c| {
-            if c.codec == "ZSTD" {
+            let family = codec_family(&c.codec);
+            let entropy = profiles
+                .iter()
+                .find(|p| p.column_name == c.column_name)
+                .and_then(|p| p.entropy.as_ref());
+            if let Some(e) = entropy {
+                return recommend_from_entropy(c, family, e);
+            }
+            if family == "ZSTD" {
                 return None;
             } // already optimal
             let (recommended, estimated_savings_pct, reason) = if c.is_uncompressed {
@@ -122,13 +143,13 @@ pub fn recommend_compression(
                     40.0,
                     "uncompressed column — ZSTD typically achieves 40%+ savings".into(),
                 )
-            } else if c.codec == "SNAPPY" {
+            } else if family == "SNAPPY" {
                 (
                     "ZSTD".into(),
                     15.0,
                     "ZSTD achieves ~15% better ratio than SNAPPY with comparable speed".into(),
                 )
-            } else if c.codec == "GZIP" {
+            } else if family == "GZIP" {
                 (
                     "ZSTD".into(),
                     5.0,
@@ -150,3 +171,53 @@ pub fn recommend_compression(
         })
         .collect()
 }
+
+// savings are the entropy bound minus what the current codec already removes
+fn recommend_from_entropy(
+    c: &CompressionAnalysis,
+    family: &str,
+    e: &ColumnEntropy,
+) -> Option<CompressionRecommendation> {
+    let recommend = |recommended: &str, estimated_savings_pct: f64, reason: String| {
+        Some(CompressionRecommendation {
+            column_name: c.column_name.clone(),
+            current_codec: c.codec.clone(),
+            recommended_codec: recommended.into(),
+            estimated_savings_pct,
+            reason,
+        })
+    };
+    if e.compressible_pct < INCOMPRESSIBLE_PCT {
+        if c.is_uncompressed {
+            return None;
+        }
+        return recommend(
+            "UNCOMPRESSED",
+            0.0,
+            format!(
+                "sampled data is near-random ({:.1} bits/byte) — {family} spends CPU for little gain",
+                e.bits_per_byte
+            ),
+        );
+    }
+    if family == "ZSTD" {
+        return None;
+    }
+    let current_savings_pct = if c.compression_ratio > 0.0 {
+        (1.0 - 1.0 / c.compression_ratio).max(0.0) * 100.0
+    } else {
+        0.0
+    };
+    let headroom = e.compressible_pct - current_savings_pct;
+    if headroom < 20.0 && !c.is_uncompressed {
+        return None;
+    }
+    recommend(
+        "ZSTD",
+        headroom.max(0.0),
+        format!(
+            "sampled entropy {:.1} bits/value over {:.1} bytes — ~{:.0}% of the data is redundant",
+            e.bits_per_value, e.bytes_per_value, e.compressible_pct
+        ),
+    )
+}
@@ -0,0 +1,1222 @@
+use crate::footer_cache::footer_cache;
+use crate::progress::{Progress, ProgressReporter};
+use crate::reader::ParquetFileInfo;
+#[cfg(any(feature = "s3", feature = "gcs", feature = "azure", feature = "http"))]
+use crate::retry::request_timeout;
+use crate::storage::{
+    decode_footer, footer_range, list_options, slice_tail, ListOptions, ObjectInfo, StorageBackend,
+};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path as ObjectPath;
+#[cfg(any(feature = "s3", feature = "gcs", feature = "azure", feature = "http"))]
+use object_store::ClientConfigKey;
+use object_store::ObjectStore;
+use parquet::file::metadata::ParquetMetaData;
+use parquet_lens_common::{AzureConfig, Config, GcsConfig, ParquetLensError, Result, S3Config};
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+// the first footer read fetches this much of the tail, enough for most footers
+const FOOTER_PREFETCH: u64 = 64 * 1024;
+// directory levels listed with a delimiter so their subdirectories fan out
+const LIST_FANOUT_DEPTH: usize = 3;
+// objects per progress update when streaming a subtree
+const LIST_PAGE: usize = 1000;
+
+/// Credentials and endpoints for every object store, usually the `[s3]`,
+/// `[gcs]` and `[azure]` config sections. Unset fields fall back to each
+/// provider's environment variables and default credential chain.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteConfig {
+    pub s3: S3Config,
+    pub gcs: GcsConfig,
+    pub azure: AzureConfig,
+}
+
+impl RemoteConfig {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            s3: config.s3.clone(),
+            gcs: config.gcs.clone(),
+            azure: config.azure.clone(),
+        }
+    }
+}
+
+static CONFIG: RwLock<Option<RemoteConfig>> = RwLock::new(None);
+
+/// Credentials every remote read uses from now on.
+pub fn set_remote_config(config: RemoteConfig) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = Some(config);
+}
+
+pub(crate) fn remote_config() -> RemoteConfig {
+    CONFIG
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// One object store plus the URI prefix its objects are addressed by. Every
+/// remote backend lists, range-reads and parses footers through this, so they
+/// share retries, credential handling and error categories.
+#[derive(Debug, Clone)]
+pub struct ObjectStoreReader {
+    store: Arc<dyn ObjectStore>,
+    root: String, // e.g. "s3://bucket" (no trailing slash); an object's URI is `root/location`
+}
+
+impl ObjectStoreReader {
+    pub fn new(store: Arc<dyn ObjectStore>, root: impl Into<String>) -> Self {
+        Self {
+            store,
+            root: root.into(),
+        }
+    }
+
+    /// Reader for the bucket or container `uri` names (or the local filesystem
+    /// for plain paths), plus the object's location in it. Each cloud scheme
+    /// needs its cargo feature (`s3`, `gcs`, `azure`, `http`).
+    pub fn for_uri(uri: &str, config: &RemoteConfig) -> Result<(Self, ObjectPath)> {
+        #[cfg(not(any(feature = "s3", feature = "gcs", feature = "azure")))]
+        let _ = config;
+        let url = url_for(uri)?;
+        let root = url[..url::Position::BeforePath].to_string();
+        let store: Arc<dyn ObjectStore> = match url.scheme() {
+            #[cfg(feature = "s3")]
+            "s3" | "s3a" => Arc::new(s3_store(uri, &config.s3)?),
+            #[cfg(feature = "gcs")]
+            "gs" => Arc::new(gcs_store(uri, &config.gcs)?),
+            #[cfg(feature = "azure")]
+            "az" | "abfs" | "abfss" => {
+                // container-scoped store; the host may carry the account
+                let (store, parsed) = crate::azure_reader::azure_store(uri, &config.azure)?;
+                let reader = Self::new(Arc::new(store), parsed.root());
+                return Ok((reader, ObjectPath::from(parsed.path.as_str())));
+            }
+            #[cfg(feature = "azure")]
+            "adl" | "azure" => Arc::new(
+                object_store::azure::MicrosoftAzureBuilder::from_env()
+                    .with_url(uri)
+                    .with_retry(retry_config())
+                    .with_config(
+                        object_store::azure::AzureConfigKey::Client(ClientConfigKey::Timeout),
+                        request_timeout(),
+                    )
+                    .build()
+                    .map_err(store_err)?,
+            ),
+            // a pre-signed URL is its own store: the path and signature can't be split
+            #[cfg(feature = "http")]
+            "http" | "https" if crate::http_reader::is_presigned_url(uri) => {
+                let store = object_store::http::HttpBuilder::new()
+                    .with_url(uri)
+                    .with_client_options(
+                        object_store::ClientOptions::new().with_allow_http(url.scheme() == "http"),
+                    )
+                    .with_config(ClientConfigKey::Timeout, request_timeout())
+                    .with_retry(retry_config())
+                    .build()
+                    .map_err(store_err)?;
+                return Ok((Self::new(Arc::new(store), uri), ObjectPath::default()));
+            }
+            // the store is rooted at the origin; the URL path becomes the object path
+            #[cfg(feature = "http")]
+            "http" | "https" => Arc::new(
+                object_store::http::HttpBuilder::new()
+                    .with_url(&url[..url::Position::BeforePath])
+                    .with_client_options(
+                        object_store::ClientOptions::new().with_allow_http(url.scheme() == "http"),
+                    )
+                    .with_config(ClientConfigKey::Timeout, request_timeout())
+                    .with_retry(retry_config())
+                    .build()
+                    .map_err(store_err)?,
+            ),
+            "file" => Arc::new(object_store::local::LocalFileSystem::new()),
+            other => {
+                return Err(ParquetLensError::Unsupported {
+                    feature: format!("object store scheme {other}://"),
+                })
+            }
+        };
+        let location = match url.scheme() {
+            "file" => ObjectPath::from_filesystem_path(url.path()).map_err(|e| {
+                ParquetLensError::Other(format!("invalid local path {}: {e}", url.path()))
+            })?,
+            _ => ObjectPath::from_url_path(url.path())
+                .map_err(|e| ParquetLensError::Other(format!("invalid object path {uri}: {e}")))?,
+        };
+        Ok((Self::new(store, root), location))
+    }
+
+    pub fn store(&self) -> Arc<dyn ObjectStore> {
+        self.store.clone()
+    }
+
+    /// URI of the object at `location`, in the form the reader was opened with.
+    pub fn uri(&self, location: &ObjectPath) -> String {
+        if location.as_ref().is_empty() {
+            return self.root.clone();
+        }
+        format!("{}/{location}", self.root)
+    }
+
+    /// `.parquet` objects under `prefix`, as sorted URIs. A prefix naming a
+    /// `.parquet` object lists just itself; one that is not a directory
+    /// matches keys by string prefix, like `s3://bucket/events/2024-`. With
+    /// `ListOptions::max_files` set this is the first that many found, which
+    /// need not be the first that many in key order.
+    pub async fn list_parquet(&self, prefix: &ObjectPath) -> Result<Vec<String>> {
+        if prefix.as_ref().ends_with(".parquet") {
+            return Ok(vec![self.uri(prefix)]);
+        }
+        let options = list_options();
+        let mut keys = self.list_keys(Some(prefix), "", &options).await?;
+        if keys.is_empty() && !prefix.as_ref().is_empty() {
+            let parent = prefix
+                .as_ref()
+                .rsplit_once(object_store::path::DELIMITER)
+                .map(|(p, _)| ObjectPath::from(p));
+            keys = self
+                .list_keys(parent.as_ref(), prefix.as_ref(), &options)
+                .await?;
+        }
+        let mut uris: Vec<String> = keys
+            .into_iter()
+            .map(|k| format!("{}/{k}", self.root))
+            .collect();
+        uris.sort();
+        if let Some(max) = options.max_files {
+            uris.truncate(max);
+        }
+        Ok(uris)
+    }
+
+    /// `.parquet` keys under `prefix` that start with `starts_with`. The top
+    /// `LIST_FANOUT_DEPTH` directory levels are listed one level at a time so
+    /// their subdirectories can be listed concurrently; each subtree below that
+    /// is one paginated listing. Stops early once `max_files` keys are found.
+    async fn list_keys(
+        &self,
+        prefix: Option<&ObjectPath>,
+        starts_with: &str,
+        options: &ListOptions,
+    ) -> Result<Vec<String>> {
+        let progress =
+            ProgressReporter::start(options.progress.clone(), "list", Progress::default());
+        let found = AtomicUsize::new(0);
+        let mut keys = Vec::new();
+        let mut level = vec![prefix.cloned()];
+        for depth in 0..=LIST_FANOUT_DEPTH {
+            if level.is_empty() || options.is_full(found.load(Ordering::Relaxed)) {
+                break;
+            }
+            let (found, progress) = (&found, &progress);
+            let mut listings = futures::stream::iter(level)
+                .map(|dir| async move {
+                    if depth == LIST_FANOUT_DEPTH {
+                        let keys = self.list_subtree(dir.as_ref(), options, found, progress);
+                        return Ok((keys.await?, Vec::new()));
+                    }
+                    let listing = self
+                        .store
+                        .list_with_delimiter(dir.as_ref())
+                        .await
+                        .map_err(|e| self.err(dir.as_ref().unwrap_or(&ObjectPath::default()), e))?;
+                    progress.advance(listing.objects.len() as u64, 0);
+                    let keys: Vec<String> = listing
+                        .objects
+                        .into_iter()
+                        .map(|o| o.location.to_string())
+                        .filter(|k| k.ends_with(".parquet") && k.starts_with(starts_with))
+                        .collect();
+                    found.fetch_add(keys.len(), Ordering::Relaxed);
+                    let dirs = listing
+                        .common_prefixes
+                        .into_iter()
+                        .filter(|d| d.as_ref().starts_with(starts_with))
+                        .map(Some)
+                        .collect();
+                    Ok::<_, ParquetLensError>((keys, dirs))
+                })
+                .buffer_unordered(options.concurrency());
+            let mut next = Vec::new();
+            while let Some(listing) = listings.next().await {
+                let (found_keys, dirs) = listing?;
+                keys.extend(found_keys);
+                next.extend(dirs);
+                if options.is_full(found.load(Ordering::Relaxed)) {
+                    break;
+                }
+            }
+            level = next;
+        }
+        Ok(keys)
+    }
+
+    // every `.parquet` key under `dir`, page by page, until the cap is reached
+    async fn list_subtree(
+        &self,
+        dir: Option<&ObjectPath>,
+        options: &ListOptions,
+        found: &AtomicUsize,
+        progress: &ProgressReporter,
+    ) -> Result<Vec<String>> {
+        let mut pages = self.store.list(dir).try_chunks(LIST_PAGE);
+        let mut keys = Vec::new();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| self.err(dir.unwrap_or(&ObjectPath::default()), e.1))?;
+            progress.advance(page.len() as u64, 0);
+            let before = keys.len();
+            keys.extend(
+                page.into_iter()
+                    .map(|o| o.location.to_string())
+                    .filter(|k| k.ends_with(".parquet")),
+            );
+            let total =
+                found.fetch_add(keys.len() - before, Ordering::Relaxed) + keys.len() - before;
+            if options.is_full(total) {
+                break;
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Size, last modification (RFC 3339) and ETag.
+    pub async fn head(&self, location: &ObjectPath) -> Result<ObjectInfo> {
+        let meta = self
+            .store
+            .head(location)
+            .await
+            .map_err(|e| self.err(location, e))?;
+        Ok(ObjectInfo {
+            uri: self.uri(location),
+            size: meta.size as u64,
+            last_modified: Some(meta.last_modified.to_rfc3339()),
+            etag: meta.e_tag,
+        })
+    }
+
+    /// Bytes `range.start..range.end` of the object.
+    pub async fn read_range(&self, location: &ObjectPath, range: Range<u64>) -> Result<Bytes> {
+        if range.is_empty() {
+            return Ok(Bytes::new());
+        }
+        self.store
+            .get_range(location, range.start as usize..range.end as usize)
+            .await
+            .map_err(|e| self.err(location, e))
+    }
+
+    /// Parses the footer: a HEAD, then one read of the file's tail that
+    /// usually holds the whole footer. The footer cache, when set, can answer
+    /// without the read, or without any request while its entry is fresh.
+    pub async fn open(
+        &self,
+        location: &ObjectPath,
+    ) -> Result<(ParquetFileInfo, Arc<ParquetMetaData>)> {
+        let uri = self.uri(location);
+        let cache = footer_cache();
+        if let Some(opened) = cache.as_ref().and_then(|c| c.open_fresh(&uri)) {
+            return Ok(opened);
+        }
+        let info = self.head(location).await?;
+        if let Some(opened) = cache.as_ref().and_then(|c| c.open_validated(&info)) {
+            return Ok(opened);
+        }
+        let size = info.size;
+        let tail = self
+            .read_range(location, size.saturating_sub(FOOTER_PREFETCH)..size)
+            .await?;
+        let range = footer_range(&uri, size, &tail)?;
+        let footer = match slice_tail(&tail, size, &range) {
+            Some(footer) => footer,
+            None => self.read_range(location, range).await?,
+        };
+        let opened = decode_footer(&uri, size, &footer)?;
+        if let Some(cache) = &cache {
+            // best-effort; a failed write only costs a refetch next time
+            let _ = cache.store(&info, &footer);
+        }
+        Ok(opened)
+    }
+
+    // missing objects and denied access name the URI rather than the store path
+    fn err(&self, location: &ObjectPath, e: object_store::Error) -> ParquetLensError {
+        match e {
+            object_store::Error::NotFound { .. } => ParquetLensError::NotFound(self.uri(location)),
+            object_store::Error::PermissionDenied { .. } => {
+                ParquetLensError::PermissionDenied(self.uri(location))
+            }
+            e => store_err(e).context(self.uri(location)),
+        }
+    }
+}
+
+/// A `StorageBackend` whose objects live in an `object_store` store: a new
+/// backend only says which URIs it serves and how to build their reader, and
+/// gets listing, range reads and footer parsing from `ObjectStoreReader`.
+pub trait ObjectStoreBackend: Send + Sync {
+    fn name(&self) -> &str;
+    fn handles(&self, uri: &str) -> bool;
+    /// Reader for the store holding `uri`, plus the object's location in it.
+    fn reader(&self, uri: &str) -> Result<(ObjectStoreReader, ObjectPath)>;
+}
+
+impl<T: ObjectStoreBackend> StorageBackend for T {
+    fn name(&self) -> &str {
+        ObjectStoreBackend::name(self)
+    }
+
+    fn handles(&self, uri: &str) -> bool {
+        ObjectStoreBackend::handles(self, uri)
+    }
+
+    fn list<'a>(&'a self, prefix: &'a str) -> BoxFuture<'a, Result<Vec<String>>> {
+        Box::pin(async move {
+            let (reader, location) = self.reader(prefix)?;
+            reader.list_parquet(&location).await
+        })
+    }
+
+    fn metadata<'a>(&'a self, uri: &'a str) -> BoxFuture<'a, Result<ObjectInfo>> {
+        Box::pin(async move {
+            let (reader, location) = self.reader(uri)?;
+            // keep the caller's spelling of the URI (e.g. s3a://)
+            Ok(ObjectInfo {
+                uri: uri.to_string(),
+                ..reader.head(&location).await?
+            })
+        })
+    }
+
+    fn read_range<'a>(&'a self, uri: &'a str, range: Range<u64>) -> BoxFuture<'a, Result<Bytes>> {
+        Box::pin(async move {
+            let (reader, location) = self.reader(uri)?;
+            reader.read_range(&location, range).await
+        })
+    }
+
+    fn open<'a>(
+        &'a self,
+        uri: &'a str,
+    ) -> BoxFuture<'a, Result<(ParquetFileInfo, Arc<ParquetMetaData>)>> {
+        Box::pin(async move {
+            let (reader, location) = self.reader(uri)?;
+            reader.open(&location).await
+        })
+    }
+}
+
+/// Maps an object store failure onto the categorized crate error.
+pub(crate) fn store_err(e: object_store::Error) -> ParquetLensError {
+    use object_store::Error as E;
+    match e {
+        E::NotFound { path, .. } => ParquetLensError::NotFound(path),
+        E::PermissionDenied { path, .. } => ParquetLensError::PermissionDenied(path),
+        // raised by our own credential providers, whose message says it all
+        E::Unauthenticated { path, source } if path.is_empty() => {
+            ParquetLensError::Auth(source.to_string())
+        }
+        E::Unauthenticated { .. } => {
+            ParquetLensError::Auth(format!("object store auth error: {e}"))
+        }
+        E::NotSupported { .. } | E::NotImplemented => ParquetLensError::Unsupported {
+            feature: e.to_string(),
+        },
+        other => {
+            let msg = other.to_string();
+            if is_throttle_message(&msg) {
+                ParquetLensError::Throttled(msg)
+            } else {
+                ParquetLensError::Other(msg)
+            }
+        }
+    }
+}
+
+/// Recognizes rate-limit / overload responses in provider error text.
+pub(crate) fn is_throttle_message(msg: &str) -> bool {
+    [
+        "429",
+        "503",
+        "SlowDown",
+        "Throttl",
+        "TooManyRequests",
+        "ServerBusy",
+    ]
+    .iter()
+    .any(|p| msg.contains(p))
+}
+
+// plain filesystem paths become file:// urls so every input goes through one code path
+fn url_for(uri: &str) -> Result<url::Url> {
+    if uri.contains("://") {
+        return url::Url::parse(uri)
+            .map_err(|e| ParquetLensError::Other(format!("invalid URI {uri}: {e}")));
+    }
+    let abs = std::path::absolute(uri)?;
+    url::Url::from_file_path(&abs)
+        .map_err(|_| ParquetLensError::Other(format!("invalid local path: {}", abs.display())))
+}
+
+/// Retries shared by every cloud store: throttling, server errors and dropped
+/// connections are retried with exponential backoff, as `[retry]` configures.
+#[cfg(any(feature = "s3", feature = "gcs", feature = "azure", feature = "http"))]
+pub(crate) fn retry_config() -> object_store::RetryConfig {
+    use std::time::Duration;
+    let policy = crate::retry::retry_policy();
+    object_store::RetryConfig {
+        backoff: object_store::BackoffConfig {
+            init_backoff: Duration::from_millis(policy.initial_backoff_ms),
+            max_backoff: Duration::from_millis(policy.max_backoff_ms),
+            base: policy.backoff_base.max(1.0),
+        },
+        max_retries: policy.max_retries,
+        retry_timeout: Duration::from_secs(policy.retry_timeout_secs),
+    }
+}
+
+#[cfg(feature = "s3")]
+fn s3_store(uri: &str, config: &S3Config) -> Result<object_store::aws::AmazonS3> {
+    let mut builder = object_store::aws::AmazonS3Builder::from_env()
+        .with_url(uri)
+        .with_credentials(aws::credentials(config))
+        .with_request_payer(config.requester_pays)
+        .with_retry(retry_config());
+    let bucket = url::Url::parse(uri)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| config.for_bucket(h)))
+        .unwrap_or_else(|| config.for_bucket(""));
+    if let Some(region) = &bucket.region {
+        builder = builder.with_region(region);
+    }
+    if let Some(path) = &bucket.ca_bundle {
+        builder = builder.with_client_options(s3_client_options(path)?);
+    }
+    if let Some(ep) = &bucket.endpoint_url {
+        builder = builder.with_endpoint(ep);
+    }
+    let allow_http = bucket.allow_http.or_else(|| {
+        let ep = bucket.endpoint_url.as_ref()?;
+        Some(ep.starts_with("http://"))
+    });
+    if let Some(allow_http) = allow_http {
+        builder = builder.with_allow_http(allow_http);
+    }
+    if let Some(path_style) = bucket.force_path_style {
+        builder = builder.with_virtual_hosted_style_request(!path_style);
+    }
+    // after `with_client_options`, which would reset it
+    builder
+        .with_config(
+            object_store::aws::AmazonS3ConfigKey::Client(ClientConfigKey::Timeout),
+            request_timeout(),
+        )
+        .build()
+        .map_err(store_err)
+}
+
+// HTTP client settings trusting the roots in the PEM bundle at `ca_bundle`.
+// They replace what `from_env` read, so the AWS_* client variables are reapplied.
+#[cfg(feature = "s3")]
+fn s3_client_options(ca_bundle: &str) -> Result<object_store::ClientOptions> {
+    use object_store::aws::AmazonS3ConfigKey;
+    let pem = std::fs::read(ca_bundle)
+        .map_err(|e| ParquetLensError::io_at(e, std::path::Path::new(ca_bundle)))?;
+    let certs = object_store::Certificate::from_pem_bundle(&pem)
+        .map_err(|e| ParquetLensError::Other(format!("[s3] ca_bundle {ca_bundle}: {e}")))?;
+    let mut options = object_store::ClientOptions::new();
+    for (key, value) in std::env::vars() {
+        if let Ok(AmazonS3ConfigKey::Client(key)) = key.to_ascii_lowercase().parse() {
+            options = options.with_config(key, value);
+        }
+    }
+    Ok(certs
+        .into_iter()
+        .fold(options, |o, cert| o.with_root_certificate(cert)))
+}
+
+#[cfg(feature = "gcs")]
+fn gcs_store(uri: &str, config: &GcsConfig) -> Result<object_store::gcp::GoogleCloudStorage> {
+    let mut builder = object_store::gcp::GoogleCloudStorageBuilder::from_env()
+        .with_url(uri)
+        .with_retry(retry_config())
+        .with_config(
+            object_store::gcp::GoogleConfigKey::Client(ClientConfigKey::Timeout),
+            request_timeout(),
+        );
+    // a service account key or `gcloud auth application-default login` file
+    if let Some(path) = &config.credentials_file {
+        builder = builder.with_application_credentials(path);
+    }
+    let Some(target) = &config.impersonate_service_account else {
+        return builder.build().map_err(store_err);
+    };
+    // the ambient credentials only sign the token exchange for `target`
+    let source = builder.clone().build().map_err(store_err)?;
+    let credentials = gcp::impersonated(config, target, source.credentials().clone());
+    builder
+        .with_credentials(credentials)
+        .build()
+        .map_err(store_err)
+}
+
+/// GCS tokens for `[gcs] impersonate_service_account`, minted through the IAM
+/// Credentials API with whatever credentials the store would otherwise use.
+/// Tokens last an hour and are refreshed shortly before they run out.
+#[cfg(feature = "gcs")]
+mod gcp {
+    use async_trait::async_trait;
+    use object_store::gcp::{GcpCredential, GcpCredentialProvider};
+    use object_store::CredentialProvider;
+    use parquet_lens_common::GcsConfig;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    const IAM_CREDENTIALS: &str = "https://iamcredentials.googleapis.com";
+    const LIFETIME: Duration = Duration::from_secs(3600);
+    // refresh this long before a token expires
+    const EXPIRY_MARGIN: Duration = Duration::from_secs(300);
+
+    #[derive(Debug)]
+    pub(super) struct Impersonated {
+        key: (String, Option<String>), // target and credentials file, to reuse across stores
+        endpoint: String,
+        source: GcpCredentialProvider,
+        client: reqwest::Client,
+        cached: tokio::sync::Mutex<Option<(Arc<GcpCredential>, Instant)>>,
+    }
+
+    /// Process-wide provider, so a token is minted once rather than per store.
+    pub(super) fn impersonated(
+        config: &GcsConfig,
+        target: &str,
+        source: GcpCredentialProvider,
+    ) -> Arc<Impersonated> {
+        static SHARED: Mutex<Option<Arc<Impersonated>>> = Mutex::new(None);
+        let key = (target.to_string(), config.credentials_file.clone());
+        let mut shared = SHARED.lock().unwrap_or_else(|e| e.into_inner());
+        match shared.as_ref() {
+            Some(c) if c.key == key => c.clone(),
+            _ => {
+                let c = Arc::new(Impersonated::new(key, IAM_CREDENTIALS, source));
+                *shared = Some(c.clone());
+                c
+            }
+        }
+    }
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct AccessToken {
+        access_token: String,
+    }
+
+    impl Impersonated {
+        fn new(
+            key: (String, Option<String>),
+            endpoint: &str,
+            source: GcpCredentialProvider,
+        ) -> Self {
+            Self {
+                key,
+                endpoint: endpoint.trim_end_matches('/').to_string(),
+                source,
+                client: crate::retry::http_client(),
+                cached: tokio::sync::Mutex::new(None),
+            }
+        }
+
+        async fn mint(&self) -> std::result::Result<String, String> {
+            let target = &self.key.0;
+            let source = self
+                .source
+                .get_credential()
+                .await
+                .map_err(|e| e.to_string())?;
+            let url = format!(
+                "{}/v1/projects/-/serviceAccounts/{target}:generateAccessToken",
+                self.endpoint
+            );
+            let body = serde_json::json!({
+                "scope": ["https://www.googleapis.com/auth/cloud-platform"],
+                "lifetime": format!("{}s", LIFETIME.as_secs()),
+            });
+            let response = self
+                .client
+                .post(url)
+                .bearer_auth(&source.bearer)
+                .json(&body)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            let status = response.status();
+            if !status.is_success() {
+                let detail = response.text().await.unwrap_or_default();
+                return Err(format!("{status}: {}", detail.trim()));
+            }
+            let token: AccessToken = response.json().await.map_err(|e| e.to_string())?;
+            Ok(token.access_token)
+        }
+    }
+
+    #[async_trait]
+    impl CredentialProvider for Impersonated {
+        type Credential = GcpCredential;
+
+        async fn get_credential(&self) -> object_store::Result<Arc<GcpCredential>> {
+            let mut cached = self.cached.lock().await;
+            if let Some((credential, expiry)) = cached.as_ref() {
+                if *expiry > Instant::now() + EXPIRY_MARGIN {
+                    return Ok(credential.clone());
+                }
+            }
+            let requested = Instant::now();
+            let bearer = self.mint().await.map_err(|e| {
+                let message = format!("impersonating service account {}: {e}", self.key.0);
+                object_store::Error::Unauthenticated {
+                    path: String::new(),
+                    source: message.into(),
+                }
+            })?;
+            let credential = Arc::new(GcpCredential { bearer });
+            *cached = Some((credential.clone(), requested + LIFETIME));
+            Ok(credential)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests_impersonation {
+        use super::*;
+        use object_store::StaticCredentialProvider;
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // generateAccessToken for sa@p.iam: 200 with a token for the "source"
+        // bearer, 403 for anything else; returns the endpoint and a call count
+        fn serve_iam() -> (String, Arc<AtomicUsize>) {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            let calls = Arc::new(AtomicUsize::new(0));
+            let counted = calls.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request = String::new();
+                    reader.read_line(&mut request).unwrap();
+                    let (mut authorized, mut length) = (false, 0);
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap() > 2 {
+                        let lower = line.to_ascii_lowercase();
+                        authorized |= lower.trim() == "authorization: bearer source";
+                        if let Some(v) = lower.strip_prefix("content-length:") {
+                            length = v.trim().parse().unwrap();
+                        }
+                        line.clear();
+                    }
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).unwrap();
+                    counted.fetch_add(1, Ordering::SeqCst);
+                    let path_ok = request
+                        .contains("/v1/projects/-/serviceAccounts/sa@p.iam:generateAccessToken");
+                    let response = if authorized && path_ok {
+                        let token =
+                            r#"{"accessToken":"minted","expireTime":"2030-01-01T00:00:00Z"}"#;
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{token}",
+                            token.len()
+                        )
+                    } else {
+                        "HTTP/1.1 403 Forbidden\r\nContent-Length: 6\r\n\r\ndenied".to_string()
+                    };
+                    stream.write_all(response.as_bytes()).unwrap();
+                }
+            });
+            (format!("http://{addr}"), calls)
+        }
+
+        fn source(bearer: &str) -> GcpCredentialProvider {
+            Arc::new(StaticCredentialProvider::new(GcpCredential {
+                bearer: bearer.to_string(),
+            }))
+        }
+
+        #[tokio::test]
+        async fn mints_once_then_reuses_the_token() {
+            let (endpoint, calls) = serve_iam();
+            let key = ("sa@p.iam".to_string(), None);
+            let provider = Impersonated::new(key.clone(), &endpoint, source("source"));
+            assert_eq!(provider.get_credential().await.unwrap().bearer, "minted");
+            assert_eq!(provider.get_credential().await.unwrap().bearer, "minted");
+            assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+            let denied = Impersonated::new(key, &endpoint, source("someone-else"));
+            let err = super::super::store_err(denied.get_credential().await.unwrap_err());
+            assert_eq!(err.code(), "auth");
+            let message = err.to_string();
+            assert!(
+                message.contains("impersonating service account sa@p.iam"),
+                "{message}"
+            );
+            assert!(message.contains("403"), "{message}");
+        }
+    }
+}
+
+/// S3 credentials from the AWS SDK's providers, tried in the SDK's default
+/// order: environment, shared config/credentials files (`[s3] profile`, which
+/// covers IAM Identity Center SSO and `credential_process`), the web identity
+/// token file (EKS/IRSA), then the ECS container and EC2 instance metadata
+/// endpoints. Unlike the SDK's own chain, a failure names the provider.
+/// With `[s3] role_arn` those credentials only sign the STS AssumeRole call.
+#[cfg(feature = "s3")]
+mod aws {
+    use async_trait::async_trait;
+    use aws_config::default_provider::region::DefaultRegionChain;
+    use aws_config::ecs::EcsCredentialsProvider;
+    use aws_config::environment::EnvironmentVariableCredentialsProvider;
+    use aws_config::imds::credentials::ImdsCredentialsProvider;
+    use aws_config::profile::ProfileFileCredentialsProvider;
+    use aws_config::provider_config::ProviderConfig;
+    use aws_config::sts::AssumeRoleProvider;
+    use aws_config::web_identity_token::WebIdentityTokenCredentialsProvider;
+    use aws_credential_types::provider::error::CredentialsError;
+    use aws_credential_types::provider::{future, ProvideCredentials, SharedCredentialsProvider};
+    use object_store::aws::AwsCredential;
+    use object_store::CredentialProvider;
+    use parquet_lens_common::S3Config;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime};
+
+    // refresh this long before the SDK says a credential expires
+    const EXPIRY_MARGIN: Duration = Duration::from_secs(300);
+
+    /// The `[s3]` settings that decide which credentials come back.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Source {
+        profile: Option<String>,
+        region: Option<String>,
+        role_arn: Option<String>,
+        external_id: Option<String>,
+        session_name: Option<String>,
+    }
+
+    #[derive(Debug)]
+    pub(super) struct SdkCredentials {
+        source: Source,
+        chain: tokio::sync::OnceCell<SharedCredentialsProvider>,
+        cached: tokio::sync::Mutex<Option<(Arc<AwsCredential>, Option<SystemTime>)>>,
+    }
+
+    /// Process-wide provider, so credentials are resolved once rather than per store.
+    pub(super) fn credentials(config: &S3Config) -> Arc<SdkCredentials> {
+        static SHARED: Mutex<Option<Arc<SdkCredentials>>> = Mutex::new(None);
+        let source = Source {
+            profile: config.profile.clone(),
+            region: config.region.clone(),
+            role_arn: config.role_arn.clone(),
+            external_id: config.external_id.clone(),
+            session_name: config.session_name.clone(),
+        };
+        let mut shared = SHARED.lock().unwrap_or_else(|e| e.into_inner());
+        match shared.as_ref() {
+            Some(c) if c.source == source => c.clone(),
+            _ => {
+                let c = Arc::new(SdkCredentials {
+                    source,
+                    chain: tokio::sync::OnceCell::new(),
+                    cached: tokio::sync::Mutex::new(None),
+                });
+                *shared = Some(c.clone());
+                c
+            }
+        }
+    }
+
+    // SDK errors keep the useful part (which token expired, say) in their
+    // sources, under a generic "an error occurred while loading credentials"
+    fn with_causes(e: &dyn std::error::Error) -> String {
+        let mut parts = Vec::new();
+        let mut level = Some(e);
+        while let Some(err) = level {
+            level = err.source();
+            if level.is_some() && err.to_string() == "an error occurred while loading credentials" {
+                continue;
+            }
+            parts.push(err.to_string());
+        }
+        parts.join(": ")
+    }
+
+    /// Providers tried in order until one returns credentials. A provider
+    /// that applies but fails (an expired SSO token, a rejected web identity
+    /// token) stops the chain with an error naming it.
+    #[derive(Debug)]
+    pub(super) struct NamedChain {
+        providers: Vec<(&'static str, SharedCredentialsProvider)>,
+    }
+
+    impl NamedChain {
+        async fn credentials(&self) -> aws_credential_types::provider::Result {
+            for (name, provider) in &self.providers {
+                match provider.provide_credentials().await {
+                    Ok(creds) => {
+                        tracing::debug!(provider = name, "loaded AWS credentials");
+                        return Ok(creds);
+                    }
+                    Err(CredentialsError::CredentialsNotLoaded(_)) => continue,
+                    Err(e) => {
+                        let detail = with_causes(&e);
+                        // a missing or stale token cache means the session ran out
+                        let hint = if detail.contains("sso/cache") || detail.contains("SSO") {
+                            " (run `aws sso login` to refresh the session)"
+                        } else {
+                            ""
+                        };
+                        return Err(CredentialsError::provider_error(format!(
+                            "{name} credentials provider failed: {detail}{hint}"
+                        )));
+                    }
+                }
+            }
+            let tried: Vec<&str> = self.providers.iter().map(|(name, _)| *name).collect();
+            Err(CredentialsError::not_loaded(format!(
+                "no AWS credentials found (tried {})",
+                tried.join(", ")
+            )))
+        }
+    }
+
+    impl ProvideCredentials for NamedChain {
+        fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+        where
+            Self: 'a,
+        {
+            future::ProvideCredentials::new(self.credentials())
+        }
+    }
+
+    impl Source {
+        // the SDK's default chain, with its region resolution, as a NamedChain
+        async fn chain(&self) -> NamedChain {
+            let mut region = DefaultRegionChain::builder();
+            if let Some(profile) = &self.profile {
+                region = region.profile_name(profile);
+            }
+            let region = match &self.region {
+                Some(r) => Some(aws_config::Region::new(r.clone())),
+                None => region.build().region().await,
+            };
+            let conf = ProviderConfig::default().with_region(region);
+            let mut profile = ProfileFileCredentialsProvider::builder().configure(&conf);
+            if let Some(name) = &self.profile {
+                profile = profile.profile_name(name);
+            }
+            NamedChain {
+                providers: vec![
+                    (
+                        "environment",
+                        SharedCredentialsProvider::new(
+                            EnvironmentVariableCredentialsProvider::new(),
+                        ),
+                    ),
+                    (
+                        "profile (incl. SSO)",
+                        SharedCredentialsProvider::new(profile.build()),
+                    ),
+                    (
+                        "web identity token",
+                        SharedCredentialsProvider::new(
+                            WebIdentityTokenCredentialsProvider::builder()
+                                .configure(&conf)
+                                .build(),
+                        ),
+                    ),
+                    (
+                        "ECS container",
+                        SharedCredentialsProvider::new(
+                            EcsCredentialsProvider::builder().configure(&conf).build(),
+                        ),
+                    ),
+                    (
+                        "EC2 instance metadata",
+                        SharedCredentialsProvider::new(
+                            ImdsCredentialsProvider::builder().configure(&conf).build(),
+                        ),
+                    ),
+                ],
+            }
+        }
+
+        async fn provider(&self) -> SharedCredentialsProvider {
+            let chain = self.chain().await;
+            let Some(role_arn) = &self.role_arn else {
+                return SharedCredentialsProvider::new(chain);
+            };
+            let mut role = AssumeRoleProvider::builder(role_arn)
+                .session_name(self.session_name.as_deref().unwrap_or("parquet-lens"));
+            if let Some(id) = &self.external_id {
+                role = role.external_id(id);
+            }
+            if let Some(region) = &self.region {
+                role = role.region(aws_config::Region::new(region.clone()));
+            }
+            SharedCredentialsProvider::new(role.build_from_provider(chain).await)
+        }
+    }
+
+    #[async_trait]
+    impl CredentialProvider for SdkCredentials {
+        type Credential = AwsCredential;
+
+        async fn get_credential(&self) -> object_store::Result<Arc<AwsCredential>> {
+            let mut cached = self.cached.lock().await;
+            if let Some((credential, expiry)) = cached.as_ref() {
+                if expiry.is_none_or(|e| e > SystemTime::now() + EXPIRY_MARGIN) {
+                    return Ok(credential.clone());
+                }
+            }
+            let chain = self.chain.get_or_init(|| self.source.provider()).await;
+            let creds = chain.provide_credentials().await.map_err(|e| {
+                let message = match &self.source.role_arn {
+                    Some(arn) => format!("assuming role {arn}: {}", with_causes(&e)),
+                    None => with_causes(&e),
+                };
+                object_store::Error::Unauthenticated {
+                    path: String::new(),
+                    source: message.into(),
+                }
+            })?;
+            let credential = Arc::new(AwsCredential {
+                key_id: creds.access_key_id().to_string(),
+                secret_key: creds.secret_access_key().to_string(),
+                token: creds.session_token().map(str::to_string),
+            });
+            *cached = Some((credential.clone(), creds.expiry()));
+            Ok(credential)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests_named_chain {
+        use super::*;
+        use aws_credential_types::Credentials;
+
+        #[derive(Debug)]
+        struct Fails(fn() -> CredentialsError);
+
+        impl ProvideCredentials for Fails {
+            fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+            where
+                Self: 'a,
+            {
+                future::ProvideCredentials::ready(Err((self.0)()))
+            }
+        }
+
+        fn chain(providers: Vec<(&'static str, SharedCredentialsProvider)>) -> NamedChain {
+            NamedChain { providers }
+        }
+
+        #[tokio::test]
+        async fn skips_absent_providers_and_names_the_one_that_failed() {
+            let absent = || CredentialsError::not_loaded("no token file");
+            let expired = || CredentialsError::provider_error("SSO token expired");
+            let creds = Credentials::new("AKID", "secret", None, None, "test");
+
+            let found = chain(vec![
+                ("environment", SharedCredentialsProvider::new(Fails(absent))),
+                ("profile (incl. SSO)", SharedCredentialsProvider::new(creds)),
+            ]);
+            let got = found.provide_credentials().await.unwrap();
+            assert_eq!(got.access_key_id(), "AKID");
+
+            let failed = chain(vec![
+                ("environment", SharedCredentialsProvider::new(Fails(absent))),
+                (
+                    "profile (incl. SSO)",
+                    SharedCredentialsProvider::new(Fails(expired)),
+                ),
+                (
+                    "web identity token",
+                    SharedCredentialsProvider::new(Fails(absent)),
+                ),
+            ]);
+            let err = with_causes(&failed.provide_credentials().await.unwrap_err());
+            assert!(
+                err.contains("profile (incl. SSO) credentials provider failed"),
+                "{err}"
+            );
+            assert!(err.contains("SSO token expired"), "{err}");
+            assert!(err.contains("aws sso login"), "{err}");
+
+            let none = chain(vec![
+                ("environment", SharedCredentialsProvider::new(Fails(absent))),
+                (
+                    "EC2 instance metadata",
+                    SharedCredentialsProvider::new(Fails(absent)),
+                ),
+            ]);
+            let err = with_causes(&none.provide_credentials().await.unwrap_err());
+            assert!(
+                err.contains("no AWS credentials found (tried environment, EC2 instance metadata)"),
+                "{err}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_object_store_reader {
+    use super::*;
+    use arrow::array::Int32Array;
+    use arrow::record_batch::RecordBatch;
+    use object_store::memory::InMemory;
+    use parquet::arrow::ArrowWriter;
+
+    // a whole backend is just the URIs it serves and how to build their reader
+    struct MemBackend(ObjectStoreReader);
+
+    impl ObjectStoreBackend for MemBackend {
+        fn name(&self) -> &str {
+            "mem"
+        }
+
+        fn handles(&self, uri: &str) -> bool {
+            uri.starts_with("memstore://bucket")
+        }
+
+        fn reader(&self, uri: &str) -> Result<(ObjectStoreReader, ObjectPath)> {
+            let key = uri.strip_prefix("memstore://bucket/").unwrap_or("");
+            Ok((self.0.clone(), ObjectPath::from(key)))
+        }
+    }
+
+    fn parquet_bytes(rows: i32) -> Bytes {
+        let batch = RecordBatch::try_from_iter([(
+            "id",
+            Arc::new(Int32Array::from_iter_values(0..rows)) as _,
+        )])
+        .unwrap();
+        let mut buf = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        buf.into()
+    }
+
+    #[tokio::test]
+    async fn adapter_lists_reads_and_opens() {
+        let store = Arc::new(InMemory::new());
+        for (key, body) in [
+            ("data/2024-01.parquet", parquet_bytes(10)),
+            ("data/2024-02.parquet", parquet_bytes(20)),
+            ("data/notes.txt", Bytes::from_static(b"x")),
+            ("other/x.parquet", parquet_bytes(1)),
+        ] {
+            store
+                .put(&ObjectPath::from(key), body.into())
+                .await
+                .unwrap();
+        }
+        let backend = MemBackend(ObjectStoreReader::new(store, "memstore://bucket"));
+
+        let listed = backend.list("memstore://bucket/data").await.unwrap();
+        assert_eq!(
+            listed,
+            [
+                "memstore://bucket/data/2024-01.parquet",
+                "memstore://bucket/data/2024-02.parquet"
+            ]
+        );
+        // not a directory: keys match by string prefix
+        let partial = backend.list("memstore://bucket/data/2024-0").await.unwrap();
+        assert_eq!(partial, listed);
+
+        let uri = &listed[1];
+        let info = backend.metadata(uri).await.unwrap();
+        assert_eq!(info.size, parquet_bytes(20).len() as u64);
+        assert!(info.etag.is_some() && info.last_modified.is_some());
+        let tail = backend
+            .read_range(uri, info.size - 4..info.size)
+            .await
+            .unwrap();
+        assert_eq!(&tail[..], b"PAR1");
+        let (file_info, _) = backend.open(uri).await.unwrap();
+        assert_eq!(file_info.row_count, 20);
+
+        let err = backend
+            .open("memstore://bucket/missing.parquet")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "not found: memstore://bucket/missing.parquet"
+        );
+    }
+
+    #[derive(Default)]
+    struct Counter(std::sync::Mutex<u64>);
+
+    impl crate::ProgressSink for Counter {
+        fn on_finish(&self, _operation: &str, done: Progress) {
+            *self.0.lock().unwrap() = done.rows;
+        }
+    }
+
+    #[tokio::test]
+    async fn fans_out_below_deep_partitions_and_stops_at_the_cap() {
+        let store = Arc::new(InMemory::new());
+        // year=/month=/day=/hour= nests one level past the fan-out depth
+        let mut expected = Vec::new();
+        for month in 1..=3 {
+            for day in 1..=4 {
+                for hour in 0..2 {
+                    let dir = format!("t/year=2024/month={month}/day={day}/hour={hour}");
+                    for key in [format!("{dir}/part-0.parquet"), format!("{dir}/_SUCCESS")] {
+                        store
+                            .put(&ObjectPath::from(key.as_str()), Bytes::new().into())
+                            .await
+                            .unwrap();
+                    }
+                    expected.push(format!("{dir}/part-0.parquet"));
+                }
+            }
+        }
+        expected.sort();
+        let reader = ObjectStoreReader::new(store, "memstore://bucket");
+        let counter = Arc::new(Counter::default());
+        let options = ListOptions {
+            concurrency: 2,
+            progress: Some(counter.clone()),
+            ..ListOptions::default()
+        };
+
+        let mut keys = reader
+            .list_keys(Some(&ObjectPath::from("t")), "", &options)
+            .await
+            .unwrap();
+        keys.sort();
+        assert_eq!(keys, expected);
+        assert_eq!(*counter.0.lock().unwrap(), 48);
+
+        let capped = ListOptions {
+            max_files: Some(5),
+            concurrency: 1,
+            ..ListOptions::default()
+        };
+        let keys = reader
+            .list_keys(Some(&ObjectPath::from("t")), "", &capped)
+            .await
+            .unwrap();
+        assert!((5..expected.len()).contains(&keys.len()), "{}", keys.len());
+        assert!(keys.iter().all(|k| expected.contains(k)));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::stats::{AggregatedColumnStats, EncodingAnalysis, RowGroupProfile};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepairSuggestion {
     pub issue: String,
     pub severity: String, // "high", "medium", "low"
@@ -0,0 +1,137 @@
+use crate::baseline::BaselineRegression;
+use crate::engine::EngineInfo;
+use crate::mask::MaskSpec;
+use crate::nested::{NestedColumnProfile, NestedDataProfile};
+use crate::null_patterns::NullPatternGroup;
+use crate::parallel_reader::DatasetProfile;
+use crate::profile::ColumnProfileResult;
+use crate::quality::{DatasetQuality, PiiReport, QualityScore};
+use crate::quarantine::QuarantineReport;
+use crate::reader::ParquetFileInfo;
+use crate::recommendations::RowGroupSizeRecommendation;
+use crate::repair::RepairSuggestion;
+use crate::row_count::RowCountCheck;
+use crate::run_info::RunInfo;
+use crate::sample::MetricConfidence;
+use crate::stats::{AggregatedColumnStats, CompressionAnalysis, EncodingAnalysis, RowGroupProfile};
+use crate::timeseries::TimeSeriesProfile;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Everything one profiling run produced, and the only shape results are passed
+/// around in: the `Profiler` builds it, exports serialize it, the TUI loads it and
+/// `check` reads regressions from it. File-level sections (row groups, column
+/// stats, encodings, ...) describe the first resolved file; `dataset` covers all.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct ProfileReport {
+    pub dataset: DatasetProfile,
+    pub file_info: ParquetFileInfo,
+    pub row_groups: Vec<RowGroupProfile>,
+    pub column_stats: Vec<AggregatedColumnStats>,
+    pub encodings: Vec<EncodingAnalysis>,
+    pub compression: Vec<CompressionAnalysis>,
+    pub quality: DatasetQuality,
+    pub null_patterns: Vec<NullPatternGroup>,
+    pub repair_suggestions: Vec<RepairSuggestion>,
+    pub row_group_recommendation: Option<RowGroupSizeRecommendation>,
+    pub engine_info: Option<EngineInfo>,
+    pub timeseries_profiles: Vec<TimeSeriesProfile>,
+    pub nested_profiles: Vec<NestedColumnProfile>,
+    #[serde(default)]
+    pub nested_data: Option<NestedDataProfile>, // list lengths, map keys, struct nulls; full scans only
+    pub baseline_captured_at: Option<u64>, // unix secs; None when no baseline was loaded
+    pub baseline_regressions: Vec<BaselineRegression>,
+    pub full_scan: Option<Vec<ColumnProfileResult>>, // sampled when `sample_note` is set
+    pub sample_note: Option<String>,
+    #[serde(default)]
+    pub sample_confidence: Vec<MetricConfidence>, // per-metric 95% CIs when sampled
+    pub quarantine: Option<QuarantineReport>, // row groups the scans had to skip
+    pub row_counts: Option<RowCountCheck>,    // set when row counts were verified
+    #[serde(default)]
+    pub pii: Option<PiiReport>, // set when string columns were scanned for PII
+    pub warnings: Vec<String>,                // analyses that failed without failing the run
+    #[serde(default)]
+    pub run: Option<RunInfo>, // provenance; None for reports not built by `Profiler`
+}
+
+/// Serialized with a top-level `quality_scores` copy of `quality.column_scores`,
+/// the key exports carried before the report had a `quality` section; it's
+/// ignored when a report is read back.
+impl Serialize for ProfileReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Fields<'a>(&'a ProfileReport);
+        impl Serialize for Fields<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                ProfileReport::serialize(self.0, serializer)
+            }
+        }
+        #[derive(Serialize)]
+        struct WithScores<'a> {
+            #[serde(flatten)]
+            report: Fields<'a>,
+            quality_scores: &'a [QualityScore],
+        }
+        WithScores {
+            report: Fields(self),
+            quality_scores: self.quality_scores(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProfileReport {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ProfileReport::deserialize(deserializer)
+    }
+}
+
+impl ProfileReport {
+    pub fn quality_scores(&self) -> &[QualityScore] {
+        &self.quality.column_scores
+    }
+
+    /// Distinct values of `column`: the full scan's HLL estimate, whose
+    /// sketches are unioned across files, else the footer distinct counts
+    /// summed over row groups, which overcounts values repeated between them.
+    pub fn distinct_estimate(&self, column: &str) -> Option<u64> {
+        self.full_scan
+            .as_ref()
+            .and_then(|fs| fs.iter().find(|r| r.column_name == column))
+            .map(|r| r.cardinality.approximate_distinct)
+            .or_else(|| {
+                self.column_stats
+                    .iter()
+                    .find(|s| s.column_name == column)?
+                    .total_distinct_count_estimate
+            })
+    }
+
+    /// Drops every per-column section for columns not in `columns`.
+    pub fn retain_columns(&mut self, columns: &[String]) {
+        let keep = |name: &str| columns.iter().any(|c| c == name);
+        self.column_stats.retain(|s| keep(&s.column_name));
+        self.encodings.retain(|e| keep(&e.column_name));
+        self.compression.retain(|c| keep(&c.column_name));
+        self.quality.column_scores.retain(|q| keep(&q.column_name));
+        if let Some(fs) = &mut self.full_scan {
+            fs.retain(|r| keep(&r.column_name));
+        }
+        self.sample_confidence.retain(|m| keep(&m.column));
+    }
+
+    /// Rewrites the full scan's frequency tables of every column `mask` covers,
+    /// so exported value lists and suites carry no cleartext values.
+    pub fn mask_values(&mut self, mask: &MaskSpec) {
+        for profile in self.full_scan.iter_mut().flatten() {
+            if let Some(freq) = &mut profile.frequency {
+                mask.apply_to_values(&profile.column_name, &mut freq.top_values);
+            }
+        }
+    }
+
+    /// Keeps only the first `limit` columns' stats and scores.
+    pub fn truncate_columns(&mut self, limit: usize) {
+        self.column_stats.truncate(limit);
+        self.quality.column_scores.truncate(limit);
+    }
+}
@@ -0,0 +1,164 @@
+use parquet_lens_common::RetryConfig;
+#[cfg(any(feature = "http", feature = "hdfs"))]
+use parquet_lens_common::{ParquetLensError, Result};
+#[cfg(any(feature = "http", feature = "hdfs"))]
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+#[cfg(any(feature = "http", feature = "hdfs", feature = "gcs"))]
+use std::time::Duration;
+#[cfg(any(feature = "http", feature = "hdfs"))]
+use std::time::Instant;
+
+static CONFIG: RwLock<Option<RetryConfig>> = RwLock::new(None);
+static RETRIES: AtomicU64 = AtomicU64::new(0);
+
+/// Retry policy every remote reader uses from now on, usually `Config::retry`.
+pub fn set_retry_config(config: RetryConfig) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = Some(config);
+}
+
+#[cfg(any(
+    feature = "s3",
+    feature = "gcs",
+    feature = "azure",
+    feature = "http",
+    feature = "hdfs"
+))]
+pub(crate) fn retry_policy() -> RetryConfig {
+    CONFIG
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// Remote requests retried so far in this process.
+pub fn retry_count() -> u64 {
+    RETRIES.load(Ordering::Relaxed)
+}
+
+/// Counts a retry made outside this crate's own loops, e.g. one object_store
+/// only reports through its logs.
+pub fn record_retry() {
+    RETRIES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Wait before retry number `retry` (0-based) under `policy`.
+#[cfg(any(feature = "http", feature = "hdfs"))]
+pub(crate) fn backoff(policy: &RetryConfig, retry: usize) -> Duration {
+    let initial = policy.initial_backoff_ms as f64;
+    let ms = initial * policy.backoff_base.max(1.0).powi(retry.min(64) as i32);
+    Duration::from_millis(ms.min(policy.max_backoff_ms as f64) as u64)
+}
+
+/// The per-request timeout as object_store's client option value.
+#[cfg(any(feature = "s3", feature = "gcs", feature = "azure", feature = "http"))]
+pub(crate) fn request_timeout() -> String {
+    format!("{}s", retry_policy().request_timeout_secs)
+}
+
+/// HTTP client for the readers that call reqwest directly.
+#[cfg(any(feature = "http", feature = "hdfs", feature = "gcs"))]
+pub(crate) fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(retry_policy().request_timeout_secs))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Runs `op` until it succeeds, fails with a non-transient error, or the
+/// policy's retries or time run out. `what` names the request in the log.
+#[cfg(any(feature = "http", feature = "hdfs"))]
+pub(crate) async fn with_retries<T, F, Fut>(what: &str, mut op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let policy = retry_policy();
+    let deadline = Instant::now() + Duration::from_secs(policy.retry_timeout_secs);
+    let mut retries = 0;
+    loop {
+        match op().await {
+            Err(e)
+                if is_transient(&e)
+                    && retries < policy.max_retries
+                    && Instant::now() < deadline =>
+            {
+                let wait = backoff(&policy, retries);
+                retries += 1;
+                record_retry();
+                tracing::warn!(
+                    "{what}: {e}; retry {retries} of {} in {:.1}s",
+                    policy.max_retries,
+                    wait.as_secs_f32()
+                );
+                tokio::time::sleep(wait).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+// throttling, 5xx responses and timeouts; the readers map all three to Throttled
+#[cfg(any(feature = "http", feature = "hdfs"))]
+fn is_transient(e: &ParquetLensError) -> bool {
+    matches!(e, ParquetLensError::Throttled(_))
+}
+
+#[cfg(all(test, any(feature = "http", feature = "hdfs")))]
+mod tests_retry {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_the_cap() {
+        let policy = RetryConfig {
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1000,
+            backoff_base: 3.0,
+            ..RetryConfig::default()
+        };
+        let waits: Vec<u64> = (0..4)
+            .map(|n| backoff(&policy, n).as_millis() as u64)
+            .collect();
+        assert_eq!(waits, [100, 300, 900, 1000]);
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors_only() {
+        set_retry_config(RetryConfig {
+            max_retries: 3,
+            initial_backoff_ms: 1,
+            ..RetryConfig::default()
+        });
+        let before = retry_count();
+        let calls = AtomicUsize::new(0);
+        let got = with_retries("GET flaky", || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(ParquetLensError::Throttled("HTTP 503".into())),
+                _ => Ok(7),
+            }
+        })
+        .await;
+        assert_eq!(got.unwrap(), 7);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(retry_count() >= before + 2);
+
+        calls.store(0, Ordering::SeqCst);
+        let missing: Result<()> = with_retries("GET gone", || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(ParquetLensError::NotFound("gone".into()))
+        })
+        .await;
+        assert_eq!(missing.unwrap_err().code(), "not_found");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let exhausted: Result<()> = with_retries("GET down", || async {
+            Err(ParquetLensError::Throttled("HTTP 500".into()))
+        })
+        .await;
+        assert_eq!(exhausted.unwrap_err().code(), "throttled");
+        set_retry_config(RetryConfig::default());
+    }
+}
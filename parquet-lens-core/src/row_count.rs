@@ -0,0 +1,184 @@
+use crate::handle::{FileHandle, HandleRegistry};
+use crate::options::ScanOptions;
+use crate::profile::full_scan::{corrupt_detail, scan_pool};
+use crate::progress::row_group_totals;
+use parquet::arrow::ProjectionMask;
+use parquet::file::metadata::ParquetMetaData;
+use parquet_lens_common::{ParquetLensError, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A row group whose footer `num_rows` disagrees with what is actually stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowCountMismatch {
+    pub row_group: usize,
+    pub column: Option<String>, // set for column chunk value counts
+    pub footer_rows: i64,
+    pub actual_rows: Option<i64>, // None when the row group couldn't be decoded
+    pub source: String,           // "column_chunk" or "decoded"
+    pub error: Option<String>,
+}
+
+/// Footer row counts checked against the data. Chunk value counts of flat
+/// columns are compared from metadata alone; one leaf column per row group
+/// (the smallest) is then decoded to count the rows actually present.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowCountCheck {
+    pub file: String,
+    pub footer_rows: i64,    // file-level num_rows
+    pub row_group_rows: i64, // sum of row group num_rows
+    pub decoded_rows: i64,   // rows read back from the smallest column
+    pub mismatches: Vec<RowCountMismatch>,
+}
+
+impl RowCountCheck {
+    /// True when every count agrees, i.e. the file wasn't truncated or torn.
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+            && self.footer_rows == self.row_group_rows
+            && self.row_group_rows == self.decoded_rows
+    }
+
+    /// One line per disagreement, e.g. `row group 4: footer 1000 rows, decoded 812`.
+    pub fn lines(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        if self.footer_rows != self.row_group_rows {
+            out.push(format!(
+                "footer {} rows, row groups sum to {}",
+                self.footer_rows, self.row_group_rows
+            ));
+        }
+        for m in &self.mismatches {
+            let column = m
+                .column
+                .as_deref()
+                .map(|c| format!(" column {c}"))
+                .unwrap_or_default();
+            let actual = match (m.actual_rows, &m.error) {
+                (Some(n), _) => n.to_string(),
+                (None, Some(e)) => format!("unreadable ({e})"),
+                (None, None) => "unknown".into(),
+            };
+            out.push(format!(
+                "row group {}{column}: footer {} rows, {} {actual}",
+                m.row_group, m.footer_rows, m.source
+            ));
+        }
+        out
+    }
+}
+
+pub fn reconcile_row_counts(path: &Path, opts: &ScanOptions) -> Result<RowCountCheck> {
+    reconcile_row_counts_with_handle(&HandleRegistry::global().get(path)?, opts)
+}
+
+/// Row groups are decoded concurrently on a pool of `opts.threads` workers.
+#[tracing::instrument(name = "row_counts", skip_all, fields(path = %handle.path().display()))]
+pub fn reconcile_row_counts_with_handle(
+    handle: &FileHandle,
+    opts: &ScanOptions,
+) -> Result<RowCountCheck> {
+    let meta = handle.metadata();
+    let rgs: Vec<usize> = (0..meta.num_row_groups()).collect();
+    let mut mismatches = chunk_value_mismatches(meta);
+    let progress = opts.start_progress("row_counts", row_group_totals(meta, &rgs));
+    let pool = scan_pool(opts.threads)?;
+    let decoded: Vec<(usize, Result<i64>)> = pool.install(|| {
+        rgs.par_iter()
+            .map(|&rg| {
+                let rows = decode_row_count(handle, meta, rg, opts.batch_size);
+                progress.advance(
+                    *rows.as_ref().unwrap_or(&0) as u64,
+                    meta.row_group(rg).compressed_size().max(0) as u64,
+                );
+                (rg, rows)
+            })
+            .collect()
+    });
+    let mut decoded_rows = 0;
+    for (rg, rows) in decoded {
+        let footer_rows = meta.row_group(rg).num_rows();
+        match rows {
+            Ok(n) => {
+                decoded_rows += n;
+                if n != footer_rows {
+                    mismatches.push(RowCountMismatch {
+                        row_group: rg,
+                        column: None,
+                        footer_rows,
+                        actual_rows: Some(n),
+                        source: "decoded".into(),
+                        error: None,
+                    });
+                }
+            }
+            Err(e) => mismatches.push(RowCountMismatch {
+                row_group: rg,
+                column: None,
+                footer_rows,
+                actual_rows: None,
+                source: "decoded".into(),
+                error: Some(corrupt_detail(&e)),
+            }),
+        }
+    }
+    mismatches.sort_by_key(|m| (m.row_group, m.column.is_none()));
+    Ok(RowCountCheck {
+        file: handle.path().display().to_string(),
+        footer_rows: meta.file_metadata().num_rows(),
+        row_group_rows: meta.row_groups().iter().map(|rg| rg.num_rows()).sum(),
+        decoded_rows,
+        mismatches,
+    })
+}
+
+// a non-repeated column stores one value (null or not) per row
+fn chunk_value_mismatches(meta: &ParquetMetaData) -> Vec<RowCountMismatch> {
+    let mut out = Vec::new();
+    for (rg_idx, rg) in meta.row_groups().iter().enumerate() {
+        for col in rg.columns() {
+            if col.column_descr().max_rep_level() > 0 || col.num_values() == rg.num_rows() {
+                continue;
+            }
+            out.push(RowCountMismatch {
+                row_group: rg_idx,
+                column: Some(col.column_path().string()),
+                footer_rows: rg.num_rows(),
+                actual_rows: Some(col.num_values()),
+                source: "column_chunk".into(),
+                error: None,
+            });
+        }
+    }
+    out
+}
+
+// rows in `rg`, counted by decoding its cheapest leaf column
+fn decode_row_count(
+    handle: &FileHandle,
+    meta: &ParquetMetaData,
+    rg: usize,
+    batch_size: usize,
+) -> Result<i64> {
+    let columns = meta.row_group(rg).columns();
+    let Some(leaf) = (0..columns.len()).min_by_key(|&i| columns[i].compressed_size()) else {
+        return Ok(0);
+    };
+    let location = || format!("{} row group {rg}", handle.path().display());
+    let mask = ProjectionMask::leaves(meta.file_metadata().schema_descr(), [leaf]);
+    let reader = handle
+        .reader_builder()?
+        .with_projection(mask)
+        .with_row_groups(vec![rg])
+        .with_batch_size(batch_size)
+        .build()
+        .map_err(|e| ParquetLensError::corrupt(location(), e))?;
+    let mut rows = 0;
+    for batch in reader {
+        rows += batch
+            .map_err(|e| ParquetLensError::corrupt(location(), e))?
+            .num_rows() as i64;
+    }
+    Ok(rows)
+}
@@ -0,0 +1,102 @@
+use crate::storage::ObjectInfo;
+use serde::{Deserialize, Serialize};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Who produced a report, from what, and how long each stage took; embedded
+/// in exports so archived reports can be audited and reproduced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunInfo {
+    pub tool_version: String,
+    pub build: Option<String>, // `git describe` of the build; None outside a git checkout
+    pub args: Vec<String>,     // command line of the process that ran the profile
+    pub started_at: u64,       // unix secs
+    pub finished_at: u64,      // unix secs
+    pub duration_ms: u64,
+    pub stages: Vec<StageTiming>,
+    pub inputs: Vec<ObjectInfo>, // size, mtime and (remote) ETag of every input file
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageTiming {
+    pub stage: String,
+    pub duration_ms: u64,
+}
+
+impl RunInfo {
+    /// `key,value` rows for CSV exports; inputs become `input.N.<field>`.
+    pub fn csv_rows(&self) -> Vec<(String, String)> {
+        let mut rows = vec![
+            ("tool_version".into(), self.tool_version.clone()),
+            ("build".into(), self.build.clone().unwrap_or_default()),
+            ("args".into(), self.args.join(" ")),
+            ("started_at".into(), self.started_at.to_string()),
+            ("finished_at".into(), self.finished_at.to_string()),
+            ("duration_ms".into(), self.duration_ms.to_string()),
+        ];
+        for s in &self.stages {
+            rows.push((format!("stage.{}_ms", s.stage), s.duration_ms.to_string()));
+        }
+        for (i, input) in self.inputs.iter().enumerate() {
+            rows.push((format!("input.{i}.uri"), input.uri.clone()));
+            rows.push((format!("input.{i}.size"), input.size.to_string()));
+            rows.push((
+                format!("input.{i}.last_modified"),
+                input.last_modified.clone().unwrap_or_default(),
+            ));
+            rows.push((
+                format!("input.{i}.etag"),
+                input.etag.clone().unwrap_or_default(),
+            ));
+        }
+        rows
+    }
+}
+
+/// Times consecutive stages of one run: each `lap` records the time since the
+/// previous one.
+pub(crate) struct StageClock {
+    started_at: u64,
+    start: Instant,
+    last: Instant,
+    stages: Vec<StageTiming>,
+}
+
+impl StageClock {
+    pub(crate) fn start() -> Self {
+        let now = Instant::now();
+        Self {
+            started_at: unix_secs(),
+            start: now,
+            last: now,
+            stages: Vec::new(),
+        }
+    }
+
+    pub(crate) fn lap(&mut self, stage: &str) {
+        let now = Instant::now();
+        self.stages.push(StageTiming {
+            stage: stage.to_string(),
+            duration_ms: now.duration_since(self.last).as_millis() as u64,
+        });
+        self.last = now;
+    }
+
+    pub(crate) fn finish(self, inputs: Vec<ObjectInfo>) -> RunInfo {
+        RunInfo {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            build: option_env!("PARQUET_LENS_GIT_DESCRIBE").map(str::to_string),
+            args: std::env::args().collect(),
+            started_at: self.started_at,
+            finished_at: unix_secs(),
+            duration_ms: self.start.elapsed().as_millis() as u64,
+            stages: self.stages,
+            inputs,
+        }
+    }
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
@@ -1,9 +1,38 @@
+use crate::handle::{FileHandle, HandleRegistry};
+use crate::options::ScanOptions;
 use crate::profile::ColumnProfileResult;
-use crate::stats::{AggregatedColumnStats, RowGroupProfile};
-use crate::{aggregate_column_stats, profile_row_groups, read_column_stats};
-use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use crate::scanner::ParquetFilePath;
+use crate::stats::{AggregatedColumnStats, ColumnStats, RowGroupProfile};
+use crate::{aggregate_column_stats, analyze_partitions, profile_row_groups, read_column_stats};
+use arrow::array::{Array, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use parquet::arrow::arrow_reader::{RowSelection, RowSelector};
+use parquet::arrow::ProjectionMask;
+use parquet::file::metadata::ParquetMetaData;
 use parquet_lens_common::{ParquetLensError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use xxhash_rust::xxh3::xxh3_64_with_seed;
+
+/// How sampled rows are chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleMethod {
+    /// Whole row groups: unselected ones are never read, but stats are biased
+    /// when rows are clustered by time or key.
+    #[default]
+    RowGroups,
+    /// Individual rows drawn uniformly from every row group during the scan.
+    Rows,
+    /// Both stages: row groups worth about `percentage` of the file (at least
+    /// a few, for spread), then about `rows` rows drawn from within them.
+    /// Chosen by the profiler for files over `auto_sample_threshold_bytes`.
+    Auto,
+}
 
 // seed: None produces non-deterministic results (defaults to seed=0 internally).
 // CI pipelines must always pass --sample-seed for reproducibility.
@@ -11,28 +40,129 @@ pub struct SampleConfig {
     pub percentage: f64,        // 0.0–100.0
     pub no_extrapolation: bool, // when true, skip confidence extrapolation
     pub seed: Option<u64>,      // deterministic rg selection seed; None uses default (seed=0)
+    pub method: SampleMethod,
+    pub rows: Option<u64>, // fixed sample size for SampleMethod::Rows; overrides percentage
+    pub stratify_by: Option<String>, // row-level sample balanced over this column's values
+    pub allocation: StrataAllocation,
+}
+
+/// How a stratified sample's rows are split between strata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrataAllocation {
+    /// Same share of every stratum, so the sample mirrors the column's
+    /// distribution; each stratum still gets at least one row.
+    #[default]
+    Proportional,
+    /// Same number of rows from every stratum, over-sampling rare ones so
+    /// per-segment estimates are usable.
+    Equal,
 }
 
+/// One value of the stratify column: rows in the file and in the sample.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StratumSample {
+    pub value: String,
+    pub population_rows: u64,
+    pub sampled_rows: u64,
+}
+
+// more distinct values than this and the column isn't a sensible stratifier
+const MAX_STRATA: usize = 1000;
+const NULL_STRATUM: &str = "(null)";
+
 pub struct SampledProfile {
     pub agg_stats: Vec<AggregatedColumnStats>,
     pub row_groups: Vec<RowGroupProfile>,
     pub profile_results: Vec<ColumnProfileResult>,
     pub sampled_rg_count: usize,
     pub total_rg_count: usize,
+    pub sampled_rows: u64,
+    pub strata: Vec<StratumSample>, // empty unless stratified
+    pub metric_confidence: Vec<MetricConfidence>,
     pub confidence_note: String,
 }
 
+/// A sampled metric with its 95% confidence half-width, in the metric's own
+/// unit (percentage points for rates, rows for counts, bytes for sizes).
+/// `exact` metrics come from footer stats or a sample that covered every row;
+/// `margin` is None when the sample is too small to give an interval at all.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricConfidence {
+    pub column: String,
+    /// "null_rate", "mean" or "true_rate"; "null_count", "data_page_size" and
+    /// "compressed_size" when extrapolated from whole row groups; "distinct"
+    pub metric: String,
+    pub estimate: f64,
+    pub margin: Option<f64>,
+    pub exact: bool,
+    /// Bounds of an interval that isn't symmetric around `estimate`; `margin`
+    /// is then half its width.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<(f64, f64)>,
+}
+
+impl MetricConfidence {
+    /// The 95% interval, clamped at zero for metrics that can't go negative.
+    pub fn bounds(&self) -> Option<(f64, f64)> {
+        if let Some(range) = self.range {
+            return Some(range);
+        }
+        let margin = self.margin?;
+        Some(if self.metric == "mean" {
+            (self.estimate - margin, self.estimate + margin)
+        } else {
+            ((self.estimate - margin).max(0.0), self.estimate + margin)
+        })
+    }
+
+    /// e.g. `price mean 12.40 ±0.31`, `price null_rate 0.00% (exact)`,
+    /// `price distinct 1840 [1500, 2600]` or `price mean 12.40 (no interval)`
+    pub fn line(&self) -> String {
+        let (unit, places) = match self.metric.as_str() {
+            "mean" => ("", 2),
+            m if m.ends_with("_rate") => ("%", 2),
+            _ => ("", 0),
+        };
+        if self.exact {
+            format!(
+                "{} {} {:.places$}{unit} (exact)",
+                self.column, self.metric, self.estimate
+            )
+        } else if let Some((low, high)) = self.range {
+            format!(
+                "{} {} {:.places$}{unit} [{low:.places$}, {high:.places$}]",
+                self.column, self.metric, self.estimate
+            )
+        } else if let Some(margin) = self.margin {
+            format!(
+                "{} {} {:.places$}{unit} ±{margin:.places$}",
+                self.column, self.metric, self.estimate
+            )
+        } else {
+            format!(
+                "{} {} {:.places$}{unit} (no interval)",
+                self.column, self.metric, self.estimate
+            )
+        }
+    }
+}
+
+// row groups an auto sample reads at minimum, so one odd row group can't
+// stand in for the whole file
+const AUTO_MIN_ROW_GROUPS: usize = 4;
+
 // note: seed: None in SampleConfig produces non-deterministic results.
 // CI pipelines must always pass --sample-seed for reproducibility.
+#[tracing::instrument(skip_all, fields(path = %path.display(), percentage = config.percentage))]
 pub fn sample_row_groups(
     path: &Path,
     config: &SampleConfig,
     histogram_bins: usize,
+    opts: &ScanOptions,
 ) -> Result<SampledProfile> {
-    let file = std::fs::File::open(path)?;
-    let builder =
-        ParquetRecordBatchReaderBuilder::try_new(file).map_err(ParquetLensError::Parquet)?;
-    let meta = builder.metadata().clone();
+    let handle = HandleRegistry::global().get(path)?;
+    let meta = handle.metadata().clone();
     let total = meta.num_row_groups();
     if total == 0 {
         return Err(ParquetLensError::Io(std::io::Error::new(
@@ -40,13 +170,13 @@ pub fn sample_row_groups(
             "no row groups in file",
         )));
     }
-    let n = ((config.percentage / 100.0) * total as f64).ceil() as usize;
-    let n = n.clamp(1, total);
-    // deterministic pseudo-random selection: XOR index with seed then knuth hash
-    let seed = config.seed.unwrap_or(0);
-    let mut indices: Vec<usize> = (0..total).collect();
-    indices.sort_by_key(|&i| (i as u64 ^ seed).wrapping_mul(2654435761)); // knuth multiplicative hash
-    let selected: Vec<usize> = indices[..n].to_vec();
+    match config.method {
+        SampleMethod::Rows => return sample_rows(&handle, config, histogram_bins, opts),
+        SampleMethod::Auto => return sample_auto(&handle, config, histogram_bins, opts),
+        SampleMethod::RowGroups => {}
+    }
+    let selected = select_row_groups(total, config.percentage, config.seed.unwrap_or(0));
+    let n = selected.len();
 
     // profile only selected row groups
     let rg_all = profile_row_groups(&meta);
@@ -75,17 +205,30 @@ pub fn sample_row_groups(
 
     // profile columns (full data read on selected rgs only via row group filter)
     // build a temp file reader restricted to selected row groups
-    let profile_results = profile_columns_sampled(path, &selected, histogram_bins)?;
+    let profile_results =
+        profile_columns_sampled(&handle, Some(&selected), None, histogram_bins, opts)?;
 
-    // 95% CI margin: p=0.5, n=sampled rg count → ±1.96*sqrt(0.25/n)*100
-    let margin = if n > 0 {
-        1.96 * (0.25_f64 / n as f64).sqrt() * 100.0
-    } else {
-        100.0
-    };
+    let sampled_rows = sampled_row_count.max(0) as u64;
+    let total_rows = meta.file_metadata().num_rows().max(0) as u64;
+    // the footer stats of unread row groups were extrapolated, so their
+    // intervals come from how much the read row groups differ from each other
+    let metric_confidence: Vec<MetricConfidence> =
+        row_group_confidence(&meta, &col_stats_all, &selected, !config.no_extrapolation)
+            .into_iter()
+            .chain(metric_confidence(
+                &[],
+                &profile_results,
+                sampled_rows,
+                total_rows,
+                |_| false,
+            ))
+            .collect();
     let confidence_note = format!(
-        "~{:.0}% sample ({} of {} row groups); stats extrapolated; ±{:.1}% CI",
-        config.percentage, n, total, margin
+        "~{:.0}% sample ({} of {} row groups); stats extrapolated; {}",
+        config.percentage,
+        n,
+        total,
+        confidence_summary(&metric_confidence)
     );
 
     Ok(SampledProfile {
@@ -94,381 +237,830 @@ pub fn sample_row_groups(
         profile_results,
         sampled_rg_count: n,
         total_rg_count: total,
+        sampled_rows,
+        strata: Vec::new(),
+        metric_confidence,
         confidence_note,
     })
 }
 
-fn profile_columns_sampled(
-    path: &Path,
-    rg_indices: &[usize],
+/// The row groups a `SampleMethod::RowGroups` sample of `percentage` reads.
+pub(crate) fn select_row_groups(total: usize, percentage: f64, seed: u64) -> Vec<usize> {
+    let n = ((percentage / 100.0) * total as f64).ceil() as usize;
+    let n = n.clamp(1, total);
+    // deterministic pseudo-random selection: XOR index with seed then knuth hash
+    let mut indices: Vec<usize> = (0..total).collect();
+    indices.sort_by_key(|&i| (i as u64 ^ seed).wrapping_mul(2654435761)); // knuth multiplicative hash
+    indices.truncate(n);
+    indices
+}
+
+// row-level sample: every row group is read, only the selected rows are
+// profiled; footer stats already cover the whole file so nothing is extrapolated
+fn sample_rows(
+    handle: &FileHandle,
+    config: &SampleConfig,
     histogram_bins: usize,
-) -> Result<Vec<ColumnProfileResult>> {
-    use arrow::array::*;
-    use arrow::datatypes::{DataType, TimeUnit};
-    // accumulator types imported below
-    use crate::profile::full_scan::ColumnProfileResult as CPR;
-    // re-use profile_columns but with row group restriction
-    // build reader filtered to selected row groups only
-    let file = std::fs::File::open(path)?;
-    let builder = ParquetRecordBatchReaderBuilder::try_new(file)
-        .map_err(ParquetLensError::Parquet)?
-        .with_row_groups(rg_indices.to_vec());
-    let reader = builder.build().map_err(ParquetLensError::Parquet)?;
-    // delegate to inner accumulation logic — reuse profile_columns internals via direct call
-    // simpler: just call profile_columns after writing a filtered parquet to /tmp — too heavy
-    // instead, inline the accumulation using the restricted reader
-    drop(reader); // drop, rebuild below for actual processing
-                  // re-open and read with restriction, delegating to existing profile_columns logic
-                  // profile_columns doesn't accept rg filter — call it via temp path workaround is too heavy
-                  // instead read all data from selected RGs directly here
-    let file2 = std::fs::File::open(path)?;
-    let builder2 = ParquetRecordBatchReaderBuilder::try_new(file2)
-        .map_err(ParquetLensError::Parquet)?
-        .with_row_groups(rg_indices.to_vec());
-    let schema = builder2.schema().clone();
-    let reader2 = builder2
-        .with_batch_size(8192)
-        .build()
-        .map_err(ParquetLensError::Parquet)?;
-
-    let field_names: Vec<String> = schema.fields().iter().map(|f| f.name().clone()).collect();
-    let ncols = field_names.len();
-
-    // import accumulator types from profile submodules
-    use super::profile::boolean::BooleanAccumulator;
-    use super::profile::cardinality::HllEstimator;
-    use super::profile::frequency::FrequencyCounter;
-    use super::profile::histogram::build_histogram as bh;
-    use super::profile::numeric::NumericAccumulator;
-    use super::profile::string_profiler::StringAccumulator;
-    use super::profile::temporal::TemporalAccumulator;
-
-    let mut hlls: Vec<HllEstimator> = (0..ncols).map(|_| HllEstimator::new()).collect();
-    let mut freq_counters: Vec<FrequencyCounter> =
-        (0..ncols).map(|_| FrequencyCounter::new()).collect();
-    let mut numeric_accs: Vec<Option<NumericAccumulator>> = schema
-        .fields()
+    opts: &ScanOptions,
+) -> Result<SampledProfile> {
+    let meta = handle.metadata();
+    let total = meta.num_row_groups();
+    let total_rows = meta.file_metadata().num_rows().max(0) as u64;
+    let seed = config.seed.unwrap_or(0);
+    let sampler = RowSampler::for_config(handle, config)?;
+    let profile_results =
+        profile_columns_sampled(handle, None, Some(&sampler), histogram_bins, opts)?;
+    let n = sampler.selected_rows();
+    let strata = sampler.strata();
+    let agg_stats = aggregate_column_stats(&read_column_stats(meta), total_rows as i64);
+    let exact = footer_null_counts([meta.as_ref()]);
+    let metric_confidence = metric_confidence(&agg_stats, &profile_results, n, total_rows, |c| {
+        exact.contains(c)
+    });
+    let pct = if total_rows > 0 {
+        n as f64 / total_rows as f64 * 100.0
+    } else {
+        0.0
+    };
+    let method = match (&config.stratify_by, config.allocation) {
+        (None, _) => "row-level sample".to_string(),
+        (Some(c), StrataAllocation::Proportional) => {
+            format!("stratified by {c}, {} strata", strata.len())
+        }
+        (Some(c), StrataAllocation::Equal) => {
+            format!(
+                "stratified by {c}, {} strata, equal allocation",
+                strata.len()
+            )
+        }
+    };
+    let confidence_note = format!(
+        "{n} of {total_rows} rows ({pct:.2}% {method}, seed {seed}); footer stats exact; {}{}",
+        confidence_summary(&metric_confidence),
+        strata_note(&strata)
+    );
+    Ok(SampledProfile {
+        agg_stats,
+        row_groups: profile_row_groups(meta),
+        profile_results,
+        sampled_rg_count: total,
+        total_rg_count: total,
+        sampled_rows: n,
+        strata,
+        metric_confidence,
+        confidence_note,
+    })
+}
+
+// two-stage sample: a spread of row groups, then rows within them; footer
+// stats still cover the whole file
+fn sample_auto(
+    handle: &FileHandle,
+    config: &SampleConfig,
+    histogram_bins: usize,
+    opts: &ScanOptions,
+) -> Result<SampledProfile> {
+    let meta = handle.metadata();
+    let total = meta.num_row_groups();
+    let total_rows = meta.file_metadata().num_rows().max(0) as u64;
+    let seed = config.seed.unwrap_or(0);
+    let (selected, sampler) = auto_plan(meta, config);
+    let profile_results = profile_columns_sampled(
+        handle,
+        Some(&selected),
+        Some(&sampler),
+        histogram_bins,
+        opts,
+    )?;
+    let n = sampler.selected_rows();
+    let agg_stats = aggregate_column_stats(&read_column_stats(meta), total_rows as i64);
+    let exact = footer_null_counts([meta.as_ref()]);
+    let metric_confidence = metric_confidence(&agg_stats, &profile_results, n, total_rows, |c| {
+        exact.contains(c)
+    });
+    let confidence_note = format!(
+        "auto sample: {n} of {total_rows} rows from {} of {total} row groups (seed {seed}); footer stats exact; {}",
+        selected.len(),
+        confidence_summary(&metric_confidence)
+    );
+    Ok(SampledProfile {
+        agg_stats,
+        row_groups: profile_row_groups(meta),
+        profile_results,
+        sampled_rg_count: selected.len(),
+        total_rg_count: total,
+        sampled_rows: n,
+        strata: Vec::new(),
+        metric_confidence,
+        confidence_note,
+    })
+}
+
+/// Row-level sample of a hive-partitioned dataset balanced over the values of
+/// partition `key`, with strata and their row counts from `analyze_partitions`;
+/// files outside any `key=` directory form a `(null)` stratum. Every file is
+/// read, and footer stats cover all of them, so nothing is extrapolated.
+pub fn sample_partitions(
+    paths: &[ParquetFilePath],
+    key: &str,
+    config: &SampleConfig,
+    histogram_bins: usize,
+    opts: &ScanOptions,
+) -> Result<SampledProfile> {
+    let partition = analyze_partitions(paths)
+        .into_iter()
+        .find(|p| p.key == key)
+        .ok_or_else(|| ParquetLensError::Other(format!("--stratify-by: no partition key {key}")))?;
+    let handles = paths
         .iter()
-        .map(|f| match f.data_type() {
-            DataType::Int8
-            | DataType::Int16
-            | DataType::Int32
-            | DataType::Int64
-            | DataType::UInt8
-            | DataType::UInt16
-            | DataType::UInt32
-            | DataType::UInt64
-            | DataType::Float32
-            | DataType::Float64 => Some(NumericAccumulator::new()),
-            _ => None,
-        })
+        .map(|p| HandleRegistry::global().get(&p.path))
+        .collect::<Result<Vec<_>>>()?;
+    let file_rows = |h: &FileHandle| h.metadata().file_metadata().num_rows().max(0) as u64;
+    let stratum_of: Vec<&str> = paths
+        .iter()
+        .map(|p| p.partitions.get(key).map_or(NULL_STRATUM, String::as_str))
         .collect();
-    let mut str_accs: Vec<Option<StringAccumulator>> = schema
-        .fields()
+    let mut population: HashMap<String, u64> = partition
+        .partition_row_counts
         .iter()
-        .map(|f| match f.data_type() {
-            DataType::Utf8 | DataType::LargeUtf8 => Some(StringAccumulator::new()),
-            _ => None,
-        })
+        .map(|(value, &rows)| (value.clone(), rows.max(0) as u64))
         .collect();
-    let mut temporal_accs: Vec<Option<TemporalAccumulator>> = schema
-        .fields()
+    for (h, _) in handles
         .iter()
-        .map(|f| match f.data_type() {
-            DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => {
-                Some(TemporalAccumulator::new())
-            }
-            _ => None,
+        .zip(&stratum_of)
+        .filter(|(_, &s)| s == NULL_STRATUM)
+    {
+        *population.entry(NULL_STRATUM.to_string()).or_default() += file_rows(h);
+    }
+    if population.len() > MAX_STRATA {
+        return Err(ParquetLensError::Other(format!(
+            "--stratify-by {key}: more than {MAX_STRATA} partitions"
+        )));
+    }
+    let total_rows: u64 = handles.iter().map(file_rows).sum();
+    let target = config
+        .rows
+        .unwrap_or((config.percentage / 100.0 * total_rows as f64) as u64);
+    let fractions = strata_fractions(&population, target, config.allocation);
+    // each file gets its own seed so equal row offsets aren't picked in every file
+    let seed = config.seed.unwrap_or(0);
+    let samplers: Vec<RowSampler> = handles
+        .iter()
+        .zip(&stratum_of)
+        .map(|(h, s)| {
+            let file_seed = xxh3_64_with_seed(h.path().as_os_str().as_encoded_bytes(), seed);
+            RowSampler::bernoulli(h.metadata(), file_seed, fractions[*s])
         })
         .collect();
-    let mut bool_accs: Vec<Option<BooleanAccumulator>> = schema
-        .fields()
+    let files: Vec<(&FileHandle, Option<&RowSampler>)> = handles
         .iter()
-        .map(|f| match f.data_type() {
-            DataType::Boolean => Some(BooleanAccumulator::new()),
-            _ => None,
-        })
+        .zip(&samplers)
+        .map(|(h, s)| (h, Some(s)))
         .collect();
-    let mut numeric_vals: Vec<Vec<f64>> = (0..ncols).map(|_| Vec::new()).collect();
-
-    for batch_result in reader2 {
-        let batch = batch_result.map_err(ParquetLensError::Arrow)?;
-        for (col_idx, col_array) in batch.columns().iter().enumerate() {
-            for row in 0..col_array.len() {
-                if col_array.is_null(row) {
-                    if let Some(acc) = &mut temporal_accs[col_idx] {
-                        acc.add_null();
-                    }
-                    if let Some(acc) = &mut bool_accs[col_idx] {
-                        acc.add(None);
-                    }
-                    continue;
-                }
-                let val_str = array_val_str(col_array.as_ref(), row);
-                hlls[col_idx].add_bytes(val_str.as_bytes());
-                freq_counters[col_idx].add(val_str);
-                match col_array.data_type() {
-                    DataType::Int8 => {
-                        let a = col_array.as_any().downcast_ref::<Int8Array>().unwrap();
-                        let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
-                            acc.add(v);
-                        }
-                        numeric_vals[col_idx].push(v);
-                    }
-                    DataType::Int16 => {
-                        let a = col_array.as_any().downcast_ref::<Int16Array>().unwrap();
-                        let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
-                            acc.add(v);
-                        }
-                        numeric_vals[col_idx].push(v);
-                    }
-                    DataType::Int32 => {
-                        let a = col_array.as_any().downcast_ref::<Int32Array>().unwrap();
-                        let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
-                            acc.add(v);
-                        }
-                        numeric_vals[col_idx].push(v);
-                    }
-                    DataType::Int64 => {
-                        let a = col_array.as_any().downcast_ref::<Int64Array>().unwrap();
-                        let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
-                            acc.add(v);
-                        }
-                        numeric_vals[col_idx].push(v);
-                    }
-                    DataType::UInt8 => {
-                        let a = col_array.as_any().downcast_ref::<UInt8Array>().unwrap();
-                        let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
-                            acc.add(v);
-                        }
-                        numeric_vals[col_idx].push(v);
-                    }
-                    DataType::UInt16 => {
-                        let a = col_array.as_any().downcast_ref::<UInt16Array>().unwrap();
-                        let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
-                            acc.add(v);
-                        }
-                        numeric_vals[col_idx].push(v);
-                    }
-                    DataType::UInt32 => {
-                        let a = col_array.as_any().downcast_ref::<UInt32Array>().unwrap();
-                        let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
-                            acc.add(v);
-                        }
-                        numeric_vals[col_idx].push(v);
-                    }
-                    DataType::UInt64 => {
-                        let a = col_array.as_any().downcast_ref::<UInt64Array>().unwrap();
-                        let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
-                            acc.add(v);
-                        }
-                        numeric_vals[col_idx].push(v);
-                    }
-                    DataType::Float32 => {
-                        let a = col_array.as_any().downcast_ref::<Float32Array>().unwrap();
-                        let v = a.value(row) as f64;
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
-                            acc.add(v);
-                        }
-                        numeric_vals[col_idx].push(v);
-                    }
-                    DataType::Float64 => {
-                        let a = col_array.as_any().downcast_ref::<Float64Array>().unwrap();
-                        let v = a.value(row);
-                        if let Some(acc) = &mut numeric_accs[col_idx] {
-                            acc.add(v);
-                        }
-                        numeric_vals[col_idx].push(v);
-                    }
-                    DataType::Utf8 => {
-                        let a = col_array.as_any().downcast_ref::<StringArray>().unwrap();
-                        if let Some(acc) = &mut str_accs[col_idx] {
-                            acc.add(a.value(row));
-                        }
-                    }
-                    DataType::LargeUtf8 => {
-                        let a = col_array
-                            .as_any()
-                            .downcast_ref::<LargeStringArray>()
-                            .unwrap();
-                        if let Some(acc) = &mut str_accs[col_idx] {
-                            acc.add(a.value(row));
-                        }
-                    }
-                    DataType::Boolean => {
-                        let a = col_array.as_any().downcast_ref::<BooleanArray>().unwrap();
-                        if let Some(acc) = &mut bool_accs[col_idx] {
-                            acc.add(Some(a.value(row)));
-                        }
-                    }
-                    DataType::Timestamp(TimeUnit::Millisecond, _) => {
-                        let a = col_array
-                            .as_any()
-                            .downcast_ref::<TimestampMillisecondArray>()
-                            .unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
-                            acc.add_ms(a.value(row));
-                        }
-                    }
-                    DataType::Timestamp(TimeUnit::Second, _) => {
-                        let a = col_array
-                            .as_any()
-                            .downcast_ref::<TimestampSecondArray>()
-                            .unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
-                            acc.add_ms(a.value(row) * 1000);
-                        }
-                    }
-                    DataType::Timestamp(TimeUnit::Microsecond, _) => {
-                        let a = col_array
-                            .as_any()
-                            .downcast_ref::<TimestampMicrosecondArray>()
-                            .unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
-                            acc.add_ms(a.value(row) / 1000);
-                        }
-                    }
-                    DataType::Timestamp(TimeUnit::Nanosecond, _) => {
-                        let a = col_array
-                            .as_any()
-                            .downcast_ref::<TimestampNanosecondArray>()
-                            .unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
-                            acc.add_ms(a.value(row) / 1_000_000);
-                        }
-                    }
-                    DataType::Date32 => {
-                        let a = col_array.as_any().downcast_ref::<Date32Array>().unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
-                            acc.add_ms(a.value(row) as i64 * 86400 * 1000);
-                        }
-                    }
-                    DataType::Date64 => {
-                        let a = col_array.as_any().downcast_ref::<Date64Array>().unwrap();
-                        if let Some(acc) = &mut temporal_accs[col_idx] {
-                            acc.add_ms(a.value(row));
-                        }
-                    }
-                    _ => {}
-                }
+    let profile_results = crate::profile::full_scan::profile_files(
+        &files,
+        None,
+        histogram_bins,
+        None,
+        opts,
+        "sample",
+    )?;
+    let mut selected: HashMap<String, u64> = HashMap::new();
+    for (sampler, s) in samplers.iter().zip(&stratum_of) {
+        *selected.entry(s.to_string()).or_default() += sampler.selected_rows();
+    }
+    let n: u64 = selected.values().sum();
+    let strata = stratum_samples(&population, &selected);
+    let mut per_rg = Vec::new();
+    let mut row_groups = Vec::new();
+    for h in &handles {
+        per_rg.extend(read_column_stats(h.metadata()));
+        row_groups.extend(profile_row_groups(h.metadata()));
+    }
+    let agg_stats = aggregate_column_stats(&per_rg, total_rows as i64);
+    let exact = footer_null_counts(handles.iter().map(|h| h.metadata().as_ref()));
+    let metric_confidence = metric_confidence(&agg_stats, &profile_results, n, total_rows, |c| {
+        exact.contains(c)
+    });
+    let pct = if total_rows > 0 {
+        n as f64 / total_rows as f64 * 100.0
+    } else {
+        0.0
+    };
+    let allocation = match config.allocation {
+        StrataAllocation::Proportional => "",
+        StrataAllocation::Equal => ", equal allocation",
+    };
+    let confidence_note = format!(
+        "{n} of {total_rows} rows ({pct:.2}% stratified by partition {key}, {} strata{allocation}, seed {seed}); footer stats exact; {}{}",
+        strata.len(),
+        confidence_summary(&metric_confidence),
+        strata_note(&strata)
+    );
+    Ok(SampledProfile {
+        agg_stats,
+        sampled_rg_count: row_groups.len(),
+        total_rg_count: row_groups.len(),
+        row_groups,
+        profile_results,
+        sampled_rows: n,
+        strata,
+        metric_confidence,
+        confidence_note,
+    })
+}
+
+/// The row groups and row sampler a `SampleMethod::Auto` config reads.
+pub(crate) fn auto_plan(meta: &ParquetMetaData, config: &SampleConfig) -> (Vec<usize>, RowSampler) {
+    let total = meta.num_row_groups();
+    let seed = config.seed.unwrap_or(0);
+    let min_pct = AUTO_MIN_ROW_GROUPS.min(total) as f64 / total.max(1) as f64 * 100.0;
+    let mut selected = select_row_groups(total, config.percentage.max(min_pct), seed);
+    selected.sort_unstable();
+    let rows_in: u64 = selected
+        .iter()
+        .map(|&rg| meta.row_group(rg).num_rows().max(0) as u64)
+        .sum();
+    let fraction = match config.rows {
+        Some(target) if rows_in > 0 => target as f64 / rows_in as f64,
+        _ => 1.0,
+    };
+    (selected, RowSampler::bernoulli(meta, seed, fraction))
+}
+
+/// Percentage of the file's row groups an auto sample reads so it touches
+/// about `budget_bytes` of a `file_size`-byte file.
+pub(crate) fn auto_sample_percentage(file_size: u64, budget_bytes: u64) -> f64 {
+    if file_size == 0 {
+        return 100.0;
+    }
+    (budget_bytes as f64 / file_size as f64 * 100.0).clamp(0.0, 100.0)
+}
+
+// columns whose footer null counts cover every row group of every file
+fn footer_null_counts<'a>(metas: impl IntoIterator<Item = &'a ParquetMetaData>) -> HashSet<String> {
+    let mut missing = HashSet::new();
+    let mut all = HashSet::new();
+    for cs in metas.into_iter().flat_map(read_column_stats) {
+        if cs.null_count.is_none() {
+            missing.insert(cs.column_name.clone());
+        }
+        all.insert(cs.column_name);
+    }
+    all.retain(|c| !missing.contains(c));
+    all
+}
+
+// 95% intervals for footer stats read from the `selected` row groups only,
+// treating each row group as one draw: null rates as a ratio of nulls to rows,
+// and, when `extrapolated`, null counts and sizes scaled up to every row group.
+// The spread between row groups, not the row count, sets the width, so a file
+// whose row groups differ a lot gets wide intervals however big they are.
+fn row_group_confidence(
+    meta: &ParquetMetaData,
+    per_rg: &[ColumnStats],
+    selected: &[usize],
+    extrapolated: bool,
+) -> Vec<MetricConfidence> {
+    let total = meta.num_row_groups();
+    let n = selected.len();
+    let fpc = if total > 1 {
+        ((total - n) as f64 / (total - 1) as f64).sqrt()
+    } else {
+        0.0
+    };
+    let exact = fpc == 0.0;
+    // 1.96 standard errors of the mean of `values`, one per selected row
+    // group; a single row group says nothing about the spread between them
+    let margin_of_mean = |values: &[f64]| {
+        if exact {
+            return Some(0.0);
+        }
+        if values.len() < 2 {
+            return None;
+        }
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let var =
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+        Some(1.96 * (var / values.len() as f64).sqrt() * fpc)
+    };
+    let rows: Vec<f64> = selected
+        .iter()
+        .map(|&rg| meta.row_group(rg).num_rows().max(0) as f64)
+        .collect();
+    let mean_rows = rows.iter().sum::<f64>() / n.max(1) as f64;
+    let mut by_column: Vec<(&str, Vec<&ColumnStats>)> = Vec::new();
+    for cs in per_rg
+        .iter()
+        .filter(|cs| selected.contains(&cs.row_group_index))
+    {
+        match by_column.iter_mut().find(|(c, _)| *c == cs.column_name) {
+            Some((_, v)) => v.push(cs),
+            None => by_column.push((&cs.column_name, vec![cs])),
+        }
+    }
+    by_column.sort_by(|a, b| a.0.cmp(b.0));
+    let mut out = Vec::new();
+    for (column, stats) in by_column {
+        // in `selected` order, to line up with `rows`
+        let stat_of = |rg: usize| stats.iter().find(|cs| cs.row_group_index == rg);
+        let nulls: Option<Vec<f64>> = selected
+            .iter()
+            .map(|&rg| stat_of(rg).and_then(|cs| cs.null_count).map(|c| c as f64))
+            .collect();
+        let series = |f: fn(&ColumnStats) -> i64| -> Vec<f64> {
+            selected
+                .iter()
+                .map(|&rg| stat_of(rg).map_or(0.0, |cs| f(cs) as f64))
+                .collect()
+        };
+        let mut push = |metric: &str, estimate: f64, margin: Option<f64>| {
+            out.push(MetricConfidence {
+                column: column.to_string(),
+                metric: metric.into(),
+                estimate,
+                margin,
+                exact,
+                range: None,
+            });
+        };
+        if let Some(nulls) = &nulls {
+            let rate = nulls.iter().sum::<f64>() / rows.iter().sum::<f64>().max(1.0);
+            // ratio estimator: residuals of each row group from the overall rate
+            let residuals: Vec<f64> = nulls.iter().zip(&rows).map(|(y, m)| y - rate * m).collect();
+            let margin = margin_of_mean(&residuals).map(|m| m / mean_rows.max(1.0) * 100.0);
+            push("null_rate", rate * 100.0, margin);
+            if extrapolated {
+                let mean = nulls.iter().sum::<f64>() / n as f64;
+                push(
+                    "null_count",
+                    mean * total as f64,
+                    margin_of_mean(nulls).map(|m| m * total as f64),
+                );
+            }
+        }
+        if extrapolated {
+            for (metric, values) in [
+                ("data_page_size", series(|cs| cs.data_page_size)),
+                ("compressed_size", series(|cs| cs.compressed_size)),
+            ] {
+                let mean = values.iter().sum::<f64>() / n as f64;
+                push(
+                    metric,
+                    mean * total as f64,
+                    margin_of_mean(&values).map(|m| m * total as f64),
+                );
             }
         }
     }
+    out
+}
 
-    let results = field_names
-        .into_iter()
-        .enumerate()
-        .map(|(i, name)| {
-            let cardinality = hlls.remove(0).estimate();
-            let freq_counter = freq_counters.remove(0);
-            let frequency = if cardinality.approximate_distinct < 10000 {
-                Some(freq_counter.top_n(20))
-            } else {
-                let _ = freq_counter.top_n(0);
-                None
-            };
-            let numeric = numeric_accs[i].take().map(|acc| acc.finish());
-            let histogram = if !numeric_vals[i].is_empty() {
-                Some(bh(&numeric_vals[i], histogram_bins))
+// 95% intervals from `sampled` of `total` rows, with the finite population
+// correction so a sample of every row comes out exact
+fn metric_confidence(
+    agg_stats: &[AggregatedColumnStats],
+    profile_results: &[ColumnProfileResult],
+    sampled: u64,
+    total: u64,
+    null_rate_exact: impl Fn(&str) -> bool,
+) -> Vec<MetricConfidence> {
+    let fpc = if total > 1 {
+        ((total.saturating_sub(sampled)) as f64 / (total - 1) as f64).sqrt()
+    } else {
+        0.0
+    };
+    let proportion =
+        |p: f64, n: u64| (n > 0).then(|| 1.96 * (p * (1.0 - p) / n as f64).sqrt() * fpc * 100.0);
+    let mut out = Vec::new();
+    let mut stats: Vec<&AggregatedColumnStats> = agg_stats.iter().collect();
+    stats.sort_by(|a, b| a.column_name.cmp(&b.column_name));
+    for s in stats {
+        let exact = null_rate_exact(&s.column_name) || fpc == 0.0;
+        out.push(MetricConfidence {
+            column: s.column_name.clone(),
+            metric: "null_rate".into(),
+            estimate: s.null_percentage,
+            margin: if exact {
+                Some(0.0)
             } else {
-                None
+                proportion(s.null_percentage / 100.0, sampled)
+            },
+            exact,
+            range: None,
+        });
+    }
+    for r in profile_results {
+        if let Some(num) = &r.numeric {
+            let margin =
+                (num.count > 0).then(|| 1.96 * num.stddev / (num.count as f64).sqrt() * fpc);
+            out.push(MetricConfidence {
+                column: r.column_name.clone(),
+                metric: "mean".into(),
+                estimate: num.mean,
+                margin,
+                exact: fpc == 0.0,
+                range: None,
+            });
+        }
+        if let Some(b) = &r.boolean {
+            out.push(MetricConfidence {
+                column: r.column_name.clone(),
+                metric: "true_rate".into(),
+                estimate: b.true_percentage,
+                margin: proportion(b.true_percentage / 100.0, b.true_count + b.false_count),
+                exact: fpc == 0.0,
+                range: None,
+            });
+        }
+        if sampled < total {
+            out.push(distinct_confidence(r, sampled, total));
+        }
+    }
+    out
+}
+
+// Distinct values among `total` rows from those seen in `sampled`. Values seen
+// more than once are assumed common enough to have been caught anyway, and
+// each singleton stands for between one and total/sampled values; the
+// estimate is the geometric middle of that range (Charikar et al.'s GEE).
+// Without exact frequencies every sampled value is taken as a singleton.
+fn distinct_confidence(r: &ColumnProfileResult, sampled: u64, total: u64) -> MetricConfidence {
+    let seen = r.cardinality.approximate_distinct as f64;
+    let singletons = r
+        .frequency
+        .as_ref()
+        .and_then(|f| f.singletons)
+        .map_or(seen, |s| (s as f64).min(seen));
+    let scale = total as f64 / sampled.max(1) as f64;
+    let repeated = seen - singletons;
+    let estimate = repeated + scale.sqrt() * singletons;
+    // the sketch's own error widens both ends
+    let err = 2.0 * r.cardinality.error_rate;
+    let low = seen * (1.0 - err).max(0.0);
+    let high = ((repeated + scale * singletons) * (1.0 + err)).min(total as f64);
+    MetricConfidence {
+        column: r.column_name.clone(),
+        metric: "distinct".into(),
+        estimate: estimate.clamp(low, high.max(low)),
+        margin: Some((high - low).max(0.0) / 2.0),
+        exact: false,
+        range: Some((low, high.max(low))),
+    }
+}
+
+// the largest strata with their sampled/population rows, for the one-line note
+fn strata_note(strata: &[StratumSample]) -> String {
+    if strata.is_empty() {
+        return String::new();
+    }
+    let shown: Vec<String> = strata
+        .iter()
+        .take(5)
+        .map(|s| format!("{} {}/{}", s.value, s.sampled_rows, s.population_rows))
+        .collect();
+    let mut note = format!("; strata: {}", shown.join(", "));
+    let more = strata.len().saturating_sub(shown.len());
+    if more > 0 {
+        note.push_str(&format!(", +{more} more"));
+    }
+    note
+}
+
+// the widest interval, for the one-line note; the rest are in `metric_confidence`
+fn confidence_summary(metrics: &[MetricConfidence]) -> String {
+    let widest = metrics
+        .iter()
+        .filter(|m| !m.exact && m.metric.ends_with("_rate"))
+        .filter_map(|m| Some((m, m.margin?)))
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+    match widest {
+        Some((m, margin)) => format!("widest CI {} {} ±{margin:.1}pp", m.column, m.metric),
+        None if metrics.iter().all(|m| m.exact) => "all metrics exact".into(),
+        None => "null rates exact".into(),
+    }
+}
+
+fn profile_columns_sampled(
+    handle: &FileHandle,
+    rg_indices: Option<&[usize]>,
+    rows: Option<&RowSampler>,
+    histogram_bins: usize,
+    opts: &ScanOptions,
+) -> Result<Vec<ColumnProfileResult>> {
+    crate::profile::full_scan::profile_row_group_subset(
+        handle,
+        None,
+        rg_indices,
+        rows,
+        histogram_bins,
+        None,
+        opts,
+        "sample",
+    )
+}
+
+/// Uniform row-level selection: row `i` of the file is kept when
+/// `hash(seed, i) <= threshold`, so the same seed always picks the same rows
+/// and row groups can be sampled independently and in parallel. Stratified
+/// samplers look the threshold up by the row's stratum instead.
+pub(crate) struct RowSampler {
+    seed: u64,
+    threshold: Option<u64>, // None selects nothing
+    first_rows: Vec<u64>,   // file offset of each row group's first row
+    selected: AtomicU64,
+    strata: Option<Strata>,
+}
+
+struct Strata {
+    column: usize, // root field index
+    thresholds: HashMap<String, u64>,
+    population: HashMap<String, u64>,
+    selected: Mutex<HashMap<String, u64>>,
+}
+
+impl RowSampler {
+    fn new(meta: &ParquetMetaData, seed: u64, threshold: Option<u64>) -> Self {
+        let first_rows = meta
+            .row_groups()
+            .iter()
+            .scan(0u64, |next, rg| {
+                let first = *next;
+                *next += rg.num_rows().max(0) as u64;
+                Some(first)
+            })
+            .collect();
+        Self {
+            seed,
+            threshold,
+            first_rows,
+            selected: AtomicU64::new(0),
+            strata: None,
+        }
+    }
+
+    /// The sampler a `SampleMethod::Rows` config describes.
+    pub(crate) fn for_config(handle: &FileHandle, config: &SampleConfig) -> Result<Self> {
+        let meta = handle.metadata();
+        let seed = config.seed.unwrap_or(0);
+        Ok(match (&config.stratify_by, config.rows) {
+            (Some(column), rows) => {
+                let total_rows = meta.file_metadata().num_rows().max(0) as f64;
+                let target = rows.unwrap_or((config.percentage / 100.0 * total_rows) as u64);
+                Self::stratified(handle, seed, column, target, config.allocation)?
+            }
+            (None, Some(k)) => Self::reservoir(meta, seed, k),
+            (None, None) => Self::bernoulli(meta, seed, config.percentage / 100.0),
+        })
+    }
+
+    /// About `target` rows split over the values of `column`; one pass over
+    /// that column counts each stratum's rows first.
+    fn stratified(
+        handle: &FileHandle,
+        seed: u64,
+        column: &str,
+        target: u64,
+        allocation: StrataAllocation,
+    ) -> Result<Self> {
+        let meta = handle.metadata();
+        let col = handle
+            .arrow_metadata()?
+            .schema()
+            .index_of(column)
+            .map_err(|_| ParquetLensError::Other(format!("--stratify-by: no column {column}")))?;
+        let mut population: HashMap<String, u64> = HashMap::new();
+        for rg in 0..meta.num_row_groups() {
+            for key in stratum_keys(handle, col, rg)? {
+                *population.entry(key).or_default() += 1;
+            }
+            if population.len() > MAX_STRATA {
+                return Err(ParquetLensError::Other(format!(
+                    "--stratify-by {column}: more than {MAX_STRATA} distinct values"
+                )));
+            }
+        }
+        let thresholds = strata_fractions(&population, target, allocation)
+            .into_iter()
+            .map(|(key, fraction)| (key, fraction_threshold(fraction)))
+            .collect();
+        let mut sampler = Self::new(meta, seed, None);
+        sampler.strata = Some(Strata {
+            column: col,
+            thresholds,
+            population,
+            selected: Mutex::new(HashMap::new()),
+        });
+        Ok(sampler)
+    }
+
+    /// Each row independently with probability `fraction`.
+    fn bernoulli(meta: &ParquetMetaData, seed: u64, fraction: f64) -> Self {
+        Self::new(meta, seed, Some(fraction_threshold(fraction)))
+    }
+
+    /// Exactly `k` rows (or all of them): the threshold is the k-th smallest
+    /// row hash, found in one pass over row indices without reading any data.
+    fn reservoir(meta: &ParquetMetaData, seed: u64, k: u64) -> Self {
+        let total = meta.file_metadata().num_rows().max(0) as u64;
+        if k == 0 {
+            return Self::new(meta, seed, None);
+        }
+        if k >= total {
+            return Self::new(meta, seed, Some(u64::MAX));
+        }
+        let mut heap = BinaryHeap::with_capacity(k as usize);
+        for row in 0..total {
+            let key = row_key(seed, row);
+            if (heap.len() as u64) < k {
+                heap.push(key);
+            } else if heap.peek().is_some_and(|&max| key < max) {
+                heap.pop();
+                heap.push(key);
+            }
+        }
+        Self::new(meta, seed, heap.peek().copied())
+    }
+
+    /// Rows of row group `rg` to read; also counted towards `selected_rows`.
+    pub(crate) fn selection(&self, handle: &FileHandle, rg: usize) -> Result<RowSelection> {
+        let rows = handle.metadata().row_group(rg).num_rows().max(0) as usize;
+        let first = self.first_rows.get(rg).copied().unwrap_or_default();
+        let keys = match &self.strata {
+            Some(s) => Some(stratum_keys(handle, s.column, rg)?),
+            None => None,
+        };
+        let mut selectors: Vec<RowSelector> = Vec::new();
+        let mut picked = 0u64;
+        let mut picked_by_stratum: HashMap<&str, u64> = HashMap::new();
+        for i in 0..rows {
+            let threshold = match (&self.strata, &keys) {
+                (Some(s), Some(keys)) => s.thresholds.get(&keys[i]).copied(),
+                _ => self.threshold,
             };
-            let string = str_accs[i].take().map(|acc| acc.finish());
-            let temporal = temporal_accs[i].take().map(|acc| acc.finish());
-            let boolean = bool_accs[i].take().map(|acc| acc.finish());
-            CPR {
-                column_name: name,
-                cardinality,
-                frequency,
-                numeric,
-                histogram,
-                string,
-                temporal,
-                boolean,
-                truncated: false,
+            let keep = threshold.is_some_and(|t| row_key(self.seed, first + i as u64) <= t);
+            picked += keep as u64;
+            if let (true, Some(keys)) = (keep, &keys) {
+                *picked_by_stratum.entry(keys[i].as_str()).or_default() += 1;
+            }
+            match selectors.last_mut() {
+                Some(last) if last.skip != keep => last.row_count += 1,
+                _ => selectors.push(if keep {
+                    RowSelector::select(1)
+                } else {
+                    RowSelector::skip(1)
+                }),
+            }
+        }
+        self.selected.fetch_add(picked, Ordering::Relaxed);
+        if let Some(s) = &self.strata {
+            let mut selected = s.selected.lock().unwrap();
+            for (key, n) in picked_by_stratum {
+                *selected.entry(key.to_string()).or_default() += n;
             }
+        }
+        Ok(RowSelection::from(selectors))
+    }
+
+    pub(crate) fn selected_rows(&self) -> u64 {
+        self.selected.load(Ordering::Relaxed)
+    }
+
+    /// Per-stratum counts, largest stratum first; empty when not stratified.
+    pub(crate) fn strata(&self) -> Vec<StratumSample> {
+        let Some(s) = &self.strata else {
+            return Vec::new();
+        };
+        stratum_samples(&s.population, &s.selected.lock().unwrap())
+    }
+}
+
+/// Share of each stratum's rows to sample so about `target` rows are drawn in
+/// all; every non-empty stratum gets at least one row.
+fn strata_fractions(
+    population: &HashMap<String, u64>,
+    target: u64,
+    allocation: StrataAllocation,
+) -> HashMap<String, f64> {
+    let total: u64 = population.values().sum();
+    let per_stratum = target.div_ceil(population.len().max(1) as u64);
+    population
+        .iter()
+        .map(|(key, &rows)| {
+            let rows = rows.max(1) as f64;
+            let fraction = match allocation {
+                StrataAllocation::Proportional => {
+                    (target as f64 / total.max(1) as f64).max(1.0 / rows)
+                }
+                StrataAllocation::Equal => per_stratum as f64 / rows,
+            };
+            (key.clone(), fraction)
+        })
+        .collect()
+}
+
+// largest stratum first
+fn stratum_samples(
+    population: &HashMap<String, u64>,
+    selected: &HashMap<String, u64>,
+) -> Vec<StratumSample> {
+    let mut out: Vec<StratumSample> = population
+        .iter()
+        .map(|(value, &population_rows)| StratumSample {
+            value: value.clone(),
+            population_rows,
+            sampled_rows: selected.get(value).copied().unwrap_or(0),
         })
         .collect();
-    Ok(results)
+    out.sort_by(|a, b| {
+        b.population_rows
+            .cmp(&a.population_rows)
+            .then_with(|| a.value.cmp(&b.value))
+    });
+    out
 }
 
-fn array_val_str(array: &dyn arrow::array::Array, row: usize) -> String {
-    use arrow::array::*;
-    use arrow::datatypes::DataType;
-    match array.data_type() {
-        DataType::Int8 => array
-            .as_any()
-            .downcast_ref::<Int8Array>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::Int16 => array
-            .as_any()
-            .downcast_ref::<Int16Array>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::Int32 => array
-            .as_any()
-            .downcast_ref::<Int32Array>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::Int64 => array
-            .as_any()
-            .downcast_ref::<Int64Array>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::UInt8 => array
-            .as_any()
-            .downcast_ref::<UInt8Array>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::UInt16 => array
-            .as_any()
-            .downcast_ref::<UInt16Array>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::UInt32 => array
-            .as_any()
-            .downcast_ref::<UInt32Array>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::UInt64 => array
-            .as_any()
-            .downcast_ref::<UInt64Array>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::Float32 => array
-            .as_any()
-            .downcast_ref::<Float32Array>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::Float64 => array
-            .as_any()
-            .downcast_ref::<Float64Array>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::Utf8 => array
-            .as_any()
-            .downcast_ref::<StringArray>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::LargeUtf8 => array
-            .as_any()
-            .downcast_ref::<LargeStringArray>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        DataType::Boolean => array
-            .as_any()
-            .downcast_ref::<BooleanArray>()
-            .map(|a| a.value(row).to_string())
-            .unwrap_or_default(),
-        _ => format!("row_{row}"),
+fn fraction_threshold(fraction: f64) -> u64 {
+    if fraction >= 1.0 {
+        u64::MAX
+    } else {
+        (fraction.max(0.0) * u64::MAX as f64) as u64
+    }
+}
+
+// the stratify column's values in `rg`, rendered as strings
+fn stratum_keys(handle: &FileHandle, column: usize, rg: usize) -> Result<Vec<String>> {
+    let location = || format!("{} row group {rg}", handle.path().display());
+    let schema = handle.metadata().file_metadata().schema_descr();
+    let reader = handle
+        .reader_builder()?
+        .with_projection(ProjectionMask::roots(schema, [column]))
+        .with_row_groups(vec![rg])
+        .build()
+        .map_err(|e| ParquetLensError::corrupt(location(), e))?;
+    let mut keys = Vec::with_capacity(handle.metadata().row_group(rg).num_rows().max(0) as usize);
+    for batch in reader {
+        let batch = batch.map_err(|e| ParquetLensError::corrupt(location(), e))?;
+        let values = cast(batch.column(0), &DataType::Utf8).map_err(|e| {
+            ParquetLensError::Other(format!("--stratify-by: can't group by this column: {e}"))
+        })?;
+        let values = values.as_string::<i32>();
+        keys.extend((0..values.len()).map(|i| {
+            if values.is_null(i) {
+                NULL_STRATUM.to_string()
+            } else {
+                values.value(i).to_string()
+            }
+        }));
+    }
+    Ok(keys)
+}
+
+fn row_key(seed: u64, row: u64) -> u64 {
+    xxh3_64_with_seed(&row.to_le_bytes(), seed)
+}
+
+#[cfg(test)]
+mod tests_metric_confidence {
+    use super::*;
+
+    fn stats(name: &str, null_percentage: f64) -> AggregatedColumnStats {
+        AggregatedColumnStats {
+            column_name: name.into(),
+            total_null_count: 0,
+            null_percentage,
+            total_distinct_count_estimate: None,
+            total_data_page_size: 0,
+            total_compressed_size: 0,
+            compression_ratio: 1.0,
+            min_bytes: None,
+            max_bytes: None,
+        }
+    }
+
+    #[test]
+    fn null_rate_margin_shrinks_with_sample_size() {
+        let agg = [stats("a", 20.0), stats("b", 5.0)];
+        let small = metric_confidence(&agg, &[], 100, 1_000_000, |_| false);
+        let large = metric_confidence(&agg, &[], 10_000, 1_000_000, |_| false);
+        assert_eq!(small[0].column, "a");
+        // 1.96 * sqrt(0.2 * 0.8 / 100) ≈ 7.8 percentage points
+        assert!((small[0].margin.unwrap() - 7.84).abs() < 0.05);
+        assert!(large[0].margin.unwrap() < small[0].margin.unwrap() / 9.0);
+        assert_eq!(
+            confidence_summary(&small),
+            format!("widest CI a null_rate ±{:.1}pp", small[0].margin.unwrap())
+        );
+    }
+
+    #[test]
+    fn footer_and_complete_samples_are_exact() {
+        let agg = [stats("a", 20.0), stats("b", 5.0)];
+        let footer = metric_confidence(&agg, &[], 100, 1000, |c| c == "a");
+        assert!(footer[0].exact && footer[0].margin == Some(0.0));
+        assert!(!footer[1].exact);
+        let everything = metric_confidence(&agg, &[], 1000, 1000, |_| false);
+        assert!(everything.iter().all(|m| m.exact));
+        assert_eq!(confidence_summary(&everything), "all metrics exact");
     }
 }
@@ -0,0 +1,183 @@
+use crate::handle::{FileHandle, HandleRegistry};
+use crate::mask::{MaskRule, MaskSpec};
+use crate::options::ScanOptions;
+use crate::progress::row_group_totals;
+use crate::sample::{
+    auto_plan, select_row_groups, RowSampler, SampleConfig, SampleMethod, StrataAllocation,
+    StratumSample,
+};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use parquet_lens_common::{ParquetLensError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Everything needed to draw the same sample again, written next to the
+/// exported rows as `<output>.manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleManifest {
+    pub source: String,
+    pub source_size: u64,
+    pub source_rows: u64,
+    pub source_row_groups: usize,
+    pub method: SampleMethod,
+    pub percentage: Option<f64>, // unset when `rows` fixed the sample size
+    pub rows: Option<u64>,
+    pub seed: u64,
+    pub stratify_by: Option<String>,
+    pub allocation: Option<StrataAllocation>,
+    pub row_groups: Vec<usize>, // row groups read; every one for row-level samples
+    pub sampled_rows: u64,
+    pub strata: Vec<StratumSample>,
+    #[serde(default)]
+    pub masked: Vec<MaskRule>, // columns redacted in the exported rows
+    pub output: String,
+    pub created_at: u64, // unix timestamp secs
+    pub tool_version: String,
+}
+
+impl SampleManifest {
+    /// The sampling parameters, for replaying the sample against the source.
+    pub fn config(&self) -> SampleConfig {
+        SampleConfig {
+            percentage: self.percentage.unwrap_or(100.0),
+            no_extrapolation: false,
+            seed: Some(self.seed),
+            method: self.method,
+            rows: self.rows,
+            stratify_by: self.stratify_by.clone(),
+            allocation: self.allocation.unwrap_or_default(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        serde_json::from_slice(&data).map_err(|e| {
+            ParquetLensError::Other(format!("{}: not a sample manifest: {e}", path.display()))
+        })
+    }
+}
+
+/// `out.parquet` -> `out.parquet.manifest.json`
+pub fn sample_manifest_path(output: &Path) -> PathBuf {
+    let mut name = output.as_os_str().to_owned();
+    name.push(".manifest.json");
+    PathBuf::from(name)
+}
+
+/// Write the rows `config` selects from `path` to a Parquet file at `output`
+/// (same schema apart from `mask`ed columns, snappy-compressed) plus its
+/// manifest. The seed defaults to 0, so the same config always exports the
+/// same rows.
+pub fn export_sample(
+    path: &Path,
+    config: &SampleConfig,
+    output: &Path,
+    mask: &MaskSpec,
+    opts: &ScanOptions,
+) -> Result<SampleManifest> {
+    export_sample_with_handle(
+        &HandleRegistry::global().get(path)?,
+        config,
+        output,
+        mask,
+        opts,
+    )
+}
+
+#[tracing::instrument(name = "sample_export", skip_all, fields(path = %handle.path().display()))]
+pub fn export_sample_with_handle(
+    handle: &FileHandle,
+    config: &SampleConfig,
+    output: &Path,
+    mask: &MaskSpec,
+    opts: &ScanOptions,
+) -> Result<SampleManifest> {
+    let meta = handle.metadata();
+    let total = meta.num_row_groups();
+    let seed = config.seed.unwrap_or(0);
+    let (row_groups, sampler) = match config.method {
+        SampleMethod::RowGroups if total > 0 => {
+            let mut rgs = select_row_groups(total, config.percentage, seed);
+            rgs.sort_unstable();
+            (rgs, None)
+        }
+        SampleMethod::RowGroups => (Vec::new(), None),
+        SampleMethod::Rows => (
+            (0..total).collect(),
+            Some(RowSampler::for_config(handle, config)?),
+        ),
+        SampleMethod::Auto if total > 0 => {
+            let (rgs, sampler) = auto_plan(meta, config);
+            (rgs, Some(sampler))
+        }
+        SampleMethod::Auto => (Vec::new(), None),
+    };
+
+    let schema = handle.arrow_metadata()?.schema().clone();
+    mask.check(&schema)?;
+    let mut mask = mask.clone();
+    mask.fit_with_handle(handle, opts)?;
+    // the written schema is whatever masking turns the source schema into
+    let schema = mask.apply(&RecordBatch::new_empty(schema))?.schema();
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let file = std::fs::File::create(output)?;
+    let mut writer = ArrowWriter::try_new(file, schema, Some(props))?;
+    let progress = opts.start_progress("sample_export", row_group_totals(meta, &row_groups));
+    let mut sampled_rows = 0u64;
+    for &rg in &row_groups {
+        let location = || format!("{} row group {rg}", handle.path().display());
+        let mut builder = handle
+            .reader_builder()?
+            .with_row_groups(vec![rg])
+            .with_batch_size(opts.batch_size);
+        if let Some(sampler) = &sampler {
+            builder = builder.with_row_selection(sampler.selection(handle, rg)?);
+        }
+        let reader = builder
+            .build()
+            .map_err(|e| ParquetLensError::corrupt(location(), e))?;
+        for batch in reader {
+            let batch = batch.map_err(|e| ParquetLensError::corrupt(location(), e))?;
+            sampled_rows += batch.num_rows() as u64;
+            writer.write(&mask.apply(&batch)?)?;
+        }
+        let rg_meta = meta.row_group(rg);
+        progress.advance(
+            rg_meta.num_rows().max(0) as u64,
+            rg_meta.compressed_size().max(0) as u64,
+        );
+    }
+    writer.close()?;
+
+    let manifest = SampleManifest {
+        source: handle.path().display().to_string(),
+        source_size: handle.file_size(),
+        source_rows: meta.file_metadata().num_rows().max(0) as u64,
+        source_row_groups: total,
+        method: config.method,
+        percentage: config.rows.is_none().then_some(config.percentage),
+        rows: config.rows,
+        seed,
+        stratify_by: config.stratify_by.clone(),
+        allocation: config.stratify_by.as_ref().map(|_| config.allocation),
+        row_groups,
+        sampled_rows,
+        strata: sampler.map(|s| s.strata()).unwrap_or_default(),
+        masked: mask.rules.clone(),
+        output: output.display().to_string(),
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    let json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| ParquetLensError::Other(e.to_string()))?;
+    std::fs::write(sample_manifest_path(output), json)?;
+    Ok(manifest)
+}
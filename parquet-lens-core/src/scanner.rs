@@ -48,33 +48,43 @@ fn scan_recursive(base: &Path, dir: &Path, out: &mut Vec<ParquetFilePath>) -> Re
     Ok(())
 }
 
-/// resolve a path string: single file, directory, glob pattern, or S3/GCS URI (async)
+/// resolve a path string: single file, directory, glob pattern, Iceberg table
+/// (its current snapshot's data files), or a URI listed by the storage backend
+/// registered for its scheme (S3, GCS, user-provided)
+#[tracing::instrument]
 pub async fn resolve_paths(input: &str) -> Result<Vec<ParquetFilePath>> {
-    use crate::gcs_reader::{is_gcs_uri, list_gcs_parquet};
-    use crate::s3_reader::{is_s3_uri, list_s3_parquet};
-    // S3 URI detection
-    if is_s3_uri(input) {
-        let keys = list_s3_parquet(input).await?;
-        return Ok(keys
-            .into_iter()
-            .map(|k| ParquetFilePath {
-                path: PathBuf::from(k),
-                partitions: HashMap::new(),
-            })
-            .collect());
+    #[cfg(feature = "iceberg")]
+    if let Some(table) = crate::iceberg::open_iceberg_table(input).await? {
+        return Ok(table.data_files);
     }
-    // GCS URI detection
-    if is_gcs_uri(input) {
-        let keys = list_gcs_parquet(input).await?;
-        return Ok(keys
-            .into_iter()
-            .map(|k| ParquetFilePath {
-                path: PathBuf::from(k),
-                partitions: HashMap::new(),
-            })
-            .collect());
+    if !is_remote_uri(input) {
+        return resolve_local(input);
     }
-    // local path resolution (sync ops are fine in async context)
+    let backend = crate::storage::StorageRegistry::global().for_uri(input)?;
+    let mut keys = backend.list(input).await?;
+    if let Some(max) = crate::storage::list_options().max_files {
+        if keys.len() >= max {
+            tracing::warn!("{input}: listing stopped at {max} Parquet files");
+            keys.truncate(max);
+        }
+    }
+    Ok(keys
+        .into_iter()
+        .map(|k| ParquetFilePath {
+            path: PathBuf::from(k),
+            partitions: HashMap::new(),
+        })
+        .collect())
+}
+
+/// true for `scheme://` inputs other than `file://`
+pub(crate) fn is_remote_uri(input: &str) -> bool {
+    input.contains("://") && !input.starts_with("file://")
+}
+
+/// local file, directory, or glob pattern
+pub(crate) fn resolve_local(input: &str) -> Result<Vec<ParquetFilePath>> {
+    let input = input.strip_prefix("file://").unwrap_or(input);
     let path = Path::new(input);
     if path.is_file() {
         return Ok(vec![ParquetFilePath {
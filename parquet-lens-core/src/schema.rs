@@ -1,7 +1,8 @@
-use bytes::Bytes;
-use memmap2::Mmap;
-use parquet::file::reader::{FileReader, SerializedFileReader};
-use parquet_lens_common::{ParquetLensError, Result};
+use crate::handle::{FileHandle, HandleRegistry};
+use parquet::basic::{ConvertedType, LogicalType};
+use parquet::file::metadata::ParquetMetaData;
+use parquet::schema::types::Type;
+use parquet_lens_common::Result;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -16,13 +17,16 @@ pub struct ColumnSchema {
 }
 
 pub fn extract_schema(path: &Path) -> Result<Vec<ColumnSchema>> {
-    let file = std::fs::File::open(path)?;
-    let mmap: Mmap = unsafe { Mmap::map(&file)? };
-    let bytes = Bytes::copy_from_slice(&mmap);
-    let reader = SerializedFileReader::new(bytes).map_err(ParquetLensError::Parquet)?;
-    let meta = reader.metadata();
+    extract_schema_with_handle(&HandleRegistry::global().get(path)?)
+}
+
+pub fn extract_schema_with_handle(handle: &FileHandle) -> Result<Vec<ColumnSchema>> {
+    Ok(schema_from_metadata(handle.metadata()))
+}
+
+pub fn schema_from_metadata(meta: &ParquetMetaData) -> Vec<ColumnSchema> {
     let schema = meta.file_metadata().schema_descr();
-    let columns = (0..schema.num_columns())
+    (0..schema.num_columns())
         .map(|i| {
             let col = schema.column(i);
             let basic = col.self_type().get_basic_info();
@@ -39,6 +43,101 @@ pub fn extract_schema(path: &Path) -> Result<Vec<ColumnSchema>> {
                 max_rep_level: col.max_rep_level(),
             }
         })
-        .collect();
-    Ok(columns)
+        .collect()
+}
+
+/// A schema element as the file declares it, groups included, so nesting
+/// (structs, lists, maps) survives instead of being flattened to leaf columns.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchemaTreeNode {
+    pub name: String,
+    pub physical_type: Option<String>, // None for groups
+    pub annotation: Option<String>,    // logical type; list, map or struct for groups
+    pub repetition: Option<String>,    // None for the root
+    pub children: Vec<SchemaTreeNode>,
+}
+
+impl SchemaTreeNode {
+    /// `tags: group (list) OPTIONAL`, `element: BYTE_ARRAY (String) REQUIRED`
+    pub fn label(&self) -> String {
+        let mut label = match &self.physical_type {
+            Some(t) => format!("{}: {t}", self.name),
+            None if self.repetition.is_none() => self.name.clone(),
+            None => format!("{}: group", self.name),
+        };
+        if let Some(a) = &self.annotation {
+            label.push_str(&format!(" ({a})"));
+        }
+        if let Some(r) = &self.repetition {
+            label.push(' ');
+            label.push_str(r);
+        }
+        label
+    }
+
+    /// One box-drawn line per node, root first.
+    pub fn render(&self) -> Vec<String> {
+        let mut lines = vec![self.label()];
+        self.render_children("", &mut lines);
+        lines
+    }
+
+    fn render_children(&self, prefix: &str, lines: &mut Vec<String>) {
+        for (i, child) in self.children.iter().enumerate() {
+            let last = i + 1 == self.children.len();
+            let (branch, indent) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            lines.push(format!("{prefix}{branch}{}", child.label()));
+            child.render_children(&format!("{prefix}{indent}"), lines);
+        }
+    }
+}
+
+pub fn schema_tree(meta: &ParquetMetaData) -> SchemaTreeNode {
+    tree_node(meta.file_metadata().schema_descr().root_schema(), true)
+}
+
+fn tree_node(ty: &Type, root: bool) -> SchemaTreeNode {
+    let info = ty.get_basic_info();
+    let logical = info.logical_type().map(|lt| format!("{lt:?}"));
+    let converted = (info.converted_type() != ConvertedType::NONE)
+        .then(|| format!("{:?}", info.converted_type()));
+    let repetition = (!root).then(|| {
+        if info.has_repetition() {
+            format!("{:?}", info.repetition())
+        } else {
+            "REQUIRED".into()
+        }
+    });
+    if ty.is_group() {
+        let annotation = match (info.logical_type(), info.converted_type()) {
+            (Some(LogicalType::List), _) | (_, ConvertedType::LIST) => Some("list"),
+            (Some(LogicalType::Map), _)
+            | (_, ConvertedType::MAP | ConvertedType::MAP_KEY_VALUE) => Some("map"),
+            _ if root => None,
+            _ => Some("struct"),
+        };
+        SchemaTreeNode {
+            name: ty.name().to_owned(),
+            physical_type: None,
+            annotation: annotation.map(str::to_owned),
+            repetition,
+            children: ty
+                .get_fields()
+                .iter()
+                .map(|f| tree_node(f, false))
+                .collect(),
+        }
+    } else {
+        SchemaTreeNode {
+            name: ty.name().to_owned(),
+            physical_type: Some(format!("{:?}", ty.get_physical_type())),
+            annotation: logical.or(converted),
+            repetition,
+            children: Vec::new(),
+        }
+    }
 }
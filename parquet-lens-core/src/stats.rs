@@ -322,3 +322,26 @@ pub fn analyze_compression(meta: &ParquetMetaData) -> Vec<CompressionAnalysis> {
         })
         .collect()
 }
+
+/// The footer-derived stats of one file, as `ProfileCache::file_stats` stores
+/// them so a cache hit needs no footer read.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStats {
+    pub info: crate::reader::ParquetFileInfo,
+    pub column_stats: Vec<ColumnStats>,
+    pub row_groups: Vec<RowGroupProfile>,
+    pub encodings: Vec<EncodingAnalysis>,
+    pub compression: Vec<CompressionAnalysis>,
+}
+
+impl FileStats {
+    pub fn from_metadata(info: crate::reader::ParquetFileInfo, meta: &ParquetMetaData) -> Self {
+        Self {
+            info,
+            column_stats: read_column_stats(meta),
+            row_groups: profile_row_groups(meta),
+            encodings: analyze_encodings(meta),
+            compression: analyze_compression(meta),
+        }
+    }
+}
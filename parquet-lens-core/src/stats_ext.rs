@@ -84,12 +84,11 @@ pub struct CorrelationMatrix {
 pub fn compute_correlation(_meta: &ParquetMetaData, path: &Path) -> Result<CorrelationMatrix> {
     use arrow::array::*;
     use arrow::datatypes::DataType;
-    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
     use parquet_lens_common::ParquetLensError;
 
-    let file = std::fs::File::open(path)?;
-    let builder =
-        ParquetRecordBatchReaderBuilder::try_new(file).map_err(ParquetLensError::Parquet)?;
+    let builder = crate::handle::HandleRegistry::global()
+        .get(path)?
+        .reader_builder()?;
     let schema = builder.schema().clone();
     let numeric_cols: Vec<usize> = schema
         .fields()
@@ -214,11 +213,10 @@ pub struct StringLengthHist {
 pub fn string_length_histogram(path: &Path, column: &str, bins: usize) -> Result<StringLengthHist> {
     use arrow::array::{LargeStringArray, StringArray};
     use arrow::datatypes::DataType;
-    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
     use parquet_lens_common::ParquetLensError;
-    let file = std::fs::File::open(path)?;
-    let builder =
-        ParquetRecordBatchReaderBuilder::try_new(file).map_err(ParquetLensError::Parquet)?;
+    let builder = crate::handle::HandleRegistry::global()
+        .get(path)?
+        .reader_builder()?;
     let reader = builder
         .with_batch_size(65536)
         .build()
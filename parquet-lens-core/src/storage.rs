@@ -0,0 +1,465 @@
+use crate::progress::ProgressSink;
+use crate::reader::{open_parquet_file, ParquetFileInfo};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use parquet::file::metadata::{ParquetMetaData, ParquetMetaDataReader};
+use parquet_lens_common::{ParquetLensError, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+
+#[cfg(any(feature = "s3", feature = "gcs", feature = "azure"))]
+use crate::remote::{remote_config, ObjectStoreBackend, ObjectStoreReader};
+
+/// Size, modification time and version tag of one stored object.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectInfo {
+    pub uri: String,
+    pub size: u64,
+    pub last_modified: Option<String>, // backend-specific rendering (RFC 3339 or unix seconds)
+    #[serde(default)]
+    pub etag: Option<String>, // None for local files
+}
+
+/// How remote listings walk large prefixes, set once with `set_list_options`.
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    pub max_files: Option<usize>, // stop once this many Parquet objects are found
+    pub concurrency: usize,       // subdirectories listed at once; 0 means 16
+    pub progress: Option<Arc<dyn ProgressSink>>, // operation "list"; `rows` counts objects seen
+}
+
+#[cfg(feature = "tokio")]
+impl ListOptions {
+    pub(crate) fn concurrency(&self) -> usize {
+        if self.concurrency == 0 {
+            16
+        } else {
+            self.concurrency
+        }
+    }
+
+    pub(crate) fn is_full(&self, found: usize) -> bool {
+        self.max_files.is_some_and(|max| found >= max)
+    }
+}
+
+static LIST_OPTIONS: RwLock<Option<ListOptions>> = RwLock::new(None);
+
+/// Listing limits and progress every remote listing uses from now on.
+pub fn set_list_options(options: ListOptions) {
+    *LIST_OPTIONS.write().unwrap_or_else(|e| e.into_inner()) = Some(options);
+}
+
+pub(crate) fn list_options() -> ListOptions {
+    LIST_OPTIONS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// File access for one family of URIs. The built-in backends cover local paths,
+/// `s3://`, `gs://`, Azure, `http(s)://` and HDFS; implement this (or, for stores the
+/// `object_store` crate reaches, `ObjectStoreBackend`) for other stores and add it
+/// with `StorageRegistry::register` so `resolve_paths` and `open_parquet_auto` reach it.
+pub trait StorageBackend: Send + Sync {
+    /// Short name for logs and errors, e.g. `"s3"`.
+    fn name(&self) -> &str;
+    /// Whether this backend serves `uri`.
+    fn handles(&self, uri: &str) -> bool;
+    /// Parquet objects under `prefix` (an object, directory, or key prefix).
+    fn list<'a>(&'a self, prefix: &'a str) -> BoxFuture<'a, Result<Vec<String>>>;
+    fn metadata<'a>(&'a self, uri: &'a str) -> BoxFuture<'a, Result<ObjectInfo>>;
+    /// Bytes `range.start..range.end` of the object.
+    fn read_range<'a>(&'a self, uri: &'a str, range: Range<u64>) -> BoxFuture<'a, Result<Bytes>>;
+    /// Parses the footer. The default reads it with two range requests.
+    fn open<'a>(
+        &'a self,
+        uri: &'a str,
+    ) -> BoxFuture<'a, Result<(ParquetFileInfo, Arc<ParquetMetaData>)>> {
+        Box::pin(read_footer(self, uri))
+    }
+}
+
+impl std::fmt::Debug for dyn StorageBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StorageBackend({})", self.name())
+    }
+}
+
+/// Reads the 8-byte trailer, then the footer it points at.
+pub async fn read_footer<B: StorageBackend + ?Sized>(
+    backend: &B,
+    uri: &str,
+) -> Result<(ParquetFileInfo, Arc<ParquetMetaData>)> {
+    let size = backend.metadata(uri).await?.size;
+    let tail = backend
+        .read_range(uri, size.saturating_sub(8)..size)
+        .await?;
+    let range = footer_range(uri, size, &tail)?;
+    let footer = backend.read_range(uri, range).await?;
+    decode_footer(uri, size, &footer)
+}
+
+// bytes per range request when copying a whole object
+const DOWNLOAD_CHUNK: u64 = 8 << 20;
+
+/// Copies the whole object into `out` one range request at a time, so only a
+/// chunk is ever held in memory. Returns the number of bytes written.
+pub async fn download_object<B: StorageBackend + ?Sized, W: std::io::Write + ?Sized>(
+    backend: &B,
+    uri: &str,
+    out: &mut W,
+) -> Result<u64> {
+    let size = backend.metadata(uri).await?.size;
+    let mut offset = 0;
+    while offset < size {
+        let end = (offset + DOWNLOAD_CHUNK).min(size);
+        let chunk = backend.read_range(uri, offset..end).await?;
+        if chunk.is_empty() {
+            return Err(ParquetLensError::Other(format!(
+                "{uri}: object ended at byte {offset} of {size}"
+            )));
+        }
+        out.write_all(&chunk)
+            .map_err(|e| ParquetLensError::Io(e).context(format!("downloading {uri}")))?;
+        offset += chunk.len() as u64;
+    }
+    out.flush()?;
+    Ok(size)
+}
+
+/// Where the footer of a `size`-byte file lies, from the file's last bytes
+/// (at least the 8-byte trailer).
+pub(crate) fn footer_range(uri: &str, size: u64, tail: &[u8]) -> Result<Range<u64>> {
+    if size < 12 || tail.len() < 8 {
+        return Err(ParquetLensError::corrupt(
+            uri,
+            format!("{size} bytes is too small for a Parquet file"),
+        ));
+    }
+    let trailer = &tail[tail.len() - 8..];
+    if &trailer[4..] != b"PAR1" {
+        return Err(ParquetLensError::corrupt(uri, "missing PAR1 trailer"));
+    }
+    let footer_len = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]) as u64;
+    if footer_len + 12 > size {
+        return Err(ParquetLensError::corrupt(
+            uri,
+            format!("footer length {footer_len} exceeds file size {size}"),
+        ));
+    }
+    Ok(size - 8 - footer_len..size - 8)
+}
+
+/// `range` out of `tail`, the last bytes of a `size`-byte file, when it holds all of it.
+#[cfg(feature = "tokio")]
+pub(crate) fn slice_tail(tail: &Bytes, size: u64, range: &Range<u64>) -> Option<Bytes> {
+    let tail_start = size.saturating_sub(tail.len() as u64);
+    (range.start >= tail_start)
+        .then(|| tail.slice((range.start - tail_start) as usize..(range.end - tail_start) as usize))
+}
+
+pub(crate) fn decode_footer(
+    uri: &str,
+    size: u64,
+    footer: &[u8],
+) -> Result<(ParquetFileInfo, Arc<ParquetMetaData>)> {
+    let meta = ParquetMetaDataReader::decode_metadata(footer)
+        .map_err(|e| ParquetLensError::corrupt(format!("{uri} footer"), e))?;
+    Ok((
+        ParquetFileInfo::from_metadata(PathBuf::from(uri), size, &meta),
+        Arc::new(meta),
+    ))
+}
+
+/// Local files, directories and globs; also `file://` URIs.
+#[derive(Debug, Clone, Default)]
+pub struct LocalBackend;
+
+fn local_path(uri: &str) -> &Path {
+    Path::new(uri.strip_prefix("file://").unwrap_or(uri))
+}
+
+impl StorageBackend for LocalBackend {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn handles(&self, uri: &str) -> bool {
+        !crate::scanner::is_remote_uri(uri)
+    }
+
+    fn list<'a>(&'a self, prefix: &'a str) -> BoxFuture<'a, Result<Vec<String>>> {
+        Box::pin(async move {
+            Ok(crate::scanner::resolve_local(prefix)?
+                .into_iter()
+                .map(|p| p.path.to_string_lossy().into_owned())
+                .collect())
+        })
+    }
+
+    fn metadata<'a>(&'a self, uri: &'a str) -> BoxFuture<'a, Result<ObjectInfo>> {
+        Box::pin(async move {
+            let path = local_path(uri);
+            let md = std::fs::metadata(path).map_err(|e| ParquetLensError::io_at(e, path))?;
+            let last_modified = md
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs().to_string());
+            Ok(ObjectInfo {
+                uri: uri.to_string(),
+                size: md.len(),
+                last_modified,
+                etag: None,
+            })
+        })
+    }
+
+    fn read_range<'a>(&'a self, uri: &'a str, range: Range<u64>) -> BoxFuture<'a, Result<Bytes>> {
+        Box::pin(async move {
+            let path = local_path(uri);
+            let mut f = std::fs::File::open(path).map_err(|e| ParquetLensError::io_at(e, path))?;
+            f.seek(SeekFrom::Start(range.start))?;
+            let mut buf = vec![0u8; range.end.saturating_sub(range.start) as usize];
+            f.read_exact(&mut buf)?;
+            Ok(Bytes::from(buf))
+        })
+    }
+
+    // shares the parsed footer with the rest of the crate through the handle registry
+    fn open<'a>(
+        &'a self,
+        uri: &'a str,
+    ) -> BoxFuture<'a, Result<(ParquetFileInfo, Arc<ParquetMetaData>)>> {
+        Box::pin(async move { open_parquet_file(local_path(uri)) })
+    }
+}
+
+/// `s3://bucket/key`; `endpoint` overrides `[s3] endpoint_url`, though not a
+/// `[s3.buckets.<name>]` entry's.
+#[cfg(feature = "s3")]
+#[derive(Debug, Clone, Default)]
+pub struct S3Backend {
+    pub endpoint: Option<String>,
+}
+
+#[cfg(feature = "s3")]
+impl S3Backend {
+    pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: Some(endpoint.into()),
+        }
+    }
+}
+
+#[cfg(feature = "s3")]
+impl ObjectStoreBackend for S3Backend {
+    fn name(&self) -> &str {
+        "s3"
+    }
+
+    fn handles(&self, uri: &str) -> bool {
+        crate::s3_reader::is_s3_uri(uri)
+    }
+
+    fn reader(&self, uri: &str) -> Result<(ObjectStoreReader, object_store::path::Path)> {
+        let mut config = remote_config();
+        if let Some(ep) = &self.endpoint {
+            config.s3.endpoint_url = Some(ep.clone());
+        }
+        ObjectStoreReader::for_uri(uri, &config)
+    }
+}
+
+/// `gs://bucket/object`; `credentials_file` overrides `[gcs] credentials_file`.
+#[cfg(feature = "gcs")]
+#[derive(Debug, Clone, Default)]
+pub struct GcsBackend {
+    pub credentials_file: Option<String>,
+}
+
+#[cfg(feature = "gcs")]
+impl ObjectStoreBackend for GcsBackend {
+    fn name(&self) -> &str {
+        "gcs"
+    }
+
+    fn handles(&self, uri: &str) -> bool {
+        crate::gcs_reader::is_gcs_uri(uri)
+    }
+
+    fn reader(&self, uri: &str) -> Result<(ObjectStoreReader, object_store::path::Path)> {
+        let mut config = remote_config();
+        if let Some(path) = &self.credentials_file {
+            config.gcs.credentials_file = Some(path.clone());
+        }
+        ObjectStoreReader::for_uri(uri, &config)
+    }
+}
+
+/// `az://` and `abfs[s]://` with `[azure]` credentials over the environment.
+#[cfg(feature = "azure")]
+#[derive(Debug, Clone, Default)]
+pub struct AzureBackend;
+
+#[cfg(feature = "azure")]
+impl ObjectStoreBackend for AzureBackend {
+    fn name(&self) -> &str {
+        "azure"
+    }
+
+    fn handles(&self, uri: &str) -> bool {
+        crate::azure_reader::is_azure_uri(uri)
+    }
+
+    fn reader(&self, uri: &str) -> Result<(ObjectStoreReader, object_store::path::Path)> {
+        ObjectStoreReader::for_uri(uri, &remote_config())
+    }
+}
+
+/// `http://` and `https://` URLs on any server; reads the footer with range
+/// requests, or the whole body when the server ignores them.
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, Default)]
+pub struct HttpBackend;
+
+#[cfg(feature = "http")]
+impl StorageBackend for HttpBackend {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    fn handles(&self, uri: &str) -> bool {
+        crate::http_reader::is_http_uri(uri)
+    }
+
+    // plain HTTP has no listing, so a URL names exactly one object
+    fn list<'a>(&'a self, prefix: &'a str) -> BoxFuture<'a, Result<Vec<String>>> {
+        Box::pin(async move { Ok(vec![prefix.to_string()]) })
+    }
+
+    fn metadata<'a>(&'a self, uri: &'a str) -> BoxFuture<'a, Result<ObjectInfo>> {
+        Box::pin(async move {
+            let (size, modified, etag) = crate::http_reader::http_object_metadata(uri).await?;
+            Ok(ObjectInfo {
+                uri: uri.to_string(),
+                size,
+                last_modified: modified,
+                etag,
+            })
+        })
+    }
+
+    fn read_range<'a>(&'a self, uri: &'a str, range: Range<u64>) -> BoxFuture<'a, Result<Bytes>> {
+        Box::pin(crate::http_reader::read_http_range(uri, range))
+    }
+
+    fn open<'a>(
+        &'a self,
+        uri: &'a str,
+    ) -> BoxFuture<'a, Result<(ParquetFileInfo, Arc<ParquetMetaData>)>> {
+        Box::pin(crate::http_reader::open_http_parquet(uri))
+    }
+}
+
+/// `hdfs://`, `webhdfs://` and `swebhdfs://` through the namenode's WebHDFS API.
+#[cfg(feature = "hdfs")]
+#[derive(Debug, Clone, Default)]
+pub struct HdfsBackend;
+
+#[cfg(feature = "hdfs")]
+impl StorageBackend for HdfsBackend {
+    fn name(&self) -> &str {
+        "hdfs"
+    }
+
+    fn handles(&self, uri: &str) -> bool {
+        crate::hdfs_reader::is_hdfs_uri(uri)
+    }
+
+    fn list<'a>(&'a self, prefix: &'a str) -> BoxFuture<'a, Result<Vec<String>>> {
+        Box::pin(crate::hdfs_reader::list_hdfs_parquet(prefix))
+    }
+
+    fn metadata<'a>(&'a self, uri: &'a str) -> BoxFuture<'a, Result<ObjectInfo>> {
+        Box::pin(async move {
+            let (size, modified, etag) = crate::hdfs_reader::hdfs_object_metadata(uri).await?;
+            Ok(ObjectInfo {
+                uri: uri.to_string(),
+                size,
+                last_modified: modified,
+                etag,
+            })
+        })
+    }
+
+    fn read_range<'a>(&'a self, uri: &'a str, range: Range<u64>) -> BoxFuture<'a, Result<Bytes>> {
+        Box::pin(crate::hdfs_reader::read_hdfs_range(uri, range))
+    }
+
+    fn open<'a>(
+        &'a self,
+        uri: &'a str,
+    ) -> BoxFuture<'a, Result<(ParquetFileInfo, Arc<ParquetMetaData>)>> {
+        Box::pin(crate::hdfs_reader::open_hdfs_parquet(uri))
+    }
+}
+
+/// Backends consulted in order by URI. Registered backends take precedence over
+/// the built-ins, so a user backend can also replace how `s3://` is read.
+#[derive(Debug)]
+pub struct StorageRegistry {
+    backends: RwLock<Vec<Arc<dyn StorageBackend>>>,
+}
+
+impl StorageRegistry {
+    /// Registry with the local backend plus S3, GCS, Azure and HTTP when those features are on.
+    pub fn with_builtins() -> Self {
+        let backends: Vec<Arc<dyn StorageBackend>> = vec![
+            #[cfg(feature = "s3")]
+            Arc::new(S3Backend::default()),
+            #[cfg(feature = "gcs")]
+            Arc::new(GcsBackend::default()),
+            #[cfg(feature = "azure")]
+            Arc::new(AzureBackend),
+            #[cfg(feature = "http")]
+            Arc::new(HttpBackend),
+            #[cfg(feature = "hdfs")]
+            Arc::new(HdfsBackend),
+            Arc::new(LocalBackend),
+        ];
+        Self {
+            backends: RwLock::new(backends),
+        }
+    }
+
+    /// Process-wide registry used by `resolve_paths` and `open_parquet_auto`.
+    pub fn global() -> &'static StorageRegistry {
+        static REGISTRY: OnceLock<StorageRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(StorageRegistry::with_builtins)
+    }
+
+    pub fn register(&self, backend: Arc<dyn StorageBackend>) {
+        self.backends
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(0, backend);
+    }
+
+    pub fn for_uri(&self, uri: &str) -> Result<Arc<dyn StorageBackend>> {
+        self.backends
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|b| b.handles(uri))
+            .cloned()
+            .ok_or_else(|| ParquetLensError::Unsupported {
+                feature: format!("{}:// storage", uri.split("://").next().unwrap_or(uri)),
+            })
+    }
+}
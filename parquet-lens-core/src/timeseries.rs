@@ -1,11 +1,20 @@
-use bytes::Bytes;
-use memmap2::Mmap;
-use parquet::file::reader::{FileReader, SerializedFileReader};
+use crate::handle::{FileHandle, HandleRegistry};
+use crate::profile::temporal::int96_to_ms;
+use crate::schema::ColumnSchema;
+use parquet::basic::Type as PhysicalType;
+use parquet::column::reader::get_typed_column_reader;
+use parquet::data_type::Int96Type;
+use parquet::file::metadata::RowGroupMetaData;
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::RowGroupReader;
+use parquet::file::serialized_reader::SerializedRowGroupReader;
 use parquet_lens_common::{ParquetLensError, Result};
 use serde::{Deserialize, Serialize};
+use std::fs::File;
 use std::path::Path;
+use std::sync::Arc;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeSeriesProfile {
     pub column_name: String,
     pub min_timestamp: Option<i64>, // epoch millis
@@ -18,15 +27,37 @@ pub struct TimeSeriesProfile {
     pub has_data: bool, // false when all values are null (no min/max available)
 }
 
+/// Columns worth a time-series profile: timestamp/date/time logical types, plus
+/// INT96 without a logical type (legacy Spark timestamps).
+pub fn detect_timestamp_columns(schema: &[ColumnSchema]) -> Vec<String> {
+    schema
+        .iter()
+        .filter(|c| {
+            let logical_match = c
+                .logical_type
+                .as_deref()
+                .map(|t| t.contains("Timestamp") || t.contains("Date") || t.contains("Time"))
+                .unwrap_or(false);
+            let int96_fallback = c.physical_type == "INT96" && c.logical_type.is_none();
+            logical_match || int96_fallback
+        })
+        .map(|c| c.name.clone())
+        .collect()
+}
+
 pub fn profile_timeseries(
     path: &Path,
     timestamp_columns: &[String],
 ) -> Result<Vec<TimeSeriesProfile>> {
-    let file = std::fs::File::open(path)?;
-    let mmap: Mmap = unsafe { Mmap::map(&file)? };
-    let bytes = Bytes::copy_from_slice(&mmap);
-    let reader = SerializedFileReader::new(bytes).map_err(ParquetLensError::Parquet)?;
-    let meta = reader.metadata();
+    profile_timeseries_with_handle(&HandleRegistry::global().get(path)?, timestamp_columns)
+}
+
+#[tracing::instrument(name = "timeseries", skip_all, fields(path = %handle.path().display()))]
+pub fn profile_timeseries_with_handle(
+    handle: &FileHandle,
+    timestamp_columns: &[String],
+) -> Result<Vec<TimeSeriesProfile>> {
+    let meta = handle.metadata();
     let schema = meta.file_metadata().schema_descr();
     let num_rgs = meta.num_row_groups();
     let mut profiles = Vec::new();
@@ -37,13 +68,36 @@ pub fn profile_timeseries(
         let Some(col_idx) = col_idx else {
             continue;
         };
+        let int96 = schema.column(col_idx).physical_type() == PhysicalType::INT96;
         let mut rg_mins: Vec<i64> = Vec::new();
         let mut rg_maxs: Vec<i64> = Vec::new();
+        let mut file = None;
         for rg_i in 0..num_rgs {
             let rg = meta.row_group(rg_i);
             if col_idx >= rg.num_columns() {
                 continue;
             }
+            // legacy Spark timestamps have no defined sort order, so their
+            // statistics can't be trusted; read the values instead
+            if int96 {
+                let file = match &file {
+                    Some(f) => f,
+                    None => file.insert(Arc::new(
+                        File::open(handle.path())
+                            .map_err(|e| ParquetLensError::io_at(e, handle.path()))?,
+                    )),
+                };
+                if let Some((min_v, max_v)) = int96_range(file, rg, col_idx).map_err(|e| {
+                    ParquetLensError::corrupt(
+                        format!("{} row group {rg_i}", handle.path().display()),
+                        e,
+                    )
+                })? {
+                    rg_mins.push(min_v);
+                    rg_maxs.push(max_v);
+                }
+                continue;
+            }
             let col_meta = rg.column(col_idx);
             if let Some(stats) = col_meta.statistics() {
                 let min_bytes = stats.min_bytes_opt();
@@ -117,3 +171,35 @@ pub fn profile_timeseries(
     }
     Ok(profiles)
 }
+
+/// Min and max epoch millis of INT96 column `col_idx` in row group `rg`, or
+/// None when every value is null.
+fn int96_range(
+    file: &Arc<File>,
+    rg: &RowGroupMetaData,
+    col_idx: usize,
+) -> parquet::errors::Result<Option<(i64, i64)>> {
+    let reader = SerializedRowGroupReader::new(
+        file.clone(),
+        rg,
+        None,
+        Arc::new(ReaderProperties::builder().build()),
+    )?;
+    let mut col = get_typed_column_reader::<Int96Type>(reader.get_column_reader(col_idx)?);
+    let (mut defs, mut reps, mut vals) = (Vec::new(), Vec::new(), Vec::new());
+    let mut range: Option<(i64, i64)> = None;
+    loop {
+        defs.clear();
+        reps.clear();
+        vals.clear();
+        let (records, _, _) =
+            col.read_records(8192, Some(&mut defs), Some(&mut reps), &mut vals)?;
+        if records == 0 {
+            return Ok(range);
+        }
+        for v in &vals {
+            let ms = int96_to_ms(v);
+            range = Some(range.map_or((ms, ms), |(lo, hi)| (lo.min(ms), hi.max(ms))));
+        }
+    }
+}
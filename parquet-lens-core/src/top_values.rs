@@ -0,0 +1,246 @@
+use crate::export::csv_field;
+use crate::handle::{FileHandle, HandleRegistry};
+use crate::mask::MaskSpec;
+use crate::options::ScanOptions;
+use crate::profile::frequency::{FrequencyCounter, FrequencyEntry, MAX_TRACKED_VALUES};
+use crate::profile::full_scan::{
+    count_value, hashable_array, projection, read_row_group, scan_pool,
+};
+use crate::profile::ColumnProfileResult;
+use crate::progress::row_group_totals;
+use arrow::array::Array;
+use arrow::compute::can_cast_types;
+use arrow::datatypes::DataType;
+use parquet_lens_common::{ParquetLensError, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A column's most frequent values, counted exactly over every row. Shares
+/// are of the non-null rows; `distinct` and `values` stay empty for columns
+/// with more than `MAX_TRACKED_VALUES` distinct values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnTopValues {
+    pub column: String,
+    pub non_null_count: u64,
+    pub null_count: u64,
+    pub distinct: Option<u64>, // None when too many distinct values to count
+    pub values: Vec<FrequencyEntry>,
+}
+
+impl ColumnTopValues {
+    /// True when `values` lists every distinct value of the column.
+    pub fn is_complete(&self) -> bool {
+        self.distinct == Some(self.values.len() as u64)
+    }
+}
+
+struct Tally {
+    column: String,
+    counter: FrequencyCounter,
+    nulls: u64,
+}
+
+/// Top `k` values (all counted values when `k` is 0) of `columns` (every
+/// top-level column when None) across `paths`, merged by column name.
+pub fn top_values(
+    paths: &[PathBuf],
+    columns: Option<&[String]>,
+    k: usize,
+    opts: &ScanOptions,
+) -> Result<Vec<ColumnTopValues>> {
+    let mut merged: Vec<Tally> = Vec::new();
+    for path in paths {
+        let handle = HandleRegistry::global().get(path)?;
+        for tally in count_values(&handle, columns, opts)? {
+            match merged.iter_mut().find(|t| t.column == tally.column) {
+                Some(t) => {
+                    t.counter.merge(tally.counter);
+                    t.nulls += tally.nulls;
+                }
+                None => merged.push(tally),
+            }
+        }
+    }
+    if let Some(cols) = columns {
+        if let Some(missing) = cols
+            .iter()
+            .find(|c| !merged.iter().any(|t| &t.column == *c))
+        {
+            return Err(ParquetLensError::NotFound(format!("column {missing}")));
+        }
+    }
+    Ok(merged.into_iter().map(|t| finish(t, k)).collect())
+}
+
+pub fn top_values_with_handle(
+    handle: &FileHandle,
+    columns: Option<&[String]>,
+    k: usize,
+    opts: &ScanOptions,
+) -> Result<Vec<ColumnTopValues>> {
+    top_values(&[handle.path().to_path_buf()], columns, k, opts)
+}
+
+/// Every value of the columns in `profiles` whose HLL estimate is at most
+/// `max_distinct`, counted exactly in a second pass over `paths`. A column
+/// whose exact distinct count turns out larger than `max_distinct` is dropped,
+/// so each returned column `is_complete`.
+pub fn distinct_values(
+    paths: &[PathBuf],
+    profiles: &[ColumnProfileResult],
+    max_distinct: u64,
+    opts: &ScanOptions,
+) -> Result<Vec<ColumnTopValues>> {
+    let Some(first) = paths.first() else {
+        return Ok(Vec::new());
+    };
+    let meta = HandleRegistry::global().get(first)?.arrow_metadata()?;
+    let max_distinct = max_distinct.min(MAX_TRACKED_VALUES as u64);
+    // nested columns have a cardinality but no single value to list
+    let columns: Vec<String> = profiles
+        .iter()
+        .filter(|p| p.cardinality.approximate_distinct <= max_distinct)
+        .filter(|p| {
+            meta.schema()
+                .field_with_name(&p.column_name)
+                .is_ok_and(|f| can_cast_types(f.data_type(), &DataType::Utf8))
+        })
+        .map(|p| p.column_name.clone())
+        .collect();
+    if columns.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut tables = top_values(paths, Some(&columns), 0, opts)?;
+    tables.retain(|t| t.is_complete() && t.distinct.is_some_and(|d| d <= max_distinct));
+    Ok(tables)
+}
+
+/// Rewrites the values of every column `mask` covers before they're written;
+/// see `MaskSpec::apply_to_values`.
+pub fn mask_top_values(columns: &mut [ColumnTopValues], mask: &MaskSpec) {
+    for col in columns {
+        mask.apply_to_values(&col.column, &mut col.values);
+    }
+}
+
+fn finish(tally: Tally, k: usize) -> ColumnTopValues {
+    let distinct = (!tally.counter.overflowed()).then(|| tally.counter.distinct() as u64);
+    let n = if k == 0 { MAX_TRACKED_VALUES } else { k };
+    let result = tally.counter.top_n(n);
+    ColumnTopValues {
+        column: tally.column,
+        non_null_count: result.total_count,
+        null_count: tally.nulls,
+        distinct,
+        values: result.top_values,
+    }
+}
+
+#[tracing::instrument(name = "top_values", skip_all, fields(path = %handle.path().display()))]
+fn count_values(
+    handle: &FileHandle,
+    columns: Option<&[String]>,
+    opts: &ScanOptions,
+) -> Result<Vec<Tally>> {
+    let meta = handle.arrow_metadata()?;
+    // nested columns have no single value to count; skipped unless asked for by name
+    let mut countable = Vec::new();
+    for field in meta.schema().fields() {
+        if columns.is_some_and(|cols| !cols.contains(field.name())) {
+            continue;
+        }
+        if can_cast_types(field.data_type(), &DataType::Utf8) {
+            countable.push(field.name().clone());
+        } else if columns.is_some() {
+            return Err(ParquetLensError::Unsupported {
+                feature: format!(
+                    "top values of {} column {}",
+                    field.data_type(),
+                    field.name()
+                ),
+            });
+        }
+    }
+    let (mask, fields) = projection(&meta, Some(&countable));
+    let rgs: Vec<usize> = (0..meta.metadata().num_row_groups()).collect();
+    let progress = opts.start_progress("top_values", row_group_totals(meta.metadata(), &rgs));
+    let empty = || -> Vec<Tally> {
+        fields
+            .iter()
+            .map(|f| Tally {
+                column: f.name().clone(),
+                counter: FrequencyCounter::new(),
+                nulls: 0,
+            })
+            .collect()
+    };
+    let pool = scan_pool(opts.threads)?;
+    pool.install(|| {
+        rgs.par_iter()
+            .map(|&rg| -> Result<Vec<Tally>> {
+                let mut tallies = empty();
+                let mut gauge = opts.gauge();
+                let reader =
+                    read_row_group(handle, &meta, mask.clone(), rg, None, opts.batch_size)?;
+                for batch in reader {
+                    let batch = batch.map_err(|e| {
+                        ParquetLensError::corrupt(
+                            format!("{} row group {rg}", handle.path().display()),
+                            e,
+                        )
+                    })?;
+                    for (tally, array) in tallies.iter_mut().zip(batch.columns()) {
+                        tally.nulls += array.null_count() as u64;
+                        let array = hashable_array(array)?;
+                        for row in (0..array.len()).filter(|&r| array.is_valid(r)) {
+                            count_value(array.as_ref(), row, &mut tally.counter);
+                        }
+                    }
+                    gauge.set(
+                        batch.get_array_memory_size()
+                            + tallies
+                                .iter()
+                                .map(|t| t.counter.approx_bytes())
+                                .sum::<usize>(),
+                    );
+                    progress.advance_rows(batch.num_rows() as u64);
+                }
+                Ok(tallies)
+            })
+            .try_reduce(empty, |mut a, b| {
+                for (x, y) in a.iter_mut().zip(b) {
+                    x.counter.merge(y.counter);
+                    x.nulls += y.nulls;
+                }
+                Ok(a)
+            })
+    })
+}
+
+/// One `column,value,count,pct` row per listed value; pct is of non-null rows.
+pub fn write_top_values_csv(output_path: &Path, columns: &[ColumnTopValues]) -> Result<()> {
+    let mut file = std::fs::File::create(output_path)?;
+    writeln!(file, "column,value,count,pct")?;
+    for col in columns {
+        for entry in &col.values {
+            writeln!(
+                file,
+                "{},{},{},{:.4}",
+                csv_field(&col.column),
+                csv_field(&entry.value),
+                entry.count,
+                entry.percentage
+            )?;
+        }
+    }
+    Ok(())
+}
+
+pub fn write_top_values_json(output_path: &Path, columns: &[ColumnTopValues]) -> Result<()> {
+    let mut file = std::fs::File::create(output_path)?;
+    serde_json::to_writer_pretty(&mut file, columns)
+        .map_err(|e| ParquetLensError::Other(e.to_string()))?;
+    Ok(())
+}
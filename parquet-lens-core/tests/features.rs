@@ -0,0 +1,86 @@
+//! Checks that each cargo feature combination exposes the expected API. Run with
+//! `cargo test -p parquet-lens-core --no-default-features --features minimal`
+//! as well as the default features.
+use parquet_lens_core::{
+    aggregate_column_stats, open_parquet_file, profile_columns, read_column_stats, score_columns,
+    ParquetFileInfo, Profiler, StorageRegistry,
+};
+
+// the local analysis surface must compile in every feature set
+#[allow(dead_code)]
+fn local_api(path: &std::path::Path) -> parquet_lens_core::Result<()> {
+    let (info, meta): (ParquetFileInfo, _) = open_parquet_file(path)?;
+    let stats = aggregate_column_stats(&read_column_stats(&meta), info.row_count);
+    let _ = score_columns(&stats, &[], info.row_count);
+    let _ = profile_columns(path, None, 1024, 10)?;
+    let _ = Profiler::builder()
+        .path(path.to_string_lossy())
+        .run_blocking()?;
+    Ok(())
+}
+
+#[cfg(feature = "s3")]
+#[test]
+fn s3_feature_registers_backend() {
+    let _ = parquet_lens_core::S3Backend::default();
+    assert!(StorageRegistry::global().for_uri("s3://bucket/key").is_ok());
+}
+
+#[cfg(not(feature = "s3"))]
+#[test]
+fn s3_uris_unsupported_without_feature() {
+    let err = StorageRegistry::global()
+        .for_uri("s3://bucket/key")
+        .unwrap_err();
+    assert_eq!(err.code(), "unsupported");
+}
+
+#[cfg(feature = "gcs")]
+#[test]
+fn gcs_feature_registers_backend() {
+    let _ = parquet_lens_core::GcsBackend::default();
+    assert!(StorageRegistry::global().for_uri("gs://bucket/key").is_ok());
+}
+
+#[cfg(not(feature = "gcs"))]
+#[test]
+fn gcs_uris_unsupported_without_feature() {
+    let err = StorageRegistry::global()
+        .for_uri("gs://bucket/key")
+        .unwrap_err();
+    assert_eq!(err.code(), "unsupported");
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn http_feature_registers_backend() {
+    let _ = parquet_lens_core::HttpBackend;
+    assert!(StorageRegistry::global()
+        .for_uri("https://host/data.parquet")
+        .is_ok());
+}
+
+#[cfg(not(feature = "http"))]
+#[test]
+fn http_uris_unsupported_without_feature() {
+    let err = StorageRegistry::global()
+        .for_uri("https://host/data.parquet")
+        .unwrap_err();
+    assert_eq!(err.code(), "unsupported");
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn tokio_feature_exposes_async_scan() {
+    let _ = parquet_lens_core::profile_columns_async;
+    let _ = parquet_lens_core::object_store_for_uri;
+}
+
+#[cfg(feature = "hdfs")]
+#[test]
+fn hdfs_feature_registers_backend() {
+    let _ = parquet_lens_core::HdfsBackend;
+    assert!(StorageRegistry::global()
+        .for_uri("hdfs://namenode:8020/warehouse/t")
+        .is_ok());
+}
@@ -1,13 +1,36 @@
-use arrow::array::{Int32Array, StringArray};
+use arrow::array::{Array, AsArray, Decimal128Array, Int32Array, StringArray};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 use parquet::arrow::ArrowWriter;
+use parquet::file::properties::{WriterProperties, WriterPropertiesBuilder};
 use parquet_lens_core::{
-    aggregate_column_stats, open_parquet_file, read_column_stats, score_column,
+    aggregate_column_stats, analyze_compression, apply_full_scan_findings, build_histogram,
+    check_constraints, columns_table, detect_duplicates, diff_schemas, distinct_values,
+    explain_filter, export_csv, export_json, export_ndjson, export_sample, filter_aggregate,
+    filter_count, filter_count_dataset, filter_rows, filter_rows_each, mask_top_values,
+    open_parquet_file, parse_aggregate, parse_predicate, profile_columns_with_timeout,
+    profile_dataset, read_column_stats, read_metadata_parallel_with_progress,
+    recommend_compression, reconcile_row_counts, rule_constraints, run_rules, sample_manifest_path,
+    sample_partitions, sample_row_groups, scan_directory, scan_pii, score_column, top_values,
+    union_cardinality, validate_contract, write_rows_jsonl, write_top_values_csv,
+    BenfordConformity, CacheKey, CalendarBucket, Constraint, Contract, DiffStatus, HandleRegistry,
+    HistogramScale, HistoryEntry, HistoryStore, MaskSpec, MemoryTracker, NumberFormat,
+    ParquetFilePath, PiiKind, ProfileCache, ProfileReport, Profiler, Progress, ProgressSink,
+    QuarantineLog, SampleConfig, SampleManifest, SampleMethod, ScanOptions, Severity, SkipReason,
+    StorageBackend, StorageRegistry, StrataAllocation,
 };
 use std::sync::Arc;
 use tempfile::NamedTempFile;
 
+fn scan_opts(threads: Option<usize>) -> ScanOptions {
+    ScanOptions {
+        batch_size: 64,
+        threads,
+        prefetch_depth: 3,
+        ..ScanOptions::default()
+    }
+}
+
 fn write_fixture() -> NamedTempFile {
     let tmp = tempfile::Builder::new()
         .suffix(".parquet")
@@ -26,6 +49,35 @@ fn write_fixture() -> NamedTempFile {
     tmp
 }
 
+// 1000 rows split over 10 row groups; every id appears twice
+fn write_multi_rg_fixture() -> NamedTempFile {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("name", DataType::Utf8, true),
+    ]));
+    let ids: Vec<i32> = (0..1000).map(|i| i % 500).collect();
+    let names: Vec<Option<String>> = ids.iter().map(|i| Some(format!("n{}", i % 7))).collect();
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(ids)),
+            Arc::new(StringArray::from(names)),
+        ],
+    )
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(100)
+        .build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    tmp
+}
+
 #[test]
 fn open_parquet_file_returns_info() {
     let tmp = write_fixture();
@@ -74,3 +126,3650 @@ fn score_column_on_fixture_col() {
     assert_eq!(qs.column_name, "name");
     assert!(qs.score <= 100);
 }
+
+#[test]
+fn parallel_profile_matches_single_thread() {
+    let tmp = write_multi_rg_fixture();
+    let serial =
+        profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(Some(1))).unwrap();
+    let parallel =
+        profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(Some(4))).unwrap();
+    assert_eq!(serial.len(), 2);
+    for (a, b) in serial.iter().zip(&parallel) {
+        assert_eq!(a.column_name, b.column_name);
+        assert_eq!(
+            a.cardinality.approximate_distinct,
+            b.cardinality.approximate_distinct
+        );
+        assert!(!b.truncated);
+    }
+    let id = parallel.iter().find(|c| c.column_name == "id").unwrap();
+    let num = id.numeric.as_ref().unwrap();
+    assert_eq!(num.count, 1000);
+    assert_eq!(num.min, 0.0);
+    assert_eq!(num.max, 499.0);
+    let name = parallel.iter().find(|c| c.column_name == "name").unwrap();
+    let freq = name.frequency.as_ref().unwrap();
+    assert_eq!(freq.total_count, 1000);
+    assert_eq!(freq.top_values.len(), 7);
+}
+
+#[test]
+fn parallel_duplicates_counts_across_row_groups() {
+    let tmp = write_multi_rg_fixture();
+    let report = detect_duplicates(tmp.path(), true, &[], &scan_opts(Some(4))).unwrap();
+    assert_eq!(report.total_rows, 1000);
+    assert_eq!(report.estimated_duplicates, 500);
+}
+
+#[test]
+fn duplicates_skip_ignored_columns() {
+    let tmp = write_multi_rg_fixture();
+    let opts = scan_opts(Some(2));
+    // with id left out only the 7 names remain to tell rows apart
+    let report = detect_duplicates(tmp.path(), true, &["id".to_string()], &opts).unwrap();
+    assert_eq!(report.estimated_duplicates, 993);
+    assert_eq!(report.ignored_columns, vec!["id".to_string()]);
+
+    let err = detect_duplicates(tmp.path(), true, &["nope".to_string()], &opts).unwrap_err();
+    assert_eq!(err.code(), "not_found");
+    let all = ["id".to_string(), "name".to_string()];
+    assert!(detect_duplicates(tmp.path(), true, &all, &opts).is_err());
+}
+
+#[test]
+fn filter_batch_size_and_prefetch_do_not_change_results() {
+    let tmp = write_multi_rg_fixture();
+    let pred = parse_predicate("id < 10").unwrap();
+    let inline = ScanOptions {
+        batch_size: 7,
+        threads: None,
+        prefetch_depth: 0,
+        ..ScanOptions::default()
+    };
+    let a = filter_count(tmp.path(), &pred, &inline).unwrap();
+    let b = filter_count(tmp.path(), &pred, &scan_opts(None)).unwrap();
+    assert_eq!(a.matched_rows, 20);
+    assert_eq!(a.matched_rows, b.matched_rows);
+    // limit stops early; the prefetch thread must not keep the call alive
+    let rows = filter_rows(tmp.path(), &pred, Some(5), &scan_opts(None)).unwrap();
+    assert_eq!(rows.iter().map(|b| b.num_rows()).sum::<usize>(), 5);
+}
+
+#[test]
+fn streamed_filter_stops_reading_at_the_limit() {
+    let tmp = write_multi_rg_fixture();
+    let pred = parse_predicate("id >= 100").unwrap();
+    let mut streamed: Vec<RecordBatch> = Vec::new();
+    let result = filter_rows_each(tmp.path(), &pred, Some(150), &scan_opts(None), &mut |b| {
+        assert!(b.num_rows() > 0);
+        streamed.push(b);
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(streamed.iter().map(|b| b.num_rows()).sum::<usize>(), 150);
+    // ids 100..500 start in row group 1; reading stops long before the 1000th row
+    assert!(result.scanned_rows < 400, "{}", result.scanned_rows);
+    assert!(result.matched_rows >= 150);
+    let collected = filter_rows(tmp.path(), &pred, Some(150), &scan_opts(None)).unwrap();
+    assert_eq!(
+        arrow::compute::concat_batches(&collected[0].schema(), &collected).unwrap(),
+        arrow::compute::concat_batches(&streamed[0].schema(), &streamed).unwrap()
+    );
+    // an error from the callback ends the scan
+    let err = filter_rows_each(tmp.path(), &pred, None, &scan_opts(None), &mut |_| {
+        Err("sink closed".to_string())
+    });
+    assert_eq!(err.unwrap_err(), "sink closed");
+}
+
+#[test]
+fn bloom_filters_skip_row_groups_statistics_cannot() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("name", DataType::Utf8, true),
+    ]));
+    // 4 row groups with overlapping min/max: group k holds ids j*4+k
+    let ids: Vec<i32> = (0..4)
+        .flat_map(|k| (0..100).map(move |j| j * 4 + k))
+        .collect();
+    let names: Vec<String> = ids.iter().map(|i| format!("n{i}")).collect();
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(ids)),
+            Arc::new(StringArray::from(names)),
+        ],
+    )
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(100)
+        .set_bloom_filter_enabled(true)
+        .build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let by_id = filter_count(
+        tmp.path(),
+        &parse_predicate("id = 150").unwrap(),
+        &scan_opts(None),
+    )
+    .unwrap();
+    assert_eq!(by_id.matched_rows, 1);
+    assert_eq!((by_id.skipped_rgs, by_id.bloom_skipped_rgs), (0, 3));
+    assert_eq!(by_id.scanned_rows, 100);
+
+    let pred = parse_predicate("name = 'n150' AND id > 0").unwrap();
+    let by_name = filter_count(tmp.path(), &pred, &scan_opts(None)).unwrap();
+    assert_eq!((by_name.matched_rows, by_name.bloom_skipped_rgs), (1, 3));
+    let rows = filter_rows(tmp.path(), &pred, None, &scan_opts(None)).unwrap();
+    assert_eq!(rows.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+
+    // statistics rule it out first; only the rest are counted as bloom skips
+    let out_of_range = filter_count(
+        tmp.path(),
+        &parse_predicate("id = 1000").unwrap(),
+        &scan_opts(None),
+    )
+    .unwrap();
+    assert_eq!(
+        (out_of_range.skipped_rgs, out_of_range.bloom_skipped_rgs),
+        (4, 0)
+    );
+
+    // non-equality predicates never consult the filters
+    let range = filter_count(
+        tmp.path(),
+        &parse_predicate("id >= 150").unwrap(),
+        &scan_opts(None),
+    )
+    .unwrap();
+    assert_eq!(range.bloom_skipped_rgs, 0);
+}
+
+#[test]
+fn dataset_filter_adds_up_per_file_counts() {
+    let a = write_multi_rg_fixture();
+    let b = write_fixture();
+    let paths = vec![a.path().to_path_buf(), b.path().to_path_buf()];
+    let pred = parse_predicate("id < 3").unwrap();
+    let r = filter_count_dataset(&paths, &pred, &scan_opts(Some(2))).unwrap();
+    let each: Vec<u64> = r.files.iter().map(|f| f.result.matched_rows).collect();
+    assert_eq!(each, [6, 2]);
+    assert_eq!(r.matched_rows, 8);
+    assert_eq!(r.files[1].path, b.path().display().to_string());
+    assert_eq!(r.total_rgs, 11);
+    assert_eq!(
+        r.scanned_rows,
+        r.files.iter().map(|f| f.result.scanned_rows).sum::<u64>()
+    );
+
+    let missing = vec![a.path().to_path_buf(), "/no/such/file.parquet".into()];
+    let err = filter_count_dataset(&missing, &pred, &scan_opts(None)).unwrap_err();
+    assert!(err.contains("/no/such/file.parquet"), "{err}");
+}
+
+#[test]
+fn jsonl_rows_keep_nested_and_temporal_values() {
+    use arrow::array::{ListArray, StructArray, TimestampMillisecondArray};
+    use arrow::datatypes::{Int32Type, TimeUnit};
+    let ts = TimestampMillisecondArray::from(vec![1704196800000, 1704283200000])
+        .with_timezone("Europe/Oslo");
+    let tags =
+        ListArray::from_iter_primitive::<Int32Type, _, _>(vec![Some(vec![Some(1), Some(2)]), None]);
+    let addr = StructArray::from(vec![(
+        Arc::new(Field::new("city", DataType::Utf8, true)),
+        Arc::new(StringArray::from(vec![Some("Oslo"), None])) as arrow::array::ArrayRef,
+    )]);
+    let schema = Arc::new(Schema::new(vec![
+        Field::new(
+            "ts",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("Europe/Oslo".into())),
+            false,
+        ),
+        Field::new("tags", tags.data_type().clone(), true),
+        Field::new("addr", addr.data_type().clone(), true),
+    ]));
+    let batch =
+        RecordBatch::try_new(schema, vec![Arc::new(ts), Arc::new(tags), Arc::new(addr)]).unwrap();
+    let mut out = Vec::new();
+    assert_eq!(write_rows_jsonl(&mut out, &[batch]).unwrap(), 2);
+    let lines: Vec<serde_json::Value> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(
+        lines[0],
+        serde_json::json!({"ts": "2024-01-02T12:00:00Z", "tags": [1, 2], "addr": {"city": "Oslo"}})
+    );
+    assert_eq!(
+        lines[1],
+        serde_json::json!({"ts": "2024-01-03T12:00:00Z", "tags": null, "addr": {"city": null}})
+    );
+}
+
+#[test]
+fn aggregates_stream_over_matching_rows_of_every_file() {
+    let a = write_multi_rg_fixture();
+    let b = write_multi_rg_fixture();
+    let paths = vec![a.path().to_path_buf(), b.path().to_path_buf()];
+    let pred = parse_predicate("id < 14").unwrap();
+    let query = parse_aggregate("count(*), sum(id), avg(id), max(id) group by name").unwrap();
+    let r = filter_aggregate(&paths, &pred, &query, &scan_opts(None)).unwrap();
+    assert_eq!(
+        r.headers,
+        ["name", "count(*)", "sum(id)", "avg(id)", "max(id)"]
+    );
+    // ids 0..14 appear twice per file; name is n{id % 7}
+    assert_eq!(r.matched_rows, 56);
+    assert_eq!(r.rows.len(), 7);
+    assert_eq!(r.rows[0], ["n0", "8", "28", "3.5", "7"]);
+    assert_eq!(r.rows[6], ["n6", "8", "76", "9.5", "13"]);
+
+    let none = parse_predicate("id > 1000").unwrap();
+    let total = parse_aggregate("count(*), sum(id)").unwrap();
+    let r = filter_aggregate(&paths, &none, &total, &scan_opts(None)).unwrap();
+    assert_eq!(r.rows, [["0", "NULL"]]);
+    assert_eq!(r.scanned_rows, 0);
+
+    let bad = parse_aggregate("avg(name)").unwrap();
+    let err = filter_aggregate(&paths, &pred, &bad, &scan_opts(None)).unwrap_err();
+    assert!(err.contains("not numeric"), "{err}");
+}
+
+// 4 row groups of categories; each group has one category of its own
+fn write_categorical_fixture(props: WriterPropertiesBuilder) -> NamedTempFile {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new("cat", DataType::Utf8, false)]));
+    let cats: Vec<String> = (0..4)
+        .flat_map(|k| (0..99).map(move |j| ["apple", "zebra", &format!("c{k}")][j % 3].to_string()))
+        .collect();
+    let batch =
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(StringArray::from(cats))]).unwrap();
+    let props = props.set_max_row_group_size(99).build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    tmp
+}
+
+#[test]
+fn dictionary_pages_skip_row_groups_without_the_literal() {
+    let tmp = write_categorical_fixture(WriterProperties::builder());
+    let pred = parse_predicate("cat = 'c2'").unwrap();
+    let r = filter_count(tmp.path(), &pred, &scan_opts(None)).unwrap();
+    assert_eq!(r.matched_rows, 33);
+    assert_eq!(
+        (r.skipped_rgs, r.bloom_skipped_rgs, r.dict_skipped_rgs),
+        (0, 0, 3)
+    );
+    let rows = filter_rows(tmp.path(), &pred, None, &scan_opts(None)).unwrap();
+    assert_eq!(rows.iter().map(|b| b.num_rows()).sum::<usize>(), 33);
+
+    let absent = parse_predicate("cat = 'c9' OR cat = 'c1'").unwrap();
+    let r = filter_count(tmp.path(), &absent, &scan_opts(None)).unwrap();
+    assert_eq!((r.matched_rows, r.dict_skipped_rgs), (33, 3));
+
+    // a dictionary that overflowed into plain pages no longer lists every value
+    let plain = write_categorical_fixture(
+        WriterProperties::builder()
+            .set_dictionary_page_size_limit(1)
+            .set_data_page_row_count_limit(10)
+            .set_write_batch_size(10),
+    );
+    let r = filter_count(plain.path(), &pred, &scan_opts(None)).unwrap();
+    assert_eq!((r.matched_rows, r.dict_skipped_rgs), (33, 0));
+}
+
+#[test]
+fn decimal_predicates_compare_scaled_values_and_prune() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    // one column per physical backing: INT32, INT64, FIXED_LEN_BYTE_ARRAY (x2)
+    let types = [
+        ("d32", DataType::Decimal128(9, 2)),
+        ("d64", DataType::Decimal128(18, 2)),
+        ("flba", DataType::Decimal128(20, 2)),
+        ("wide", DataType::Decimal256(40, 2)),
+    ];
+    let schema = Arc::new(Schema::new(
+        types
+            .iter()
+            .map(|(n, t)| Field::new(*n, t.clone(), false))
+            .collect::<Vec<_>>(),
+    ));
+    // 4 row groups: group k holds k.00 ..= k.99
+    let raw: Vec<i128> = (0..400).collect();
+    let columns: Vec<arrow::array::ArrayRef> = types
+        .iter()
+        .map(|(_, t)| {
+            let a = Decimal128Array::from(raw.clone())
+                .with_precision_and_scale(9, 2)
+                .unwrap();
+            arrow::compute::cast(&a, t).unwrap()
+        })
+        .collect();
+    let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(100)
+        .set_bloom_filter_enabled(true)
+        .build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let (_, meta) = open_parquet_file(tmp.path()).unwrap();
+    let physical: Vec<_> = (0..4)
+        .map(|i| meta.row_group(0).column(i).column_type())
+        .collect();
+    use parquet::basic::Type;
+    assert_eq!(
+        physical,
+        [
+            Type::INT32,
+            Type::INT64,
+            Type::FIXED_LEN_BYTE_ARRAY,
+            Type::FIXED_LEN_BYTE_ARRAY
+        ]
+    );
+    for (col, _) in types {
+        let count = |expr: String| {
+            let pred = parse_predicate(&expr).unwrap();
+            filter_count(tmp.path(), &pred, &scan_opts(None)).unwrap()
+        };
+        let r = count(format!("{col} > 3.5"));
+        assert_eq!((r.matched_rows, r.skipped_rgs), (49, 3), "{col}");
+        let r = count(format!("{col} <= '0.995'"));
+        assert_eq!((r.matched_rows, r.skipped_rgs), (100, 3), "{col}");
+        // 1 means 1.00 (raw 100 in group 1), not the raw value 1 in group 0
+        let r = count(format!("{col} = 1"));
+        assert_eq!((r.matched_rows, r.scanned_rows), (1, 100), "{col}");
+        assert_eq!(r.bloom_skipped_rgs, 0, "{col}");
+        let r = count(format!("{col} = 2.345"));
+        assert_eq!((r.matched_rows, r.skipped_rgs), (0, 3), "{col}");
+    }
+    let groups = explain_filter(tmp.path(), &parse_predicate("flba = 1.5").unwrap()).unwrap();
+    assert_eq!(
+        groups[1].evidence[0],
+        "flba = 1.5: min 1.00, max 1.99 may match; bloom filter may contain it"
+    );
+}
+
+#[test]
+fn nested_paths_filter_prune_and_aggregate() {
+    use arrow::array::{ListArray, StructArray};
+    use arrow::datatypes::{Fields, Int32Type};
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    // user.address.zip = 1000 + i, tags = [i % 3, i]; 4 row groups of 50
+    let zips = Int32Array::from_iter_values((0..200).map(|i| 1000 + i));
+    let address = StructArray::from(vec![(
+        Arc::new(Field::new("zip", DataType::Int32, false)),
+        Arc::new(zips) as arrow::array::ArrayRef,
+    )]);
+    let user = StructArray::new(
+        Fields::from(vec![
+            Field::new("address", address.data_type().clone(), false),
+            Field::new("name", DataType::Utf8, false),
+        ]),
+        vec![
+            Arc::new(address),
+            Arc::new(StringArray::from_iter_values(
+                (0..200).map(|i| format!("u{i}")),
+            )),
+        ],
+        None,
+    );
+    let tags = ListArray::from_iter_primitive::<Int32Type, _, _>(
+        (0..200).map(|i| Some(vec![Some(i % 3), Some(i)])),
+    );
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("user", user.data_type().clone(), false),
+        Field::new("tags", tags.data_type().clone(), true),
+    ]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(user), Arc::new(tags)]).unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(50)
+        .build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let count = |expr: &str| {
+        filter_count(
+            tmp.path(),
+            &parse_predicate(expr).unwrap(),
+            &scan_opts(None),
+        )
+        .unwrap()
+    };
+    // the struct leaf has statistics of its own
+    let r = count("user.address.zip >= 1175");
+    assert_eq!((r.matched_rows, r.skipped_rgs, r.scanned_rows), (25, 3, 50));
+    let r = count("tags[0] = 2 AND tags[1] < 10");
+    assert_eq!(r.matched_rows, 3);
+    assert_eq!(count("tags[2] IS NOT NULL").matched_rows, 0);
+    let err = filter_count(
+        tmp.path(),
+        &parse_predicate("user.zip = 1").unwrap(),
+        &scan_opts(None),
+    )
+    .unwrap_err();
+    assert!(
+        err.contains("available: user.address.zip, user.name, tags[n]"),
+        "{err}"
+    );
+
+    let query = parse_aggregate("count(*), max(user.address.zip) group by tags[0]").unwrap();
+    let pred = parse_predicate("user.address.zip < 1010").unwrap();
+    let r = filter_aggregate(&[tmp.path().to_path_buf()], &pred, &query, &scan_opts(None)).unwrap();
+    assert_eq!(r.headers, ["tags[0]", "count(*)", "max(user.address.zip)"]);
+    assert_eq!(
+        r.rows,
+        [["0", "4", "1009"], ["1", "3", "1007"], ["2", "3", "1008"]]
+    );
+}
+
+#[test]
+fn null_safe_equality_prunes_like_equality() {
+    let tmp = write_multi_rg_fixture();
+    let count = |expr: &str| {
+        filter_count(
+            tmp.path(),
+            &parse_predicate(expr).unwrap(),
+            &scan_opts(None),
+        )
+        .unwrap()
+    };
+    let r = count("id IS NOT DISTINCT FROM 7");
+    assert_eq!((r.matched_rows, r.skipped_rgs), (2, 8));
+    // NOT is never pruned, but evaluates to the same rows
+    let r = count("NOT (id IS DISTINCT FROM 7)");
+    assert_eq!((r.matched_rows, r.skipped_rgs), (2, 0));
+}
+
+#[test]
+fn explain_names_the_step_that_pruned_each_row_group() {
+    let tmp = write_categorical_fixture(WriterProperties::builder());
+    let pred = parse_predicate("cat = 'c2' AND cat != 'apple'").unwrap();
+    let groups = explain_filter(tmp.path(), &pred).unwrap();
+    let decisions: Vec<_> = groups.iter().map(|g| g.skipped_by).collect();
+    let dict = Some(SkipReason::Dictionary);
+    assert_eq!(decisions, [dict, dict, None, dict]);
+    // the decisions are the ones filter_count acts on
+    let r = filter_count(tmp.path(), &pred, &scan_opts(None)).unwrap();
+    assert_eq!(r.dict_skipped_rgs, 3);
+    assert_eq!(groups[0].num_rows, 99);
+    assert_eq!(
+        groups[0].evidence,
+        [
+            "cat = 'c2': min 'apple', max 'zebra' not comparable with the literal; \
+             no bloom filter; dictionary lacks it",
+            "cat != 'apple': min 'apple', max 'zebra' != is never pruned",
+        ]
+    );
+    assert!(groups[2].evidence[0].ends_with("dictionary contains it"));
+
+    let bloom =
+        write_categorical_fixture(WriterProperties::builder().set_bloom_filter_enabled(true));
+    let groups = explain_filter(bloom.path(), &pred).unwrap();
+    assert_eq!(groups[0].skipped_by, Some(SkipReason::BloomFilter));
+    assert!(groups[0].evidence[0].contains("bloom filter rules it out"));
+    assert!(groups[2].evidence[0].contains("bloom filter may contain it"));
+
+    let ids = write_multi_rg_fixture();
+    let groups = explain_filter(ids.path(), &parse_predicate("id IS NULL").unwrap()).unwrap();
+    assert!(groups
+        .iter()
+        .all(|g| g.skipped_by == Some(SkipReason::Statistics)));
+    assert_eq!(
+        groups[0].evidence,
+        ["id IS NULL: null count 0 rules it out"]
+    );
+
+    let err = explain_filter(ids.path(), &parse_predicate("nope = 1").unwrap()).unwrap_err();
+    assert!(err.contains("column 'nope' not found"));
+}
+
+#[test]
+fn memory_tracker_reports_peak_per_analysis() {
+    let tmp = write_multi_rg_fixture();
+    let scan = Arc::new(MemoryTracker::default());
+    let opts = ScanOptions {
+        memory: Some(scan.clone()),
+        ..scan_opts(Some(2))
+    };
+    profile_columns_with_timeout(tmp.path(), None, 10, None, &opts).unwrap();
+    // at least one HLL sketch per column was live at some point
+    assert!(scan.peak() >= 2 * 16 * 1024);
+    assert_eq!(scan.current(), 0);
+
+    let dups = Arc::new(MemoryTracker::default());
+    let opts = ScanOptions {
+        memory: Some(dups.clone()),
+        ..scan_opts(Some(2))
+    };
+    detect_duplicates(tmp.path(), true, &[], &opts).unwrap();
+    // 500 distinct hashes need at least 500 hash set slots
+    assert!(dups.peak() >= 500 * 8);
+    assert_eq!(dups.current(), 0);
+}
+
+#[test]
+fn full_scan_quantiles_and_histograms_come_from_merged_digests() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Float64, false)]));
+    // 0..20000 shuffled across row groups so every worker sees the whole range
+    let vals: Vec<f64> = (0..20_000u64)
+        .map(|i| ((i * 7919) % 20_000) as f64)
+        .collect();
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(arrow::array::Float64Array::from(vals))],
+    )
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(2_000)
+        .build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    let cols =
+        profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(Some(4))).unwrap();
+    let numeric = cols[0].numeric.as_ref().unwrap();
+    assert_eq!(numeric.count, 20_000);
+    for (p, want) in [
+        (numeric.p1, 200.0),
+        (numeric.p50, 10_000.0),
+        (numeric.p99, 19_800.0),
+    ] {
+        assert!((p - want).abs() < 200.0, "{p} vs {want}");
+    }
+    let bins = cols[0].histogram.as_ref().unwrap();
+    assert_eq!(bins.len(), 10);
+    assert_eq!(bins.iter().map(|b| b.count).sum::<u64>(), 20_000);
+    for b in bins {
+        assert!(b.count.abs_diff(2_000) < 200, "{b:?}");
+    }
+    assert_eq!((bins[0].range_start, bins[9].range_end), (0.0, 19_999.0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn numeric_outliers_are_counted_with_examples_and_cost_quality() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("sensor", DataType::Float64, false),
+        Field::new("spiky", DataType::Float64, false),
+    ]));
+    // readings around 20 with four bad ones; 5% of `spiky` are one huge value
+    let mut sensor: Vec<f64> = (0..996).map(|i| 20.0 + (i % 10) as f64 * 0.1).collect();
+    sensor.extend([-999.0, 9_999.0, 5_000.0, 8_888.0]);
+    let spiky: Vec<f64> = (0..1000)
+        .map(|i| if i % 20 == 0 { 1e6 } else { (i % 50) as f64 })
+        .collect();
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(arrow::array::Float64Array::from(sensor)),
+            Arc::new(arrow::array::Float64Array::from(spiky)),
+        ],
+    )
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(250)
+        .build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .with_full_scan(true)
+        .scan_options(scan_opts(Some(4)))
+        .run()
+        .await
+        .unwrap();
+    let fs = report.full_scan.as_ref().unwrap();
+    let sensor = fs[0].numeric.as_ref().unwrap();
+    assert_eq!(sensor.iqr_outlier_count, 4);
+    assert_eq!(sensor.zscore_outlier_count, 3);
+    assert_eq!(sensor.outlier_examples, [9_999.0, 8_888.0, 5_000.0, -999.0]);
+    assert!((sensor.outlier_pct - 0.4).abs() < 1e-9);
+    // past the exactly kept extremes the count comes from the digest, which
+    // smears the step from 49 to 1e6 a little
+    let spiky = fs[1].numeric.as_ref().unwrap();
+    assert!(spiky.iqr_outlier_count.abs_diff(50) <= 10, "{spiky:?}");
+    assert_eq!(spiky.outlier_examples, [1e6; 5]);
+
+    let scores = report.quality_scores();
+    let score = |col: &str| scores.iter().find(|s| s.column_name == col).unwrap();
+    assert_eq!(score("sensor").full_scan_penalty, 0);
+    assert!(!score("sensor").breakdown.contains("outliers"));
+    let spiky_score = score("spiky");
+    assert_eq!(
+        spiky_score.full_scan_penalty,
+        (spiky.outlier_pct - 1.0).round() as u8
+    );
+    assert!(spiky_score
+        .breakdown
+        .contains(&format!("outliers={:.1}%", spiky.outlier_pct)));
+}
+
+#[test]
+fn benford_conformity_is_scored_for_amount_like_columns() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("amount", DataType::Float64, false),
+        Field::new("fabricated", DataType::Float64, false),
+        Field::new("rating", DataType::Float64, false),
+    ]));
+    // growth by 1% a step walks the leading digits the way Benford predicts
+    let amount: Vec<f64> = (0..2000).map(|i| 1.01f64.powi(i)).collect();
+    let fabricated: Vec<f64> = (0..2000)
+        .map(|i| 9.0 * 10f64.powi(i % 5) + (i % 7) as f64 * 0.01)
+        .collect();
+    let rating: Vec<f64> = (0..2000).map(|i| (i % 5 + 1) as f64).collect();
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(arrow::array::Float64Array::from(amount)),
+            Arc::new(arrow::array::Float64Array::from(fabricated)),
+            Arc::new(arrow::array::Float64Array::from(rating)),
+        ],
+    )
+    .unwrap();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    let cols = profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(None)).unwrap();
+    let amount = cols[0].numeric.as_ref().unwrap().benford.as_ref().unwrap();
+    assert_eq!(amount.digit_counts.iter().sum::<u64>(), 2000);
+    assert!(amount.digit_counts[0] > amount.digit_counts[8] * 5);
+    assert_eq!(amount.conformity, BenfordConformity::Close);
+    assert!(!amount.chi_square_rejects, "{amount:?}");
+    let fabricated = cols[1].numeric.as_ref().unwrap().benford.as_ref().unwrap();
+    assert_eq!(fabricated.digit_counts[8], 2000);
+    assert_eq!(fabricated.conformity, BenfordConformity::Nonconforming);
+    assert!(fabricated.chi_square_rejects);
+    // one decade of values isn't expected to follow Benford
+    assert!(cols[2].numeric.as_ref().unwrap().benford.is_none());
+}
+
+#[test]
+fn entropy_estimates_drive_compression_recommendations() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("digest", DataType::UInt64, false),
+        Field::new("status", DataType::Utf8, false),
+    ]));
+    // xorshift output is as close to random bytes as a test needs
+    let mut x = 0x9E37_79B9_7F4A_7C15u64;
+    let digest: Vec<u64> = (0..5000)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        })
+        .collect();
+    let status: Vec<&str> = (0..5000)
+        .map(|i| ["shipped", "pending", "cancelled"][i % 3])
+        .collect();
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(arrow::array::UInt64Array::from(digest)),
+            Arc::new(StringArray::from(status)),
+        ],
+    )
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_dictionary_enabled(false)
+        .build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    let cols = profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(None)).unwrap();
+    let digest = cols[0].entropy.as_ref().unwrap();
+    assert_eq!(digest.sampled_values, 4096);
+    assert_eq!(digest.bytes_per_value, 8.0);
+    assert!(digest.compressible_pct < 10.0, "{digest:?}");
+    let status = cols[1].entropy.as_ref().unwrap();
+    assert!(
+        (status.bits_per_value - 3f64.log2()).abs() < 0.01,
+        "{status:?}"
+    );
+    assert!(status.compressible_pct > 90.0, "{status:?}");
+
+    let (_, meta) = open_parquet_file(tmp.path()).unwrap();
+    let compression = analyze_compression(&meta);
+    let recs = recommend_compression(&compression, &cols);
+    assert_eq!(recs.len(), 1, "{recs:?}");
+    assert_eq!(recs[0].column_name, "status");
+    assert_eq!(recs[0].recommended_codec, "ZSTD");
+    assert!(recs[0].estimated_savings_pct > 90.0);
+    // without a full scan both uncompressed columns get the generic advice
+    let recs = recommend_compression(&compression, &[]);
+    assert_eq!(recs.len(), 2);
+    assert!(recs.iter().all(|r| r.estimated_savings_pct == 40.0));
+}
+
+#[test]
+fn log_scale_histograms_spread_power_law_columns() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("latency", DataType::Float64, false),
+        Field::new("small", DataType::Float64, false),
+    ]));
+    // log-uniform over 1..=1e6, so each decade holds a sixth of the rows
+    let latency: Vec<f64> = (0..=6_000)
+        .map(|i| 10f64.powf(i as f64 / 1_000.0))
+        .collect();
+    let small: Vec<f64> = (0..=6_000).map(|i| (i % 10 + 1) as f64).collect();
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(arrow::array::Float64Array::from(latency.clone())),
+            Arc::new(arrow::array::Float64Array::from(small)),
+        ],
+    )
+    .unwrap();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let linear = build_histogram(&latency, 6, HistogramScale::Linear);
+    assert!(linear[0].count > 5_000, "{linear:?}");
+    let log = build_histogram(&latency, 6, HistogramScale::Log);
+    assert!((log[1].range_start - 10.0).abs() < 1e-6, "{log:?}");
+    for b in &log {
+        assert!(b.count.abs_diff(1_000) <= 2, "{b:?}");
+    }
+
+    let opts = ScanOptions {
+        histogram_scale: HistogramScale::Log,
+        ..scan_opts(None)
+    };
+    let cols = profile_columns_with_timeout(tmp.path(), None, 6, None, &opts).unwrap();
+    let bins = cols[0].histogram.as_ref().unwrap();
+    assert_eq!(bins.iter().map(|b| b.count).sum::<u64>(), 6_001);
+    for b in bins {
+        assert!(b.count.abs_diff(1_000) < 100, "{b:?}");
+    }
+    // 1..=10 spans a single decade, so its bins stay evenly spaced
+    let small = cols[1].histogram.as_ref().unwrap();
+    assert_eq!((small[0].range_start, small[0].range_end), (1.0, 2.5));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn temporal_profiles_split_rows_by_weekday_and_hour_and_find_gaps() {
+    use arrow::array::{Date32Array, TimestampMillisecondArray};
+    use arrow::datatypes::TimeUnit;
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new(
+            "ts",
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+            false,
+        ),
+        Field::new("day", DataType::Date32, false),
+    ]));
+    // four weeks from Monday 2024-01-01, weekdays only, 06:00-23:00 hourly,
+    // and nothing at all on Wednesday the 10th
+    let monday = 19_723i64;
+    let mut ts = Vec::new();
+    for day in monday..monday + 28 {
+        if (day - monday) % 7 >= 5 || day == monday + 9 {
+            continue;
+        }
+        ts.extend((6..24).map(|h| day * 86_400_000 + h * 3_600_000));
+    }
+    let days: Vec<i32> = ts.iter().map(|t| (t / 86_400_000) as i32).collect();
+    let n = ts.len() as u64;
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(TimestampMillisecondArray::from(ts)),
+            Arc::new(Date32Array::from(days)),
+        ],
+    )
+    .unwrap();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .with_full_scan(true)
+        .scan_options(scan_opts(None))
+        .run()
+        .await
+        .unwrap();
+    let fs = report.full_scan.as_ref().unwrap();
+    let t = fs[0].temporal.as_ref().unwrap();
+    assert_eq!(t.weekday_distribution, [72, 72, 54, 72, 72, 0, 0]);
+    assert_eq!(t.hour_distribution[..6], [0; 6]);
+    assert_eq!(t.hour_distribution.iter().sum::<u64>(), n);
+    let gaps: Vec<(CalendarBucket, &str, u64)> = t
+        .gaps
+        .iter()
+        .map(|g| (g.bucket, g.label.as_str(), g.span))
+        .collect();
+    assert_eq!(
+        gaps,
+        [
+            (CalendarBucket::Weekday, "Sat-Sun", 2),
+            (CalendarBucket::Hour, "00:00-05:59", 6),
+            (CalendarBucket::Day, "2024-01-10", 1),
+        ]
+    );
+    // dates sit at midnight, so they say nothing about hours
+    let d = fs[1].temporal.as_ref().unwrap();
+    assert!(d.gaps.iter().all(|g| g.bucket != CalendarBucket::Hour));
+
+    let out = tempfile::tempdir().unwrap();
+    export_csv(&out.path().join("profile.csv"), &report).unwrap();
+    let csv = std::fs::read_to_string(out.path().join("temporal.csv")).unwrap();
+    assert!(csv.starts_with("column,bucket,label,count\nts,weekday,Mon,72\n"));
+    assert!(csv.contains("ts,hour,06,19\n"));
+    assert!(csv.contains("ts,weekday_gap,Sat-Sun,2\n"));
+    assert!(csv.contains("day,day_gap,2024-01-10,1\n"));
+}
+
+#[test]
+fn int96_timestamps_profile_as_epoch_millis() {
+    use parquet::data_type::{Int96, Int96Type};
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    // how Spark writes TimestampType with spark.sql.parquet.outputTimestampType=INT96
+    let schema = Arc::new(parse_message_type("message spark { optional int96 ts; }").unwrap());
+    let mut writer = SerializedFileWriter::new(
+        tmp.reopen().unwrap(),
+        schema,
+        Arc::new(WriterProperties::builder().build()),
+    )
+    .unwrap();
+    let mut rg = writer.next_row_group().unwrap();
+    let mut col = rg.next_column().unwrap().unwrap();
+    // 2022-01-08 00:00, 2022-01-08 12:00, 2022-01-10 00:00 (Julian day 2459588 = 2022-01-08)
+    let vals: Vec<Int96> = [
+        (0u64, 2_459_588),
+        (43_200_000_000_000, 2_459_588),
+        (0, 2_459_590),
+    ]
+    .iter()
+    .map(|&(nanos, day)| {
+        let mut v = Int96::new();
+        v.set_data(nanos as u32, (nanos >> 32) as u32, day);
+        v
+    })
+    .collect();
+    col.typed::<Int96Type>()
+        .write_batch(&vals, Some(&[1, 1, 0, 1]), None)
+        .unwrap();
+    col.close().unwrap();
+    rg.close().unwrap();
+    writer.close().unwrap();
+
+    let jan8 = 1_641_600_000_000;
+    let jan10 = jan8 + 2 * 86_400_000;
+    let cols = profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(None)).unwrap();
+    let temporal = cols[0].temporal.as_ref().unwrap();
+    assert_eq!((temporal.count, temporal.null_count), (3, 1));
+    assert_eq!(
+        (temporal.min_timestamp_ms, temporal.max_timestamp_ms),
+        (Some(jan8), Some(jan10))
+    );
+    assert_eq!(temporal.year_distribution, vec![(2022, 3)]);
+    assert_eq!(cols[0].cardinality.approximate_distinct, 3);
+    let top = &cols[0].frequency.as_ref().unwrap().top_values;
+    assert!(
+        top.iter().any(|e| e.value == "2022-01-08T12:00:00"),
+        "{top:?}"
+    );
+
+    let ts = parquet_lens_core::profile_timeseries(tmp.path(), &["ts".into()]).unwrap();
+    assert_eq!(
+        (ts[0].min_timestamp, ts[0].max_timestamp),
+        (Some(jan8), Some(jan10))
+    );
+    assert_eq!(ts[0].total_duration_ms, Some(2 * 86_400_000));
+}
+
+#[test]
+fn binary_columns_get_lengths_content_types_and_entropy() {
+    use arrow::array::{BinaryArray, FixedSizeBinaryArray};
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("blob", DataType::Binary, true),
+        Field::new("id", DataType::FixedSizeBinary(16), false),
+    ]));
+    let jpeg: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0, 0x10];
+    let png: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    let gzip: &[u8] = &[0x1F, 0x8B, 0x08, 0, 0, 0, 0, 0];
+    // field 1 varint 150, field 2 string "hi"
+    let proto: &[u8] = &[0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i'];
+    let blobs = BinaryArray::from(vec![
+        Some(jpeg),
+        Some(png),
+        Some(gzip),
+        Some(proto),
+        Some(b""),
+        None,
+    ]);
+    // v4 uuids except the last, whose version nibble is 0
+    let ids: Vec<[u8; 16]> = (0..6u8)
+        .map(|i| {
+            let mut id = [i; 16];
+            id[6] = if i < 5 { 0x40 | i } else { 0 };
+            id[8] = 0x80 | i;
+            id
+        })
+        .collect();
+    let ids = FixedSizeBinaryArray::try_from_iter(ids.iter()).unwrap();
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(blobs), Arc::new(ids)]).unwrap();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let cols = profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(None)).unwrap();
+    let blob = cols[0].binary.as_ref().unwrap();
+    assert_eq!((blob.count, blob.empty_count), (5, 1));
+    assert_eq!((blob.min_length, blob.max_length), (0, 16));
+    assert_eq!(blob.length_distribution, vec![(0, 1), (8, 3), (16, 1)]);
+    let kinds: Vec<&str> = blob.content_types.iter().map(|(k, _)| k.as_str()).collect();
+    for kind in ["jpeg", "png", "gzip", "protobuf", "empty"] {
+        assert!(kinds.contains(&kind), "{kinds:?}");
+    }
+    assert!(blob.entropy_bits_per_byte > 0.0 && blob.entropy_bits_per_byte <= 8.0);
+    // values are hashed by content, so each distinct blob counts once
+    assert_eq!(cols[0].cardinality.approximate_distinct, 5);
+    assert!(cols[0].frequency.as_ref().unwrap().top_values[0]
+        .value
+        .starts_with("0x"));
+
+    let id = cols[1].binary.as_ref().unwrap();
+    assert!((id.uuid_like_pct - 500.0 / 6.0).abs() < 1e-9);
+    assert_eq!(id.length_distribution, vec![(16, 6)]);
+    assert!(cols[1].string.is_none());
+}
+
+#[test]
+fn string_columns_report_semantic_pattern_matches() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Utf8, false)]));
+    let values = [
+        "ana@example.com",
+        "https://example.com/a?b=1",
+        "10.0.0.1",
+        "2001:db8::1",
+        "+1 (555) 123-4567",
+        r#"{"id": 7, "tags": ["a"]}"#,
+        "2024-01-15",
+        "123e4567-e89b-12d3-a456-426614174000",
+        "5551234567",
+        "{not json}",
+    ];
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(StringArray::from(values.to_vec()))],
+    )
+    .unwrap();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    let cols = profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(None)).unwrap();
+    let p = &cols[0].string.as_ref().unwrap().patterns;
+    assert_eq!(
+        (
+            p.email_like_pct,
+            p.url_like_pct,
+            p.ip_like_pct,
+            p.phone_like_pct,
+            p.json_like_pct,
+            p.iso_date_like_pct,
+            p.uuid_like_pct,
+            p.all_numeric_pct,
+        ),
+        (10.0, 10.0, 20.0, 10.0, 10.0, 10.0, 10.0, 10.0)
+    );
+    assert_eq!(p.matches()[0], ("ip", 20.0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn encoding_anomalies_are_counted_and_cost_quality() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("clean", DataType::Utf8, false),
+    ]));
+    let mut names: Vec<&str> = vec!["José"; 16];
+    names[0] = "JosÃ©"; // UTF-8 read back as Latin-1
+    names[1] = "itâ€™s";
+    names[2] = "tab\tok\u{1}";
+    names[3] = "bad \u{FFFD} byte";
+    names[4] = "p\u{430}ypal"; // Cyrillic a
+    names[5] = "iPhone 手机"; // mixed across words only
+    let clean = vec!["Zoë"; 16];
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(names)),
+            Arc::new(StringArray::from(clean)),
+        ],
+    )
+    .unwrap();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .with_full_scan(true)
+        .scan_options(scan_opts(None))
+        .run()
+        .await
+        .unwrap();
+    let fs = report.full_scan.as_ref().unwrap();
+    let e = &fs[0].string.as_ref().unwrap().encoding;
+    assert_eq!(
+        (
+            e.mojibake_count,
+            e.control_char_count,
+            e.replacement_char_count,
+            e.mixed_script_count,
+            e.affected_count
+        ),
+        (2, 1, 1, 1, 5)
+    );
+    assert_eq!(fs[1].string.as_ref().unwrap().encoding.affected_count, 0);
+
+    let scores = report.quality_scores();
+    let score = |col: &str| scores.iter().position(|s| s.column_name == col).unwrap();
+    let (name, clean) = (&scores[score("name")], &scores[score("clean")]);
+    assert!((name.encoding_anomaly_pct - 31.25).abs() < 1e-9);
+    assert_eq!(name.full_scan_penalty, 30);
+    assert_eq!(name.score + 30, clean.score);
+    assert!(name
+        .breakdown
+        .contains("encoding_anomalies=31.2% (mojibake=2 control=1 replacement=1 mixed_script=1)"));
+    // rescoring replaces the deduction instead of stacking another
+    let mut again = scores.to_vec();
+    apply_full_scan_findings(&mut again, fs);
+    assert_eq!(again[score("name")].score, name.score);
+    assert_eq!(again[score("name")].breakdown, name.breakdown);
+}
+
+#[test]
+fn nested_data_profiles_list_lengths_map_keys_and_struct_nulls() {
+    use arrow::array::{Int32Builder, ListBuilder, MapBuilder, StringBuilder, StructArray};
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    // tags: [], [1], [1,2,3], null, [1..=9]
+    let mut tags = ListBuilder::new(Int32Builder::new());
+    for list in [
+        Some(vec![]),
+        Some(vec![1]),
+        Some(vec![1, 2, 3]),
+        None,
+        Some((1..=9).collect()),
+    ] {
+        match list {
+            Some(items) => {
+                tags.values().append_slice(&items);
+                tags.append(true);
+            }
+            None => tags.append(false),
+        }
+    }
+    let tags = tags.finish();
+    // attrs: {a,b}, {a}, {}, {a,c}, {a}
+    let mut attrs = MapBuilder::new(None, StringBuilder::new(), Int32Builder::new());
+    for keys in [&["a", "b"][..], &["a"], &[], &["a", "c"], &["a"]] {
+        for k in keys {
+            attrs.keys().append_value(k);
+            attrs.values().append_value(1);
+        }
+        attrs.append(true).unwrap();
+    }
+    let attrs = attrs.finish();
+    // addr: {city, zip} with zip missing twice and one whole struct null
+    let city: arrow::array::ArrayRef = Arc::new(StringArray::from(vec![
+        Some("x"),
+        Some("y"),
+        None,
+        Some("z"),
+        Some("w"),
+    ]));
+    let zip: arrow::array::ArrayRef =
+        Arc::new(Int32Array::from(vec![Some(1), None, None, None, Some(5)]));
+    let addr = StructArray::try_new(
+        vec![
+            Arc::new(Field::new("city", DataType::Utf8, true)),
+            Arc::new(Field::new("zip", DataType::Int32, true)),
+        ]
+        .into(),
+        vec![city, zip],
+        Some(vec![true, true, false, true, true].into()),
+    )
+    .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("tags", tags.data_type().clone(), true),
+        Field::new("attrs", attrs.data_type().clone(), false),
+        Field::new("addr", addr.data_type().clone(), true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5])),
+            Arc::new(tags),
+            Arc::new(attrs),
+            Arc::new(addr),
+        ],
+    )
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(2)
+        .build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let data = parquet_lens_core::profile_nested_data(tmp.path(), &scan_opts(Some(2))).unwrap();
+    let list = data.lists.iter().find(|l| l.path == "tags").unwrap();
+    assert_eq!((list.count, list.null_count, list.empty_count), (4, 1, 1));
+    assert_eq!((list.min_length, list.max_length), (0, 9));
+    assert!((list.mean_length - 13.0 / 4.0).abs() < 1e-9);
+    assert_eq!(
+        list.length_distribution,
+        vec![(0, 1), (1, 1), (4, 1), (16, 1)]
+    );
+
+    let map = data.maps.iter().find(|m| m.path == "attrs").unwrap();
+    assert_eq!((map.count, map.entry_count), (5, 6));
+    assert_eq!(map.key_cardinality.approximate_distinct, 3);
+    let top = &map.top_keys.as_ref().unwrap().top_values[0];
+    assert_eq!((top.value.as_str(), top.count), ("a", 4));
+
+    let zip = data
+        .struct_fields
+        .iter()
+        .find(|f| f.path == "addr.zip")
+        .unwrap();
+    assert_eq!((zip.parent_rows, zip.null_count), (4, 2));
+    assert!((zip.null_percentage - 50.0).abs() < 1e-9);
+    let city = data
+        .struct_fields
+        .iter()
+        .find(|f| f.path == "addr.city")
+        .unwrap();
+    assert_eq!(city.null_count, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn profiler_builder_runs_whole_pipeline() {
+    let tmp = write_multi_rg_fixture();
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .sample(50.0)
+        .sample_seed(7)
+        .with_full_scan(true)
+        .histogram_bins(10)
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(report.dataset.total_rows, 1000);
+    assert_eq!(report.row_groups.len(), 10);
+    assert_eq!(report.quality.column_scores.len(), 2);
+    assert!(report.sample_note.is_some());
+    let scanned = report.full_scan.as_ref().unwrap();
+    let id = scanned.iter().find(|c| c.column_name == "id").unwrap();
+    // half the row groups at 100 rows each
+    assert_eq!(id.numeric.as_ref().unwrap().count, 500);
+    assert!(report.warnings.is_empty());
+
+    let missing = Profiler::builder()
+        .path("/nonexistent/*.parquet")
+        .run()
+        .await;
+    assert!(missing.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn profiler_scans_every_file_of_a_dataset() {
+    let dir = tempfile::tempdir().unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int32, true)]));
+    // disjoint halves: 0..500 in the first file, 500..1000 (a tenth null) in the second
+    for (name, vals) in [
+        ("a.parquet", (0..500).map(Some).collect::<Vec<_>>()),
+        (
+            "b.parquet",
+            (500..1000)
+                .map(|i| (i % 10 != 0).then_some(i))
+                .collect::<Vec<_>>(),
+        ),
+    ] {
+        let file = std::fs::File::create(dir.path().join(name)).unwrap();
+        let batch =
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(vals))]).unwrap();
+        let mut writer = ArrowWriter::try_new(file, schema.clone(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+    let report = Profiler::builder()
+        .path(dir.path().to_str().unwrap())
+        .with_full_scan(true)
+        .histogram_bins(4)
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(report.dataset.file_count, 2);
+    let stats = &report.column_stats[0];
+    assert_eq!(stats.total_null_count, 50);
+    assert!((stats.null_percentage - 5.0).abs() < 1e-9);
+    let v = &report.full_scan.as_ref().unwrap()[0];
+    let numeric = v.numeric.as_ref().unwrap();
+    assert_eq!(numeric.count, 950);
+    assert_eq!((numeric.min, numeric.max), (0.0, 999.0));
+    // merged sketch counts both halves, well past either file's 500
+    assert!(v.cardinality.approximate_distinct > 850);
+    let bins = v.histogram.as_ref().unwrap();
+    assert_eq!(bins.iter().map(|b| b.count).sum::<u64>(), 950);
+    assert!(bins[3].count > 0);
+}
+
+#[derive(Default)]
+struct RecordingSink(std::sync::Mutex<Vec<(String, Progress, Progress)>>); // op, total, finished
+
+impl ProgressSink for RecordingSink {
+    fn on_start(&self, operation: &str, total: Progress) {
+        let mut runs = self.0.lock().unwrap();
+        runs.push((operation.to_string(), total, Progress::default()));
+    }
+    fn on_finish(&self, operation: &str, done: Progress) {
+        let mut runs = self.0.lock().unwrap();
+        let run = runs.iter_mut().rev().find(|r| r.0 == operation).unwrap();
+        run.2 = done;
+    }
+}
+
+#[test]
+fn progress_sink_sees_every_operation_complete() {
+    let tmp = write_multi_rg_fixture();
+    let sink = Arc::new(RecordingSink::default());
+    let opts = ScanOptions {
+        progress: Some(sink.clone()),
+        ..scan_opts(Some(2))
+    };
+    profile_columns_with_timeout(tmp.path(), None, 10, None, &opts).unwrap();
+    detect_duplicates(tmp.path(), true, &[], &opts).unwrap();
+    filter_count(tmp.path(), &parse_predicate("id >= 0").unwrap(), &opts).unwrap();
+    let paths = vec![ParquetFilePath {
+        path: tmp.path().to_path_buf(),
+        partitions: Default::default(),
+    }];
+    read_metadata_parallel_with_progress(&paths, Some(sink.clone())).unwrap();
+
+    let runs = sink.0.lock().unwrap();
+    let ops: Vec<&str> = runs.iter().map(|r| r.0.as_str()).collect();
+    assert_eq!(ops, ["profile", "duplicates", "filter", "metadata"]);
+    for (op, total, done) in runs.iter() {
+        assert_eq!(done.rows, 1000, "{op}");
+        assert_eq!(done.bytes, total.bytes, "{op}");
+    }
+}
+
+#[derive(Default)]
+struct StepSink {
+    rows: std::sync::Mutex<Vec<u64>>,
+    row_groups: std::sync::Mutex<Vec<usize>>,
+}
+
+impl ProgressSink for StepSink {
+    fn on_progress(&self, _operation: &str, done: Progress) {
+        self.rows.lock().unwrap().push(done.rows);
+    }
+    fn on_row_group(&self, operation: &str, file: &str, row_group: usize) {
+        assert_eq!(operation, "profile");
+        assert!(file.ends_with(".parquet"));
+        self.row_groups.lock().unwrap().push(row_group);
+    }
+}
+
+#[test]
+fn profile_columns_reports_progress_per_batch_and_row_group() {
+    let tmp = write_multi_rg_fixture();
+    let sink = Arc::new(StepSink::default());
+    let opts = ScanOptions {
+        progress: Some(sink.clone()),
+        ..scan_opts(Some(2))
+    };
+    profile_columns_with_timeout(tmp.path(), None, 10, None, &opts).unwrap();
+    // one update per decoded batch, not a single jump at the end
+    let mut rows = sink.rows.lock().unwrap().clone();
+    assert!(rows.len() >= 10, "{rows:?}");
+    rows.sort_unstable();
+    assert_eq!(rows.last(), Some(&1000));
+    let mut rgs = sink.row_groups.lock().unwrap().clone();
+    rgs.sort_unstable();
+    assert_eq!(rgs, (0..10).collect::<Vec<_>>());
+}
+
+// flips the cancel flag as soon as the first batch is reported
+struct CancelOnProgress(Arc<std::sync::atomic::AtomicBool>, std::sync::Mutex<u64>);
+
+impl ProgressSink for CancelOnProgress {
+    fn on_progress(&self, _operation: &str, done: Progress) {
+        self.0.store(true, std::sync::atomic::Ordering::Relaxed);
+        *self.1.lock().unwrap() = done.rows;
+    }
+}
+
+#[test]
+fn cancelled_scans_stop_with_a_cancelled_error() {
+    let tmp = write_multi_rg_fixture();
+    let cancel = Arc::new(std::sync::atomic::AtomicBool::new(true));
+    let opts = ScanOptions {
+        cancel: Some(cancel.clone()),
+        ..scan_opts(Some(1))
+    };
+    let err = profile_columns_with_timeout(tmp.path(), None, 10, None, &opts).unwrap_err();
+    assert_eq!(err.code(), "cancelled");
+    let err = detect_duplicates(tmp.path(), true, &[], &opts).unwrap_err();
+    assert_eq!(err.code(), "cancelled");
+    let err = filter_count(tmp.path(), &parse_predicate("id >= 0").unwrap(), &opts).unwrap_err();
+    assert_eq!(err, "cancelled");
+
+    // set mid-scan: the remaining row groups are never read
+    cancel.store(false, std::sync::atomic::Ordering::Relaxed);
+    let sink = Arc::new(CancelOnProgress(cancel, Default::default()));
+    let opts = ScanOptions {
+        progress: Some(sink.clone()),
+        ..opts
+    };
+    let err = profile_columns_with_timeout(tmp.path(), None, 10, None, &opts).unwrap_err();
+    assert_eq!(err.code(), "cancelled");
+    assert!(*sink.1.lock().unwrap() < 1000);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn memory_limit_degrades_to_sketches_instead_of_failing() {
+    let tmp = write_multi_rg_fixture();
+    let exact = profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(None)).unwrap();
+    let tracker = Arc::new(MemoryTracker::with_limit(1));
+    let opts = ScanOptions {
+        memory: Some(tracker.clone()),
+        ..scan_opts(None)
+    };
+    let lean = profile_columns_with_timeout(tmp.path(), None, 10, None, &opts).unwrap();
+    assert!(tracker.over_limit());
+    for (e, l) in exact.iter().zip(&lean) {
+        let (ef, lf) = (e.frequency.as_ref().unwrap(), l.frequency.as_ref().unwrap());
+        assert!(!ef.approximate);
+        assert!(lf.approximate, "{}", l.column_name);
+        assert_eq!(lf.total_count, 1000);
+    }
+    // seven names fit the sketch, so their counts stay exact
+    let names = &lean[1].frequency.as_ref().unwrap().top_values;
+    assert_eq!(names.iter().map(|v| v.count).sum::<u64>(), 1000);
+    let (e, l) = (
+        exact[0].numeric.as_ref().unwrap(),
+        lean[0].numeric.as_ref().unwrap(),
+    );
+    assert_eq!((l.count, l.min, l.max), (1000, 0.0, 499.0));
+    assert!((l.p50 - e.p50).abs() < 25.0, "{} vs {}", l.p50, e.p50);
+    assert_eq!(
+        lean[0]
+            .histogram
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|b| b.count)
+            .sum::<u64>(),
+        1000
+    );
+
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .with_full_scan(true)
+        .scan_options(ScanOptions {
+            memory: Some(Arc::new(MemoryTracker::with_limit(1))),
+            ..scan_opts(None)
+        })
+        .run()
+        .await
+        .unwrap();
+    assert!(report.warnings.iter().any(|w| w.contains("memory limit")));
+}
+
+#[test]
+fn errors_are_categorized() {
+    let missing = profile_columns_with_timeout(
+        std::path::Path::new("/nonexistent/x.parquet"),
+        None,
+        10,
+        None,
+        &ScanOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(missing.code(), "not_found");
+    assert!(!missing.is_retryable());
+
+    let mut garbage = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut garbage, b"definitely not parquet").unwrap();
+    let corrupt =
+        detect_duplicates(garbage.path(), true, &[], &ScanOptions::default()).unwrap_err();
+    assert_eq!(corrupt.code(), "corrupt");
+    assert!(corrupt.to_string().contains("footer"));
+}
+
+#[test]
+fn corrupt_row_groups_are_quarantined() {
+    let tmp = write_multi_rg_fixture();
+    let (_, meta) = open_parquet_file(tmp.path()).unwrap();
+    // clobber the first page header of row group 3's `name` chunk
+    let chunk = meta.row_group(3).column(1);
+    let offset = chunk
+        .dictionary_page_offset()
+        .unwrap_or(chunk.data_page_offset());
+    let mut bytes = std::fs::read(tmp.path()).unwrap();
+    bytes[offset as usize..offset as usize + 16].fill(0xFF);
+    std::fs::write(tmp.path(), bytes).unwrap();
+    HandleRegistry::global().invalidate(tmp.path());
+
+    let strict = profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(None));
+    assert_eq!(strict.unwrap_err().code(), "corrupt");
+
+    let log = Arc::new(QuarantineLog::default());
+    let opts = ScanOptions {
+        quarantine: Some(log.clone()),
+        ..scan_opts(None)
+    };
+    let results = profile_columns_with_timeout(tmp.path(), None, 10, None, &opts).unwrap();
+    assert_eq!(results.len(), 2);
+    let report = log.report(1000).unwrap();
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].row_group, 3);
+    assert_eq!(report.entries[0].column.as_deref(), Some("name"));
+    assert_eq!(report.rows_affected, 100);
+    assert!((report.pct_rows_affected - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn row_counts_reconcile_with_footer() {
+    let tmp = write_multi_rg_fixture();
+    let check = reconcile_row_counts(tmp.path(), &scan_opts(Some(2))).unwrap();
+    assert!(check.is_consistent(), "{:?}", check.lines());
+    assert_eq!(check.footer_rows, 1000);
+    assert_eq!(check.decoded_rows, 1000);
+
+    // clobber every chunk of row group 7 so whichever column is decoded fails
+    let (_, meta) = open_parquet_file(tmp.path()).unwrap();
+    let mut bytes = std::fs::read(tmp.path()).unwrap();
+    for chunk in meta.row_group(7).columns() {
+        let offset = chunk
+            .dictionary_page_offset()
+            .unwrap_or(chunk.data_page_offset()) as usize;
+        bytes[offset..offset + 16].fill(0xFF);
+    }
+    std::fs::write(tmp.path(), bytes).unwrap();
+    HandleRegistry::global().invalidate(tmp.path());
+
+    let check = reconcile_row_counts(tmp.path(), &scan_opts(None)).unwrap();
+    assert!(!check.is_consistent());
+    assert_eq!(check.decoded_rows, 900);
+    assert_eq!(check.mismatches.len(), 1);
+    assert_eq!(check.mismatches[0].row_group, 7);
+    assert_eq!(check.mismatches[0].actual_rows, None);
+    assert!(check.mismatches[0].error.is_some());
+}
+
+#[test]
+fn row_level_sampling_draws_from_every_row_group() {
+    let tmp = write_multi_rg_fixture();
+    let cfg = |rows, percentage| SampleConfig {
+        percentage,
+        no_extrapolation: false,
+        seed: Some(3),
+        method: SampleMethod::Rows,
+        rows,
+        stratify_by: None,
+        allocation: StrataAllocation::default(),
+    };
+    let exact =
+        sample_row_groups(tmp.path(), &cfg(Some(100), 100.0), 10, &scan_opts(None)).unwrap();
+    assert_eq!(exact.sampled_rows, 100);
+    assert_eq!(exact.sampled_rg_count, 10);
+    assert!(exact.confidence_note.contains("100 of 1000 rows"));
+    // footer stats aren't extrapolated from the sample
+    assert_eq!(exact.agg_stats.len(), 2);
+
+    let again =
+        sample_row_groups(tmp.path(), &cfg(Some(100), 100.0), 10, &scan_opts(Some(2))).unwrap();
+    let distinct = |p: &parquet_lens_core::SampledProfile| {
+        p.profile_results
+            .iter()
+            .map(|r| r.cardinality.approximate_distinct)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(distinct(&exact), distinct(&again));
+
+    let bernoulli = sample_row_groups(tmp.path(), &cfg(None, 50.0), 10, &scan_opts(None)).unwrap();
+    assert!((400..=600).contains(&bernoulli.sampled_rows));
+}
+
+#[test]
+fn stratified_sampling_balances_rare_values() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("country", DataType::Utf8, true),
+    ]));
+    let countries: Vec<Option<&str>> = (0..1000)
+        .map(|i| match i % 100 {
+            0 => Some("SG"),
+            1..=9 => Some("DE"),
+            _ => Some("US"),
+        })
+        .collect();
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from((0..1000).collect::<Vec<i32>>())),
+            Arc::new(StringArray::from(countries)),
+        ],
+    )
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(100)
+        .build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let cfg = |allocation, rows| SampleConfig {
+        percentage: 10.0,
+        no_extrapolation: false,
+        seed: Some(7),
+        method: SampleMethod::Rows,
+        rows,
+        stratify_by: Some("country".into()),
+        allocation,
+    };
+    let sampled = |p: &parquet_lens_core::SampledProfile, value: &str| {
+        let s = p.strata.iter().find(|s| s.value == value).unwrap();
+        (s.sampled_rows, s.population_rows)
+    };
+
+    let equal = sample_row_groups(
+        tmp.path(),
+        &cfg(StrataAllocation::Equal, Some(30)),
+        10,
+        &scan_opts(None),
+    )
+    .unwrap();
+    assert_eq!(equal.strata.len(), 3);
+    assert_eq!(equal.strata[0].value, "US"); // largest first
+    assert_eq!(sampled(&equal, "SG"), (10, 10)); // rare stratum taken whole
+    assert!((4..=18).contains(&sampled(&equal, "DE").0));
+    assert!((4..=18).contains(&sampled(&equal, "US").0));
+    assert!(equal.confidence_note.contains("stratified by country"));
+
+    let proportional = sample_row_groups(
+        tmp.path(),
+        &cfg(StrataAllocation::Proportional, None),
+        10,
+        &scan_opts(Some(2)),
+    )
+    .unwrap();
+    let (us, _) = sampled(&proportional, "US");
+    let (de, _) = sampled(&proportional, "DE");
+    assert!((60..=120).contains(&us));
+    assert!(de < us / 4);
+    assert_eq!(
+        proportional.sampled_rows,
+        proportional
+            .strata
+            .iter()
+            .map(|s| s.sampled_rows)
+            .sum::<u64>()
+    );
+
+    let mut missing = cfg(StrataAllocation::Equal, Some(30));
+    missing.stratify_by = Some("region".into());
+    assert!(sample_row_groups(tmp.path(), &missing, 10, &scan_opts(None)).is_err());
+}
+
+#[test]
+fn partition_stratified_sampling_covers_every_partition() {
+    let dir = tempfile::tempdir().unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+    for (region, rows) in [("us", 800), ("eu", 150), ("ap", 50)] {
+        let part = dir.path().join(format!("region={region}"));
+        std::fs::create_dir(&part).unwrap();
+        let file = std::fs::File::create(part.join("part-0.parquet")).unwrap();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from((0..rows).collect::<Vec<i32>>()))],
+        )
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(file, schema.clone(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+    let paths = scan_directory(dir.path()).unwrap();
+    let cfg = |allocation, rows| SampleConfig {
+        percentage: 10.0,
+        no_extrapolation: false,
+        seed: Some(3),
+        method: SampleMethod::Rows,
+        rows,
+        stratify_by: Some("region".into()),
+        allocation,
+    };
+    let sampled = |p: &parquet_lens_core::SampledProfile, value: &str| {
+        let s = p.strata.iter().find(|s| s.value == value).unwrap();
+        (s.sampled_rows, s.population_rows)
+    };
+
+    let equal = sample_partitions(
+        &paths,
+        "region",
+        &cfg(StrataAllocation::Equal, Some(60)),
+        10,
+        &scan_opts(None),
+    )
+    .unwrap();
+    assert_eq!(equal.strata.len(), 3);
+    assert_eq!(equal.strata[0].value, "us"); // largest first
+    assert_eq!(sampled(&equal, "ap").1, 50);
+    for region in ["us", "eu", "ap"] {
+        assert!((8..=34).contains(&sampled(&equal, region).0), "{region}");
+    }
+    // rows from every partition reach the profile
+    let id = equal.profile_results[0].numeric.as_ref().unwrap();
+    assert_eq!(id.count, equal.sampled_rows);
+    assert!(equal
+        .confidence_note
+        .contains("stratified by partition region, 3 strata, equal allocation"));
+    assert_eq!(equal.total_rg_count, 3);
+
+    let proportional = sample_partitions(
+        &paths,
+        "region",
+        &cfg(StrataAllocation::Proportional, None),
+        10,
+        &scan_opts(Some(2)),
+    )
+    .unwrap();
+    let (us, _) = sampled(&proportional, "us");
+    let (ap, _) = sampled(&proportional, "ap");
+    assert!((50..=110).contains(&us));
+    assert!(ap < us / 4);
+    assert!(proportional.confidence_note.contains("of 1000 rows"));
+
+    assert!(sample_partitions(
+        &paths,
+        "country",
+        &cfg(StrataAllocation::Equal, None),
+        10,
+        &scan_opts(None)
+    )
+    .is_err());
+}
+
+#[test]
+fn sample_export_is_reproducible_from_manifest() {
+    let tmp = write_multi_rg_fixture();
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("sample.parquet");
+    let config = SampleConfig {
+        percentage: 100.0,
+        no_extrapolation: false,
+        seed: Some(11),
+        method: SampleMethod::Rows,
+        rows: Some(40),
+        stratify_by: None,
+        allocation: StrataAllocation::default(),
+    };
+    let manifest = export_sample(
+        tmp.path(),
+        &config,
+        &first,
+        &MaskSpec::default(),
+        &scan_opts(None),
+    )
+    .unwrap();
+    assert_eq!(manifest.sampled_rows, 40);
+    assert_eq!(manifest.source_rows, 1000);
+    assert_eq!(manifest.row_groups.len(), 10);
+    let (exported, _) = open_parquet_file(&first).unwrap();
+    assert_eq!(exported.row_count, 40);
+
+    let loaded = SampleManifest::load(&sample_manifest_path(&first)).unwrap();
+    assert_eq!(loaded.seed, 11);
+    let second = dir.path().join("replay.parquet");
+    export_sample(
+        tmp.path(),
+        &loaded.config(),
+        &second,
+        &MaskSpec::default(),
+        &scan_opts(Some(2)),
+    )
+    .unwrap();
+    assert_eq!(
+        std::fs::read(&first).unwrap(),
+        std::fs::read(&second).unwrap()
+    );
+
+    let by_rg = SampleConfig {
+        percentage: 30.0,
+        method: SampleMethod::RowGroups,
+        rows: None,
+        ..config
+    };
+    let third = dir.path().join("row_groups.parquet");
+    let mask = MaskSpec::parse("name:partial,id:null").unwrap();
+    let manifest = export_sample(tmp.path(), &by_rg, &third, &mask, &scan_opts(None)).unwrap();
+    assert_eq!(manifest.row_groups.len(), 3);
+    assert_eq!(manifest.sampled_rows, 300);
+    assert_eq!(manifest.masked, mask.rules);
+    let batches: Vec<RecordBatch> = HandleRegistry::global()
+        .get(&third)
+        .unwrap()
+        .reader_builder()
+        .unwrap()
+        .build()
+        .unwrap()
+        .map(|b| b.unwrap())
+        .collect();
+    let names = batches[0].column(1).as_string::<i32>();
+    assert!(names.iter().flatten().all(|n| n.starts_with('*')));
+    assert!(batches
+        .iter()
+        .all(|b| b.column(0).null_count() == b.num_rows()));
+
+    let typo = MaskSpec::parse("nmae").unwrap();
+    assert!(export_sample(tmp.path(), &by_rg, &third, &typo, &scan_opts(None)).is_err());
+}
+
+#[test]
+fn large_files_are_auto_sampled_with_per_metric_confidence() {
+    let tmp = write_multi_rg_fixture();
+    let size = std::fs::metadata(tmp.path()).unwrap().len();
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .with_full_scan(true)
+        .auto_sample(Some(size / 4), 120)
+        .sample_seed(5)
+        .run_blocking()
+        .unwrap();
+    let note = report.sample_note.as_deref().unwrap();
+    assert!(note.starts_with("auto sample:"), "{note}");
+    assert!(note.contains("of 10 row groups"), "{note}");
+    // footer null counts cover the whole file; the mean is estimated
+    let null_rate = report
+        .sample_confidence
+        .iter()
+        .find(|m| m.column == "id" && m.metric == "null_rate")
+        .unwrap();
+    assert!(null_rate.exact);
+    let mean = report
+        .sample_confidence
+        .iter()
+        .find(|m| m.column == "id" && m.metric == "mean")
+        .unwrap();
+    assert!(!mean.exact);
+    let margin = mean.margin.unwrap();
+    assert!(margin > 0.0 && (mean.estimate - 249.5).abs() < 3.0 * margin);
+
+    let below = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .with_full_scan(true)
+        .auto_sample(Some(size * 2), 120)
+        .run_blocking()
+        .unwrap();
+    assert!(below.sample_note.is_none());
+    assert!(below.sample_confidence.is_empty());
+}
+
+#[test]
+fn fake_masking_keeps_column_shapes() {
+    let tmp = write_multi_rg_fixture();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("fake.parquet");
+    let config = SampleConfig {
+        percentage: 50.0,
+        no_extrapolation: false,
+        seed: Some(2),
+        method: SampleMethod::RowGroups,
+        rows: None,
+        stratify_by: None,
+        allocation: StrataAllocation::default(),
+    };
+    let mask = MaskSpec::parse("id:fake,name:fake").unwrap();
+    export_sample(tmp.path(), &config, &out, &mask, &scan_opts(None)).unwrap();
+    let batches: Vec<RecordBatch> = HandleRegistry::global()
+        .get(&out)
+        .unwrap()
+        .reader_builder()
+        .unwrap()
+        .build()
+        .unwrap()
+        .map(|b| b.unwrap())
+        .collect();
+    assert_eq!(batches[0].schema().field(0).data_type(), &DataType::Int32);
+    let ids: Vec<i32> = batches
+        .iter()
+        .flat_map(|b| {
+            b.column(0)
+                .as_primitive::<arrow::datatypes::Int32Type>()
+                .values()
+                .to_vec()
+        })
+        .collect();
+    assert_eq!(ids.len(), 500);
+    assert!(ids.iter().all(|&i| (0..=499).contains(&i)));
+    // the fixture's ids repeat in order; fakes don't
+    assert_ne!(ids[..10], [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    // seven common categories are reused, each about as often as in the source
+    let names: Vec<String> = batches
+        .iter()
+        .flat_map(|b| {
+            let col = b.column(1).as_string::<i32>();
+            col.iter()
+                .map(|v| v.unwrap().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let n0 = names.iter().filter(|n| n.as_str() == "n0").count();
+    assert!(names.iter().all(|n| n.len() == 2 && n.starts_with('n')));
+    assert!((40..=110).contains(&n0), "{n0}");
+}
+
+#[test]
+fn profile_bytes_matches_file_report() {
+    let tmp = write_multi_rg_fixture();
+    let from_file = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .run_blocking()
+        .unwrap();
+    let data = std::fs::read(tmp.path()).unwrap();
+    let from_bytes = parquet_lens_core::profile_bytes("upload.parquet", data).unwrap();
+    assert_eq!(from_bytes.dataset.total_rows, 1000);
+    assert_eq!(from_bytes.row_groups.len(), 10);
+    assert_eq!(
+        from_bytes.file_info.file_size,
+        from_file.file_info.file_size
+    );
+    assert_eq!(
+        from_bytes.quality.overall_score,
+        from_file.quality.overall_score
+    );
+    assert_eq!(from_bytes.column_stats.len(), from_file.column_stats.len());
+
+    let err = parquet_lens_core::profile_bytes("junk", b"not parquet".to_vec()).unwrap_err();
+    assert_eq!(err.code(), "corrupt");
+}
+
+#[test]
+fn prometheus_metrics_label_dataset_and_columns() {
+    let tmp = write_fixture();
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .run_blocking()
+        .unwrap();
+    let text = parquet_lens_core::prometheus_metrics(&report, "s3://b/\"q\"");
+    assert!(text.contains("# TYPE parquet_rows gauge\n"));
+    assert!(text.contains(r#"parquet_rows{dataset="s3://b/\"q\""} 3"#));
+    let name_nulls = text
+        .lines()
+        .find(|l| l.starts_with("parquet_null_pct") && l.contains(r#"column="name""#))
+        .unwrap();
+    let value: f64 = name_nulls.rsplit(' ').next().unwrap().parse().unwrap();
+    assert!((value - 100.0 / 3.0).abs() < 1e-9);
+    assert!(text.contains("parquet_quality_score{"));
+}
+
+#[test]
+fn openlineage_event_carries_schema_and_quality_facets() {
+    let tmp = write_fixture();
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .run_blocking()
+        .unwrap();
+    let run = parquet_lens_core::LineageRun {
+        namespace: "team",
+        job_name: "parquet-lens.check",
+        run_id: "0190f0c4-0000-7000-8000-000000000000",
+        event_time: "2026-01-01T00:00:00Z",
+    };
+    let event = parquet_lens_core::openlineage_event(&report, "s3://bucket/a/b.parquet", &run);
+    assert_eq!(event["eventType"], "COMPLETE");
+    assert_eq!(event["job"]["namespace"], "team");
+    let input = &event["inputs"][0];
+    assert_eq!(input["namespace"], "s3://bucket");
+    assert_eq!(input["name"], "a/b.parquet");
+    let fields = input["facets"]["schema"]["fields"].as_array().unwrap();
+    assert_eq!(fields.len(), 2);
+    let metrics = &input["inputFacets"]["dataQualityMetrics"];
+    assert_eq!(metrics["rowCount"], 3);
+    assert_eq!(metrics["columnMetrics"]["name"]["nullCount"], 1);
+    let quality = &input["inputFacets"]["parquetLens_quality"];
+    assert_eq!(quality["overallScore"], report.quality.overall_score);
+
+    let local = parquet_lens_core::openlineage_event(&report, "data/x.parquet", &run);
+    assert_eq!(local["inputs"][0]["namespace"], "file");
+    assert!(local["inputs"][0]["name"]
+        .as_str()
+        .unwrap()
+        .ends_with("data/x.parquet"));
+}
+
+#[test]
+fn great_expectations_suite_reflects_observed_values() {
+    let tmp = write_fixture();
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .with_full_scan(true)
+        .run_blocking()
+        .unwrap();
+    let suite = parquet_lens_core::great_expectations_suite(&report, "fixture");
+    assert_eq!(suite["expectation_suite_name"], "fixture");
+    let find = |kind: &str, column: &str| {
+        suite["expectations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|e| e["expectation_type"] == kind && e["kwargs"]["column"] == column)
+            .map(|e| e["kwargs"].clone())
+    };
+    let id_not_null = find("expect_column_values_to_not_be_null", "id").unwrap();
+    assert!(id_not_null.get("mostly").is_none());
+    let name_not_null = find("expect_column_values_to_not_be_null", "name").unwrap();
+    assert_eq!(name_not_null["mostly"], 0.6666);
+    let range = find("expect_column_values_to_be_between", "id").unwrap();
+    assert_eq!(range["min_value"], 1.0);
+    assert_eq!(range["max_value"], 3.0);
+    let set = find("expect_column_values_to_be_in_set", "id").unwrap();
+    assert_eq!(set["value_set"], serde_json::json!([1, 2, 3]));
+}
+
+#[test]
+fn masked_exports_redact_value_sets() {
+    let tmp = write_multi_rg_fixture();
+    let mut report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .with_full_scan(true)
+        .run_blocking()
+        .unwrap();
+    let mask = MaskSpec::parse("name,id:null").unwrap();
+    report.mask_values(&mask);
+    let suite = parquet_lens_core::great_expectations_suite(&report, "fixture");
+    let sets: Vec<&serde_json::Value> = suite["expectations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["expectation_type"] == "expect_column_values_to_be_in_set")
+        .collect();
+    // the nulled column lists no values, so it gets no set at all
+    assert_eq!(sets.len(), 1);
+    assert_eq!(sets[0]["kwargs"]["column"], "name");
+    let values = sets[0]["kwargs"]["value_set"].as_array().unwrap();
+    assert_eq!(values.len(), 7);
+    assert!(values.iter().all(|v| v.as_str().unwrap().len() == 32));
+
+    let out = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+    export_json(out.path(), &report).unwrap();
+    let json = std::fs::read_to_string(out.path()).unwrap();
+    assert!(!json.contains("\"n0\""));
+
+    let profiles = report.full_scan.as_deref().unwrap();
+    let paths = vec![tmp.path().to_path_buf()];
+    let mut tables = distinct_values(&paths, profiles, 50, &scan_opts(None)).unwrap();
+    mask_top_values(&mut tables, &mask);
+    assert_eq!(tables[0].column, "name");
+    assert!(tables[0].values.iter().all(|v| v.value.len() == 32));
+}
+
+#[test]
+fn metadata_only_skips_page_decoding_analyses() {
+    let tmp = write_multi_rg_fixture();
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .metadata_only(true)
+        .with_full_scan(true)
+        .sample(50.0)
+        .run_blocking()
+        .unwrap();
+    assert!(report.full_scan.is_none());
+    assert!(report.sample_note.is_none());
+    assert_eq!(report.dataset.total_rows, 1000);
+    assert_eq!(report.row_groups.len(), 10);
+}
+
+#[test]
+fn history_store_appends_and_filters_by_dataset() {
+    let tmp = write_fixture();
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .run_blocking()
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let store = HistoryStore::new(dir.path().join("nested").join("history.jsonl"));
+    assert!(store.entries(None).unwrap().is_empty());
+    store
+        .append(&HistoryEntry::from_report("a", 1, &report))
+        .unwrap();
+    store
+        .append(&HistoryEntry::from_report("b", 2, &report))
+        .unwrap();
+    store
+        .append(&HistoryEntry::from_report("a", 3, &report))
+        .unwrap();
+    let a = store.entries(Some("a")).unwrap();
+    assert_eq!(a.iter().map(|e| e.captured_at).collect::<Vec<_>>(), [1, 3]);
+    assert_eq!(a[0].total_rows, 3);
+    assert_eq!(store.entries(None).unwrap().len(), 3);
+}
+
+#[test]
+fn exported_report_round_trips() {
+    let tmp = write_multi_rg_fixture();
+    let mut report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .run_blocking()
+        .unwrap();
+    report.retain_columns(&["name".to_string()]);
+    assert_eq!(report.column_stats.len(), 1);
+    assert_eq!(report.quality_scores().len(), 1);
+
+    let out = tempfile::tempdir().unwrap();
+    let json = out.path().join("profile.json");
+    export_json(&json, &report).unwrap();
+    let back: ProfileReport =
+        serde_json::from_str(&std::fs::read_to_string(&json).unwrap()).unwrap();
+    assert_eq!(back.dataset.total_rows, report.dataset.total_rows);
+    assert_eq!(back.column_stats[0].column_name, "name");
+    assert_eq!(back.quality.overall_score, report.quality.overall_score);
+
+    let ndjson = out.path().join("profile.ndjson");
+    export_ndjson(&ndjson, &report).unwrap();
+    // the run line, then the one remaining column
+    assert_eq!(std::fs::read_to_string(&ndjson).unwrap().lines().count(), 2);
+}
+
+#[test]
+fn reports_carry_run_metadata() {
+    let tmp = write_multi_rg_fixture();
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .with_full_scan(true)
+        .run_blocking()
+        .unwrap();
+    let run = report.run.as_ref().unwrap();
+    assert_eq!(run.tool_version, env!("CARGO_PKG_VERSION"));
+    assert!(run.finished_at >= run.started_at);
+    let stages: Vec<&str> = run.stages.iter().map(|s| s.stage.as_str()).collect();
+    assert_eq!(
+        stages,
+        [
+            "metadata",
+            "full_scan",
+            "stats",
+            "timeseries_nested",
+            "fingerprint"
+        ]
+    );
+    let size = std::fs::metadata(tmp.path()).unwrap().len();
+    assert_eq!(run.inputs.len(), 1);
+    assert_eq!(run.inputs[0].size, size);
+    assert!(run.inputs[0].last_modified.is_some());
+
+    let out = tempfile::tempdir().unwrap();
+    export_csv(&out.path().join("profile.csv"), &report).unwrap();
+    let run_csv = std::fs::read_to_string(out.path().join("run.csv")).unwrap();
+    assert!(run_csv.starts_with("key,value\ntool_version,"));
+    assert!(run_csv.contains(&format!("input.0.size,{size}\n")));
+    assert!(run_csv.contains("stage.full_scan_ms,"));
+
+    let ndjson = out.path().join("profile.ndjson");
+    export_ndjson(&ndjson, &report).unwrap();
+    let ndjson = std::fs::read_to_string(ndjson).unwrap();
+    let lines: Vec<serde_json::Value> = ndjson
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 1 + report.column_stats.len());
+    assert_eq!(lines[0]["run"]["tool_version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(lines[0]["run"]["inputs"][0]["size"], size);
+    assert!(lines[1..].iter().all(|l| l.get("column_name").is_some()));
+}
+
+#[test]
+fn columns_table_aligns_per_column_stats() {
+    let tmp = write_multi_rg_fixture();
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .with_full_scan(true)
+        .run_blocking()
+        .unwrap();
+    let table = columns_table(&report, &NumberFormat::default());
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("column  type"));
+    // distinct falls back to the full scan's estimate; column order isn't fixed
+    let row = lines.iter().find(|l| l.starts_with("name ")).unwrap();
+    let name: Vec<&str> = row.split_whitespace().collect();
+    assert_eq!(name[..4], ["name", "String", "0.0", "7"]);
+    assert_eq!(name.last(), Some(&"100"));
+    // right-aligned numbers end at the same offset as their header
+    let header_end = lines[0].find("null %").unwrap() + "null %".len();
+    assert_eq!(&lines[1][header_end - 3..header_end], "0.0");
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(flavor = "multi_thread")]
+async fn async_profile_matches_sync_scan() {
+    use parquet_lens_core::profile_columns_async;
+    let tmp = write_multi_rg_fixture();
+    let uri = tmp.path().to_str().unwrap();
+    let cols = vec!["name".to_string()];
+    let remote = profile_columns_async(uri, Some(&cols), 10, &scan_opts(None), None)
+        .await
+        .unwrap();
+    let local =
+        profile_columns_with_timeout(tmp.path(), Some(&cols), 10, None, &scan_opts(Some(1)))
+            .unwrap();
+    assert_eq!(remote.len(), 1);
+    assert_eq!(remote[0].column_name, "name");
+    assert_eq!(
+        remote[0].cardinality.approximate_distinct,
+        local[0].cardinality.approximate_distinct
+    );
+    assert_eq!(remote[0].string.as_ref().unwrap().count, 1000);
+}
+
+#[test]
+fn profile_cache_reuses_and_invalidates() {
+    let tmp = write_multi_rg_fixture();
+    let dir = tempfile::tempdir().unwrap();
+    let cache = ProfileCache::new(dir.path().to_path_buf());
+    let name = vec!["name".to_string()];
+    let first = cache
+        .profile_columns(tmp.path(), Some(&name), 10, None, &scan_opts(None))
+        .unwrap();
+    assert_eq!(first.len(), 1);
+    // full scan only needs to fill in "id"; both come back in file order
+    let all = cache
+        .profile_columns(tmp.path(), None, 10, None, &scan_opts(None))
+        .unwrap();
+    let names: Vec<&str> = all.iter().map(|c| c.column_name.as_str()).collect();
+    assert_eq!(names, ["id", "name"]);
+    let key = CacheKey::for_file(tmp.path(), "bins=10").unwrap();
+    assert_eq!(key.size, std::fs::metadata(tmp.path()).unwrap().len());
+
+    // rewriting the file changes its size, so the old entry no longer matches
+    let small = write_fixture();
+    std::fs::copy(small.path(), tmp.path()).unwrap();
+    let fresh = cache
+        .profile_columns(tmp.path(), None, 10, None, &scan_opts(None))
+        .unwrap();
+    let id = fresh.iter().find(|c| c.column_name == "id").unwrap();
+    assert_eq!(id.numeric.as_ref().unwrap().count, 3);
+    assert!(cache.clear().unwrap() >= 1);
+}
+
+#[test]
+fn profile_cache_serves_file_stats_without_reading_the_footer() {
+    let tmp = write_multi_rg_fixture();
+    let dir = tempfile::tempdir().unwrap();
+    let cache = ProfileCache::new(dir.path().to_path_buf());
+    let first = cache.file_stats(tmp.path()).unwrap();
+    assert_eq!(first.info.row_count, 1000);
+    assert_eq!(first.row_groups.len(), first.info.row_group_count);
+
+    // same size and mtime but no longer Parquet: only a cache hit can answer
+    let modified = std::fs::metadata(tmp.path()).unwrap().modified().unwrap();
+    let len = std::fs::metadata(tmp.path()).unwrap().len() as usize;
+    std::fs::write(tmp.path(), vec![0u8; len]).unwrap();
+    tmp.as_file().set_modified(modified).unwrap();
+    HandleRegistry::global().invalidate(tmp.path());
+    let cached = cache.file_stats(tmp.path()).unwrap();
+    assert_eq!(cached.info.row_count, 1000);
+    assert_eq!(cached.column_stats.len(), first.column_stats.len());
+    assert!(
+        ProfileCache::new(tempfile::tempdir().unwrap().path().to_path_buf())
+            .file_stats(tmp.path())
+            .is_err()
+    );
+}
+
+#[test]
+fn profile_cache_keys_datasets_by_every_file_and_content() {
+    let (a, b) = (write_multi_rg_fixture(), write_multi_rg_fixture());
+    let paths: Vec<ParquetFilePath> = [&a, &b]
+        .iter()
+        .map(|f| ParquetFilePath {
+            path: f.path().to_path_buf(),
+            partitions: Default::default(),
+        })
+        .collect();
+    let dir = tempfile::tempdir().unwrap();
+    let cache = ProfileCache::new(dir.path().to_path_buf());
+    let id_count = |results: &[parquet_lens_core::ColumnProfileResult]| {
+        let id = results.iter().find(|c| c.column_name == "id").unwrap();
+        id.numeric.as_ref().unwrap().count
+    };
+    let first = cache
+        .profile_dataset(&paths, None, 10, None, &scan_opts(None))
+        .unwrap();
+    assert_eq!(id_count(&first), 2000);
+    let stored = std::fs::read_dir(dir.path())
+        .unwrap()
+        .filter(|e| {
+            let name = e.as_ref().unwrap().file_name();
+            name.to_string_lossy().starts_with("dataset_")
+        })
+        .count();
+    assert_eq!(stored, 1);
+
+    // changing either file invalidates the merged entry
+    let small = write_fixture();
+    std::fs::copy(small.path(), b.path()).unwrap();
+    let fresh = cache
+        .profile_dataset(&paths, None, 10, None, &scan_opts(None))
+        .unwrap();
+    assert_eq!(id_count(&fresh), 1003);
+
+    // a content key survives a touch that changes the mtime key
+    let by_content = CacheKey::for_file_contents(a.path(), "bins=10").unwrap();
+    let by_mtime = CacheKey::for_file(a.path(), "bins=10").unwrap();
+    let earlier = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+    a.as_file().set_modified(earlier).unwrap();
+    assert_eq!(
+        CacheKey::for_file_contents(a.path(), "bins=10").unwrap(),
+        by_content
+    );
+    assert_ne!(CacheKey::for_file(a.path(), "bins=10").unwrap(), by_mtime);
+}
+
+#[test]
+fn handle_registry_shares_footer_until_file_changes() {
+    let tmp = write_multi_rg_fixture();
+    let registry = HandleRegistry::default();
+    let a = registry.get(tmp.path()).unwrap();
+    let b = registry.get(tmp.path()).unwrap();
+    assert!(Arc::ptr_eq(a.metadata(), b.metadata()));
+    assert_eq!(a.file_info().row_group_count, 10);
+    let batch = a.reader_builder().unwrap().build().unwrap().next().unwrap();
+    assert_eq!(batch.unwrap().num_columns(), 2);
+
+    let small = write_fixture();
+    std::fs::copy(small.path(), tmp.path()).unwrap();
+    let c = registry.get(tmp.path()).unwrap();
+    assert!(!Arc::ptr_eq(a.metadata(), c.metadata()));
+    assert_eq!(c.file_info().row_count, 3);
+}
+
+// a user-provided backend serving fixture bytes under mem://
+struct MemBackend(bytes::Bytes);
+
+impl StorageBackend for MemBackend {
+    fn name(&self) -> &str {
+        "mem"
+    }
+    fn handles(&self, uri: &str) -> bool {
+        uri.starts_with("mem://")
+    }
+    fn list<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> futures::future::BoxFuture<'a, parquet_lens_core::Result<Vec<String>>> {
+        Box::pin(async move { Ok(vec![format!("{prefix}/part-0.parquet")]) })
+    }
+    fn metadata<'a>(
+        &'a self,
+        uri: &'a str,
+    ) -> futures::future::BoxFuture<'a, parquet_lens_core::Result<parquet_lens_core::ObjectInfo>>
+    {
+        Box::pin(async move {
+            Ok(parquet_lens_core::ObjectInfo {
+                uri: uri.to_string(),
+                size: self.0.len() as u64,
+                last_modified: None,
+                etag: None,
+            })
+        })
+    }
+    fn read_range<'a>(
+        &'a self,
+        _uri: &'a str,
+        range: std::ops::Range<u64>,
+    ) -> futures::future::BoxFuture<'a, parquet_lens_core::Result<bytes::Bytes>> {
+        Box::pin(async move { Ok(self.0.slice(range.start as usize..range.end as usize)) })
+    }
+}
+
+#[tokio::test]
+async fn custom_storage_backend_serves_listing_and_footer() {
+    let tmp = write_fixture();
+    let data = bytes::Bytes::from(std::fs::read(tmp.path()).unwrap());
+    StorageRegistry::global().register(Arc::new(MemBackend(data.clone())));
+
+    let paths = parquet_lens_core::resolve_paths("mem://bucket")
+        .await
+        .unwrap();
+    assert_eq!(paths.len(), 1);
+    let uri = paths[0].path.to_str().unwrap();
+    let (info, meta) = parquet_lens_core::open_parquet_auto(uri, None)
+        .await
+        .unwrap();
+    assert_eq!(info.row_count, 3);
+    assert_eq!(info.file_size, data.len() as u64);
+    assert_eq!(info.schema_fields.len(), 2);
+    assert_eq!(meta.num_row_groups(), 1);
+
+    let err = parquet_lens_core::open_parquet_auto("nope://bucket/x.parquet", None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "unsupported");
+}
+
+#[tokio::test]
+async fn download_object_streams_a_filterable_copy() {
+    let tmp = write_multi_rg_fixture();
+    let data = bytes::Bytes::from(std::fs::read(tmp.path()).unwrap());
+    let backend = MemBackend(data.clone());
+    let mut copy = NamedTempFile::new().unwrap();
+    let written =
+        parquet_lens_core::download_object(&backend, "mem://bucket/part-0.parquet", &mut copy)
+            .await
+            .unwrap();
+    assert_eq!(written, data.len() as u64);
+    assert_eq!(std::fs::read(copy.path()).unwrap(), data);
+
+    let predicate = parse_predicate("id < 10").unwrap();
+    let local = filter_count(tmp.path(), &predicate, &scan_opts(None)).unwrap();
+    let copied = filter_count(copy.path(), &predicate, &scan_opts(None)).unwrap();
+    assert_eq!(copied.matched_rows, local.matched_rows);
+    assert_eq!(copied.scanned_rows, local.scanned_rows);
+}
+
+#[tokio::test]
+async fn full_scan_of_a_remote_object_reads_a_downloaded_copy() {
+    let tmp = write_fixture();
+    let data = bytes::Bytes::from(std::fs::read(tmp.path()).unwrap());
+    // no object store serves mem://, so only a streamed local copy can be scanned
+    StorageRegistry::global().register(Arc::new(MemBackend(data)));
+    let report = Profiler::builder()
+        .path("mem://bucket")
+        .with_full_scan(true)
+        .scan_options(scan_opts(None))
+        .run()
+        .await
+        .unwrap();
+    let full_scan = report.full_scan.unwrap();
+    let local = profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(None)).unwrap();
+    assert_eq!(full_scan.len(), local.len());
+    for (remote, local) in full_scan.iter().zip(&local) {
+        assert_eq!(remote.column_name, local.column_name);
+        assert_eq!(
+            remote.cardinality.approximate_distinct,
+            local.cardinality.approximate_distinct
+        );
+    }
+}
+
+#[test]
+fn masked_top_values_are_hashed_or_nulled() {
+    let tmp = write_multi_rg_fixture();
+    let paths = vec![tmp.path().to_path_buf()];
+    let mut tables = top_values(&paths, None, 0, &scan_opts(None)).unwrap();
+    mask_top_values(&mut tables, &MaskSpec::parse("name,id:null").unwrap());
+    let name = tables.iter().find(|t| t.column == "name").unwrap();
+    assert_eq!(name.values.len(), 7);
+    assert!(name
+        .values
+        .iter()
+        .all(|v| v.value.len() == 32 && !v.value.starts_with('n')));
+    assert_eq!(name.values.iter().map(|v| v.count).sum::<u64>(), 1000);
+    let id = tables.iter().find(|t| t.column == "id").unwrap();
+    assert!(id.values.is_empty());
+
+    let out = NamedTempFile::new().unwrap();
+    write_top_values_csv(out.path(), &tables).unwrap();
+    let csv = std::fs::read_to_string(out.path()).unwrap();
+    assert!(!csv.contains(",n0,"));
+    assert_eq!(csv.lines().count(), 1 + 7);
+}
+
+#[test]
+fn top_values_export_counts_every_row() {
+    let tmp = write_multi_rg_fixture();
+    let paths = vec![tmp.path().to_path_buf()];
+    let cols = vec!["name".to_string()];
+    let tables = top_values(&paths, Some(&cols), 3, &scan_opts(Some(2))).unwrap();
+    assert_eq!(tables.len(), 1);
+    let name = &tables[0];
+    assert_eq!(name.non_null_count, 1000);
+    assert_eq!(name.distinct, Some(7));
+    assert_eq!(name.values.len(), 3);
+    assert!(!name.is_complete());
+    // ids 0..500 twice, so n0..n2 hold 144 rows and the rest 142; ties list by value
+    assert_eq!(name.values[0].value, "n0");
+    assert_eq!(name.values[0].count, 144);
+
+    // k = 0 keeps the whole inventory, summing to every counted row
+    let all = top_values(&paths, None, 0, &scan_opts(None)).unwrap();
+    assert_eq!(all.len(), 2);
+    let name = all.iter().find(|t| t.column == "name").unwrap();
+    assert!(name.is_complete());
+    assert_eq!(name.values.iter().map(|v| v.count).sum::<u64>(), 1000);
+
+    let out = NamedTempFile::new().unwrap();
+    write_top_values_csv(out.path(), &tables).unwrap();
+    let csv = std::fs::read_to_string(out.path()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "column,value,count,pct");
+    assert_eq!(lines[1], "name,n0,144,14.4000");
+    assert_eq!(lines.len(), 4);
+
+    let missing = vec!["nope".to_string()];
+    let err = top_values(&paths, Some(&missing), 3, &scan_opts(None)).unwrap_err();
+    assert_eq!(err.code(), "not_found");
+}
+
+#[test]
+fn per_file_cardinality_sketches_union_across_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+    // the two files share ids 500..1000
+    let mut paths = Vec::new();
+    for (name, ids) in [("a.parquet", 0..1000), ("b.parquet", 500..1500)] {
+        let path = dir.path().join(name);
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(ids.collect::<Vec<i32>>()))],
+        )
+        .unwrap();
+        let file = std::fs::File::create(&path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, schema.clone(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        paths.push(ParquetFilePath {
+            path,
+            partitions: Default::default(),
+        });
+    }
+    let cache = ProfileCache::new(dir.path().join("cache"));
+    let scan = |p: &ParquetFilePath| {
+        cache
+            .profile_columns(&p.path, None, 10, None, &scan_opts(None))
+            .unwrap()
+    };
+    for p in &paths {
+        scan(p);
+    }
+    // the second pass is served from the cache, which keeps the sketches
+    let per_file: Vec<Vec<parquet_lens_core::ColumnProfileResult>> =
+        paths.iter().map(scan).collect();
+    assert!(per_file.iter().all(|f| f[0].cardinality.sketch.is_some()));
+    // reports carry the estimate, never the registers
+    let json = serde_json::to_value(&per_file[0]).unwrap();
+    assert!(json[0]["cardinality"].get("sketch").is_none());
+    assert!(json[0]["cardinality"]["approximate_distinct"].is_u64());
+    let merged = union_cardinality(per_file.iter().map(|f| f.as_slice()));
+    assert_eq!(merged.len(), 1);
+    let (name, ids) = &merged[0];
+    assert_eq!(name, "id");
+    assert!((1450..=1550).contains(&ids.approximate_distinct), "{ids:?}");
+    // the same registers a single scan over both files ends up with
+    let dataset = profile_dataset(&paths, None, 10, None, &scan_opts(None)).unwrap();
+    assert_eq!(
+        dataset[0].cardinality.approximate_distinct,
+        ids.approximate_distinct
+    );
+
+    // without a sketch on one side, merging falls back to the sum
+    let mut summed = per_file[0][0].cardinality.clone();
+    let mut bare = per_file[1][0].cardinality.clone();
+    bare.sketch = None;
+    summed.merge(&bare);
+    assert!(summed.sketch.is_none());
+    assert!(summed.approximate_distinct > 1900);
+}
+
+#[test]
+fn distinct_values_list_every_value_of_low_cardinality_columns() {
+    let tmp = write_multi_rg_fixture();
+    let paths = vec![tmp.path().to_path_buf()];
+    let profiles =
+        profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(None)).unwrap();
+    // 500 distinct ids stay over the limit; the 7 names are listed in full
+    let tables = distinct_values(&paths, &profiles, 50, &scan_opts(Some(2))).unwrap();
+    assert_eq!(tables.len(), 1);
+    let name = &tables[0];
+    assert_eq!(name.column, "name");
+    assert!(name.is_complete());
+    assert_eq!(name.values.len(), 7);
+    assert_eq!(name.values.iter().map(|v| v.count).sum::<u64>(), 1000);
+
+    let both = distinct_values(&paths, &profiles, 1000, &scan_opts(None)).unwrap();
+    assert_eq!(both.len(), 2);
+    assert!(distinct_values(&paths, &profiles, 5, &scan_opts(None))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn inline_constraints_count_violations_with_samples() {
+    let tmp = write_multi_rg_fixture();
+    let paths = vec![tmp.path().to_path_buf()];
+    let constraints = vec![
+        Constraint::NotNull("name".into()),
+        Constraint::Unique("id".into()),
+        Constraint::range("id >= 0 AND id < 400").unwrap(),
+    ];
+    let results = check_constraints(&paths, &constraints, &scan_opts(None)).unwrap();
+    assert!(results[0].passed());
+    assert_eq!(results[0].checked_rows, 1000);
+
+    // ids repeat from row 500 on
+    assert_eq!(results[1].constraint, "unique(id)");
+    assert_eq!(results[1].violations, 500);
+    assert_eq!(results[1].samples[0].row, 500);
+    assert_eq!(results[1].samples[0].value.as_deref(), Some("0"));
+    assert_eq!(results[1].samples.len(), 10);
+
+    // 400..500 appears twice
+    assert_eq!(results[2].violations, 200);
+    assert_eq!(results[2].samples[0].row, 400);
+    assert_eq!(results[2].samples[0].value.as_deref(), Some("400"));
+
+    assert!(Constraint::range("id > 0 AND name = 'x'").is_err());
+    let missing = [Constraint::NotNull("nope".into())];
+    let err = check_constraints(&paths, &missing, &scan_opts(None)).unwrap_err();
+    assert_eq!(err.code(), "not_found");
+}
+
+#[test]
+fn schema_diff_reports_retyped_and_added_columns() {
+    let left = parquet_lens_core::extract_schema(write_multi_rg_fixture().path()).unwrap();
+    let mut retyped = left.clone();
+    retyped[0].physical_type = "INT64".into();
+    let diffs = diff_schemas(&left, &retyped);
+    assert_eq!(diffs[0].status, DiffStatus::TypeChanged);
+    assert_eq!(diffs[0].left_type.as_deref(), Some("INT32/-"));
+    assert_eq!(diffs[0].right_type.as_deref(), Some("INT64/-"));
+    assert!(diff_schemas(&left, &left)
+        .iter()
+        .all(|d| d.status == DiffStatus::Matching));
+    // added and removed columns carry the same physical/logical label
+    let added = diff_schemas(&[], &left);
+    assert!(added
+        .iter()
+        .all(|d| d.status == DiffStatus::Added && d.right_type.as_ref().unwrap().contains('/')));
+}
+
+#[test]
+fn schema_tree_keeps_nesting() {
+    use arrow::array::{ListBuilder, MapBuilder, StringBuilder, StructArray};
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let mut tags = ListBuilder::new(StringBuilder::new());
+    tags.values().append_value("a");
+    tags.append(true);
+    let mut attrs = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+    attrs.keys().append_value("k");
+    attrs.values().append_value("v");
+    attrs.append(true).unwrap();
+    let address = StructArray::from(vec![(
+        Arc::new(Field::new("city", DataType::Utf8, true)),
+        Arc::new(StringArray::from(vec!["x"])) as arrow::array::ArrayRef,
+    )]);
+    let batch = RecordBatch::try_from_iter(vec![
+        ("tags", Arc::new(tags.finish()) as arrow::array::ArrayRef),
+        ("attrs", Arc::new(attrs.finish())),
+        ("address", Arc::new(address)),
+    ])
+    .unwrap();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), batch.schema(), None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let handle = HandleRegistry::global().get(tmp.path()).unwrap();
+    let tree = parquet_lens_core::schema_tree(handle.metadata());
+    let lines = tree.render();
+    assert_eq!(lines[0], "arrow_schema");
+    assert_eq!(lines[1], "├── tags: group (list) REQUIRED");
+    assert_eq!(lines[2], "│   └── list: group (struct) REPEATED");
+    assert_eq!(lines[3], "│       └── item: BYTE_ARRAY (String) OPTIONAL");
+    assert_eq!(lines[4], "├── attrs: group (map) REQUIRED");
+    assert_eq!(lines[6], "│       ├── keys: BYTE_ARRAY (String) REQUIRED");
+    assert_eq!(
+        lines.last().unwrap(),
+        "    └── city: BYTE_ARRAY (String) OPTIONAL"
+    );
+    // the flat schema only has the four leaves
+    assert_eq!(
+        parquet_lens_core::schema_from_metadata(handle.metadata()).len(),
+        4
+    );
+}
+
+// serves `data` over HTTP/1.0, honouring `Range` only when `ranges` is set; a
+// `signed` server acts like a pre-signed S3 URL, refusing HEAD and unsigned GETs
+#[cfg(feature = "http")]
+fn serve_http(data: Vec<u8>, ranges: bool, signed: bool) -> String {
+    use std::io::{BufRead, BufReader, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            let mut range = None;
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                if request.is_empty() {
+                    request = line.clone();
+                }
+                if let Some(v) = line.to_ascii_lowercase().strip_prefix("range: bytes=") {
+                    range = Some(v.trim().to_string());
+                }
+                line.clear();
+            }
+            let len = data.len();
+            let forbidden =
+                signed && (request.starts_with("HEAD") || !request.contains("X-Amz-Signature=abc"));
+            let (status, body) = match range.filter(|_| ranges) {
+                _ if forbidden => ("403 Forbidden".to_string(), &[][..]),
+                Some(r) => {
+                    let (a, b) = r.split_once('-').unwrap();
+                    let (start, end) = match a {
+                        "" => (len.saturating_sub(b.parse().unwrap()), len),
+                        _ => (
+                            a.parse().unwrap(),
+                            (b.parse::<usize>().unwrap() + 1).min(len),
+                        ),
+                    };
+                    (
+                        format!(
+                            "206 Partial Content\r\nContent-Range: bytes {start}-{}/{len}",
+                            end - 1
+                        ),
+                        &data[start..end],
+                    )
+                }
+                None => ("200 OK".to_string(), &data[..]),
+            };
+            let head = format!(
+                "HTTP/1.0 {status}\r\nContent-Length: {}\r\nETag: \"v1\"\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(head.as_bytes());
+            if !request.starts_with("HEAD") {
+                let _ = stream.write_all(body);
+            }
+        }
+    });
+    let query = match signed {
+        true => "?X-Amz-Algorithm=AWS4-HMAC-SHA256&X-Amz-Expires=900&X-Amz-Signature=abc",
+        false => "",
+    };
+    format!("http://{addr}/data/fixture.parquet{query}")
+}
+
+#[cfg(feature = "http")]
+#[tokio::test(flavor = "multi_thread")]
+async fn http_reader_reads_footer_with_and_without_ranges() {
+    let tmp = write_multi_rg_fixture();
+    let data = std::fs::read(tmp.path()).unwrap();
+    let (local, _) = open_parquet_file(tmp.path()).unwrap();
+    for ranges in [true, false] {
+        let uri = serve_http(data.clone(), ranges, false);
+        let (info, meta) = parquet_lens_core::open_parquet_auto(&uri, None)
+            .await
+            .unwrap();
+        assert_eq!(info.row_count, local.row_count);
+        assert_eq!(info.file_size, data.len() as u64);
+        assert_eq!(meta.num_row_groups(), local.row_group_count);
+        let tail =
+            parquet_lens_core::read_http_range(&uri, data.len() as u64 - 4..data.len() as u64)
+                .await
+                .unwrap();
+        assert_eq!(&tail[..], b"PAR1");
+    }
+
+    // metadata-only profiling never touches the local filesystem
+    let uri = serve_http(data.clone(), true, false);
+    let report = Profiler::builder()
+        .path(uri.clone())
+        .metadata_only(true)
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(report.dataset.total_rows, local.row_count);
+    assert_eq!(report.dataset.files[0].path.to_str(), Some(uri.as_str()));
+    let input = &report.run.unwrap().inputs[0];
+    assert_eq!(
+        (input.size, input.etag.as_deref()),
+        (data.len() as u64, Some("\"v1\""))
+    );
+}
+
+#[cfg(feature = "http")]
+#[tokio::test(flavor = "multi_thread")]
+async fn presigned_urls_are_read_without_head_requests() {
+    let tmp = write_multi_rg_fixture();
+    let data = std::fs::read(tmp.path()).unwrap();
+    let (local, _) = open_parquet_file(tmp.path()).unwrap();
+    let uri = serve_http(data.clone(), true, true);
+    assert!(parquet_lens_core::is_presigned_url(&uri));
+    assert!(!parquet_lens_core::is_presigned_url(
+        uri.split('?').next().unwrap()
+    ));
+
+    let (size, _, etag) = parquet_lens_core::http_object_metadata(&uri).await.unwrap();
+    assert_eq!((size, etag.as_deref()), (data.len() as u64, Some("\"v1\"")));
+    let report = Profiler::builder()
+        .path(uri.clone())
+        .with_full_scan(true)
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(report.dataset.files.len(), 1);
+    assert_eq!(report.dataset.total_rows, local.row_count);
+    let full_scan = report.full_scan.unwrap();
+    assert_eq!(full_scan.len(), 2);
+
+    let unsigned = uri.replace("X-Amz-Signature=abc", "X-Amz-Signature=old");
+    let err = parquet_lens_core::open_parquet_auto(&unsigned, None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "auth");
+    assert!(err.to_string().contains("may have expired"), "{err}");
+}
+
+#[cfg(feature = "http")]
+#[tokio::test(flavor = "multi_thread")]
+async fn remote_full_scan_honours_the_timeout() {
+    use parquet_lens_core::profile_columns_async_with_timeout;
+    let tmp = write_multi_rg_fixture();
+    let uri = serve_http(std::fs::read(tmp.path()).unwrap(), true, false);
+    let local = profile_columns_with_timeout(tmp.path(), None, 10, None, &scan_opts(None)).unwrap();
+    let remote =
+        profile_columns_async_with_timeout(&uri, None, 10, Some(60), &scan_opts(None), None)
+            .await
+            .unwrap();
+    assert_eq!(remote.len(), local.len());
+    for (r, l) in remote.iter().zip(&local) {
+        assert_eq!(r.column_name, l.column_name);
+        assert_eq!(
+            r.cardinality.approximate_distinct,
+            l.cardinality.approximate_distinct
+        );
+        assert!(!r.truncated);
+    }
+
+    // an expired deadline still yields one result per column, marked truncated
+    let cut = profile_columns_async_with_timeout(&uri, None, 10, Some(0), &scan_opts(None), None)
+        .await
+        .unwrap();
+    assert_eq!(cut.len(), local.len());
+    assert!(cut.iter().all(|r| r.truncated));
+}
+
+#[cfg(feature = "http")]
+#[tokio::test(flavor = "multi_thread")]
+async fn remote_preview_reads_only_the_first_row_group() {
+    let tmp = write_multi_rg_fixture();
+    let uri = serve_http(std::fs::read(tmp.path()).unwrap(), true, false);
+    let (schema, batches) = parquet_lens_core::preview_object(&uri, 5, None)
+        .await
+        .unwrap();
+    assert_eq!(schema.fields().len(), 2);
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 5);
+    let ids = batches[0]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(ids.values()[..5], [0, 1, 2, 3, 4]);
+
+    // asking for more than a row group holds stops at the row group
+    let (_, batches) = parquet_lens_core::preview_object(&uri, 10_000, None)
+        .await
+        .unwrap();
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 100);
+}
+
+#[cfg(feature = "http")]
+#[tokio::test(flavor = "multi_thread")]
+async fn flaky_http_reads_are_retried_per_policy() {
+    use std::io::{BufRead, BufReader, Write};
+    // answers every other request with a 503, the rest with the whole body
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for (n, stream) in listener.incoming().enumerate() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let response = match n % 2 {
+                0 => "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n",
+                _ => "HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\n0123456789",
+            };
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    let uri = format!("http://{addr}/flaky.parquet");
+    parquet_lens_core::set_retry_config(parquet_lens_common::RetryConfig {
+        initial_backoff_ms: 1,
+        ..Default::default()
+    });
+    let before = parquet_lens_core::retry_count();
+    let bytes = parquet_lens_core::read_http_range(&uri, 2..5)
+        .await
+        .unwrap();
+    assert_eq!(&bytes[..], b"234");
+    assert!(parquet_lens_core::retry_count() > before);
+
+    parquet_lens_core::set_retry_config(parquet_lens_common::RetryConfig {
+        max_retries: 0,
+        ..Default::default()
+    });
+    let err = parquet_lens_core::read_http_range(&uri, 2..5)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "throttled");
+    parquet_lens_core::set_retry_config(Default::default());
+}
+
+// a namenode and datanode in one: LISTSTATUS/GETFILESTATUS for `files` (path ->
+// bytes) and OPEN via a redirect, as WebHDFS does; calls without user.name get 401
+#[cfg(feature = "hdfs")]
+fn serve_webhdfs(files: Vec<(&'static str, Vec<u8>)>) -> String {
+    use std::io::{BufRead, BufReader, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let target = request.split(' ').nth(1).unwrap().to_string();
+            let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+            let param = |k: &str| {
+                query
+                    .split('&')
+                    .find_map(|kv| kv.strip_prefix(&format!("{k}=")))
+                    .map(str::to_string)
+            };
+            let status = |kind: &str, suffix: &str, len: usize| {
+                format!(
+                    r#"{{"pathSuffix":"{suffix}","type":"{kind}","length":{len},"modificationTime":1700000000000}}"#
+                )
+            };
+            let path = path.strip_prefix("/webhdfs/v1").unwrap_or(path);
+            let children: Vec<&str> = files
+                .iter()
+                .filter_map(|(f, _)| f.strip_prefix(path)?.strip_prefix('/'))
+                .collect();
+            let file = files.iter().find(|(f, _)| *f == path);
+            let (head, body): (String, Vec<u8>) = match (param("op").as_deref(), file) {
+                _ if param("user.name").as_deref() != Some("alice") => {
+                    ("401 Unauthorized".into(), Vec::new())
+                }
+                (Some("OPEN"), Some(_)) if param("datanode").is_none() => (
+                    format!("307 Temporary Redirect\r\nLocation: http://{addr}{target}&datanode=1"),
+                    Vec::new(),
+                ),
+                (Some("OPEN"), Some((_, data))) => {
+                    let start: usize = param("offset").unwrap().parse().unwrap();
+                    let len: usize = param("length").unwrap().parse().unwrap();
+                    ("200 OK".into(), data[start..(start + len).min(data.len())].to_vec())
+                }
+                (Some("GETFILESTATUS"), Some((_, data))) => (
+                    "200 OK".into(),
+                    format!(r#"{{"FileStatus":{}}}"#, status("FILE", "", data.len())).into(),
+                ),
+                (Some("GETFILESTATUS"), None) if !children.is_empty() => (
+                    "200 OK".into(),
+                    format!(r#"{{"FileStatus":{}}}"#, status("DIRECTORY", "", 0)).into(),
+                ),
+                (Some("LISTSTATUS"), None) if !children.is_empty() => {
+                    let mut entries: Vec<String> = Vec::new();
+                    for child in &children {
+                        let entry = match child.split_once('/') {
+                            Some((dir, _)) => status("DIRECTORY", dir, 0),
+                            None => status("FILE", child, 1),
+                        };
+                        if !entries.contains(&entry) {
+                            entries.push(entry);
+                        }
+                    }
+                    (
+                        "200 OK".into(),
+                        format!(r#"{{"FileStatuses":{{"FileStatus":[{}]}}}}"#, entries.join(","))
+                            .into(),
+                    )
+                }
+                _ => (
+                    "404 Not Found".into(),
+                    br#"{"RemoteException":{"exception":"FileNotFoundException","message":"File does not exist"}}"#.to_vec(),
+                ),
+            };
+            let head = format!(
+                "HTTP/1.0 {head}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(head.as_bytes());
+            let _ = stream.write_all(&body);
+        }
+    });
+    format!("webhdfs://{addr}")
+}
+
+#[cfg(feature = "hdfs")]
+#[tokio::test(flavor = "multi_thread")]
+async fn webhdfs_lists_reads_ranges_and_footers() {
+    let tmp = write_multi_rg_fixture();
+    let data = std::fs::read(tmp.path()).unwrap();
+    let (local, _) = open_parquet_file(tmp.path()).unwrap();
+    let root = serve_webhdfs(vec![
+        ("/warehouse/t/part-0.parquet", data.clone()),
+        ("/warehouse/t/_SUCCESS", Vec::new()),
+        ("/warehouse/t/_temporary/part-9.parquet", data.clone()),
+        ("/warehouse/t/year=2024/part-1.parquet", data.clone()),
+    ]);
+    parquet_lens_core::set_hdfs_config(parquet_lens_common::HdfsConfig {
+        user: Some("alice".into()),
+        ..Default::default()
+    });
+
+    let paths = parquet_lens_core::resolve_paths(&format!("{root}/warehouse/t"))
+        .await
+        .unwrap();
+    let listed: Vec<_> = paths.iter().map(|p| p.path.to_str().unwrap()).collect();
+    assert_eq!(
+        listed,
+        [
+            format!("{root}/warehouse/t/part-0.parquet"),
+            format!("{root}/warehouse/t/year=2024/part-1.parquet")
+        ]
+    );
+
+    let uri = &listed[1];
+    let (info, meta) = parquet_lens_core::open_parquet_auto(uri, None)
+        .await
+        .unwrap();
+    assert_eq!(info.row_count, local.row_count);
+    assert_eq!(info.file_size, data.len() as u64);
+    assert_eq!(meta.num_row_groups(), local.row_group_count);
+    let tail = parquet_lens_core::read_hdfs_range(uri, data.len() as u64 - 4..data.len() as u64)
+        .await
+        .unwrap();
+    assert_eq!(&tail[..], b"PAR1");
+
+    let err = parquet_lens_core::open_parquet_auto(&format!("{root}/nope.parquet"), None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "not_found");
+}
+
+#[test]
+fn hll_precision_trades_sketch_size_for_accuracy() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ids.parquet");
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(Int32Array::from(
+            (0..20_000).collect::<Vec<i32>>(),
+        ))],
+    )
+    .unwrap();
+    let file = std::fs::File::create(&path).unwrap();
+    let mut writer = ArrowWriter::try_new(file, schema.clone(), None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let scan = |precision: u8| {
+        let opts = ScanOptions {
+            hll_precision: precision,
+            ..scan_opts(None)
+        };
+        let results = profile_columns_with_timeout(&path, None, 10, None, &opts).unwrap();
+        results[0].cardinality.clone()
+    };
+    let coarse = scan(6);
+    let fine = scan(14);
+    assert_eq!(coarse.precision, 6);
+    assert_eq!(fine.precision, 14);
+    assert!((coarse.error_rate - 1.04 / 8.0).abs() < 1e-9, "{coarse:?}");
+    assert!((fine.error_rate - 1.04 / 128.0).abs() < 1e-9, "{fine:?}");
+    assert_eq!(coarse.sketch.as_ref().unwrap().approx_bytes(), 64);
+    assert_eq!(fine.sketch.as_ref().unwrap().approx_bytes(), 16_384);
+    // within three standard errors of the true count
+    for est in [&coarse, &fine] {
+        let off = (est.approximate_distinct as f64 - 20_000.0).abs() / 20_000.0;
+        assert!(off < est.error_rate * 3.0, "{est:?}");
+    }
+    // out-of-range precisions are clamped rather than panicking
+    assert_eq!(scan(2).precision, 4);
+    assert_eq!(scan(30).precision, 14);
+
+    // sketches of different precisions fall back to summing
+    let mut merged = coarse.clone();
+    merged.merge(&fine);
+    assert!(merged.sketch.is_none());
+    assert_eq!(
+        merged.approximate_distinct,
+        coarse.approximate_distinct + fine.approximate_distinct
+    );
+    assert_eq!(merged.error_rate, coarse.error_rate);
+}
+
+#[test]
+fn row_group_samples_carry_intervals_for_extrapolated_stats() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("even", DataType::Int32, true),
+        Field::new("skewed", DataType::Int32, true),
+        Field::new("cat", DataType::Utf8, false),
+    ]));
+    // every row group has 10 nulls in `even`; `skewed` has 20 in odd row groups only
+    let ids: Vec<i32> = (0..1000).collect();
+    let even: Vec<Option<i32>> = ids.iter().map(|&i| (i % 10 != 0).then_some(i)).collect();
+    let skewed: Vec<Option<i32>> = ids
+        .iter()
+        .map(|&i| ((i / 100) % 2 == 0 || i % 100 >= 20).then_some(i))
+        .collect();
+    let cats: Vec<String> = ids.iter().map(|i| format!("c{}", i % 5)).collect();
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(ids)),
+            Arc::new(Int32Array::from(even)),
+            Arc::new(Int32Array::from(skewed)),
+            Arc::new(StringArray::from(cats)),
+        ],
+    )
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(100)
+        .build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let config = SampleConfig {
+        percentage: 40.0,
+        no_extrapolation: false,
+        seed: Some(1),
+        method: SampleMethod::RowGroups,
+        rows: None,
+        stratify_by: None,
+        allocation: StrataAllocation::default(),
+    };
+    let sp = sample_row_groups(tmp.path(), &config, 10, &scan_opts(None)).unwrap();
+    assert_eq!(sp.sampled_rg_count, 4);
+    let metric = |column: &str, metric: &str| {
+        sp.metric_confidence
+            .iter()
+            .find(|m| m.column == column && m.metric == metric)
+            .unwrap_or_else(|| panic!("no {column} {metric}"))
+            .clone()
+    };
+
+    // identical row groups extrapolate with no spread at all
+    let even_nulls = metric("even", "null_count");
+    assert_eq!((even_nulls.estimate, even_nulls.margin), (100.0, Some(0.0)));
+    assert_eq!(metric("even", "null_rate").margin, Some(0.0));
+    // row groups that differ give an interval, and it covers the real count
+    let skewed_nulls = metric("skewed", "null_count");
+    assert!(skewed_nulls.margin.unwrap() > 0.0, "{skewed_nulls:?}");
+    let (low, high) = skewed_nulls.bounds().unwrap();
+    assert!(low <= 100.0 && 100.0 <= high, "{skewed_nulls:?}");
+    assert!(metric("skewed", "null_rate").margin.unwrap() > 0.0);
+    let meta = open_parquet_file(tmp.path()).unwrap().1;
+    let compressed: i64 = read_column_stats(&meta)
+        .iter()
+        .filter(|cs| cs.column_name == "id")
+        .map(|cs| cs.compressed_size)
+        .sum();
+    let (low, high) = metric("id", "compressed_size").bounds().unwrap();
+    assert!(low <= compressed as f64 && compressed as f64 <= high);
+
+    // 400 unique ids seen: the file could hold anywhere from those to all 1000
+    let ids = metric("id", "distinct");
+    let (low, high) = ids.range.unwrap();
+    assert!(low < 400.0 && high >= 1000.0, "{ids:?}");
+    assert!(ids.estimate > 400.0 && ids.estimate < 1000.0, "{ids:?}");
+    assert!(ids.line().contains('['), "{}", ids.line());
+    // every category turned up more than once, so none are likely missing
+    let cats = metric("cat", "distinct");
+    assert!(cats.range.unwrap().1 < 6.0, "{cats:?}");
+
+    // without extrapolation only the sampled rates get intervals
+    let sampled_only = sample_row_groups(
+        tmp.path(),
+        &SampleConfig {
+            no_extrapolation: true,
+            ..config
+        },
+        10,
+        &scan_opts(None),
+    )
+    .unwrap();
+    assert!(sampled_only
+        .metric_confidence
+        .iter()
+        .all(|m| m.metric != "null_count" && m.metric != "compressed_size"));
+}
+
+#[test]
+fn config_rules_report_each_check_on_its_own() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("orders.parquet");
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("email", DataType::Utf8, true),
+        Field::new(
+            "updated_at",
+            DataType::Timestamp(arrow::datatypes::TimeUnit::Millisecond, None),
+            false,
+        ),
+    ]));
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+    let hour = 3_600_000;
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3, 3, 5])),
+            Arc::new(StringArray::from(vec![
+                "open", "shipped", "open", "lost", "open",
+            ])),
+            Arc::new(StringArray::from(vec![
+                Some("a@x.io"),
+                None,
+                Some("b@x.io"),
+                Some("nope"),
+                Some("c@x.io"),
+            ])),
+            Arc::new(arrow::array::TimestampMillisecondArray::from(vec![
+                now_ms - 72 * hour,
+                now_ms - 50 * hour,
+                now_ms - 2 * hour,
+                now_ms - 30 * hour,
+                now_ms - 90 * hour,
+            ])),
+        ],
+    )
+    .unwrap();
+    let file = std::fs::File::create(&path).unwrap();
+    let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let rules_path = dir.path().join("rules.toml");
+    std::fs::write(
+        &rules_path,
+        r#"
+[[rules]]
+column = "id"
+not_null = true
+unique = true
+min = 1
+max = 4
+
+[[rules]]
+column = "status"
+allowed_values = ["open", "shipped"]
+
+[[rules]]
+column = "email"
+regex = "^[^@]+@[^@]+$"
+max_null_pct = 25.0
+
+[[rules]]
+column = "updated_at"
+freshness = "24h"
+
+[[rules]]
+column = "updated_at"
+path = "elsewhere/*.parquet"
+freshness = "1h"
+"#,
+    )
+    .unwrap();
+    let rules = parquet_lens_common::RuleConfig::load_file(&rules_path).unwrap();
+    let rules: Vec<_> = rules
+        .into_iter()
+        .filter(|r| r.matches(path.to_str().unwrap()))
+        .collect();
+    assert_eq!(rules.len(), 4);
+    let files = [path.clone()];
+    let results = run_rules(&files, &rules, &scan_opts(None)).unwrap();
+    let outcome: Vec<(&str, bool, u64)> = results
+        .iter()
+        .map(|r| (r.constraint.as_str(), r.passed(), r.violations))
+        .collect();
+    assert_eq!(
+        outcome,
+        vec![
+            ("not_null(id)", true, 0),
+            ("unique(id)", false, 1),
+            ("range(id >= 1 AND id <= 4)", false, 1),
+            ("allowed_values(status in 'open', 'shipped')", false, 1),
+            ("regex(email ~ ^[^@]+@[^@]+$)", false, 1),
+            ("max_null_pct(email <= 25%)", true, 1),
+            ("freshness(updated_at within 24h)", true, 0),
+        ]
+    );
+    assert_eq!(results[3].samples[0].value.as_deref(), Some("lost"));
+    assert!(
+        results[5].line().contains("20.00% of 5 rows null"),
+        "{}",
+        results[5].line()
+    );
+    assert!(results[6].line().contains("2h"), "{}", results[6].line());
+
+    // a stale column fails with its newest value as the sample
+    let stale = rule_constraints(&[parquet_lens_common::RuleConfig {
+        column: "updated_at".into(),
+        freshness: Some("1h".into()),
+        ..Default::default()
+    }])
+    .unwrap();
+    let result = &check_constraints(&files, &stale, &scan_opts(None)).unwrap()[0];
+    assert!(!result.passed());
+    assert_eq!(result.samples[0].row, 2);
+    // freshness needs a date or timestamp column, and bad rules fail up front
+    let wrong = parquet_lens_common::RuleConfig {
+        column: "status".into(),
+        freshness: Some("1h".into()),
+        ..Default::default()
+    };
+    assert!(run_rules(&files, &[wrong], &scan_opts(None)).is_err());
+    let bad_regex = parquet_lens_common::RuleConfig {
+        column: "email".into(),
+        regex: Some("(".into()),
+        ..Default::default()
+    };
+    assert!(rule_constraints(&[bad_regex]).is_err());
+}
+
+#[tokio::test]
+async fn pii_scan_reports_match_rates_and_flags_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("customers.parquet");
+    let names = ["email", "phone", "ssn", "card", "ip", "notes", "sku"];
+    let schema = Arc::new(Schema::new(
+        names
+            .iter()
+            .map(|n| Field::new(*n, DataType::Utf8, true))
+            .collect::<Vec<_>>(),
+    ));
+    let col = |v: [Option<&str>; 4]| Arc::new(StringArray::from(v.to_vec())) as _;
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            col([
+                Some("ann@example.com"),
+                Some("bo@mail.co.uk"),
+                None,
+                Some("n/a"),
+            ]),
+            col([
+                Some("(555) 123-4567"),
+                Some("+44 20 7946 0958"),
+                Some("555.987.6543"),
+                Some("12"),
+            ]),
+            col([
+                Some("123-45-6789"),
+                Some("078-05-1120"),
+                Some("000-12-3456"),
+                None,
+            ]),
+            col([
+                Some("4111 1111 1111 1111"),
+                Some("5500-0000-0000-0004"),
+                Some("4111 1111 1111 1112"),
+                Some("1234567890123"),
+            ]),
+            col([
+                Some("10.0.0.1"),
+                Some("2001:db8::8a2e:370:7334"),
+                Some("999.1.1.1"),
+                Some("::1"),
+            ]),
+            col([
+                Some("call me at 555-867-5309"),
+                Some("shipped fine"),
+                Some("left at door"),
+                Some("no issues"),
+            ]),
+            col([
+                Some("SKU-001"),
+                Some("SKU-002"),
+                Some("v1.2.3"),
+                Some("a::b"),
+            ]),
+        ],
+    )
+    .unwrap();
+    let file = std::fs::File::create(&path).unwrap();
+    let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let files = [path.clone()];
+    let report = scan_pii(&files, &scan_opts(None)).unwrap();
+    assert_eq!(report.scanned_rows, 4);
+    let rate = |column: &str, kind: PiiKind| {
+        let c = report.columns.iter().find(|c| c.column == column).unwrap();
+        c.matches
+            .iter()
+            .find(|m| m.kind == kind)
+            .map(|m| (m.pct * 100.0).round() / 100.0)
+    };
+    // the null isn't scanned, "n/a" is
+    assert_eq!(rate("email", PiiKind::Email), Some(66.67));
+    assert_eq!(rate("phone", PiiKind::Phone), Some(75.0));
+    // 000 area numbers are never issued
+    assert_eq!(rate("ssn", PiiKind::NationalId), Some(66.67));
+    // a Luhn failure and an unknown issuer prefix don't count
+    assert_eq!(rate("card", PiiKind::CreditCard), Some(50.0));
+    // octets past 255 and IPv6 shorthand too short to tell from code don't count
+    assert_eq!(rate("ip", PiiKind::IpAddress), Some(50.0));
+    // free text is caught by a match anywhere in the value
+    assert_eq!(rate("notes", PiiKind::Phone), Some(25.0));
+    let sku = report.columns.iter().find(|c| c.column == "sku").unwrap();
+    assert!(sku.matches.is_empty() && !sku.likely_pii);
+    let flagged: Vec<&str> = report.flagged().map(|c| c.column.as_str()).collect();
+    assert_eq!(flagged, ["email", "phone", "ssn", "card", "ip", "notes"]);
+
+    let profiled = Profiler::builder()
+        .path(path.to_str().unwrap())
+        .scan_pii(true)
+        .scan_options(scan_opts(None))
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(profiled.pii.as_ref().unwrap().flagged().count(), 6);
+    let score = |column: &str| {
+        profiled
+            .quality_scores()
+            .iter()
+            .find(|q| q.column_name == column)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(score("card").pii, [PiiKind::CreditCard]);
+    assert!(score("card").breakdown.contains("pii=credit_card"));
+    assert!(score("sku").pii.is_empty());
+    assert!(!score("sku").breakdown.contains("pii="));
+
+    let csv_path = dir.path().join("report.csv");
+    export_csv(&csv_path, &profiled).unwrap();
+    let csv = std::fs::read_to_string(&csv_path).unwrap();
+    assert!(csv.lines().next().unwrap().ends_with(",pii"));
+    assert!(csv
+        .lines()
+        .any(|l| l.starts_with("ssn,") && l.ends_with(",national_id")));
+}
+
+#[test]
+fn contract_reports_schema_and_value_violations_with_severities() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("orders.parquet");
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("status", DataType::Utf8, true),
+        Field::new("amount", DataType::Utf8, true),
+        Field::new("note", DataType::Utf8, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3, 40])),
+            Arc::new(StringArray::from(vec![
+                Some("open"),
+                None,
+                Some("lost"),
+                Some("open"),
+            ])),
+            Arc::new(StringArray::from(vec!["1.50", "2.00", "3.25", "0.10"])),
+            Arc::new(StringArray::from(vec![None::<&str>; 4])),
+        ],
+    )
+    .unwrap();
+    let file = std::fs::File::create(&path).unwrap();
+    let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let contract_path = dir.path().join("contract.yaml");
+    std::fs::write(
+        &contract_path,
+        r#"
+name: orders
+extra_columns: warning
+columns:
+  - name: id
+    type: int
+    nullable: false
+    min: 1
+    max: 10
+  - name: status
+    type: string
+    nullable: false
+    enum: [open, shipped]
+    severity: warning
+  - name: amount
+    type: double
+  - name: customer_id
+    type: bigint
+"#,
+    )
+    .unwrap();
+    let contract = Contract::load(&contract_path).unwrap();
+    let files = [path.clone()];
+    let report = validate_contract(&files, &contract, &scan_opts(None)).unwrap();
+    let lines: Vec<String> = report.violations.iter().map(|v| v.line()).collect();
+    assert_eq!(report.contract.as_deref(), Some("orders"));
+    assert_eq!(
+        lines,
+        [
+            "warning nullable(status): declared non-nullable, but the schema allows nulls",
+            "error type(amount): declared double, found Utf8",
+            "error missing(customer_id): not in the schema",
+            "warning extra(note): not declared in the contract",
+            "error range(id >= 1 AND id <= 10): 1 of 4 rows violate (row 3 = 40)",
+            "warning not_null(status): 1 of 4 rows violate (row 1)",
+            "warning allowed_values(status in 'open', 'shipped'): 1 of 4 rows violate (row 2 = lost)",
+        ]
+    );
+    assert_eq!(report.count(Severity::Error), 3);
+    assert!(!report.passed());
+
+    // a contract the data meets passes, and unknown keys are rejected
+    let mut ok = Contract {
+        columns: contract.columns[..1].to_vec(),
+        ..Default::default()
+    };
+    ok.columns[0].max = None;
+    let report = validate_contract(&files, &ok, &scan_opts(None)).unwrap();
+    assert!(report.violations.is_empty() && report.passed());
+    std::fs::write(
+        &contract_path,
+        "columns:\n  - name: id\n    nulable: false\n",
+    )
+    .unwrap();
+    assert!(Contract::load(&contract_path).is_err());
+}
+
+#[tokio::test]
+async fn single_row_group_sample_has_no_interval_and_round_trips() {
+    let tmp = tempfile::Builder::new()
+        .suffix(".parquet")
+        .tempfile()
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int32, true)]));
+    let values: Vec<Option<i32>> = (0..400).map(|i| (i % 50 != 0).then_some(i)).collect();
+    let batch =
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(values))]).unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(100)
+        .build();
+    let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .sample(25.0)
+        .sample_seed(7)
+        .sample_method(SampleMethod::RowGroups)
+        .scan_options(scan_opts(None))
+        .run()
+        .await
+        .unwrap();
+    // one of four row groups: no spread between row groups to size an interval by
+    let null_rate = report
+        .sample_confidence
+        .iter()
+        .find(|m| m.metric == "null_rate")
+        .unwrap();
+    assert!(!null_rate.exact && null_rate.margin.is_none() && null_rate.bounds().is_none());
+    assert!(null_rate.line().ends_with("(no interval)"));
+    assert!(report
+        .sample_confidence
+        .iter()
+        .all(|m| m.margin.is_none_or(f64::is_finite)));
+    assert!(!report.sample_note.as_deref().unwrap().contains("inf"));
+
+    let out = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+    export_json(out.path(), &report).unwrap();
+    let back: ProfileReport =
+        serde_json::from_str(&std::fs::read_to_string(out.path()).unwrap()).unwrap();
+    let margins = |r: &ProfileReport| -> Vec<(String, bool)> {
+        r.sample_confidence
+            .iter()
+            .map(|m| (m.metric.clone(), m.margin.is_some()))
+            .collect()
+    };
+    assert_eq!(margins(&back), margins(&report));
+}
+
+#[tokio::test]
+async fn exported_reports_leave_out_cardinality_sketches() {
+    let tmp = write_multi_rg_fixture();
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .with_full_scan(true)
+        .scan_options(scan_opts(None))
+        .run()
+        .await
+        .unwrap();
+    let full_scan = report.full_scan.as_ref().unwrap();
+    assert!(full_scan.iter().all(|r| r.cardinality.sketch.is_some()));
+    let out = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+    export_json(out.path(), &report).unwrap();
+    let json = std::fs::read_to_string(out.path()).unwrap();
+    assert!(!json.contains("\"sketch\""));
+    assert!(json.contains("\"approximate_distinct\""));
+}
+
+#[tokio::test]
+async fn exported_reports_keep_top_level_quality_scores() {
+    let tmp = write_multi_rg_fixture();
+    let report = Profiler::builder()
+        .path(tmp.path().to_str().unwrap())
+        .scan_options(scan_opts(None))
+        .run()
+        .await
+        .unwrap();
+    let out = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+    export_json(out.path(), &report).unwrap();
+    let json = std::fs::read_to_string(out.path()).unwrap();
+    let doc: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let scores = doc["quality_scores"].as_array().unwrap();
+    assert_eq!(scores.len(), report.quality_scores().len());
+    assert_eq!(scores, doc["quality"]["column_scores"].as_array().unwrap());
+    let back: ProfileReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.quality_scores().len(), report.quality_scores().len());
+}
@@ -0,0 +1,41 @@
+/* C interface to parquet-lens. Link against libparquet_lens (cdylib or staticlib).
+ *
+ * All strings are UTF-8 and NUL-terminated. Strings returned by the library are
+ * owned by the caller and must be released with parquet_lens_string_free.
+ * Failures are reported as {"error": {"code": ..., "message": ..., "retryable": ...}}.
+ */
+#ifndef PARQUET_LENS_H
+#define PARQUET_LENS_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define PARQUET_LENS_ABI_VERSION 1
+
+#define PARQUET_LENS_CHECK_OK 0
+#define PARQUET_LENS_CHECK_REGRESSIONS 1
+#define PARQUET_LENS_CHECK_ERROR 2
+
+/* ABI version of the loaded library; compare with PARQUET_LENS_ABI_VERSION. */
+uint32_t parquet_lens_abi_version(void);
+
+/* Profiles a file, directory, glob or URI and returns the report as JSON.
+ * options_json may be NULL, or an object with any of:
+ *   "sample" (percent), "sample_seed", "full_scan", "columns", "histogram_bins", "baseline". */
+char *parquet_lens_profile(const char *path, const char *options_json);
+
+/* Compares path against its saved baseline. Returns one of the CHECK codes above;
+ * if out_json is not NULL it receives the regressions array or the error object. */
+int32_t parquet_lens_check(const char *path, char **out_json);
+
+/* Releases a string returned by this library. NULL is ignored. */
+void parquet_lens_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PARQUET_LENS_H */
@@ -0,0 +1,193 @@
+//! C ABI for embedding the profiler. Every call takes UTF-8 C strings and hands
+//! back JSON as a heap string that must be released with `parquet_lens_string_free`.
+//! Errors come back as `{"error": {"code", "message", "retryable"}}`, the same
+//! shape the CLI prints with `--json`. See `include/parquet_lens.h`.
+use parquet_lens_common::{ErrorInfo, ParquetLensError};
+use parquet_lens_core::Profiler;
+use serde::Deserialize;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Bumped whenever a signature or the meaning of a return value changes.
+pub const ABI_VERSION: u32 = 1;
+
+pub const CHECK_OK: i32 = 0;
+pub const CHECK_REGRESSIONS: i32 = 1;
+pub const CHECK_ERROR: i32 = 2;
+
+/// Optional settings for `parquet_lens_profile`, passed as a JSON object.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ProfileOptions {
+    sample: Option<f64>,
+    sample_seed: Option<u64>,
+    full_scan: bool,
+    columns: Option<Vec<String>>,
+    histogram_bins: Option<usize>,
+    baseline: bool,
+}
+
+fn error_json(info: ErrorInfo) -> String {
+    serde_json::json!({ "error": info }).to_string()
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    // JSON output never contains interior NULs; fall back to an empty string anyway
+    CString::new(s).unwrap_or_default().into_raw()
+}
+
+unsafe fn read_str<'a>(ptr: *const c_char, what: &str) -> Result<&'a str, ParquetLensError> {
+    if ptr.is_null() {
+        return Err(ParquetLensError::Other(format!("{what} is NULL")));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| ParquetLensError::Other(format!("{what} is not valid UTF-8")))
+}
+
+// runs `f`, turning errors and panics into the error JSON
+fn guarded(f: impl FnOnce() -> Result<String, ParquetLensError>) -> Result<String, String> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(json)) => Ok(json),
+        Ok(Err(e)) => Err(error_json(e.info())),
+        Err(_) => Err(error_json(
+            ParquetLensError::Other("panic inside parquet-lens".into()).info(),
+        )),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn parquet_lens_abi_version() -> u32 {
+    ABI_VERSION
+}
+
+/// Profiles `path` (file, directory, glob, or URI) and returns the report as JSON.
+/// `options_json` may be NULL.
+///
+/// # Safety
+/// `path` must be a NUL-terminated string; `options_json` NULL or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn parquet_lens_profile(
+    path: *const c_char,
+    options_json: *const c_char,
+) -> *mut c_char {
+    let out = guarded(|| {
+        let path = read_str(path, "path")?;
+        let opts: ProfileOptions = if options_json.is_null() {
+            ProfileOptions::default()
+        } else {
+            serde_json::from_str(read_str(options_json, "options_json")?)
+                .map_err(|e| ParquetLensError::Other(format!("invalid options_json: {e}")))?
+        };
+        let mut builder = Profiler::builder()
+            .path(path)
+            .with_full_scan(opts.full_scan)
+            .with_baseline(opts.baseline);
+        if let Some(pct) = opts.sample {
+            builder = builder.sample(pct);
+        }
+        if let Some(seed) = opts.sample_seed {
+            builder = builder.sample_seed(seed);
+        }
+        if let Some(cols) = opts.columns {
+            builder = builder.columns(cols);
+        }
+        if let Some(bins) = opts.histogram_bins {
+            builder = builder.histogram_bins(bins);
+        }
+        let report = builder.run_blocking()?;
+        serde_json::to_string(&report).map_err(|e| ParquetLensError::Other(e.to_string()))
+    });
+    into_c_string(out.unwrap_or_else(|e| e))
+}
+
+/// Compares `path` against its saved baseline, like `parquet-lens check`.
+/// Returns `CHECK_OK`, `CHECK_REGRESSIONS` or `CHECK_ERROR`. When `out_json` is
+/// not NULL it receives the regressions array, or the error object.
+///
+/// # Safety
+/// `path` must be a NUL-terminated string; `out_json` NULL or valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn parquet_lens_check(
+    path: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    let mut regressions = 0;
+    let out = guarded(|| {
+        let path = read_str(path, "path")?;
+        let report = Profiler::builder()
+            .path(path)
+            .with_baseline(true)
+            .run_blocking()?;
+        regressions = report.baseline_regressions.len();
+        serde_json::to_string(&report.baseline_regressions)
+            .map_err(|e| ParquetLensError::Other(e.to_string()))
+    });
+    let (code, json) = match out {
+        Ok(json) if regressions == 0 => (CHECK_OK, json),
+        Ok(json) => (CHECK_REGRESSIONS, json),
+        Err(json) => (CHECK_ERROR, json),
+    };
+    if !out_json.is_null() {
+        *out_json = into_c_string(json);
+    }
+    code
+}
+
+/// Releases a string returned by this library. NULL is ignored.
+///
+/// # Safety
+/// `s` must come from this library and not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn parquet_lens_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests_ffi {
+    use super::*;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    fn take(ptr: *mut c_char) -> serde_json::Value {
+        let s = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_owned();
+        unsafe { parquet_lens_string_free(ptr) };
+        serde_json::from_str(&s).unwrap()
+    }
+
+    #[test]
+    fn profile_and_errors_round_trip_as_json() {
+        let tmp = tempfile::Builder::new()
+            .suffix(".parquet")
+            .tempfile()
+            .unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batch =
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(vec![1, 2]))])
+                .unwrap();
+        let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let path = CString::new(tmp.path().to_str().unwrap()).unwrap();
+        let opts = CString::new(r#"{"full_scan": true}"#).unwrap();
+        let report = take(unsafe { parquet_lens_profile(path.as_ptr(), opts.as_ptr()) });
+        assert_eq!(report["dataset"]["total_rows"], 2);
+        assert_eq!(report["full_scan"][0]["column_name"], "id");
+
+        let missing = CString::new("/nonexistent/x.parquet").unwrap();
+        let err = take(unsafe { parquet_lens_profile(missing.as_ptr(), std::ptr::null()) });
+        assert_eq!(err["error"]["code"], "not_found");
+
+        let mut out = std::ptr::null_mut();
+        let code = unsafe { parquet_lens_check(missing.as_ptr(), &mut out) };
+        assert_eq!(code, CHECK_ERROR);
+        assert_eq!(take(out)["error"]["code"], "not_found");
+        assert_eq!(parquet_lens_abi_version(), ABI_VERSION);
+    }
+}
@@ -0,0 +1,188 @@
+use parquet_lens_core::{ContractReport, ProfileReport, Severity};
+use std::io::Write;
+
+/// CI systems `--ci` knows how to talk to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CiMode {
+    /// Step summary, `::error`/`::warning` annotations and step outputs
+    Github,
+}
+
+/// Publishes a check/validate result to the CI system.
+pub fn publish(mode: CiMode, input_path: &str, report: &ProfileReport) -> anyhow::Result<()> {
+    match mode {
+        CiMode::Github => github(input_path, report),
+    }
+}
+
+/// Publishes `check --contract` violations, each at its own severity.
+pub fn publish_contract(
+    mode: CiMode,
+    input_path: &str,
+    contract: &ContractReport,
+) -> anyhow::Result<()> {
+    match mode {
+        CiMode::Github => github_contract(input_path, contract),
+    }
+}
+
+fn github_contract(input_path: &str, contract: &ContractReport) -> anyhow::Result<()> {
+    let name = contract.contract.as_deref().unwrap_or("data contract");
+    for v in &contract.violations {
+        println!(
+            "::{} title={}::{}",
+            v.severity.label(),
+            escape_property(&format!("{name}: {} ({})", v.check, v.column)),
+            escape_data(&format!("{} ({input_path})", v.detail))
+        );
+    }
+    if let Some(path) = std::env::var_os("GITHUB_STEP_SUMMARY") {
+        let mut md = format!("#### Contract `{name}`\n\n");
+        if contract.violations.is_empty() {
+            md.push_str("No violations.\n\n");
+        } else {
+            md.push_str("| Severity | Column | Check | Detail |\n|---|---|---|---|\n");
+            for v in &contract.violations {
+                md.push_str(&format!(
+                    "| {} | `{}` | {} | {} |\n",
+                    v.severity.label(),
+                    v.column,
+                    escape_cell(&v.check),
+                    escape_cell(&v.detail)
+                ));
+            }
+            md.push('\n');
+        }
+        append(&path, &md)?;
+    }
+    if let Some(path) = std::env::var_os("GITHUB_OUTPUT") {
+        append(
+            &path,
+            &format!(
+                "contract_errors={}\ncontract_warnings={}\n",
+                contract.count(Severity::Error),
+                contract.count(Severity::Warning)
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+fn github(input_path: &str, report: &ProfileReport) -> anyhow::Result<()> {
+    for r in &report.baseline_regressions {
+        println!(
+            "::error title={}::{}",
+            escape_property(&format!("Regression in {}", r.column)),
+            escape_data(&format!("{}: {} ({input_path})", r.kind, r.detail))
+        );
+    }
+    for s in &report.repair_suggestions {
+        let level = if s.severity == "high" {
+            "error"
+        } else {
+            "warning"
+        };
+        println!(
+            "::{level} title={}::{}",
+            escape_property(&format!("Repair suggestion ({})", s.severity)),
+            escape_data(&format!(
+                "{} — {} ({input_path})",
+                s.issue, s.recommendation
+            ))
+        );
+    }
+    // both files are only set inside a workflow run; elsewhere the annotations suffice
+    if let Some(path) = std::env::var_os("GITHUB_STEP_SUMMARY") {
+        append(&path, &step_summary(input_path, report))?;
+    }
+    if let Some(path) = std::env::var_os("GITHUB_OUTPUT") {
+        append(
+            &path,
+            &format!(
+                "quality_score={}\nregression_count={}\nsuggestion_count={}\n",
+                report.quality.overall_score,
+                report.baseline_regressions.len(),
+                report.repair_suggestions.len()
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+fn append(path: &std::ffi::OsStr, text: &str) -> anyhow::Result<()> {
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(text.as_bytes())?;
+    Ok(())
+}
+
+fn step_summary(input_path: &str, report: &ProfileReport) -> String {
+    let mut md = format!("### parquet-lens: `{input_path}`\n\n");
+    md.push_str("| Quality | Rows | Files | Regressions | Suggestions |\n|---|---|---|---|---|\n");
+    md.push_str(&format!(
+        "| {}/100 | {} | {} | {} | {} |\n\n",
+        report.quality.overall_score,
+        report.dataset.total_rows,
+        report.dataset.file_count,
+        report.baseline_regressions.len(),
+        report.repair_suggestions.len()
+    ));
+    if !report.baseline_regressions.is_empty() {
+        md.push_str("#### Regressions\n\n| Column | Kind | Detail |\n|---|---|---|\n");
+        for r in &report.baseline_regressions {
+            md.push_str(&format!(
+                "| `{}` | {} | {} |\n",
+                r.column,
+                r.kind,
+                escape_cell(&r.detail)
+            ));
+        }
+        md.push('\n');
+    }
+    if !report.repair_suggestions.is_empty() {
+        md.push_str("#### Repair suggestions\n\n");
+        for s in &report.repair_suggestions {
+            md.push_str(&format!(
+                "- **{}** {} — {}\n",
+                s.severity, s.issue, s.recommendation
+            ));
+        }
+        md.push('\n');
+    }
+    let mut low: Vec<_> = report
+        .quality_scores()
+        .iter()
+        .filter(|q| q.score < 80)
+        .collect();
+    if !low.is_empty() {
+        low.sort_by_key(|q| q.score);
+        md.push_str("#### Lowest column scores\n\n| Column | Score | Breakdown |\n|---|---|---|\n");
+        for q in low.iter().take(20) {
+            md.push_str(&format!(
+                "| `{}` | {} | {} |\n",
+                q.column_name,
+                q.score,
+                escape_cell(&q.breakdown)
+            ));
+        }
+        md.push('\n');
+    }
+    md
+}
+
+fn escape_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+// workflow command escaping, as in @actions/core
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
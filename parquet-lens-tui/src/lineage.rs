@@ -0,0 +1,61 @@
+use parquet_lens_common::Config;
+use parquet_lens_core::{openlineage_event, LineageRun, ProfileReport};
+
+/// Reports a finished profiling run to the OpenLineage endpoint, if one is
+/// configured. Failures are logged rather than failing the command.
+pub fn emit(config: &Config, command: &str, dataset: &str, report: &ProfileReport) {
+    if let Err(e) = try_emit(config, command, dataset, report) {
+        tracing::warn!("OpenLineage event not sent: {e:#}");
+    }
+}
+
+fn try_emit(
+    config: &Config,
+    command: &str,
+    dataset: &str,
+    report: &ProfileReport,
+) -> anyhow::Result<()> {
+    let ol = &config.openlineage;
+    // config wins; the OPENLINEAGE_* variables are what the official clients read
+    let setting =
+        |value: &Option<String>, var: &str| value.clone().or_else(|| std::env::var(var).ok());
+    let Some(url) = setting(&ol.url, "OPENLINEAGE_URL") else {
+        return Ok(());
+    };
+    let endpoint =
+        setting(&ol.endpoint, "OPENLINEAGE_ENDPOINT").unwrap_or_else(|| "api/v1/lineage".into());
+    let namespace =
+        setting(&ol.namespace, "OPENLINEAGE_NAMESPACE").unwrap_or_else(|| "parquet-lens".into());
+    let job_name = ol
+        .job_name
+        .clone()
+        .unwrap_or_else(|| format!("parquet-lens.{command}"));
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let event_time = humantime::format_rfc3339_millis(std::time::SystemTime::now()).to_string();
+    let event = openlineage_event(
+        report,
+        dataset,
+        &LineageRun {
+            namespace: &namespace,
+            job_name: &job_name,
+            run_id: &run_id,
+            event_time: &event_time,
+        },
+    );
+    let target = format!(
+        "{}/{}",
+        url.trim_end_matches('/'),
+        endpoint.trim_start_matches('/')
+    );
+    let mut req = reqwest::Client::new().post(&target).json(&event);
+    if let Some(key) = setting(&ol.api_key, "OPENLINEAGE_API_KEY") {
+        req = req.bearer_auth(key);
+    }
+    let resp =
+        tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(req.send()))?;
+    if !resp.status().is_success() {
+        anyhow::bail!("{target} returned HTTP {}", resp.status());
+    }
+    tracing::debug!(run_id, "OpenLineage event sent to {target}");
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+// where object_store logs the requests it retries, at info
+const OBJECT_STORE_RETRY: &str = "object_store::client::retry";
+
+/// Log verbosity from `-v`/`-q`; `RUST_LOG` overrides it when set. Retried
+/// requests are logged unless `-q` is given.
+fn default_directive(verbose: u8, quiet: bool) -> &'static str {
+    match (quiet, verbose) {
+        (true, _) => "error",
+        (false, 0) => "warn,object_store::client::retry=info",
+        (false, 1) => "info",
+        (false, 2) => "debug",
+        _ => "trace",
+    }
+}
+
+/// Installs the global subscriber. Logs go to stderr, or appended to `file` when
+/// set (the TUI owns the terminal). With `-v` or more, analysis spans log their
+/// duration when they close. Built with the `otel` feature, spans are also
+/// exported over OTLP when an endpoint is configured.
+pub fn init(verbose: u8, quiet: bool, json: bool, file: Option<&Path>) -> anyhow::Result<()> {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(default_directive(verbose, quiet)));
+    let spans = if verbose > 0 {
+        FmtSpan::CLOSE
+    } else {
+        FmtSpan::NONE
+    };
+    let fmt = tracing_subscriber::fmt::layer()
+        .with_span_events(spans)
+        .with_target(false);
+    let fmt: BoxedLayer = match (file, json) {
+        (Some(path), json) => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let writer = Mutex::new(
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)?,
+            );
+            let fmt = fmt.with_ansi(false).with_writer(writer);
+            if json {
+                fmt.json().with_filter(filter).boxed()
+            } else {
+                fmt.with_filter(filter).boxed()
+            }
+        }
+        (None, true) => fmt
+            .json()
+            .with_writer(std::io::stderr)
+            .with_filter(filter)
+            .boxed(),
+        (None, false) => fmt
+            .with_ansi(std::io::stderr().is_terminal())
+            .with_writer(std::io::stderr)
+            .with_filter(filter)
+            .boxed(),
+    };
+    let retries = RetryCounter
+        .with_filter(Targets::new().with_target(OBJECT_STORE_RETRY, Level::INFO))
+        .boxed();
+    #[allow(unused_mut)]
+    let mut layers = vec![fmt, retries];
+    #[cfg(feature = "otel")]
+    layers.extend(crate::otel::layer()?);
+    tracing_subscriber::registry()
+        .with(layers)
+        .try_init()
+        .map_err(|e| anyhow::anyhow!("logging init failed: {e}"))
+}
+
+/// Counts object_store's retries into `parquet_lens_core::retry_count`, which
+/// the status bar reports; object_store itself only logs them.
+struct RetryCounter;
+
+impl<S: Subscriber> Layer<S> for RetryCounter {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut message = RetryMessage(false);
+        event.record(&mut message);
+        if message.0 {
+            parquet_lens_core::record_retry();
+        }
+    }
+}
+
+// whether the event's message announces a backoff (the module also logs other things)
+struct RetryMessage(bool);
+
+impl Visit for RetryMessage {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 |= format!("{value:?}").contains("backing off");
+        }
+    }
+}
+
+/// Flushes anything buffered by exporters; call before exiting.
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    crate::otel::shutdown();
+}
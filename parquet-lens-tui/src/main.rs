@@ -1,53 +1,43 @@
+mod ci;
+mod lineage;
+mod logging;
+mod metrics;
+#[cfg(feature = "otel")]
+mod otel;
+mod poll;
+mod rest;
+mod rpc;
 mod tui;
+mod webhook;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
-use parquet::file::metadata::ParquetMetaData;
 use parquet_lens_core::{
-    aggregate_column_stats,
-    analyze_compression,
-    analyze_encodings,
-    analyze_null_patterns,
-    analyze_partitions,
-    compare_datasets,
-    detect_duplicates,
-    detect_repair_suggestions,
-    export_csv,
-    export_json,
-    identify_engine,
-    is_gcs_uri,
-    is_s3_uri,
-    load_baseline_regressions,
-    open_parquet_file, // resolve_paths used in rp() helper
-    print_summary,
-    profile_nested_columns,
-    profile_row_groups,
-    profile_timeseries,
-    read_column_stats,
-    read_gcs_parquet_metadata,
-    read_metadata_parallel,
-    read_s3_parquet_metadata,
-    recommend_row_group_size,
-    resolve_paths,
-    sample_row_groups,
-    score_column,
-    summarize_quality,
-    AggregatedColumnStats,
-    DatasetProfile,
-    EncodingAnalysis,
-    ParquetFileInfo,
-    ParquetFilePath,
-    QualityScore,
-    SampleConfig,
+    aggregate_column_stats, analyze_encodings, analyze_null_patterns, analyze_partitions,
+    apply_full_scan_findings, check_constraints, compare_datasets, detect_duplicates,
+    detect_repair_suggestions, diff_schemas, distinct_values, export_csv,
+    export_great_expectations, export_json, export_ndjson, export_prometheus, export_sample,
+    is_azure_uri, is_gcs_uri, is_hdfs_uri, is_http_uri, is_s3_uri, load_baseline_regressions,
+    mask_top_values, open_iceberg_table, print_summary, profile_columns_with_timeout,
+    profile_dataset, profile_row_groups, prometheus_metrics, read_column_stats,
+    read_metadata_parallel, read_metadata_remote, recommend_row_group_size, resolve_paths,
+    run_rules, sample_manifest_path, scan_pii, schema_tree, score_columns, summarize_quality,
+    top_values, validate_contract, write_top_values_csv, write_top_values_json, BaselineRegression,
+    ChunkCache, ColumnProfileResult, Constraint, Contract, DatasetProfile, DiffStatus, ErrorInfo,
+    FileHandle, FileStats, FooterCache, HandleRegistry, HistogramScale, IcebergTable, ListOptions,
+    MaskSpec, MemoryTracker, ParquetFilePath, ParquetLensError, ProfileCache, ProfileReport,
+    ProfilerBuilder, Progress, ProgressSink, RemoteConfig, SampleConfig, SampleManifest,
+    SampleMethod, ScanOptions, Severity, SkipReason, StorageRegistry, StrataAllocation, WEEKDAYS,
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
+use std::io::IsTerminal;
+use std::sync::Arc;
 use std::{io, time::Duration};
-use tui::app::{App, View};
+use tui::app::{App, ScanProgress, ScanUpdate, View};
 use tui::events::handle_key;
 use tui::session::Session;
 use tui::ui::render;
@@ -62,66 +52,354 @@ fn parse_sample_pct(s: &str) -> Result<f64, String> {
     }
 }
 
+// "512M", "2GiB", "1048576": binary multiples, the i and B are optional
+fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let n: f64 = num.parse().map_err(|_| format!("not a size: {s}"))?;
+    let unit = unit.trim().to_ascii_lowercase();
+    let shift = match unit.trim_end_matches('b').trim_end_matches('i') {
+        "" => 0,
+        "k" => 10,
+        "m" => 20,
+        "g" => 30,
+        "t" => 40,
+        _ => return Err(format!("unknown size unit in {s} (use K, M, G or T)")),
+    };
+    Ok((n * (1u64 << shift) as f64) as u64)
+}
+
+fn parse_range(s: &str) -> Result<Constraint, String> {
+    Constraint::range(s).map_err(|e| e.to_string())
+}
+
+// a dotted key from `Config::keys()`, with a real name for any `<name>`
+fn parse_config_key(s: &str) -> Result<String, String> {
+    if Config::is_key(s) {
+        Ok(s.to_string())
+    } else {
+        Err(format!(
+            "unknown config key {s}; expected one of: {}",
+            Config::keys().join(", ")
+        ))
+    }
+}
+
+fn parse_mask(s: &str) -> Result<MaskSpec, String> {
+    MaskSpec::parse(s).map_err(|e| e.to_string())
+}
+
+// sampling flags shared by the profiling commands
+#[derive(Args, Clone, Default)]
+struct SampleArgs {
+    /// Profile about this percentage (0-100] of the data
+    #[arg(long, value_parser = parse_sample_pct, group = "sample_size")]
+    sample: Option<f64>,
+    /// Profile a uniform sample of exactly this many rows (row-level)
+    #[arg(long, group = "sample_size", conflicts_with = "sample_method", value_parser = clap::value_parser!(u64).range(1..))]
+    sample_rows: Option<u64>,
+    /// How --sample picks data: whole row groups (reads less) or rows from
+    /// every row group (unbiased when data is clustered)
+    #[arg(long, value_enum, requires = "sample")]
+    sample_method: Option<SampleMethodArg>,
+    #[arg(long)]
+    sample_seed: Option<u64>,
+    /// Balance the --sample/--sample-rows sample over the values of this
+    /// low-cardinality column, or over every partition of a hive-partitioned
+    /// dataset when it names a partition key (row-level)
+    #[arg(long, value_name = "COLUMN", requires = "sample_size")]
+    stratify_by: Option<String>,
+    /// proportional keeps the column's distribution; equal takes as many
+    /// rows from each value, over-sampling rare ones
+    #[arg(long, value_enum, requires = "stratify_by")]
+    stratify_allocation: Option<StrataAllocationArg>,
+    /// Scan every row even when the file is over
+    /// profiling.auto_sample_threshold_bytes
+    #[arg(long)]
+    no_auto_sample: bool,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum SampleMethodArg {
+    RowGroups,
+    Rows,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum StrataAllocationArg {
+    Proportional,
+    Equal,
+}
+
+impl SampleArgs {
+    fn is_set(&self) -> bool {
+        self.sample.is_some() || self.sample_rows.is_some()
+    }
+
+    // the same sample `apply` asks the profiler for, if one was requested
+    fn config(&self) -> Option<SampleConfig> {
+        if !self.is_set() {
+            return None;
+        }
+        let method = match (self.sample_method, &self.stratify_by, self.sample_rows) {
+            (Some(SampleMethodArg::Rows), ..) | (_, Some(_), _) | (_, _, Some(_)) => {
+                SampleMethod::Rows
+            }
+            _ => SampleMethod::RowGroups,
+        };
+        Some(SampleConfig {
+            percentage: self.sample.unwrap_or(100.0),
+            no_extrapolation: false,
+            seed: self.sample_seed,
+            method,
+            rows: self.sample_rows,
+            stratify_by: self.stratify_by.clone(),
+            allocation: self.allocation(),
+        })
+    }
+
+    fn allocation(&self) -> StrataAllocation {
+        match self.stratify_allocation {
+            Some(StrataAllocationArg::Equal) => StrataAllocation::Equal,
+            Some(StrataAllocationArg::Proportional) | None => StrataAllocation::Proportional,
+        }
+    }
+
+    fn apply(&self, mut builder: ProfilerBuilder) -> ProfilerBuilder {
+        if let Some(pct) = self.sample {
+            builder = builder.sample(pct);
+        }
+        if let Some(rows) = self.sample_rows {
+            builder = builder.sample_rows(rows);
+        }
+        if let Some(method) = self.sample_method {
+            builder = builder.sample_method(match method {
+                SampleMethodArg::RowGroups => SampleMethod::RowGroups,
+                SampleMethodArg::Rows => SampleMethod::Rows,
+            });
+        }
+        if let Some(seed) = self.sample_seed {
+            builder = builder.sample_seed(seed);
+        }
+        if let Some(column) = &self.stratify_by {
+            builder = builder.stratify_by(column, self.allocation());
+        }
+        if self.no_auto_sample {
+            builder = builder.auto_sample(None, 0);
+        }
+        builder
+    }
+}
+
 /// block_in_place wrapper to call async resolve_paths from sync context
 fn rp(input: &str) -> anyhow::Result<Vec<ParquetFilePath>> {
     tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(resolve_paths(input)))
-        .map_err(|e| anyhow::anyhow!("{e}"))
+        .map_err(anyhow::Error::from)
 }
 
-fn compute_quality_scores(
-    agg_stats: &[AggregatedColumnStats],
-    encodings: &[EncodingAnalysis],
-    total_rows: i64,
-) -> Vec<QualityScore> {
-    agg_stats
-        .iter()
-        .map(|agg| {
-            let is_plain = encodings
-                .iter()
-                .find(|e| e.column_name == agg.column_name)
-                .map(|e| e.is_plain_only)
-                .unwrap_or(false);
-            score_column(
-                &agg.column_name,
-                agg.null_percentage,
-                agg.total_distinct_count_estimate,
-                total_rows,
-                is_plain,
-            )
+/// rp, plus the Iceberg table the input names, if it is one
+fn resolve_input(input: &str) -> anyhow::Result<(Vec<ParquetFilePath>, Option<IcebergTable>)> {
+    let table = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(open_iceberg_table(input))
+    })
+    .map_err(anyhow::Error::from)?;
+    match table {
+        Some(t) => Ok((t.data_files.clone(), Some(t))),
+        None => Ok((rp(input)?, None)),
+    }
+}
+
+/// s3://, gs://, Azure, http(s):// and HDFS inputs, which have no local file to open or watch
+fn is_cloud_uri(path: &str) -> bool {
+    is_s3_uri(path)
+        || is_gcs_uri(path)
+        || is_azure_uri(path)
+        || is_http_uri(path)
+        || is_hdfs_uri(path)
+}
+
+/// A local path for `input`: the path itself, or for a cloud URI a temporary copy
+/// streamed down in chunks, deleted when the returned guard drops.
+fn local_copy(input: &str) -> anyhow::Result<(std::path::PathBuf, Option<tempfile::TempPath>)> {
+    if !is_cloud_uri(input) {
+        return Ok((std::path::PathBuf::from(input), None));
+    }
+    let tmp = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(download_temp(input))
+    })?;
+    Ok((tmp.to_path_buf(), Some(tmp)))
+}
+
+/// local_copy for every file of a dataset; cloud objects download `concurrency` at a time.
+fn local_copies(
+    paths: &[ParquetFilePath],
+    concurrency: usize,
+) -> anyhow::Result<Vec<(std::path::PathBuf, Option<tempfile::TempPath>)>> {
+    use futures::StreamExt;
+    tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(
+            futures::stream::iter(paths.iter().map(|p| async move {
+                let input = p.path.to_string_lossy();
+                if !is_cloud_uri(&input) {
+                    return Ok((p.path.clone(), None));
+                }
+                let tmp = download_temp(&input).await?;
+                Ok((tmp.to_path_buf(), Some(tmp)))
+            }))
+            .buffered(concurrency.max(1))
+            .collect::<Vec<anyhow::Result<_>>>(),
+        )
+    })
+    .into_iter()
+    .collect()
+}
+
+async fn download_temp(input: &str) -> anyhow::Result<tempfile::TempPath> {
+    let mut tmp = tempfile::Builder::new().suffix(".parquet").tempfile()?;
+    let backend = StorageRegistry::global().for_uri(input)?;
+    parquet_lens_core::download_object(backend.as_ref(), input, tmp.as_file_mut()).await?;
+    Ok(tmp.into_temp_path())
+}
+
+/// Running object count on stderr while a big prefix is listed.
+#[derive(Default)]
+struct ListingProgress {
+    shown: std::sync::atomic::AtomicBool,
+}
+
+impl ProgressSink for ListingProgress {
+    fn on_progress(&self, _operation: &str, done: Progress) {
+        self.shown.store(true, std::sync::atomic::Ordering::Relaxed);
+        eprint!("\rlisting: {} objects", done.rows);
+    }
+
+    fn on_finish(&self, _operation: &str, _done: Progress) {
+        if self.shown.swap(false, std::sync::atomic::Ordering::Relaxed) {
+            eprint!("\r\x1b[K");
+        }
+    }
+}
+
+/// read_metadata_parallel, going through the profile cache unless it is disabled;
+/// remote footers are fetched through their storage backend instead
+fn read_dataset(
+    paths: &[ParquetFilePath],
+    cache: Option<&ProfileCache>,
+) -> anyhow::Result<DatasetProfile> {
+    if paths
+        .first()
+        .is_some_and(|p| is_cloud_uri(&p.path.to_string_lossy()))
+    {
+        return tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(read_metadata_remote(paths, None))
         })
-        .collect()
+        .map_err(anyhow::Error::from);
+    }
+    match cache {
+        Some(c) => c.read_metadata(paths),
+        None => read_metadata_parallel(paths),
+    }
+    .map_err(anyhow::Error::from)
 }
 
-// note: returned ParquetFileInfo and ParquetMetaData are from paths[0] only.
-// callers needing aggregate stats across all files must use read_metadata_parallel separately.
+// note: the returned FileStats are from paths[0] only; the dataset covers all.
+// Local files go through the profile cache, so an unchanged file isn't reopened.
 fn load_file_stats(
     paths: &[ParquetFilePath],
-) -> anyhow::Result<(DatasetProfile, ParquetFileInfo, ParquetMetaData)> {
-    let dataset = read_metadata_parallel(paths).map_err(|e| anyhow::anyhow!("{e}"))?;
+    cache: Option<&ProfileCache>,
+) -> anyhow::Result<(DatasetProfile, FileStats)> {
+    let dataset = read_dataset(paths, cache)?;
     let p0_str = paths[0].path.to_string_lossy().to_string();
-    let (file_info, meta) = tokio::task::block_in_place(|| {
-        tokio::runtime::Handle::current()
-            .block_on(parquet_lens_core::open_parquet_auto(&p0_str, None))
-    })
-    .map_err(|e| anyhow::anyhow!("{e}"))?;
-    Ok((dataset, file_info, meta))
+    let stats = match cache {
+        Some(c) if !is_cloud_uri(&p0_str) => c.file_stats(&paths[0].path)?,
+        _ => {
+            let (file_info, meta) = tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current()
+                    .block_on(parquet_lens_core::open_parquet_auto(&p0_str, None))
+            })?;
+            FileStats::from_metadata(file_info, &meta)
+        }
+    };
+    Ok((dataset, stats))
+}
+
+/// block_in_place wrapper to run the async Profiler from sync context
+fn run_profiler(builder: ProfilerBuilder) -> anyhow::Result<ProfileReport> {
+    let report =
+        tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(builder.run()))
+            .map_err(anyhow::Error::from)?;
+    #[cfg(feature = "otel")]
+    otel::record_report(&report);
+    Ok(report)
+}
+
+// comparable form of a regression list, to spot changes between watch reloads
+fn regression_details(regressions: &[BaselineRegression]) -> Vec<(&str, &str)> {
+    regressions
+        .iter()
+        .map(|r| (r.column.as_str(), r.detail.as_str()))
+        .collect()
+}
+
+/// process::exit after flushing log/trace exporters
+fn exit(code: i32) -> ! {
+    logging::shutdown();
+    std::process::exit(code)
 }
 
-use parquet_lens_common::Config;
+use parquet_lens_common::{Config, RuleConfig};
 
 #[derive(Parser)]
 #[command(name = "parquet-lens", version, about = "Parquet file inspector")]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Print elapsed time and approximate peak memory after the command
+    #[arg(long, global = true)]
+    stats: bool,
+    /// More log output (-v info with analysis timings, -vv debug); RUST_LOG overrides
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Only log errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Log line format: text or json
+    #[arg(long, global = true, default_value = "text", value_parser = ["text", "json"])]
+    log_format: String,
+    /// Also report `check` and `inspect --validate` results to a CI system
+    #[arg(long, global = true, value_enum)]
+    ci: Option<ci::CiMode>,
+    /// Assume this IAM role for s3:// reads (overrides [s3] role_arn)
+    #[arg(long, global = true)]
+    s3_role_arn: Option<String>,
+    /// External id the role's trust policy asks for (overrides [s3] external_id)
+    #[arg(long, global = true, requires = "s3_role_arn")]
+    s3_external_id: Option<String>,
+    /// Session name for the assumed role, "parquet-lens" by default
+    #[arg(long, global = true, requires = "s3_role_arn")]
+    s3_session_name: Option<String>,
+    /// Pay for reads from requester-pays buckets (x-amz-request-payer)
+    #[arg(long, global = true)]
+    s3_requester_pays: bool,
+    /// Stop listing a cloud prefix after this many Parquet files
+    #[arg(long, global = true)]
+    max_files: Option<usize>,
+    /// Keep full scans under about this much memory (e.g. 512M, 2GiB) by
+    /// falling back to top-k sketches and coarser quantiles once it is reached
+    /// (overrides [profiling] memory_limit_bytes)
+    #[arg(long, global = true, value_parser = parse_size)]
+    memory_limit: Option<u64>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     Inspect {
         path: String,
-        #[arg(long, value_parser = parse_sample_pct)]
-        sample: Option<f64>,
+        #[command(flatten)]
+        sampling: SampleArgs,
         #[arg(long)]
         watch: bool,
         #[arg(long)]
@@ -129,30 +407,46 @@ enum Commands {
         #[arg(long)]
         save_baseline: bool,
         #[arg(long)]
-        sample_seed: Option<u64>,
-        #[arg(long)]
         watch_interval: Option<u64>,
         #[arg(long)]
         fail_on_regression: bool,
         #[arg(long)]
         validate: bool,
+        /// Ignore and don't update the on-disk profile, remote footer and chunk caches
+        #[arg(long)]
+        no_cache: bool,
+        /// Post to the [notifications] webhook when a --watch reload finds new regressions
+        #[arg(long)]
+        notify: bool,
+        /// Redact columns in the data preview and filter rows:
+        /// col1,col2:partial,col3:null,col4:fake (hash when no mode is given)
+        #[arg(long, value_parser = parse_mask)]
+        mask: Option<MaskSpec>,
+        /// Scan local string columns for emails, phone numbers, national IDs,
+        /// card numbers and IP addresses, flagging likely PII columns
+        #[arg(long)]
+        pii: bool,
     },
     Summary {
         path: String,
         #[arg(long)]
         save: bool,
-        #[arg(long, default_value = "plain")]
+        #[arg(long, default_value = "plain", value_parser = ["plain", "pretty"])]
         format: String,
         #[arg(long)]
         json: bool,
-        #[arg(long, value_parser = parse_sample_pct)]
-        sample: Option<f64>,
-        #[arg(long)]
-        sample_seed: Option<u64>,
+        #[command(flatten)]
+        sampling: SampleArgs,
         #[arg(long, value_delimiter = ',')]
         columns: Option<Vec<String>>,
+        /// Follow the totals with a per-column table (type, null %, distinct, size, quality)
+        #[arg(long, conflicts_with = "json")]
+        columns_table: bool,
         #[arg(long)]
         no_color: bool,
+        /// Ignore and don't update the on-disk profile, remote footer and chunk caches
+        #[arg(long)]
+        no_cache: bool,
     },
     Compare {
         path1: String,
@@ -160,18 +454,72 @@ enum Commands {
     },
     Export {
         path: String,
-        #[arg(long, default_value = "json")]
+        /// json, csv, ndjson, or ge (Great Expectations suite; implies a full scan)
+        #[arg(long, default_value = "json", value_parser = ["json", "csv", "ndjson", "ge"])]
         format: String,
         #[arg(long, value_delimiter = ',')]
         columns: Option<Vec<String>>,
         #[arg(long)]
         output: Option<String>,
-        #[arg(long, value_parser = parse_sample_pct)]
-        sample: Option<f64>,
-        #[arg(long)]
-        sample_seed: Option<u64>,
+        #[command(flatten)]
+        sampling: SampleArgs,
         #[arg(long)]
         limit: Option<usize>,
+        /// Also write every value with its count, for columns whose estimated
+        /// distinct count is at most --max-distinct, to distinct_values.csv
+        /// (.json for json/ndjson) beside the output; implies a full scan
+        #[arg(long)]
+        distinct_values: bool,
+        /// Defaults to [export] max_distinct_values
+        #[arg(long, requires = "distinct_values")]
+        max_distinct: Option<u64>,
+        /// Scan local string columns for PII; match rates go in the report and
+        /// flagged kinds in each column's quality entry
+        #[arg(long)]
+        pii: bool,
+        /// Redact columns' values in frequency tables, distinct values and
+        /// suite value sets: col1,col2:partial,col3:null (hash when no mode
+        /// is given; fake hashes too)
+        #[arg(long, value_parser = parse_mask)]
+        mask: Option<MaskSpec>,
+    },
+    /// Write a seeded sample of a local file's rows to a small Parquet file,
+    /// plus `<output>.manifest.json` recording how it was drawn
+    SampleExport {
+        path: String,
+        #[arg(long)]
+        output: String,
+        #[command(flatten)]
+        sampling: SampleArgs,
+        /// Redraw the sample described by an earlier export's manifest
+        #[arg(long, value_name = "MANIFEST", conflicts_with_all = ["sample", "sample_rows", "sample_method", "sample_seed", "stratify_by"])]
+        replay: Option<String>,
+        /// Print the manifest as JSON
+        #[arg(long)]
+        json: bool,
+        /// Redact columns in exported rows: col1,col2:partial,col3:null,col4:fake
+        /// (hash when no mode is given)
+        #[arg(long, value_parser = parse_mask)]
+        mask: Option<MaskSpec>,
+    },
+    /// Write each column's most frequent values (value, count, pct of non-null
+    /// rows) to a CSV or JSON file of their own
+    TopValues {
+        path: String,
+        /// Values kept per column; 0 keeps every counted value
+        #[arg(long, default_value_t = 20)]
+        k: usize,
+        #[arg(long, value_delimiter = ',')]
+        columns: Option<Vec<String>>,
+        /// csv or json
+        #[arg(long, default_value = "csv", value_parser = ["csv", "json"])]
+        format: String,
+        #[arg(long)]
+        output: Option<String>,
+        /// Redact columns' listed values: col1,col2:partial,col3:null
+        /// (hash when no mode is given; fake hashes too)
+        #[arg(long, value_parser = parse_mask)]
+        mask: Option<MaskSpec>,
     },
     Duplicates {
         path: String,
@@ -180,147 +528,609 @@ enum Commands {
         exact: bool,
         #[arg(long)]
         json: bool,
+        /// Fail when the duplicate pct exceeds this; defaults to [duplicates] threshold
         #[arg(long)]
         threshold: Option<f64>,
+        /// Columns left out of the row hash (ingestion timestamps, surrogate
+        /// keys), on top of any [duplicates] ignore_columns in the config
+        #[arg(long, value_delimiter = ',')]
+        ignore_columns: Vec<String>,
+    },
+    /// Full-scan columns without launching the TUI: cardinality, top values,
+    /// numeric/string/temporal profiles and histograms
+    Profile {
+        path: String,
+        /// Columns to scan; all of them when omitted
+        #[arg(long, value_delimiter = ',')]
+        columns: Option<Vec<String>>,
+        /// Defaults to [profiling] histogram_bins
+        #[arg(long)]
+        histogram_bins: Option<usize>,
+        /// Log-spaced bins for positive columns spanning three or more orders of
+        /// magnitude (latencies, sizes); defaults to [profiling] histogram_log_scale
+        #[arg(long)]
+        log_histogram: bool,
+        /// Stop reading after this many seconds and report what was seen so far;
+        /// defaults to [profiling] full_scan_timeout_secs
+        #[arg(long)]
+        timeout: Option<u64>,
+        #[arg(long)]
+        json: bool,
+        /// Ignore and don't update the on-disk profile, remote footer and chunk caches
+        #[arg(long)]
+        no_cache: bool,
     },
     /// Check quality and baseline regressions without launching TUI.
     ///
     /// Exit codes:
     ///   0 — no regressions found
-    ///   1 — regressions found (when --fail-on-regression is set)
+    ///   1 — regressions found (when --fail-on-regression is set, always with --fast)
+    ///       or a --require-* constraint violated
     ///   2 — file not found or unreadable
+    ///   3 — --fast ran over its --budget
     #[command(
-        long_about = "Check quality and baseline regressions without launching TUI.\n\nExit codes:\n  0 — no regressions found\n  1 — regressions found (when --fail-on-regression is set)\n  2 — file not found or unreadable"
+        long_about = "Check quality and baseline regressions without launching TUI.\n\nExit codes:\n  0 — no regressions found\n  1 — regressions found (when --fail-on-regression is set, always with --fast)\n      or a --require-* constraint violated\n  2 — file not found or unreadable\n  3 — --fast ran over its --budget"
     )]
     Check {
-        path: String,
-        #[arg(long, default_value = "plain")]
+        /// One path, or several with --fast (e.g. the files a pre-commit hook passes)
+        #[arg(required = true)]
+        paths: Vec<String>,
+        #[arg(long, default_value = "plain", value_parser = ["plain", "json"])]
         format: String,
         #[arg(long)]
         fail_on_regression: bool,
+        /// Write Prometheus gauges (text exposition format) to this file
+        #[arg(long)]
+        metrics_out: Option<String>,
+        /// Push the same gauges to a Prometheus Pushgateway at this URL
+        #[arg(long)]
+        pushgateway: Option<String>,
+        /// Post regressions to the [notifications] webhook
+        #[arg(long)]
+        notify: bool,
+        /// Also decode every row group: unreadable ones are skipped and reported
+        /// as quarantined, and row counts are checked against the footer. Both
+        /// count as regressions
+        #[arg(long, conflicts_with = "fast")]
+        full_scan: bool,
+        /// Footers only, one line per violation; for pre-commit hooks
+        #[arg(long)]
+        fast: bool,
+        /// Time budget in seconds for --fast across all paths
+        #[arg(long, default_value_t = 10, requires = "fast")]
+        budget: u64,
+        /// Fail if COLUMN has any nulls (repeatable). Constraint flags scan the
+        /// named columns, report violation counts with sample rows, and turn
+        /// --format json output into {"regressions": [...], "constraints": [...]}
+        #[arg(long, value_name = "COLUMN", conflicts_with = "fast")]
+        require_not_null: Vec<String>,
+        /// Fail if any non-null value of COLUMN repeats (repeatable)
+        #[arg(long, value_name = "COLUMN", conflicts_with = "fast")]
+        require_unique: Vec<String>,
+        /// Fail unless every non-null value satisfies EXPR, a filter expression
+        /// on one column such as "amount>=0" (repeatable)
+        #[arg(long, value_name = "EXPR", value_parser = parse_range, conflicts_with = "fast")]
+        require_range: Vec<Constraint>,
+        /// Also enforce the [[rules]] in this TOML file, on top of the config's.
+        /// Each check a rule sets prints its own pass/fail line, and --format
+        /// json output gains a "rules" list
+        #[arg(long, value_name = "FILE", conflicts_with = "fast")]
+        rules: Option<std::path::PathBuf>,
+        /// Scan string columns for PII and fail if any column's match rate for
+        /// one kind reaches 5%; --format json output gains a "pii" report
+        #[arg(long, conflicts_with = "fast")]
+        fail_on_pii: bool,
+        /// Validate the schema and observed values against a YAML data contract
+        /// (column names, types, nullability, ranges and allowed values). Each
+        /// violation prints with its severity; error-severity ones fail the
+        /// check, and --format json output gains a "contract" report
+        #[arg(long, value_name = "FILE", conflicts_with = "fast")]
+        contract: Option<std::path::PathBuf>,
     },
     Filter {
+        /// A file, or a directory, glob or cloud prefix to count across with a
+        /// per-file breakdown (--output then needs a single file)
         path: String,
         expr: String,
+        /// Write the matching rows here; `-` streams them to stdout (the
+        /// counts then go to stderr)
         #[arg(long)]
         output: Option<String>,
+        /// csv or jsonl; defaults to jsonl for `-` and .jsonl/.ndjson outputs
+        #[arg(long, value_parser = ["csv", "jsonl"], requires = "output")]
+        format: Option<String>,
+        /// Aggregate the matching rows instead of exporting them, e.g.
+        /// "count(*), sum(bytes), avg(latency) group by region"
+        /// (count, sum, avg, min, max)
+        #[arg(long, value_name = "SPEC", conflicts_with_all = ["output", "limit"])]
+        agg: Option<String>,
+        /// Print, per row group, the statistics, bloom filter and dictionary
+        /// evidence that skipped it or made it scan, instead of filtering
+        #[arg(long, conflicts_with_all = ["output", "agg", "limit"])]
+        explain: bool,
+        /// Compare strings in =, != and IN and LIKE patterns ignoring case
+        /// (ILIKE does this for a single pattern)
+        #[arg(long)]
+        case_insensitive: bool,
         #[arg(long)]
         limit: Option<usize>,
+        /// Redact columns in exported rows: col1,col2:partial,col3:null,col4:fake
+        /// (hash when no mode is given)
+        #[arg(long, value_parser = parse_mask)]
+        mask: Option<MaskSpec>,
     },
+    /// Print a file's schema, or with --diff the columns added, removed or
+    /// retyped between two files (exit code 1 when they differ)
     Schema {
         path: String,
+        #[arg(requires = "diff")]
+        path2: Option<String>,
+        #[arg(long, requires = "path2")]
+        diff: bool,
+        /// Show the nested structure (groups, lists, maps) instead of leaf columns
+        #[arg(long, conflicts_with = "diff")]
+        tree: bool,
         #[arg(long)]
         json: bool,
     },
+    /// Print a shell completion script (bash, zsh, fish, elvish, powershell)
     Completions {
         shell: clap_complete::Shell,
     },
+    /// Write roff man pages for parquet-lens and every subcommand into DIR
+    Manpages {
+        dir: std::path::PathBuf,
+    },
+    /// Print the effective configuration, or the value of one dotted key
+    Config {
+        #[arg(value_parser = parse_config_key)]
+        key: Option<String>,
+    },
+    /// Manage the on-disk profile, remote footer and chunk caches
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Run as a long-lived backend for editors and other tools
+    Serve {
+        /// Speak JSON-RPC 2.0 over stdin/stdout (methods: initialize, profile,
+        /// schema, filterCount, shutdown)
+        #[arg(long, conflicts_with = "listen")]
+        rpc: bool,
+        /// Serve the HTTP API on this address, e.g. 127.0.0.1:8080 (GET /profile,
+        /// POST /check, POST /compare)
+        #[arg(long)]
+        listen: Option<String>,
+        /// Re-profile the [poll] datasets on a schedule: an interval ("15m")
+        /// or a 5-field cron expression in UTC ("0 6 * * 1-5")
+        #[arg(long)]
+        poll: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Delete every cached profile, footer and column chunk
+    Clear,
+}
+
+/// Subcommands whose stdout is JSON; their failures are reported as JSON too.
+fn wants_json(command: &Commands) -> bool {
+    match command {
+        Commands::Summary { json, .. }
+        | Commands::Duplicates { json, .. }
+        | Commands::Profile { json, .. }
+        | Commands::Schema { json, .. } => *json,
+        Commands::Check { format, .. } => format == "json",
+        _ => false,
+    }
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
-    let config = Config::load().unwrap_or_else(|e| {
-        eprintln!(
-            "warning: config load failed ({}): {e} — using defaults",
+    // the TUI owns the terminal, so its logs go to a file next to the config
+    let tui_mode = matches!(
+        cli.command,
+        Commands::Inspect {
+            validate: false,
+            ..
+        }
+    );
+    let log_file = tui_mode.then(|| Config::config_path().with_file_name("parquet-lens.log"));
+    let json_logs = match cli.log_format.as_str() {
+        "text" => false,
+        "json" => true,
+        other => anyhow::bail!("Unknown log format: {other} (use text or json)"),
+    };
+    logging::init(cli.verbose, cli.quiet, json_logs, log_file.as_deref())?;
+    let mut config = Config::load().unwrap_or_else(|e| {
+        tracing::warn!(
+            "config load failed ({}): {e} — using defaults",
             Config::config_path().display()
         );
         Config::default()
     });
-    match cli.command {
-        Commands::Inspect {
-            path,
-            sample,
-            watch,
-            no_sample_extrapolation,
-            save_baseline,
-            sample_seed,
-            watch_interval,
-            fail_on_regression,
-            validate,
-        } => {
-            if validate {
-                run_validate(path, sample, sample_seed, &config)?;
-            } else {
-                run_tui(
+    if let Some(arn) = &cli.s3_role_arn {
+        config.s3.role_arn = Some(arn.clone());
+        config.s3.external_id = cli.s3_external_id.clone();
+        config.s3.session_name = cli.s3_session_name.clone();
+    }
+    config.s3.requester_pays |= cli.s3_requester_pays;
+    if let Some(limit) = cli.memory_limit {
+        config.profiling.memory_limit_bytes = Some(limit);
+    }
+    parquet_lens_core::set_remote_config(RemoteConfig::from_config(&config));
+    parquet_lens_core::set_http_config(config.http.clone());
+    parquet_lens_core::set_hdfs_config(config.hdfs.clone());
+    parquet_lens_core::set_retry_config(config.retry.clone());
+    parquet_lens_core::set_list_options(ListOptions {
+        max_files: cli.max_files,
+        progress: (!cli.quiet && io::stderr().is_terminal())
+            .then(|| Arc::new(ListingProgress::default()) as Arc<dyn ProgressSink>),
+        ..ListOptions::default()
+    });
+    let no_cache = matches!(
+        cli.command,
+        Commands::Inspect { no_cache: true, .. }
+            | Commands::Summary { no_cache: true, .. }
+            | Commands::Profile { no_cache: true, .. }
+    );
+    if !no_cache {
+        parquet_lens_core::set_footer_cache(Some(FooterCache::open_default(
+            std::time::Duration::from_secs(config.cache.footer_ttl_secs),
+        )));
+        if config.cache.chunk_cache_mb > 0 {
+            parquet_lens_core::set_chunk_cache(Some(ChunkCache::open_default(
+                config.cache.chunk_cache_mb * 1024 * 1024,
+            )));
+        }
+    }
+    let started = std::time::Instant::now();
+    let memory = (cli.stats || config.profiling.memory_limit_bytes.is_some()).then(|| {
+        let tracker = config
+            .profiling
+            .memory_limit_bytes
+            .map_or_else(MemoryTracker::default, MemoryTracker::with_limit);
+        std::sync::Arc::new(tracker)
+    });
+    let scan_opts = ScanOptions {
+        memory: memory.clone(),
+        ..ScanOptions::from_config(&config.profiling)
+    };
+    let json_errors = wants_json(&cli.command);
+    let number_format = config.display.number_format();
+    let result = (|| -> anyhow::Result<()> {
+        match cli.command {
+            Commands::Inspect {
+                path,
+                sampling,
+                watch,
+                no_sample_extrapolation,
+                save_baseline,
+                watch_interval,
+                fail_on_regression,
+                validate,
+                no_cache,
+                notify,
+                mask,
+                pii,
+            } => {
+                if validate {
+                    run_validate(path, &sampling, &scan_opts, cli.ci, &config)?;
+                } else {
+                    run_tui(
+                        path,
+                        config,
+                        &sampling,
+                        no_sample_extrapolation,
+                        save_baseline,
+                        watch,
+                        watch_interval,
+                        fail_on_regression,
+                        no_cache,
+                        notify,
+                        mask.unwrap_or_default(),
+                        pii,
+                    )?
+                }
+            }
+            Commands::Summary {
+                path,
+                save,
+                format,
+                json,
+                sampling,
+                columns,
+                columns_table,
+                no_color,
+                no_cache,
+            } => run_summary(
+                path,
+                save,
+                &format,
+                json,
+                &sampling,
+                columns,
+                columns_table,
+                no_color,
+                no_cache,
+                &config,
+                &scan_opts,
+            )?,
+            Commands::Compare { path1, path2 } => run_compare(path1, path2, config)?,
+            Commands::Export {
+                path,
+                format,
+                columns,
+                output,
+                sampling,
+                limit,
+                distinct_values,
+                max_distinct,
+                pii,
+                mask,
+            } => {
+                let distinct = distinct_values
+                    .then(|| max_distinct.unwrap_or(config.export.max_distinct_values));
+                run_export(
                     path,
+                    format,
+                    columns,
+                    output,
+                    &sampling,
+                    limit,
+                    distinct,
+                    pii,
+                    &mask.unwrap_or_default(),
                     config,
-                    sample,
-                    no_sample_extrapolation,
-                    save_baseline,
-                    sample_seed,
-                    watch,
-                    watch_interval,
-                    fail_on_regression,
+                    &scan_opts,
+                )?
+            }
+            Commands::SampleExport {
+                path,
+                output,
+                sampling,
+                replay,
+                json,
+                mask,
+            } => run_sample_export(path, output, &sampling, replay, json, mask, &scan_opts)?,
+            Commands::TopValues {
+                path,
+                k,
+                columns,
+                format,
+                output,
+                mask,
+            } => run_top_values(
+                path,
+                k,
+                columns,
+                format,
+                output,
+                &mask.unwrap_or_default(),
+                &config,
+                &scan_opts,
+            )?,
+            Commands::Duplicates {
+                path,
+                exact,
+                json,
+                threshold,
+                ignore_columns,
+            } => run_duplicates(
+                path,
+                exact,
+                json,
+                threshold,
+                ignore_columns,
+                &config,
+                scan_opts.clone(),
+            )?,
+            Commands::Profile {
+                path,
+                columns,
+                histogram_bins,
+                log_histogram,
+                timeout,
+                json,
+                no_cache,
+            } => {
+                let mut opts = scan_opts.clone();
+                if log_histogram {
+                    opts.histogram_scale = HistogramScale::Log;
+                }
+                run_profile(
+                    path,
+                    columns,
+                    histogram_bins,
+                    timeout,
+                    json,
+                    no_cache,
+                    &config,
+                    &opts,
                 )?
             }
+            Commands::Check {
+                mut paths,
+                format,
+                fail_on_regression,
+                metrics_out,
+                pushgateway,
+                notify,
+                full_scan,
+                fast,
+                budget,
+                require_not_null,
+                require_unique,
+                require_range,
+                rules,
+                fail_on_pii,
+                contract,
+            } => {
+                let constraints: Vec<Constraint> = require_not_null
+                    .into_iter()
+                    .map(Constraint::NotNull)
+                    .chain(require_unique.into_iter().map(Constraint::Unique))
+                    .chain(require_range)
+                    .collect();
+                let contract = contract.as_deref().map(Contract::load).transpose()?;
+                let mut rules_config = config.rules.clone();
+                if let Some(file) = &rules {
+                    rules_config.extend(RuleConfig::load_file(file)?);
+                }
+                if fast {
+                    run_check_fast(paths, budget, &config)?;
+                } else if paths.len() > 1 {
+                    anyhow::bail!("check takes one path; pass several only with --fast");
+                } else {
+                    run_check(
+                        paths.remove(0),
+                        &format,
+                        fail_on_regression,
+                        cli.ci,
+                        metrics_out,
+                        pushgateway,
+                        notify,
+                        full_scan,
+                        &constraints,
+                        &rules_config,
+                        fail_on_pii,
+                        contract.as_ref(),
+                        &config,
+                        &scan_opts,
+                    )?
+                }
+            }
+            Commands::Filter {
+                path,
+                expr,
+                explain: true,
+                case_insensitive,
+                ..
+            } => run_filter_explain(path, parse_filter(&expr, case_insensitive)?, &scan_opts)?,
+            Commands::Filter {
+                path,
+                expr,
+                agg: Some(agg),
+                mask,
+                case_insensitive,
+                ..
+            } => run_filter_aggregate(
+                path,
+                parse_filter(&expr, case_insensitive)?,
+                agg,
+                &mask.unwrap_or_default(),
+                scan_opts.clone(),
+            )?,
+            Commands::Filter {
+                path,
+                expr,
+                output,
+                format,
+                limit,
+                mask,
+                agg: None,
+                explain: false,
+                case_insensitive,
+            } => run_filter(
+                path,
+                parse_filter(&expr, case_insensitive)?,
+                output,
+                format,
+                limit,
+                &mask.unwrap_or_default(),
+                scan_opts.clone(),
+            )?,
+            Commands::Schema {
+                path,
+                path2: Some(path2),
+                json,
+                ..
+            } => run_schema_diff(path, path2, json)?,
+            Commands::Schema {
+                path, json, tree, ..
+            } => run_schema(path, json, tree)?,
+            Commands::Completions { shell } => {
+                use clap::CommandFactory;
+                clap_complete::generate(
+                    shell,
+                    &mut Cli::command(),
+                    "parquet-lens",
+                    &mut std::io::stdout(),
+                );
+            }
+            Commands::Manpages { dir } => {
+                use clap::CommandFactory;
+                std::fs::create_dir_all(&dir)?;
+                clap_mangen::generate_to(Cli::command(), &dir)?;
+                println!("wrote man pages to {}", dir.display());
+            }
+            Commands::Config { key } => run_config(&config, key)?,
+            Commands::Cache { action } => match action {
+                CacheAction::Clear => {
+                    let cache = ProfileCache::open_default();
+                    let removed = cache.clear().map_err(anyhow::Error::from)?;
+                    println!(
+                        "removed {removed} cached entries from {}",
+                        cache.dir().display()
+                    );
+                }
+            },
+            Commands::Serve { rpc, listen, poll } => {
+                let schedule = match poll.as_deref() {
+                    Some(spec) => {
+                        Some((poll::Schedule::parse(spec)?, poll::history_store(&config)?))
+                    }
+                    None => None,
+                };
+                if !rpc && listen.is_none() {
+                    let Some((schedule, store)) = schedule else {
+                        anyhow::bail!("serve needs --rpc, --listen and/or --poll");
+                    };
+                    poll::run(&config, &schedule, &store);
+                }
+                if let Some((schedule, store)) = schedule {
+                    let config = config.clone();
+                    let runtime = tokio::runtime::Handle::current();
+                    // detached: the process exits once the server stops
+                    std::thread::spawn(move || {
+                        let _rt = runtime.enter();
+                        poll::run(&config, &schedule, &store)
+                    });
+                }
+                match listen {
+                    Some(addr) => rest::serve(&addr, config)?,
+                    None => rpc::serve(&config, &scan_opts)?,
+                }
+            }
         }
-        Commands::Summary {
-            path,
-            save,
-            format,
-            json,
-            sample,
-            sample_seed,
-            columns,
-            no_color,
-        } => run_summary(
-            path,
-            save,
-            &format,
-            json,
-            sample,
-            sample_seed,
-            columns,
-            no_color,
-            &config,
-        )?,
-        Commands::Compare { path1, path2 } => run_compare(path1, path2, config)?,
-        Commands::Export {
-            path,
-            format,
-            columns,
-            output,
-            sample,
-            sample_seed,
-            limit,
-        } => run_export(
-            path,
-            format,
-            columns,
-            output,
-            sample,
-            sample_seed,
-            limit,
-            config,
-        )?,
-        Commands::Duplicates {
-            path,
-            exact,
-            json,
-            threshold,
-        } => run_duplicates(path, exact, json, threshold)?,
-        Commands::Check {
-            path,
-            format,
-            fail_on_regression,
-        } => run_check(path, &format, fail_on_regression)?,
-        Commands::Filter {
-            path,
-            expr,
-            output,
-            limit,
-        } => run_filter(path, expr, output, limit)?,
-        Commands::Schema { path, json } => run_schema(path, json)?,
-        Commands::Completions { shell } => {
-            use clap::CommandFactory;
-            clap_complete::generate(
-                shell,
-                &mut Cli::command(),
-                "parquet-lens",
-                &mut std::io::stdout(),
-            );
+        Ok(())
+    })();
+    logging::shutdown();
+    if let Err(e) = result {
+        if !json_errors {
+            return Err(e);
         }
+        // keep stdout parseable for callers that asked for JSON
+        let info = match e.downcast_ref::<ParquetLensError>() {
+            Some(pe) => pe.info(),
+            None => ErrorInfo {
+                code: "other".into(),
+                message: format!("{e:#}"),
+                retryable: false,
+            },
+        };
+        println!("{}", serde_json::json!({ "error": info }));
+        std::process::exit(1);
+    }
+    if let Some(m) = memory.filter(|_| cli.stats) {
+        // stderr keeps --json output on stdout parseable
+        eprintln!(
+            "stats: {:.2}s elapsed, ~{} peak memory (accumulators, hash sets, decoded batches)",
+            started.elapsed().as_secs_f64(),
+            number_format.bytes(m.peak())
+        );
     }
     Ok(())
 }
@@ -330,41 +1140,28 @@ fn run_duplicates(
     exact: bool,
     json: bool,
     threshold: Option<f64>,
+    ignore_columns: Vec<String>,
+    config: &Config,
+    opts: ScanOptions,
 ) -> anyhow::Result<()> {
-    let dup_path = if is_s3_uri(&input_path) || is_gcs_uri(&input_path) {
-        // download to tempfile for cloud paths
-        let bytes = if is_s3_uri(&input_path) {
-            tokio::task::block_in_place(|| {
-                tokio::runtime::Handle::current().block_on(parquet_lens_core::read_s3_range(
-                    &input_path,
-                    0,
-                    i64::MAX,
-                    None,
-                ))
-            })
-            .map_err(|e| anyhow::anyhow!("{e}"))?
-        } else {
-            // GCS: fetch full object
-            tokio::task::block_in_place(|| {
-                tokio::runtime::Handle::current()
-                    .block_on(parquet_lens_core::read_gcs_parquet_metadata(&input_path))
-            })
-            .map_err(|_| anyhow::anyhow!("GCS download not fully supported for duplicates"))?;
-            anyhow::bail!(
-                "GCS duplicate detection requires local file download (not yet implemented)"
-            );
-        };
-        let mut tmp = tempfile::NamedTempFile::new()?;
-        std::io::Write::write_all(&mut tmp, &bytes)?;
-        tmp.into_temp_path().to_path_buf()
-    } else {
-        std::path::PathBuf::from(&input_path)
-    };
-    let report = detect_duplicates(&dup_path, exact).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let (config_ignores, config_threshold) = config.duplicates.resolve(&input_path);
+    let threshold = threshold.or(config_threshold);
+    // `_download` removes a cloud input's local copy on return
+    let (dup_path, _download) = local_copy(&input_path)?;
+    let ignore_columns = merge_ignored_columns(&dup_path, config_ignores, ignore_columns)?;
+    let report =
+        detect_duplicates(&dup_path, exact, &ignore_columns, &opts).map_err(anyhow::Error::from)?;
     if json {
         println!("{}", serde_json::to_string_pretty(&report)?);
     } else {
         println!("{:<24} {}", "total_rows:", report.total_rows);
+        if !report.ignored_columns.is_empty() {
+            println!(
+                "{:<24} {}",
+                "ignored_columns:",
+                report.ignored_columns.join(", ")
+            );
+        }
         println!(
             "{:<24} {}",
             "estimated_duplicates:", report.estimated_duplicates
@@ -380,51 +1177,517 @@ fn run_duplicates(
                 "FAIL: duplicate rate {:.2}% exceeds threshold {:.2}%",
                 report.estimated_duplicate_pct, thr
             );
-            std::process::exit(1);
+            exit(1);
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_profile(
+    input_path: String,
+    columns: Option<Vec<String>>,
+    histogram_bins: Option<usize>,
+    timeout: Option<u64>,
+    json: bool,
+    no_cache: bool,
+    config: &Config,
+    scan_opts: &ScanOptions,
+) -> anyhow::Result<()> {
+    let bins = histogram_bins.unwrap_or(config.profiling.histogram_bins);
+    let timeout = timeout.or(config.profiling.full_scan_timeout_secs);
+    let cols = columns.as_deref();
+    let results = if is_cloud_uri(&input_path) {
+        let (path, _download) = local_copy(&input_path)?;
+        profile_columns_with_timeout(&path, cols, bins, timeout, scan_opts)
+    } else {
+        let (paths, _) = resolve_input(&input_path)?;
+        let cache = (!no_cache).then(|| ProfileCache::from_config(&config.cache));
+        match (paths.as_slice(), cache) {
+            ([], _) => {
+                return Err(ParquetLensError::NotFound(format!(
+                    "no Parquet files match {input_path}"
+                ))
+                .into())
+            }
+            ([one], Some(c)) => c.profile_columns(&one.path, cols, bins, timeout, scan_opts),
+            ([one], None) => {
+                profile_columns_with_timeout(&one.path, cols, bins, timeout, scan_opts)
+            }
+            (_, Some(c)) => c.profile_dataset(&paths, cols, bins, timeout, scan_opts),
+            (_, None) => profile_dataset(&paths, cols, bins, timeout, scan_opts),
+        }
+    }
+    .map_err(anyhow::Error::from)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        for (i, r) in results.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            print_column_profile(r);
         }
     }
+    if results.iter().any(|r| r.truncated) {
+        eprintln!(
+            "warning: scan stopped after {}s; results cover only the rows read",
+            timeout.unwrap_or_default()
+        );
+    }
     Ok(())
 }
 
+/// One column of `profile` output: a header line, then `key: value` rows.
+fn print_column_profile(r: &ColumnProfileResult) {
+    println!("{}", r.column_name);
+    println!(
+        "  {:<22} ~{} (±{:.1}%)",
+        "distinct:",
+        r.cardinality.approximate_distinct,
+        r.cardinality.error_rate * 100.0
+    );
+    if let Some(n) = &r.numeric {
+        println!("  {:<22} {} .. {}", "range:", n.min, n.max);
+        println!("  {:<22} {:.4} (stddev {:.4})", "mean:", n.mean, n.stddev);
+        println!(
+            "  {:<22} p25 {}  p50 {}  p75 {}  p99 {}",
+            "percentiles:", n.p25, n.p50, n.p75, n.p99
+        );
+        if n.iqr_outlier_count > 0 || n.zscore_outlier_count > 0 {
+            let examples: Vec<String> = n.outlier_examples.iter().map(|v| v.to_string()).collect();
+            println!(
+                "  {:<22} {} IQR ({:.2}%), {} beyond 3 stddev; e.g. {}",
+                "outliers:",
+                n.iqr_outlier_count,
+                n.outlier_pct,
+                n.zscore_outlier_count,
+                examples.join(", ")
+            );
+        }
+        if let Some(b) = &n.benford {
+            println!(
+                "  {:<22} {} (MAD {:.4}, chi-square {:.1})",
+                "benford:",
+                b.conformity.label(),
+                b.mad,
+                b.chi_square
+            );
+        }
+    }
+    if let Some(s) = &r.string {
+        println!(
+            "  {:<22} {} .. {} (mean {:.1})",
+            "length:", s.min_length, s.max_length, s.mean_length
+        );
+        let matches: Vec<String> = s
+            .patterns
+            .matches()
+            .iter()
+            .map(|(name, pct)| format!("{name} {pct:.1}%"))
+            .collect();
+        if !matches.is_empty() {
+            println!("  {:<22} {}", "patterns:", matches.join(", "));
+        }
+        let e = &s.encoding;
+        if e.affected_count > 0 {
+            println!(
+                "  {:<22} {:.1}% (mojibake {}, control {}, replacement {}, mixed script {})",
+                "encoding anomalies:",
+                e.affected_pct(s.count),
+                e.mojibake_count,
+                e.control_char_count,
+                e.replacement_char_count,
+                e.mixed_script_count
+            );
+        }
+    }
+    if let Some(t) = &r.temporal {
+        if let Some(days) = t.range_days {
+            println!("  {:<22} {:.1} days", "span:", days);
+        }
+        let weekdays: Vec<String> = WEEKDAYS
+            .iter()
+            .zip(&t.weekday_distribution)
+            .map(|(d, n)| format!("{d} {n}"))
+            .collect();
+        if !weekdays.is_empty() {
+            println!("  {:<22} {}", "weekdays (UTC):", weekdays.join(", "));
+        }
+        if !t.gaps.is_empty() {
+            let gaps: Vec<&str> = t.gaps.iter().map(|g| g.label.as_str()).collect();
+            println!("  {:<22} {}", "no rows:", gaps.join(", "));
+        }
+    }
+    if let Some(b) = &r.boolean {
+        println!(
+            "  {:<22} {} true / {} false",
+            "values:", b.true_count, b.false_count
+        );
+    }
+    if let Some(b) = &r.binary {
+        println!(
+            "  {:<22} {} .. {} (mean {:.1})",
+            "length:", b.min_length, b.max_length, b.mean_length
+        );
+    }
+    if let Some(f) = r.frequency.as_ref().filter(|f| !f.top_values.is_empty()) {
+        let label = if f.approximate {
+            "top values (approx):"
+        } else {
+            "top values:"
+        };
+        let top: Vec<String> = f
+            .top_values
+            .iter()
+            .take(5)
+            .map(|e| format!("{} ({:.1}%)", e.value, e.percentage))
+            .collect();
+        println!("  {:<22} {}", label, top.join(", "));
+    }
+    if let Some(h) = &r.histogram {
+        let counts: Vec<String> = h.iter().map(|b| b.count.to_string()).collect();
+        println!("  {:<22} {}", "histogram:", counts.join(" "));
+    }
+    if let Some(e) = &r.entropy {
+        println!(
+            "  {:<22} {:.2} bits/value, ~{:.0}% compressible",
+            "entropy:", e.bits_per_value, e.compressible_pct
+        );
+    }
+}
+
+/// `flags` plus whichever configured `defaults` the file actually has, so a
+/// shared default doesn't fail datasets without that column; unknown flag
+/// columns are still reported by the scan.
+fn merge_ignored_columns(
+    path: &std::path::Path,
+    defaults: Vec<String>,
+    flags: Vec<String>,
+) -> anyhow::Result<Vec<String>> {
+    let schema = HandleRegistry::global()
+        .get(path)?
+        .arrow_metadata()?
+        .schema()
+        .clone();
+    let mut merged: Vec<String> = defaults
+        .into_iter()
+        .filter(|c| schema.index_of(c).is_ok())
+        .collect();
+    for c in flags {
+        if !merged.contains(&c) {
+            merged.push(c);
+        }
+    }
+    Ok(merged)
+}
+
+fn parse_filter(
+    expr: &str,
+    case_insensitive: bool,
+) -> anyhow::Result<parquet_lens_core::Predicate> {
+    let predicate = parquet_lens_core::parse_predicate(expr).map_err(|e| anyhow::anyhow!("{e}"))?;
+    Ok(if case_insensitive {
+        predicate.ignore_case()
+    } else {
+        predicate
+    })
+}
+
 fn run_filter(
     input_path: String,
-    expr: String,
+    predicate: parquet_lens_core::Predicate,
     output: Option<String>,
+    format: Option<String>,
     limit: Option<usize>,
+    mask: &MaskSpec,
+    opts: ScanOptions,
 ) -> anyhow::Result<()> {
-    let predicate =
-        parquet_lens_core::parse_predicate(&expr).map_err(|e| anyhow::anyhow!("{e}"))?;
-    let path = std::path::Path::new(&input_path);
-    let result =
-        parquet_lens_core::filter_count(path, &predicate).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let to_stdout = output.as_deref() == Some("-");
+    let format = format.unwrap_or_else(|| {
+        let jsonl = to_stdout
+            || output
+                .as_deref()
+                .is_some_and(|o| o.ends_with(".jsonl") || o.ends_with(".ndjson"));
+        if jsonl { "jsonl" } else { "csv" }.to_string()
+    });
+    // keep stdout for the rows when they are streamed there
+    let mut log: Box<dyn std::io::Write> = if to_stdout {
+        Box::new(std::io::stderr())
+    } else {
+        Box::new(std::io::stdout())
+    };
+    // a directory, glob or cloud prefix: count per file and add up
+    if !std::path::Path::new(&input_path).is_file() {
+        let paths = rp(&input_path)?;
+        let single = matches!(paths.as_slice(), [p] if p.path.to_string_lossy() == input_path);
+        if !single {
+            if output.is_some() {
+                anyhow::bail!(
+                    "--output needs a single file; {input_path} matched {} files",
+                    paths.len()
+                );
+            }
+            return run_dataset_filter(&paths, &predicate, &opts);
+        }
+    }
+    let (path, _download) = local_copy(&input_path)?;
+    let path = path.as_path();
+    let Some(out_path) = output else {
+        let result = parquet_lens_core::filter_count(path, &predicate, &opts)
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        print_filter_counts(
+            &mut log,
+            result.matched_rows,
+            result.scanned_rows,
+            [
+                result.skipped_rgs,
+                result.bloom_skipped_rgs,
+                result.dict_skipped_rgs,
+            ],
+            result.total_rgs,
+        )?;
+        return Ok(());
+    };
+    // mask before creating the file so a bad --mask leaves nothing behind
+    let mut mask = mask.clone();
+    mask.fit_with_handle(&HandleRegistry::global().get(path)?, &opts)?;
+    // created on the first matching batch so an empty result writes nothing
+    let mut sink: Option<RowSink> = None;
+    let mut written = 0usize;
+    let streamed =
+        parquet_lens_core::filter_rows_each(path, &predicate, limit, &opts, &mut |batch| {
+            let batch = mask.apply(&batch).map_err(|e| e.to_string())?;
+            if sink.is_none() {
+                sink = Some(RowSink::create(&out_path, &format).map_err(|e| e.to_string())?);
+            }
+            written += batch.num_rows();
+            sink.as_mut()
+                .expect("sink created above")
+                .write(&batch)
+                .map_err(|e| e.to_string())
+        });
+    // flush whatever was written even when a later batch failed
+    let finished = sink.map(RowSink::finish).transpose();
+    let result = streamed.map_err(|e| anyhow::anyhow!("{e}"))?;
+    finished?;
+    print_filter_counts(
+        &mut log,
+        result.matched_rows,
+        result.scanned_rows,
+        [
+            result.skipped_rgs,
+            result.bloom_skipped_rgs,
+            result.dict_skipped_rgs,
+        ],
+        result.total_rgs,
+    )?;
+    if limit.is_some_and(|l| written >= l) {
+        writeln!(
+            log,
+            "stopped at --limit {written}; counts cover the rows read so far"
+        )?;
+    }
+    if written == 0 {
+        writeln!(log, "no matching rows — {format} not written")?;
+    } else if !to_stdout {
+        writeln!(log, "exported to {out_path}")?;
+    }
+    Ok(())
+}
+
+/// Where `filter --output` streams its rows, one batch at a time.
+enum RowSink {
+    Csv(Box<arrow::csv::Writer<Box<dyn std::io::Write>>>),
+    Jsonl(Box<dyn std::io::Write>),
+}
+
+impl RowSink {
+    fn create(out_path: &str, format: &str) -> anyhow::Result<Self> {
+        let out: Box<dyn std::io::Write> = if out_path == "-" {
+            Box::new(std::io::stdout().lock())
+        } else {
+            Box::new(std::io::BufWriter::new(std::fs::File::create(out_path)?))
+        };
+        Ok(if format == "jsonl" {
+            RowSink::Jsonl(out)
+        } else {
+            RowSink::Csv(Box::new(
+                arrow::csv::WriterBuilder::new()
+                    .with_header(true)
+                    .build(out),
+            ))
+        })
+    }
+
+    fn write(&mut self, batch: &arrow::record_batch::RecordBatch) -> anyhow::Result<()> {
+        match self {
+            RowSink::Csv(w) => w.write(batch)?,
+            RowSink::Jsonl(out) => {
+                parquet_lens_core::write_rows_jsonl(out, std::slice::from_ref(batch))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        let mut out = match self {
+            RowSink::Csv(w) => w.into_inner(),
+            RowSink::Jsonl(out) => out,
+        };
+        out.flush()?;
+        Ok(())
+    }
+}
+
+fn run_filter_explain(
+    input_path: String,
+    predicate: parquet_lens_core::Predicate,
+    opts: &ScanOptions,
+) -> anyhow::Result<()> {
+    let paths = rp(&input_path)?;
+    let dataset = paths.len() > 1;
+    let copies = local_copies(&paths, opts.threads.unwrap_or(8))?;
+    for (p, (local, _download)) in paths.iter().zip(&copies) {
+        let groups = parquet_lens_core::explain_filter(local, &predicate)
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        if dataset {
+            println!("{}", p.path.display());
+        }
+        let skipped = groups.iter().filter(|g| g.skipped_by.is_some()).count();
+        println!("predicate: {predicate}");
+        println!("skipped row groups: {skipped}/{}", groups.len());
+        for g in &groups {
+            let decision = match g.skipped_by {
+                Some(SkipReason::Statistics) => "skipped by statistics",
+                Some(SkipReason::BloomFilter) => "skipped by bloom filter",
+                Some(SkipReason::Dictionary) => "skipped by dictionary",
+                None => "scanned",
+            };
+            println!(
+                "row group {} ({} rows): {decision}",
+                g.row_group, g.num_rows
+            );
+            for line in &g.evidence {
+                println!("  {line}");
+            }
+        }
+        if dataset {
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn run_filter_aggregate(
+    input_path: String,
+    predicate: parquet_lens_core::Predicate,
+    spec: String,
+    mask: &MaskSpec,
+    opts: ScanOptions,
+) -> anyhow::Result<()> {
+    let query = parquet_lens_core::parse_aggregate(&spec).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let copies = local_copies(&rp(&input_path)?, opts.threads.unwrap_or(8))?;
+    let local: Vec<std::path::PathBuf> = copies.iter().map(|(p, _)| p.clone()).collect();
+    let mut result = parquet_lens_core::filter_aggregate(&local, &predicate, &query, &opts)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    // group keys are row values, so they get the same redaction as exported rows
+    mask.apply_to_rows(&result.headers, &mut result.rows);
     println!("matched_rows:  {}", result.matched_rows);
     println!("scanned_rows:  {}", result.scanned_rows);
-    println!("skipped_rgs:   {}/{}", result.skipped_rgs, result.total_rgs);
-    if let Some(out_path) = output {
-        let batches = parquet_lens_core::filter_rows(path, &predicate, limit)
-            .map_err(|e| anyhow::anyhow!("{e}"))?;
-        if batches.is_empty() {
-            println!("no matching rows — CSV not written");
-            return Ok(());
-        }
-        let mut file = std::fs::File::create(&out_path)?;
-        let schema = batches[0].schema();
-        let mut writer = arrow::csv::WriterBuilder::new()
-            .with_header(true)
-            .build(&mut file);
-        for batch in &batches {
-            writer.write(batch).map_err(|e| anyhow::anyhow!("{e}"))?;
-        }
-        drop(writer);
-        println!("exported to {out_path}");
-        let _ = schema; // suppress unused warning
+    println!();
+    let widths: Vec<usize> = (0..result.headers.len())
+        .map(|i| {
+            result
+                .rows
+                .iter()
+                .map(|r| r[i].chars().count())
+                .chain([result.headers[i].chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    for row in std::iter::once(&result.headers).chain(&result.rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(v, w)| format!("{v:<w$}"))
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+    Ok(())
+}
+
+// `skipped` holds the statistics, bloom filter and dictionary skips
+fn print_filter_counts(
+    out: &mut dyn std::io::Write,
+    matched: u64,
+    scanned: u64,
+    skipped: [usize; 3],
+    total_rgs: usize,
+) -> std::io::Result<()> {
+    writeln!(out, "matched_rows:  {matched}")?;
+    writeln!(out, "scanned_rows:  {scanned}")?;
+    writeln!(out, "skipped_rgs:   {}/{total_rgs}", skipped[0])?;
+    writeln!(out, "bloom_skipped: {}/{total_rgs}", skipped[1])?;
+    writeln!(out, "dict_skipped:  {}/{total_rgs}", skipped[2])
+}
+
+fn run_dataset_filter(
+    paths: &[ParquetFilePath],
+    predicate: &parquet_lens_core::Predicate,
+    opts: &ScanOptions,
+) -> anyhow::Result<()> {
+    let copies = local_copies(paths, opts.threads.unwrap_or(8))?;
+    let local: Vec<std::path::PathBuf> = copies.iter().map(|(p, _)| p.clone()).collect();
+    let result = parquet_lens_core::filter_count_dataset(&local, predicate, opts)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    print_filter_counts(
+        &mut std::io::stdout(),
+        result.matched_rows,
+        result.scanned_rows,
+        [
+            result.skipped_rgs,
+            result.bloom_skipped_rgs,
+            result.dict_skipped_rgs,
+        ],
+        result.total_rgs,
+    )?;
+    println!("files:         {}", result.files.len());
+    println!();
+    println!(
+        "{:<40} {:>12} {:>12} {:>14}",
+        "file", "matched", "scanned", "skipped rgs"
+    );
+    // name downloaded objects by their URI, not the temporary copy
+    for (p, f) in paths.iter().zip(&result.files) {
+        let r = &f.result;
+        let skipped = r.skipped_rgs + r.bloom_skipped_rgs + r.dict_skipped_rgs;
+        println!(
+            "{:<40} {:>12} {:>12} {:>14}",
+            p.path.display(),
+            r.matched_rows,
+            r.scanned_rows,
+            format!("{skipped}/{}", r.total_rgs)
+        );
     }
     Ok(())
 }
 
-fn run_schema(input_path: String, json: bool) -> anyhow::Result<()> {
+fn run_schema(input_path: String, json: bool, tree: bool) -> anyhow::Result<()> {
     let path = std::path::Path::new(&input_path);
-    let schema = parquet_lens_core::extract_schema(path).map_err(|e| anyhow::anyhow!("{e}"))?;
+    if tree {
+        let tree = schema_tree(HandleRegistry::global().get(path)?.metadata());
+        if json {
+            println!("{}", serde_json::to_string_pretty(&tree)?);
+        } else {
+            for line in tree.render() {
+                println!("{line}");
+            }
+        }
+        return Ok(());
+    }
+    let schema = parquet_lens_core::extract_schema(path).map_err(anyhow::Error::from)?;
     if json {
         println!("{}", serde_json::to_string_pretty(&schema)?);
     } else {
@@ -446,309 +1709,535 @@ fn run_schema(input_path: String, json: bool) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn run_schema_diff(path1: String, path2: String, json: bool) -> anyhow::Result<()> {
+    let left = parquet_lens_core::extract_schema(std::path::Path::new(&path1))?;
+    let right = parquet_lens_core::extract_schema(std::path::Path::new(&path2))?;
+    let changes: Vec<_> = diff_schemas(&left, &right)
+        .into_iter()
+        .filter(|d| d.status != DiffStatus::Matching)
+        .collect();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&changes)?);
+    } else if changes.is_empty() {
+        println!("schemas match ({} columns)", left.len());
+    } else {
+        for d in &changes {
+            let (mark, types) = match d.status {
+                DiffStatus::Added => ("+", d.right_type.clone().unwrap_or_default()),
+                DiffStatus::Removed => ("-", d.left_type.clone().unwrap_or_default()),
+                _ => (
+                    "~",
+                    format!(
+                        "{} -> {}",
+                        d.left_type.as_deref().unwrap_or("-"),
+                        d.right_type.as_deref().unwrap_or("-")
+                    ),
+                ),
+            };
+            println!("{mark} {:<40} {types}", d.name);
+        }
+    }
+    if !changes.is_empty() {
+        exit(1);
+    }
+    Ok(())
+}
+
 fn run_validate(
     input_path: String,
-    sample_pct: Option<f64>,
-    sample_seed: Option<u64>,
-    _config: &Config,
+    sampling: &SampleArgs,
+    scan_opts: &ScanOptions,
+    ci: Option<ci::CiMode>,
+    config: &Config,
 ) -> anyhow::Result<()> {
     let paths = rp(&input_path)?;
     if paths.is_empty() {
         eprintln!("file not found: {input_path}");
-        std::process::exit(2);
+        exit(2);
     }
+    let builder = sampling.apply(
+        ProfilerBuilder::from_config(config)
+            .resolved_paths(paths)
+            .with_baseline(true)
+            .verify_row_counts(true)
+            .scan_options(scan_opts.clone()),
+    );
     #[allow(unreachable_code)]
-    let (dataset, _, meta) = load_file_stats(&paths).map_err(|e| {
+    let report = run_profiler(builder).map_err(|e| {
         eprintln!("load error: {e}");
-        std::process::exit(2);
-        anyhow::anyhow!("{e}")
+        exit(2);
+        e
     })?;
-    let total_rows = dataset.total_rows;
-    let col_stats = if let Some(pct) = sample_pct {
-        let cfg = SampleConfig {
-            percentage: pct,
-            no_extrapolation: false,
-            seed: sample_seed,
-        };
-        sample_row_groups(&paths[0].path, &cfg, 20)
-            .map(|sp| sp.agg_stats)
-            .unwrap_or_else(|_| {
-                let cs = read_column_stats(&meta);
-                aggregate_column_stats(&cs, total_rows)
-            })
-    } else {
-        let cs = read_column_stats(&meta);
-        aggregate_column_stats(&cs, total_rows)
-    };
-    let encodings = analyze_encodings(&meta);
-    let row_groups = profile_row_groups(&meta);
-    let quality_scores = compute_quality_scores(&col_stats, &encodings, total_rows);
-    let total_cells = total_rows * dataset.combined_schema.len() as i64;
-    let total_nulls: u64 = col_stats.iter().map(|s| s.total_null_count).sum();
-    let quality = summarize_quality(
-        quality_scores.clone(),
-        total_cells,
-        total_nulls,
-        dataset.schema_inconsistencies.is_empty(),
-        &col_stats,
-    );
-    let suggestions = detect_repair_suggestions(&row_groups, &col_stats, &encodings);
-    let schema: Vec<parquet_lens_core::ColumnSchema> = dataset
-        .combined_schema
-        .iter()
-        .map(|c| parquet_lens_core::ColumnSchema {
-            name: c.name.clone(),
-            physical_type: c.physical_type.clone(),
-            logical_type: c.logical_type.clone(),
-            repetition: c.repetition.clone(),
-            max_def_level: c.max_def_level,
-            max_rep_level: c.max_rep_level,
-        })
-        .collect();
-    let (_baseline, regressions) =
-        load_baseline_regressions(&paths[0].path, &col_stats, &quality_scores, &schema);
-    let has_issues =
-        !suggestions.is_empty() || !regressions.is_empty() || quality.overall_score < 80;
+    lineage::emit(config, "validate", &input_path, &report);
+    let quality = &report.quality;
+    let suggestions = &report.repair_suggestions;
+    let regressions = &report.baseline_regressions;
+    let row_count_issues = report
+        .row_counts
+        .as_ref()
+        .map(|c| c.lines())
+        .unwrap_or_default();
+    let has_issues = !suggestions.is_empty()
+        || !regressions.is_empty()
+        || !row_count_issues.is_empty()
+        || quality.overall_score < 80;
     println!("overall_quality: {}/100", quality.overall_score);
     println!("repair_suggestions: {}", suggestions.len());
-    for s in &suggestions {
+    for s in suggestions {
         println!("  [{}] {}", s.severity, s.issue);
     }
-    println!("regressions: {}", regressions.len());
-    for r in &regressions {
-        println!("  [{}] {} — {}", r.kind, r.column, r.detail);
+    println!("regressions: {}", regressions.len());
+    for r in regressions {
+        println!("  [{}] {} — {}", r.kind, r.column, r.detail);
+    }
+    println!("row_count_mismatches: {}", row_count_issues.len());
+    for line in &row_count_issues {
+        println!("  {line}");
+    }
+    if let Some(mode) = ci {
+        ci::publish(mode, &input_path, &report)?;
+    }
+    if has_issues {
+        exit(1);
+    }
+    Ok(())
+}
+
+fn run_config(config: &Config, key: Option<String>) -> anyhow::Result<()> {
+    let Some(key) = key else {
+        println!("# {}", Config::active_path().display());
+        print!("{}", toml::to_string_pretty(config)?);
+        return Ok(());
+    };
+    match config.get(&key) {
+        Some(serde_json::Value::String(s)) => println!("{s}"),
+        Some(serde_json::Value::Null) => println!("(unset)"),
+        Some(value) => println!("{value}"),
+        None => anyhow::bail!("unknown config key {key}"),
+    }
+    Ok(())
+}
+
+/// The profile `check` reads regressions from, with the `[profiling]` settings
+/// and the global scan options every other command runs with.
+fn check_profiler(
+    config: &Config,
+    scan_opts: &ScanOptions,
+    paths: Vec<ParquetFilePath>,
+    full_scan: bool,
+) -> ProfilerBuilder {
+    ProfilerBuilder::from_config(config)
+        .resolved_paths(paths)
+        .with_full_scan(full_scan)
+        .verify_row_counts(full_scan)
+        .with_baseline(true)
+        .scan_options(scan_opts.clone())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_check(
+    input_path: String,
+    format: &str,
+    fail_on_regression: bool,
+    ci: Option<ci::CiMode>,
+    metrics_out: Option<String>,
+    pushgateway: Option<String>,
+    notify: bool,
+    full_scan: bool,
+    constraints: &[Constraint],
+    rules: &[RuleConfig],
+    fail_on_pii: bool,
+    contract: Option<&Contract>,
+    config: &Config,
+    scan_opts: &ScanOptions,
+) -> anyhow::Result<()> {
+    let paths = rp(&input_path)?;
+    if paths.is_empty() {
+        return Err(
+            ParquetLensError::NotFound(format!("no Parquet files match {input_path}")).into(),
+        );
+    }
+    let rules: Vec<RuleConfig> = rules
+        .iter()
+        .filter(|r| r.matches(&input_path))
+        .cloned()
+        .collect();
+    if (!constraints.is_empty() || !rules.is_empty() || fail_on_pii || contract.is_some())
+        && is_cloud_uri(&input_path)
+    {
+        anyhow::bail!(
+            "--require-*, --fail-on-pii, --contract and [[rules]] read local files only; download {input_path} first"
+        );
+    }
+    let files: Vec<std::path::PathBuf> = paths.iter().map(|p| p.path.clone()).collect();
+    let constraint_results = if constraints.is_empty() {
+        Vec::new()
+    } else {
+        check_constraints(&files, constraints, scan_opts)?
+    };
+    let rule_results = run_rules(&files, &rules, scan_opts)?;
+    let pii = if fail_on_pii {
+        Some(scan_pii(&files, scan_opts)?)
+    } else {
+        None
+    };
+    let pii_flagged: Vec<_> = pii.iter().flat_map(|p| p.flagged()).collect();
+    let contract_report = contract
+        .map(|c| validate_contract(&files, c, scan_opts))
+        .transpose()?;
+    let report = run_profiler(check_profiler(config, scan_opts, paths, full_scan))?;
+    lineage::emit(config, "check", &input_path, &report);
+    let regressions = &report.baseline_regressions;
+    let row_count_issues = report
+        .row_counts
+        .as_ref()
+        .map(|c| c.lines())
+        .unwrap_or_default();
+    let violated = constraint_results.iter().filter(|c| !c.passed()).count();
+    let failed_rules = rule_results.iter().filter(|c| !c.passed()).count();
+    if format == "json"
+        && constraints.is_empty()
+        && rule_results.is_empty()
+        && pii.is_none()
+        && contract_report.is_none()
+    {
+        println!("{}", serde_json::to_string(&regressions)?);
+    } else if format == "json" {
+        println!(
+            "{}",
+            serde_json::json!({
+                "regressions": regressions,
+                "constraints": constraint_results,
+                "rules": rule_results,
+                "pii": pii,
+                "contract": contract_report,
+            })
+        );
+    } else if regressions.is_empty() && report.quarantine.is_none() && row_count_issues.is_empty() {
+        eprintln!("check: no regressions detected");
+    } else {
+        for r in regressions {
+            eprintln!("regression: {} — {}", r.column, r.detail);
+        }
+    }
+    if format != "json" {
+        for c in &constraint_results {
+            eprintln!("constraint: {}", c.line());
+        }
+        for r in &rule_results {
+            let status = if r.passed() { "pass" } else { "FAIL" };
+            eprintln!("rule: {status} {}", r.line());
+        }
+        for c in &pii_flagged {
+            eprintln!("pii: {}", c.line());
+        }
+        for v in contract_report.iter().flat_map(|r| &r.violations) {
+            eprintln!("contract: {}", v.line());
+        }
+        if contract_report
+            .as_ref()
+            .is_some_and(|r| r.violations.is_empty())
+        {
+            eprintln!("contract: ok");
+        }
+    }
+    for line in &row_count_issues {
+        eprintln!("row_count: {line}");
+    }
+    if let Some(q) = &report.quarantine {
+        eprintln!(
+            "quarantine: {} of {} rows ({:.2}%) unreadable and left out of the scan",
+            q.rows_affected, q.total_rows, q.pct_rows_affected
+        );
+        for line in q.lines() {
+            eprintln!("quarantine: {line}");
+        }
+    }
+    if let Some(mode) = ci {
+        ci::publish(mode, &input_path, &report)?;
+        if let Some(c) = &contract_report {
+            ci::publish_contract(mode, &input_path, c)?;
+        }
+    }
+    if let Some(out) = &metrics_out {
+        export_prometheus(std::path::Path::new(out), &report, &input_path)
+            .map_err(anyhow::Error::from)?;
+    }
+    if let Some(gateway) = &pushgateway {
+        metrics::push(
+            gateway,
+            &input_path,
+            prometheus_metrics(&report, &input_path),
+        )?;
+    }
+    if notify {
+        webhook::send(config, &input_path, &report)?;
+    }
+    if violated > 0 {
+        anyhow::bail!("{violated} constraint(s) violated");
+    }
+    if failed_rules > 0 {
+        anyhow::bail!("{failed_rules} of {} rule(s) failed", rule_results.len());
+    }
+    if !pii_flagged.is_empty() {
+        anyhow::bail!("{} column(s) likely hold PII", pii_flagged.len());
+    }
+    if let Some(c) = contract_report.as_ref().filter(|c| !c.passed()) {
+        anyhow::bail!(
+            "{} contract violation(s) at error severity",
+            c.count(Severity::Error)
+        );
+    }
+    if fail_on_regression && !regressions.is_empty() {
+        anyhow::bail!("{} regression(s) detected", regressions.len());
+    }
+    if fail_on_regression && !row_count_issues.is_empty() {
+        anyhow::bail!(
+            "{} row count mismatch(es) between footer and data",
+            row_count_issues.len()
+        );
+    }
+    if let Some(q) = report.quarantine.as_ref().filter(|_| fail_on_regression) {
+        anyhow::bail!(
+            "{:.2}% of rows unreadable (quarantined)",
+            q.pct_rows_affected
+        );
+    }
+    Ok(())
+}
+
+/// `check --fast`: footer-only analyses under a hard time budget, printing
+/// `<path>: <violation>` lines. Violations are baseline regressions, schema
+/// mismatches between files and high-severity repair suggestions.
+fn run_check_fast(paths: Vec<String>, budget_secs: u64, config: &Config) -> anyhow::Result<()> {
+    let builder = ProfilerBuilder::from_config(config)
+        .metadata_only(true)
+        .with_baseline(true);
+    let task = tokio::spawn(async move {
+        let mut results = Vec::new();
+        for path in paths {
+            let report = builder.clone().path(path.clone()).run().await;
+            results.push((path, report));
+        }
+        results
+    });
+    let budget = std::time::Duration::from_secs(budget_secs);
+    let finished = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(tokio::time::timeout(budget, task))
+    });
+    let Ok(results) = finished else {
+        println!("check --fast: over the {budget_secs}s budget");
+        exit(3);
+    };
+    let (mut violations, mut errors) = (0, 0);
+    for (path, report) in results? {
+        let report = match report {
+            Ok(r) => r,
+            Err(e) => {
+                println!("{path}: error: {e}");
+                errors += 1;
+                continue;
+            }
+        };
+        let mut lines = Vec::new();
+        for r in &report.baseline_regressions {
+            lines.push(format!("{} {}: {}", r.kind, r.column, r.detail));
+        }
+        for s in &report.dataset.schema_inconsistencies {
+            lines.push(format!("schema_mismatch: {s}"));
+        }
+        for s in report
+            .repair_suggestions
+            .iter()
+            .filter(|s| s.severity == "high")
+        {
+            lines.push(format!("repair: {}", s.issue));
+        }
+        for line in &lines {
+            println!("{path}: {line}");
+        }
+        violations += lines.len();
+    }
+    if errors > 0 {
+        exit(2);
     }
-    if has_issues {
-        std::process::exit(1);
+    if violations > 0 {
+        exit(1);
     }
     Ok(())
 }
 
-fn run_check(input_path: String, format: &str, fail_on_regression: bool) -> anyhow::Result<()> {
-    let paths = rp(&input_path)?;
-    if paths.is_empty() {
-        anyhow::bail!("No Parquet files found: {input_path}");
-    }
-    let (dataset, _, meta) = load_file_stats(&paths)?;
-    let col_stats = read_column_stats(&meta);
-    let total_rows = dataset.total_rows;
-    let agg_stats = aggregate_column_stats(&col_stats, total_rows);
-    let encodings = analyze_encodings(&meta);
-    let quality_scores = compute_quality_scores(&agg_stats, &encodings, total_rows);
-    let schema: Vec<parquet_lens_core::ColumnSchema> = dataset
-        .combined_schema
-        .iter()
-        .map(|c| parquet_lens_core::ColumnSchema {
-            name: c.name.clone(),
-            physical_type: c.physical_type.clone(),
-            logical_type: c.logical_type.clone(),
-            repetition: c.repetition.clone(),
-            max_def_level: c.max_def_level,
-            max_rep_level: c.max_rep_level,
-        })
-        .collect();
-    let (_, regressions) =
-        load_baseline_regressions(&paths[0].path, &agg_stats, &quality_scores, &schema);
-    if format == "json" {
-        println!("{}", serde_json::to_string(&regressions)?);
-    } else if regressions.is_empty() {
-        eprintln!("check: no regressions detected");
-    } else {
-        for r in &regressions {
-            eprintln!("regression: {} — {}", r.column, r.detail);
+// the first `max` rows of `batches`, rendered for the DataPreview view
+fn preview_rows(
+    batches: impl IntoIterator<Item = arrow::record_batch::RecordBatch>,
+    max: usize,
+) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    for batch in batches {
+        for row_idx in 0..batch.num_rows() {
+            if rows.len() >= max {
+                return rows;
+            }
+            let row: Vec<String> = (0..batch.num_columns())
+                .map(|c| {
+                    arrow::util::display::array_value_to_string(batch.column(c), row_idx)
+                        .unwrap_or_default()
+                })
+                .collect();
+            rows.push(row);
         }
     }
-    if fail_on_regression && !regressions.is_empty() {
-        anyhow::bail!("{} regression(s) detected", regressions.len());
-    }
-    Ok(())
+    rows
 }
 
 #[allow(clippy::too_many_arguments)]
 fn run_tui(
     input_path: String,
     config: Config,
-    sample_pct: Option<f64>,
+    sampling: &SampleArgs,
     no_sample_extrapolation: bool,
     save_baseline: bool,
-    sample_seed: Option<u64>,
     watch: bool,
     watch_interval: Option<u64>,
     fail_on_regression: bool,
+    no_cache: bool,
+    notify: bool,
+    mask: MaskSpec,
+    pii: bool,
 ) -> anyhow::Result<()> {
-    let paths = rp(&input_path)?;
+    let (paths, table) = resolve_input(&input_path)?;
     if paths.is_empty() {
-        anyhow::bail!("No Parquet files found: {input_path}");
-    }
-    let dataset = read_metadata_parallel(&paths).map_err(|e| anyhow::anyhow!("{e}"))?;
-    // task 18/19: handle S3/GCS metadata via specialized readers
-    let p0_str = paths[0].path.to_string_lossy();
-    let (file_info, meta) = if is_s3_uri(&p0_str) {
-        let meta = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(read_s3_parquet_metadata(
-                &p0_str,
-                config.s3.endpoint_url.as_deref(),
-            ))
-        })
-        .map_err(|e| anyhow::anyhow!("{e}"))?;
-        let fi = parquet_lens_core::ParquetFileInfo {
-            path: paths[0].path.clone(),
-            file_size: 0,
-            row_count: meta.file_metadata().num_rows(),
-            row_group_count: meta.num_row_groups(),
-            created_by: meta.file_metadata().created_by().map(|s| s.to_owned()),
-            parquet_version: meta.file_metadata().version(),
-            key_value_metadata: Vec::new(),
-            schema_fields: Vec::new(),
-        };
-        (fi, meta)
-    } else if is_gcs_uri(&p0_str) {
-        let meta = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(read_gcs_parquet_metadata(&p0_str))
-        })
-        .map_err(|e| anyhow::anyhow!("{e}"))?;
-        let fi = parquet_lens_core::ParquetFileInfo {
-            path: paths[0].path.clone(),
-            file_size: 0,
-            row_count: meta.file_metadata().num_rows(),
-            row_group_count: meta.num_row_groups(),
-            created_by: meta.file_metadata().created_by().map(|s| s.to_owned()),
-            parquet_version: meta.file_metadata().version(),
-            key_value_metadata: Vec::new(),
-            schema_fields: Vec::new(),
-        };
-        (fi, meta)
+        return Err(
+            ParquetLensError::NotFound(format!("no Parquet files match {input_path}")).into(),
+        );
+    }
+    let cache = (!no_cache).then(|| ProfileCache::from_config(&config.cache));
+    let p0_str = paths[0].path.to_string_lossy().into_owned();
+    // local files share one parsed footer across preview, timeseries and nested profiling
+    let handle: Option<FileHandle> = if is_cloud_uri(&p0_str) {
+        None
     } else {
-        open_parquet_file(&paths[0].path).map_err(|e| anyhow::anyhow!("{e}"))?
+        Some(
+            HandleRegistry::global()
+                .get(&paths[0].path)
+                .map_err(anyhow::Error::from)?,
+        )
     };
-    let row_groups = profile_row_groups(&meta);
-    let col_stats = read_column_stats(&meta);
-    let total_rows = file_info.row_count;
-    let agg_stats = aggregate_column_stats(&col_stats, total_rows);
-    let encoding_analysis = analyze_encodings(&meta);
-    let compression_analysis = analyze_compression(&meta);
-    let quality_scores = compute_quality_scores(&agg_stats, &encoding_analysis, total_rows);
 
     let mut app = App::new(input_path.clone(), config);
+    app.mask = mask;
+    app.iceberg = table;
     if let Some(s) = Session::load() {
         app.restore_from_session(&s);
     }
-    app.dataset = Some(dataset.clone());
-    app.file_info = Some(file_info);
-    app.row_groups = row_groups;
-    app.agg_stats = agg_stats;
-    app.encoding_analysis = encoding_analysis;
-    app.compression_analysis = compression_analysis;
-    app.quality_scores = quality_scores;
+    let mut builder = ProfilerBuilder::from_config(&app.config)
+        .resolved_paths(paths.clone())
+        .histogram_bins(20)
+        .with_baseline(true)
+        .scan_pii(pii);
+    if let Some(c) = &cache {
+        builder = builder.cache(c.clone());
+    }
+    if sampling.is_set() {
+        let opts = ScanOptions {
+            memory: Some(app.track_memory("sample")),
+            ..ScanOptions::from_config(&app.config.profiling)
+        };
+        builder = sampling.apply(
+            builder
+                .no_extrapolation(no_sample_extrapolation)
+                .with_full_scan(true)
+                .scan_options(opts),
+        );
+    }
+    let report = run_profiler(builder)?;
+    for w in &report.warnings {
+        tracing::warn!("{w}");
+    }
+    lineage::emit(&app.config, "inspect", &app.input_path, &report);
+    app.load_report(report);
+    app.profile_cache = cache;
 
+    app.schema_tree = handle.as_ref().map(|h| schema_tree(h.metadata()));
     // data preview: read up to max_rows_preview rows for DataPreview view
-    if !is_s3_uri(&p0_str) && !is_gcs_uri(&p0_str) {
-        let max_preview = app.config.display.max_rows_preview;
-        if let Ok(preview_file) = std::fs::File::open(&paths[0].path) {
-            if let Ok(pb) = ParquetRecordBatchReaderBuilder::try_new(preview_file) {
-                let schema = pb.schema().clone();
-                app.preview_headers = schema.fields().iter().map(|f| f.name().clone()).collect();
-                if let Ok(reader) = pb.with_batch_size(max_preview).build() {
-                    let mut rows = Vec::new();
-                    for batch_result in reader {
-                        if rows.len() >= max_preview {
-                            break;
-                        }
-                        if let Ok(batch) = batch_result {
-                            for row_idx in 0..batch.num_rows() {
-                                if rows.len() >= max_preview {
-                                    break;
-                                }
-                                let row: Vec<String> = (0..batch.num_columns())
-                                    .map(|c| {
-                                        arrow::util::display::array_value_to_string(
-                                            batch.column(c),
-                                            row_idx,
-                                        )
-                                        .unwrap_or_default()
-                                    })
-                                    .collect();
-                                rows.push(row);
-                            }
-                        }
-                    }
-                    app.preview_rows = rows;
-                }
+    let max_preview = app.config.display.max_rows_preview;
+    let mut preview = None;
+    if let Some(h) = &handle {
+        if let Ok(pb) = h.reader_builder() {
+            let schema = pb.schema().clone();
+            app.mask.check(&schema)?;
+            let opts = ScanOptions {
+                memory: Some(app.track_memory("mask")),
+                ..ScanOptions::from_config(&app.config.profiling)
+            };
+            app.mask.fit_with_handle(h, &opts)?;
+            if let Ok(reader) = pb.with_batch_size(max_preview).build() {
+                preview = Some((schema, preview_rows(reader.flatten(), max_preview)));
             }
         }
-    }
-
-    // repair suggestions
-    app.repair_suggestions =
-        detect_repair_suggestions(&app.row_groups, &app.agg_stats, &app.encoding_analysis);
-    app.rg_size_recommendation = recommend_row_group_size(&app.row_groups);
-
-    // time-series profiling — detect timestamp/date/time columns from schema
-    let ts_cols: Vec<String> = dataset
-        .combined_schema
-        .iter()
-        .filter(|c| {
-            let logical_match = c
-                .logical_type
-                .as_deref()
-                .map(|t| t.contains("Timestamp") || t.contains("Date") || t.contains("Time"))
-                .unwrap_or(false);
-            // fallback: INT96 with no logical type = legacy Spark timestamp
-            let int96_fallback = c.physical_type == "INT96" && c.logical_type.is_none();
-            logical_match || int96_fallback
-        })
-        .map(|c| c.name.clone())
-        .collect();
-    if !ts_cols.is_empty() {
-        match profile_timeseries(&paths[0].path, &ts_cols) {
-            Ok(ts) => {
-                app.timeseries_profiles = ts;
-            }
-            Err(e) => {
-                eprintln!("timeseries warning: {e}");
-                app.status_msg = format!("timeseries error: {e}");
+    } else if is_cloud_uri(&p0_str) {
+        // remote files: range-read the footer and first row group only
+        let s3_endpoint = app.config.s3.endpoint_url.clone();
+        let fetched = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(parquet_lens_core::preview_object(
+                &p0_str,
+                max_preview,
+                s3_endpoint.as_deref(),
+            ))
+        });
+        match fetched {
+            Ok((schema, batches)) => {
+                app.mask.check(&schema)?;
+                let fake = app.mask.fake_columns();
+                if !fake.is_empty() {
+                    let opts = ScanOptions {
+                        memory: Some(app.track_memory("mask")),
+                        ..ScanOptions::from_config(&app.config.profiling)
+                    };
+                    let profiles = tokio::task::block_in_place(|| {
+                        tokio::runtime::Handle::current().block_on(
+                            parquet_lens_core::profile_columns_async(
+                                &p0_str,
+                                Some(&fake),
+                                parquet_lens_core::FAKE_BINS,
+                                &opts,
+                                s3_endpoint.as_deref(),
+                            ),
+                        )
+                    })?;
+                    app.mask.fit_profiles(&schema, &profiles)?;
+                }
+                preview = Some((schema, preview_rows(batches, max_preview)));
             }
+            Err(e) => tracing::warn!("data preview of {p0_str} failed: {e}"),
         }
     }
-
-    // nested column profiling
-    match profile_nested_columns(&paths[0].path) {
-        Ok(np) => app.nested_profiles = np,
-        Err(e) => eprintln!("nested profile warning: {e}"),
-    }
-
-    // engine identification from created_by
-    if let Some(created_by) = app
-        .file_info
-        .as_ref()
-        .and_then(|fi| fi.created_by.as_deref())
-    {
-        app.engine_info = Some(identify_engine(created_by));
+    if let Some((schema, mut rows)) = preview {
+        app.preview_headers = schema.fields().iter().map(|f| f.name().clone()).collect();
+        let bytes: usize = rows
+            .iter()
+            .flatten()
+            .map(|v| v.capacity() + std::mem::size_of::<String>())
+            .sum();
+        app.track_memory("preview").add(bytes);
+        app.mask.apply_to_rows(&app.preview_headers, &mut rows);
+        app.preview_rows = rows;
     }
 
-    // baseline diff
-    {
-        let schema = app.columns().to_vec();
-        let (base, regressions) =
-            load_baseline_regressions(&paths[0].path, &app.agg_stats, &app.quality_scores, &schema);
-        app.baseline_captured_at = base.as_ref().map(|b| b.captured_at);
-        app.has_baseline = base.is_some();
-        app.baseline_regressions = regressions;
-        if save_baseline {
-            let new_base = parquet_lens_core::BaselineProfile::new(
-                &app.input_path,
-                schema,
-                app.agg_stats.clone(),
-                app.quality_scores.clone(),
-            );
-            match new_base.save() {
-                Ok(_) => {
-                    app.status_msg = "baseline saved (--save-baseline)".into();
-                    app.has_baseline = true;
-                }
-                Err(e) => {
-                    app.status_msg = format!("--save-baseline failed: {e}");
-                }
+    if save_baseline {
+        let new_base = parquet_lens_core::BaselineProfile::new(
+            &app.input_path,
+            app.columns().to_vec(),
+            app.agg_stats.clone(),
+            app.quality_scores.clone(),
+        );
+        match new_base.save() {
+            Ok(_) => {
+                app.status_msg = "baseline saved (--save-baseline)".into();
+                app.has_baseline = true;
+            }
+            Err(e) => {
+                app.status_msg = format!("--save-baseline failed: {e}");
             }
         }
     }
@@ -764,96 +2253,67 @@ fn run_tui(
         );
     }
 
-    // pre-compute null patterns
-    app.null_patterns = analyze_null_patterns(&app.agg_stats);
-
     // partition key analysis (hive-style key=value path segments)
     app.partition_infos = analyze_partitions(&paths);
 
-    if let Some(pct) = sample_pct {
-        let cfg = SampleConfig {
-            percentage: pct,
-            no_extrapolation: no_sample_extrapolation,
-            seed: sample_seed,
-        };
-        match sample_row_groups(&paths[0].path, &cfg, 20) {
-            Ok(sp) => {
-                app.agg_stats = sp.agg_stats;
-                app.row_groups = sp.row_groups;
-                app.full_scan_results = sp.profile_results;
-                app.sample_note = Some(sp.confidence_note.clone());
-                app.status_msg = format!("Sampled — {} | q:quit ?:help", sp.confidence_note);
-            }
-            Err(e) => {
-                app.status_msg = format!("Sample error: {e}");
-            }
-        }
-    } else {
-        app.status_msg = "Ready — q:quit ?:help".into();
-    }
+    app.status_msg = match &app.sample_note {
+        Some(note) => format!("Sampled — {note} | q:quit ?:help"),
+        None => "Ready — q:quit ?:help".into(),
+    };
 
     // --watch: local filesystem watcher
-    let _watcher_guard: Option<notify::RecommendedWatcher> =
-        if watch && !is_s3_uri(&p0_str) && !is_gcs_uri(&p0_str) {
-            use notify::{Config as NotifyConfig, RecursiveMode, Watcher};
-            let (wtx, wrx) = std::sync::mpsc::channel::<()>();
-            let mut watcher = notify::RecommendedWatcher::new(
-                move |res: Result<notify::Event, notify::Error>| {
-                    if let Ok(ev) = res {
-                        if ev.kind.is_modify() || ev.kind.is_create() {
-                            let _ = wtx.send(());
-                        }
-                    }
-                },
-                NotifyConfig::default(),
-            )
-            .map_err(|e| anyhow::anyhow!("watch init failed: {e}"))?;
-            let watch_path = std::path::Path::new(&input_path);
-            let watch_target = if watch_path.is_file() {
-                watch_path.parent().unwrap_or(watch_path)
-            } else {
-                watch_path
-            };
-            watcher
-                .watch(watch_target, RecursiveMode::NonRecursive)
-                .map_err(|e| anyhow::anyhow!("watch failed: {e}"))?;
-            app.watch_rx = Some(wrx);
-            Some(watcher)
-        } else if watch && (is_s3_uri(&p0_str) || is_gcs_uri(&p0_str)) {
-            let (wtx, wrx) = std::sync::mpsc::channel::<()>();
-            let uri = p0_str.to_string();
-            let s3_endpoint = app.config.s3.endpoint_url.clone();
-            let cloud_interval = watch_interval.unwrap_or(30);
-            let is_s3 = is_s3_uri(&uri);
-            tokio::spawn(async move {
-                let interval = tokio::time::Duration::from_secs(cloud_interval);
-                let mut prev_rows: Option<i64> = None;
-                loop {
-                    tokio::time::sleep(interval).await;
-                    let cur_rows = if is_s3 {
-                        read_s3_parquet_metadata(&uri, s3_endpoint.as_deref())
-                            .await
-                            .ok()
-                            .map(|m| m.file_metadata().num_rows())
-                    } else {
-                        read_gcs_parquet_metadata(&uri)
-                            .await
-                            .ok()
-                            .map(|m| m.file_metadata().num_rows())
-                    };
-                    if let Some(rows) = cur_rows {
-                        if prev_rows.map(|p| p != rows).unwrap_or(false) {
-                            let _ = wtx.send(());
-                        }
-                        prev_rows = Some(rows);
+    let _watcher_guard: Option<notify::RecommendedWatcher> = if watch && !is_cloud_uri(&p0_str) {
+        use notify::{Config as NotifyConfig, RecursiveMode, Watcher};
+        let (wtx, wrx) = std::sync::mpsc::channel::<()>();
+        let mut watcher = notify::RecommendedWatcher::new(
+            move |res: Result<notify::Event, notify::Error>| {
+                if let Ok(ev) = res {
+                    if ev.kind.is_modify() || ev.kind.is_create() {
+                        let _ = wtx.send(());
                     }
                 }
-            });
-            app.watch_rx = Some(wrx);
-            None
+            },
+            NotifyConfig::default(),
+        )
+        .map_err(|e| anyhow::anyhow!("watch init failed: {e}"))?;
+        let watch_path = std::path::Path::new(&input_path);
+        let watch_target = if watch_path.is_file() {
+            watch_path.parent().unwrap_or(watch_path)
         } else {
-            None
+            watch_path
         };
+        watcher
+            .watch(watch_target, RecursiveMode::NonRecursive)
+            .map_err(|e| anyhow::anyhow!("watch failed: {e}"))?;
+        app.watch_rx = Some(wrx);
+        Some(watcher)
+    } else if watch && is_cloud_uri(&p0_str) {
+        let (wtx, wrx) = std::sync::mpsc::channel::<()>();
+        let uri = p0_str.to_string();
+        let s3_endpoint = app.config.s3.endpoint_url.clone();
+        let cloud_interval = watch_interval.unwrap_or(30);
+        tokio::spawn(async move {
+            let interval = tokio::time::Duration::from_secs(cloud_interval);
+            let mut prev_rows: Option<i64> = None;
+            loop {
+                tokio::time::sleep(interval).await;
+                let cur_rows = parquet_lens_core::open_parquet_auto(&uri, s3_endpoint.as_deref())
+                    .await
+                    .ok()
+                    .map(|(info, _)| info.row_count);
+                if let Some(rows) = cur_rows {
+                    if prev_rows.map(|p| p != rows).unwrap_or(false) {
+                        let _ = wtx.send(());
+                    }
+                    prev_rows = Some(rows);
+                }
+            }
+        });
+        app.watch_rx = Some(wrx);
+        None
+    } else {
+        None
+    };
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -865,12 +2325,18 @@ fn run_tui(
     ctrlc::set_handler(move || {
         let _ = crossterm::terminal::disable_raw_mode();
         let _ = crossterm::execute!(std::io::stdout(), crossterm::terminal::LeaveAlternateScreen);
-        std::process::exit(0);
+        exit(0);
     })
     .ok();
 
     let tick = Duration::from_millis(66); // 15Hz
     loop {
+        // remote requests retried (by us or by object_store) since the last report
+        let retries = parquet_lens_core::retry_count();
+        if retries > app.remote_retries {
+            app.remote_retries = retries;
+            app.status_msg = format!("Retried {retries} remote request(s) — see log");
+        }
         terminal.draw(|f| render(f, &app))?;
         // poll watch reload events
         if let Some(ref wrx) = app.watch_rx {
@@ -879,17 +2345,16 @@ fn run_tui(
                 while wrx.try_recv().is_ok() {}
                 // reload file stats
                 if let Ok(new_paths) = rp(&app.input_path) {
-                    if let Ok((ds, fi, mt)) = load_file_stats(&new_paths) {
-                        let cs = read_column_stats(&mt);
-                        let tr = fi.row_count;
+                    if let Ok((ds, fs)) = load_file_stats(&new_paths, app.profile_cache.as_ref()) {
+                        let tr = fs.info.row_count;
                         app.dataset = Some(ds);
-                        app.file_info = Some(fi);
-                        app.row_groups = profile_row_groups(&mt);
-                        app.agg_stats = aggregate_column_stats(&cs, tr);
-                        app.encoding_analysis = analyze_encodings(&mt);
-                        app.compression_analysis = analyze_compression(&mt);
+                        app.file_info = Some(fs.info);
+                        app.row_groups = fs.row_groups;
+                        app.agg_stats = aggregate_column_stats(&fs.column_stats, tr);
+                        app.encoding_analysis = fs.encodings;
+                        app.compression_analysis = fs.compression;
                         app.quality_scores =
-                            compute_quality_scores(&app.agg_stats, &app.encoding_analysis, tr);
+                            score_columns(&app.agg_stats, &app.encoding_analysis, tr);
                         app.repair_suggestions = detect_repair_suggestions(
                             &app.row_groups,
                             &app.agg_stats,
@@ -897,12 +2362,32 @@ fn run_tui(
                         );
                         app.rg_size_recommendation = recommend_row_group_size(&app.row_groups);
                         app.null_patterns = analyze_null_patterns(&app.agg_stats);
+                        let (_, regressions) = load_baseline_regressions(
+                            &new_paths[0].path,
+                            &app.agg_stats,
+                            &app.quality_scores,
+                            app.columns(),
+                        );
+                        let changed = regression_details(&regressions)
+                            != regression_details(&app.baseline_regressions);
+                        app.baseline_regressions = regressions;
+                        if notify && changed {
+                            if let Some(report) = app.report() {
+                                if let Err(e) = webhook::send(&app.config, &app.input_path, &report)
+                                {
+                                    tracing::warn!("notification failed: {e:#}");
+                                }
+                            }
+                        }
                         let now = std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .map(|d| d.as_secs())
                             .unwrap_or(0);
                         let schema_changed = false; // track across reloads in future
-                        let entry = format!("[{now}] rows={tr} schema_changed={schema_changed}");
+                        let entry = format!(
+                            "[{now}] rows={tr} schema_changed={schema_changed} regressions={}",
+                            app.baseline_regressions.len()
+                        );
                         app.watch_log.push(entry);
                         if app.watch_log.len() > 20 {
                             app.watch_log.remove(0);
@@ -912,56 +2397,173 @@ fn run_tui(
                 }
             }
         }
-        // spawn full-scan when pending flag is set
-        if app.pending_full_scan {
+        // spawn full-scan when pending flag is set, or a scan of one column
+        if app.pending_full_scan || app.pending_column_scan.is_some() {
             app.pending_full_scan = false;
-            let total_rows = app
-                .file_info
+            app.scan_column = app.pending_column_scan.take();
+            let columns: Option<Vec<String>> = app.scan_column.clone().map(|c| vec![c]);
+            // a local multi-file dataset is scanned as a whole
+            let files: Vec<ParquetFilePath> = app
+                .dataset
                 .as_ref()
-                .map(|f| f.row_count as u64)
-                .unwrap_or(0);
+                .filter(|d| d.files.len() > 1 && !is_cloud_uri(&app.input_path))
+                .map(|d| {
+                    d.files
+                        .iter()
+                        .map(|f| ParquetFilePath {
+                            path: f.path.clone(),
+                            partitions: Default::default(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let total_rows = if files.is_empty() {
+                app.file_info
+                    .as_ref()
+                    .map(|f| f.row_count as u64)
+                    .unwrap_or(0)
+            } else {
+                app.dataset.as_ref().map_or(0, |d| d.total_rows as u64)
+            };
             app.progress = tui::app::ProgressState::Running {
                 rows_processed: 0,
                 total_rows,
             };
+            app.scan_position = None;
             let path = std::path::PathBuf::from(&app.input_path);
             let bins = app.config.profiling.histogram_bins;
             let timeout_secs = app.config.profiling.full_scan_timeout_secs;
-            let (tx, rx) =
-                std::sync::mpsc::channel::<(u64, Vec<parquet_lens_core::ColumnProfileResult>)>();
+            let quarantine = Arc::new(parquet_lens_core::QuarantineLog::default());
+            let cancel = Arc::new(std::sync::atomic::AtomicBool::new(false));
+            app.scan_cancel = Some(cancel.clone());
+            let mut opts = ScanOptions {
+                memory: Some(app.track_memory("full scan")),
+                quarantine: Some(quarantine.clone()),
+                cancel: Some(cancel),
+                ..ScanOptions::from_config(&app.config.profiling)
+            };
+            let cache = app.profile_cache.clone();
+            let (tx, rx) = std::sync::mpsc::channel::<ScanUpdate>();
+            opts.progress = Some(Arc::new(ScanProgress(tx.clone())));
             app.progress_rx = Some(rx);
-            tokio::task::spawn_blocking(move || {
-                let result = parquet_lens_core::profile_columns_with_timeout(
-                    &path,
-                    None,
-                    65536,
-                    bins,
-                    timeout_secs,
-                );
-                match result {
-                    Ok(results) => {
-                        let _ = tx.send((total_rows, results));
-                    }
-                    Err(_) => {
-                        let _ = tx.send((total_rows, Vec::new()));
-                    }
-                }
-            });
+            if is_cloud_uri(&app.input_path) {
+                // cloud inputs are streamed down to a temp file, then scanned locally
+                let uri = app.input_path.clone();
+                tokio::task::spawn_blocking(move || {
+                    let result = tokio::runtime::Handle::current()
+                        .block_on(download_temp(&uri))
+                        .and_then(|tmp| {
+                            Ok(parquet_lens_core::profile_columns_with_timeout(
+                                &tmp,
+                                columns.as_deref(),
+                                bins,
+                                timeout_secs,
+                                &opts,
+                            )?)
+                        });
+                    let result = result.unwrap_or_else(|e| {
+                        tracing::warn!("full scan of {uri} failed: {e}");
+                        Vec::new()
+                    });
+                    let _ = tx.send(ScanUpdate::Done(
+                        result,
+                        quarantine.report(total_rows as i64),
+                        None,
+                    ));
+                });
+            } else {
+                // nested values come with a scan of the whole file only
+                let has_nested = !app.nested_profiles.is_empty() && columns.is_none();
+                tokio::task::spawn_blocking(move || {
+                    let cols = columns.as_deref();
+                    let result = match cache {
+                        Some(c) if !files.is_empty() => {
+                            c.profile_dataset(&files, cols, bins, timeout_secs, &opts)
+                        }
+                        None if !files.is_empty() => parquet_lens_core::profile_dataset(
+                            &files,
+                            cols,
+                            bins,
+                            timeout_secs,
+                            &opts,
+                        ),
+                        Some(c) => c.profile_columns(&path, cols, bins, timeout_secs, &opts),
+                        None => parquet_lens_core::profile_columns_with_timeout(
+                            &path,
+                            cols,
+                            bins,
+                            timeout_secs,
+                            &opts,
+                        ),
+                    };
+                    // list lengths, map keys and struct nulls of the first file
+                    let nested_data = (has_nested && result.is_ok())
+                        .then(|| {
+                            let first = files.first().map_or(path.as_path(), |f| f.path.as_path());
+                            let quiet = ScanOptions {
+                                progress: None,
+                                ..opts.clone()
+                            };
+                            parquet_lens_core::profile_nested_data(first, &quiet)
+                                .map_err(|e| tracing::warn!("nested data: {e}"))
+                                .ok()
+                        })
+                        .flatten();
+                    let _ = tx.send(ScanUpdate::Done(
+                        result.unwrap_or_default(),
+                        quarantine.report(total_rows as i64),
+                        nested_data,
+                    ));
+                });
+            }
         }
         // poll async full-scan progress channel
         let scan_done = if let Some(rx) = &app.progress_rx {
             let mut done = false;
-            while let Ok((rows_processed, results)) = rx.try_recv() {
-                if let tui::app::ProgressState::Running { total_rows, .. } = app.progress {
-                    if rows_processed >= total_rows {
+            while let Ok(update) = rx.try_recv() {
+                match update {
+                    ScanUpdate::Rows(rows_processed) => {
+                        if let tui::app::ProgressState::Running { total_rows, .. } = app.progress {
+                            app.progress = tui::app::ProgressState::Running {
+                                rows_processed,
+                                total_rows,
+                            };
+                        }
+                    }
+                    ScanUpdate::RowGroup(file, rg) => app.scan_position = Some((file, rg)),
+                    ScanUpdate::Done(results, quarantine, nested_data) => {
                         app.progress = tui::app::ProgressState::Done;
-                        app.full_scan_results = results;
+                        app.scan_position = None;
+                        app.scan_cancel = None;
+                        match app.scan_column.take() {
+                            // a column scan replaces that column's results only
+                            Some(col) => {
+                                app.status_msg = if results.is_empty() {
+                                    format!("{col}: nothing to scan (nested leaf or scan failed)")
+                                } else {
+                                    format!("{col}: full scan done")
+                                };
+                                for r in results {
+                                    app.full_scan_results
+                                        .retain(|f| f.column_name != r.column_name);
+                                    app.full_scan_results.push(r);
+                                }
+                            }
+                            None => app.full_scan_results = results,
+                        }
+                        apply_full_scan_findings(&mut app.quality_scores, &app.full_scan_results);
+                        if nested_data.is_some() {
+                            app.nested_data = nested_data;
+                        }
+                        if let Some(q) = &quarantine {
+                            app.status_msg = format!(
+                                "Full scan skipped {} unreadable row group(s) ({:.1}% of rows) — see overview",
+                                q.entries.len(),
+                                q.pct_rows_affected
+                            );
+                        }
+                        app.quarantine = quarantine;
                         done = true;
-                    } else {
-                        app.progress = tui::app::ProgressState::Running {
-                            rows_processed,
-                            total_rows,
-                        };
                     }
                 }
             }
@@ -976,11 +2578,18 @@ fn run_tui(
         if app.pending_duplicate_scan {
             app.pending_duplicate_scan = false;
             let path = std::path::PathBuf::from(&app.input_path);
+            let opts = ScanOptions {
+                memory: Some(app.track_memory("duplicates")),
+                ..ScanOptions::from_config(&app.config.profiling)
+            };
+            let (ignores, _) = app.config.duplicates.resolve(&app.input_path);
             let (tx, rx) =
                 std::sync::mpsc::channel::<Result<parquet_lens_core::DuplicateReport, String>>();
             app.duplicate_rx = Some(rx);
             tokio::task::spawn_blocking(move || {
-                let res = detect_duplicates(&path, false).map_err(|e| e.to_string());
+                let res = merge_ignored_columns(&path, ignores, Vec::new())
+                    .and_then(|ignores| Ok(detect_duplicates(&path, false, &ignores, &opts)?))
+                    .map_err(|e| e.to_string());
                 let _ = tx.send(res);
             });
         }
@@ -1050,28 +2659,28 @@ fn run_compare(path1: String, path2: String, config: Config) -> anyhow::Result<(
     if path2.is_empty() {
         anyhow::bail!("path2 is empty");
     }
-    if !is_s3_uri(&path1) && !is_gcs_uri(&path1) && !std::path::Path::new(&path1).exists() {
+    if !is_cloud_uri(&path1) && !std::path::Path::new(&path1).exists() {
         anyhow::bail!("path1 not found: {path1}");
     }
-    if !is_s3_uri(&path2) && !is_gcs_uri(&path2) && !std::path::Path::new(&path2).exists() {
+    if !is_cloud_uri(&path2) && !std::path::Path::new(&path2).exists() {
         anyhow::bail!("path2 not found: {path2}");
     }
     let paths1 = rp(&path1)?;
     let paths2 = rp(&path2)?;
     if paths1.is_empty() {
-        anyhow::bail!("No Parquet files found: {path1}");
+        return Err(ParquetLensError::NotFound(format!("no Parquet files match {path1}")).into());
     }
     if paths2.is_empty() {
-        anyhow::bail!("No Parquet files found: {path2}");
+        return Err(ParquetLensError::NotFound(format!("no Parquet files match {path2}")).into());
     }
-    let dataset1 = read_metadata_parallel(&paths1).map_err(|e| anyhow::anyhow!("{e}"))?;
-    let dataset2 = read_metadata_parallel(&paths2).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let dataset1 = read_metadata_parallel(&paths1).map_err(anyhow::Error::from)?;
+    let dataset2 = read_metadata_parallel(&paths2).map_err(anyhow::Error::from)?;
     let p1_str = paths1[0].path.to_string_lossy().to_string();
     let (file_info, meta) = tokio::task::block_in_place(|| {
         tokio::runtime::Handle::current()
             .block_on(parquet_lens_core::open_parquet_auto(&p1_str, None))
     })
-    .map_err(|e| anyhow::anyhow!("{e}"))?;
+    .map_err(anyhow::Error::from)?;
     let row_groups = profile_row_groups(&meta);
     let col_stats = read_column_stats(&meta);
     let total_rows = file_info.row_count;
@@ -1082,11 +2691,11 @@ fn run_compare(path1: String, path2: String, config: Config) -> anyhow::Result<(
         tokio::runtime::Handle::current()
             .block_on(parquet_lens_core::open_parquet_auto(&p2_str, None))
     })
-    .map_err(|e| anyhow::anyhow!("{e}"))?;
+    .map_err(anyhow::Error::from)?;
     let col_stats2 = read_column_stats(&meta2);
     let agg_stats2 = aggregate_column_stats(&col_stats2, dataset2.total_rows);
     let comparison = compare_datasets(&dataset1, &dataset2, &agg_stats, &agg_stats2);
-    let quality_scores = compute_quality_scores(&agg_stats, &encoding_analysis, total_rows);
+    let quality_scores = score_columns(&agg_stats, &encoding_analysis, total_rows);
     let mut app = App::new(path1, config);
     app.dataset = Some(dataset1);
     app.file_info = Some(file_info);
@@ -1131,57 +2740,52 @@ fn run_summary(
     save: bool,
     format: &str,
     json_out: bool,
-    sample_pct: Option<f64>,
-    sample_seed: Option<u64>,
+    sampling: &SampleArgs,
     columns: Option<Vec<String>>,
+    columns_table: bool,
     no_color: bool,
+    no_cache: bool,
     config: &Config,
+    scan_opts: &ScanOptions,
 ) -> anyhow::Result<()> {
     let no_color = no_color || std::env::var("NO_COLOR").is_ok();
-    let paths = rp(&input_path)?;
+    let (paths, table) = resolve_input(&input_path)?;
     if paths.is_empty() {
-        anyhow::bail!("No Parquet files found: {input_path}");
+        return Err(
+            ParquetLensError::NotFound(format!("no Parquet files match {input_path}")).into(),
+        );
     }
-    let (dataset, _, meta) = load_file_stats(&paths)?;
-    let total_rows = dataset.total_rows;
-    let col_stats = if let Some(pct) = sample_pct {
-        let cfg = SampleConfig {
-            percentage: pct,
-            no_extrapolation: false,
-            seed: sample_seed,
-        };
-        match sample_row_groups(&paths[0].path, &cfg, 20) {
-            Ok(sp) => sp.agg_stats,
-            Err(e) => {
-                eprintln!("sample error: {e}");
-                let cs = read_column_stats(&meta);
-                aggregate_column_stats(&cs, total_rows)
-            }
-        }
-    } else {
-        let cs = read_column_stats(&meta);
-        aggregate_column_stats(&cs, total_rows)
-    };
-    let agg_stats = col_stats;
-    let encodings = analyze_encodings(&meta);
-    let quality_scores = compute_quality_scores(&agg_stats, &encodings, total_rows);
-    let total_cells = total_rows * dataset.combined_schema.len() as i64;
-    let total_nulls: u64 = agg_stats.iter().map(|s| s.total_null_count).sum();
-    let quality_scores = if let Some(ref cols) = columns {
-        quality_scores
-            .into_iter()
+    let mut builder = ProfilerBuilder::from_config(config)
+        .resolved_paths(paths)
+        .histogram_bins(20)
+        .scan_options(scan_opts.clone());
+    if !no_cache {
+        builder = builder.cache(ProfileCache::from_config(&config.cache));
+    }
+    builder = sampling.apply(builder);
+    let mut report = run_profiler(builder)?;
+    lineage::emit(config, "summary", &input_path, &report);
+    if let Some(ref cols) = columns {
+        // overall score covers only the selected columns; null % stays dataset-wide
+        let total_cells = report.dataset.total_rows * report.dataset.combined_schema.len() as i64;
+        let total_nulls: u64 = report.column_stats.iter().map(|s| s.total_null_count).sum();
+        let scores = report
+            .quality_scores()
+            .iter()
             .filter(|s| cols.iter().any(|c| c == &s.column_name))
-            .collect()
-    } else {
-        quality_scores
-    };
-    let quality = summarize_quality(
-        quality_scores,
-        total_cells,
-        total_nulls,
-        dataset.schema_inconsistencies.is_empty(),
-        &agg_stats,
-    );
+            .cloned()
+            .collect();
+        report.quality = summarize_quality(
+            scores,
+            total_cells,
+            total_nulls,
+            report.dataset.schema_inconsistencies.is_empty(),
+            &report.column_stats,
+        );
+    }
+    let dataset = &report.dataset;
+    let quality = &report.quality;
+    let fmt = config.display.number_format();
     if json_out {
         println!("{}", serde_json::to_string(&quality)?);
         return Ok(());
@@ -1192,11 +2796,23 @@ fn run_summary(
         } else {
             ("\x1b[1m", "\x1b[0m", "\x1b[32m", "\x1b[33m", "\x1b[31m")
         };
-        println!("{}Files:{}           {}", bold, reset, dataset.file_count);
-        println!("{}Rows:{}            {}", bold, reset, dataset.total_rows);
         println!(
-            "{}Size:{}            {} bytes",
-            bold, reset, dataset.total_bytes
+            "{}Files:{}           {}",
+            bold,
+            reset,
+            fmt.count(dataset.file_count as u64)
+        );
+        println!(
+            "{}Rows:{}            {}",
+            bold,
+            reset,
+            fmt.count(dataset.total_rows)
+        );
+        println!(
+            "{}Size:{}            {}",
+            bold,
+            reset,
+            fmt.bytes(dataset.total_bytes)
         );
         println!(
             "{}Columns:{}         {}",
@@ -1216,7 +2832,7 @@ fn run_summary(
             bold, reset, qcolor, quality.overall_score, reset
         );
         println!(
-            "{}Null cells:{}      {}{:.2}%{}",
+            "{}Null cells:{}      {}{}{}",
             bold,
             reset,
             if quality.total_null_cell_pct > 10.0 {
@@ -1224,7 +2840,7 @@ fn run_summary(
             } else {
                 green
             },
-            quality.total_null_cell_pct,
+            fmt.pct(quality.total_null_cell_pct),
             reset
         );
         if !quality.worst_columns.is_empty() {
@@ -1238,63 +2854,205 @@ fn run_summary(
             );
         }
     } else {
-        print_summary(&dataset, Some(&quality));
+        print_summary(&report, &fmt);
+    }
+    for line in table.iter().flat_map(IcebergTable::lines) {
+        println!("{line}");
+    }
+    if columns_table {
+        println!();
+        match &columns {
+            Some(cols) => {
+                let mut selected = report.clone();
+                selected.retain_columns(cols);
+                print!("{}", parquet_lens_core::columns_table(&selected, &fmt));
+            }
+            None => print!("{}", parquet_lens_core::columns_table(&report, &fmt)),
+        }
     }
     if save {
         let out_dir = std::path::Path::new(&config.export.output_dir);
         std::fs::create_dir_all(out_dir)?;
         let out_path = out_dir.join("summary.json");
-        let doc = serde_json::json!({ "dataset": dataset, "quality": quality });
-        std::fs::write(&out_path, serde_json::to_string_pretty(&doc)?)?;
+        export_json(&out_path, &report).map_err(anyhow::Error::from)?;
         println!("Summary saved to {}", out_path.display());
     }
     Ok(())
 }
 
+fn run_sample_export(
+    input_path: String,
+    output: String,
+    sampling: &SampleArgs,
+    replay: Option<String>,
+    json: bool,
+    mask: Option<MaskSpec>,
+    scan_opts: &ScanOptions,
+) -> anyhow::Result<()> {
+    if is_cloud_uri(&input_path) {
+        anyhow::bail!("sample-export reads local files only; download {input_path} first");
+    }
+    let (config, mask) = match (&replay, sampling.config()) {
+        (Some(manifest), _) => {
+            let manifest = SampleManifest::load(std::path::Path::new(manifest))?;
+            // a replay redacts at least what the original export did
+            let mut mask = mask.unwrap_or_default();
+            for rule in &manifest.masked {
+                if !mask.rules.iter().any(|r| r.column == rule.column) {
+                    mask.rules.push(rule.clone());
+                }
+            }
+            (manifest.config(), mask)
+        }
+        (None, Some(config)) => (config, mask.unwrap_or_default()),
+        (None, None) => anyhow::bail!("pass --sample, --sample-rows or --replay"),
+    };
+    let manifest = export_sample(
+        std::path::Path::new(&input_path),
+        &config,
+        std::path::Path::new(&output),
+        &mask,
+        scan_opts,
+    )?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&manifest)?);
+        return Ok(());
+    }
+    println!(
+        "Exported {} of {} rows to {} (seed {})",
+        manifest.sampled_rows, manifest.source_rows, output, manifest.seed
+    );
+    println!(
+        "Manifest: {}",
+        sample_manifest_path(std::path::Path::new(&output)).display()
+    );
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_top_values(
+    input_path: String,
+    k: usize,
+    columns: Option<Vec<String>>,
+    format: String,
+    output: Option<String>,
+    mask: &MaskSpec,
+    config: &Config,
+    scan_opts: &ScanOptions,
+) -> anyhow::Result<()> {
+    if is_cloud_uri(&input_path) {
+        anyhow::bail!("top-values reads local files only; download {input_path} first");
+    }
+    if format != "csv" && format != "json" {
+        anyhow::bail!("Unknown format: {format} (use csv or json)");
+    }
+    let paths: Vec<std::path::PathBuf> = rp(&input_path)?.into_iter().map(|p| p.path).collect();
+    if paths.is_empty() {
+        return Err(
+            ParquetLensError::NotFound(format!("no Parquet files match {input_path}")).into(),
+        );
+    }
+    mask.check(
+        HandleRegistry::global()
+            .get(&paths[0])?
+            .arrow_metadata()?
+            .schema(),
+    )?;
+    let mut tables = top_values(&paths, columns.as_deref(), k, scan_opts)?;
+    mask_top_values(&mut tables, mask);
+    let out_path = match output {
+        Some(o) => std::path::PathBuf::from(o),
+        None => {
+            std::path::Path::new(&config.export.output_dir).join(format!("top_values.{format}"))
+        }
+    };
+    if let Some(parent) = out_path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    match format.as_str() {
+        "csv" => write_top_values_csv(&out_path, &tables)?,
+        _ => write_top_values_json(&out_path, &tables)?,
+    }
+    for t in tables.iter().filter(|t| t.distinct.is_none()) {
+        eprintln!(
+            "warning: {} has too many distinct values to count; no values listed",
+            t.column
+        );
+    }
+    println!(
+        "Exported top values of {} columns to {}",
+        tables.len(),
+        out_path.display()
+    );
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 fn run_export(
     input_path: String,
     format: String,
     columns: Option<Vec<String>>,
     output: Option<String>,
-    sample_pct: Option<f64>,
-    sample_seed: Option<u64>,
+    sampling: &SampleArgs,
     limit: Option<usize>,
+    max_distinct: Option<u64>,
+    pii: bool,
+    mask: &MaskSpec,
     config: Config,
+    scan_opts: &ScanOptions,
 ) -> anyhow::Result<()> {
+    if max_distinct.is_some() && is_cloud_uri(&input_path) {
+        anyhow::bail!("--distinct-values reads local files only; download {input_path} first");
+    }
     let paths = rp(&input_path)?;
     if paths.is_empty() {
-        anyhow::bail!("No Parquet files found: {input_path}");
+        return Err(
+            ParquetLensError::NotFound(format!("no Parquet files match {input_path}")).into(),
+        );
     }
-    let (dataset, _, meta) = load_file_stats(&paths)?;
-    let row_groups = profile_row_groups(&meta);
-    let mut agg_stats = if let Some(pct) = sample_pct {
-        let cfg = SampleConfig {
-            percentage: pct,
-            no_extrapolation: false,
-            seed: sample_seed,
-        };
-        match sample_row_groups(&paths[0].path, &cfg, 20) {
-            Ok(sp) => sp.agg_stats,
-            Err(e) => {
-                eprintln!("sample error: {e}");
-                let cs = read_column_stats(&meta);
-                aggregate_column_stats(&cs, dataset.total_rows)
-            }
-        }
-    } else {
-        let cs = read_column_stats(&meta);
-        aggregate_column_stats(&cs, dataset.total_rows)
-    };
-    let encodings = analyze_encodings(&meta);
-    let mut quality_scores = compute_quality_scores(&agg_stats, &encodings, dataset.total_rows);
-    // column filtering
+    let local: Vec<std::path::PathBuf> = paths.iter().map(|p| p.path.clone()).collect();
+    // a typo in --mask must fail before anything is written
+    if !mask.is_empty() {
+        let (_, meta) = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(parquet_lens_core::open_parquet_auto(
+                &paths[0].path.to_string_lossy(),
+                config.s3.endpoint_url.as_deref(),
+            ))
+        })?;
+        let schema = parquet::arrow::parquet_to_arrow_schema(
+            meta.file_metadata().schema_descr(),
+            meta.file_metadata().key_value_metadata(),
+        )?;
+        mask.check(&schema)?;
+    }
+    let builder = sampling.apply(
+        ProfilerBuilder::from_config(&config)
+            .resolved_paths(paths)
+            .histogram_bins(20)
+            .with_baseline(true)
+            // value ranges and sets for the suite, and the cardinalities that
+            // pick distinct-value columns, come from the full scan
+            .with_full_scan(format == "ge" || max_distinct.is_some())
+            .scan_pii(pii)
+            .scan_options(scan_opts.clone()),
+    );
+    let mut report = run_profiler(builder)?;
+    report.mask_values(mask);
+    lineage::emit(&config, "export", &input_path, &report);
     if let Some(ref cols) = columns {
-        let col_set: std::collections::HashSet<&str> = cols.iter().map(|s| s.as_str()).collect();
-        agg_stats.retain(|s| col_set.contains(s.column_name.as_str()));
-        quality_scores.retain(|q| col_set.contains(q.column_name.as_str()));
+        report.retain_columns(cols);
+    }
+    // apply row limit to column stats
+    if let Some(lim) = limit {
+        report.truncate_columns(lim);
     }
-    let default_name = format!("profile.{format}");
+    let default_name = if format == "ge" {
+        "expectations.json".to_string()
+    } else {
+        format!("profile.{format}")
+    };
     let out_path: std::path::PathBuf = if let Some(ref o) = output {
         std::path::PathBuf::from(o)
     } else {
@@ -1305,69 +3063,82 @@ fn run_export(
             std::fs::create_dir_all(parent)?;
         }
     }
-    let null_patterns = analyze_null_patterns(&agg_stats);
-    let engine_info = dataset
-        .files
-        .first()
-        .and_then(|f| f.created_by.as_deref())
-        .map(identify_engine);
-    let schema = dataset
-        .combined_schema
-        .iter()
-        .map(|c| parquet_lens_core::ColumnSchema {
-            name: c.name.clone(),
-            physical_type: c.physical_type.clone(),
-            logical_type: c.logical_type.clone(),
-            repetition: c.repetition.clone(),
-            max_def_level: c.max_def_level,
-            max_rep_level: c.max_rep_level,
-        })
-        .collect::<Vec<_>>();
-    // apply row limit to column stats
-    if let Some(lim) = limit {
-        agg_stats.truncate(lim);
-        quality_scores.truncate(lim);
-    }
-    let (_, baseline_regressions) =
-        load_baseline_regressions(&paths[0].path, &agg_stats, &quality_scores, &schema);
-    let timeseries_profiles =
-        parquet_lens_core::profile_timeseries(&paths[0].path, &[]).unwrap_or_default();
-    let nested_profiles =
-        parquet_lens_core::profile_nested_columns(&paths[0].path).unwrap_or_default();
-    let repair_suggestions = detect_repair_suggestions(&row_groups, &agg_stats, &encodings);
-    match format.as_str() {
-        "json" => {
-            export_json(
-                &out_path,
-                &dataset,
-                &agg_stats,
-                &row_groups,
-                &quality_scores,
-                &null_patterns,
-                engine_info.as_ref(),
-                &baseline_regressions,
-                &timeseries_profiles,
-                &nested_profiles,
-                &repair_suggestions,
-            )
-            .map_err(|e| anyhow::anyhow!("{e}"))?;
-            println!("Exported to {}", out_path.display());
-        }
-        "csv" => {
-            export_csv(&out_path, &agg_stats, &quality_scores, &row_groups)
-                .map_err(|e| anyhow::anyhow!("{e}"))?;
-            println!("Exported to {}", out_path.display());
-        }
-        "ndjson" => {
-            let mut file = std::fs::File::create(&out_path)?;
-            for stat in &agg_stats {
-                let line = serde_json::to_string(stat)?;
-                std::io::Write::write_all(&mut file, line.as_bytes())?;
-                std::io::Write::write_all(&mut file, b"\n")?;
-            }
-            println!("Exported to {}", out_path.display());
+    let written = match format.as_str() {
+        "json" => export_json(&out_path, &report),
+        "csv" => export_csv(&out_path, &report),
+        "ndjson" => export_ndjson(&out_path, &report),
+        "ge" => {
+            let suite = std::path::Path::new(input_path.trim_end_matches('/'))
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "parquet_lens".into());
+            export_great_expectations(&out_path, &report, &suite)
+        }
+        _ => anyhow::bail!("Unknown format: {format} (use json, csv, ndjson, or ge)"),
+    };
+    written.map_err(anyhow::Error::from)?;
+    println!("Exported to {}", out_path.display());
+    if let Some(max) = max_distinct {
+        let profiles = report.full_scan.as_deref().unwrap_or_default();
+        let mut tables = distinct_values(&local, profiles, max, scan_opts)?;
+        mask_top_values(&mut tables, mask);
+        let json = format == "json" || format == "ndjson";
+        let distinct_path = out_path.with_file_name(if json {
+            "distinct_values.json"
+        } else {
+            "distinct_values.csv"
+        });
+        if json {
+            write_top_values_json(&distinct_path, &tables)?;
+        } else {
+            write_top_values_csv(&distinct_path, &tables)?;
         }
-        _ => anyhow::bail!("Unknown format: {format} (use json, csv, or ndjson)"),
+        println!(
+            "Exported distinct values of {} columns (at most {max} each) to {}",
+            tables.len(),
+            distinct_path.display()
+        );
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests_check {
+    use super::*;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+
+    fn write_fixture() -> tempfile::NamedTempFile {
+        let tmp = tempfile::Builder::new()
+            .suffix(".parquet")
+            .tempfile()
+            .unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let ids = Arc::new(Int32Array::from((0..100).collect::<Vec<i32>>()));
+        let batch = RecordBatch::try_new(schema.clone(), vec![ids]).unwrap();
+        let mut writer = ArrowWriter::try_new(tmp.as_file(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        tmp
+    }
+
+    #[test]
+    fn full_scan_check_respects_the_memory_limit() {
+        let tmp = write_fixture();
+        let mut config = Config::default();
+        config.profiling.memory_limit_bytes = Some(1);
+        let scan_opts = ScanOptions {
+            memory: Some(Arc::new(MemoryTracker::with_limit(1))),
+            ..ScanOptions::from_config(&config.profiling)
+        };
+        let paths =
+            futures::executor::block_on(resolve_paths(tmp.path().to_str().unwrap())).unwrap();
+        let report = check_profiler(&config, &scan_opts, paths, true)
+            .run_blocking()
+            .unwrap();
+        assert!(report.full_scan.is_some());
+        assert!(report.warnings.iter().any(|w| w.contains("memory limit")));
+    }
+}
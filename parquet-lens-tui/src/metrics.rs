@@ -0,0 +1,32 @@
+/// PUTs Prometheus text to a Pushgateway, grouped under job `parquet_lens` and an
+/// `instance` derived from the dataset path (replacing the previous push for it).
+pub fn push(gateway: &str, dataset: &str, body: String) -> anyhow::Result<()> {
+    // grouping values can't contain '/', and the samples keep the exact path anyway
+    let instance: String = dataset
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "-_.".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let url = format!(
+        "{}/metrics/job/parquet_lens/instance/{instance}",
+        gateway.trim_end_matches('/')
+    );
+    let resp = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(
+            reqwest::Client::new()
+                .put(&url)
+                .header("Content-Type", "text/plain; version=0.0.4")
+                .body(body)
+                .send(),
+        )
+    })?;
+    if !resp.status().is_success() {
+        anyhow::bail!("pushgateway returned HTTP {} for {url}", resp.status());
+    }
+    Ok(())
+}
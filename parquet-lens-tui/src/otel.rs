@@ -0,0 +1,105 @@
+use opentelemetry::metrics::MeterProvider as _;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use parquet_lens_core::ProfileReport;
+use std::sync::OnceLock;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+struct Providers {
+    tracer: TracerProvider,
+    meter: SdkMeterProvider,
+}
+
+static PROVIDERS: OnceLock<Providers> = OnceLock::new();
+
+/// Export is on when an OTLP endpoint is configured; everything else (headers,
+/// protocol, service name, resource attributes) comes from the usual OTEL_* vars.
+fn enabled() -> bool {
+    [
+        "OTEL_EXPORTER_OTLP_ENDPOINT",
+        "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+        "OTEL_EXPORTER_OTLP_METRICS_ENDPOINT",
+    ]
+    .iter()
+    .any(|v| std::env::var_os(v).is_some())
+}
+
+fn resource() -> Resource {
+    let detected = Resource::default();
+    if std::env::var_os("OTEL_SERVICE_NAME").is_some() {
+        return detected;
+    }
+    detected.merge(&Resource::new([KeyValue::new(
+        "service.name",
+        "parquet-lens",
+    )]))
+}
+
+/// Tracing layer that ships spans (one per analysis stage) over OTLP/HTTP, or
+/// None when no endpoint is configured. Spans are recorded at info level and
+/// above regardless of `-v`, unless RUST_LOG says otherwise.
+pub fn layer() -> anyhow::Result<Option<Box<dyn Layer<Registry> + Send + Sync>>> {
+    if !enabled() {
+        return Ok(None);
+    }
+    let spans = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .build()?;
+    let tracer = TracerProvider::builder()
+        .with_batch_exporter(spans, runtime::Tokio)
+        .with_resource(resource())
+        .build();
+    let metrics = opentelemetry_otlp::MetricExporter::builder()
+        .with_http()
+        .build()?;
+    let meter = SdkMeterProvider::builder()
+        .with_reader(PeriodicReader::builder(metrics, runtime::Tokio).build())
+        .with_resource(resource())
+        .build();
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(tracer.tracer("parquet-lens"))
+        .with_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .boxed();
+    let _ = PROVIDERS.set(Providers { tracer, meter });
+    Ok(Some(layer))
+}
+
+/// Row count and quality gauges for one profiling run.
+pub fn record_report(report: &ProfileReport) {
+    let Some(p) = PROVIDERS.get() else {
+        return;
+    };
+    let meter = p.meter.meter("parquet-lens");
+    let attrs = [KeyValue::new(
+        "dataset",
+        report.file_info.path.to_string_lossy().into_owned(),
+    )];
+    let gauge = |name: &'static str, value: u64| {
+        meter.u64_gauge(name).build().record(value, &attrs);
+    };
+    gauge("parquet.rows", report.dataset.total_rows.max(0) as u64);
+    gauge("parquet.files", report.dataset.file_count as u64);
+    gauge("parquet.bytes", report.dataset.total_bytes);
+    gauge("parquet.quality_score", report.quality.overall_score as u64);
+    gauge(
+        "parquet.baseline_regressions",
+        report.baseline_regressions.len() as u64,
+    );
+}
+
+/// Flushes pending spans and metrics; exporters run on the tokio runtime.
+pub fn shutdown() {
+    if let Some(p) = PROVIDERS.get() {
+        tokio::task::block_in_place(|| {
+            if let Err(e) = p.tracer.shutdown() {
+                eprintln!("otel: span export failed: {e}");
+            }
+            if let Err(e) = p.meter.shutdown() {
+                eprintln!("otel: metric export failed: {e}");
+            }
+        });
+    }
+}
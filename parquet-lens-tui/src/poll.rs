@@ -0,0 +1,177 @@
+use crate::{lineage, webhook};
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Timelike, Utc};
+use parquet_lens_common::Config;
+use parquet_lens_core::{HistoryEntry, HistoryStore, ProfilerBuilder};
+use std::time::Duration;
+
+/// When `--poll` fires: a fixed interval ("90s", "15m", "1h") or a five-field
+/// cron expression evaluated in UTC ("*/10 * * * *", "0 6 * * 1-5").
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    Every(Duration),
+    Cron(Box<CronSpec>),
+}
+
+impl Schedule {
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        if spec.split_whitespace().count() == 5 {
+            return Ok(Self::Cron(Box::new(CronSpec::parse(spec)?)));
+        }
+        let every = humantime::parse_duration(spec).map_err(|e| {
+            anyhow::anyhow!("--poll {spec:?}: not an interval ({e}) or a 5-field cron expression")
+        })?;
+        if every.is_zero() {
+            anyhow::bail!("--poll interval must be greater than zero");
+        }
+        Ok(Self::Every(every))
+    }
+
+    /// Time to wait from `now` until the next run.
+    fn until_next(&self, now: DateTime<Utc>) -> Duration {
+        match self {
+            Self::Every(d) => *d,
+            Self::Cron(c) => c
+                .next_after(now)
+                .and_then(|t| (t - now).to_std().ok())
+                .unwrap_or(Duration::from_secs(60)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CronSpec {
+    minutes: [bool; 60],
+    hours: [bool; 24],
+    days: [bool; 32],    // 1-31
+    months: [bool; 13],  // 1-12
+    weekdays: [bool; 7], // 0 = Sunday
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSpec {
+    fn parse(spec: &str) -> anyhow::Result<Self> {
+        let f: Vec<&str> = spec.split_whitespace().collect();
+        let mut weekdays = [false; 7];
+        for (d, on) in field::<8>(f[4], 0, 7)?.into_iter().enumerate() {
+            weekdays[d % 7] |= on; // both 0 and 7 mean Sunday
+        }
+        Ok(Self {
+            minutes: field(f[0], 0, 59)?,
+            hours: field(f[1], 0, 23)?,
+            days: field(f[2], 1, 31)?,
+            months: field(f[3], 1, 12)?,
+            weekdays,
+            any_day: f[2] == "*",
+            any_weekday: f[4] == "*",
+        })
+    }
+
+    fn matches(&self, t: DateTime<Utc>) -> bool {
+        let day = self.days[t.day() as usize];
+        let weekday = self.weekdays[t.weekday().num_days_from_sunday() as usize];
+        // as in cron(8): when both day fields are restricted, either may match
+        let day_ok = match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        };
+        self.minutes[t.minute() as usize]
+            && self.hours[t.hour() as usize]
+            && self.months[t.month() as usize]
+            && day_ok
+    }
+
+    /// First whole minute after `now` the expression matches, within four years.
+    fn next_after(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = now.with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+        for _ in 0..4 * 366 * 24 * 60 {
+            if self.matches(t) {
+                return Some(t);
+            }
+            t += ChronoDuration::minutes(1);
+        }
+        None
+    }
+}
+
+// one cron field: `*`, `N`, `A-B`, any of those with `/STEP`, comma-separated
+fn field<const N: usize>(expr: &str, min: usize, max: usize) -> anyhow::Result<[bool; N]> {
+    let mut set = [false; N];
+    for part in expr.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (r, s.parse::<usize>()?),
+            None => (part, 1),
+        };
+        let (lo, hi) = match range {
+            "*" => (min, max),
+            r => match r.split_once('-') {
+                Some((a, b)) => (a.parse()?, b.parse()?),
+                None => {
+                    let v = r.parse()?;
+                    (v, if step > 1 { max } else { v })
+                }
+            },
+        };
+        if step == 0 || lo < min || hi > max || lo > hi {
+            anyhow::bail!("cron field {expr:?} out of range {min}-{max}");
+        }
+        for v in (lo..=hi).step_by(step) {
+            set[v] = true;
+        }
+    }
+    Ok(set)
+}
+
+/// The history store runs are appended to; fails when there is nothing to poll.
+pub fn history_store(config: &Config) -> anyhow::Result<HistoryStore> {
+    if config.poll.datasets.is_empty() {
+        anyhow::bail!("--poll needs datasets: set [poll] datasets in the config");
+    }
+    Ok(match &config.poll.history_path {
+        Some(p) => HistoryStore::new(p.into()),
+        None => HistoryStore::open_default(),
+    })
+}
+
+/// Re-profiles every `[poll] datasets` entry on `schedule`, forever: appends
+/// each run to `store`, checks it against the saved baseline and reports
+/// regressions (log, and the webhook when `[poll] notify` is set).
+pub fn run(config: &Config, schedule: &Schedule, store: &HistoryStore) -> ! {
+    tracing::info!(
+        "polling {} dataset(s), history in {}",
+        config.poll.datasets.len(),
+        store.path().display()
+    );
+    loop {
+        for dataset in &config.poll.datasets {
+            if let Err(e) = poll_once(config, store, dataset) {
+                tracing::warn!("poll {dataset}: {e:#}");
+            }
+        }
+        std::thread::sleep(schedule.until_next(Utc::now()));
+    }
+}
+
+fn poll_once(config: &Config, store: &HistoryStore, dataset: &str) -> anyhow::Result<()> {
+    let report = crate::run_profiler(
+        ProfilerBuilder::from_config(config)
+            .path(dataset)
+            .with_baseline(true),
+    )?;
+    let now = Utc::now().timestamp().max(0) as u64;
+    store.append(&HistoryEntry::from_report(dataset, now, &report))?;
+    lineage::emit(config, "poll", dataset, &report);
+    let regressions = &report.baseline_regressions;
+    tracing::info!(
+        "poll {dataset}: quality {}/100, {} regression(s)",
+        report.quality.overall_score,
+        regressions.len()
+    );
+    for r in regressions {
+        tracing::warn!("poll {dataset}: regression {} — {}", r.column, r.detail);
+    }
+    if config.poll.notify && !regressions.is_empty() {
+        webhook::send(config, dataset, &report)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,284 @@
+//! HTTP API for `parquet-lens serve --listen`: `GET /profile` for read-only
+//! profiling, `POST /check` and `POST /compare` for validations driven by CI
+//! systems and dashboards. Errors use the `{"error": {...}}` shape of `--json`.
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use parquet_lens_common::{Config, ErrorInfo, ParquetLensError};
+use parquet_lens_core::{
+    compare_datasets, BaselineProfile, ProfileReport, ProfilerBuilder, SampleMethod,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const MAX_BODY_BYTES: usize = 1 << 20;
+
+struct ApiError {
+    status: StatusCode,
+    info: ErrorInfo,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            info: ErrorInfo {
+                code: "bad_request".into(),
+                message: message.into(),
+                retryable: false,
+            },
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        let info = match e.downcast_ref::<ParquetLensError>() {
+            Some(pe) => pe.info(),
+            None => ErrorInfo {
+                code: "other".into(),
+                message: format!("{e:#}"),
+                retryable: false,
+            },
+        };
+        let status = match info.code.as_str() {
+            "not_found" => StatusCode::NOT_FOUND,
+            "auth" | "permission_denied" => StatusCode::FORBIDDEN,
+            "throttled" => StatusCode::SERVICE_UNAVAILABLE,
+            "corrupt" | "unsupported" | "parquet" | "arrow" => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self { status, info }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CheckRequest {
+    path: String,
+    /// regression kinds that fail the check, e.g. ["schema_removed", "null_increase"]; all when unset
+    rules: Option<Vec<String>>,
+    min_quality: Option<u8>,
+    /// path whose saved baseline to compare against; `path`'s own when unset
+    baseline: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CompareRequest {
+    path1: String,
+    path2: String,
+    #[serde(default)]
+    tolerances: Tolerances,
+}
+
+/// Limits beyond which `/compare` reports a violation; unset limits aren't checked.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct Tolerances {
+    row_delta_pct: Option<f64>,
+    null_rate_delta: Option<f64>, // percentage points
+    size_delta_pct: Option<f64>,
+    allow_schema_changes: bool,
+}
+
+/// Serves the API on `addr` until the process is stopped.
+pub fn serve(addr: &str, config: Config) -> anyhow::Result<()> {
+    tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(async {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            tracing::info!("listening on http://{}", listener.local_addr()?);
+            let config = Arc::new(config);
+            loop {
+                let (stream, _) = listener.accept().await?;
+                let config = config.clone();
+                tokio::spawn(async move {
+                    let service = hyper::service::service_fn(move |req| {
+                        let config = config.clone();
+                        async move { Ok::<_, std::convert::Infallible>(handle(req, &config).await) }
+                    });
+                    if let Err(e) = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await
+                    {
+                        tracing::debug!("connection closed: {e}");
+                    }
+                });
+            }
+        })
+    })
+}
+
+async fn handle(req: Request<Incoming>, config: &Config) -> Response<Full<Bytes>> {
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let result = match (&method, path.as_str()) {
+        (&Method::GET, "/health") => Ok(json!({ "status": "ok" })),
+        (&Method::GET, "/profile") => profile(req.uri().query().unwrap_or(""), config),
+        (&Method::POST, "/check") => match read_json(req).await {
+            Ok(body) => check(body, config),
+            Err(e) => Err(e),
+        },
+        (&Method::POST, "/compare") => match read_json(req).await {
+            Ok(body) => compare(body, config),
+            Err(e) => Err(e),
+        },
+        _ => Err(ApiError {
+            status: StatusCode::NOT_FOUND,
+            info: ErrorInfo {
+                code: "not_found".into(),
+                message: format!("no route for {method} {path}"),
+                retryable: false,
+            },
+        }),
+    };
+    let (status, body) = match result {
+        Ok(v) => (StatusCode::OK, v),
+        Err(e) => (e.status, json!({ "error": e.info })),
+    };
+    tracing::info!("{method} {path} -> {}", status.as_u16());
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .expect("static response parts are valid")
+}
+
+async fn read_json<T: serde::de::DeserializeOwned>(req: Request<Incoming>) -> Result<T, ApiError> {
+    let body = Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+        .map_err(|e| ApiError::bad_request(format!("reading body: {e}")))?
+        .to_bytes();
+    serde_json::from_slice(&body).map_err(|e| ApiError::bad_request(format!("invalid JSON: {e}")))
+}
+
+fn run(builder: ProfilerBuilder) -> Result<ProfileReport, ApiError> {
+    Ok(crate::run_profiler(builder)?)
+}
+
+fn profile(query: &str, config: &Config) -> Result<Value, ApiError> {
+    let mut builder = ProfilerBuilder::from_config(config);
+    let mut has_path = false;
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+        let bad = |e: &dyn std::fmt::Display| ApiError::bad_request(format!("{key}: {e}"));
+        builder = match key.as_ref() {
+            "path" => {
+                has_path = true;
+                builder.path(value.as_ref())
+            }
+            "sample" => builder.sample(value.parse().map_err(|e| bad(&e))?),
+            "sample_seed" => builder.sample_seed(value.parse().map_err(|e| bad(&e))?),
+            "sample_rows" => builder.sample_rows(value.parse().map_err(|e| bad(&e))?),
+            "sample_method" => builder.sample_method(match value.as_ref() {
+                "rows" => SampleMethod::Rows,
+                "row_groups" => SampleMethod::RowGroups,
+                other => return Err(bad(&format!("{other:?} (use rows or row_groups)"))),
+            }),
+            "full_scan" => builder.with_full_scan(value.parse().map_err(|e| bad(&e))?),
+            "baseline" => builder.with_baseline(value.parse().map_err(|e| bad(&e))?),
+            "columns" => builder.columns(value.split(',').map(String::from).collect()),
+            _ => return Err(ApiError::bad_request(format!("unknown parameter {key}"))),
+        };
+    }
+    if !has_path {
+        return Err(ApiError::bad_request("missing ?path="));
+    }
+    Ok(serde_json::to_value(run(builder)?).map_err(anyhow::Error::from)?)
+}
+
+fn check(req: CheckRequest, config: &Config) -> Result<Value, ApiError> {
+    let report = run(ProfilerBuilder::from_config(config)
+        .path(&req.path)
+        .with_baseline(req.baseline.is_none()))?;
+    let regressions = match &req.baseline {
+        None => report.baseline_regressions.clone(),
+        Some(reference) => BaselineProfile::load(reference)
+            .ok_or_else(|| {
+                anyhow::Error::from(ParquetLensError::NotFound(format!(
+                    "no baseline saved for {reference}"
+                )))
+            })?
+            .diff(
+                &report.column_stats,
+                report.quality_scores(),
+                &report.dataset.combined_schema,
+            ),
+    };
+    let failed: Vec<_> = regressions
+        .iter()
+        .filter(|r| req.rules.as_ref().is_none_or(|k| k.contains(&r.kind)))
+        .collect();
+    let below_min = req
+        .min_quality
+        .is_some_and(|min| report.quality.overall_score < min);
+    Ok(json!({
+        "path": req.path,
+        "passed": failed.is_empty() && !below_min,
+        "quality_score": report.quality.overall_score,
+        "below_min_quality": below_min,
+        "failed": failed,
+        "regressions": regressions,
+        "quarantine": report.quarantine,
+    }))
+}
+
+fn compare(req: CompareRequest, config: &Config) -> Result<Value, ApiError> {
+    let profile = |path: &str| {
+        run(ProfilerBuilder::from_config(config)
+            .path(path)
+            .metadata_only(true))
+    };
+    let (left, right) = (profile(&req.path1)?, profile(&req.path2)?);
+    let comparison = compare_datasets(
+        &left.dataset,
+        &right.dataset,
+        &left.column_stats,
+        &right.column_stats,
+    );
+    let t = &req.tolerances;
+    let mut violations = Vec::new();
+    if let Some(max) = t.row_delta_pct {
+        if comparison.row_delta_pct.abs() > max {
+            violations.push(format!(
+                "row count changed {:+.2}% (tolerance {max}%)",
+                comparison.row_delta_pct
+            ));
+        }
+    }
+    if let Some(max) = t.size_delta_pct {
+        let pct = if comparison.left_bytes > 0 {
+            comparison.size_delta_bytes as f64 / comparison.left_bytes as f64 * 100.0
+        } else {
+            0.0
+        };
+        if pct.abs() > max {
+            violations.push(format!("size changed {pct:+.2}% (tolerance {max}%)"));
+        }
+    }
+    if let Some(max) = t.null_rate_delta {
+        for d in &comparison.stats_diffs {
+            if d.null_rate_delta.abs() > max {
+                violations.push(format!(
+                    "column {} null rate changed {:+.2} points (tolerance {max})",
+                    d.name, d.null_rate_delta
+                ));
+            }
+        }
+    }
+    if !t.allow_schema_changes {
+        for d in &comparison.schema_diffs {
+            if d.status != parquet_lens_core::DiffStatus::Matching {
+                violations.push(format!("column {}: {:?}", d.name, d.status));
+            }
+        }
+    }
+    Ok(json!({
+        "passed": violations.is_empty(),
+        "violations": violations,
+        "comparison": comparison,
+    }))
+}
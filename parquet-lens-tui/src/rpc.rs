@@ -0,0 +1,320 @@
+//! JSON-RPC 2.0 over stdio for editor integrations (`parquet-lens serve --rpc`).
+//! Messages are framed LSP-style with `Content-Length` headers, or one JSON
+//! object per line; each reply uses the framing of its request. Long scans send
+//! `progress` notifications tagged with the request id.
+use parquet_lens_common::{Config, ErrorInfo, ParquetLensError};
+use parquet_lens_core::{
+    extract_schema, filter_count, parse_predicate, ProfilerBuilder, Progress, ProgressSink,
+    SampleMethod, ScanOptions, StrataAllocation,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const METHODS: &[&str] = &["initialize", "profile", "schema", "filterCount", "shutdown"];
+
+// JSON-RPC reserved codes, plus one for errors raised by the profiler itself
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    Headers,
+    Lines,
+}
+
+/// stdout, shared by replies and notifications sent from scan workers
+struct Output {
+    framing: Mutex<Framing>,
+    out: Mutex<std::io::Stdout>,
+}
+
+impl Output {
+    fn send(&self, msg: &Value) {
+        let body = msg.to_string();
+        let framing = *self.framing.lock().unwrap();
+        let mut out = self.out.lock().unwrap();
+        let written = match framing {
+            Framing::Headers => write!(out, "Content-Length: {}\r\n\r\n{body}", body.len()),
+            Framing::Lines => writeln!(out, "{body}"),
+        };
+        if let Err(e) = written.and_then(|_| out.flush()) {
+            tracing::warn!("rpc write failed: {e}");
+        }
+    }
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<ErrorInfo>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<ParquetLensError> for RpcError {
+    fn from(e: ParquetLensError) -> Self {
+        let info = e.info();
+        Self {
+            code: SERVER_ERROR,
+            message: info.message.clone(),
+            data: Some(info),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<ParquetLensError>() {
+            Ok(pe) => pe.into(),
+            Err(e) => Self::new(SERVER_ERROR, format!("{e:#}")),
+        }
+    }
+}
+
+/// Forwards scan progress as `progress` notifications, at most every 100ms.
+struct RpcProgress {
+    output: Arc<Output>,
+    id: Value,
+    last: Mutex<Option<Instant>>,
+}
+
+impl RpcProgress {
+    fn notify(&self, operation: &str, phase: &str, p: Progress) {
+        self.output.send(&json!({
+            "jsonrpc": "2.0",
+            "method": "progress",
+            "params": {
+                "id": self.id,
+                "operation": operation,
+                "phase": phase,
+                "rows": p.rows,
+                "bytes": p.bytes,
+            },
+        }));
+    }
+}
+
+impl ProgressSink for RpcProgress {
+    fn on_start(&self, operation: &str, total: Progress) {
+        self.notify(operation, "start", total);
+    }
+    fn on_progress(&self, operation: &str, done: Progress) {
+        let mut last = self.last.lock().unwrap();
+        if last.is_some_and(|t| t.elapsed() < Duration::from_millis(100)) {
+            return;
+        }
+        *last = Some(Instant::now());
+        drop(last);
+        self.notify(operation, "progress", done);
+    }
+    fn on_finish(&self, operation: &str, done: Progress) {
+        self.notify(operation, "finish", done);
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileParams {
+    path: String,
+    sample: Option<f64>,
+    sample_seed: Option<u64>,
+    sample_rows: Option<u64>,
+    sample_method: Option<SampleMethod>,
+    stratify_by: Option<String>,
+    strata_allocation: Option<StrataAllocation>,
+    #[serde(default)]
+    full_scan: bool,
+    columns: Option<Vec<String>>,
+    histogram_bins: Option<usize>,
+    #[serde(default)]
+    baseline: bool,
+}
+
+#[derive(Deserialize)]
+struct PathParams {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct FilterParams {
+    path: String,
+    predicate: String,
+}
+
+/// Serves requests from stdin until `shutdown` or end of input.
+pub fn serve(config: &Config, scan_opts: &ScanOptions) -> anyhow::Result<()> {
+    let output = Arc::new(Output {
+        framing: Mutex::new(Framing::Lines),
+        out: Mutex::new(std::io::stdout()),
+    });
+    let mut input = std::io::stdin().lock();
+    while let Some((framing, body)) = read_message(&mut input)? {
+        *output.framing.lock().unwrap() = framing;
+        let msg: Value = match serde_json::from_str(&body) {
+            Ok(v) => v,
+            Err(e) => {
+                output.send(&error_reply(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, e.to_string()),
+                ));
+                continue;
+            }
+        };
+        let id = msg.get("id").cloned();
+        let Some(method) = msg.get("method").and_then(Value::as_str) else {
+            output.send(&error_reply(
+                id.unwrap_or(Value::Null),
+                RpcError::new(INVALID_REQUEST, "missing method"),
+            ));
+            continue;
+        };
+        let params = msg.get("params").cloned().unwrap_or(Value::Null);
+        let progress_id = id.clone().unwrap_or(Value::Null);
+        let result = dispatch(method, params, config, scan_opts, &output, progress_id);
+        // notifications (no id) get no reply, errors included
+        if let Some(id) = id {
+            output.send(&match result {
+                Ok(value) => json!({ "jsonrpc": "2.0", "id": id, "result": value }),
+                Err(e) => error_reply(id, e),
+            });
+        }
+        if method == "shutdown" || method == "exit" {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn dispatch(
+    method: &str,
+    params: Value,
+    config: &Config,
+    scan_opts: &ScanOptions,
+    output: &Arc<Output>,
+    id: Value,
+) -> Result<Value, RpcError> {
+    let mut opts = scan_opts.clone();
+    opts.progress = Some(Arc::new(RpcProgress {
+        output: output.clone(),
+        id,
+        last: Mutex::new(None),
+    }));
+    match method {
+        "initialize" => Ok(json!({
+            "name": "parquet-lens",
+            "version": env!("CARGO_PKG_VERSION"),
+            "methods": METHODS,
+        })),
+        "profile" => {
+            let p: ProfileParams = parse_params(params)?;
+            let mut builder = ProfilerBuilder::from_config(config)
+                .path(p.path)
+                .with_full_scan(p.full_scan)
+                .with_baseline(p.baseline)
+                .scan_options(opts);
+            if let Some(pct) = p.sample {
+                builder = builder.sample(pct);
+            }
+            if let Some(seed) = p.sample_seed {
+                builder = builder.sample_seed(seed);
+            }
+            if let Some(rows) = p.sample_rows {
+                builder = builder.sample_rows(rows);
+            }
+            if let Some(method) = p.sample_method {
+                builder = builder.sample_method(method);
+            }
+            if let Some(column) = p.stratify_by {
+                builder = builder.stratify_by(column, p.strata_allocation.unwrap_or_default());
+            }
+            if let Some(cols) = p.columns {
+                builder = builder.columns(cols);
+            }
+            if let Some(bins) = p.histogram_bins {
+                builder = builder.histogram_bins(bins);
+            }
+            let report = crate::run_profiler(builder)?;
+            Ok(serde_json::to_value(report).map_err(anyhow::Error::from)?)
+        }
+        "schema" => {
+            let p: PathParams = parse_params(params)?;
+            let schema = extract_schema(std::path::Path::new(&p.path))?;
+            Ok(serde_json::to_value(schema).map_err(anyhow::Error::from)?)
+        }
+        "filterCount" => {
+            let p: FilterParams = parse_params(params)?;
+            let predicate = parse_predicate(&p.predicate)
+                .map_err(|e| RpcError::new(INVALID_PARAMS, format!("predicate: {e}")))?;
+            let result = filter_count(std::path::Path::new(&p.path), &predicate, &opts)
+                .map_err(|e| RpcError::new(SERVER_ERROR, e))?;
+            Ok(serde_json::to_value(result).map_err(anyhow::Error::from)?)
+        }
+        "shutdown" | "exit" => Ok(Value::Null),
+        other => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method {other} (available: {})", METHODS.join(", ")),
+        )),
+    }
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn error_reply(id: Value, e: RpcError) -> Value {
+    let mut error = json!({ "code": e.code, "message": e.message });
+    if let Some(data) = e.data {
+        error["data"] = json!(data);
+    }
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+// next message body and how it was framed; None at end of input
+fn read_message(input: &mut impl BufRead) -> anyhow::Result<Option<(Framing, String)>> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Some(len) = header_value(trimmed, "content-length") else {
+            return Ok(Some((Framing::Lines, trimmed.to_string())));
+        };
+        let len: usize = len.parse()?;
+        // skip any further headers (Content-Type) up to the blank line
+        loop {
+            line.clear();
+            if input.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+        }
+        let mut body = vec![0; len];
+        input.read_exact(&mut body)?;
+        return Ok(Some((Framing::Headers, String::from_utf8(body)?)));
+    }
+}
+
+fn header_value<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let (key, value) = line.split_once(':')?;
+    key.trim()
+        .eq_ignore_ascii_case(name)
+        .then_some(value.trim())
+}
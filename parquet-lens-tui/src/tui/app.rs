@@ -1,13 +1,17 @@
 use crate::tui::session::Session;
 use crate::tui::theme::Theme;
-use parquet_lens_common::Config;
+use parquet_lens_common::{Config, NumberFormat};
 use parquet_lens_core::{
-    AggregatedColumnStats, BaselineRegression, ColumnProfileResult, ColumnSchema,
-    CompressionAnalysis, DatasetComparison, DatasetProfile, DuplicateReport, EncodingAnalysis,
-    EngineInfo, FilterResult, NestedColumnProfile, NullPatternGroup, ParquetFileInfo,
-    PartitionInfo, QualityScore, RepairSuggestion, RowGroupProfile, RowGroupSizeRecommendation,
-    TimeSeriesProfile,
+    summarize_quality, AggregatedColumnStats, BaselineRegression, ColumnProfileResult,
+    ColumnSchema, CompressionAnalysis, DatasetComparison, DatasetProfile, DuplicateReport,
+    EncodingAnalysis, EngineInfo, FilterResult, IcebergTable, MaskSpec, MemoryTracker,
+    MetricConfidence, NestedColumnProfile, NestedDataProfile, NullPatternGroup, ParquetFileInfo,
+    PartitionInfo, PiiReport, ProfileReport, Progress, ProgressSink, QualityScore,
+    QuarantineReport, RepairSuggestion, RowCountCheck, RowGroupProfile, RowGroupSizeRecommendation,
+    RunInfo, SchemaTreeNode, TimeSeriesProfile,
 };
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum SidebarSort {
@@ -55,6 +59,31 @@ pub enum Focus {
     Overlay,
 }
 
+/// Messages from a background full scan to the UI loop.
+pub enum ScanUpdate {
+    Rows(u64),
+    RowGroup(String, usize), // file, row group a worker just started
+    Done(
+        Vec<ColumnProfileResult>,
+        Option<QuarantineReport>,
+        Option<NestedDataProfile>,
+    ),
+}
+
+/// Forwards core progress callbacks for one scan onto the UI channel.
+pub struct ScanProgress(pub std::sync::mpsc::Sender<ScanUpdate>);
+
+impl ProgressSink for ScanProgress {
+    fn on_progress(&self, _operation: &str, done: Progress) {
+        let _ = self.0.send(ScanUpdate::Rows(done.rows));
+    }
+    fn on_row_group(&self, _operation: &str, file: &str, row_group: usize) {
+        let _ = self
+            .0
+            .send(ScanUpdate::RowGroup(file.to_string(), row_group));
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ProgressState {
     Idle,
@@ -78,6 +107,8 @@ pub struct App {
     pub full_scan_results: Vec<ColumnProfileResult>,
     pub preview_rows: Vec<Vec<String>>,
     pub preview_headers: Vec<String>,
+    pub schema_tree: Option<SchemaTreeNode>, // local files only
+    pub schema_tree_mode: bool,              // Schema view shows the nested tree
     pub view: View,
     pub focus: Focus,
     pub profiling_mode: ProfilingMode,
@@ -87,11 +118,16 @@ pub struct App {
     pub preview_scroll_x: usize,
     pub preview_scroll_y: usize,
     pub progress: ProgressState,
-    pub progress_rx: Option<std::sync::mpsc::Receiver<(u64, Vec<ColumnProfileResult>)>>, // async full-scan progress
-    pub pending_full_scan: bool, // triggers spawn_blocking for full-scan
+    pub progress_rx: Option<std::sync::mpsc::Receiver<ScanUpdate>>, // async full-scan progress
+    pub scan_position: Option<(String, usize)>, // latest row group the scan started
+    pub scan_cancel: Option<Arc<AtomicBool>>,   // set by Esc to stop the running full scan
+    pub pending_full_scan: bool,                // triggers spawn_blocking for full-scan
+    pub pending_column_scan: Option<String>,    // full-scan just this column ('p')
+    pub scan_column: Option<String>,            // the column a running scan is limited to
     pub status_msg: String,
     pub should_quit: bool,
     pub config: Config,
+    pub fmt: NumberFormat, // from [display]; counts and sizes in every view
     pub comparison: Option<DatasetComparison>,
     pub compare_sidebar_col: usize,
     pub sidebar_search: String,
@@ -104,12 +140,21 @@ pub struct App {
     pub filter_input: String,
     pub filter_active: bool,
     pub filter_result: Option<FilterResult>,
+    pub mask: MaskSpec, // columns redacted in preview and filter rows
     pub sample_note: Option<String>,
+    pub sample_confidence: Vec<MetricConfidence>,
+    pub quarantine: Option<QuarantineReport>, // row groups the last scan skipped
+    pub remote_retries: u64, // retried remote requests already reported in the status bar
+    pub row_counts: Option<RowCountCheck>,
+    pub pii: Option<PiiReport>, // per-column PII match rates, when the scan ran
+    pub run: Option<RunInfo>,   // provenance of the loaded report, kept for exports
     pub repair_suggestions: Vec<RepairSuggestion>,
     pub rg_size_recommendation: Option<RowGroupSizeRecommendation>,
     pub timeseries_profiles: Vec<TimeSeriesProfile>,
     pub nested_profiles: Vec<NestedColumnProfile>,
+    pub nested_data: Option<NestedDataProfile>,
     pub engine_info: Option<EngineInfo>,
+    pub iceberg: Option<IcebergTable>, // set when the input is an Iceberg table
     pub null_patterns: Vec<NullPatternGroup>,
     pub baseline_regressions: Vec<BaselineRegression>,
     pub has_baseline: bool,
@@ -125,11 +170,14 @@ pub struct App {
     pub pending_duplicate_scan: bool,
     pub duplicate_rx:
         Option<std::sync::mpsc::Receiver<Result<parquet_lens_core::DuplicateReport, String>>>,
+    pub profile_cache: Option<parquet_lens_core::ProfileCache>, // None when --no-cache
+    pub memory_usage: Vec<(&'static str, Arc<MemoryTracker>)>,  // per analysis, most recent last
 }
 
 impl App {
     pub fn new(input_path: String, config: Config) -> Self {
         let sidebar_width = config.display.sidebar_width.unwrap_or(30);
+        let fmt = config.display.number_format();
         Self {
             input_path,
             dataset: None,
@@ -142,6 +190,8 @@ impl App {
             full_scan_results: Vec::new(),
             preview_rows: Vec::new(),
             preview_headers: Vec::new(),
+            schema_tree: None,
+            schema_tree_mode: false,
             view: View::FileOverview,
             focus: Focus::Sidebar,
             profiling_mode: ProfilingMode::Metadata,
@@ -152,11 +202,16 @@ impl App {
             preview_scroll_y: 0,
             progress: ProgressState::Idle,
             progress_rx: None,
+            scan_position: None,
+            scan_cancel: None,
             pending_full_scan: false,
+            pending_column_scan: None,
+            scan_column: None,
             status_msg: String::from("Loading..."),
             should_quit: false,
             theme: Theme::from_name(&config.display.theme),
             config,
+            fmt,
             comparison: None,
             compare_sidebar_col: 0,
             sidebar_search: String::new(),
@@ -169,12 +224,20 @@ impl App {
             filter_input: String::new(),
             filter_active: false,
             filter_result: None,
+            mask: MaskSpec::default(),
             sample_note: None,
+            sample_confidence: Vec::new(),
+            quarantine: None,
+            row_counts: None,
+            pii: None,
+            run: None,
             repair_suggestions: Vec::new(),
             rg_size_recommendation: None,
             timeseries_profiles: Vec::new(),
             nested_profiles: Vec::new(),
+            nested_data: None,
             engine_info: None,
+            iceberg: None,
             null_patterns: Vec::new(),
             baseline_regressions: Vec::new(),
             has_baseline: false,
@@ -188,6 +251,97 @@ impl App {
             sidebar_visible: true,
             pending_duplicate_scan: false,
             duplicate_rx: None,
+            profile_cache: None,
+            memory_usage: Vec::new(),
+            remote_retries: 0,
+        }
+    }
+    /// Fresh tracker for a (re)started analysis; replaces the previous run's.
+    pub fn track_memory(&mut self, label: &'static str) -> Arc<MemoryTracker> {
+        let tracker = Arc::new(
+            self.config
+                .profiling
+                .memory_limit_bytes
+                .map_or_else(MemoryTracker::default, MemoryTracker::with_limit),
+        );
+        self.memory_usage.retain(|(l, _)| *l != label);
+        self.memory_usage.push((label, tracker.clone()));
+        tracker
+    }
+    /// Replaces every profiling section with the contents of `report`.
+    pub fn load_report(&mut self, report: ProfileReport) {
+        self.dataset = Some(report.dataset);
+        self.file_info = Some(report.file_info);
+        self.row_groups = report.row_groups;
+        self.agg_stats = report.column_stats;
+        self.encoding_analysis = report.encodings;
+        self.compression_analysis = report.compression;
+        self.quality_scores = report.quality.column_scores;
+        self.null_patterns = report.null_patterns;
+        self.repair_suggestions = report.repair_suggestions;
+        self.rg_size_recommendation = report.row_group_recommendation;
+        self.engine_info = report.engine_info;
+        self.timeseries_profiles = report.timeseries_profiles;
+        self.nested_profiles = report.nested_profiles;
+        self.nested_data = report.nested_data;
+        self.has_baseline = report.baseline_captured_at.is_some();
+        self.baseline_captured_at = report.baseline_captured_at;
+        self.baseline_regressions = report.baseline_regressions;
+        self.full_scan_results = report.full_scan.unwrap_or_default();
+        self.sample_note = report.sample_note;
+        self.sample_confidence = report.sample_confidence;
+        self.quarantine = report.quarantine;
+        self.row_counts = report.row_counts;
+        self.pii = report.pii;
+        self.run = report.run;
+    }
+    /// Current state as a report (e.g. for export); None before a file is loaded.
+    /// Quality is re-summarized since scores may have changed after loading.
+    pub fn report(&self) -> Option<ProfileReport> {
+        let dataset = self.dataset.clone()?;
+        let file_info = self.file_info.clone()?;
+        let total_cells = dataset.total_rows * dataset.combined_schema.len() as i64;
+        let total_nulls: u64 = self.agg_stats.iter().map(|s| s.total_null_count).sum();
+        let quality = summarize_quality(
+            self.quality_scores.clone(),
+            total_cells,
+            total_nulls,
+            dataset.schema_inconsistencies.is_empty(),
+            &self.agg_stats,
+        );
+        Some(ProfileReport {
+            dataset,
+            file_info,
+            row_groups: self.row_groups.clone(),
+            column_stats: self.agg_stats.clone(),
+            encodings: self.encoding_analysis.clone(),
+            compression: self.compression_analysis.clone(),
+            quality,
+            null_patterns: self.null_patterns.clone(),
+            repair_suggestions: self.repair_suggestions.clone(),
+            row_group_recommendation: self.rg_size_recommendation.clone(),
+            engine_info: self.engine_info.clone(),
+            timeseries_profiles: self.timeseries_profiles.clone(),
+            nested_profiles: self.nested_profiles.clone(),
+            nested_data: self.nested_data.clone(),
+            baseline_captured_at: self.baseline_captured_at,
+            baseline_regressions: self.baseline_regressions.clone(),
+            full_scan: (!self.full_scan_results.is_empty()).then(|| self.full_scan_results.clone()),
+            sample_note: self.sample_note.clone(),
+            sample_confidence: self.sample_confidence.clone(),
+            quarantine: self.quarantine.clone(),
+            row_counts: self.row_counts.clone(),
+            pii: self.pii.clone(),
+            warnings: Vec::new(),
+            run: self.run.clone(),
+        })
+    }
+    /// `S` opens the schema view; pressed there it toggles the nested tree.
+    pub fn show_schema(&mut self) {
+        if self.view == View::Schema {
+            self.schema_tree_mode = !self.schema_tree_mode;
+        } else {
+            self.view = View::Schema;
         }
     }
     pub fn columns(&self) -> &[ColumnSchema] {
@@ -406,6 +560,21 @@ impl App {
         self.sidebar_width = s.sidebar_width;
     }
 
+    /// Queues a full scan of the column at `col_idx` alone and opens its
+    /// detail view, where the results land when the scan finishes.
+    pub fn scan_column(&mut self, col_idx: usize) {
+        if matches!(self.progress, ProgressState::Running { .. }) {
+            self.status_msg = "a scan is already running".into();
+            return;
+        }
+        let Some(name) = self.columns().get(col_idx).map(|c| c.name.clone()) else {
+            return;
+        };
+        self.status_msg = format!("Scanning column {name}…");
+        self.pending_column_scan = Some(name);
+        self.view = View::ColumnDetail(col_idx);
+    }
+
     pub fn cycle_profiling_mode(&mut self) {
         match self.profiling_mode {
             ProfilingMode::Metadata => {
@@ -1,8 +1,7 @@
 use crate::tui::app::{App, Focus, ProfilingMode, ProgressState, SidebarSort, View};
 use crossterm::event::{KeyCode, KeyEvent};
 use parquet_lens_core::{
-    analyze_null_patterns, export_json, filter_count, load_baseline_regressions, parse_predicate,
-    BaselineProfile, ColumnSchema,
+    analyze_null_patterns, export_json, filter_count, parse_predicate, BaselineProfile, ScanOptions,
 };
 use std::path::Path;
 
@@ -44,9 +43,13 @@ pub fn handle_key(app: &mut App, key: KeyEvent) {
             return;
         }
         KeyCode::Esc if matches!(app.progress, ProgressState::Running { .. }) => {
+            if let Some(cancel) = app.scan_cancel.take() {
+                cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+            }
             app.progress = ProgressState::Cancelled;
             app.progress_rx = None;
             app.pending_full_scan = false;
+            app.scan_column = None;
             app.status_msg = "Scan cancelled".into();
             return;
         }
@@ -108,7 +111,7 @@ fn handle_sidebar(app: &mut App, key: KeyEvent) {
                 app.focus = Focus::Main;
             }
         }
-        KeyCode::Char('S') => app.view = View::Schema,
+        KeyCode::Char('S') => app.show_schema(),
         KeyCode::Char('R') => app.view = View::RowGroups,
         KeyCode::Char('N') => app.view = View::NullHeatmap,
         KeyCode::Char('D') => app.view = View::DataPreview,
@@ -140,6 +143,11 @@ fn handle_sidebar(app: &mut App, key: KeyEvent) {
                 }
             };
         }
+        KeyCode::Char('p') => {
+            if let Some(&col_idx) = app.filtered_column_indices().get(app.sidebar_selected) {
+                app.scan_column(col_idx);
+            }
+        }
         KeyCode::Char('b') => app.toggle_bookmark(),
         KeyCode::Char('B') => {
             app.show_bookmarks_only = !app.show_bookmarks_only;
@@ -182,32 +190,11 @@ fn handle_sidebar(app: &mut App, key: KeyEvent) {
                 app.status_msg = format!("export dir error: {e}");
             } else {
                 let out_path = out_dir.join("profile.json");
-                let Some(dataset) = app.dataset.clone() else {
+                let Some(report) = app.report() else {
                     app.status_msg = "no dataset loaded".into();
                     return;
                 };
-                let null_patterns = analyze_null_patterns(&app.agg_stats);
-                let engine_info = app.engine_info.clone();
-                let schema: Vec<ColumnSchema> = app.columns().to_vec();
-                let (_, baseline_regressions) = load_baseline_regressions(
-                    std::path::Path::new(&app.input_path),
-                    &app.agg_stats,
-                    &app.quality_scores,
-                    &schema,
-                );
-                match export_json(
-                    &out_path,
-                    &dataset,
-                    &app.agg_stats,
-                    &app.row_groups,
-                    &app.quality_scores,
-                    &null_patterns,
-                    engine_info.as_ref(),
-                    &baseline_regressions,
-                    &app.timeseries_profiles,
-                    &app.nested_profiles,
-                    &app.repair_suggestions,
-                ) {
+                match export_json(&out_path, &report) {
                     Ok(_) => {
                         app.status_msg = format!("exported to {}", out_path.display());
                     }
@@ -250,10 +237,15 @@ fn handle_sidebar(app: &mut App, key: KeyEvent) {
 
 fn handle_main(app: &mut App, key: KeyEvent) {
     match key.code {
-        KeyCode::Char('S') => app.view = View::Schema,
+        KeyCode::Char('S') => app.show_schema(),
         KeyCode::Char('R') => app.view = View::RowGroups,
         KeyCode::Char('N') => app.view = View::NullHeatmap,
         KeyCode::Char('D') => app.view = View::DataPreview,
+        KeyCode::Char('p') => {
+            if let View::ColumnDetail(col_idx) = app.view {
+                app.scan_column(col_idx);
+            }
+        }
         KeyCode::Esc => {
             app.view = View::FileOverview;
             app.focus = Focus::Sidebar;
@@ -269,17 +261,13 @@ fn handle_main(app: &mut App, key: KeyEvent) {
             app.rg_sort_col = (app.rg_sort_col + 1) % 5;
         }
         KeyCode::Left | KeyCode::Char('H') => {
-            if app.preview_scroll_x > 0 {
-                app.preview_scroll_x -= 1;
-            }
+            app.preview_scroll_x = app.preview_scroll_x.saturating_sub(1);
         }
         KeyCode::Right | KeyCode::Char('L') => {
             app.preview_scroll_x += 1;
         }
         KeyCode::Up => {
-            if app.preview_scroll_y > 0 {
-                app.preview_scroll_y -= 1;
-            }
+            app.preview_scroll_y = app.preview_scroll_y.saturating_sub(1);
         }
         KeyCode::Down => {
             app.preview_scroll_y += 1;
@@ -307,13 +295,23 @@ fn handle_overlay(app: &mut App, key: KeyEvent) {
                             app.status_msg = format!("parse error: {e}");
                         }
                         Ok(pred) => {
+                            let opts = ScanOptions {
+                                memory: Some(app.track_memory("filter")),
+                                ..ScanOptions::from_config(&app.config.profiling)
+                            };
                             let path = Path::new(&app.input_path);
-                            match filter_count(path, &pred) {
-                                Ok(r) => {
+                            match filter_count(path, &pred, &opts) {
+                                Ok(mut r) => {
                                     app.status_msg = format!(
-                                        "filter: {} matched / {} scanned ({} rgs skipped)",
-                                        r.matched_rows, r.scanned_rows, r.skipped_rgs
+                                        "filter: {} matched / {} scanned ({} + {} bloom + {} dict rgs skipped)",
+                                        r.matched_rows,
+                                        r.scanned_rows,
+                                        r.skipped_rgs,
+                                        r.bloom_skipped_rgs,
+                                        r.dict_skipped_rgs
                                     );
+                                    app.mask
+                                        .apply_to_rows(&r.sample_headers, &mut r.sample_rows);
                                     app.filter_result = Some(r);
                                 }
                                 Err(e) => {
@@ -339,12 +337,10 @@ fn handle_overlay(app: &mut App, key: KeyEvent) {
             app.view = View::FileOverview;
             app.focus = Focus::Sidebar;
         }
-        KeyCode::Enter => {
-            if app.view == View::ConfirmFullScan {
-                app.profiling_mode = ProfilingMode::FullScan;
-                app.view = View::FileOverview;
-                app.focus = Focus::Sidebar;
-            }
+        KeyCode::Enter if app.view == View::ConfirmFullScan => {
+            app.profiling_mode = ProfilingMode::FullScan;
+            app.view = View::FileOverview;
+            app.focus = Focus::Sidebar;
         }
         _ => {}
     }
@@ -1,5 +1,6 @@
 use crate::tui::app::{App, Focus, ProfilingMode, ProgressState, View};
 use crate::tui::theme::Theme;
+use parquet_lens_core::{BenfordConformity, CalendarBucket, WEEKDAYS};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -52,7 +53,7 @@ pub fn render(frame: &mut Frame, app: &App) {
         total_rows,
     } = &app.progress
     {
-        render_progress(frame, area, *rows_processed, *total_rows, theme);
+        render_progress(frame, app, area, *rows_processed, *total_rows, theme);
     }
 }
 
@@ -65,9 +66,9 @@ fn render_topbar(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
         format!(
             " {} | {} files | {} rows | {}",
             app.input_path,
-            ds.file_count,
-            ds.total_rows,
-            fmt_bytes(ds.total_bytes)
+            app.fmt.count(ds.file_count as u64),
+            app.fmt.count(ds.total_rows),
+            app.fmt.bytes(ds.total_bytes)
         )
     } else {
         format!(" {}", app.input_path)
@@ -196,7 +197,10 @@ fn render_duplicates(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
         theme.success
     };
     let lines = vec![
-        Line::from(format!("Total rows:            {}", report.total_rows)),
+        Line::from(format!(
+            "Total rows:            {}",
+            app.fmt.count(report.total_rows)
+        )),
         Line::from(vec![
             Span::raw("Estimated duplicates:  "),
             Span::styled(
@@ -330,8 +334,8 @@ fn render_repair(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
                 ),
                 Span::raw(format!(
                     "avg {} / target {}",
-                    fmt_bytes(rg_rec.current_avg_bytes),
-                    fmt_bytes(rg_rec.target_bytes)
+                    app.fmt.bytes(rg_rec.current_avg_bytes),
+                    app.fmt.bytes(rg_rec.target_bytes)
                 )),
             ]),
             Line::from(rg_rec.recommendation.clone()),
@@ -470,7 +474,93 @@ fn render_timeseries(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
             .borders(Borders::ALL)
             .title("Time-Series Profile (T)"),
     );
-    frame.render_widget(table, area);
+    // weekday / hour distributions come with a full scan
+    let calendar: Vec<Line> = app
+        .full_scan_results
+        .iter()
+        .filter_map(|r| Some((&r.column_name, r.temporal.as_ref().filter(|t| t.count > 0)?)))
+        .flat_map(|(name, t)| {
+            let days: Vec<String> = WEEKDAYS
+                .iter()
+                .zip(&t.weekday_distribution)
+                .map(|(d, n)| format!("{d} {}", app.fmt.count(*n)))
+                .collect();
+            let gaps: Vec<String> = t
+                .gaps
+                .iter()
+                .map(|g| match g.bucket {
+                    CalendarBucket::Day if g.span > 1 => format!("{} ({}d)", g.label, g.span),
+                    _ => g.label.clone(),
+                })
+                .collect();
+            let gap_style = if gaps.is_empty() {
+                Style::default().fg(theme.success)
+            } else {
+                Style::default().fg(theme.warning)
+            };
+            [
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<16} ", truncate(name, 16)),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(days.join("  ")),
+                ]),
+                Line::from(format!(
+                    "{:<16} {} (hours, UTC)",
+                    "",
+                    sparkline(&t.hour_distribution)
+                )),
+                Line::from(vec![
+                    Span::raw(format!("{:<16} ", "")),
+                    Span::styled(
+                        if gaps.is_empty() {
+                            "no calendar gaps".to_string()
+                        } else {
+                            format!("no rows: {}", gaps.join(", "))
+                        },
+                        gap_style,
+                    ),
+                ]),
+            ]
+        })
+        .collect();
+    if calendar.is_empty() {
+        frame.render_widget(table, area);
+        return;
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(5),
+            Constraint::Length(calendar.len() as u16 + 2),
+        ])
+        .split(area);
+    frame.render_widget(table, chunks[0]);
+    frame.render_widget(
+        Paragraph::new(calendar).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Calendar (full scan)"),
+        ),
+        chunks[1],
+    );
+}
+
+/// One block character per value, scaled to the largest.
+fn sparkline(values: &[u64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| {
+            if v == 0 {
+                ' '
+            } else {
+                BARS[((v * 7) / max.max(1)) as usize]
+            }
+        })
+        .collect()
 }
 
 fn render_nested(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
@@ -533,7 +623,103 @@ fn render_nested(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
             .borders(Borders::ALL)
             .title("Nested Type Profile (X)"),
     );
-    frame.render_widget(table, area);
+    let Some(data) = app.nested_data.as_ref().filter(|d| !d.is_empty()) else {
+        frame.render_widget(table, area);
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(app.nested_profiles.len().min(8) as u16 + 3),
+            Constraint::Min(0),
+        ])
+        .split(area);
+    frame.render_widget(table, chunks[0]);
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines: Vec<Line> = Vec::new();
+    if !data.lists.is_empty() {
+        lines.push(Line::from(Span::styled("List lengths:", bold)));
+        for l in &data.lists {
+            let buckets: Vec<String> = l
+                .length_distribution
+                .iter()
+                .map(|(upper, n)| format!("≤{upper}:{n}"))
+                .collect();
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<28}", truncate(&l.path, 28)),
+                    Style::default().fg(theme.string),
+                ),
+                Span::raw(format!(
+                    " len {}-{}  avg={:.1}  empty={}  null={}  {}",
+                    l.min_length,
+                    l.max_length,
+                    l.mean_length,
+                    l.empty_count,
+                    l.null_count,
+                    buckets.join(" ")
+                )),
+            ]));
+        }
+    }
+    if !data.maps.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Map keys:", bold)));
+        for m in &data.maps {
+            let top: Vec<String> = m
+                .top_keys
+                .iter()
+                .flat_map(|f| f.top_values.iter().take(5))
+                .map(|e| format!("{} ({:.0}%)", truncate(&e.value, 16), e.percentage))
+                .collect();
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<28}", truncate(&m.path, 28)),
+                    Style::default().fg(theme.temporal),
+                ),
+                Span::raw(format!(
+                    " ~{} distinct keys  {} entries  top: {}",
+                    m.key_cardinality.approximate_distinct,
+                    m.entry_count,
+                    top.join(", ")
+                )),
+            ]));
+        }
+    }
+    if !data.struct_fields.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Struct field nulls:", bold)));
+        for f in &data.struct_fields {
+            let color = if f.null_percentage > 50.0 {
+                theme.error
+            } else if f.null_percentage > 0.0 {
+                theme.warning
+            } else {
+                theme.success
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<28}", truncate(&f.path, 28)),
+                    Style::default().fg(theme.numeric),
+                ),
+                Span::styled(
+                    format!(" {:5.1}%", f.null_percentage),
+                    Style::default().fg(color),
+                ),
+                Span::raw(format!(" of {} rows", f.parent_rows)),
+            ]));
+        }
+    }
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Nested values (full scan)"),
+            )
+            .wrap(Wrap { trim: false }),
+        chunks[1],
+    );
 }
 
 fn render_null_patterns(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
@@ -688,7 +874,7 @@ fn render_col_size_breakdown(frame: &mut Frame, app: &App, area: Rect) {
         .iter()
         .map(|s| (s.column_name.as_str(), s.total_compressed_size))
         .collect();
-    cols.sort_by(|a, b| b.1.cmp(&a.1));
+    cols.sort_by_key(|c| std::cmp::Reverse(c.1));
     let max_size = cols.first().map(|(_, s)| *s).unwrap_or(1).max(1);
     let bar_width = (area.width as usize).saturating_sub(30).max(10);
     let lines: Vec<Line> = cols
@@ -699,7 +885,7 @@ fn render_col_size_breakdown(frame: &mut Frame, app: &App, area: Rect) {
                 "{:<20} |{:<bw$}| {}",
                 truncate(name, 20),
                 "█".repeat(blen),
-                fmt_bytes(*size as u64),
+                app.fmt.bytes(*size as u64),
                 bw = bar_width
             ))
         })
@@ -736,8 +922,8 @@ fn render_file_list(frame: &mut Frame, app: &App, area: Rect) {
         .map(|f| {
             Row::new([
                 truncate(f.path.to_str().unwrap_or(""), 40),
-                f.row_count.to_string(),
-                fmt_bytes(f.file_size),
+                app.fmt.count(f.row_count),
+                app.fmt.bytes(f.file_size),
                 f.row_group_count.to_string(),
             ])
         })
@@ -783,7 +969,7 @@ fn render_compare(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
         "Rows:  {}  Files: {}  Size: {}",
         cmp.left_rows,
         cmp.left_files,
-        fmt_bytes(cmp.left_bytes)
+        app.fmt.bytes(cmp.left_bytes)
     )));
     left_lines.push(Line::from(format!("Cols:  {}", cmp.left_columns)));
     left_lines.push(Line::from(""));
@@ -826,7 +1012,7 @@ fn render_compare(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
         "Rows:  {}  Files: {}  Size: {}",
         cmp.right_rows,
         cmp.right_files,
-        fmt_bytes(cmp.right_bytes)
+        app.fmt.bytes(cmp.right_bytes)
     )));
     right_lines.push(Line::from(format!("Cols:  {}", cmp.right_columns)));
     right_lines.push(Line::from(format!(
@@ -883,10 +1069,13 @@ fn render_file_overview(frame: &mut Frame, app: &App, area: Rect) {
             fi.created_by.as_deref().unwrap_or("unknown")
         )));
         lines.push(Line::from(format!("Row groups:{}", fi.row_group_count)));
-        lines.push(Line::from(format!("Rows:      {}", fi.row_count)));
+        lines.push(Line::from(format!(
+            "Rows:      {}",
+            app.fmt.count(fi.row_count)
+        )));
         lines.push(Line::from(format!(
             "Size:      {}",
-            fmt_bytes(fi.file_size)
+            app.fmt.bytes(fi.file_size)
         )));
         if let Some(eng) = &app.engine_info {
             lines.push(Line::from(format!(
@@ -898,6 +1087,10 @@ fn render_file_overview(frame: &mut Frame, app: &App, area: Rect) {
                 lines.push(Line::from(format!("  hint: {hint}")));
             }
         }
+        if let Some(table) = &app.iceberg {
+            lines.push(Line::from(""));
+            lines.extend(table.lines().into_iter().map(Line::from));
+        }
         if !fi.key_value_metadata.is_empty() {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
@@ -911,6 +1104,70 @@ fn render_file_overview(frame: &mut Frame, app: &App, area: Rect) {
                 )));
             }
         }
+        if let Some(q) = &app.quarantine {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "Quarantined: {} of {} rows ({:.1}%) unreadable, left out of scan results",
+                    q.rows_affected, q.total_rows, q.pct_rows_affected
+                ),
+                Style::default()
+                    .fg(app.theme.error)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            for e in &q.entries {
+                lines.push(Line::from(format!(
+                    "  row group {}{}: {} rows — {}",
+                    e.row_group,
+                    e.column
+                        .as_deref()
+                        .map(|c| format!(" column {c}"))
+                        .unwrap_or_default(),
+                    e.rows,
+                    e.error
+                )));
+            }
+        }
+        if let Some(rc) = app.row_counts.as_ref().filter(|rc| !rc.is_consistent()) {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Row counts disagree with the footer (truncated or torn write?):",
+                Style::default()
+                    .fg(app.theme.error)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            for line in rc.lines() {
+                lines.push(Line::from(format!("  {line}")));
+            }
+        }
+        if let Some(pii) = &app.pii {
+            let flagged: Vec<_> = pii.flagged().collect();
+            if !flagged.is_empty() {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    format!("Likely PII in {} column(s):", flagged.len()),
+                    Style::default()
+                        .fg(app.theme.warning)
+                        .add_modifier(Modifier::BOLD),
+                )));
+                for c in flagged {
+                    lines.push(Line::from(format!("  {}", c.line())));
+                }
+            }
+        }
+        let estimated: Vec<_> = app.sample_confidence.iter().filter(|m| !m.exact).collect();
+        if !estimated.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Sampled metrics (95% CI):",
+                Style::default()
+                    .fg(app.theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            for m in estimated {
+                lines.push(Line::from(format!("  {}", m.line())));
+            }
+        }
     } else {
         lines.push(Line::from(app.status_msg.clone()));
     }
@@ -927,6 +1184,23 @@ fn render_file_overview(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_schema(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    if app.schema_tree_mode {
+        let lines: Vec<Line> = match &app.schema_tree {
+            Some(tree) => tree.render().into_iter().map(Line::from).collect(),
+            None => vec![Line::from(
+                "The nested tree is only available for local files.",
+            )],
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Schema tree (S: flat columns)"),
+            ),
+            area,
+        );
+        return;
+    }
     let header = Row::new(
         [
             "Name",
@@ -968,7 +1242,11 @@ fn render_schema(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
         ],
     )
     .header(header)
-    .block(Block::default().borders(Borders::ALL).title("Schema (S)"));
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Schema (S: nested tree)"),
+    );
     frame.render_widget(table, area);
 }
 
@@ -1001,8 +1279,8 @@ fn render_column_detail(frame: &mut Frame, app: &App, area: Rect, idx: usize, th
         )));
         lines.push(Line::from(format!(
             "Size:       {} uncomp / {} comp  ({:.2}x)",
-            fmt_bytes(agg.total_data_page_size as u64),
-            fmt_bytes(agg.total_compressed_size as u64),
+            app.fmt.bytes(agg.total_data_page_size as u64),
+            app.fmt.bytes(agg.total_compressed_size as u64),
             agg.compression_ratio
         )));
     }
@@ -1047,6 +1325,32 @@ fn render_column_detail(frame: &mut Frame, app: &App, area: Rect, idx: usize, th
             Span::raw(qs.breakdown.clone()),
         ]));
     }
+    if let Some(cp) = app
+        .pii
+        .as_ref()
+        .and_then(|p| p.columns.iter().find(|c| c.column == col.name))
+        .filter(|c| !c.matches.is_empty())
+    {
+        let matches: Vec<String> = cp
+            .matches
+            .iter()
+            .map(|m| format!("{} {:.2}%", m.kind.label(), m.pct))
+            .collect();
+        let style = if cp.likely_pii {
+            Style::default()
+                .fg(theme.warning)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                "PII:        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(matches.join(", "), style),
+        ]));
+    }
     if let Some(fsr) = app
         .full_scan_results
         .iter()
@@ -1070,6 +1374,39 @@ fn render_column_detail(frame: &mut Frame, app: &App, area: Rect, idx: usize, th
                 "  skew={:.3}  kurt={:.3}",
                 num.skewness, num.kurtosis
             )));
+            if num.iqr_outlier_count > 0 || num.zscore_outlier_count > 0 {
+                let examples: Vec<String> = num
+                    .outlier_examples
+                    .iter()
+                    .map(|v| format!("{v}"))
+                    .collect();
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  outliers: {} IQR ({:.1}%)  {} z>3  e.g. {}",
+                        num.iqr_outlier_count,
+                        num.outlier_pct,
+                        num.zscore_outlier_count,
+                        examples.join(", ")
+                    ),
+                    Style::default().fg(theme.warning),
+                )));
+            }
+            if let Some(b) = &num.benford {
+                let color = match b.conformity {
+                    BenfordConformity::Close | BenfordConformity::Acceptable => theme.success,
+                    BenfordConformity::Marginal => theme.warning,
+                    BenfordConformity::Nonconforming => theme.error,
+                };
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  Benford: {}  MAD={:.4}  chi2={:.1}",
+                        b.conformity.label(),
+                        b.mad,
+                        b.chi_square
+                    ),
+                    Style::default().fg(color),
+                )));
+            }
         }
         if let Some(hist) = &fsr.histogram {
             lines.push(Line::from(""));
@@ -1094,7 +1431,11 @@ fn render_column_detail(frame: &mut Frame, app: &App, area: Rect, idx: usize, th
         if let Some(freq) = &fsr.frequency {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
-                "Top values:",
+                if freq.approximate {
+                    "Top values (approximate, memory limit):"
+                } else {
+                    "Top values:"
+                },
                 Style::default().add_modifier(Modifier::BOLD),
             )));
             for e in &freq.top_values {
@@ -1111,6 +1452,28 @@ fn render_column_detail(frame: &mut Frame, app: &App, area: Rect, idx: usize, th
                 "String: len {}-{}  avg={:.1}  empty={}  ws={}",
                 s.min_length, s.max_length, s.mean_length, s.empty_count, s.whitespace_only_count
             )));
+            let matches = s.patterns.matches();
+            if !matches.is_empty() {
+                let parts: Vec<String> = matches
+                    .iter()
+                    .map(|(name, pct)| format!("{name} {pct:.1}%"))
+                    .collect();
+                lines.push(Line::from(format!("Patterns: {}", parts.join("  "))));
+            }
+            let e = &s.encoding;
+            if e.affected_count > 0 {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "Encoding: {:.1}% affected  mojibake={}  control={}  replacement={}  mixed-script={}",
+                        e.affected_pct(s.count),
+                        e.mojibake_count,
+                        e.control_char_count,
+                        e.replacement_char_count,
+                        e.mixed_script_count
+                    ),
+                    Style::default().fg(theme.warning),
+                )));
+            }
         }
         if let Some(b) = &fsr.boolean {
             lines.push(Line::from(format!(
@@ -1118,6 +1481,58 @@ fn render_column_detail(frame: &mut Frame, app: &App, area: Rect, idx: usize, th
                 b.true_count, b.false_count, b.null_count, b.true_percentage
             )));
         }
+        if let Some(b) = &fsr.binary {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Binary:",
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            lines.push(Line::from(format!(
+                "  len {}-{}  avg={:.1}  empty={}  entropy={:.2} bits/byte  uuid-like={:.1}%",
+                b.min_length,
+                b.max_length,
+                b.mean_length,
+                b.empty_count,
+                b.entropy_bits_per_byte,
+                b.uuid_like_pct
+            )));
+            let kinds: Vec<String> = b
+                .content_types
+                .iter()
+                .map(|(kind, n)| format!("{kind}={n}"))
+                .collect();
+            lines.push(Line::from(format!("  content: {}", kinds.join("  "))));
+            let max_c = b
+                .length_distribution
+                .iter()
+                .map(|(_, c)| *c)
+                .max()
+                .unwrap_or(1);
+            let bw = (area.width as usize).saturating_sub(30).max(10);
+            for (upper, count) in &b.length_distribution {
+                let blen = (*count as f64 / max_c as f64 * bw as f64) as usize;
+                lines.push(Line::from(format!(
+                    "  ≤{:<8} |{:<bw$}| {}",
+                    app.fmt.bytes(*upper as u64),
+                    "█".repeat(blen),
+                    count,
+                    bw = bw
+                )));
+            }
+        }
+        if let Some(e) = &fsr.entropy {
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "Entropy:    ",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(
+                    "{:.2} bits/value over {:.1} bytes  ~{:.0}% compressible  ({} sampled)",
+                    e.bits_per_value, e.bytes_per_value, e.compressible_pct, e.sampled_values
+                )),
+            ]));
+        }
     }
     frame.render_widget(
         Paragraph::new(lines)
@@ -1163,9 +1578,9 @@ fn render_row_groups(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
             let outlier = (rg.total_byte_size as f64 - mean_b).abs() > 2.0 * std_b && std_b > 0.0;
             Row::new([
                 rg.index.to_string(),
-                rg.num_rows.to_string(),
-                fmt_bytes(rg.total_byte_size as u64),
-                fmt_bytes(rg.compressed_size as u64),
+                app.fmt.count(rg.num_rows),
+                app.fmt.bytes(rg.total_byte_size as u64),
+                app.fmt.bytes(rg.compressed_size as u64),
                 format!("{:.2}x", rg.compression_ratio),
             ])
             .style(if outlier {
@@ -1329,8 +1744,13 @@ fn render_filter_overlay(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(ratatui::widgets::Clear, popup);
     let result_line = if let Some(r) = &app.filter_result {
         format!(
-            "matched: {}  scanned: {}  skipped rgs: {}/{}",
-            r.matched_rows, r.scanned_rows, r.skipped_rgs, r.total_rgs
+            "matched: {}  scanned: {}  skipped rgs: {}/{} (+{} by bloom filter, +{} by dictionary)",
+            r.matched_rows,
+            r.scanned_rows,
+            r.skipped_rgs,
+            r.total_rgs,
+            r.bloom_skipped_rgs,
+            r.dict_skipped_rgs
         )
     } else {
         String::from("Enter expression, press Enter to run, Esc to cancel")
@@ -1399,7 +1819,7 @@ fn render_help(frame: &mut Frame, app: &App, area: Rect) {
         ("?", "Toggle help (this view) / scroll j/k"),
         ("Tab", "Cycle focus sidebar ↔ main"),
         ("m", "Toggle profiling mode (meta / full-scan)"),
-        ("S", "Schema view"),
+        ("S", "Schema view (again: nested tree)"),
         ("R", "Row groups"),
         ("N", "Null heatmap"),
         ("D", "Data preview"),
@@ -1420,6 +1840,7 @@ fn render_help(frame: &mut Frame, app: &App, area: Rect) {
         ("H / L", "Scroll data preview left / right"),
         ("arrows", "Scroll data preview"),
         ("Enter", "Open column detail"),
+        ("p", "Full-scan only the selected column"),
         ("< / >", "Cycle row-group sort column"),
         ("o", "Cycle sidebar sort order"),
         ("b", "Toggle bookmark on column"),
@@ -1463,7 +1884,7 @@ fn render_confirm(frame: &mut Frame, area: Rect) {
     );
 }
 
-fn render_progress(frame: &mut Frame, area: Rect, rp: u64, tr: u64, theme: &Theme) {
+fn render_progress(frame: &mut Frame, app: &App, area: Rect, rp: u64, tr: u64, theme: &Theme) {
     let popup = centered_rect(50, 10, area);
     frame.render_widget(ratatui::widgets::Clear, popup);
     let ratio = if tr > 0 {
@@ -1471,27 +1892,44 @@ fn render_progress(frame: &mut Frame, area: Rect, rp: u64, tr: u64, theme: &Them
     } else {
         0.0
     };
+    let position = app
+        .scan_position
+        .as_ref()
+        .map(|(file, rg)| {
+            let name = file.rsplit(['/', '\\']).next().unwrap_or(file);
+            format!(" | {name} row group {rg}")
+        })
+        .unwrap_or_default();
     frame.render_widget(
         Gauge::default()
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Profiling... (Esc cancel)"),
+                    .title(match &app.scan_column {
+                        Some(col) => format!("Profiling {col}... (Esc cancel)"),
+                        None => "Profiling... (Esc cancel)".to_string(),
+                    }),
             )
             .gauge_style(Style::default().fg(theme.numeric))
             .ratio(ratio)
-            .label(format!("{rp}/{tr}")),
+            .label(format!("{rp}/{tr} rows{position}")),
         popup,
     );
 }
 
 fn render_bottombar(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    // peak of the latest analysis; grows live while a scan runs
+    let mem = app
+        .memory_usage
+        .last()
+        .map(|(label, t)| format!(" | mem {label} ~{}", app.fmt.bytes(t.peak())))
+        .unwrap_or_default();
     let bar_text = if let Some(r) = &app.filter_result {
-        format!(" {} | filter: {} matched / {} scanned ({} rgs skipped) | q:quit ?:help Tab:focus S R N D T X W m P",
-            app.status_msg, r.matched_rows, r.scanned_rows, r.skipped_rgs)
+        format!(" {} | filter: {} matched / {} scanned ({} + {} bloom + {} dict rgs skipped){mem} | q:quit ?:help Tab:focus S R N D T X W m P",
+            app.status_msg, r.matched_rows, r.scanned_rows, r.skipped_rgs, r.bloom_skipped_rgs, r.dict_skipped_rgs)
     } else {
         format!(
-            " {} | q:quit ?:help Tab:focus S R N D T X W m P",
+            " {}{mem} | q:quit ?:help Tab:focus S R N D T X W m P",
             app.status_msg
         )
     };
@@ -1546,18 +1984,6 @@ fn type_color(phys: &str, log: Option<&str>, theme: &Theme) -> Color {
     }
 }
 
-fn fmt_bytes(b: u64) -> String {
-    if b < 1024 {
-        format!("{b}B")
-    } else if b < 1 << 20 {
-        format!("{:.1}KB", b as f64 / 1024.0)
-    } else if b < 1 << 30 {
-        format!("{:.1}MB", b as f64 / 1048576.0)
-    } else {
-        format!("{:.2}GB", b as f64 / 1073741824.0)
-    }
-}
-
 fn fmt_ms(ms: i64) -> String {
     if ms.abs() < 1000 {
         format!("{ms}ms")
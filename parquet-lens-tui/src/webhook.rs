@@ -0,0 +1,97 @@
+use parquet_lens_common::{Config, NotificationsConfig};
+use parquet_lens_core::{export_json, ProfileReport};
+use std::collections::BTreeMap;
+
+const DEFAULT_TEMPLATE: &str = "parquet-lens: {regression_count} regression(s) in {dataset} \
+(quality {quality}/100)\nFailed checks: {failed_rules}\nWorst deltas:\n{worst_deltas}\nReport: {report}";
+
+/// Posts a regression alert for `report` to the configured webhook. Does nothing
+/// when there are no regressions. The full report is exported next to other
+/// exports so the message can link to it.
+pub fn send(config: &Config, dataset: &str, report: &ProfileReport) -> anyhow::Result<()> {
+    let n = &config.notifications;
+    let Some(url) = n.webhook_url.as_deref() else {
+        anyhow::bail!("--notify needs [notifications] webhook_url in the config");
+    };
+    if report.baseline_regressions.is_empty() {
+        return Ok(());
+    }
+    let link = export_report(config, report)?;
+    let text = render(n, dataset, report, &link);
+    let body = match kind(n, url) {
+        // both accept a plain text message; Teams renders it as markdown
+        "slack" | "teams" => serde_json::json!({ "text": text }),
+        _ => serde_json::json!({
+            "text": text,
+            "dataset": dataset,
+            "quality_score": report.quality.overall_score,
+            "regressions": report.baseline_regressions,
+            "report": link,
+        }),
+    };
+    let resp = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current()
+            .block_on(reqwest::Client::new().post(url).json(&body).send())
+    })?;
+    if !resp.status().is_success() {
+        anyhow::bail!("webhook returned HTTP {}", resp.status());
+    }
+    Ok(())
+}
+
+fn kind<'a>(n: &'a NotificationsConfig, url: &str) -> &'a str {
+    match n.kind.as_deref() {
+        Some(k) => k,
+        None if url.contains("hooks.slack.com") => "slack",
+        None if url.contains("webhook.office.com") || url.contains("logic.azure.com") => "teams",
+        None => "generic",
+    }
+}
+
+// writes the report as JSON into [export] output_dir; returns its URL or path
+fn export_report(config: &Config, report: &ProfileReport) -> anyhow::Result<String> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let name = format!("parquet-lens-report-{now}.json");
+    let dir = std::path::Path::new(&config.export.output_dir);
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(&name);
+    export_json(&path, report).map_err(anyhow::Error::from)?;
+    Ok(match &config.notifications.report_url {
+        Some(base) => format!("{}/{name}", base.trim_end_matches('/')),
+        None => std::path::absolute(&path)?.display().to_string(),
+    })
+}
+
+fn render(n: &NotificationsConfig, dataset: &str, report: &ProfileReport, link: &str) -> String {
+    let regressions = &report.baseline_regressions;
+    let mut kinds: BTreeMap<&str, usize> = BTreeMap::new();
+    for r in regressions {
+        *kinds.entry(r.kind.as_str()).or_default() += 1;
+    }
+    let failed_rules = kinds
+        .iter()
+        .map(|(k, c)| format!("{k} ({c})"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // schema changes first, then the rest in detection order
+    let mut worst: Vec<_> = regressions.iter().collect();
+    worst.sort_by_key(|r| !r.kind.starts_with("schema") && r.kind != "type_changed");
+    let worst_deltas = worst
+        .iter()
+        .take(5)
+        .map(|r| format!("• {}: {}", r.column, r.detail))
+        .collect::<Vec<_>>()
+        .join("\n");
+    n.template
+        .as_deref()
+        .unwrap_or(DEFAULT_TEMPLATE)
+        .replace("{dataset}", dataset)
+        .replace("{quality}", &report.quality.overall_score.to_string())
+        .replace("{regression_count}", &regressions.len().to_string())
+        .replace("{failed_rules}", &failed_rules)
+        .replace("{worst_deltas}", &worst_deltas)
+        .replace("{report}", link)
+}